@@ -1,13 +1,80 @@
-use std::str;
+use std::{collections::BTreeMap, str};
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Serialize, Default, Clone)]
+#[derive(Deserialize, Serialize, Clone)]
 #[serde(default)]
 pub struct ChunkConfig {
     pub compression: ChunkCompression,
     pub format: ChunkFormat,
     pub write_in_place: bool,
+    /// Keeps generated chunks apart from the world, so that they are read back instead of being
+    /// generated again when the world is regenerated with the same seed and settings.
+    pub generation_cache: bool,
+    /// How many threads generate the chunks of each dimension. `0` starts one for every core but
+    /// the `reserved_cores`.
+    pub generation_threads: usize,
+    /// The cores left to the rest of the server when there is a generation thread for every core.
+    pub reserved_cores: usize,
+    /// How many milliseconds a generation thread works at a stretch before it lets the rest of
+    /// the server run, keeping generation from hogging cores shared with the main loop. `0` works
+    /// through the whole queue at once.
+    pub generation_budget_ms: u64,
+    /// How far apart the structures of a structure set, like `villages`, are generated instead
+    /// of vanilla's spacing. Only sets spread over a grid, which is all of them but the
+    /// strongholds, can be spaced out.
+    pub structure_spacing: BTreeMap<String, StructureSpacing>,
+    /// Reshapes the terrain of the overworld, which is vanilla's by default.
+    pub terrain_shape: TerrainShape,
+}
+
+impl Default for ChunkConfig {
+    fn default() -> Self {
+        Self {
+            compression: ChunkCompression::default(),
+            format: ChunkFormat::default(),
+            write_in_place: false,
+            generation_cache: false,
+            generation_threads: 0,
+            reserved_cores: 1,
+            generation_budget_ms: 0,
+            structure_spacing: BTreeMap::new(),
+            terrain_shape: TerrainShape::default(),
+        }
+    }
+}
+
+/// Like vanilla's random spread placement, every square of `spacing` by `spacing` chunks has a
+/// chance to start a structure, in a chunk at least `separation` chunks away from the next square.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StructureSpacing {
+    pub spacing: i32,
+    pub separation: i32,
+}
+
+/// Knobs on the splines vanilla's overworld shapes its terrain with, by how continental, eroded
+/// and weird the land is.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct TerrainShape {
+    /// Multiplies how fast the land turns to air above its surface. Higher values flatten the
+    /// terrain, lower ones leave more to the noise, with more cliffs and overhangs.
+    pub factor: f32,
+    /// Added to how far below the surface every block is. Positive values raise the land,
+    /// negative ones sink it.
+    pub offset: f32,
+    /// Multiplies the height of the jagged peaks on top of the mountains; `0` rounds them off.
+    pub jaggedness: f32,
+}
+
+impl Default for TerrainShape {
+    fn default() -> Self {
+        Self {
+            factor: 1.0,
+            offset: 0.0,
+            jaggedness: 1.0,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone)]
@@ -113,6 +113,8 @@ pub struct BasicConfiguration {
     pub bedrock_edition_address: SocketAddr,
     /// The seed for world generation.
     pub seed: String,
+    /// How the terrain of new chunks is generated.
+    pub level_type: LevelType,
     /// The maximum number of players allowed on the server. Specifying `0` disables the limit.
     pub max_players: u32,
     /// The maximum view distance for players.
@@ -163,6 +165,7 @@ impl Default for BasicConfiguration {
             bedrock_edition: true,
             bedrock_edition_address: "0.0.0.0:19132".parse().unwrap(),
             seed: "".to_string(),
+            level_type: LevelType::default(),
             max_players: 1000,
             view_distance: NonZeroU8::new(16).unwrap(),
             simulation_distance: NonZeroU8::new(10).unwrap(),
@@ -193,6 +196,19 @@ impl BasicConfiguration {
     }
 }
 
+/// The generators vanilla's `level-type` selects, and a void one.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LevelType {
+    #[default]
+    Normal,
+    /// A few layers of grass and dirt on bedrock.
+    Flat,
+    LargeBiomes,
+    Amplified,
+    /// Nothing but a small platform to spawn on.
+    Void,
+}
+
 trait LoadConfiguration {
     fn load(exec_dir: &Path) -> Self
     where
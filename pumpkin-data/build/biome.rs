@@ -3,7 +3,7 @@ use std::{collections::BTreeMap, fs};
 use heck::ToShoutySnakeCase;
 use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote};
-use serde::Deserialize;
+use serde::{Deserialize, Deserializer};
 use syn::LitInt;
 
 #[derive(Deserialize)]
@@ -12,7 +12,8 @@ pub struct Biome {
     temperature: f32,
     downfall: f32,
     temperature_modifier: Option<TemperatureModifier>,
-    //carvers: Vec<String>,
+    #[serde(deserialize_with = "carver_list")]
+    carvers: Vec<String>,
     features: Vec<Vec<String>>,
     creature_spawn_probability: Option<f32>,
     spawners: SpawnGroups,
@@ -20,6 +21,21 @@ pub struct Biome {
     pub id: u8,
 }
 
+/// Biomes with a single carver name it on its own instead of in a list.
+fn carver_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Carvers {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match Carvers::deserialize(deserializer)? {
+        Carvers::One(carver) => vec![carver],
+        Carvers::Many(carvers) => carvers,
+    })
+}
+
 #[derive(Deserialize, PartialEq, Eq, Hash)]
 struct SpawnGroups {
     monster: Vec<Spawner>,
@@ -178,7 +194,7 @@ pub(crate) fn build() -> TokenStream {
         let has_precipitation = biome.has_precipitation;
         let temperature = biome.temperature;
         let downfall = biome.downfall;
-        //  let carvers = &biome.carvers;
+        let carvers = &biome.carvers;
         let features = &biome.features;
         let creature_spawn_probability = &biome.creature_spawn_probability.unwrap_or(0.1);
 
@@ -272,6 +288,7 @@ pub(crate) fn build() -> TokenStream {
                      #temperature_modifier,
                      #downfall
                 ),
+                carvers: &[#(#carvers),*],
                 features: &[#(&[#(#features),*]),*],
                 creature_spawn_probability: #creature_spawn_probability,
                 spawners: #spawners,
@@ -301,7 +318,7 @@ pub(crate) fn build() -> TokenStream {
             pub id: u8,
             pub registry_id: &'static str,
             pub weather: Weather,
-            // carvers: &'static [&str],
+            pub carvers: &'static [&'static str],
             pub features: &'static [&'static [&'static str]],
             pub creature_spawn_probability: f32,
             pub spawners: SpawnGroups,
@@ -16,6 +16,10 @@ mod chunk_status;
 mod composter_increase_chance;
 mod damage_type;
 mod data_component;
+// Shared with the world generator, which loads the routers of custom dimensions into it
+#[path = "../src/density_function_repr.rs"]
+mod density_function_repr;
+mod dimension_type;
 mod effect;
 mod enchantments;
 mod entity_pose;
@@ -69,6 +73,7 @@ pub fn main() {
         (noise_parameter::build, "noise_parameter.rs"),
         (biome::build, "biome.rs"),
         (damage_type::build, "damage_type.rs"),
+        (dimension_type::build, "dimension_type.rs"),
         (message_type::build, "message_type.rs"),
         (spawn_egg::build, "spawn_egg.rs"),
         (block::build, "block.rs"),
@@ -0,0 +1,79 @@
+use heck::ToShoutySnakeCase;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use serde::Deserialize;
+use std::{collections::BTreeMap, fs};
+
+#[derive(Deserialize)]
+struct SyncedRegistries {
+    dimension_type: BTreeMap<String, DimensionTypeData>,
+}
+
+#[derive(Deserialize)]
+struct DimensionTypeData {
+    min_y: i32,
+    height: u16,
+    logical_height: u16,
+    has_skylight: bool,
+    has_ceiling: bool,
+}
+
+pub(crate) fn build() -> TokenStream {
+    println!("cargo:rerun-if-changed=../assets/synced_registries.json");
+
+    let registries: SyncedRegistries =
+        serde_json::from_str(&fs::read_to_string("../assets/synced_registries.json").unwrap())
+            .expect("Failed to parse synced_registries.json");
+
+    let mut constants = Vec::new();
+    let mut type_from_name = TokenStream::new();
+
+    for (name, data) in registries.dimension_type {
+        let const_ident = format_ident!("{}", name.to_shouty_snake_case());
+        let resource_name = format!("minecraft:{name}");
+
+        type_from_name.extend(quote! {
+            #resource_name => Some(Self::#const_ident),
+        });
+
+        let DimensionTypeData {
+            min_y,
+            height,
+            logical_height,
+            has_skylight,
+            has_ceiling,
+        } = data;
+        constants.push(quote! {
+            pub const #const_ident: DimensionType = DimensionType {
+                min_y: #min_y,
+                height: #height,
+                logical_height: #logical_height,
+                has_skylight: #has_skylight,
+                has_ceiling: #has_ceiling,
+            };
+        });
+    }
+
+    quote! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct DimensionType {
+            pub min_y: i32,
+            pub height: u16,
+            pub logical_height: u16,
+            pub has_skylight: bool,
+            pub has_ceiling: bool,
+        }
+
+        impl DimensionType {
+            #(#constants)*
+
+            #[doc = r" Try to parse a dimension type from a resource location string."]
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    #type_from_name
+                    _ => None
+                }
+            }
+        }
+    }
+}
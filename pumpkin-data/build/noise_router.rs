@@ -1,23 +1,14 @@
-use std::{
-    collections::BTreeMap,
-    fs,
-    hash::{DefaultHasher, Hash, Hasher},
-};
+use std::{collections::BTreeMap, fs};
 
 use proc_macro2::{Punct, Spacing, Span, TokenStream};
 use quote::{ToTokens, TokenStreamExt, quote};
 use serde::Deserialize;
 use syn::Ident;
 
-#[derive(Clone)]
-struct HashableF32(pub f32);
-
-// Normally this is bad, but we just care about checking if components are the same
-impl Hash for HashableF32 {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.to_le_bytes().hash(state);
-    }
-}
+use crate::density_function_repr::{
+    BinaryOperation, DensityFunctionRepr, HashableF32, HashableF64, LinearOperation,
+    NoiseRouterRepr, SplineRepr, UnaryOperation, WeirdScaledMapper, WrapperType,
+};
 
 impl ToTokens for HashableF32 {
     fn to_tokens(&self, tokens: &mut TokenStream) {
@@ -39,22 +30,6 @@ impl ToTokens for HashableF32 {
     }
 }
 
-impl<'de> Deserialize<'de> for HashableF32 {
-    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        f32::deserialize(deserializer).map(Self)
-    }
-}
-
-#[derive(Clone)]
-struct HashableF64(pub f64);
-
-// Normally this is bad, but we just care about checking if components are the same
-impl Hash for HashableF64 {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.0.to_le_bytes().hash(state);
-    }
-}
-
 impl ToTokens for HashableF64 {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let value = self.0;
@@ -75,27 +50,6 @@ impl ToTokens for HashableF64 {
     }
 }
 
-impl<'de> Deserialize<'de> for HashableF64 {
-    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        f64::deserialize(deserializer).map(Self)
-    }
-}
-
-#[derive(Deserialize, Hash, Clone)]
-#[serde(tag = "_type", content = "value")]
-enum SplineRepr {
-    #[serde(rename(deserialize = "standard"))]
-    Standard {
-        #[serde(rename(deserialize = "locationFunction"))]
-        location_function: Box<DensityFunctionRepr>,
-        locations: Box<[HashableF32]>,
-        values: Box<[SplineRepr]>,
-        derivatives: Box<[HashableF32]>,
-    },
-    #[serde(rename(deserialize = "fixed"))]
-    Fixed { value: HashableF32 },
-}
-
 impl SplineRepr {
     fn into_token_stream(
         self,
@@ -135,7 +89,7 @@ impl SplineRepr {
                         quote! {
                             SplinePoint {
                                 location: #location,
-                                value: &#value_repr,
+                                value: #value_repr,
                                 derivative: #derivative,
                             }
                         }
@@ -145,7 +99,7 @@ impl SplineRepr {
                 quote! {
                     SplineRepr::Standard {
                         location_function_index: #function_index,
-                        points: &[#(#point_reprs),*],
+                        points: Cow::Borrowed(&[#(#point_reprs),*]),
                     }
                 }
             }
@@ -153,18 +107,6 @@ impl SplineRepr {
     }
 }
 
-#[derive(Deserialize, Hash, Copy, Clone)]
-enum BinaryOperation {
-    #[serde(rename(deserialize = "ADD"))]
-    Add,
-    #[serde(rename(deserialize = "MUL"))]
-    Mul,
-    #[serde(rename(deserialize = "MIN"))]
-    Min,
-    #[serde(rename(deserialize = "MAX"))]
-    Max,
-}
-
 impl BinaryOperation {
     fn into_token_stream(self) -> TokenStream {
         match self {
@@ -192,14 +134,6 @@ impl BinaryOperation {
     }
 }
 
-#[derive(Deserialize, Hash, Copy, Clone)]
-enum LinearOperation {
-    #[serde(rename(deserialize = "ADD"))]
-    Add,
-    #[serde(rename(deserialize = "MUL"))]
-    Mul,
-}
-
 impl LinearOperation {
     fn into_token_stream(self) -> TokenStream {
         match self {
@@ -217,22 +151,6 @@ impl LinearOperation {
     }
 }
 
-#[derive(Deserialize, Hash, Copy, Clone)]
-enum UnaryOperation {
-    #[serde(rename(deserialize = "ABS"))]
-    Abs,
-    #[serde(rename(deserialize = "SQUARE"))]
-    Square,
-    #[serde(rename(deserialize = "CUBE"))]
-    Cube,
-    #[serde(rename(deserialize = "HALF_NEGATIVE"))]
-    HalfNegative,
-    #[serde(rename(deserialize = "QUARTER_NEGATIVE"))]
-    QuarterNegative,
-    #[serde(rename(deserialize = "SQUEEZE"))]
-    Squeeze,
-}
-
 impl UnaryOperation {
     fn into_token_stream(self) -> TokenStream {
         match self {
@@ -270,14 +188,6 @@ impl UnaryOperation {
     }
 }
 
-#[derive(Deserialize, Hash, Copy, Clone)]
-enum WeirdScaledMapper {
-    #[serde(rename(deserialize = "TYPE2"))]
-    Caves,
-    #[serde(rename(deserialize = "TYPE1"))]
-    Tunnels,
-}
-
 impl WeirdScaledMapper {
     fn into_token_stream(self) -> TokenStream {
         match self {
@@ -295,16 +205,6 @@ impl WeirdScaledMapper {
     }
 }
 
-#[derive(Copy, Clone, Deserialize, PartialEq, Eq, Hash)]
-enum WrapperType {
-    Interpolated,
-    #[serde(rename(deserialize = "FlatCache"))]
-    CacheFlat,
-    Cache2D,
-    CacheOnce,
-    CellCache,
-}
-
 impl WrapperType {
     fn into_token_stream(self) -> TokenStream {
         match self {
@@ -337,230 +237,7 @@ impl WrapperType {
     }
 }
 
-#[derive(Deserialize, Hash, Clone)]
-struct NoiseData {
-    #[serde(rename(deserialize = "noise"))]
-    noise_id: String,
-    #[serde(rename(deserialize = "xzScale"))]
-    xz_scale: HashableF64,
-    #[serde(rename(deserialize = "yScale"))]
-    y_scale: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct ShiftedNoiseData {
-    #[serde(rename(deserialize = "xzScale"))]
-    xz_scale: HashableF64,
-    #[serde(rename(deserialize = "yScale"))]
-    y_scale: HashableF64,
-    #[serde(rename(deserialize = "noise"))]
-    noise_id: String,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct WeirdScaledData {
-    #[serde(rename(deserialize = "noise"))]
-    noise_id: String,
-    #[serde(rename(deserialize = "rarityValueMapper"))]
-    mapper: WeirdScaledMapper,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct InterpolatedNoiseSamplerData {
-    #[serde(rename(deserialize = "scaledXzScale"))]
-    scaled_xz_scale: HashableF64,
-    #[serde(rename(deserialize = "scaledYScale"))]
-    scaled_y_scale: HashableF64,
-    #[serde(rename(deserialize = "xzFactor"))]
-    xz_factor: HashableF64,
-    #[serde(rename(deserialize = "yFactor"))]
-    y_factor: HashableF64,
-    #[serde(rename(deserialize = "smearScaleMultiplier"))]
-    smear_scale_multiplier: HashableF64,
-    #[serde(rename(deserialize = "maxValue"))]
-    max_value: HashableF64,
-    // These are unused currently
-    //#[serde(rename(deserialize = "xzScale"))]
-    //xz_scale: HashableF64,
-    //#[serde(rename(deserialize = "yScale"))]
-    //y_scale: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct ClampedYGradientData {
-    #[serde(rename(deserialize = "fromY"))]
-    from_y: i32,
-    #[serde(rename(deserialize = "toY"))]
-    to_y: i32,
-    #[serde(rename(deserialize = "fromValue"))]
-    from_value: HashableF64,
-    #[serde(rename(deserialize = "toValue"))]
-    to_value: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct BinaryData {
-    #[serde(rename(deserialize = "type"))]
-    operation: BinaryOperation,
-    #[serde(rename(deserialize = "minValue"))]
-    min_value: HashableF64,
-    #[serde(rename(deserialize = "maxValue"))]
-    max_value: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct LinearData {
-    #[serde(rename(deserialize = "specificType"))]
-    operation: LinearOperation,
-    argument: HashableF64,
-    #[serde(rename(deserialize = "minValue"))]
-    min_value: HashableF64,
-    #[serde(rename(deserialize = "maxValue"))]
-    max_value: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct UnaryData {
-    #[serde(rename(deserialize = "type"))]
-    operation: UnaryOperation,
-    #[serde(rename(deserialize = "minValue"))]
-    min_value: HashableF64,
-    #[serde(rename(deserialize = "maxValue"))]
-    max_value: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct ClampData {
-    #[serde(rename(deserialize = "minValue"))]
-    min_value: HashableF64,
-    #[serde(rename(deserialize = "maxValue"))]
-    max_value: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct RangeChoiceData {
-    #[serde(rename(deserialize = "minInclusive"))]
-    min_inclusive: HashableF64,
-    #[serde(rename(deserialize = "maxExclusive"))]
-    max_exclusive: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-struct SplineData {
-    #[serde(rename(deserialize = "minValue"))]
-    min_value: HashableF64,
-    #[serde(rename(deserialize = "maxValue"))]
-    max_value: HashableF64,
-}
-
-#[derive(Deserialize, Hash, Clone)]
-#[serde(tag = "_class", content = "value")]
-enum DensityFunctionRepr {
-    // This is a placeholder for leaving space for world structures
-    Beardifier,
-    // These functions is initialized by a seed at runtime
-    BlendAlpha,
-    BlendOffset,
-    BlendDensity {
-        input: Box<DensityFunctionRepr>,
-    },
-    EndIslands,
-    Noise {
-        #[serde(flatten)]
-        data: NoiseData,
-    },
-    ShiftA {
-        #[serde(rename(deserialize = "offsetNoise"))]
-        noise_id: String,
-    },
-    ShiftB {
-        #[serde(rename(deserialize = "offsetNoise"))]
-        noise_id: String,
-    },
-    ShiftedNoise {
-        #[serde(rename(deserialize = "shiftX"))]
-        shift_x: Box<DensityFunctionRepr>,
-        #[serde(rename(deserialize = "shiftY"))]
-        shift_y: Box<DensityFunctionRepr>,
-        #[serde(rename(deserialize = "shiftZ"))]
-        shift_z: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: ShiftedNoiseData,
-    },
-    InterpolatedNoiseSampler {
-        #[serde(flatten)]
-        data: InterpolatedNoiseSamplerData,
-    },
-    #[serde(rename(deserialize = "WeirdScaledSampler"))]
-    WeirdScaled {
-        input: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: WeirdScaledData,
-    },
-    // The wrapped function is wrapped in a new wrapper at runtime
-    #[serde(rename(deserialize = "Wrapping"))]
-    Wrapper {
-        #[serde(rename(deserialize = "wrapped"))]
-        input: Box<DensityFunctionRepr>,
-        #[serde(rename(deserialize = "type"))]
-        wrapper: WrapperType,
-    },
-    // These functions are unchanged except possibly for internal functions
-    Constant {
-        value: HashableF64,
-    },
-    #[serde(rename(deserialize = "YClampedGradient"))]
-    ClampedYGradient {
-        #[serde(flatten)]
-        data: ClampedYGradientData,
-    },
-    #[serde(rename(deserialize = "BinaryOperation"))]
-    Binary {
-        argument1: Box<DensityFunctionRepr>,
-        argument2: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: BinaryData,
-    },
-    #[serde(rename(deserialize = "LinearOperation"))]
-    Linear {
-        input: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: LinearData,
-    },
-    #[serde(rename(deserialize = "UnaryOperation"))]
-    Unary {
-        input: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: UnaryData,
-    },
-    Clamp {
-        input: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: ClampData,
-    },
-    RangeChoice {
-        input: Box<DensityFunctionRepr>,
-        #[serde(rename(deserialize = "whenInRange"))]
-        when_in_range: Box<DensityFunctionRepr>,
-        #[serde(rename(deserialize = "whenOutOfRange"))]
-        when_out_range: Box<DensityFunctionRepr>,
-        #[serde(flatten)]
-        data: RangeChoiceData,
-    },
-    Spline {
-        spline: SplineRepr,
-        #[serde(flatten)]
-        data: SplineData,
-    },
-}
-
 impl DensityFunctionRepr {
-    fn unique_id(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
-        hasher.finish()
-    }
-
     fn get_index_for_component(
         self,
         stack: &mut Vec<TokenStream>,
@@ -584,13 +261,12 @@ impl DensityFunctionRepr {
         hash_to_index_map: &mut BTreeMap<u64, usize>,
     ) -> TokenStream {
         match self {
-            Self::Spline { spline, data } => {
-                let _ = data;
+            Self::Spline { spline } => {
                 let spline_repr = spline.into_token_stream(stack, hash_to_index_map);
 
                 quote! {
                     BaseNoiseFunctionComponent::Spline {
-                        spline: &#spline_repr,
+                        spline: #spline_repr,
                     }
                 }
             }
@@ -604,8 +280,8 @@ impl DensityFunctionRepr {
 
                 quote! {
                     BaseNoiseFunctionComponent::Noise {
-                        data: &NoiseData {
-                            noise_id: #noise_id,
+                        data: NoiseData {
+                            noise_id: Cow::Borrowed(#noise_id),
                             xz_scale: #xz_scale,
                             y_scale: #y_scale,
                         }
@@ -615,14 +291,14 @@ impl DensityFunctionRepr {
             Self::ShiftA { noise_id } => {
                 quote! {
                     BaseNoiseFunctionComponent::ShiftA {
-                        noise_id: #noise_id
+                        noise_id: Cow::Borrowed(#noise_id)
                     }
                 }
             }
             Self::ShiftB { noise_id } => {
                 quote! {
                     BaseNoiseFunctionComponent::ShiftB {
-                        noise_id: #noise_id
+                        noise_id: Cow::Borrowed(#noise_id)
                     }
                 }
             }
@@ -669,10 +345,10 @@ impl DensityFunctionRepr {
                         shift_x_index: #shift_x_index,
                         shift_y_index: #shift_y_index,
                         shift_z_index: #shift_z_index,
-                        data: &ShiftedNoiseData {
+                        data: ShiftedNoiseData {
                             xz_scale: #xz_scale,
                             y_scale: #y_scale,
-                            noise_id: #noise_id,
+                            noise_id: Cow::Borrowed(#noise_id),
                         },
                     }
                 }
@@ -696,7 +372,7 @@ impl DensityFunctionRepr {
                         input_index: #input_index,
                         when_in_range_index: #when_in_index,
                         when_out_range_index: #when_out_index,
-                        data: &RangeChoiceData {
+                        data: RangeChoiceData {
                             min_inclusive: #min_inclusive,
                             max_exclusive: #max_exclusive,
                         },
@@ -716,7 +392,7 @@ impl DensityFunctionRepr {
                     BaseNoiseFunctionComponent::Binary {
                         argument1_index: #argument1_index,
                         argument2_index: #argument2_index,
-                        data: &BinaryData {
+                        data: BinaryData {
                             operation: #action,
                         },
                     }
@@ -730,7 +406,7 @@ impl DensityFunctionRepr {
 
                 quote! {
                     BaseNoiseFunctionComponent::ClampedYGradient {
-                        data: &ClampedYGradientData {
+                        data: ClampedYGradientData {
                             from_y: #from_y,
                             to_y: #to_y,
                             from_value: #from_value,
@@ -765,7 +441,7 @@ impl DensityFunctionRepr {
                 quote! {
                     BaseNoiseFunctionComponent::Linear {
                         input_index: #input_index,
-                        data: &LinearData {
+                        data: LinearData {
                             operation: #action,
                             argument: #argument,
                         },
@@ -781,7 +457,7 @@ impl DensityFunctionRepr {
                 quote! {
                     BaseNoiseFunctionComponent::Clamp {
                         input_index: #input_index,
-                        data: &ClampData {
+                        data: ClampData {
                             min_value: #min_value,
                             max_value: #max_value,
                         },
@@ -796,7 +472,7 @@ impl DensityFunctionRepr {
                 quote! {
                     BaseNoiseFunctionComponent::Unary {
                         input_index: #input_index,
-                        data: &UnaryData {
+                        data: UnaryData {
                             operation: #action,
                         },
                     }
@@ -811,8 +487,8 @@ impl DensityFunctionRepr {
                 quote! {
                     BaseNoiseFunctionComponent::WeirdScaled {
                         input_index: #input_index,
-                        data: &WeirdScaledData {
-                            noise_id: #noise_id,
+                        data: WeirdScaledData {
+                            noise_id: Cow::Borrowed(#noise_id),
                             mapper: #action,
                         },
                     }
@@ -827,7 +503,7 @@ impl DensityFunctionRepr {
 
                 quote! {
                     BaseNoiseFunctionComponent::InterpolatedNoiseSampler {
-                        data: &InterpolatedNoiseSamplerData {
+                        data: InterpolatedNoiseSamplerData {
                             scaled_xz_scale: #scaled_xz_scale,
                             scaled_y_scale: #scaled_y_scale,
                             xz_factor: #xz_factor,
@@ -854,34 +530,6 @@ struct NoiseRouterReprs {
     end_islands: NoiseRouterRepr,
 }
 
-#[derive(Deserialize)]
-struct NoiseRouterRepr {
-    #[serde(rename(deserialize = "barrierNoise"))]
-    barrier_noise: DensityFunctionRepr,
-    #[serde(rename(deserialize = "fluidLevelFloodednessNoise"))]
-    fluid_level_floodedness_noise: DensityFunctionRepr,
-    #[serde(rename(deserialize = "fluidLevelSpreadNoise"))]
-    fluid_level_spread_noise: DensityFunctionRepr,
-    #[serde(rename(deserialize = "lavaNoise"))]
-    lava_noise: DensityFunctionRepr,
-    temperature: DensityFunctionRepr,
-    vegetation: DensityFunctionRepr,
-    continents: DensityFunctionRepr,
-    erosion: DensityFunctionRepr,
-    depth: DensityFunctionRepr,
-    ridges: DensityFunctionRepr,
-    #[serde(rename(deserialize = "initialDensityWithoutJaggedness"))]
-    initial_density_without_jaggedness: DensityFunctionRepr,
-    #[serde(rename(deserialize = "finalDensity"))]
-    final_density: DensityFunctionRepr,
-    #[serde(rename(deserialize = "veinToggle"))]
-    vein_toggle: DensityFunctionRepr,
-    #[serde(rename(deserialize = "veinRidged"))]
-    vein_ridged: DensityFunctionRepr,
-    #[serde(rename(deserialize = "veinGap"))]
-    vein_gap: DensityFunctionRepr,
-}
-
 impl NoiseRouterRepr {
     fn into_token_stream(self) -> TokenStream {
         let mut noise_component_stack = Vec::new();
@@ -955,7 +603,7 @@ impl NoiseRouterRepr {
         quote! {
             BaseNoiseRouters {
                 noise: BaseNoiseRouter {
-                    full_component_stack: &[#(#noise_component_stack),*],
+                    full_component_stack: Cow::Borrowed(&[#(#noise_component_stack),*]),
                     barrier_noise: #barrier_noise,
                     fluid_level_floodedness_noise: #fluid_level_floodedness_noise,
                     fluid_level_spread_noise: #fluid_level_spread_noise,
@@ -968,10 +616,10 @@ impl NoiseRouterRepr {
                     vein_gap: #vein_gap,
                 },
                 surface_estimator: BaseSurfaceEstimator {
-                    full_component_stack: &[#(#surface_component_stack),*],
+                    full_component_stack: Cow::Borrowed(&[#(#surface_component_stack),*]),
                 },
                 multi_noise: BaseMultiNoiseRouter {
-                    full_component_stack: &[#(#multinoise_component_stack),*],
+                    full_component_stack: Cow::Borrowed(&[#(#multinoise_component_stack),*]),
                     temperature: #temperature,
                     vegetation: #vegetation,
                     continents: #continents,
@@ -986,23 +634,13 @@ impl NoiseRouterRepr {
 
 macro_rules! fix_final_density {
     ($router:expr) => {{
-        $router.final_density = DensityFunctionRepr::Wrapper {
-            input: Box::new(DensityFunctionRepr::Binary {
-                argument1: Box::new($router.final_density),
-                argument2: Box::new(DensityFunctionRepr::Beardifier),
-                data: BinaryData {
-                    operation: BinaryOperation::Add,
-                    max_value: HashableF64(f64::INFINITY),
-                    min_value: HashableF64(f64::NEG_INFINITY),
-                },
-            }),
-            wrapper: WrapperType::CellCache,
-        };
+        $router.final_density = $router.final_density.with_beardifier();
     }};
 }
 
 pub(crate) fn build() -> TokenStream {
     println!("cargo:rerun-if-changed=../assets/density_function.json");
+    println!("cargo:rerun-if-changed=src/density_function_repr.rs");
 
     let mut reprs: NoiseRouterReprs =
         serde_json5::from_str(&fs::read_to_string("../assets/density_function.json").unwrap())
@@ -1018,20 +656,25 @@ pub(crate) fn build() -> TokenStream {
     let _ = reprs.end_islands;
 
     let overworld_router = reprs.overworld.into_token_stream();
+    let overworld_amplified_router = reprs.overworld_amplified.into_token_stream();
     let nether_router = reprs.nether.into_token_stream();
     let end_router = reprs.end.into_token_stream();
 
     quote! {
+        use std::borrow::Cow;
+
+        #[derive(Clone)]
         pub struct NoiseData {
-            pub noise_id: &'static str,
+            pub noise_id: Cow<'static, str>,
             pub xz_scale: f64,
             pub y_scale: f64,
         }
 
+        #[derive(Clone)]
         pub struct ShiftedNoiseData {
             pub xz_scale: f64,
             pub y_scale: f64,
-            pub noise_id: &'static str,
+            pub noise_id: Cow<'static, str>,
         }
 
         #[derive(Copy, Clone)]
@@ -1080,11 +723,13 @@ pub(crate) fn build() -> TokenStream {
             }
         }
 
+        #[derive(Clone)]
         pub struct WeirdScaledData {
-            pub noise_id: &'static str,
+            pub noise_id: Cow<'static, str>,
             pub mapper: WeirdScaledMapper,
         }
 
+        #[derive(Copy, Clone)]
         pub struct InterpolatedNoiseSamplerData {
             pub scaled_xz_scale: f64,
             pub scaled_y_scale: f64,
@@ -1093,6 +738,7 @@ pub(crate) fn build() -> TokenStream {
             pub smear_scale_multiplier: f64,
         }
 
+        #[derive(Copy, Clone)]
         pub struct ClampedYGradientData {
             pub from_y: f64,
             pub to_y: f64,
@@ -1108,6 +754,7 @@ pub(crate) fn build() -> TokenStream {
             Max,
         }
 
+        #[derive(Copy, Clone)]
         pub struct BinaryData {
             pub operation: BinaryOperation,
         }
@@ -1118,6 +765,7 @@ pub(crate) fn build() -> TokenStream {
             Mul,
         }
 
+        #[derive(Copy, Clone)]
         pub struct LinearData {
             pub operation: LinearOperation,
             pub argument: f64,
@@ -1143,6 +791,7 @@ pub(crate) fn build() -> TokenStream {
             Squeeze,
         }
 
+        #[derive(Copy, Clone)]
         pub struct UnaryData {
             pub operation: UnaryOperation,
         }
@@ -1176,6 +825,7 @@ pub(crate) fn build() -> TokenStream {
             }
         }
 
+        #[derive(Copy, Clone)]
         pub struct ClampData {
             pub min_value: f64,
             pub max_value: f64,
@@ -1188,21 +838,24 @@ pub(crate) fn build() -> TokenStream {
             }
         }
 
+        #[derive(Copy, Clone)]
         pub struct RangeChoiceData {
             pub min_inclusive: f64,
             pub max_exclusive: f64,
         }
 
+        #[derive(Clone)]
         pub struct SplinePoint {
             pub location: f32,
-            pub value: &'static SplineRepr,
+            pub value: SplineRepr,
             pub derivative: f32,
         }
 
+        #[derive(Clone)]
         pub enum SplineRepr {
             Standard {
                 location_function_index: usize,
-                points: &'static [SplinePoint],
+                points: Cow<'static, [SplinePoint]>,
             },
             Fixed { value: f32 },
         }
@@ -1216,6 +869,7 @@ pub(crate) fn build() -> TokenStream {
             CellCache,
         }
 
+        #[derive(Clone)]
         pub enum BaseNoiseFunctionComponent {
             // This is a placeholder for leaving space for world structures
             Beardifier,
@@ -1227,26 +881,26 @@ pub(crate) fn build() -> TokenStream {
             },
             EndIslands,
             Noise {
-                data: &'static NoiseData,
+                data: NoiseData,
             },
             ShiftA {
-                noise_id: &'static str,
+                noise_id: Cow<'static, str>,
             },
             ShiftB {
-                noise_id: &'static str,
+                noise_id: Cow<'static, str>,
             },
             ShiftedNoise {
                 shift_x_index: usize,
                 shift_y_index: usize,
                 shift_z_index: usize,
-                data: &'static ShiftedNoiseData,
+                data: ShiftedNoiseData,
             },
             InterpolatedNoiseSampler {
-                data: &'static InterpolatedNoiseSamplerData,
+                data: InterpolatedNoiseSamplerData,
             },
             WeirdScaled {
                 input_index: usize,
-                data: &'static WeirdScaledData,
+                data: WeirdScaledData,
             },
             // The wrapped function is wrapped in a new wrapper at runtime
             Wrapper {
@@ -1258,38 +912,39 @@ pub(crate) fn build() -> TokenStream {
                 value: f64,
             },
             ClampedYGradient {
-                data: &'static ClampedYGradientData,
+                data: ClampedYGradientData,
             },
             Binary {
                 argument1_index: usize,
                 argument2_index: usize,
-                data: &'static BinaryData,
+                data: BinaryData,
             },
             Linear {
                 input_index: usize,
-                data: &'static LinearData,
+                data: LinearData,
             },
             Unary {
                 input_index: usize,
-                data: &'static UnaryData,
+                data: UnaryData,
             },
             Clamp {
                 input_index: usize,
-                data: &'static ClampData,
+                data: ClampData,
             },
             RangeChoice {
                 input_index: usize,
                 when_in_range_index: usize,
                 when_out_range_index: usize,
-                data: &'static RangeChoiceData,
+                data: RangeChoiceData,
             },
             Spline {
-                spline: &'static SplineRepr,
+                spline: SplineRepr,
             },
         }
 
+        #[derive(Clone)]
         pub struct BaseNoiseRouter {
-            pub full_component_stack: &'static [BaseNoiseFunctionComponent],
+            pub full_component_stack: Cow<'static, [BaseNoiseFunctionComponent]>,
             pub barrier_noise: usize,
             pub fluid_level_floodedness_noise: usize,
             pub fluid_level_spread_noise: usize,
@@ -1302,12 +957,14 @@ pub(crate) fn build() -> TokenStream {
             pub vein_gap: usize,
         }
 
+        #[derive(Clone)]
         pub struct BaseSurfaceEstimator {
-            pub full_component_stack: &'static [BaseNoiseFunctionComponent],
+            pub full_component_stack: Cow<'static, [BaseNoiseFunctionComponent]>,
         }
 
+        #[derive(Clone)]
         pub struct BaseMultiNoiseRouter {
-            pub full_component_stack: &'static [BaseNoiseFunctionComponent],
+            pub full_component_stack: Cow<'static, [BaseNoiseFunctionComponent]>,
             pub temperature: usize,
             pub vegetation: usize,
             pub continents: usize,
@@ -1316,6 +973,7 @@ pub(crate) fn build() -> TokenStream {
             pub ridges: usize,
         }
 
+        #[derive(Clone)]
         pub struct BaseNoiseRouters {
             pub noise: BaseNoiseRouter,
             pub surface_estimator: BaseSurfaceEstimator,
@@ -1323,6 +981,7 @@ pub(crate) fn build() -> TokenStream {
         }
 
         pub const OVERWORLD_BASE_NOISE_ROUTER: BaseNoiseRouters = #overworld_router;
+        pub const OVERWORLD_AMPLIFIED_BASE_NOISE_ROUTER: BaseNoiseRouters = #overworld_amplified_router;
         pub const NETHER_BASE_NOISE_ROUTER: BaseNoiseRouters = #nether_router;
         pub const END_BASE_NOISE_ROUTER: BaseNoiseRouters = #end_router;
     }
@@ -0,0 +1,354 @@
+//! The density functions of the noise routers the way the extractor dumps them, with every
+//! reference resolved and every function tagged with its class.
+//!
+//! The build script bakes the vanilla routers out of these, and the world generator loads the
+//! ones of custom dimensions into them, so both flatten the same functions into the same
+//! component stacks.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde::Deserialize;
+
+#[derive(Clone, Copy)]
+pub struct HashableF32(pub f32);
+
+// Normally this is bad, but we just care about checking if components are the same
+impl Hash for HashableF32 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_le_bytes().hash(state);
+    }
+}
+
+impl<'de> Deserialize<'de> for HashableF32 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f32::deserialize(deserializer).map(Self)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct HashableF64(pub f64);
+
+// Normally this is bad, but we just care about checking if components are the same
+impl Hash for HashableF64 {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.to_le_bytes().hash(state);
+    }
+}
+
+impl<'de> Deserialize<'de> for HashableF64 {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Self)
+    }
+}
+
+#[derive(Deserialize, Hash, Clone)]
+#[serde(tag = "_type", content = "value")]
+pub enum SplineRepr {
+    #[serde(rename(deserialize = "standard"))]
+    Standard {
+        #[serde(rename(deserialize = "locationFunction"))]
+        location_function: Box<DensityFunctionRepr>,
+        locations: Box<[HashableF32]>,
+        values: Box<[SplineRepr]>,
+        derivatives: Box<[HashableF32]>,
+    },
+    #[serde(rename(deserialize = "fixed"))]
+    Fixed { value: HashableF32 },
+}
+
+#[derive(Deserialize, Hash, Copy, Clone)]
+pub enum BinaryOperation {
+    #[serde(rename(deserialize = "ADD"))]
+    Add,
+    #[serde(rename(deserialize = "MUL"))]
+    Mul,
+    #[serde(rename(deserialize = "MIN"))]
+    Min,
+    #[serde(rename(deserialize = "MAX"))]
+    Max,
+}
+
+#[derive(Deserialize, Hash, Copy, Clone)]
+pub enum LinearOperation {
+    #[serde(rename(deserialize = "ADD"))]
+    Add,
+    #[serde(rename(deserialize = "MUL"))]
+    Mul,
+}
+
+#[derive(Deserialize, Hash, Copy, Clone)]
+pub enum UnaryOperation {
+    #[serde(rename(deserialize = "ABS"))]
+    Abs,
+    #[serde(rename(deserialize = "SQUARE"))]
+    Square,
+    #[serde(rename(deserialize = "CUBE"))]
+    Cube,
+    #[serde(rename(deserialize = "HALF_NEGATIVE"))]
+    HalfNegative,
+    #[serde(rename(deserialize = "QUARTER_NEGATIVE"))]
+    QuarterNegative,
+    #[serde(rename(deserialize = "SQUEEZE"))]
+    Squeeze,
+}
+
+#[derive(Deserialize, Hash, Copy, Clone)]
+pub enum WeirdScaledMapper {
+    #[serde(rename(deserialize = "TYPE2"))]
+    Caves,
+    #[serde(rename(deserialize = "TYPE1"))]
+    Tunnels,
+}
+
+#[derive(Copy, Clone, Deserialize, PartialEq, Eq, Hash)]
+pub enum WrapperType {
+    Interpolated,
+    #[serde(rename(deserialize = "FlatCache"))]
+    CacheFlat,
+    Cache2D,
+    CacheOnce,
+    CellCache,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct NoiseData {
+    #[serde(rename(deserialize = "noise"))]
+    pub noise_id: String,
+    #[serde(rename(deserialize = "xzScale"))]
+    pub xz_scale: HashableF64,
+    #[serde(rename(deserialize = "yScale"))]
+    pub y_scale: HashableF64,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct ShiftedNoiseData {
+    #[serde(rename(deserialize = "xzScale"))]
+    pub xz_scale: HashableF64,
+    #[serde(rename(deserialize = "yScale"))]
+    pub y_scale: HashableF64,
+    #[serde(rename(deserialize = "noise"))]
+    pub noise_id: String,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct WeirdScaledData {
+    #[serde(rename(deserialize = "noise"))]
+    pub noise_id: String,
+    #[serde(rename(deserialize = "rarityValueMapper"))]
+    pub mapper: WeirdScaledMapper,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct InterpolatedNoiseSamplerData {
+    #[serde(rename(deserialize = "scaledXzScale"))]
+    pub scaled_xz_scale: HashableF64,
+    #[serde(rename(deserialize = "scaledYScale"))]
+    pub scaled_y_scale: HashableF64,
+    #[serde(rename(deserialize = "xzFactor"))]
+    pub xz_factor: HashableF64,
+    #[serde(rename(deserialize = "yFactor"))]
+    pub y_factor: HashableF64,
+    #[serde(rename(deserialize = "smearScaleMultiplier"))]
+    pub smear_scale_multiplier: HashableF64,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct ClampedYGradientData {
+    #[serde(rename(deserialize = "fromY"))]
+    pub from_y: i32,
+    #[serde(rename(deserialize = "toY"))]
+    pub to_y: i32,
+    #[serde(rename(deserialize = "fromValue"))]
+    pub from_value: HashableF64,
+    #[serde(rename(deserialize = "toValue"))]
+    pub to_value: HashableF64,
+}
+
+// The extractor also dumps the bounds of the functions, which are left out here: functions that
+// are the same have the same bounds, and the generator works them out itself
+#[derive(Deserialize, Hash, Clone)]
+pub struct BinaryData {
+    #[serde(rename(deserialize = "type"))]
+    pub operation: BinaryOperation,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct LinearData {
+    #[serde(rename(deserialize = "specificType"))]
+    pub operation: LinearOperation,
+    pub argument: HashableF64,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct UnaryData {
+    #[serde(rename(deserialize = "type"))]
+    pub operation: UnaryOperation,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct ClampData {
+    #[serde(rename(deserialize = "minValue"))]
+    pub min_value: HashableF64,
+    #[serde(rename(deserialize = "maxValue"))]
+    pub max_value: HashableF64,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+pub struct RangeChoiceData {
+    #[serde(rename(deserialize = "minInclusive"))]
+    pub min_inclusive: HashableF64,
+    #[serde(rename(deserialize = "maxExclusive"))]
+    pub max_exclusive: HashableF64,
+}
+
+#[derive(Deserialize, Hash, Clone)]
+#[serde(tag = "_class", content = "value")]
+pub enum DensityFunctionRepr {
+    // This is a placeholder for leaving space for world structures
+    Beardifier,
+    // These functions is initialized by a seed at runtime
+    BlendAlpha,
+    BlendOffset,
+    BlendDensity {
+        input: Box<DensityFunctionRepr>,
+    },
+    EndIslands,
+    Noise {
+        #[serde(flatten)]
+        data: NoiseData,
+    },
+    ShiftA {
+        #[serde(rename(deserialize = "offsetNoise"))]
+        noise_id: String,
+    },
+    ShiftB {
+        #[serde(rename(deserialize = "offsetNoise"))]
+        noise_id: String,
+    },
+    ShiftedNoise {
+        #[serde(rename(deserialize = "shiftX"))]
+        shift_x: Box<DensityFunctionRepr>,
+        #[serde(rename(deserialize = "shiftY"))]
+        shift_y: Box<DensityFunctionRepr>,
+        #[serde(rename(deserialize = "shiftZ"))]
+        shift_z: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: ShiftedNoiseData,
+    },
+    InterpolatedNoiseSampler {
+        #[serde(flatten)]
+        data: InterpolatedNoiseSamplerData,
+    },
+    #[serde(rename(deserialize = "WeirdScaledSampler"))]
+    WeirdScaled {
+        input: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: WeirdScaledData,
+    },
+    // The wrapped function is wrapped in a new wrapper at runtime
+    #[serde(rename(deserialize = "Wrapping"))]
+    Wrapper {
+        #[serde(rename(deserialize = "wrapped"))]
+        input: Box<DensityFunctionRepr>,
+        #[serde(rename(deserialize = "type"))]
+        wrapper: WrapperType,
+    },
+    // These functions are unchanged except possibly for internal functions
+    Constant {
+        value: HashableF64,
+    },
+    #[serde(rename(deserialize = "YClampedGradient"))]
+    ClampedYGradient {
+        #[serde(flatten)]
+        data: ClampedYGradientData,
+    },
+    #[serde(rename(deserialize = "BinaryOperation"))]
+    Binary {
+        argument1: Box<DensityFunctionRepr>,
+        argument2: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: BinaryData,
+    },
+    #[serde(rename(deserialize = "LinearOperation"))]
+    Linear {
+        input: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: LinearData,
+    },
+    #[serde(rename(deserialize = "UnaryOperation"))]
+    Unary {
+        input: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: UnaryData,
+    },
+    Clamp {
+        input: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: ClampData,
+    },
+    RangeChoice {
+        input: Box<DensityFunctionRepr>,
+        #[serde(rename(deserialize = "whenInRange"))]
+        when_in_range: Box<DensityFunctionRepr>,
+        #[serde(rename(deserialize = "whenOutOfRange"))]
+        when_out_range: Box<DensityFunctionRepr>,
+        #[serde(flatten)]
+        data: RangeChoiceData,
+    },
+    Spline {
+        spline: SplineRepr,
+    },
+}
+
+impl DensityFunctionRepr {
+    /// Identifies the function among the others of a router: identical functions share it, and
+    /// with it their component and their caches.
+    pub fn unique_id(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Makes room for the structures in this final density of a router and caches it per cell.
+    /// Java does this at runtime for the aquifer generator.
+    pub fn with_beardifier(self) -> Self {
+        Self::Wrapper {
+            input: Box::new(Self::Binary {
+                argument1: Box::new(self),
+                argument2: Box::new(Self::Beardifier),
+                data: BinaryData {
+                    operation: BinaryOperation::Add,
+                },
+            }),
+            wrapper: WrapperType::CellCache,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct NoiseRouterRepr {
+    #[serde(rename(deserialize = "barrierNoise"))]
+    pub barrier_noise: DensityFunctionRepr,
+    #[serde(rename(deserialize = "fluidLevelFloodednessNoise"))]
+    pub fluid_level_floodedness_noise: DensityFunctionRepr,
+    #[serde(rename(deserialize = "fluidLevelSpreadNoise"))]
+    pub fluid_level_spread_noise: DensityFunctionRepr,
+    #[serde(rename(deserialize = "lavaNoise"))]
+    pub lava_noise: DensityFunctionRepr,
+    pub temperature: DensityFunctionRepr,
+    pub vegetation: DensityFunctionRepr,
+    pub continents: DensityFunctionRepr,
+    pub erosion: DensityFunctionRepr,
+    pub depth: DensityFunctionRepr,
+    pub ridges: DensityFunctionRepr,
+    #[serde(rename(deserialize = "initialDensityWithoutJaggedness"))]
+    pub initial_density_without_jaggedness: DensityFunctionRepr,
+    #[serde(rename(deserialize = "finalDensity"))]
+    pub final_density: DensityFunctionRepr,
+    #[serde(rename(deserialize = "veinToggle"))]
+    pub vein_toggle: DensityFunctionRepr,
+    #[serde(rename(deserialize = "veinRidged"))]
+    pub vein_ridged: DensityFunctionRepr,
+    #[serde(rename(deserialize = "veinGap"))]
+    pub vein_gap: DensityFunctionRepr,
+}
@@ -117,6 +117,10 @@ pub mod scoreboard;
 #[path = "generated/damage_type.rs"]
 pub mod damage;
 
+#[rustfmt::skip]
+#[path = "generated/dimension_type.rs"]
+pub mod dimension_type;
+
 #[rustfmt::skip]
 #[path = "generated/fluid.rs"]
 pub mod fluid;
@@ -165,6 +169,7 @@ mod block_direction;
 pub mod block_state;
 mod blocks;
 mod collision_shape;
+pub mod density_function_repr;
 
 pub use block_direction::BlockDirection;
 pub use block_direction::FacingExt;
@@ -1,6 +1,8 @@
 use pumpkin_protocol::codec::var_int::VarInt;
 use serde::{Deserialize, Serialize};
 
+use crate::DataPool;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Biome {
     has_precipitation: bool,
@@ -21,6 +23,8 @@ struct BiomeEffects {
     #[serde(skip_serializing_if = "Option::is_none")]
     grass_color: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    dry_foliage_color: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     grass_color_modifier: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     particle: Option<Particle>,
@@ -30,8 +34,8 @@ struct BiomeEffects {
     mood_sound: Option<MoodSound>,
     #[serde(skip_serializing_if = "Option::is_none")]
     additions_sound: Option<AdditionsSound>,
-    //   #[serde(skip_serializing_if = "Option::is_none")]
-    //   music: Option<Vec<DataPool<Music>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    music: Option<Vec<DataPool<Music>>>,
     music_volume: f32,
 }
 
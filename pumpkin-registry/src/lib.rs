@@ -296,3 +296,39 @@ impl Registry {
         ]
     }
 }
+
+/// Checks that every biome the generator writes into chunks is sent to the clients under the id
+/// the chunks use for it, its index in the synced biome registry. The clients would take any
+/// other biome for an unknown or a wrong one. Returns the names of the biomes that aren't.
+pub fn check_biome_ids() -> Result<(), Vec<&'static str>> {
+    let mismatched: Vec<_> = (0..=u8::MAX)
+        .filter_map(pumpkin_data::chunk::Biome::from_id)
+        .filter(|biome| {
+            SYNCED_REGISTRIES.biome.get_index_of(biome.registry_id) != Some(usize::from(biome.id))
+        })
+        .map(|biome| biome.registry_id)
+        .collect();
+    if mismatched.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatched)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SYNCED_REGISTRIES, check_biome_ids};
+
+    #[test]
+    fn generated_biomes_are_synced() {
+        assert_eq!(check_biome_ids(), Ok(()));
+        // Along with what the clients render them with
+        let serialized = serde_json::to_value(&SYNCED_REGISTRIES.biome["deep_dark"]).unwrap();
+        let effects = &serialized["effects"];
+        assert!(effects["sky_color"].is_i64());
+        assert_eq!(
+            effects["music"][0]["data"]["sound"],
+            "minecraft:music.overworld.deep_dark"
+        );
+    }
+}
@@ -260,28 +260,11 @@ impl TrapezoidFloatProvider {
     }
 
     pub fn get(&self, random: &mut impl RandomImpl) -> f32 {
-        // Trapezoid distribution: flat plateau in the middle, linear ramps on sides
+        // The sum of two uniform values, one stretched over the ramps and one over the rest
         let range = self.max - self.min;
-        let plateau_range = range * self.plateau;
-        let ramp_range = (range - plateau_range) * 0.5;
-
-        let random_value = random.next_f32();
-
-        if random_value < 0.5 - self.plateau * 0.5 {
-            // Left ramp: quadratic distribution biased toward plateau
-            let scaled = random_value / (0.5 - self.plateau * 0.5);
-            let sqrt_scaled = scaled.sqrt();
-            self.min + ramp_range * sqrt_scaled
-        } else if random_value > 0.5 + self.plateau * 0.5 {
-            // Right ramp: quadratic distribution biased toward plateau
-            let scaled = (random_value - (0.5 + self.plateau * 0.5)) / (0.5 - self.plateau * 0.5);
-            let sqrt_scaled = (1.0 - scaled).sqrt();
-            self.max - ramp_range * sqrt_scaled
-        } else {
-            // Plateau: uniform distribution
-            let plateau_pos = (random_value - (0.5 - self.plateau * 0.5)) / self.plateau;
-            self.min + ramp_range + plateau_pos * plateau_range
-        }
+        let ramp = (range - self.plateau) / 2.0;
+        let rest = range - ramp;
+        self.min + random.next_f32() * rest + random.next_f32() * ramp
     }
 
     pub fn get_max(&self) -> f32 {
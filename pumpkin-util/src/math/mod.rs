@@ -1,3 +1,5 @@
+use std::sync::LazyLock;
+
 use num_traits::{Float, One, PrimInt, Zero};
 
 pub mod boundingbox;
@@ -41,6 +43,24 @@ pub fn magnitude(a: f64, b: f64, c: f64) -> f64 {
     squared_magnitude(a, b, c).sqrt()
 }
 
+/// The sine of every 65536th of a full turn, like vanilla's `Mth.SIN`.
+static SIN_TABLE: LazyLock<Box<[f32]>> = LazyLock::new(|| {
+    (0..65536)
+        .map(|i| (i as f64 * std::f64::consts::TAU / 65536.0).sin() as f32)
+        .collect()
+});
+
+/// The sine of `value` looked up in a table, exactly like vanilla's `Mth.sin`. World generation
+/// has to use this instead of [`f64::sin`] to shape terrain the same way.
+pub fn sin(value: f64) -> f32 {
+    SIN_TABLE[((value * 10430.378350470453) as i64 & 65535) as usize]
+}
+
+/// The cosine of `value` looked up in a table, exactly like vanilla's `Mth.cos`.
+pub fn cos(value: f64) -> f32 {
+    SIN_TABLE[((value * 10430.378350470453 + 16384.0) as i64 & 65535) as usize]
+}
+
 /// Converts a world coordinate to the corresponding chunk-section coordinate.
 // TODO: This probably shouldn't be placed here
 pub const fn get_section_cord(coord: i32) -> i32 {
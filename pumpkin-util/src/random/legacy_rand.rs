@@ -95,7 +95,7 @@ impl RandomImpl for LegacyRand {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct LegacySplitter {
     seed: u64,
 }
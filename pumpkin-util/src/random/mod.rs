@@ -37,14 +37,16 @@ pub enum RandomGenerator {
     Legacy(LegacyRand),
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 #[enum_dispatch(RandomDeriverImpl)]
 pub enum RandomDeriver {
     Xoroshiro(XoroshiroSplitter),
     Legacy(LegacySplitter),
 }
 
-// TODO: Write unit test for this
+/// Adds `get_population_seed` to a random: the seed the features of the chunk starting at
+/// `block_x`, `block_z` are placed from, the way vanilla's `WorldgenRandom::setDecorationSeed`
+/// works it out with this kind of random.
 #[macro_export]
 macro_rules! population_seed_fn {
     () => {
@@ -60,7 +62,15 @@ macro_rules! population_seed_fn {
     };
 }
 
-// TODO: Write unit test for this
+/// The seed the features of the chunk at `chunk_x`, `chunk_z` are placed from, which is what
+/// vanilla decorates chunks with.
+#[inline]
+pub fn population_seed(world_seed: u64, chunk_x: i32, chunk_z: i32) -> u64 {
+    Xoroshiro::get_population_seed(world_seed, chunk_x << 4, chunk_z << 4)
+}
+
+/// The seed the feature at `index` of decoration step `step` is placed with, salted from the
+/// [`population_seed`] of its chunk like vanilla's `WorldgenRandom::setFeatureSeed`.
 #[inline]
 pub fn get_decorator_seed(population_seed: u64, index: usize, step: usize) -> u64 {
     population_seed
@@ -152,7 +162,9 @@ pub fn hash_block_pos(x: i32, y: i32, z: i32) -> i64 {
 #[cfg(test)]
 mod tests {
 
-    use super::hash_block_pos;
+    use super::{
+        RandomImpl, get_decorator_seed, hash_block_pos, population_seed, xoroshiro128::Xoroshiro,
+    };
 
     #[test]
     fn block_position_hash() {
@@ -171,4 +183,38 @@ mod tests {
             assert_eq!(hash_block_pos(x, y, z), value);
         }
     }
+
+    #[test]
+    fn population_seed_matches_vanilla() {
+        let values: [(u64, (i32, i32), u64); 8] = [
+            (0, (0, 0), 0),
+            (0, (3, -7), 6201346228156482944),
+            (0, (-100, 250), 7249680717734513440),
+            (42, (3, -7), 3786945509393804074),
+            (42, (-100, 250), 5331163800687209994),
+            (u64::MAX, (3, -7), 5596013377720191135),
+            (u64::MAX, (-100, 250), -4102193136865524577i64 as u64),
+            (1234567890, (-100, 250), 1596061360112996850),
+        ];
+
+        for (world_seed, (x, z), value) in values {
+            assert_eq!(population_seed(world_seed, x, z), value);
+        }
+    }
+
+    #[test]
+    fn feature_seed_matches_vanilla() {
+        // The first squares a feature at index 5 of the vegetal step is spread over, as vanilla
+        // picks them
+        let mut random = Xoroshiro::from_seed(get_decorator_seed(population_seed(0, 3, -7), 5, 9));
+        let squares: Vec<_> = (0..3)
+            .map(|_| {
+                (
+                    48 + random.next_bounded_i32(16),
+                    -112 + random.next_bounded_i32(16),
+                )
+            })
+            .collect();
+        assert_eq!(squares, [(61, -102), (62, -108), (59, -104)]);
+    }
 }
@@ -129,7 +129,7 @@ impl RandomImpl for Xoroshiro {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 pub struct XoroshiroSplitter {
     lo: u64,
     hi: u64,
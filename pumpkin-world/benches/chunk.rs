@@ -1,9 +1,14 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use pumpkin_data::noise_router::OVERWORLD_BASE_NOISE_ROUTER;
+use pumpkin_data::{Block, chunk::Biome, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
 use pumpkin_world::{
     GENERATION_SETTINGS, GeneratorSetting, GlobalRandomConfig, ProtoNoiseRouters,
     bench_create_and_populate_biome, bench_create_and_populate_noise,
-    bench_create_and_populate_noise_with_surface, generation::proto_chunk::TerrainCache,
+    bench_create_and_populate_noise_with_surface,
+    chunk::{
+        BIOME_VOLUME, CHUNK_AREA, CHUNK_WIDTH, ChunkSections, SUBCHUNK_VOLUME, SubChunk,
+        palette::BiomePalette,
+    },
+    generation::proto_chunk::TerrainCache,
 };
 
 fn bench_terrain_gen(c: &mut Criterion) {
@@ -53,5 +58,77 @@ fn bench_terrain_gen(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_terrain_gen);
+fn bench_section_copy(c: &mut Criterion) {
+    // A dense section, like one deep underground with ores, caves and aquifers
+    let states = [
+        Block::STONE.default_state.id,
+        Block::DEEPSLATE.default_state.id,
+        Block::COAL_ORE.default_state.id,
+        Block::IRON_ORE.default_state.id,
+        Block::GRAVEL.default_state.id,
+        Block::WATER.default_state.id,
+        Block::AIR.default_state.id,
+    ];
+    let mut blocks = [0; SUBCHUNK_VOLUME];
+    for (index, block) in blocks.iter_mut().enumerate() {
+        *block = states[(index * 31 / 17) % states.len()];
+    }
+    let sections = || ChunkSections::new(Box::new([SubChunk::default()]), 0);
+
+    c.bench_function("section copy one by one", |b| {
+        b.iter(|| {
+            let mut sections = sections();
+            for (index, block) in blocks.iter().enumerate() {
+                sections.set_relative_block(
+                    index % CHUNK_WIDTH,
+                    index / CHUNK_AREA,
+                    index / CHUNK_WIDTH % CHUNK_WIDTH,
+                    *block,
+                );
+            }
+            sections
+        });
+    });
+
+    c.bench_function("section copy filled", |b| {
+        b.iter(|| {
+            let mut sections = sections();
+            sections.fill_section_from(0, &blocks);
+            sections
+        });
+    });
+
+    // Where a river cuts through a forest by the coast
+    let biomes = [Biome::FOREST.id, Biome::RIVER.id, Biome::BEACH.id];
+    let mut cells = [0; BIOME_VOLUME];
+    for (index, cell) in cells.iter_mut().enumerate() {
+        *cell = biomes[(index * 5 / 3) % biomes.len()];
+    }
+
+    c.bench_function("section biomes one by one", |b| {
+        b.iter(|| {
+            let mut sections = sections();
+            let size = BiomePalette::SIZE;
+            for (index, cell) in cells.iter().enumerate() {
+                sections.set_relative_biome(
+                    index % size,
+                    index / (size * size),
+                    index / size % size,
+                    *cell,
+                );
+            }
+            sections
+        });
+    });
+
+    c.bench_function("section biomes filled", |b| {
+        b.iter(|| {
+            let mut sections = sections();
+            sections.fill_section_biomes_from(0, &cells);
+            sections
+        });
+    });
+}
+
+criterion_group!(benches, bench_terrain_gen, bench_section_copy);
 criterion_main!(benches);
@@ -3,8 +3,11 @@ use criterion::{Criterion, criterion_group, criterion_main};
 use pumpkin_data::BlockDirection;
 use pumpkin_util::math::position::BlockPos;
 use pumpkin_util::math::vector2::Vector2;
-use pumpkin_world::generation::generator::WorldGenerator;
+use pumpkin_world::generation::generator::{
+    LoadRequest, WorkerSettings, WorldGenerator, initialize_generator,
+};
 use std::sync::Arc;
+use std::time::Duration;
 use temp_dir::TempDir;
 
 use pumpkin_world::dimension::Dimension;
@@ -29,10 +32,15 @@ impl BlockRegistryExt for BlockRegistry {
 }
 
 fn chunk_generation_seed(seed: i64) {
-    let generator: Arc<dyn WorldGenerator> =
-        get_world_gen(Seed(seed as u64), Dimension::Overworld).into();
-    let temp_dir = TempDir::new().unwrap();
     let block_registry = Arc::new(BlockRegistry);
+    let generator: Arc<dyn WorldGenerator> = get_world_gen(
+        Seed(seed as u64),
+        Dimension::Overworld,
+        block_registry.clone(),
+    )
+    .unwrap()
+    .into();
+    let temp_dir = TempDir::new().unwrap();
     let level = Arc::new(Level::from_root_folder(
         temp_dir.path().to_path_buf(),
         block_registry.clone(),
@@ -46,16 +54,62 @@ fn chunk_generation_seed(seed: i64) {
         .collect();
 
     positions.par_iter().for_each(|position| {
-        generator.generate_chunk(&level, block_registry.as_ref(), position);
+        generator.request_load(&level, LoadRequest::new(*position, 1));
     });
 }
 
+/// Generates a square of 15 by 15 chunks on a pool of `threads` generator threads.
+fn chunk_generation_pool(threads: usize) {
+    let generator: Arc<dyn WorldGenerator> =
+        get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+            .unwrap()
+            .into();
+    let temp_dir = TempDir::new().unwrap();
+    let level = Level::from_root_folder(
+        temp_dir.path().to_path_buf(),
+        Arc::new(BlockRegistry),
+        0,
+        Dimension::Overworld,
+    );
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    let (settings, workers) = WorkerSettings::new(threads);
+    let threads: Vec<_> = workers
+        .into_iter()
+        .map(|worker| {
+            let (rx, generator, level, settings) = (
+                rx.clone(),
+                generator.clone(),
+                level.clone(),
+                settings.clone(),
+            );
+            std::thread::spawn(move || initialize_generator(rx, generator, level, worker, settings))
+        })
+        .collect();
+    tx.send(LoadRequest::new(Vector2::new(0, 0), 8).into())
+        .unwrap();
+    while level.loaded_chunk_count() < 15 * 15 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    drop(tx);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
 fn bench_chunk_generation(c: &mut Criterion) {
     let seeds = [0];
     for seed in seeds {
         let name = format!("chunk generation seed {seed}");
         c.bench_function(&name, |b| b.iter(|| chunk_generation_seed(seed)));
     }
+
+    // The speedup over a single thread should be close to the number of cores
+    for threads in [1, num_cpus::get()] {
+        let name = format!("chunk generation pool of {threads} threads");
+        c.bench_function(&name, |b| b.iter(|| chunk_generation_pool(threads)));
+    }
 }
 
 criterion_group! {
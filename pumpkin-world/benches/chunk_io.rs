@@ -15,7 +15,7 @@ use tokio::{runtime::Runtime, sync::RwLock};
 
 async fn test_reads(level: &Arc<Level>, positions: Vec<Vector2<i32>>) {
     let level = level.clone();
-    let mut receiver = level.receive_chunks(positions);
+    let mut receiver = level.receive_chunks(positions, Vector2::new(0, 0));
 
     while let Some(x) = receiver.recv().await {
         // Don't compile me away!
@@ -114,7 +114,7 @@ fn initialize_level(
         let chunks_to_generate = (MIN_CHUNK..MAX_CHUNK)
             .flat_map(|x| (MIN_CHUNK..MAX_CHUNK).map(move |z| Vector2::new(x, z)))
             .collect::<Vec<_>>();
-        let mut receiver = level_to_fetch.receive_chunks(chunks_to_generate);
+        let mut receiver = level_to_fetch.receive_chunks(chunks_to_generate, Vector2::new(0, 0));
 
         while let Some((chunk, _)) = receiver.recv().await {
             let pos = chunk.read().await.position;
@@ -23,7 +23,7 @@ impl BiomeSupplier for TheEndBiomeSupplier {
     fn biome(
         global_biome_pos: &Vector3<i32>,
         noise: &mut MultiNoiseSampler<'_>,
-        _dimension: Dimension,
+        _dimension: &Dimension,
     ) -> &'static Biome {
         let x = biome_coords::to_block(global_biome_pos.x);
         let y = biome_coords::to_block(global_biome_pos.y);
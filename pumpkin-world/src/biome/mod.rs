@@ -21,7 +21,7 @@ pub trait BiomeSupplier {
     fn biome(
         at: &Vector3<i32>,
         noise: &mut MultiNoiseSampler<'_>,
-        dimension: Dimension,
+        dimension: &Dimension,
     ) -> &'static Biome;
 }
 
@@ -31,12 +31,13 @@ impl BiomeSupplier for MultiNoiseBiomeSupplier {
     fn biome(
         global_biome_pos: &Vector3<i32>,
         noise: &mut MultiNoiseSampler<'_>,
-        dimension: Dimension,
+        dimension: &Dimension,
     ) -> &'static Biome {
-        let source: &'static BiomeTree = match dimension {
+        let source: &'static BiomeTree = match dimension.vanilla() {
             Dimension::Overworld => &OVERWORLD_BIOME_SOURCE,
             Dimension::Nether => &NETHER_BIOME_SOURCE,
             Dimension::End => unreachable!(), // Use TheEndBiomeSupplier
+            Dimension::Custom(_) => unreachable!(), // `vanilla` resolves custom dimensions
         };
         let point = noise.sample(global_biome_pos.x, global_biome_pos.y, global_biome_pos.z);
         let point_list = point.convert_to_list();
@@ -87,7 +88,7 @@ mod test {
         let biome = MultiNoiseBiomeSupplier::biome(
             &pumpkin_util::math::vector3::Vector3 { x: -24, y: 1, z: 8 },
             &mut sampler,
-            Dimension::Overworld,
+            &Dimension::Overworld,
         );
         assert_eq!(biome, &Biome::DESERT)
     }
@@ -124,7 +125,7 @@ mod test {
                 &terrain_cache,
                 default_block,
             );
-            chunk.populate_biomes(Dimension::Overworld);
+            chunk.populate_biomes(&Dimension::Overworld);
 
             for (biome_x, biome_y, biome_z, biome_id) in data.data {
                 let global_biome_pos = Vector3::new(biome_x, biome_y, biome_z);
@@ -146,6 +147,52 @@ mod test {
         }
     }
 
+    #[test]
+    fn cave_biomes_lie_below_the_surface() {
+        let random_config = GlobalRandomConfig::new(0, false);
+        let noise_router =
+            ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &random_config);
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let terrain_cache = TerrainCache::from_random(&random_config);
+        // Dripstone caves under a forest for this seed
+        let chunk_pos = Vector2::new(-200, -100);
+        let mut chunk = ProtoChunk::new(
+            chunk_pos,
+            &noise_router,
+            &random_config,
+            settings,
+            &terrain_cache,
+            settings.default_block.get_state(),
+        );
+        chunk.populate_biomes(&Dimension::Overworld);
+
+        let caves = [
+            &Biome::DRIPSTONE_CAVES,
+            &Biome::LUSH_CAVES,
+            &Biome::DEEP_DARK,
+        ];
+        let mut underground = 0;
+        for x in 0..4 {
+            for z in 0..4 {
+                let biome_at = |y: i32| {
+                    chunk.get_biome(&Vector3::new(
+                        chunk_pos.x * 4 + x,
+                        y >> 2,
+                        chunk_pos.y * 4 + z,
+                    ))
+                };
+                // The same column changes biome with the depth
+                assert!(!caves.contains(&biome_at(80)));
+                if biome_at(-30) == &Biome::DRIPSTONE_CAVES {
+                    underground += 1;
+                }
+            }
+        }
+        assert!(underground > 0);
+    }
+
     #[test]
     fn test_hash_seed() {
         let hashed_seed = hash_seed(0);
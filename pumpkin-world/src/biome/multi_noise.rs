@@ -105,7 +105,7 @@ mod test {
             let calculated_biome = MultiNoiseBiomeSupplier::biome(
                 &global_biome_pos,
                 &mut sampler,
-                Dimension::Overworld,
+                &Dimension::Overworld,
             );
 
             assert_eq!(
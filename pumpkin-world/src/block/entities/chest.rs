@@ -33,6 +33,9 @@ pub struct ChestBlockEntity {
     pub position: BlockPos,
     pub items: [Arc<Mutex<ItemStack>>; 27],
     pub dirty: AtomicBool,
+    /// The loot table the chest is filled from once it is first opened, like
+    /// `minecraft:chests/desert_pyramid` for the chests of structures.
+    pub loot_table: Option<String>,
 
     // Viewer
     pub viewers: ViewerCountTracker,
@@ -56,6 +59,7 @@ impl BlockEntity for ChestBlockEntity {
             position,
             items: from_fn(|_| Arc::new(Mutex::new(ItemStack::EMPTY.clone()))),
             dirty: AtomicBool::new(false),
+            loot_table: nbt.get_string("LootTable").map(str::to_string),
             viewers: ViewerCountTracker::new(),
         };
 
@@ -66,6 +70,9 @@ impl BlockEntity for ChestBlockEntity {
 
     async fn write_nbt(&self, nbt: &mut pumpkin_nbt::compound::NbtCompound) {
         self.write_data(nbt, &self.items, true).await;
+        if let Some(loot_table) = &self.loot_table {
+            nbt.put_string("LootTable", loot_table.clone());
+        }
         // Safety precaution
         //self.clear().await;
     }
@@ -121,10 +128,19 @@ impl ChestBlockEntity {
             position,
             items: from_fn(|_| Arc::new(Mutex::new(ItemStack::EMPTY.clone()))),
             dirty: AtomicBool::new(false),
+            loot_table: None,
             viewers: ViewerCountTracker::new(),
         }
     }
 
+    /// An empty chest to be filled from `loot_table` once it is first opened.
+    pub fn with_loot_table(position: BlockPos, loot_table: &str) -> Self {
+        Self {
+            loot_table: Some(loot_table.to_string()),
+            ..Self::new(position)
+        }
+    }
+
     async fn play_sound(&self, world: &Arc<dyn SimpleWorld>, sound: Sound) {
         let mut rng = Xoroshiro::from_seed(get_seed());
 
@@ -37,12 +37,44 @@ fn block_to_string<S: Serializer>(block: &'static Block, serializer: S) -> Resul
     serializer.serialize_str(block.name)
 }
 
+impl From<&BlockState> for BlockStateCodec {
+    fn from(state: &BlockState) -> Self {
+        let block = Block::from_state_id(state.id);
+        Self {
+            name: block,
+            properties: block
+                .properties(state.id)
+                .map(|props| props.to_props().into_iter().collect()),
+        }
+    }
+}
+
 impl BlockStateCodec {
     pub fn get_state(&self) -> &'static BlockState {
         let state_id = self.get_state_id();
         BlockState::from_id(state_id)
     }
 
+    /// Like [`BlockStateCodec::get_state`], but `None` instead of a panic if the block doesn't
+    /// have these properties.
+    pub fn try_get_state(&self) -> Option<&'static BlockState> {
+        let block = self.name;
+        let Some(properties) = self.properties.as_ref().filter(|props| !props.is_empty()) else {
+            return Some(block.default_state);
+        };
+
+        let mut props = block.properties(block.default_state.id)?.to_props();
+        for (key, value) in properties {
+            let (_, prop) = props.iter_mut().find(|(prop_key, _)| prop_key == key)?;
+            prop.clone_from(value);
+        }
+        block.states.iter().find(|state| {
+            block
+                .properties(state.id)
+                .is_some_and(|state_props| state_props.to_props() == props)
+        })
+    }
+
     pub fn get_block(&self) -> &'static Block {
         self.name
     }
@@ -67,8 +99,11 @@ impl BlockStateCodec {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
+
     use pumpkin_data::Block;
 
+    use super::BlockStateCodec;
     use crate::chunk::palette::BLOCK_NETWORK_MAX_BITS;
 
     #[test]
@@ -78,4 +113,16 @@ mod test {
             panic!("We need to update our constants!");
         }
     }
+
+    #[test]
+    fn unknown_properties_have_no_state() {
+        let codec = |key: &str, value: &str| BlockStateCodec {
+            name: &Block::WATER,
+            properties: Some(HashMap::from([(key.to_string(), value.to_string())])),
+        };
+        let state = codec("level", "0").try_get_state().unwrap();
+        assert_eq!(state.id, codec("level", "0").get_state().id);
+        assert!(codec("level", "16").try_get_state().is_none());
+        assert!(codec("waterlogged", "true").try_get_state().is_none());
+    }
 }
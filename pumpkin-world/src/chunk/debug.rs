@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, HashMap};
+
+use pumpkin_data::{Block, chunk::Biome, chunk::ChunkStatus};
+use serde::Serialize;
+
+use super::{
+    CHUNK_WIDTH, ChunkData, ChunkHeightmapType, format::LightContainer, palette::BlockPalette,
+};
+
+/// A summary of a chunk meant to be read and diffed by people rather than loaded again.
+#[derive(Serialize)]
+struct ChunkSummary<'a> {
+    x: i32,
+    z: i32,
+    status: &'a ChunkStatus,
+    min_y: i32,
+    sections: Vec<SectionSummary>,
+    /// The y of the highest block of every column each heightmap counts, row by row along z.
+    heightmaps: BTreeMap<&'static str, Vec<Vec<i32>>>,
+    light: Vec<LightSummary>,
+}
+
+#[derive(Serialize)]
+struct SectionSummary {
+    y: i32,
+    blocks: Vec<PaletteEntry>,
+    biomes: Vec<PaletteEntry>,
+}
+
+/// A block state or biome with how many blocks or biome cells of the section it fills.
+#[derive(Serialize)]
+struct PaletteEntry {
+    name: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    properties: BTreeMap<String, String>,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct LightSummary {
+    y: i32,
+    /// The lowest and highest level in the section.
+    sky: [u8; 2],
+    block: [u8; 2],
+}
+
+impl ChunkData {
+    /// Summarizes the blocks, biomes, heightmaps and light of the chunk section by section as
+    /// pretty printed JSON, e.g. to compare the output of two generators or to attach to a bug
+    /// report. Palette entries are sorted by count, so the bulk of a section comes first.
+    pub fn to_debug_json(&self) -> String {
+        let min_y = self.section.min_y;
+        let min_section_y = min_y.div_euclid(BlockPalette::SIZE as i32);
+
+        let sections = self
+            .section
+            .sections
+            .iter()
+            .zip(min_section_y..)
+            .map(|(section, y)| {
+                let mut blocks = HashMap::new();
+                section
+                    .block_states
+                    .for_each(|state_id| *blocks.entry(state_id).or_insert(0) += 1);
+                let mut biomes = HashMap::new();
+                section
+                    .biomes
+                    .for_each(|biome_id| *biomes.entry(biome_id).or_insert(0) += 1);
+                SectionSummary {
+                    y,
+                    blocks: sorted_by_count(blocks.into_iter().map(|(state_id, count)| {
+                        let block = Block::from_state_id(state_id);
+                        PaletteEntry {
+                            name: block.name.to_string(),
+                            properties: block
+                                .properties(state_id)
+                                .map(|props| props.to_props().into_iter().collect())
+                                .unwrap_or_default(),
+                            count,
+                        }
+                    })),
+                    biomes: sorted_by_count(biomes.into_iter().map(|(biome_id, count)| {
+                        PaletteEntry {
+                            name: Biome::from_id(biome_id).map_or_else(
+                                || biome_id.to_string(),
+                                |biome| biome.registry_id.to_string(),
+                            ),
+                            properties: BTreeMap::new(),
+                            count,
+                        }
+                    })),
+                }
+            })
+            .collect();
+
+        let heightmaps = [
+            ("world_surface", ChunkHeightmapType::WorldSurface),
+            ("motion_blocking", ChunkHeightmapType::MotionBlocking),
+            (
+                "motion_blocking_no_leaves",
+                ChunkHeightmapType::MotionBlockingNoLeaves,
+            ),
+            ("ocean_floor", ChunkHeightmapType::OceanFloor),
+        ]
+        .into_iter()
+        .map(|(name, heightmap)| {
+            let rows = (0..CHUNK_WIDTH as i32)
+                .map(|z| {
+                    (0..CHUNK_WIDTH as i32)
+                        .map(|x| self.heightmap.get_height(heightmap, x, z, min_y))
+                        .collect()
+                })
+                .collect();
+            (name, rows)
+        })
+        .collect();
+
+        // The light reaches one section past the blocks on either side
+        let light = self
+            .light_engine
+            .sky_light
+            .iter()
+            .zip(self.light_engine.block_light.iter())
+            .zip(min_section_y - 1..)
+            .map(|((sky, block), y)| LightSummary {
+                y,
+                sky: light_range(sky),
+                block: light_range(block),
+            })
+            .collect();
+
+        let summary = ChunkSummary {
+            x: self.position.x,
+            z: self.position.y,
+            status: &self.status,
+            min_y,
+            sections,
+            heightmaps,
+            light,
+        };
+        serde_json::to_string_pretty(&summary).expect("Chunk summaries are plain data")
+    }
+}
+
+fn sorted_by_count(entries: impl Iterator<Item = PaletteEntry>) -> Vec<PaletteEntry> {
+    let mut entries: Vec<_> = entries.collect();
+    entries.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.properties.cmp(&b.properties))
+    });
+    entries
+}
+
+fn light_range(light: &LightContainer) -> [u8; 2] {
+    match light {
+        LightContainer::Empty(level) => [*level, *level],
+        LightContainer::Full(_) => {
+            let mut range = [u8::MAX, 0];
+            for y in 0..LightContainer::DIM {
+                for z in 0..LightContainer::DIM {
+                    for x in 0..LightContainer::DIM {
+                        let level = light.get(x, y, z);
+                        range = [range[0].min(level), range[1].max(level)];
+                    }
+                }
+            }
+            range
+        }
+    }
+}
@@ -837,6 +837,7 @@ mod tests {
     use crate::chunk::io::file_manager::{ChunkFileManager, PathFromLevelFolder};
     use crate::chunk::io::{FileIO, LoadedData};
     use crate::dimension::Dimension;
+    use crate::generation::generator::LoadRequest;
     use crate::generation::{Seed, get_world_gen};
     use crate::level::{Level, LevelFolder, SyncChunk};
     use crate::world::{BlockAccessor, BlockRegistryExt};
@@ -927,7 +928,8 @@ mod tests {
 
         let _ = env_logger::try_init();
 
-        let generator = get_world_gen(Seed(0), Dimension::Overworld);
+        let generator =
+            get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
 
         let temp_dir = TempDir::new().unwrap();
         let level_folder = LevelFolder {
@@ -947,12 +949,16 @@ mod tests {
             0,
             Dimension::Overworld,
         ));
-        for x in -5..5 {
-            for y in -5..5 {
-                let position = Vector2::new(x, y);
-                let chunk = generator.generate_chunk(&level, block_registry.as_ref(), &position);
-                chunks.push((position, Arc::new(RwLock::new(chunk))));
-            }
+        let positions: Vec<_> = (-5..5)
+            .flat_map(|x| (-5..5).map(move |y| Vector2::new(x, y)))
+            .collect();
+        generator.request_load(
+            &level,
+            LoadRequest::for_chunks(Vector2::new(0, 0), positions.iter().copied()),
+        );
+        for position in positions {
+            let chunk = level.try_get_chunk(&position).unwrap().clone();
+            chunks.push((position, chunk));
         }
 
         // TEST APPEND TO END
@@ -1203,7 +1209,8 @@ mod tests {
 
         let _ = env_logger::try_init();
 
-        let generator = get_world_gen(Seed(0), Dimension::Overworld);
+        let generator =
+            get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
 
         let temp_dir = TempDir::new().unwrap();
         let level_folder = LevelFolder {
@@ -1223,12 +1230,16 @@ mod tests {
             0,
             Dimension::Overworld,
         ));
-        for x in -5..5 {
-            for y in -5..5 {
-                let position = Vector2::new(x, y);
-                let chunk = generator.generate_chunk(&level, block_registry.as_ref(), &position);
-                chunks.push((position, Arc::new(RwLock::new(chunk))));
-            }
+        let positions: Vec<_> = (-5..5)
+            .flat_map(|x| (-5..5).map(move |y| Vector2::new(x, y)))
+            .collect();
+        generator.request_load(
+            &level,
+            LoadRequest::for_chunks(Vector2::new(0, 0), positions.iter().copied()),
+        );
+        for position in positions {
+            let chunk = level.try_get_chunk(&position).unwrap().clone();
+            chunks.push((position, chunk));
         }
 
         for _ in 0..5 {
@@ -1284,6 +1295,49 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn generated_chunks_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let level = Arc::new(Level::from_root_folder(
+            temp_dir.path().to_path_buf(),
+            Arc::new(BlockRegistry),
+            0,
+            Dimension::Overworld,
+        ));
+        let position = Vector2::new(0, 0);
+        get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+            .unwrap()
+            .request_load(&level, LoadRequest::new(position, 1));
+        let chunk = level.try_get_chunk(&position).unwrap().clone();
+        let chunk = chunk.read().await;
+
+        let bytes = chunk.to_bytes().await.unwrap();
+        let read = ChunkData::from_bytes(bytes, position).unwrap();
+
+        assert_eq!(read.section.min_y, chunk.section.min_y);
+        assert_eq!(read.section.dump_blocks(), chunk.section.dump_blocks());
+        assert_eq!(read.section.dump_biomes(), chunk.section.dump_biomes());
+        assert_eq!(read.heightmap.world_surface, chunk.heightmap.world_surface);
+        assert_eq!(
+            read.heightmap.motion_blocking,
+            chunk.heightmap.motion_blocking
+        );
+        assert_eq!(read.heightmap.ocean_floor, chunk.heightmap.ocean_floor);
+        // Light is kept for the sections above and below the chunk too
+        let light = |chunk: &ChunkData| {
+            let light = &chunk.light_engine;
+            light
+                .sky_light
+                .iter()
+                .chain(&light.block_light)
+                .flat_map(|light| {
+                    (0..16 * 16 * 16).map(|i| light.get(i & 15, i >> 8, (i >> 4) & 15))
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(light(&read), light(&chunk));
+    }
+
     // TODO
     /*
     #[test]
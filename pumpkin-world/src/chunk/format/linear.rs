@@ -380,13 +380,13 @@ mod tests {
     use std::path::PathBuf;
     use std::sync::Arc;
     use temp_dir::TempDir;
-    use tokio::sync::RwLock;
 
     use crate::chunk::ChunkData;
     use crate::chunk::format::linear::LinearFile;
     use crate::chunk::io::file_manager::ChunkFileManager;
     use crate::chunk::io::{FileIO, LoadedData};
     use crate::dimension::Dimension;
+    use crate::generation::generator::LoadRequest;
     use crate::generation::{Seed, get_world_gen};
     use crate::level::{Level, LevelFolder};
     use crate::world::{BlockAccessor, BlockRegistryExt};
@@ -437,7 +437,8 @@ mod tests {
     async fn test_writing() {
         let _ = env_logger::try_init();
 
-        let generator = get_world_gen(Seed(0), Dimension::Overworld);
+        let generator =
+            get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
 
         let temp_dir = TempDir::new().unwrap();
         let level_folder = LevelFolder {
@@ -456,12 +457,16 @@ mod tests {
             0,
             Dimension::Overworld,
         ));
-        for x in -5..5 {
-            for y in -5..5 {
-                let position = Vector2::new(x, y);
-                let chunk = generator.generate_chunk(&level, block_registry.as_ref(), &position);
-                chunks.push((position, Arc::new(RwLock::new(chunk))));
-            }
+        let positions: Vec<_> = (-5..5)
+            .flat_map(|x| (-5..5).map(move |y| Vector2::new(x, y)))
+            .collect();
+        generator.request_load(
+            &level,
+            LoadRequest::for_chunks(Vector2::new(0, 0), positions.iter().copied()),
+        );
+        for position in positions {
+            let chunk = level.try_get_chunk(&position).unwrap().clone();
+            chunks.push((position, chunk));
         }
 
         for i in 0..5 {
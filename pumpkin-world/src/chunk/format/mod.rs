@@ -22,7 +22,8 @@ use pumpkin_util::math::vector2::Vector2;
 use serde::{Deserialize, Serialize};
 
 use super::{
-    ChunkData, ChunkHeightmaps, ChunkLight, ChunkParsingError, ChunkSections, SubChunk,
+    ChunkData, ChunkHeightmaps, ChunkLight, ChunkParsingError, ChunkSections, GeneratorStamp,
+    SubChunk,
     palette::{BiomePalette, BlockPalette},
 };
 use crate::block::BlockStateCodec;
@@ -133,8 +134,14 @@ impl ChunkData {
             )));
         }
 
+        // The sections just above and below the chunk are only saved for their light
+        let section_count = chunk_data
+            .sections
+            .iter()
+            .filter(|section| section.block_states.is_some() || section.biomes.is_some())
+            .count();
         let light_engine = ChunkLight {
-            block_light: (0..chunk_data.sections.len() + 2)
+            block_light: (0..section_count + 2)
                 .map(|index| {
                     chunk_data
                         .sections
@@ -147,7 +154,7 @@ impl ChunkData {
                         .unwrap_or_default()
                 })
                 .collect(),
-            sky_light: (0..chunk_data.sections.len() + 2)
+            sky_light: (0..section_count + 2)
                 .map(|index| {
                     chunk_data
                         .sections
@@ -164,7 +171,7 @@ impl ChunkData {
         let sub_chunks = chunk_data
             .sections
             .into_iter()
-            .filter(|section| section.y >= chunk_data.min_y_section as i8)
+            .filter(|section| section.block_states.is_some() || section.biomes.is_some())
             .map(|section| SubChunk {
                 block_states: section
                     .block_states
@@ -184,6 +191,7 @@ impl ChunkData {
             heightmap: chunk_data.heightmaps,
             position,
             status: chunk_data.status,
+            generated_by: chunk_data.generated_by,
             // This chunk is read from disk, so it has not been modified
             dirty: false,
             block_ticks: ChunkTickScheduler::from_vec(&chunk_data.block_ticks),
@@ -212,14 +220,8 @@ impl ChunkData {
                     y: (i as i8) - 1i8 + section_coords::block_to_section(self.section.min_y) as i8,
                     block_states: section.map(|section| section.block_states.to_disk_nbt()),
                     biomes: section.map(|section| section.biomes.to_disk_nbt()),
-                    block_light: match self.light_engine.block_light[i].clone() {
-                        LightContainer::Empty(_) => None,
-                        LightContainer::Full(data) => Some(data),
-                    },
-                    sky_light: match self.light_engine.sky_light[i].clone() {
-                        LightContainer::Empty(_) => None,
-                        LightContainer::Full(data) => Some(data),
-                    },
+                    block_light: self.light_engine.block_light[i].to_disk(),
+                    sky_light: self.light_engine.sky_light[i].to_disk(),
                 }
             })
             .filter(|nbt| {
@@ -248,6 +250,7 @@ impl ChunkData {
             .await,
             // we have not implemented light engine
             light_correct: false,
+            generated_by: self.generated_by.clone(),
         };
 
         let mut result = Vec::new();
@@ -405,6 +408,8 @@ pub enum LightContainer {
 impl LightContainer {
     pub const DIM: usize = 16;
     pub const ARRAY_SIZE: usize = Self::DIM * Self::DIM * Self::DIM / 2;
+    /// The level of light that isn't saved.
+    pub const DEFAULT_LEVEL: u8 = 15;
 
     pub fn new_empty(default: u8) -> Self {
         if default > 15 {
@@ -432,6 +437,16 @@ impl LightContainer {
         matches!(self, Self::Empty(_))
     }
 
+    /// The light to save, if any. Missing light is read back at the default level, so light of
+    /// any other level has to be saved in full.
+    fn to_disk(&self) -> Option<Box<[u8]>> {
+        match self {
+            Self::Empty(Self::DEFAULT_LEVEL) => None,
+            Self::Empty(level) => Some([*level << 4 | *level; Self::ARRAY_SIZE].into()),
+            Self::Full(data) => Some(data.clone()),
+        }
+    }
+
     fn index(x: usize, y: usize, z: usize) -> usize {
         y * 16 * 16 + z * 16 + x
     }
@@ -466,7 +481,7 @@ impl LightContainer {
 
 impl Default for LightContainer {
     fn default() -> Self {
-        Self::new_empty(15)
+        Self::new_empty(Self::DEFAULT_LEVEL)
     }
 }
 
@@ -492,6 +507,13 @@ struct ChunkNbt {
     block_entities: Vec<NbtCompound>,
     #[serde(rename = "isLightOn")]
     light_correct: bool,
+    /// Not a vanilla tag, vanilla ignores it.
+    #[serde(
+        rename = "PumpkinGenerator",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    generated_by: Option<GeneratorStamp>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
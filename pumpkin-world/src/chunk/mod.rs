@@ -9,15 +9,19 @@ use pumpkin_data::tag::Taggable;
 use pumpkin_data::{Block, BlockState};
 use pumpkin_nbt::compound::NbtCompound;
 use pumpkin_nbt::nbt_long_array;
-use pumpkin_util::math::{position::BlockPos, vector2::Vector2};
+use pumpkin_util::math::{position::BlockPos, vector2::Vector2, vector3::Vector3};
 use serde::{Deserialize, Serialize};
+use std::iter;
+use std::mem;
 use std::ops::{BitAnd, BitOr};
 use std::{collections::HashMap, sync::Arc};
 use thiserror::Error;
 
 use crate::BlockStateId;
 use crate::chunk::format::LightContainer;
+use crate::generation::light::LightRegion;
 
+mod debug;
 pub mod format;
 pub mod io;
 pub mod palette;
@@ -79,10 +83,26 @@ pub struct ChunkData {
     pub block_entities: HashMap<BlockPos, Arc<dyn BlockEntity>>,
     pub light_engine: ChunkLight,
     pub status: ChunkStatus,
+    /// What generated the chunk, unless it was saved before that was recorded.
+    pub generated_by: Option<GeneratorStamp>,
 
     pub dirty: bool,
 }
 
+/// The seed, dimension and [`GENERATOR_VERSION`] a chunk was generated with. It is saved along
+/// with the chunk, so that chunks generated with another configuration than the level's are
+/// noticed when they are loaded, instead of silently not lining up with their new neighbors.
+///
+/// [`GENERATOR_VERSION`]: crate::generation::generator::GENERATOR_VERSION
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct GeneratorStamp {
+    pub seed: i64,
+    /// The [`Dimension::name`](crate::dimension::Dimension::name).
+    pub dimension: String,
+    pub version: i32,
+}
+
 #[derive(Clone)]
 pub struct ChunkEntityData {
     pub chunk_position: Vector2<i32>,
@@ -146,6 +166,8 @@ pub enum ChunkHeightmapType {
     WorldSurface = 0,
     MotionBlocking = 1,
     MotionBlockingNoLeaves = 2,
+    /// The highest block that blocks movement, ignoring fluids. Only used on the server.
+    OceanFloor = 3,
 }
 impl TryFrom<usize> for ChunkHeightmapType {
     type Error = &'static str;
@@ -155,7 +177,8 @@ impl TryFrom<usize> for ChunkHeightmapType {
             0 => Ok(ChunkHeightmapType::WorldSurface),
             1 => Ok(ChunkHeightmapType::MotionBlocking),
             2 => Ok(ChunkHeightmapType::MotionBlockingNoLeaves),
-            _ => Err("Invalid usize value for ChunkHeightmapType. The value should be 0~2."),
+            3 => Ok(ChunkHeightmapType::OceanFloor),
+            _ => Err("Invalid usize value for ChunkHeightmapType. The value should be 0~3."),
         }
     }
 }
@@ -169,6 +192,15 @@ pub struct ChunkHeightmaps {
     pub motion_blocking: Box<[i64]>,
     #[serde(serialize_with = "nbt_long_array")]
     pub motion_blocking_no_leaves: Box<[i64]>,
+    /// Chunks saved before this heightmap was tracked load it as empty.
+    #[serde(serialize_with = "nbt_long_array", default = "empty_heightmap")]
+    pub ocean_floor: Box<[i64]>,
+}
+
+fn empty_heightmap() -> Box<[i64]> {
+    // 9 bits per entry
+    // 0 packed into an i64 7 times.
+    vec![0; 37].into_boxed_slice()
 }
 
 impl ChunkHeightmaps {
@@ -177,6 +209,7 @@ impl ChunkHeightmaps {
             ChunkHeightmapType::WorldSurface => &mut self.world_surface,
             ChunkHeightmapType::MotionBlocking => &mut self.motion_blocking,
             ChunkHeightmapType::MotionBlockingNoLeaves => &mut self.motion_blocking_no_leaves,
+            ChunkHeightmapType::OceanFloor => &mut self.ocean_floor,
         };
 
         let local_x = (pos.0.x & 15) as usize;
@@ -221,6 +254,7 @@ impl ChunkHeightmaps {
             ChunkHeightmapType::WorldSurface => &self.world_surface,
             ChunkHeightmapType::MotionBlocking => &self.motion_blocking,
             ChunkHeightmapType::MotionBlockingNoLeaves => &self.motion_blocking_no_leaves,
+            ChunkHeightmapType::OceanFloor => &self.ocean_floor,
         };
 
         let local_x = (x & 15) as usize;
@@ -276,11 +310,10 @@ impl ChunkHeightmaps {
 impl Default for ChunkHeightmaps {
     fn default() -> Self {
         Self {
-            // 9 bits per entry
-            // 0 packed into an i64 7 times.
-            motion_blocking: vec![0; 37].into_boxed_slice(),
-            motion_blocking_no_leaves: vec![0; 37].into_boxed_slice(),
-            world_surface: vec![0; 37].into_boxed_slice(),
+            motion_blocking: empty_heightmap(),
+            motion_blocking_no_leaves: empty_heightmap(),
+            world_surface: empty_heightmap(),
+            ocean_floor: empty_heightmap(),
         }
     }
 }
@@ -395,6 +428,29 @@ impl ChunkSections {
         0
     }
 
+    /// Replaces every block of the section at `index` with `blocks`, laid out by y, then z, then
+    /// x like a [`BlockPalette`]. The palette is built once for the whole section, which is a lot
+    /// cheaper than setting the blocks one by one.
+    pub fn fill_section_from(&mut self, index: usize, blocks: &[BlockStateId; SUBCHUNK_VOLUME]) {
+        let mut cube =
+            Box::new([[[0; BlockPalette::SIZE]; BlockPalette::SIZE]; BlockPalette::SIZE]);
+        cube.as_flattened_mut()
+            .as_flattened_mut()
+            .copy_from_slice(blocks);
+        self.sections[index].block_states = BlockPalette::from_cube(cube);
+    }
+
+    /// Like [`ChunkSections::fill_section_from`], but replaces the biome cells of the section at
+    /// `index` with the biome ids of `biomes`.
+    pub fn fill_section_biomes_from(&mut self, index: usize, biomes: &[u8; BIOME_VOLUME]) {
+        let mut cube =
+            Box::new([[[0; BiomePalette::SIZE]; BiomePalette::SIZE]; BiomePalette::SIZE]);
+        cube.as_flattened_mut()
+            .as_flattened_mut()
+            .copy_from_slice(biomes);
+        self.sections[index].biomes = BiomePalette::from_cube(cube);
+    }
+
     pub fn set_relative_biome(
         &mut self,
         relative_x: usize,
@@ -501,7 +557,7 @@ impl ChunkData {
         z: usize,
     ) {
         let start_height = (start_sub_chunk as i32) * 16 - self.section.min_y.abs() + 15;
-        let mut has_found = [false, false, false];
+        let mut has_found = [false; 4];
 
         for y in (self.section.min_y..=start_height).rev() {
             let pos = BlockPos::new(x as i32, y, z as i32);
@@ -514,7 +570,13 @@ impl ChunkData {
                 has_found[ChunkHeightmapType::WorldSurface as usize] = true;
             }
 
-            let is_motion_blocking = blocks_movement(block_state)
+            let blocks_motion = blocks_movement(block_state);
+            if !has_found[ChunkHeightmapType::OceanFloor as usize] && blocks_motion {
+                heightmaps.set(ChunkHeightmapType::OceanFloor, pos, self.section.min_y);
+                has_found[ChunkHeightmapType::OceanFloor as usize] = true;
+            }
+
+            let is_motion_blocking = blocks_motion
                 || Fluid::from_registry_key(block.registry_key())
                     .is_some_and(|fluid| !fluid.states.is_empty());
 
@@ -548,6 +610,91 @@ impl ChunkData {
         }
     }
 
+    /// Updates the heightmaps of the column at `relative_x`, `relative_z` and the light around it
+    /// after blocks in it were changed, e.g. by a tool editing many blocks at once, without going
+    /// over the rest of the chunk. `neighbor_block` provides the blocks of the chunks around it
+    /// like for [`LightRegion::new`]; their light is left as it is.
+    pub fn relight_column(
+        &mut self,
+        relative_x: usize,
+        relative_z: usize,
+        neighbor_block: impl Fn(Vector3<i32>) -> Option<&'static BlockState>,
+    ) {
+        let mut heightmaps = mem::take(&mut self.heightmap);
+        let highest_non_empty_subchunk = self.get_highest_non_empty_subchunk();
+        self.populate_heightmaps(
+            &mut heightmaps,
+            highest_non_empty_subchunk,
+            relative_x,
+            relative_z,
+        );
+        self.heightmap = heightmaps;
+
+        LightRegion::new(&self.section, neighbor_block).relight_column(
+            &mut self.light_engine,
+            relative_x as i32,
+            relative_z as i32,
+        );
+        self.dirty = true;
+    }
+
+    /// Fits a chunk saved with another build height into the `height` blocks above `min_y`,
+    /// like one saved before the Overworld grew deeper, by filling the sections it is missing
+    /// with air. Chunks which have blocks outside of these bounds can't be fitted.
+    pub fn fit_to_height(&mut self, min_y: i32, height: u16) -> Result<(), ChunkParsingError> {
+        let size = BlockPalette::SIZE as i32;
+        let saved_min_y = self.section.min_y;
+        let saved_max_y = saved_min_y + self.section.sections.len() as i32 * size;
+        // A partial top section is kept, like in generated chunks
+        let max_y = min_y + (height as usize).div_ceil(BlockPalette::SIZE) as i32 * size;
+        if saved_min_y < min_y || saved_max_y > max_y || (saved_min_y - min_y) % size != 0 {
+            return Err(ChunkParsingError::IncompatibleHeight {
+                saved_min_y,
+                saved_max_y,
+                min_y,
+                max_y,
+            });
+        }
+
+        let below = ((saved_min_y - min_y) / size) as usize;
+        let above = ((max_y - saved_max_y) / size) as usize;
+        if below == 0 && above == 0 {
+            return Ok(());
+        }
+
+        // The new sections keep the biome of the closest saved one
+        let pad = |section: Option<&SubChunk>| SubChunk {
+            block_states: BlockPalette::default(),
+            biomes: section.map_or_else(BiomePalette::default, |section| {
+                BiomePalette::Homogeneous(section.biomes.get(0, 0, 0))
+            }),
+        };
+        let sections = &self.section.sections;
+        let bottom = pad(sections.first());
+        let top = pad(sections.last());
+        self.section = ChunkSections::new(
+            iter::repeat_n(bottom, below)
+                .chain(sections.iter().cloned())
+                .chain(iter::repeat_n(top, above))
+                .collect(),
+            min_y,
+        );
+
+        // The sky doesn't reach down into the sections below the saved ones
+        let pad_light = |light: &mut Box<[LightContainer]>, below_level, above_level| {
+            *light = iter::repeat_with(|| LightContainer::new_empty(below_level))
+                .take(below)
+                .chain(light.iter().cloned())
+                .chain(iter::repeat_with(|| LightContainer::new_empty(above_level)).take(above))
+                .collect();
+        };
+        pad_light(&mut self.light_engine.sky_light, 0, 15);
+        pad_light(&mut self.light_engine.block_light, 0, 0);
+        self.heightmap = self.calculate_heightmap();
+        self.dirty = true;
+        Ok(())
+    }
+
     pub fn get_highest_non_empty_subchunk(&self) -> usize {
         let idx = self
             .section
@@ -569,6 +716,15 @@ pub enum ChunkParsingError {
     ChunkNotGenerated,
     #[error("Error deserializing chunk: {0}")]
     ErrorDeserializingChunk(String),
+    #[error(
+        "The chunk spans y {saved_min_y}..{saved_max_y}, which doesn't fit into y {min_y}..{max_y}"
+    )]
+    IncompatibleHeight {
+        saved_min_y: i32,
+        saved_max_y: i32,
+        min_y: i32,
+        max_y: i32,
+    },
 }
 
 #[derive(Error, Debug)]
@@ -576,3 +732,244 @@ pub enum ChunkSerializingError {
     #[error("Error serializing chunk: {0}")]
     ErrorSerializingChunk(pumpkin_nbt::Error),
 }
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use pumpkin_data::{
+        Block,
+        chunk::{Biome, ChunkStatus},
+    };
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::{
+        BIOME_VOLUME, CHUNK_AREA, CHUNK_WIDTH, ChunkData, ChunkHeightmapType, ChunkLight,
+        ChunkParsingError, ChunkSections, SUBCHUNK_VOLUME, SubChunk,
+    };
+    use crate::chunk::format::{LightContainer, anvil::SingleChunkDataSerializer};
+    use crate::chunk::palette::{BiomePalette, BlockPalette};
+
+    /// An all-air chunk of `sections` sections starting at `min_y`.
+    fn chunk(sections: usize, min_y: i32) -> ChunkData {
+        ChunkData {
+            section: ChunkSections::new(
+                (0..sections).map(|_| SubChunk::default()).collect(),
+                min_y,
+            ),
+            heightmap: Default::default(),
+            position: Vector2::new(0, 0),
+            block_ticks: Default::default(),
+            fluid_ticks: Default::default(),
+            block_entities: HashMap::new(),
+            light_engine: ChunkLight {
+                sky_light: (0..sections + 2)
+                    .map(|_| LightContainer::new_filled(15))
+                    .collect(),
+                block_light: (0..sections + 2)
+                    .map(|_| LightContainer::new_empty(0))
+                    .collect(),
+            },
+            status: ChunkStatus::Full,
+            generated_by: None,
+            dirty: false,
+        }
+    }
+
+    #[test]
+    fn shallow_chunks_fit_deeper_dimensions() {
+        // Saved before the Overworld went down to -64
+        let mut chunk = chunk(16, 0);
+        let stone = Block::STONE.default_state.id;
+        chunk.set_relative_block(0, 0, 0, stone);
+
+        chunk.fit_to_height(-64, 384).unwrap();
+        assert_eq!(chunk.section.min_y, -64);
+        assert_eq!(chunk.section.sections.len(), 24);
+        assert_eq!(chunk.light_engine.sky_light.len(), 26);
+        assert_eq!(chunk.light_engine.block_light.len(), 26);
+        assert_eq!(chunk.section.get_block_absolute_y(0, 0, 0), Some(stone));
+        assert_eq!(
+            chunk.section.get_block_absolute_y(0, -1, 0),
+            Some(Block::AIR.default_state.id)
+        );
+        assert!(chunk.dirty);
+    }
+
+    #[test]
+    fn filled_sections_match_blocks_set_one_by_one() {
+        let states = [
+            Block::STONE.default_state.id,
+            Block::DIRT.default_state.id,
+            Block::WATER.default_state.id,
+            Block::AIR.default_state.id,
+        ];
+        let mut blocks = [0; SUBCHUNK_VOLUME];
+        for (index, block) in blocks.iter_mut().enumerate() {
+            *block = states[(index * 7 / 5) % states.len()];
+        }
+
+        let mut filled = chunk(2, 0);
+        filled.section.fill_section_from(1, &blocks);
+        let mut set = chunk(2, 0);
+        for (index, block) in blocks.iter().enumerate() {
+            let (y, z, x) = (
+                index / CHUNK_AREA,
+                index / CHUNK_WIDTH % CHUNK_WIDTH,
+                index % CHUNK_WIDTH,
+            );
+            set.set_relative_block(x, CHUNK_WIDTH + y, z, *block);
+        }
+        for y in 0..2 * CHUNK_WIDTH as i32 {
+            for (x, z) in [(0, 0), (3, 9), (15, 15)] {
+                assert_eq!(
+                    filled.section.get_block_absolute_y(x, y, z),
+                    set.section.get_block_absolute_y(x, y, z)
+                );
+            }
+        }
+
+        // A section of a single block needs no palette
+        filled
+            .section
+            .fill_section_from(0, &[states[0]; SUBCHUNK_VOLUME]);
+        assert!(matches!(
+            filled.section.sections[0].block_states,
+            BlockPalette::Homogeneous(_)
+        ));
+    }
+
+    #[test]
+    fn filled_biomes_match_cells_set_one_by_one() {
+        let biomes = [Biome::PLAINS.id, Biome::RIVER.id, Biome::BEACH.id];
+        let mut cells = [0; BIOME_VOLUME];
+        for (index, cell) in cells.iter_mut().enumerate() {
+            *cell = biomes[(index * 5 / 3) % biomes.len()];
+        }
+
+        let mut filled = chunk(2, 0);
+        filled.section.fill_section_biomes_from(1, &cells);
+        let mut set = chunk(2, 0);
+        let size = BiomePalette::SIZE;
+        for (index, cell) in cells.iter().enumerate() {
+            let (y, z, x) = (index / (size * size), index / size % size, index % size);
+            set.section.set_relative_biome(x, size + y, z, *cell);
+        }
+        for y in 0..2 * CHUNK_WIDTH as i32 {
+            for (x, z) in [(0, 0), (5, 10), (15, 15)] {
+                assert_eq!(
+                    filled.section.get_rough_biome_absolute_y(x, y, z),
+                    set.section.get_rough_biome_absolute_y(x, y, z)
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn unknown_biomes_load_as_plains() {
+        let mut chunk = chunk(2, 0);
+        chunk.section.sections[0].biomes = BiomePalette::Homogeneous(Biome::DESERT.id);
+        chunk.section.sections[1].biomes = BiomePalette::Homogeneous(Biome::BADLANDS.id);
+        let bytes = chunk.to_bytes().await.unwrap().to_vec();
+
+        // Like a chunk saved with a data pack's biome, and one saved by vanilla
+        let replace = |bytes: &[u8], from: &str, to: &str| {
+            let string =
+                |name: &str| [&(name.len() as u16).to_be_bytes(), name.as_bytes()].concat();
+            let (from, to) = (string(from), string(to));
+            let at = bytes
+                .windows(from.len())
+                .position(|window| window == from)
+                .unwrap();
+            [&bytes[..at], &to, &bytes[at + from.len()..]].concat()
+        };
+        let bytes = replace(&bytes, "desert", "my_pack:dunes");
+        let bytes = replace(&bytes, "badlands", "minecraft:badlands");
+
+        let loaded = ChunkData::from_bytes(bytes.into(), chunk.position).unwrap();
+        assert_eq!(
+            loaded.section.sections[0].biomes.get(0, 0, 0),
+            Biome::PLAINS.id
+        );
+        assert_eq!(
+            loaded.section.sections[1].biomes.get(0, 0, 0),
+            Biome::BADLANDS.id
+        );
+
+        // An id no biome has is saved as plains
+        chunk.section.sections[0].biomes = BiomePalette::Homogeneous(u8::MAX);
+        let bytes = chunk.to_bytes().await.unwrap();
+        let loaded = ChunkData::from_bytes(bytes, chunk.position).unwrap();
+        assert_eq!(
+            loaded.section.sections[0].biomes.get(0, 0, 0),
+            Biome::PLAINS.id
+        );
+    }
+
+    #[test]
+    fn debug_json_counts_the_palette() {
+        let mut chunk = chunk(2, 0);
+        let log = Block::OAK_LOG.default_state.id;
+        chunk.set_relative_block(0, 0, 0, log);
+        chunk.set_relative_block(1, 0, 0, log);
+        chunk.set_relative_block(0, 16, 0, Block::STONE.default_state.id);
+        chunk.heightmap = chunk.calculate_heightmap();
+
+        let json: serde_json::Value = serde_json::from_str(&chunk.to_debug_json()).unwrap();
+        let sections = json["sections"].as_array().unwrap();
+        assert_eq!(sections.len(), 2);
+        let blocks = &sections[0]["blocks"];
+        assert_eq!(blocks[0]["name"], "air");
+        assert_eq!(blocks[0]["count"], 4094);
+        assert_eq!(blocks[1]["name"], "oak_log");
+        assert_eq!(blocks[1]["properties"]["axis"], "y");
+        assert_eq!(blocks[1]["count"], 2);
+        assert_eq!(sections[1]["y"], 1);
+        assert_eq!(sections[1]["blocks"][1]["name"], "stone");
+        assert_eq!(sections[0]["biomes"][0]["count"], 64);
+
+        assert_eq!(json["heightmaps"]["world_surface"][0][0], 16);
+        let light = json["light"].as_array().unwrap();
+        assert_eq!(light.len(), 4);
+        assert_eq!(light[0]["y"], -1);
+        assert_eq!(light[0]["sky"], serde_json::json!([15, 15]));
+    }
+
+    #[test]
+    fn relit_column_gets_its_heightmaps_and_light() {
+        let mut chunk = chunk(24, -64);
+        let stone = Block::STONE.default_state.id;
+        chunk.set_relative_block(5, 94, 5, stone);
+        chunk.relight_column(5, 5, |_| None);
+
+        let height = |heightmap| chunk.heightmap.get_height(heightmap, 5, 5, -64);
+        assert_eq!(height(ChunkHeightmapType::WorldSurface), 30);
+        assert_eq!(height(ChunkHeightmapType::MotionBlocking), 30);
+        assert_eq!(
+            chunk
+                .heightmap
+                .get_height(ChunkHeightmapType::WorldSurface, 6, 5, -64),
+            -64
+        );
+        // In the shade of the stone, lit from the side
+        let sky_light = |y: usize| chunk.light_engine.sky_light[y / 16 + 1].get(5, y % 16, 5);
+        assert_eq!(sky_light(93), 14);
+        assert_eq!(sky_light(95), 15);
+        assert!(chunk.dirty);
+    }
+
+    #[test]
+    fn taller_chunks_do_not_fit() {
+        let mut chunk = chunk(24, -64);
+        let err = chunk.fit_to_height(0, 256).unwrap_err();
+        assert!(matches!(
+            err,
+            ChunkParsingError::IncompatibleHeight {
+                saved_min_y: -64,
+                saved_max_y: 320,
+                min_y: 0,
+                max_y: 256,
+            }
+        ));
+    }
+}
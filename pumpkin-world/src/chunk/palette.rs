@@ -1,14 +1,43 @@
-use std::{collections::HashMap, hash::Hash};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    sync::{LazyLock, Mutex},
+};
 
-use pumpkin_data::{Block, BlockState, chunk::Biome};
+use pumpkin_data::{BlockState, chunk::Biome};
 use pumpkin_util::encompassing_bits;
 
 use crate::block::BlockStateCodec;
 
 use super::format::{ChunkSectionBiomes, ChunkSectionBlockStates, PaletteBiomeEntry};
 
+/// The biomes chunks referred to that aren't known, by name or id, so that each is only warned
+/// about once.
+static UNKNOWN_BIOMES: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Mutex::default);
+
+/// The biome called `name`, with or without the `minecraft:` namespace, or plains if there is no
+/// such biome, e.g. because it comes from a data pack the server doesn't have.
+fn biome_or_plains(name: &str) -> &'static Biome {
+    let path = name.strip_prefix("minecraft:").unwrap_or(name);
+    Biome::from_name(path).unwrap_or_else(|| unknown_biome(name.to_string()))
+}
+
+/// Like [`biome_or_plains`], for the biome with the id `id`.
+pub(crate) fn biome_id_or_plains(id: u8) -> &'static Biome {
+    Biome::from_id(id).unwrap_or_else(|| unknown_biome(format!("with the id {id}")))
+}
+
+fn unknown_biome(biome: String) -> &'static Biome {
+    let mut unknown = UNKNOWN_BIOMES.lock().unwrap();
+    if !unknown.contains(&biome) {
+        log::warn!("Unknown biome {biome} in a chunk, using minecraft:plains instead");
+        unknown.insert(biome);
+    }
+    &Biome::PLAINS
+}
+
 /// 3d array indexed by y,z,x
-type AbstractCube<T, const DIM: usize> = [[[T; DIM]; DIM]; DIM];
+pub type AbstractCube<T, const DIM: usize> = [[[T; DIM]; DIM]; DIM];
 
 #[derive(Debug, Clone)]
 pub struct HeterogeneousPaletteData<V: Hash + Eq + Copy, const DIM: usize> {
@@ -69,20 +98,28 @@ impl<V: Hash + Eq + Copy + Default, const DIM: usize> PalettedContainer<V, DIM>
     pub const SIZE: usize = DIM;
     pub const VOLUME: usize = DIM * DIM * DIM;
 
-    fn from_cube(cube: Box<AbstractCube<V, DIM>>) -> Self {
+    /// Creates a container holding all of `cube` at once, which is a lot cheaper than setting
+    /// every value one by one.
+    pub fn from_cube(cube: Box<AbstractCube<V, DIM>>) -> Self {
         let mut palette: Vec<V> = Vec::new();
         let mut counts: Vec<u16> = Vec::new();
 
-        // Iterate over the flattened cube to populate the palette and counts
+        // Iterate over the flattened cube to populate the palette and counts. Neighbors mostly
+        // hold the same value, so the one of the previous entry is tried first
+        let mut last = 0;
         for val in cube.as_flattened().as_flattened().iter() {
-            if let Some(index) = palette.iter().position(|v| v == val) {
-                // Value already exists, increment its count
-                counts[index] += 1;
-            } else {
-                // New value, add it to the palette and start its count
-                palette.push(*val);
-                counts.push(1);
+            if palette.get(last) != Some(val) {
+                last = match palette.iter().position(|v| v == val) {
+                    Some(index) => index,
+                    None => {
+                        // New value, add it to the palette and start its count
+                        palette.push(*val);
+                        counts.push(0);
+                        palette.len() - 1
+                    }
+                };
             }
+            counts[last] += 1;
         }
 
         if palette.len() == 1 {
@@ -325,7 +362,7 @@ impl BiomePalette {
         let palette = nbt
             .palette
             .into_iter()
-            .map(|entry| Biome::from_name(&entry.name).unwrap_or(&Biome::PLAINS).id)
+            .map(|entry| biome_or_plains(&entry.name).id)
             .collect::<Vec<_>>();
 
         Self::from_palette_and_packed_data(
@@ -348,7 +385,7 @@ impl BiomePalette {
             palette: palette
                 .into_iter()
                 .map(|registry_id| PaletteBiomeEntry {
-                    name: Biome::from_id(registry_id).unwrap().registry_id.into(),
+                    name: biome_id_or_plains(registry_id).registry_id.into(),
                 })
                 .collect(),
         }
@@ -516,14 +553,7 @@ impl BlockPalette {
     }
 
     fn block_state_id_to_palette_entry(registry_id: u16) -> BlockStateCodec {
-        let block = Block::from_state_id(registry_id);
-
-        BlockStateCodec {
-            name: block,
-            properties: block
-                .properties(registry_id)
-                .map(|p| p.to_props().into_iter().collect()),
-        }
+        BlockState::from_id(registry_id).into()
     }
 }
 
@@ -1,29 +1,169 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+    path::PathBuf,
+    sync::Arc,
+};
 
+use pumpkin_data::{dimension_type::DimensionType, noise_router::BaseNoiseRouters};
 use serde::Deserialize;
 
-use crate::{level::Level, world::BlockRegistryExt};
+use crate::{
+    generation::{GeneratorKind, generator::GeneratorError, settings::BiomeSource},
+    level::Level,
+    world::BlockRegistryExt,
+};
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash, Deserialize, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Deserialize, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum Dimension {
     Overworld,
     Nether,
     End,
+    /// A dimension that isn't one of vanilla's, e.g. one added by a plugin. See
+    /// [`DimensionConfig`].
+    #[serde(skip)]
+    Custom(Arc<DimensionConfig>),
+}
+
+/// The settings of a [`Dimension::Custom`]. It is generated like the vanilla dimension it is
+/// `based_on`, with the surface rules, blocks and terrain shape of its generation settings, but
+/// with its own height, terrain, sea level and biomes.
+///
+/// Custom dimensions are told apart by their `name`, so two configs with the same name count as
+/// the same dimension.
+pub struct DimensionConfig {
+    /// The namespaced id of the dimension, e.g. `my_plugin:moon`.
+    pub name: &'static str,
+    /// The dimension it is generated like. A custom one stands for the vanilla dimension it is
+    /// based on in turn.
+    pub based_on: Dimension,
+    pub min_y: i32,
+    pub height: u16,
+    pub has_skylight: bool,
+    pub sea_level: i32,
+    /// Shapes the terrain, e.g. the one of [`load_noise_settings`].
+    ///
+    /// [`load_noise_settings`]: crate::generation::noise::router::custom_router::load_noise_settings
+    pub noise_router: BaseNoiseRouters,
+    pub biome_source: BiomeSource,
+}
+
+impl PartialEq for DimensionConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for DimensionConfig {}
+
+impl Hash for DimensionConfig {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl fmt::Debug for DimensionConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DimensionConfig")
+            .field("name", &self.name)
+            .field("based_on", &self.based_on)
+            .field("min_y", &self.min_y)
+            .field("height", &self.height)
+            .field("has_skylight", &self.has_skylight)
+            .field("sea_level", &self.sea_level)
+            .field("biome_source", &self.biome_source)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Dimension {
+    /// The name the dimension is saved under, which is the namespaced id of custom dimensions.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dimension::Overworld => "overworld",
+            Dimension::Nether => "nether",
+            Dimension::End => "end",
+            Dimension::Custom(config) => config.name,
+        }
+    }
+
+    /// The vanilla dimension this one is generated like, which is itself unless it's a custom
+    /// one.
+    pub fn vanilla(&self) -> Dimension {
+        match self {
+            Dimension::Custom(config) => config.based_on.vanilla(),
+            _ => self.clone(),
+        }
+    }
+
+    /// The vanilla dimension type of [`Self::vanilla`], as synced to the clients.
+    pub fn vanilla_type(&self) -> DimensionType {
+        match self.vanilla() {
+            Dimension::Nether => DimensionType::THE_NETHER,
+            Dimension::End => DimensionType::THE_END,
+            _ => DimensionType::OVERWORLD,
+        }
+    }
+
+    /// The lowest block of the dimension type.
+    pub fn min_y(&self) -> i32 {
+        match self {
+            Dimension::Custom(config) => config.min_y,
+            _ => self.vanilla_type().min_y,
+        }
+    }
+
+    /// The number of blocks between `min_y` and the build limit of the dimension type.
+    ///
+    /// This can be taller than the terrain shape of the dimension: the Nether and the End only
+    /// generate their lowest 128 blocks.
+    pub fn height(&self) -> u16 {
+        match self {
+            Dimension::Custom(config) => config.height,
+            _ => self.vanilla_type().height,
+        }
+    }
+
+    /// Whether the sky lights this dimension, which of the vanilla ones only the Overworld's
+    /// does.
+    pub fn has_skylight(&self) -> bool {
+        match self {
+            Dimension::Custom(config) => config.has_skylight,
+            _ => self.vanilla_type().has_skylight,
+        }
+    }
+
+    /// Opens the level of this dimension in the world at `base_directory`, generating new chunks
+    /// with the generator of `generator`. Fails if that generator can't be created.
     pub fn into_level(
         &self,
         mut base_directory: PathBuf,
         block_registry: Arc<dyn BlockRegistryExt>,
         seed: i64,
-    ) -> Arc<Level> {
+        generator: GeneratorKind,
+    ) -> Result<Arc<Level>, GeneratorError> {
         match self {
             Dimension::Overworld => {}
             Dimension::Nether => base_directory.push("DIM-1"),
             Dimension::End => base_directory.push("DIM1"),
+            // Like vanilla, which keeps the dimensions of data packs in `dimensions/<namespace>/<path>`
+            Dimension::Custom(config) => {
+                let (namespace, path) = config
+                    .name
+                    .split_once(':')
+                    .unwrap_or(("minecraft", config.name));
+                base_directory.push("dimensions");
+                base_directory.push(namespace);
+                base_directory.push(path);
+            }
         }
-        Level::from_root_folder(base_directory, block_registry, seed, *self)
+        Level::with_generator(
+            base_directory,
+            block_registry,
+            seed,
+            self.clone(),
+            generator,
+        )
     }
 }
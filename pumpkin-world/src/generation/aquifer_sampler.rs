@@ -601,6 +601,17 @@ impl AquiferSamplerImpl for WorldAquiferSampler {
         let density = router.final_density(pos, sample_options);
         self.apply_internal(router, pos, sample_options, height_estimator, density)
     }
+
+    #[inline]
+    fn fill_carved(
+        &mut self,
+        router: &mut ChunkNoiseRouter,
+        pos: &impl NoisePos,
+        sample_options: &ChunkNoiseFunctionSampleOptions,
+        height_estimator: &mut SurfaceHeightEstimateSampler,
+    ) -> Option<&'static BlockState> {
+        self.apply_internal(router, pos, sample_options, height_estimator, 0.0)
+    }
 }
 
 pub struct SeaLevelAquiferSampler {
@@ -634,6 +645,21 @@ impl AquiferSamplerImpl for SeaLevelAquiferSampler {
             )
         }
     }
+
+    fn fill_carved(
+        &mut self,
+        _router: &mut ChunkNoiseRouter,
+        pos: &impl NoisePos,
+        _sample_options: &ChunkNoiseFunctionSampleOptions,
+        _height_estimator: &mut SurfaceHeightEstimateSampler,
+    ) -> Option<&'static BlockState> {
+        Some(
+            self.level_sampler
+                .get_fluid_level(pos.x(), pos.y(), pos.z())
+                .get_block(pos.y())
+                .default_state,
+        )
+    }
 }
 
 #[enum_dispatch]
@@ -645,6 +671,16 @@ pub trait AquiferSamplerImpl {
         sample_options: &ChunkNoiseFunctionSampleOptions,
         height_estimator: &mut SurfaceHeightEstimateSampler,
     ) -> Option<&'static BlockState>;
+
+    /// Returns what fills the space a carver dug out at `pos`, or `None` if it lies in the
+    /// barrier between two aquifers and has to stay solid.
+    fn fill_carved(
+        &mut self,
+        router: &mut ChunkNoiseRouter,
+        pos: &impl NoisePos,
+        sample_options: &ChunkNoiseFunctionSampleOptions,
+        height_estimator: &mut SurfaceHeightEstimateSampler,
+    ) -> Option<&'static BlockState>;
 }
 
 #[cfg(test)]
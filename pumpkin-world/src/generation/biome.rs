@@ -100,7 +100,7 @@ pub fn get_biome_blend(
     // Java's `getBiomeForNoiseGen`
     let bottom_y = bottom_y as i32;
     let biome_bottom = biome_coords::from_block(bottom_y);
-    let biome_top = biome_bottom + biome_coords::from_block(height as i32) - 1;
+    let biome_top = biome_bottom + biome_coords::cells_covering(height as i32) - 1;
     let biome_y = biome_y.clamp(biome_bottom, biome_top);
 
     Vector3::new(biome_x, biome_y, biome_z)
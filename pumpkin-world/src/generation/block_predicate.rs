@@ -4,7 +4,8 @@ use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk, block::BlockStateCodec, generation::height_limit::HeightLimitView,
+    block::BlockStateCodec,
+    generation::{height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion},
     world::BlockRegistryExt,
 };
 
@@ -19,7 +20,7 @@ pub enum BlockPredicate {
     #[serde(rename = "minecraft:matching_block_tag")]
     MatchingBlockTag(MatchingBlockTagPredicate),
     #[serde(rename = "minecraft:matching_fluids")]
-    MatchingFluids(EmptyTODOStruct),
+    MatchingFluids(MatchingFluidsBlockPredicate),
     #[serde(rename = "minecraft:has_sturdy_face")]
     HasSturdyFace(HasSturdyFacePredicate),
     #[serde(rename = "minecraft:solid")]
@@ -47,13 +48,13 @@ impl BlockPredicate {
     pub fn test(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
     ) -> bool {
         match self {
             BlockPredicate::MatchingBlocks(predicate) => predicate.test(chunk, pos),
             BlockPredicate::MatchingBlockTag(predicate) => predicate.test(chunk, pos),
-            BlockPredicate::MatchingFluids(_predicate) => false,
+            BlockPredicate::MatchingFluids(predicate) => predicate.test(chunk, pos),
             BlockPredicate::HasSturdyFace(predicate) => predicate.test(chunk, pos),
             BlockPredicate::Solid(predicate) => predicate.test(chunk, pos),
             BlockPredicate::Replaceable(predicate) => predicate.test(chunk, pos),
@@ -76,7 +77,7 @@ pub struct MatchingBlocksBlockPredicate {
 }
 
 impl MatchingBlocksBlockPredicate {
-    pub fn test(&self, chunk: &ProtoChunk, pos: &BlockPos) -> bool {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
         let block = self.offset.get_block(chunk, pos);
         match &self.blocks {
             MatchingBlocksWrapper::Single(single_block) => {
@@ -90,13 +91,68 @@ impl MatchingBlocksBlockPredicate {
     }
 }
 
+#[derive(Deserialize)]
+pub struct MatchingFluidsBlockPredicate {
+    #[serde(flatten)]
+    offset: OffsetBlocksBlockPredicate,
+    fluids: MatchingBlocksWrapper,
+}
+
+impl MatchingFluidsBlockPredicate {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
+        let Some(fluid) = fluid_name(self.offset.get_state(chunk, pos)) else {
+            return false;
+        };
+        match &self.fluids {
+            MatchingBlocksWrapper::Single(single_fluid) => {
+                single_fluid.strip_prefix("minecraft:").unwrap() == fluid
+            }
+            MatchingBlocksWrapper::Multiple(fluids) => fluids
+                .iter()
+                .map(|s| s.strip_prefix("minecraft:").unwrap())
+                .contains(fluid),
+        }
+    }
+}
+
+/// The fluid filling `state`, if any, with sources and flowing fluids told apart like vanilla does.
+fn fluid_name(state: &BlockState) -> Option<&'static str> {
+    let block = Block::from_state_id(state.id);
+    if block == &Block::WATER {
+        Some(if state.id == Block::WATER.default_state.id {
+            "water"
+        } else {
+            "flowing_water"
+        })
+    } else if block == &Block::LAVA {
+        Some(if state.id == Block::LAVA.default_state.id {
+            "lava"
+        } else {
+            "flowing_lava"
+        })
+    } else if [
+        &Block::BUBBLE_COLUMN,
+        &Block::KELP,
+        &Block::KELP_PLANT,
+        &Block::SEAGRASS,
+        &Block::TALL_SEAGRASS,
+    ]
+    .contains(&block)
+        || block.is_waterlogged(state.id)
+    {
+        Some("water")
+    } else {
+        None
+    }
+}
+
 #[derive(Deserialize)]
 pub struct InsideWorldBoundsBlockPredicate {
     offset: Vector3<i32>,
 }
 
 impl InsideWorldBoundsBlockPredicate {
-    pub fn test(&self, chunk: &ProtoChunk, pos: &BlockPos) -> bool {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
         let pos = pos.offset(self.offset);
         !chunk.out_of_height(pos.0.y as i16)
     }
@@ -110,7 +166,7 @@ pub struct MatchingBlockTagPredicate {
 }
 
 impl MatchingBlockTagPredicate {
-    pub fn test(&self, chunk: &ProtoChunk, pos: &BlockPos) -> bool {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
         let block = self.offset.get_block(chunk, pos);
         block.is_tagged_with(&self.tag).unwrap()
     }
@@ -124,7 +180,7 @@ pub struct HasSturdyFacePredicate {
 }
 
 impl HasSturdyFacePredicate {
-    pub fn test(&self, chunk: &ProtoChunk, pos: &BlockPos) -> bool {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
         let state = self.offset.get_state(chunk, pos);
         state.is_side_solid(self.direction)
     }
@@ -139,7 +195,7 @@ impl AnyOfBlockPredicate {
     pub fn test(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
     ) -> bool {
         for predicate in &self.predicates {
@@ -161,7 +217,7 @@ impl AllOfBlockPredicate {
     pub fn test(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
     ) -> bool {
         for predicate in &self.predicates {
@@ -183,7 +239,7 @@ impl NotBlockPredicate {
     pub fn test(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
     ) -> bool {
         !self.predicate.test(block_registry, chunk, pos)
@@ -197,7 +253,7 @@ pub struct SolidBlockPredicate {
 }
 
 impl SolidBlockPredicate {
-    pub fn test(&self, chunk: &ProtoChunk, pos: &BlockPos) -> bool {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
         let state = self.offset.get_state(chunk, pos);
         state.is_solid()
     }
@@ -214,7 +270,7 @@ impl WouldSurviveBlockPredicate {
     pub fn test(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
     ) -> bool {
         let block = self.state.get_block();
@@ -230,7 +286,7 @@ pub struct ReplaceableBlockPredicate {
 }
 
 impl ReplaceableBlockPredicate {
-    pub fn test(&self, chunk: &ProtoChunk, pos: &BlockPos) -> bool {
+    pub fn test(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
         let state = self.offset.get_state(chunk, pos);
         state.replaceable()
     }
@@ -248,11 +304,11 @@ impl OffsetBlocksBlockPredicate {
         }
         *pos
     }
-    pub fn get_block(&self, chunk: &ProtoChunk, pos: &BlockPos) -> &'static Block {
+    pub fn get_block(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> &'static Block {
         let pos = self.get(pos);
         chunk.get_block_state(&pos.0).to_block()
     }
-    pub fn get_state(&self, chunk: &ProtoChunk, pos: &BlockPos) -> &'static BlockState {
+    pub fn get_state(&self, chunk: &ProtoChunkRegion, pos: &BlockPos) -> &'static BlockState {
         let pos = self.get(pos);
         chunk.get_block_state(&pos.0).to_state()
     }
@@ -12,9 +12,12 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::block::BlockStateCodec;
+use crate::{block::BlockStateCodec, world::BlockRegistryExt};
 
-use super::noise::perlin::DoublePerlinNoiseSampler;
+use super::{
+    block_predicate::BlockPredicate, noise::perlin::DoublePerlinNoiseSampler,
+    proto_chunk_region::ProtoChunkRegion,
+};
 
 #[derive(Deserialize)]
 #[serde(tag = "type")]
@@ -216,3 +219,32 @@ impl NoiseThresholdBlockStateProvider {
         self.default_state.get_state()
     }
 }
+
+/// Picks the provider of the first rule whose predicate holds at the position, or the fallback.
+#[derive(Deserialize)]
+pub struct RuleBasedBlockStateProvider {
+    fallback: BlockStateProvider,
+    rules: Vec<Rule>,
+}
+
+#[derive(Deserialize)]
+struct Rule {
+    if_true: BlockPredicate,
+    then: BlockStateProvider,
+}
+
+impl RuleBasedBlockStateProvider {
+    pub fn get(
+        &self,
+        block_registry: &dyn BlockRegistryExt,
+        chunk: &ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> &'static BlockState {
+        self.rules
+            .iter()
+            .find(|rule| rule.if_true.test(block_registry, chunk, &pos))
+            .map_or(&self.fallback, |rule| &rule.then)
+            .get(random, pos)
+    }
+}
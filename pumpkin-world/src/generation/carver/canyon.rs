@@ -0,0 +1,151 @@
+use std::f32::consts::{PI, TAU};
+
+use pumpkin_util::{
+    math::{cos, float_provider::FloatProvider, sin, vector2::Vector2, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl, legacy_rand::LegacyRand},
+};
+use serde::Deserialize;
+
+use crate::generation::positions::chunk_pos;
+
+use super::{CarverConfig, CarvingContext, can_reach, carve_block, cave::TUNNEL_LENGTH};
+
+/// Digs a single long, deep and narrow ravine.
+#[derive(Deserialize)]
+pub struct CanyonCarver {
+    #[serde(flatten)]
+    pub(super) config: CarverConfig,
+    vertical_rotation: FloatProvider,
+    shape: CanyonShape,
+}
+
+#[derive(Deserialize)]
+struct CanyonShape {
+    distance_factor: FloatProvider,
+    thickness: FloatProvider,
+    /// Every how many blocks the width changes on average, going up the walls.
+    width_smoothness: i32,
+    horizontal_radius_factor: FloatProvider,
+    vertical_radius_default_factor: f32,
+    vertical_radius_center_factor: f32,
+}
+
+impl CanyonCarver {
+    pub fn carve(
+        &self,
+        context: &mut CarvingContext,
+        random: &mut RandomGenerator,
+        start_chunk: &Vector2<i32>,
+    ) {
+        let x = (chunk_pos::start_block_x(start_chunk) + random.next_bounded_i32(16)) as f64;
+        let y = self.config.y.get(random, context.min_y, context.height) as f64;
+        let z = (chunk_pos::start_block_z(start_chunk) + random.next_bounded_i32(16)) as f64;
+        let yaw = random.next_f32() * TAU;
+        let pitch = self.vertical_rotation.get(random);
+        let ratio = self.config.y_scale.get(random) as f64;
+        let thickness = self.shape.thickness.get(random);
+        let steps = (TUNNEL_LENGTH as f32 * self.shape.distance_factor.get(random)) as i32;
+
+        let seed = random.next_i64();
+        self.carve_canyon(
+            context,
+            seed,
+            Vector3::new(x, y, z),
+            thickness,
+            yaw,
+            pitch,
+            steps,
+            ratio,
+        );
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn carve_canyon(
+        &self,
+        context: &mut CarvingContext,
+        seed: i64,
+        start: Vector3<f64>,
+        thickness: f32,
+        mut yaw: f32,
+        mut pitch: f32,
+        steps: i32,
+        ratio: f64,
+    ) {
+        let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(seed as u64));
+        let width_factors = self.width_factors(context, &mut random);
+        let (mut yaw_change, mut pitch_change) = (0.0f32, 0.0f32);
+        let Vector3 {
+            mut x,
+            mut y,
+            mut z,
+        } = start;
+
+        for step in 0..steps {
+            let horizontal_radius =
+                1.5 + (sin((step as f32 * PI / steps as f32) as f64) * thickness) as f64;
+            let vertical_radius = horizontal_radius * ratio;
+            let horizontal_radius =
+                horizontal_radius * self.shape.horizontal_radius_factor.get(&mut random) as f64;
+            let vertical_radius = self.vertical_radius(&mut random, vertical_radius, steps, step);
+
+            let pitch_cos = cos(pitch as f64);
+            let pitch_sin = sin(pitch as f64);
+            x += (cos(yaw as f64) * pitch_cos) as f64;
+            y += pitch_sin as f64;
+            z += (sin(yaw as f64) * pitch_cos) as f64;
+            pitch *= 0.7;
+            pitch += pitch_change * 0.05;
+            yaw += yaw_change * 0.05;
+            pitch_change *= 0.8;
+            yaw_change *= 0.5;
+            pitch_change += (random.next_f32() - random.next_f32()) * random.next_f32() * 2.0;
+            yaw_change += (random.next_f32() - random.next_f32()) * random.next_f32() * 4.0;
+
+            if random.next_bounded_i32(4) != 0 {
+                if !can_reach(&context.chunk.chunk_pos, x, z, step, steps, thickness) {
+                    return;
+                }
+                let min_y = context.min_y as i32;
+                context.carve_ellipsoid(
+                    &self.config,
+                    Vector3::new(x, y, z),
+                    horizontal_radius,
+                    vertical_radius,
+                    |x, y, z, block_y| {
+                        let width_factor = width_factors[(block_y - min_y - 1) as usize] as f64;
+                        (x * x + z * z) * width_factor + y * y / 6.0 >= 1.0
+                    },
+                    carve_block,
+                );
+            }
+        }
+    }
+
+    /// Picks how much narrower than its radius the canyon is at every height, which makes its
+    /// walls rugged.
+    fn width_factors(&self, context: &CarvingContext, random: &mut RandomGenerator) -> Box<[f32]> {
+        let mut factor = 1.0f32;
+        (0..context.height)
+            .map(|y| {
+                if y == 0 || random.next_bounded_i32(self.shape.width_smoothness) == 0 {
+                    factor = 1.0 + random.next_f32() * random.next_f32();
+                }
+                factor * factor
+            })
+            .collect()
+    }
+
+    /// Scales `vertical_radius` so the canyon is the deepest wherever the shape wants it to be.
+    fn vertical_radius(
+        &self,
+        random: &mut RandomGenerator,
+        vertical_radius: f64,
+        steps: i32,
+        step: i32,
+    ) -> f64 {
+        let center = 1.0 - (0.5 - step as f32 / steps as f32).abs() * 2.0;
+        let factor = self.shape.vertical_radius_default_factor
+            + self.shape.vertical_radius_center_factor * center;
+        factor as f64 * vertical_radius * (random.next_f32() * 0.25 + 0.75) as f64
+    }
+}
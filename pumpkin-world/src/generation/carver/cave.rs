@@ -1,166 +1,201 @@
-// use pumpkin_macros::block_state;
-// use pumpkin_util::{
-//     math::{float_provider::FloatProvider, vector2::Vector2, vector3::Vector3},
-//     random::RandomGenerator,
-// };
-// use serde::Deserialize;
-
-// use crate::{
-//     ProtoChunk,
-//     block::{BlockState, registry::get_block},
-//     generation::{
-//         aquifer_sampler::{AquiferSamplerImpl, WorldAquiferSampler},
-//         height_limit::HeightLimitView,
-//         height_provider::HeightProvider,
-//         positions::chunk_pos,
-//         section_coords,
-//         y_offset::YOffset,
-//     },
-// };
-
-// use super::mask::CarvingMask;
-
-// #[derive(Deserialize)]
-// pub struct CaveCraver {
-//     vertical_radius_multiplier: FloatProvider,
-//     horizontal_radius_multiplier: FloatProvider,
-//     floor_level: FloatProvider,
-//     y: HeightProvider,
-//     #[serde(rename = "yScale")]
-//     y_scale: FloatProvider,
-//     lava_level: YOffset,
-//     probability: f32,
-// }
-// const BRANCH_FACTOR: i32 = 4;
-// const MAX_CAVE_COUNT: i32 = 15;
-
-// impl CaveCraver {
-//     pub fn should_carve(&self, random: &mut RandomGenerator) -> bool {
-//         random.next_f32() <= self.probability
-//     }
-
-//     pub fn carve(
-//         &self,
-//         random: &mut RandomGenerator,
-//         chunk_pos: &Vector2<i32>,
-//         min_y: i8,
-//         height: u16,
-//     ) {
-//         todo!();
-//         let block_coord = section_coords::section_to_block(BRANCH_FACTOR * 2 - 1);
-//         let first_rnd = random.next_bounded_i32(MAX_CAVE_COUNT);
-//         let sec_rnd = random.next_bounded_i32(first_rnd + 1);
-//         let third_rnd = random.next_bounded_i32(sec_rnd + 1);
-//         let range = third_rnd;
-//         for _ in 0..range {
-//             let x = chunk_pos::start_block_x(chunk_pos) + random.next_bounded_i32(16); // offset
-//             let y = self.y.get(random, min_y, height);
-//             let z = chunk_pos::start_block_z(chunk_pos) + random.next_bounded_i32(16); // offset
-//             let vertical = self.vertical_radius_multiplier.get();
-//             let horizontal = self.horizontal_radius_multiplier.get();
-//             let floor = self.floor_level.get();
-//             let mut pitch;
-//             let mut tries = 0;
-//             if random.next_bounded_i32(4) == 0 {
-//                 let scale = self.y_scale.get();
-//                 pitch = 1.0 + random.next_f32() * 6.0;
-//                 tries += random.next_bounded_i32(4);
-//             }
-//         }
-//     }
-
-//     fn carve_cave(&self, chunk_pos: &Vector2<i32>, width: f64, height: f64) {
-//         todo!();
-//       //  let width = 1.5 + 1.5707964f64.sin() * width;
-//      //   let height = width * height;
-//      //   Self::carve_region(chunk_pos, width, height)
-//     }
-
-//     fn carve_region(
-//         min_y: i8,
-//         chunk_height: u16,
-//         chunk_pos: &Vector2<i32>,
-//         x: f64,
-//         y: f64,
-//         z: f64,
-//         width: f64,
-//         height: f64,
-//         mask: &mut CarvingMask,
-//         floor_level: f64,
-//     ) {
-//         let start_x = chunk_pos::start_block_x(chunk_pos);
-//         let start_z = chunk_pos::start_block_z(chunk_pos);
-
-//         let chunk_center_x = (start_x + 8) as f64;
-//         let chunk_center_z = (start_z + 8) as f64;
-
-//         let max_width = 16.0 + width * 2.0;
-//         if (x - chunk_center_x).abs() > max_width || (z - chunk_center_z).abs() > max_width {
-//             return;
-//         }
-//         let x_start = (x - width).floor() as i32 - start_x - 1.max(0);
-//         let max_x = (x - width).floor() as i32 - start_x - 1.min(15);
-
-//         let z_start = (z - width).floor() as i32 - start_z - 1.max(0);
-//         let max_z = (z - width).floor() as i32 - start_z - 1.min(15);
-
-//         let init_height =
-//             (y + height).floor() as i32 + 1.min(min_y as i32 + chunk_height as i32 - 1 - 7);
-
-//         let end_height = (y - height).floor() as i32 - 1.max(min_y as i32 + 1);
-
-//         for current_x in max_x..x_start {
-//             let x_offset = chunk_pos::start_block_x(chunk_pos) + current_x;
-//             let x_offwidth = (x_offset as f64 + 0.5 - x) / width;
-//             for current_z in max_z..z_start {
-//                 let z_offset = chunk_pos::start_block_z(chunk_pos) + current_z;
-//                 let z_offwidth = (z_offset as f64 + 0.5 - z) / width;
-
-//                 if x_offwidth * x_offwidth + z_offwidth * z_offwidth >= 1.0 {
-//                     continue;
-//                 }
-//                 for current_y in init_height..end_height {
-//                     let y_offwidth = (current_y as f64 - 0.5 - y) / height;
-//                     if Self::is_pos_excluded(
-//                         Vector3::new(x_offwidth, y_offwidth, z_offwidth),
-//                         floor_level,
-//                     ) {
-//                         continue;
-//                     }
-//                     mask.set(current_x, current_y, current_z);
-//                 }
-//             }
-//         }
-//     }
-
-//     fn carve_at_point(&self, chunk: &ProtoChunk, pos: Vector3<i32>, min_y: i8, height: u16) {
-//         let state = chunk.get_block_state(&pos);
-
-//         // if state.block_id == block_state!("grass_block").block_id || state.block_id == block_state!("mycelium").block_id {
-//         // TODO
-//         // }
-//         let state = self.get_state(pos, min_y, height);
-//     }
-
-//     fn get_state(&self, pos: Vector3<i32>, min_y: i8, height: u16) -> Option<BlockState> {
-//         if pos.y <= self.lava_level.get_y(min_y, height) as i32 {
-//             return Some(block_state!("lava"));
-//         }
-//         None
-//     }
-
-//     fn is_pos_excluded(scaled: Vector3<f64>, floor_y: f64) -> bool {
-//         if scaled.y <= floor_y {
-//             return true;
-//         }
-//         scaled.x * scaled.x + scaled.y * scaled.y + scaled.z * scaled.z >= 1.0
-//     }
-
-//     fn get_tunnel_width(random: &mut RandomGenerator) -> f32 {
-//         let mut width = random.next_f32() * 2.0 + random.next_f32();
-//         if random.next_bounded_i32(10) == 0 {
-//             width *= random.next_f32() * random.next_f32() * 3.0 + 1.0;
-//         }
-//         width
-//     }
-// }
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use pumpkin_util::{
+    math::{cos, float_provider::FloatProvider, sin, vector2::Vector2, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl, legacy_rand::LegacyRand},
+};
+use serde::Deserialize;
+
+use crate::generation::positions::chunk_pos;
+
+use super::{
+    BlockCarver, CarverConfig, CarvingContext, can_reach, carve_block, carve_nether_block,
+};
+
+/// How many steps a tunnel winds through at most, which keeps it within the
+/// [`CARVER_RADIUS`](super::CARVER_RADIUS).
+pub(super) const TUNNEL_LENGTH: i32 = (super::CARVER_RADIUS - 1) * 16;
+
+/// Digs systems of winding tunnels, sometimes branching off of a larger room.
+#[derive(Deserialize)]
+pub struct CaveCarver {
+    #[serde(flatten)]
+    pub(super) config: CarverConfig,
+    horizontal_radius_multiplier: FloatProvider,
+    vertical_radius_multiplier: FloatProvider,
+    floor_level: FloatProvider,
+}
+
+/// The tunnel currently being dug, and how it was shaped.
+struct Tunnel<'a> {
+    horizontal_radius_multiplier: f64,
+    vertical_radius_multiplier: f64,
+    floor_level: f64,
+    carve_block: BlockCarver,
+    config: &'a CarverConfig,
+}
+
+impl CaveCarver {
+    pub fn carve(
+        &self,
+        context: &mut CarvingContext,
+        random: &mut RandomGenerator,
+        start_chunk: &Vector2<i32>,
+        nether: bool,
+    ) {
+        let (cave_bound, y_scale, carve_block): (i32, f64, BlockCarver) = if nether {
+            (10, 5.0, carve_nether_block)
+        } else {
+            (15, 1.0, carve_block)
+        };
+
+        let bound = random.next_bounded_i32(cave_bound) + 1;
+        let bound = random.next_bounded_i32(bound) + 1;
+        let caves = random.next_bounded_i32(bound);
+        for _ in 0..caves {
+            let x = (chunk_pos::start_block_x(start_chunk) + random.next_bounded_i32(16)) as f64;
+            let y = self.config.y.get(random, context.min_y, context.height) as f64;
+            let z = (chunk_pos::start_block_z(start_chunk) + random.next_bounded_i32(16)) as f64;
+            let tunnel = Tunnel {
+                horizontal_radius_multiplier: self.horizontal_radius_multiplier.get(random) as f64,
+                vertical_radius_multiplier: self.vertical_radius_multiplier.get(random) as f64,
+                floor_level: self.floor_level.get(random) as f64,
+                carve_block,
+                config: &self.config,
+            };
+            let center = Vector3::new(x, y, z);
+
+            let mut tunnels = 1;
+            if random.next_bounded_i32(4) == 0 {
+                let ratio = self.config.y_scale.get(random) as f64;
+                let radius = 1.0 + random.next_f32() * 6.0;
+                tunnel.carve_room(context, center, radius, ratio);
+                tunnels += random.next_bounded_i32(4);
+            }
+
+            for _ in 0..tunnels {
+                let yaw = random.next_f32() * TAU;
+                let pitch = (random.next_f32() - 0.5) / 4.0;
+                let thickness = if nether {
+                    (random.next_f32() * 2.0 + random.next_f32()) * 2.0
+                } else {
+                    let mut thickness = random.next_f32() * 2.0 + random.next_f32();
+                    if random.next_bounded_i32(10) == 0 {
+                        thickness *= random.next_f32() * random.next_f32() * 3.0 + 1.0;
+                    }
+                    thickness
+                };
+                let steps = TUNNEL_LENGTH - random.next_bounded_i32(TUNNEL_LENGTH / 4);
+                tunnel.carve_tunnel(
+                    context,
+                    random.next_i64(),
+                    center,
+                    thickness,
+                    yaw,
+                    pitch,
+                    0..steps,
+                    y_scale,
+                );
+            }
+        }
+    }
+}
+
+impl Tunnel<'_> {
+    fn should_skip(&self, relative_x: f64, relative_y: f64, relative_z: f64) -> bool {
+        relative_y <= self.floor_level
+            || relative_x * relative_x + relative_y * relative_y + relative_z * relative_z >= 1.0
+    }
+
+    fn carve_room(
+        &self,
+        context: &mut CarvingContext,
+        center: Vector3<f64>,
+        radius: f32,
+        ratio: f64,
+    ) {
+        let horizontal_radius = 1.5 + (sin(FRAC_PI_2 as f64) * radius) as f64;
+        context.carve_ellipsoid(
+            self.config,
+            Vector3::new(center.x + 1.0, center.y, center.z),
+            horizontal_radius,
+            horizontal_radius * ratio,
+            |x, y, z, _| self.should_skip(x, y, z),
+            self.carve_block,
+        );
+    }
+
+    /// Digs a tunnel from `start` along the `steps` of its path, splitting it in two once it is
+    /// thick enough.
+    #[allow(clippy::too_many_arguments)]
+    fn carve_tunnel(
+        &self,
+        context: &mut CarvingContext,
+        seed: i64,
+        start: Vector3<f64>,
+        thickness: f32,
+        mut yaw: f32,
+        mut pitch: f32,
+        steps: std::ops::Range<i32>,
+        ratio: f64,
+    ) {
+        let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(seed as u64));
+        let branch_step = random.next_bounded_i32(steps.end / 2) + steps.end / 4;
+        let steep = random.next_bounded_i32(6) == 0;
+        let (mut yaw_change, mut pitch_change) = (0.0f32, 0.0f32);
+        let Vector3 {
+            mut x,
+            mut y,
+            mut z,
+        } = start;
+
+        for step in steps.clone() {
+            let horizontal_radius =
+                1.5 + (sin((PI * step as f32 / steps.end as f32) as f64) * thickness) as f64;
+            let vertical_radius = horizontal_radius * ratio;
+            let pitch_cos = cos(pitch as f64);
+            x += (cos(yaw as f64) * pitch_cos) as f64;
+            y += sin(pitch as f64) as f64;
+            z += (sin(yaw as f64) * pitch_cos) as f64;
+            pitch *= if steep { 0.92 } else { 0.7 };
+            pitch += pitch_change * 0.1;
+            yaw += yaw_change * 0.1;
+            pitch_change *= 0.9;
+            yaw_change *= 0.75;
+            pitch_change += (random.next_f32() - random.next_f32()) * random.next_f32() * 2.0;
+            yaw_change += (random.next_f32() - random.next_f32()) * random.next_f32() * 4.0;
+
+            if step == branch_step && thickness > 1.0 {
+                for turn in [-FRAC_PI_2, FRAC_PI_2] {
+                    let seed = random.next_i64();
+                    let thickness = random.next_f32() * 0.5 + 0.5;
+                    self.carve_tunnel(
+                        context,
+                        seed,
+                        Vector3::new(x, y, z),
+                        thickness,
+                        yaw + turn,
+                        pitch / 3.0,
+                        step..steps.end,
+                        1.0,
+                    );
+                }
+                return;
+            }
+
+            if random.next_bounded_i32(4) != 0 {
+                if !can_reach(&context.chunk.chunk_pos, x, z, step, steps.end, thickness) {
+                    return;
+                }
+                context.carve_ellipsoid(
+                    self.config,
+                    Vector3::new(x, y, z),
+                    horizontal_radius * self.horizontal_radius_multiplier,
+                    vertical_radius * self.vertical_radius_multiplier,
+                    |x, y, z, _| self.should_skip(x, y, z),
+                    self.carve_block,
+                );
+            }
+        }
+    }
+}
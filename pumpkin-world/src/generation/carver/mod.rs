@@ -1,2 +1,232 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+use pumpkin_data::{Block, BlockState, tag::Taggable};
+use pumpkin_util::{
+    math::{float_provider::FloatProvider, vector2::Vector2, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl},
+    read_data_from_file,
+};
+use serde::Deserialize;
+
+use crate::ProtoChunk;
+
+use super::{
+    chunk_noise::LAVA_BLOCK, height_provider::HeightProvider, positions::chunk_pos,
+    y_offset::YOffset,
+};
+
+mod canyon;
 mod cave;
 mod mask;
+
+use canyon::CanyonCarver;
+use cave::CaveCarver;
+pub use mask::CarvingMask;
+
+/// How many chunks away from the chunk it starts in a carver can still dig.
+pub const CARVER_RADIUS: i32 = 8;
+
+pub static CONFIGURED_CARVERS: LazyLock<HashMap<String, ConfiguredCarver>> =
+    LazyLock::new(|| read_data_from_file!("../../../../assets/carver.json"));
+
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "config")]
+pub enum ConfiguredCarver {
+    #[serde(rename = "minecraft:cave")]
+    Cave(CaveCarver),
+    #[serde(rename = "minecraft:nether_cave")]
+    NetherCave(CaveCarver),
+    #[serde(rename = "minecraft:canyon")]
+    Canyon(CanyonCarver),
+}
+
+impl ConfiguredCarver {
+    fn config(&self) -> &CarverConfig {
+        match self {
+            Self::Cave(carver) | Self::NetherCave(carver) => &carver.config,
+            Self::Canyon(carver) => &carver.config,
+        }
+    }
+
+    /// Whether this carver starts digging in the chunk `random` was seeded for.
+    pub fn should_carve(&self, random: &mut RandomGenerator) -> bool {
+        random.next_f32() <= self.config().probability
+    }
+
+    /// Digs the caves starting in `start_chunk` into the chunk of `context`.
+    pub fn carve(
+        &self,
+        context: &mut CarvingContext,
+        random: &mut RandomGenerator,
+        start_chunk: &Vector2<i32>,
+    ) {
+        match self {
+            Self::Cave(carver) => carver.carve(context, random, start_chunk, false),
+            Self::NetherCave(carver) => carver.carve(context, random, start_chunk, true),
+            Self::Canyon(carver) => carver.carve(context, random, start_chunk),
+        }
+    }
+}
+
+/// The settings every carver has.
+#[derive(Deserialize)]
+pub struct CarverConfig {
+    probability: f32,
+    y: HeightProvider,
+    #[serde(rename = "yScale")]
+    y_scale: FloatProvider,
+    lava_level: YOffset,
+    replaceable: String,
+}
+
+/// The chunk being carved, with what carvers need to know about it.
+pub struct CarvingContext<'a, 'b> {
+    pub chunk: &'a mut ProtoChunk<'b>,
+    pub mask: &'a mut CarvingMask,
+    /// The bottom of the terrain shape.
+    pub min_y: i8,
+    /// The height of the terrain shape.
+    pub height: u16,
+}
+
+/// Decides what a single block inside of a carved ellipsoid becomes. Returns whether it was
+/// carved out.
+type BlockCarver =
+    fn(&mut CarvingContext, &CarverConfig, &Vector3<i32>, &mut Option<&'static BlockState>) -> bool;
+
+impl CarvingContext<'_, '_> {
+    /// Carves out the blocks of the chunk inside of the ellipsoid around `center`, leaving those
+    /// `should_skip` picks with the position relative to the radii and the absolute y.
+    #[allow(clippy::too_many_arguments)]
+    fn carve_ellipsoid(
+        &mut self,
+        config: &CarverConfig,
+        center: Vector3<f64>,
+        horizontal_radius: f64,
+        vertical_radius: f64,
+        should_skip: impl Fn(f64, f64, f64, i32) -> bool,
+        carve_block: BlockCarver,
+    ) {
+        let chunk_pos = self.chunk.chunk_pos;
+        let start_x = chunk_pos::start_block_x(&chunk_pos);
+        let start_z = chunk_pos::start_block_z(&chunk_pos);
+        let max_distance = 16.0 + horizontal_radius * 2.0;
+        if (center.x - (start_x + 8) as f64).abs() > max_distance
+            || (center.z - (start_z + 8) as f64).abs() > max_distance
+        {
+            return;
+        }
+
+        let min_x = ((center.x - horizontal_radius).floor() as i32 - start_x - 1).max(0);
+        let max_x = ((center.x + horizontal_radius).floor() as i32 - start_x).min(15);
+        let min_y = ((center.y - vertical_radius).floor() as i32 - 1).max(self.min_y as i32 + 1);
+        // The top 7 blocks are left alone
+        let max_y = ((center.y + vertical_radius).floor() as i32 + 1)
+            .min(self.min_y as i32 + self.height as i32 - 1 - 7);
+        let min_z = ((center.z - horizontal_radius).floor() as i32 - start_z - 1).max(0);
+        let max_z = ((center.z + horizontal_radius).floor() as i32 - start_z).min(15);
+
+        for local_x in min_x..=max_x {
+            let x = start_x + local_x;
+            let relative_x = (x as f64 + 0.5 - center.x) / horizontal_radius;
+            for local_z in min_z..=max_z {
+                let z = start_z + local_z;
+                let relative_z = (z as f64 + 0.5 - center.z) / horizontal_radius;
+                if relative_x * relative_x + relative_z * relative_z >= 1.0 {
+                    continue;
+                }
+
+                // The grass or mycelium block this column was carved through, if any
+                let mut surface = None;
+                for y in (min_y + 1..=max_y).rev() {
+                    let relative_y = (y as f64 - 0.5 - center.y) / vertical_radius;
+                    if should_skip(relative_x, relative_y, relative_z, y)
+                        || self.mask.get(local_x, y, local_z)
+                    {
+                        continue;
+                    }
+                    self.mask.set(local_x, y, local_z);
+                    carve_block(self, config, &Vector3::new(x, y, z), &mut surface);
+                }
+            }
+        }
+    }
+}
+
+/// Carves out a single block if `config` allows it, filling it like an aquifer would.
+fn carve_block(
+    context: &mut CarvingContext,
+    config: &CarverConfig,
+    pos: &Vector3<i32>,
+    surface: &mut Option<&'static BlockState>,
+) -> bool {
+    let state = context.chunk.get_block_state(pos).to_state();
+    let block = Block::from_state_id(state.id);
+    if block == &Block::GRASS_BLOCK || block == &Block::MYCELIUM {
+        *surface = Some(state);
+    }
+    if state.id != context.chunk.fill_block().id
+        && !block.is_tagged_with(&config.replaceable).unwrap_or(false)
+    {
+        return false;
+    }
+
+    let carved = if pos.y <= config.lava_level.get_y(context.min_y, context.height) as i32 {
+        LAVA_BLOCK.default_state
+    } else {
+        // Carving into the barrier between two aquifers would let them leak into each other
+        let Some(carved) = context.chunk.carved_block_state(pos) else {
+            return false;
+        };
+        carved
+    };
+    context.chunk.set_block_state(pos, carved);
+
+    // Vanilla runs the surface rules to grow the exposed dirt back over; the surface block
+    // that was carved away is close enough
+    if let Some(surface) = *surface
+        && !carved.is_liquid()
+    {
+        let below = Vector3::new(pos.x, pos.y - 1, pos.z);
+        if context.chunk.get_block_state(&below).to_block() == &Block::DIRT {
+            context.chunk.set_block_state(&below, surface);
+        }
+    }
+    true
+}
+
+/// Carves out a single block of the Nether, which has no aquifers: everything low enough is
+/// flooded with lava instead.
+fn carve_nether_block(
+    context: &mut CarvingContext,
+    config: &CarverConfig,
+    pos: &Vector3<i32>,
+    _surface: &mut Option<&'static BlockState>,
+) -> bool {
+    let state = context.chunk.get_block_state(pos).to_state();
+    if state.id != context.chunk.fill_block().id
+        && !Block::from_state_id(state.id)
+            .is_tagged_with(&config.replaceable)
+            .unwrap_or(false)
+    {
+        return false;
+    }
+
+    let carved = if pos.y <= context.min_y as i32 + 31 {
+        LAVA_BLOCK.default_state
+    } else {
+        Block::CAVE_AIR.default_state
+    };
+    context.chunk.set_block_state(pos, carved);
+    true
+}
+
+/// Whether a tunnel at `x`, `z` that is `step` of `steps` along and `width` wide can still reach
+/// the chunk at `chunk_pos`.
+fn can_reach(chunk_pos: &Vector2<i32>, x: f64, z: f64, step: i32, steps: i32, width: f32) -> bool {
+    let offset_x = x - (chunk_pos::start_block_x(chunk_pos) + 8) as f64;
+    let offset_z = z - (chunk_pos::start_block_z(chunk_pos) + 8) as f64;
+    let remaining = (steps - step) as f64;
+    let reach = (width + 2.0 + 16.0) as f64;
+    offset_x * offset_x + offset_z * offset_z - remaining * remaining <= reach * reach
+}
@@ -49,6 +49,26 @@ impl BlockStateSampler {
             Self::Chained(chained) => chained.sample(router, pos, sample_options, height_estimator),
         }
     }
+
+    /// Returns what the aquifer fills the space a carver dug out at `pos` with, see
+    /// [`AquiferSamplerImpl::fill_carved`].
+    pub fn fill_carved(
+        &mut self,
+        router: &mut ChunkNoiseRouter,
+        pos: &impl NoisePos,
+        sample_options: &ChunkNoiseFunctionSampleOptions,
+        height_estimator: &mut SurfaceHeightEstimateSampler,
+    ) -> Option<&'static BlockState> {
+        match self {
+            Self::Aquifer(aquifer) => {
+                aquifer.fill_carved(router, pos, sample_options, height_estimator)
+            }
+            Self::Ore(_) => None,
+            Self::Chained(chained) => chained.samplers.iter_mut().find_map(|sampler| {
+                sampler.fill_carved(router, pos, sample_options, height_estimator)
+            }),
+        }
+    }
 }
 
 pub struct ChainedBlockStateSampler {
@@ -396,6 +416,21 @@ impl<'a> ChunkNoiseGenerator<'a> {
             .sample(&mut self.router, &pos, &options, height_estimator)
     }
 
+    /// Returns what fills the space a carver dug out at `pos`, or `None` if it has to stay solid.
+    pub fn sample_carved_block_state(
+        &mut self,
+        pos: &Vector3<i32>,
+        height_estimator: &mut SurfaceHeightEstimateSampler,
+    ) -> Option<&'static BlockState> {
+        let pos = UnblendedNoisePos::new(pos.x, pos.y, pos.z);
+        // The cell caches are long gone once the terrain is carved
+        let options =
+            ChunkNoiseFunctionSampleOptions::new(false, SampleAction::SkipCellCaches, 0, 0, 0);
+
+        self.state_sampler
+            .fill_carved(&mut self.router, &pos, &options, height_estimator)
+    }
+
     #[inline]
     pub fn horizontal_cell_block_count(&self) -> u8 {
         self.generation_shape.horizontal_cell_block_count()
@@ -0,0 +1,44 @@
+use pumpkin_data::chunk::Biome;
+use pumpkin_util::{
+    HeightMap,
+    math::{vector2::Vector2, vector3::Vector3},
+};
+
+use crate::{ProtoChunk, block::RawBlockState};
+
+use super::height_limit::HeightLimitView;
+
+/// Read access to the terrain of a chunk still being generated, e.g. for drawing maps of a seed
+/// without finishing every chunk first.
+///
+/// Block positions are absolute and must lie within the chunk horizontally; blocks below or above
+/// it are void air.
+pub trait ChunkView: HeightLimitView {
+    fn chunk_pos(&self) -> Vector2<i32>;
+
+    fn get_block_state(&self, pos: &Vector3<i32>) -> RawBlockState;
+
+    /// The biome of the cell at `biome_pos`, in absolute biome coordinates.
+    fn get_biome(&self, biome_pos: &Vector3<i32>) -> &'static Biome;
+
+    /// The lowest y above every block `heightmap` counts in the column at `pos`.
+    fn get_top_y(&self, heightmap: &HeightMap, pos: &Vector2<i32>) -> i32;
+}
+
+impl ChunkView for ProtoChunk<'_> {
+    fn chunk_pos(&self) -> Vector2<i32> {
+        self.chunk_pos
+    }
+
+    fn get_block_state(&self, pos: &Vector3<i32>) -> RawBlockState {
+        ProtoChunk::get_block_state(self, pos)
+    }
+
+    fn get_biome(&self, biome_pos: &Vector3<i32>) -> &'static Biome {
+        ProtoChunk::get_biome(self, biome_pos)
+    }
+
+    fn get_top_y(&self, heightmap: &HeightMap, pos: &Vector2<i32>) -> i32 {
+        ProtoChunk::get_top_y(self, heightmap, pos)
+    }
+}
@@ -1,12 +1,9 @@
-use std::{
-    collections::HashMap,
-    sync::{Arc, LazyLock},
-};
+use std::{collections::HashMap, sync::LazyLock};
 
 use pumpkin_util::{math::position::BlockPos, random::RandomGenerator, read_data_from_file};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, level::Level, world::BlockRegistryExt};
+use crate::{generation::proto_chunk_region::ProtoChunkRegion, world::BlockRegistryExt};
 
 use super::features::{
     bamboo::BambooFeature,
@@ -212,8 +209,7 @@ impl ConfiguredFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         min_y: i8,
         height: u16,
@@ -241,6 +237,17 @@ impl ConfiguredFeature {
                 pos,
             ),
             Self::PointedDripstone(feature) => feature.generate(chunk, random, pos),
+            Self::DripstoneCluster(feature) => feature.generate(chunk, random, pos),
+            Self::LargeDripstone(feature) => feature.generate(chunk, random, pos),
+            Self::VegetationPatch(feature) => feature.generate(
+                chunk,
+                block_registry,
+                min_y,
+                height,
+                feature_name,
+                random,
+                pos,
+            ),
             Self::CoralMushroom(feature) => {
                 feature.generate(chunk, min_y, height, feature_name, random, pos)
             }
@@ -268,11 +275,12 @@ impl ConfiguredFeature {
                 random,
                 pos,
             ),
+            Self::EndIsland(feature) => feature.generate(chunk, random, pos),
+            Self::EndGateway(feature) => feature.generate(chunk, pos),
             Self::SpringFeature(feature) => feature.generate(block_registry, chunk, random, pos),
             Self::SimpleBlock(feature) => feature.generate(block_registry, chunk, random, pos),
             Self::Flower(feature) => feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -282,7 +290,6 @@ impl ConfiguredFeature {
             ),
             Self::NoBonemealFlower(feature) => feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -293,6 +300,10 @@ impl ConfiguredFeature {
             Self::DesertWell(feature) => {
                 feature.generate(chunk, min_y, height, feature_name, random, pos)
             }
+            Self::MonsterRoom(feature) => feature.generate(chunk, random, pos),
+            Self::Lake(feature) => feature.generate(chunk, random, pos),
+            Self::FreezeTopLayer(feature) => feature.generate(chunk, pos),
+            Self::Disk(feature) => feature.generate(chunk, block_registry, random, pos),
             Self::Bamboo(feature) => feature.generate(
                 chunk,
                 block_registry,
@@ -313,7 +324,6 @@ impl ConfiguredFeature {
             ),
             Self::RandomPatch(feature) => feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -323,7 +333,6 @@ impl ConfiguredFeature {
             ),
             Self::RandomBooleanSelector(feature) => feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -332,11 +341,10 @@ impl ConfiguredFeature {
                 pos,
             ),
             Self::Tree(feature) => {
-                feature.generate(chunk, level, min_y, height, feature_name, random, pos)
+                feature.generate(chunk, min_y, height, feature_name, random, pos)
             }
             Self::RandomSelector(feature) => feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -346,7 +354,6 @@ impl ConfiguredFeature {
             ),
             Self::SimpleRandomSelector(feature) => feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -366,6 +373,9 @@ impl ConfiguredFeature {
             Self::Seagrass(feature) => {
                 feature.generate(chunk, min_y, height, feature_name, random, pos)
             }
+            Self::Kelp(feature) => {
+                feature.generate(chunk, min_y, height, feature_name, random, pos)
+            }
             Self::SeaPickle(feature) => {
                 feature.generate(chunk, min_y, height, feature_name, random, pos)
             }
@@ -0,0 +1,215 @@
+use std::{
+    cmp::Reverse,
+    collections::{BTreeSet, BinaryHeap, HashMap},
+    sync::LazyLock,
+};
+
+use pumpkin_data::chunk::Biome;
+use pumpkin_util::{
+    math::vector2::Vector2,
+    random::{RandomGenerator, get_decorator_seed, population_seed, xoroshiro128::Xoroshiro},
+};
+
+/// The steps a chunk is decorated in, in order. Every feature of a step is placed before any
+/// feature of the next one, so that e.g. the grass grows on top of the ores and the lakes rather
+/// than getting replaced by them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DecorationStep {
+    RawGeneration,
+    Lakes,
+    LocalModifications,
+    UndergroundStructures,
+    SurfaceStructures,
+    Strongholds,
+    UndergroundOres,
+    UndergroundDecoration,
+    FluidSprings,
+    VegetalDecoration,
+    TopLayerModification,
+}
+
+impl DecorationStep {
+    pub const ALL: [Self; 11] = [
+        Self::RawGeneration,
+        Self::Lakes,
+        Self::LocalModifications,
+        Self::UndergroundStructures,
+        Self::SurfaceStructures,
+        Self::Strongholds,
+        Self::UndergroundOres,
+        Self::UndergroundDecoration,
+        Self::FluidSprings,
+        Self::VegetalDecoration,
+        Self::TopLayerModification,
+    ];
+
+    /// Where the step comes in the features of a biome, which also goes into the seed of every
+    /// feature placed in it.
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// The random the feature at `index` of this step is placed with in `chunk`. It is the one
+    /// vanilla places the feature with, so features at their index in [`FEATURE_ORDER`] end up
+    /// exactly where they do in vanilla.
+    pub fn feature_random(
+        self,
+        world_seed: u64,
+        chunk: &Vector2<i32>,
+        index: usize,
+    ) -> RandomGenerator {
+        let seed = get_decorator_seed(
+            population_seed(world_seed, chunk.x, chunk.y),
+            index,
+            self.index(),
+        );
+        RandomGenerator::Xoroshiro(Xoroshiro::from_seed(seed))
+    }
+}
+
+/// The placed features of every biome, by the step they are placed in.
+pub static FEATURE_ORDER: LazyLock<FeatureOrder> =
+    LazyLock::new(|| FeatureOrder::new((0..=u8::MAX).filter_map(Biome::from_id)));
+
+/// The order the placed features of a step run in, which keeps the order every biome lists them
+/// in. Chunks with several biomes place each of their features once, at its place in this order.
+pub struct FeatureOrder {
+    /// The names of the features without the `minecraft:` prefix, step by step.
+    steps: [Vec<&'static str>; DecorationStep::ALL.len()],
+}
+
+impl FeatureOrder {
+    pub fn new<'a>(biomes: impl IntoIterator<Item = &'a Biome> + Clone) -> Self {
+        Self {
+            steps: DecorationStep::ALL.map(|step| {
+                sort(biomes.clone().into_iter().map(|biome| {
+                    biome
+                        .features
+                        .get(step.index())
+                        .copied()
+                        .unwrap_or_default()
+                }))
+            }),
+        }
+    }
+
+    pub fn features(&self, step: DecorationStep) -> &[&'static str] {
+        &self.steps[step.index()]
+    }
+
+    /// Where the feature called `name` comes in `step`, which goes into the seed it is placed
+    /// with.
+    pub fn index(&self, step: DecorationStep, name: &str) -> Option<usize> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        self.features(step)
+            .iter()
+            .position(|feature| *feature == name)
+    }
+}
+
+/// Merges `lists` into one list keeping the order of each, breaking ties by which feature was
+/// listed first. Features the lists disagree on the order of come last.
+fn sort<'a>(lists: impl Iterator<Item = &'a [&'static str]>) -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = Vec::new();
+    let mut indices = HashMap::new();
+    let mut after: Vec<BTreeSet<usize>> = Vec::new();
+    for list in lists {
+        let mut previous: Option<usize> = None;
+        for name in list {
+            let name = name.strip_prefix("minecraft:").unwrap_or(name);
+            let index = *indices.entry(name).or_insert_with(|| {
+                names.push(name);
+                after.push(BTreeSet::new());
+                names.len() - 1
+            });
+            if let Some(previous) = previous
+                && previous != index
+            {
+                after[previous].insert(index);
+            }
+            previous = Some(index);
+        }
+    }
+
+    let mut before = vec![0; names.len()];
+    for next in after.iter().flatten() {
+        before[*next] += 1;
+    }
+    let mut ready: BinaryHeap<_> = (0..names.len())
+        .filter(|index| before[*index] == 0)
+        .map(Reverse)
+        .collect();
+    let mut sorted = Vec::with_capacity(names.len());
+    let mut placed = vec![false; names.len()];
+    while let Some(Reverse(index)) = ready.pop() {
+        sorted.push(names[index]);
+        placed[index] = true;
+        for next in &after[index] {
+            before[*next] -= 1;
+            if before[*next] == 0 {
+                ready.push(Reverse(*next));
+            }
+        }
+    }
+    sorted.extend(
+        (0..names.len())
+            .filter(|index| !placed[*index])
+            .map(|index| names[index]),
+    );
+    sorted
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_data::chunk::Biome;
+
+    use super::{DecorationStep, FEATURE_ORDER, sort};
+
+    #[test]
+    fn ores_come_before_grass() {
+        // Every step runs after the one before it
+        assert!(DecorationStep::UndergroundOres < DecorationStep::VegetalDecoration);
+        assert!(
+            FEATURE_ORDER
+                .index(DecorationStep::UndergroundOres, "minecraft:ore_coal_upper")
+                .is_some()
+        );
+        assert!(
+            FEATURE_ORDER
+                .index(DecorationStep::VegetalDecoration, "patch_grass_plain")
+                .is_some()
+        );
+        assert_eq!(
+            FEATURE_ORDER.index(DecorationStep::UndergroundOres, "patch_grass_plain"),
+            None
+        );
+
+        // Every biome keeps its own order
+        for biome in [&Biome::PLAINS, &Biome::FOREST, &Biome::WARM_OCEAN] {
+            for step in DecorationStep::ALL {
+                let indices: Vec<_> = biome.features[step.index()]
+                    .iter()
+                    .map(|name| FEATURE_ORDER.index(step, name).unwrap())
+                    .collect();
+                assert!(indices.is_sorted(), "{} {step:?}", biome.registry_id);
+            }
+        }
+    }
+
+    #[test]
+    fn lists_are_merged_in_order() {
+        let lists: [&[&str]; 3] = [
+            &["minecraft:a", "minecraft:c"],
+            &["minecraft:b", "minecraft:c", "minecraft:d"],
+            &["minecraft:a", "minecraft:b"],
+        ];
+        assert_eq!(sort(lists.into_iter()), ["a", "b", "c", "d"]);
+        // A cycle can't be ordered, so it goes last
+        let lists: [&[&str]; 3] = [
+            &["minecraft:x", "minecraft:y"],
+            &["minecraft:y", "minecraft:x"],
+            &["minecraft:z"],
+        ];
+        assert_eq!(sort(lists.into_iter()), ["z", "x", "y"]);
+    }
+}
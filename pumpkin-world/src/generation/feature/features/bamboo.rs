@@ -10,7 +10,7 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{ProtoChunk, world::BlockRegistryExt};
+use crate::{generation::proto_chunk_region::ProtoChunkRegion, world::BlockRegistryExt};
 
 #[derive(Deserialize)]
 pub struct BambooFeature {
@@ -21,7 +21,7 @@ impl BambooFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
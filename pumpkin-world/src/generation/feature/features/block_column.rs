@@ -6,8 +6,10 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk,
-    generation::{block_predicate::BlockPredicate, block_state_provider::BlockStateProvider},
+    generation::{
+        block_predicate::BlockPredicate, block_state_provider::BlockStateProvider,
+        proto_chunk_region::ProtoChunkRegion,
+    },
     world::BlockRegistryExt,
 };
 
@@ -29,7 +31,7 @@ impl BlockColumnFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
@@ -5,7 +5,7 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 use super::CoralFeature;
 
@@ -15,7 +15,7 @@ pub struct CoralClawFeature;
 impl CoralClawFeature {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
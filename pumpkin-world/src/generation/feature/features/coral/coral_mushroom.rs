@@ -4,7 +4,7 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 use super::CoralFeature;
 
@@ -14,7 +14,7 @@ pub struct CoralMushroomFeature;
 impl CoralMushroomFeature {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
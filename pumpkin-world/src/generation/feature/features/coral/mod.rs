@@ -9,7 +9,7 @@ use pumpkin_util::{
     random::{RandomGenerator, RandomImpl},
 };
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 pub mod coral_claw;
 pub mod coral_mushroom;
@@ -19,7 +19,7 @@ pub struct CoralFeature;
 
 impl CoralFeature {
     pub fn generate_coral_piece(
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         state: &BlockState,
         pos: BlockPos,
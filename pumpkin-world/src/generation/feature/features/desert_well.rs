@@ -5,9 +5,8 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{
-    ProtoChunk,
-    generation::{chunk_noise::WATER_BLOCK, height_limit::HeightLimitView},
+use crate::generation::{
+    chunk_noise::WATER_BLOCK, height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion,
 };
 
 // TODO: remove .to_state()
@@ -23,7 +22,7 @@ impl DesertWellFeature {
 
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
@@ -1,6 +1,136 @@
+use pumpkin_util::{
+    math::{int_provider::IntProvider, position::BlockPos, vector3::Vector3},
+    random::RandomGenerator,
+};
 use serde::Deserialize;
 
+use crate::{
+    generation::{
+        block_predicate::BlockPredicate, block_state_provider::RuleBasedBlockStateProvider,
+        proto_chunk_region::ProtoChunkRegion,
+    },
+    world::BlockRegistryExt,
+};
+
+/// A round patch of another block, like the sand, clay and gravel in riverbeds, replacing the
+/// target blocks a few blocks above and below the origin.
 #[derive(Deserialize)]
 pub struct DiskFeature {
-    // TODO
+    state_provider: RuleBasedBlockStateProvider,
+    target: BlockPredicate,
+    radius: IntProvider,
+    half_height: i32,
+}
+
+impl DiskFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        block_registry: &dyn BlockRegistryExt,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        let origin = pos.0;
+        let top = origin.y + self.half_height;
+        let bottom = origin.y - self.half_height - 1;
+        let radius = self.radius.get(random);
+
+        let mut placed = false;
+        for z in -radius..=radius {
+            for x in -radius..=radius {
+                if x * x + z * z > radius * radius {
+                    continue;
+                }
+                for y in (bottom + 1..=top).rev() {
+                    let pos = BlockPos(Vector3::new(origin.x + x, y, origin.z + z));
+                    if !self.target.test(block_registry, chunk, &pos) {
+                        continue;
+                    }
+                    let state = self.state_provider.get(block_registry, chunk, random, pos);
+                    chunk.set_block_state(&pos.0, state);
+                    placed = true;
+                }
+            }
+        }
+        placed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::LazyLock};
+
+    use pumpkin_data::{Block, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            generator::{decorate, tests::BlockRegistry, with_neighbors},
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    /// How many blocks of `block` lie right below water in `chunks`.
+    fn under_water(chunks: &HashMap<Vector2<i32>, ProtoChunk>, block: &Block) -> usize {
+        chunks
+            .values()
+            .flat_map(|chunk| {
+                (0..16)
+                    .flat_map(|x| (0..16).flat_map(move |z| (-64..320).map(move |y| (x, y, z))))
+                    .filter(|(x, y, z)| {
+                        chunk.get_block_state(&Vector3::new(*x, *y, *z)).to_block() == block
+                            && chunk
+                                .get_block_state(&Vector3::new(*x, y + 1, *z))
+                                .to_block()
+                                == &Block::WATER
+                    })
+            })
+            .count()
+    }
+
+    #[test]
+    fn riverbeds_get_clay_and_gravel() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        // A river for this seed, with its banks around it
+        let pos = Vector2::new(-2, -2);
+        let mut chunks: HashMap<_, _> = with_neighbors(pos)
+            .into_iter()
+            .map(|pos| {
+                let mut chunk = ProtoChunk::new(
+                    pos,
+                    &BASE_NOISE_ROUTER,
+                    &RANDOM_CONFIG,
+                    settings,
+                    &TERRAIN_CACHE,
+                    settings.default_block.get_state(),
+                );
+                chunk.populate_biomes(&Dimension::Overworld);
+                chunk.populate_noise();
+                chunk.build_surface();
+                (pos, chunk)
+            })
+            .collect();
+        let clay = under_water(&chunks, &Block::CLAY);
+        let gravel = under_water(&chunks, &Block::GRAVEL);
+
+        decorate(&mut chunks, pos, &BlockRegistry);
+
+        // The disks reach across the banks into the neighbors
+        assert!(under_water(&chunks, &Block::CLAY) > clay);
+        assert!(under_water(&chunks, &Block::GRAVEL) > gravel);
+    }
 }
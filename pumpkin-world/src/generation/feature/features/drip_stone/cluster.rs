@@ -1,6 +1,245 @@
+use pumpkin_data::{Block, BlockDirection, BlockState, tag, tag::Taggable};
+use pumpkin_util::{
+    math::{
+        clamped_map, float_provider::FloatProvider, int_provider::IntProvider, position::BlockPos,
+        vector3::Vector3,
+    },
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
+
+use super::{Column, grow_pointed_dripstone, is_empty_or_water, state_at};
+
+/// A patch of stalactites and stalagmites between the floor and the ceiling of a cave, longer
+/// towards its middle, with dripstone blocks above and below them and now and then a puddle.
 #[derive(Deserialize)]
 pub struct DripstoneClusterFeature {
-    // TODO
+    floor_to_ceiling_search_range: i32,
+    height: IntProvider,
+    radius: IntProvider,
+    max_stalagmite_stalactite_height_diff: i32,
+    height_deviation: i32,
+    dripstone_block_layer_thickness: IntProvider,
+    density: FloatProvider,
+    wetness: FloatProvider,
+    chance_of_dripstone_column_at_max_distance_from_center: f32,
+    max_distance_from_edge_affecting_chance_of_dripstone_column: i32,
+    max_distance_from_center_affecting_height_bias: i32,
+}
+
+impl DripstoneClusterFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        if !is_empty_or_water(state_at(chunk, pos)) {
+            return false;
+        }
+        let height = self.height.get(random);
+        let wetness = self.wetness.get(random);
+        let density = self.density.get(random);
+        let radius_x = self.radius.get(random);
+        let radius_z = self.radius.get(random);
+        for x in -radius_x..=radius_x {
+            for z in -radius_z..=radius_z {
+                let chance = self.chance_of_column(radius_x, radius_z, x, z);
+                let pos = pos.add(x, 0, z);
+                self.place_column(chunk, random, pos, x, z, wetness, chance, height, density);
+            }
+        }
+        true
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    fn place_column(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+        x: i32,
+        z: i32,
+        wetness: f32,
+        chance: f64,
+        height: i32,
+        density: f32,
+    ) {
+        let Some(column) = Column::scan(
+            chunk,
+            pos,
+            self.floor_to_ceiling_search_range,
+            is_empty_or_water,
+            |state| !is_empty_or_water(state),
+        ) else {
+            return;
+        };
+        let at = |y| BlockPos(Vector3::new(pos.0.x, y, pos.0.z));
+        let ceiling = column.ceiling;
+        if ceiling.is_none() && column.floor.is_none() {
+            return;
+        }
+
+        // A puddle on the floor lowers it by one block
+        let wet = random.next_f32() < wetness;
+        let column = match column.floor {
+            Some(floor) if wet && can_place_pool(chunk, at(floor)) => {
+                chunk.set_block_state(&at(floor).0, Block::WATER.default_state);
+                Column {
+                    floor: Some(floor - 1),
+                    ceiling,
+                }
+            }
+            _ => column,
+        };
+        let floor = column.floor;
+
+        let grows = random.next_f64() < chance;
+        let stalactite = match ceiling {
+            Some(ceiling) if grows && !is_lava(chunk, at(ceiling)) => {
+                let thickness = self.dripstone_block_layer_thickness.get(random);
+                replace_with_dripstone_blocks(chunk, at(ceiling), thickness, BlockDirection::Up);
+                let height = floor.map_or(height, |floor| height.min(ceiling - floor));
+                self.dripstone_height(random, x, z, density, height)
+            }
+            _ => 0,
+        };
+        let grows = random.next_f64() < chance;
+        let stalagmite = match floor {
+            Some(floor) if grows && !is_lava(chunk, at(floor)) => {
+                let thickness = self.dripstone_block_layer_thickness.get(random);
+                replace_with_dripstone_blocks(chunk, at(floor), thickness, BlockDirection::Down);
+                if ceiling.is_some() {
+                    let diff = self.max_stalagmite_stalactite_height_diff;
+                    0.max(stalactite + random.next_inbetween_i32(-diff, diff))
+                } else {
+                    self.dripstone_height(random, x, z, density, height)
+                }
+            }
+            _ => 0,
+        };
+
+        // Where the two would overlap, they meet somewhere in between instead
+        let (stalactite, stalagmite) = match (ceiling, floor) {
+            (Some(ceiling), Some(floor)) if ceiling - stalactite <= floor + stalagmite => {
+                let lowest = (ceiling - stalactite).max(floor + 1);
+                let highest = (floor + stalagmite).min(ceiling - 1);
+                let meet = random.next_inbetween_i32(lowest, highest + 1);
+                (ceiling - meet, meet - 1 - floor)
+            }
+            _ => (stalactite, stalagmite),
+        };
+        let merge_tip = random.next_bool()
+            && stalactite > 0
+            && stalagmite > 0
+            && column.height() == Some(stalactite + stalagmite);
+        if let Some(ceiling) = ceiling {
+            grow_pointed_dripstone(
+                chunk,
+                at(ceiling - 1),
+                BlockDirection::Down,
+                stalactite,
+                merge_tip,
+            );
+        }
+        if let Some(floor) = floor {
+            grow_pointed_dripstone(
+                chunk,
+                at(floor + 1),
+                BlockDirection::Up,
+                stalagmite,
+                merge_tip,
+            );
+        }
+    }
+
+    /// How long a dripstone `x`, `z` away from the middle grows, if it grows at all.
+    fn dripstone_height(
+        &self,
+        random: &mut RandomGenerator,
+        x: i32,
+        z: i32,
+        density: f32,
+        height: i32,
+    ) -> i32 {
+        if random.next_f32() > density {
+            return 0;
+        }
+        let distance = x.abs() + z.abs();
+        let mean = clamped_map(
+            distance as f64,
+            0.0,
+            self.max_distance_from_center_affecting_height_bias as f64,
+            height as f64 / 2.0,
+            0.0,
+        ) as f32;
+        let value = mean + random.next_gaussian() as f32 * self.height_deviation as f32;
+        value.clamp(0.0, height as f32) as i32
+    }
+
+    /// The chance of a column `x`, `z` away from the middle, lower towards the edge.
+    fn chance_of_column(&self, radius_x: i32, radius_z: i32, x: i32, z: i32) -> f64 {
+        let distance = (radius_x - x.abs()).min(radius_z - z.abs());
+        clamped_map(
+            distance as f64,
+            0.0,
+            self.max_distance_from_edge_affecting_chance_of_dripstone_column as f64,
+            self.chance_of_dripstone_column_at_max_distance_from_center as f64,
+            1.0,
+        ) as f32 as f64
+    }
+}
+
+fn is_lava(chunk: &ProtoChunkRegion, pos: BlockPos) -> bool {
+    chunk.get_block_state(&pos.0).to_block() == &Block::LAVA
+}
+
+/// Whether the floor block at `pos` can hold a puddle without it running off.
+fn can_place_pool(chunk: &ProtoChunkRegion, pos: BlockPos) -> bool {
+    let block = chunk.get_block_state(&pos.0).to_block();
+    if block == &Block::WATER
+        || block == &Block::DRIPSTONE_BLOCK
+        || block == &Block::POINTED_DRIPSTONE
+    {
+        return false;
+    }
+    if is_water(state_at(chunk, pos.up())) {
+        return false;
+    }
+    BlockDirection::horizontal()
+        .into_iter()
+        .all(|direction| can_be_next_to_water(chunk, pos.offset(direction.to_offset())))
+        && can_be_next_to_water(chunk, pos.down())
+}
+
+fn can_be_next_to_water(chunk: &ProtoChunkRegion, pos: BlockPos) -> bool {
+    let state = state_at(chunk, pos);
+    Block::from_state_id(state.id)
+        .is_tagged_with_by_tag(&tag::Block::MINECRAFT_BASE_STONE_OVERWORLD)
+        || is_water(state)
+}
+
+/// Water or a block holding water, like a waterlogged dripstone.
+fn is_water(state: &BlockState) -> bool {
+    let block = Block::from_state_id(state.id);
+    block == &Block::WATER || block.is_waterlogged(state.id)
+}
+
+/// Turns up to `thickness` blocks from `pos` towards `direction` into dripstone blocks, stopping
+/// at the first block dripstone can't replace.
+fn replace_with_dripstone_blocks(
+    chunk: &mut ProtoChunkRegion,
+    pos: BlockPos,
+    thickness: i32,
+    direction: BlockDirection,
+) {
+    let mut pos = pos;
+    for _ in 0..thickness {
+        if !super::gen_dripstone(chunk, pos) {
+            return;
+        }
+        pos = pos.offset(direction.to_offset());
+    }
 }
@@ -1,6 +1,265 @@
+use pumpkin_data::{Block, tag, tag::Taggable};
+use pumpkin_util::{
+    math::{
+        cos, float_provider::FloatProvider, int_provider::IntProvider, position::BlockPos, sin,
+        vector2::Vector2, vector3::Vector3,
+    },
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
+
+use super::{Column, can_replace, is_empty_or_water, is_empty_or_water_or_lava, state_at};
+
+/// A thick stalactite and stalagmite of dripstone blocks facing each other across a cave, both
+/// sometimes bent to the same side, as if by the wind.
 #[derive(Deserialize)]
 pub struct LargeDripstoneFeature {
-    // TODO
+    floor_to_ceiling_search_range: i32,
+    column_radius: IntProvider,
+    height_scale: FloatProvider,
+    max_column_radius_to_cave_height_ratio: f32,
+    stalactite_bluntness: FloatProvider,
+    stalagmite_bluntness: FloatProvider,
+    wind_speed: FloatProvider,
+    min_radius_for_wind: i32,
+    min_bluntness_for_wind: f32,
+}
+
+impl LargeDripstoneFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        if !is_empty_or_water(state_at(chunk, pos)) {
+            return false;
+        }
+        let Some(column) = Column::scan(
+            chunk,
+            pos,
+            self.floor_to_ceiling_search_range,
+            is_empty_or_water,
+            |state| {
+                let block = Block::from_state_id(state.id);
+                can_replace(block) || block == &Block::LAVA
+            },
+        ) else {
+            return false;
+        };
+        let (Some(floor), Some(ceiling), Some(height)) =
+            (column.floor, column.ceiling, column.height())
+        else {
+            return false;
+        };
+        if height < 4 {
+            return false;
+        }
+
+        let max_radius = ((height as f32 * self.max_column_radius_to_cave_height_ratio) as i32)
+            .clamp(self.column_radius.get_min(), self.column_radius.get_max());
+        let radius = random.next_inbetween_i32(self.column_radius.get_min(), max_radius);
+        let at = |y| BlockPos(Vector3::new(pos.0.x, y, pos.0.z));
+        let mut stalactite = LargeDripstone {
+            root: at(ceiling - 1),
+            pointing_up: false,
+            radius,
+            bluntness: self.stalactite_bluntness.get(random) as f64,
+            scale: self.height_scale.get(random) as f64,
+        };
+        let mut stalagmite = LargeDripstone {
+            root: at(floor + 1),
+            pointing_up: true,
+            radius,
+            bluntness: self.stalagmite_bluntness.get(random) as f64,
+            scale: self.height_scale.get(random) as f64,
+        };
+        let wind =
+            if self.is_suitable_for_wind(&stalactite) && self.is_suitable_for_wind(&stalagmite) {
+                Wind::new(pos.0.y, random, &self.wind_speed)
+            } else {
+                Wind::none()
+            };
+
+        if stalactite.move_into_stone(chunk, &wind) {
+            stalactite.place(chunk, random, &wind);
+        }
+        if stalagmite.move_into_stone(chunk, &wind) {
+            stalagmite.place(chunk, random, &wind);
+        }
+        true
+    }
+
+    fn is_suitable_for_wind(&self, dripstone: &LargeDripstone) -> bool {
+        dripstone.radius >= self.min_radius_for_wind
+            && dripstone.bluntness >= self.min_bluntness_for_wind as f64
+    }
+}
+
+struct LargeDripstone {
+    /// The middle of the thick end.
+    root: BlockPos,
+    pointing_up: bool,
+    radius: i32,
+    bluntness: f64,
+    scale: f64,
+}
+
+impl LargeDripstone {
+    fn height(&self) -> i32 {
+        self.height_at(0.0)
+    }
+
+    /// How long the dripstone is `distance` away from its middle.
+    fn height_at(&self, distance: f32) -> i32 {
+        dripstone_height(
+            distance as f64,
+            self.radius as f64,
+            self.scale,
+            self.bluntness,
+        ) as i32
+    }
+
+    /// Moves the root back into the rock it grows out of, so that it doesn't float in the air,
+    /// narrowing the dripstone until it fits. `false` if it doesn't fit at all.
+    fn move_into_stone(&mut self, chunk: &ProtoChunkRegion, wind: &Wind) -> bool {
+        while self.radius > 1 {
+            let mut pos = self.root;
+            for _ in 0..self.height().min(10) {
+                if chunk.get_block_state(&pos.0).to_block() == &Block::LAVA {
+                    return false;
+                }
+                if is_circle_mostly_embedded_in_stone(chunk, wind.offset(pos), self.radius) {
+                    self.root = pos;
+                    return true;
+                }
+                pos = if self.pointing_up {
+                    pos.down()
+                } else {
+                    pos.up()
+                };
+            }
+            self.radius /= 2;
+        }
+        false
+    }
+
+    fn place(&self, chunk: &mut ProtoChunkRegion, random: &mut RandomGenerator, wind: &Wind) {
+        for x in -self.radius..=self.radius {
+            for z in -self.radius..=self.radius {
+                let distance = ((x * x + z * z) as f32).sqrt();
+                if distance > self.radius as f32 {
+                    continue;
+                }
+                let mut height = self.height_at(distance);
+                if height <= 0 {
+                    continue;
+                }
+                if random.next_f32() < 0.2 {
+                    height = (height as f32 * (random.next_f32() * 0.2 + 0.8)) as i32;
+                }
+                let mut pos = self.root.add(x, 0, z);
+                // Stalagmites don't grow out of the surface
+                let top = if self.pointing_up {
+                    chunk.top_block_height_exclusive(&Vector2::new(pos.0.x, pos.0.z))
+                } else {
+                    i32::MAX
+                };
+                let mut placed = false;
+                for _ in 0..height {
+                    if pos.0.y >= top {
+                        break;
+                    }
+                    let blown = wind.offset(pos);
+                    if is_empty_or_water_or_lava(state_at(chunk, blown)) {
+                        placed = true;
+                        chunk.set_block_state(&blown.0, Block::DRIPSTONE_BLOCK.default_state);
+                    } else if placed
+                        && chunk
+                            .get_block_state(&blown.0)
+                            .to_block()
+                            .is_tagged_with_by_tag(&tag::Block::MINECRAFT_BASE_STONE_OVERWORLD)
+                    {
+                        break;
+                    }
+                    pos = if self.pointing_up {
+                        pos.up()
+                    } else {
+                        pos.down()
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Shifts the blocks of a dripstone sideways, more the further they are from where it started.
+struct Wind {
+    origin_y: i32,
+    speed: Option<(f64, f64)>,
+}
+
+impl Wind {
+    fn new(origin_y: i32, random: &mut RandomGenerator, speed: &FloatProvider) -> Self {
+        let magnitude = speed.get(random);
+        let angle = random.next_f32() * std::f32::consts::PI;
+        Self {
+            origin_y,
+            speed: Some((
+                (cos(angle as f64) * magnitude) as f64,
+                (sin(angle as f64) * magnitude) as f64,
+            )),
+        }
+    }
+
+    fn none() -> Self {
+        Self {
+            origin_y: 0,
+            speed: None,
+        }
+    }
+
+    fn offset(&self, pos: BlockPos) -> BlockPos {
+        let Some((x, z)) = self.speed else {
+            return pos;
+        };
+        let distance = (self.origin_y - pos.0.y) as f64;
+        pos.add(
+            (x * distance).floor() as i32,
+            0,
+            (z * distance).floor() as i32,
+        )
+    }
+}
+
+/// The length of a dripstone `distance` away from its middle, shaped like the curve vanilla uses.
+fn dripstone_height(distance: f64, radius: f64, scale: f64, bluntness: f64) -> f64 {
+    let distance = distance.max(bluntness);
+    let scaled = distance / radius * 0.384;
+    let shape = 0.75 * scaled.powf(4.0 / 3.0) - scaled.powf(2.0 / 3.0) - scaled.ln() / 3.0;
+    (scale * shape).max(0.0) / 0.384 * radius
+}
+
+/// Whether the ring of `radius` around `pos` and `pos` itself lie in rock rather than in a cave.
+fn is_circle_mostly_embedded_in_stone(
+    chunk: &ProtoChunkRegion,
+    pos: BlockPos,
+    radius: i32,
+) -> bool {
+    if is_empty_or_water_or_lava(state_at(chunk, pos)) {
+        return false;
+    }
+    let step = 6.0 / radius as f32;
+    let mut angle = 0.0f32;
+    while angle < std::f32::consts::TAU {
+        let x = (cos(angle as f64) * radius as f32) as i32;
+        let z = (sin(angle as f64) * radius as f32) as i32;
+        if is_empty_or_water_or_lava(state_at(chunk, pos.add(x, 0, z))) {
+            return false;
+        }
+        angle += step;
+    }
+    true
 }
@@ -1,8 +1,14 @@
 use pumpkin_data::tag;
-use pumpkin_data::{Block, tag::Taggable};
+use pumpkin_data::{
+    Block, BlockDirection, BlockState,
+    block_properties::{
+        BlockProperties, PointedDripstoneLikeProperties, Thickness, VerticalDirection,
+    },
+    tag::Taggable,
+};
 use pumpkin_util::math::position::BlockPos;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 pub mod cluster;
 pub mod large;
@@ -13,7 +19,7 @@ pub(super) fn can_replace(block: &Block) -> bool {
         || block.is_tagged_with_by_tag(&tag::Block::MINECRAFT_DRIPSTONE_REPLACEABLE_BLOCKS)
 }
 
-pub(super) fn gen_dripstone(chunk: &mut ProtoChunk, pos: BlockPos) -> bool {
+pub(super) fn gen_dripstone(chunk: &mut ProtoChunkRegion, pos: BlockPos) -> bool {
     let block = chunk.get_block_state(&pos.0).to_block();
     if block.is_tagged_with_by_tag(&tag::Block::MINECRAFT_DRIPSTONE_REPLACEABLE_BLOCKS) {
         chunk.set_block_state(&pos.0, Block::DRIPSTONE_BLOCK.default_state);
@@ -21,3 +27,168 @@ pub(super) fn gen_dripstone(chunk: &mut ProtoChunk, pos: BlockPos) -> bool {
     }
     false
 }
+
+fn is_empty_or_water(state: &BlockState) -> bool {
+    state.is_air() || Block::from_state_id(state.id) == &Block::WATER
+}
+
+fn is_empty_or_water_or_lava(state: &BlockState) -> bool {
+    is_empty_or_water(state) || Block::from_state_id(state.id) == &Block::LAVA
+}
+
+fn state_at(chunk: &ProtoChunkRegion, pos: BlockPos) -> &'static BlockState {
+    chunk.get_block_state(&pos.0).to_state()
+}
+
+/// Grows a pointed dripstone of `height` blocks from `pos` towards `direction`, if there is
+/// something to hang from or stand on behind it. A merged tip meets another dripstone head-on.
+fn grow_pointed_dripstone(
+    chunk: &mut ProtoChunkRegion,
+    pos: BlockPos,
+    direction: BlockDirection,
+    height: i32,
+    merge_tip: bool,
+) {
+    let base = pos.offset(direction.opposite().to_offset());
+    if !can_replace(chunk.get_block_state(&base.0).to_block()) {
+        return;
+    }
+    let vertical_direction = if direction == BlockDirection::Up {
+        VerticalDirection::Up
+    } else {
+        VerticalDirection::Down
+    };
+    let tip = if merge_tip {
+        Thickness::TipMerge
+    } else {
+        Thickness::Tip
+    };
+    // From the base to the tip, each block one step further towards `direction`
+    let thicknesses = (0..height).map(|i| match height - i {
+        1 => tip,
+        2 => Thickness::Frustum,
+        _ if i == 0 => Thickness::Base,
+        _ => Thickness::Middle,
+    });
+    let mut pos = pos;
+    for thickness in thicknesses {
+        let waterlogged = chunk.get_block_state(&pos.0).to_block() == &Block::WATER;
+        let props = PointedDripstoneLikeProperties {
+            thickness,
+            vertical_direction,
+            waterlogged,
+        };
+        let state = BlockState::from_id(props.to_state_id(&Block::POINTED_DRIPSTONE));
+        chunk.set_block_state(&pos.0, state);
+        pos = pos.offset(direction.to_offset());
+    }
+}
+
+/// The blocks bounding a vertical run of blocks, found by searching up and down from inside it.
+/// Either end is missing when the search gave up before finding the bounding block.
+struct Column {
+    floor: Option<i32>,
+    ceiling: Option<i32>,
+}
+
+impl Column {
+    /// Searches at most `max_distance` blocks up and down from `pos` within the blocks matching
+    /// `inside`, for a block matching `bound`. `None` if `pos` isn't inside a column at all.
+    fn scan(
+        chunk: &ProtoChunkRegion,
+        pos: BlockPos,
+        max_distance: i32,
+        inside: fn(&BlockState) -> bool,
+        bound: fn(&BlockState) -> bool,
+    ) -> Option<Self> {
+        if !inside(state_at(chunk, pos)) {
+            return None;
+        }
+        let search = |direction: BlockDirection| {
+            let mut pos = pos;
+            for _ in 0..max_distance {
+                if !inside(state_at(chunk, pos)) {
+                    break;
+                }
+                pos = pos.offset(direction.to_offset());
+            }
+            bound(state_at(chunk, pos)).then_some(pos.0.y)
+        };
+        let ceiling = search(BlockDirection::Up);
+        let floor = search(BlockDirection::Down);
+        Some(Self { floor, ceiling })
+    }
+
+    /// The number of blocks between the floor and the ceiling, if both were found.
+    fn height(&self) -> Option<i32> {
+        Some(self.ceiling? - self.floor? - 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::LazyLock};
+
+    use pumpkin_data::{Block, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            generator::{decorate, tests::BlockRegistry, with_neighbors},
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    fn count(chunk: &ProtoChunk, block: &Block) -> usize {
+        (-64..320)
+            .flat_map(|y| (0..16).flat_map(move |z| (0..16).map(move |x| Vector3::new(x, y, z))))
+            .filter(|pos| chunk.get_block_state(pos).to_block() == block)
+            .count()
+    }
+
+    #[test]
+    fn dripstone_caves_grow_pointed_dripstone() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        // Dripstone caves for this seed, cut open by the cave noise
+        let pos = Vector2::new(-64, 87);
+        let mut chunks: HashMap<_, _> = with_neighbors(pos)
+            .into_iter()
+            .map(|pos| {
+                let mut chunk = ProtoChunk::new(
+                    pos,
+                    &BASE_NOISE_ROUTER,
+                    &RANDOM_CONFIG,
+                    settings,
+                    &TERRAIN_CACHE,
+                    settings.default_block.get_state(),
+                );
+                chunk.populate_biomes(&Dimension::Overworld);
+                chunk.populate_noise();
+                chunk.build_surface();
+                chunk.carve(&Dimension::Overworld, 1);
+                (pos, chunk)
+            })
+            .collect();
+        assert_eq!(count(&chunks[&pos], &Block::POINTED_DRIPSTONE), 0);
+
+        decorate(&mut chunks, pos, &BlockRegistry);
+
+        let center = &chunks[&pos];
+        assert!(count(center, &Block::POINTED_DRIPSTONE) > 0);
+        assert!(count(center, &Block::DRIPSTONE_BLOCK) > 0);
+    }
+}
@@ -5,8 +5,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
+/// A single pointed dripstone of one or two blocks on a small patch of dripstone blocks.
 #[derive(Deserialize)]
 pub struct SmallDripstoneFeature {
     chance_of_taller_dripstone: f32,
@@ -18,21 +19,29 @@ pub struct SmallDripstoneFeature {
 impl SmallDripstoneFeature {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
         if let Some(dir) = Self::get_direction(chunk, pos, random) {
-            let pos = pos.offset(dir.opposite().to_offset());
-            self.gen_dripstone_blocks(chunk, pos, random);
-            // TODO
+            let base = pos.offset(dir.opposite().to_offset());
+            self.gen_dripstone_blocks(chunk, base, random);
+            let tip = pos.offset(dir.to_offset());
+            let height = if random.next_f32() < self.chance_of_taller_dripstone
+                && super::is_empty_or_water(super::state_at(chunk, tip))
+            {
+                2
+            } else {
+                1
+            };
+            super::grow_pointed_dripstone(chunk, pos, dir, height, false);
             return true;
         }
         false
     }
 
     fn get_direction(
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         pos: BlockPos,
         random: &mut RandomGenerator,
     ) -> Option<BlockDirection> {
@@ -56,7 +65,7 @@ impl SmallDripstoneFeature {
 
     fn gen_dripstone_blocks(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         pos: BlockPos,
         random: &mut RandomGenerator,
     ) {
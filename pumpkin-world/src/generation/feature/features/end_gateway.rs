@@ -1,6 +1,83 @@
+use pumpkin_data::Block;
+use pumpkin_util::{
+    math::position::BlockPos,
+    random::{RandomImpl, legacy_rand::LegacyRand},
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
+
+/// A gateway block in a frame of bedrock, like the ones leading back from the outer islands.
+///
+/// The exit of a gateway is kept by its block entity, which generation doesn't place yet, so the
+/// exit in the config isn't used; gateways without one look for an exit when they are entered.
 #[derive(Deserialize)]
-pub struct EndGatewayFeature {
-    // TODO
+pub struct EndGatewayFeature {}
+
+impl EndGatewayFeature {
+    pub fn generate(&self, chunk: &mut ProtoChunkRegion, pos: BlockPos) -> bool {
+        for block_pos in BlockPos::iterate(pos.add(-1, -2, -1), pos.add(1, 2, 1)) {
+            let same_x = block_pos.0.x == pos.0.x;
+            let same_y = block_pos.0.y == pos.0.y;
+            let same_z = block_pos.0.z == pos.0.z;
+            let cap = (block_pos.0.y - pos.0.y).abs() == 2;
+            let state = if same_x && same_y && same_z {
+                Block::END_GATEWAY.default_state
+            } else if same_y {
+                Block::AIR.default_state
+            } else if (same_x || same_z) && (!cap || same_x && same_z) {
+                Block::BEDROCK.default_state
+            } else {
+                Block::AIR.default_state
+            };
+            chunk.set_block_state(&block_pos.0, state);
+        }
+        true
+    }
+}
+
+/// The twenty gateways around the main island of a world with `seed`, in the order they open, one
+/// for each time the dragon is killed. Like the pillars, they only depend on the world seed.
+pub fn fixed_gateway_positions(seed: u64) -> Vec<BlockPos> {
+    let mut gateways: Vec<i32> = (0..20).collect();
+    let mut random = LegacyRand::from_seed(seed);
+    for i in (2..=gateways.len()).rev() {
+        let j = random.next_bounded_i32(i as i32) as usize;
+        gateways.swap(i - 1, j);
+    }
+
+    // The next gateway is taken from the end of the list
+    gateways
+        .into_iter()
+        .rev()
+        .map(|i| {
+            let angle = 2.0 * (-std::f64::consts::PI + std::f64::consts::PI / 20.0 * i as f64);
+            BlockPos::new(
+                (96.0 * angle.cos()).floor() as i32,
+                75,
+                (96.0 * angle.sin()).floor() as i32,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::fixed_gateway_positions;
+
+    #[test]
+    fn twenty_gateways_around_the_island() {
+        let gateways = fixed_gateway_positions(0);
+        assert_eq!(gateways.len(), 20);
+        let distinct: HashSet<_> = gateways.iter().map(|pos| (pos.0.x, pos.0.z)).collect();
+        assert_eq!(distinct.len(), 20);
+        for pos in &gateways {
+            assert_eq!(pos.0.y, 75);
+            let distance = (pos.0.x as f64).hypot(pos.0.z as f64);
+            assert!((94.0..=97.0).contains(&distance), "{distance}");
+        }
+        assert_ne!(gateways, fixed_gateway_positions(1));
+    }
 }
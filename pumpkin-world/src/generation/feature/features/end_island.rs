@@ -1,6 +1,37 @@
+use pumpkin_data::Block;
+use pumpkin_util::{
+    math::position::BlockPos,
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
+
+/// A small floating island of end stone, a rough cone narrowing downwards.
 #[derive(Deserialize)]
-pub struct EndIslandFeature {
-    // TODO
+pub struct EndIslandFeature;
+
+impl EndIslandFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        let mut radius = random.next_bounded_i32(3) as f32 + 4.0;
+        let mut y = 0;
+        while radius > 0.5 {
+            let reach = (radius + 1.0) * (radius + 1.0);
+            for x in (-radius).floor() as i32..=radius.ceil() as i32 {
+                for z in (-radius).floor() as i32..=radius.ceil() as i32 {
+                    if (x * x + z * z) as f32 <= reach {
+                        chunk.set_block_state(&pos.add(x, y, z).0, Block::END_STONE.default_state);
+                    }
+                }
+            }
+            radius -= random.next_bounded_i32(2) as f32 + 0.5;
+            y -= 1;
+        }
+        true
+    }
 }
@@ -2,8 +2,9 @@ use pumpkin_data::Block;
 use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, world::BlockRegistryExt};
+use crate::{generation::proto_chunk_region::ProtoChunkRegion, world::BlockRegistryExt};
 
+/// The obsidian platform players arrive on in the end, with room to stand above it.
 #[derive(Deserialize)]
 pub struct EndPlatformFeature;
 
@@ -11,7 +12,7 @@ impl EndPlatformFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         _block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
@@ -19,10 +20,11 @@ impl EndPlatformFeature {
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
-        for _ in -2..2 {
-            for _ in -2..2 {
-                for t in -1..3 {
-                    let state = if t == -1 {
+        for x in -2..=2 {
+            for z in -2..=2 {
+                for y in -1..3 {
+                    let pos = pos.add(x, y, z);
+                    let state = if y == -1 {
                         Block::OBSIDIAN.default_state
                     } else {
                         Block::AIR.default_state
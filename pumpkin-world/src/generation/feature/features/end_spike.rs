@@ -1,13 +1,17 @@
-use pumpkin_data::Block;
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{BlockProperties, OakFenceLikeProperties},
+};
 use pumpkin_util::{
-    math::position::BlockPos,
-    random::{RandomGenerator, RandomImpl},
+    math::{position::BlockPos, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl, legacy_rand::LegacyRand},
 };
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk,
-    generation::{height_limit::HeightLimitView, section_coords},
+    generation::{
+        height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion, section_coords,
+    },
     world::BlockRegistryExt,
 };
 
@@ -39,35 +43,19 @@ impl EndSpikeFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         _block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
-        random: &mut RandomGenerator,
+        _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
-        let mut spikes = self.spikes.clone();
-        if spikes.is_empty() {
-            for i in 0..10 {
-                let angle = 2.0 * (-std::f64::consts::PI + 0.3141592653589793 * i as f64);
-                let center_x = (42.0 * angle.cos()).floor() as i32;
-                let center_z = (42.0 * angle.sin()).floor() as i32;
-
-                let l = random.next_bounded_i32(10); // TODO
-                let radius = 2 + l / 3;
-                let height = 76 + l * 3;
-                let guarded = l == 1 || l == 2;
-
-                spikes.push(Spike {
-                    center_x,
-                    center_z,
-                    radius,
-                    height,
-                    guarded,
-                });
-            }
-        }
+        let spikes = if self.spikes.is_empty() {
+            Self::spikes_for_seed(chunk.center().random_config.seed)
+        } else {
+            self.spikes.clone()
+        };
         for spike in spikes {
             if !spike.is_in_chunk(&pos) {
                 continue;
@@ -78,7 +66,49 @@ impl EndSpikeFeature {
         true
     }
 
-    fn gen_spike(spike: &Spike, chunk: &mut ProtoChunk<'_>) {
+    /// Where the end crystals of a world with `seed` sit, one on the bedrock cap of each pillar.
+    /// They are entities, so generation leaves them to be spawned once the chunks are loaded.
+    pub fn crystal_positions(seed: u64) -> Vec<Vector3<f64>> {
+        Self::spikes_for_seed(seed)
+            .iter()
+            .map(|spike| {
+                Vector3::new(
+                    spike.center_x as f64 + 0.5,
+                    (spike.height + 1) as f64,
+                    spike.center_z as f64 + 0.5,
+                )
+            })
+            .collect()
+    }
+
+    /// The ten pillars in a ring around the main island. Every chunk of a world has to agree on
+    /// them, so they only depend on the world seed, like in vanilla.
+    fn spikes_for_seed(seed: u64) -> Vec<Spike> {
+        let key = LegacyRand::from_seed(seed).next_i64() & 0xFFFF;
+        let mut random = LegacyRand::from_seed(key as u64);
+        let mut sizes: Vec<i32> = (0..10).collect();
+        for i in (2..=sizes.len()).rev() {
+            let j = random.next_bounded_i32(i as i32) as usize;
+            sizes.swap(i - 1, j);
+        }
+
+        sizes
+            .into_iter()
+            .enumerate()
+            .map(|(i, size)| {
+                let angle = 2.0 * (-std::f64::consts::PI + std::f64::consts::PI / 10.0 * i as f64);
+                Spike {
+                    center_x: (42.0 * angle.cos()).floor() as i32,
+                    center_z: (42.0 * angle.sin()).floor() as i32,
+                    radius: 2 + size / 3,
+                    height: 76 + size * 3,
+                    guarded: size == 1 || size == 2,
+                }
+            })
+            .collect()
+    }
+
+    fn gen_spike(spike: &Spike, chunk: &mut ProtoChunkRegion<'_, '_>) {
         let radius = spike.radius;
         for pos in BlockPos::iterate(
             BlockPos::new(
@@ -88,7 +118,7 @@ impl EndSpikeFeature {
             ),
             BlockPos::new(
                 spike.center_x + radius,
-                chunk.height() as i32 + 10,
+                spike.height + 10,
                 spike.center_z + radius,
             ),
         ) {
@@ -106,6 +136,119 @@ impl EndSpikeFeature {
             }
             chunk.set_block_state(&pos.0, Block::AIR.default_state);
         }
-        // TODO
+
+        if spike.guarded {
+            Self::gen_cage(spike, chunk);
+        }
+        // The end crystal is an entity and isn't spawned here, only the bedrock and fire below it
+        let top = BlockPos::new(spike.center_x, spike.height, spike.center_z);
+        chunk.set_block_state(&top.0, Block::BEDROCK.default_state);
+        chunk.set_block_state(&top.up().0, Block::FIRE.default_state);
+    }
+
+    /// The iron bars around the crystal on top of the guarded spikes.
+    fn gen_cage(spike: &Spike, chunk: &mut ProtoChunkRegion<'_, '_>) {
+        for x in -2..=2 {
+            for z in -2..=2 {
+                for y in 0..=3 {
+                    let edge_x = x == -2 || x == 2;
+                    let edge_z = z == -2 || z == 2;
+                    let roof = y == 3;
+                    if !edge_x && !edge_z && !roof {
+                        continue;
+                    }
+                    let along_x = edge_x || roof;
+                    let along_z = edge_z || roof;
+                    let props = OakFenceLikeProperties {
+                        north: along_x && z != -2,
+                        south: along_x && z != 2,
+                        west: along_z && x != -2,
+                        east: along_z && x != 2,
+                        waterlogged: false,
+                    };
+                    let state = BlockState::from_id(props.to_state_id(&Block::IRON_BARS));
+                    let pos =
+                        BlockPos::new(spike.center_x + x, spike.height + y, spike.center_z + z);
+                    chunk.set_block_state(&pos.0, state);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_data::Block;
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::EndSpikeFeature;
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            Seed,
+            generator::{GeneratorInit, VanillaGenerator, tests::BlockRegistry},
+        },
+    };
+
+    #[test]
+    fn ten_pillars_of_every_size() {
+        let spikes = EndSpikeFeature::spikes_for_seed(0);
+        let mut heights: Vec<_> = spikes.iter().map(|spike| spike.height).collect();
+        heights.sort();
+        assert_eq!(
+            heights,
+            (0..10).map(|size| 76 + size * 3).collect::<Vec<_>>()
+        );
+        for spike in &spikes {
+            let size = (spike.height - 76) / 3;
+            assert_eq!(spike.radius, 2 + size / 3);
+            // Only the second and third smallest are caged
+            assert_eq!(spike.guarded, size == 1 || size == 2);
+            let distance = (spike.center_x as f64).hypot(spike.center_z as f64);
+            assert!((40.0..=43.0).contains(&distance), "{distance}");
+        }
+
+        // The order around the island depends on the seed
+        let heights_for = |seed| {
+            EndSpikeFeature::spikes_for_seed(seed)
+                .iter()
+                .map(|spike| spike.height)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(heights_for(0), heights_for(0));
+        assert_ne!(heights_for(0), heights_for(1));
+
+        let crystals = EndSpikeFeature::crystal_positions(0);
+        for (crystal, spike) in crystals.iter().zip(&spikes) {
+            assert_eq!(crystal.y, (spike.height + 1) as f64);
+            assert_eq!(crystal.x.floor() as i32, spike.center_x);
+        }
+    }
+
+    #[test]
+    fn pillars_are_capped() {
+        let generator =
+            VanillaGenerator::new(Seed(0), Dimension::End, Arc::new(BlockRegistry)).unwrap();
+        let spikes = EndSpikeFeature::spikes_for_seed(0);
+        let spike = spikes.iter().find(|spike| spike.guarded).unwrap();
+        let chunk =
+            generator.generate_chunk(Vector2::new(spike.center_x >> 4, spike.center_z >> 4));
+        let (x, z) = (spike.center_x & 15, spike.center_z & 15);
+        let block_at = |dx: i32, y, dz: i32| {
+            let state = chunk
+                .section
+                .get_block_absolute_y((x + dx) as usize, y, (z + dz) as usize)
+                .unwrap();
+            Block::from_state_id(state)
+        };
+
+        for y in 70..spike.height {
+            assert_eq!(block_at(0, y, 0), &Block::OBSIDIAN, "{y}");
+        }
+        assert_eq!(block_at(0, spike.height, 0), &Block::BEDROCK);
+        assert_eq!(block_at(0, spike.height + 1, 0), &Block::FIRE);
+        assert_eq!(block_at(0, spike.height + 3, 0), &Block::IRON_BARS);
     }
 }
@@ -1,7 +1,9 @@
 use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::block_state_provider::BlockStateProvider};
+use crate::generation::{
+    block_state_provider::BlockStateProvider, proto_chunk_region::ProtoChunkRegion,
+};
 
 #[derive(Deserialize)]
 pub struct FallenTreeFeature {
@@ -11,7 +13,7 @@ pub struct FallenTreeFeature {
 impl FallenTreeFeature {
     pub fn generate(
         &self,
-        _chunk: &mut ProtoChunk,
+        _chunk: &mut ProtoChunkRegion,
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
@@ -21,7 +23,7 @@ impl FallenTreeFeature {
         false
     }
 
-    fn gen_stump(&self, chunk: &mut ProtoChunk, random: &mut RandomGenerator, pos: BlockPos) {
+    fn gen_stump(&self, chunk: &mut ProtoChunkRegion, random: &mut RandomGenerator, pos: BlockPos) {
         chunk.set_block_state(&pos.0, self.trunk_provider.get(random, pos));
     }
 }
@@ -1,6 +1,75 @@
+use pumpkin_data::{
+    Block, BlockDirection, BlockState,
+    block_properties::{BlockProperties, GrassBlockLikeProperties},
+};
+use pumpkin_util::{
+    HeightMap,
+    math::{position::BlockPos, vector2::Vector2, vector3::Vector3},
+};
 use serde::Deserialize;
 
+use crate::generation::{height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion};
+
+/// Biomes colder than this get snow instead of rain.
+const FREEZING_TEMPERATURE: f32 = 0.15;
+
+/// Covers the ground of the cold parts of the chunk with snow and freezes the water on top, going
+/// by the temperature of the biome at the top of every column, which drops with the height.
 #[derive(Deserialize)]
-pub struct FreezeTopLayerFeature {
-    // TODO
+pub struct FreezeTopLayerFeature {}
+
+impl FreezeTopLayerFeature {
+    pub fn generate(&self, chunk: &mut ProtoChunkRegion, pos: BlockPos) -> bool {
+        let sea_level = chunk.center().sea_level();
+        for x in pos.0.x..pos.0.x + 16 {
+            for z in pos.0.z..pos.0.z + 16 {
+                let y = chunk.get_top_y(&HeightMap::MotionBlocking, &Vector2::new(x, z));
+                let top = Vector3::new(x, y, z);
+                let below = Vector3::new(x, y - 1, z);
+                let biome = chunk.get_biome_for_terrain_gen(&top);
+                let temperature = biome.weather.compute_temperature(&below, sea_level);
+                if temperature < FREEZING_TEMPERATURE && Self::is_still_water(chunk, &below) {
+                    chunk.set_block_state(&below, Block::ICE.default_state);
+                }
+                let temperature = biome.weather.compute_temperature(&top, sea_level);
+                if temperature < FREEZING_TEMPERATURE && Self::can_snow(chunk, &top) {
+                    chunk.set_block_state(&top, Block::SNOW.default_state);
+                    // Grass, podzol and mycelium turn white under the snow
+                    let ground = chunk.get_block_state(&below).to_block();
+                    if GrassBlockLikeProperties::handles_block_id(ground.id) {
+                        let mut props = GrassBlockLikeProperties::default(ground);
+                        props.snowy = true;
+                        chunk.set_block_state(
+                            &below,
+                            BlockState::from_id(props.to_state_id(ground)),
+                        );
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    fn is_still_water(chunk: &ProtoChunkRegion, pos: &Vector3<i32>) -> bool {
+        chunk.get_block_state(pos).to_state().id == Block::WATER.default_state.id
+    }
+
+    /// Whether a layer of snow would stay at `pos`, like on top of any full block but ice.
+    fn can_snow(chunk: &ProtoChunkRegion, pos: &Vector3<i32>) -> bool {
+        if pos.y < chunk.bottom_y() as i32 || pos.y >= chunk.top_y() as i32 {
+            return false;
+        }
+        let state = chunk.get_block_state(pos).to_state();
+        if !state.is_air() {
+            return false;
+        }
+        let below = chunk.get_block_state(&Vector3::new(pos.x, pos.y - 1, pos.z));
+        let block = below.to_block();
+        if block == &Block::ICE || block == &Block::PACKED_ICE || block == &Block::BARRIER {
+            return false;
+        }
+        block == &Block::HONEY_BLOCK
+            || block == &Block::SOUL_SAND
+            || below.to_state().is_side_solid(BlockDirection::Up)
+    }
 }
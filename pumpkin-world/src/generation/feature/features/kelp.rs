@@ -1,6 +1,80 @@
+use pumpkin_data::{
+    Block, BlockDirection, BlockState,
+    block_properties::{BlockProperties, EnumVariants, Integer0To25, KelpLikeProperties},
+};
+use pumpkin_util::{
+    math::{position::BlockPos, vector2::Vector2},
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
+
 #[derive(Deserialize)]
-pub struct KelpFeature {
-    // TODO
+pub struct KelpFeature {}
+
+impl KelpFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        _min_y: i8,
+        _height: u16,
+        _feature: &str, // This placed feature
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        let y = chunk.ocean_floor_height_exclusive(&Vector2::new(pos.0.x, pos.0.z));
+        let mut pos = BlockPos::new(pos.0.x, y, pos.0.z);
+        if !Self::is_water(chunk, &pos) {
+            return false;
+        }
+
+        let mut placed = false;
+        let height = 1 + random.next_bounded_i32(10);
+        for i in 0..=height {
+            if Self::is_water(chunk, &pos)
+                && Self::is_water(chunk, &pos.up())
+                && Self::can_survive(chunk, &pos)
+            {
+                if i == height {
+                    chunk.set_block_state(&pos.0, Self::top(random));
+                    placed = true;
+                } else {
+                    chunk.set_block_state(&pos.0, Block::KELP_PLANT.default_state);
+                }
+            } else if i > 0 {
+                // The column reached the surface, so the plant below becomes its top
+                let below = pos.down();
+                if Self::can_survive(chunk, &below)
+                    && chunk.get_block_state(&below.down().0).to_block() != &Block::KELP
+                {
+                    chunk.set_block_state(&below.0, Self::top(random));
+                    placed = true;
+                }
+                break;
+            }
+            pos = pos.up();
+        }
+        placed
+    }
+
+    fn is_water(chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
+        chunk.get_block_state(&pos.0).to_block() == &Block::WATER
+    }
+
+    /// Kelp grows on top of more kelp or any sturdy block but magma.
+    fn can_survive(chunk: &ProtoChunkRegion, pos: &BlockPos) -> bool {
+        let below = chunk.get_block_state(&pos.down().0);
+        let block = below.to_block();
+        block == &Block::KELP
+            || block == &Block::KELP_PLANT
+            || (block != &Block::MAGMA_BLOCK && below.to_state().is_side_solid(BlockDirection::Up))
+    }
+
+    /// The top of a kelp column, already old enough to not grow much further.
+    fn top(random: &mut RandomGenerator) -> &'static BlockState {
+        let mut props = KelpLikeProperties::default(&Block::KELP);
+        props.age = Integer0To25::from_index(random.next_bounded_i32(4) as u16 + 20);
+        BlockState::from_id(props.to_state_id(&Block::KELP))
+    }
 }
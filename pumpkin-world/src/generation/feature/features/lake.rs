@@ -1,6 +1,323 @@
+use pumpkin_data::{Block, BlockState, tag, tag::Taggable};
+use pumpkin_util::{
+    math::{position::BlockPos, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::{
+    block_state_provider::BlockStateProvider, height_limit::HeightLimitView,
+    proto_chunk_region::ProtoChunkRegion,
+};
+
+const WIDTH: i32 = 16;
+const HEIGHT: i32 = 8;
+/// The layer the fluid is filled up to, the rest of the hole is left as air.
+const SURFACE: i32 = 4;
+
+/// A pool of a few overlapping ellipsoids in a 16 x 8 x 16 box, filled with fluid to halfway up
+/// and walled in by the barrier where the rock around it would let the fluid out.
 #[derive(Deserialize)]
 pub struct LakeFeature {
-    // TODO
+    fluid: BlockStateProvider,
+    barrier: BlockStateProvider,
+}
+
+impl LakeFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        if pos.0.y <= chunk.bottom_y() as i32 + SURFACE {
+            return false;
+        }
+        // The box reaches east and south of the origin, into the neighbors
+        let origin = pos.down_height(SURFACE);
+        let at = |x, y, z| origin.0.add(&Vector3::new(x, y, z));
+
+        let mut hole = [false; (WIDTH * WIDTH * HEIGHT) as usize];
+        for _ in 0..random.next_bounded_i32(4) + 4 {
+            let size_x = random.next_f64() * 6.0 + 3.0;
+            let size_y = random.next_f64() * 4.0 + 2.0;
+            let size_z = random.next_f64() * 6.0 + 3.0;
+            let center_x = random.next_f64() * (16.0 - size_x - 2.0) + 1.0 + size_x / 2.0;
+            let center_y = random.next_f64() * (8.0 - size_y - 4.0) + 2.0 + size_y / 2.0;
+            let center_z = random.next_f64() * (16.0 - size_z - 2.0) + 1.0 + size_z / 2.0;
+            for x in 1..WIDTH - 1 {
+                for z in 1..WIDTH - 1 {
+                    for y in 1..HEIGHT - 1 {
+                        let dx = (x as f64 - center_x) / (size_x / 2.0);
+                        let dy = (y as f64 - center_y) / (size_y / 2.0);
+                        let dz = (z as f64 - center_z) / (size_z / 2.0);
+                        if dx * dx + dy * dy + dz * dz < 1.0 {
+                            hole[index(x, y, z)] = true;
+                        }
+                    }
+                }
+            }
+        }
+        let in_hole = |x, y, z| {
+            (0..WIDTH).contains(&x)
+                && (0..HEIGHT).contains(&y)
+                && (0..WIDTH).contains(&z)
+                && hole[index(x, y, z)]
+        };
+        // The blocks right next to the hole, which hold the fluid in
+        let is_rim = |x, y, z| {
+            !in_hole(x, y, z)
+                && (in_hole(x + 1, y, z)
+                    || in_hole(x - 1, y, z)
+                    || in_hole(x, y, z + 1)
+                    || in_hole(x, y, z - 1)
+                    || in_hole(x, y + 1, z)
+                    || in_hole(x, y - 1, z))
+        };
+
+        // No fluid flowing in above the surface, and none flowing out below it
+        let fluid = self.fluid.get(random, origin);
+        for x in 0..WIDTH {
+            for z in 0..WIDTH {
+                for y in 0..HEIGHT {
+                    if !is_rim(x, y, z) {
+                        continue;
+                    }
+                    let state = chunk.get_block_state(&at(x, y, z)).to_state();
+                    if y >= SURFACE && state.is_liquid() {
+                        return false;
+                    }
+                    if y < SURFACE && !state.is_solid() && state.id != fluid.id {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        for x in 0..WIDTH {
+            for z in 0..WIDTH {
+                for y in 0..HEIGHT {
+                    if in_hole(x, y, z) {
+                        let state = if y < SURFACE {
+                            fluid
+                        } else {
+                            Block::CAVE_AIR.default_state
+                        };
+                        set_if_replaceable(chunk, &at(x, y, z), state);
+                    }
+                }
+            }
+        }
+
+        let barrier = self.barrier.get(random, origin);
+        if !barrier.is_air() {
+            for x in 0..WIDTH {
+                for z in 0..WIDTH {
+                    for y in 0..HEIGHT {
+                        // Only some of the rim above the surface, where nothing can leak
+                        if !is_rim(x, y, z) || (y >= SURFACE && random.next_bounded_i32(2) == 0) {
+                            continue;
+                        }
+                        let pos = at(x, y, z);
+                        let block = chunk.get_block_state(&pos);
+                        if block.to_state().is_solid()
+                            && !block.to_block().is_tagged_with_by_tag(
+                                &tag::Block::MINECRAFT_LAVA_POOL_STONE_CANNOT_REPLACE,
+                            )
+                        {
+                            chunk.set_block_state(&pos, barrier);
+                        }
+                    }
+                }
+            }
+        }
+
+        if Block::from_state_id(fluid.id) == &Block::WATER {
+            let sea_level = chunk.center().sea_level();
+            for x in 0..WIDTH {
+                for z in 0..WIDTH {
+                    let pos = at(x, SURFACE - 1, z);
+                    if should_freeze(chunk, &pos, sea_level) {
+                        set_if_replaceable(chunk, &pos, Block::ICE.default_state);
+                    }
+                }
+            }
+        }
+        true
+    }
+}
+
+fn index(x: i32, y: i32, z: i32) -> usize {
+    ((x * WIDTH + z) * HEIGHT + y) as usize
+}
+
+/// Whether the water source at `pos` is cold enough to turn into ice.
+fn should_freeze(chunk: &ProtoChunkRegion, pos: &Vector3<i32>, sea_level: i32) -> bool {
+    let biome = chunk.get_biome_for_terrain_gen(pos);
+    biome.weather.compute_temperature(pos, sea_level) < 0.15
+        && chunk.get_block_state(pos).to_state().id == Block::WATER.default_state.id
+}
+
+/// Places `state` unless the block there is one features must not replace, like bedrock.
+fn set_if_replaceable(chunk: &mut ProtoChunkRegion, pos: &Vector3<i32>, state: &BlockState) {
+    if !chunk
+        .get_block_state(pos)
+        .to_block()
+        .is_tagged_with_by_tag(&tag::Block::MINECRAFT_FEATURES_CANNOT_REPLACE)
+    {
+        chunk.set_block_state(pos, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::LazyLock;
+
+    use pumpkin_data::{Block, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::{
+        math::{position::BlockPos, vector2::Vector2, vector3::Vector3},
+        random::{RandomGenerator, xoroshiro128::Xoroshiro},
+    };
+
+    use super::LakeFeature;
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            feature::configured_features::{CONFIGURED_FEATURES, ConfiguredFeature},
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            proto_chunk_region::ProtoChunkRegion,
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    /// The chunk at `pos` and the three east and south of it, made of stone from `bottom` up to
+    /// right below `top` and empty everywhere else.
+    fn stone_chunks(pos: Vector2<i32>, bottom: i32, top: i32) -> [ProtoChunk<'static>; 4] {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, z)| {
+            let chunk_pos = pos.add(&Vector2::new(x, z));
+            let mut chunk = ProtoChunk::new(
+                chunk_pos,
+                &BASE_NOISE_ROUTER,
+                &RANDOM_CONFIG,
+                settings,
+                &TERRAIN_CACHE,
+                settings.default_block.get_state(),
+            );
+            chunk.populate_biomes(&Dimension::Overworld);
+            for x in 0..16 {
+                for z in 0..16 {
+                    for y in bottom..top {
+                        chunk.set_block_state(&Vector3::new(x, y, z), Block::STONE.default_state);
+                    }
+                }
+            }
+            chunk
+        })
+    }
+
+    /// Places the lake at `origin` once the region around it is built, returning whether it did
+    /// and the positions of its fluid.
+    fn place(
+        feature: &LakeFeature,
+        chunks: &mut [ProtoChunk<'static>; 4],
+        origin: Vector3<i32>,
+    ) -> (bool, Vec<Vector3<i32>>) {
+        let [center, east, south, south_east] = chunks;
+        let mut region = ProtoChunkRegion::new(center)
+            .with_neighbor(east)
+            .with_neighbor(south)
+            .with_neighbor(south_east);
+        let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(SEED));
+        let placed = feature.generate(&mut region, &mut random, BlockPos(origin));
+
+        let fluid = feature.fluid.get(&mut random, BlockPos(origin));
+        let fluids = (origin.x - 8..origin.x + 24)
+            .flat_map(|x| (origin.z - 8..origin.z + 24).map(move |z| (x, z)))
+            .flat_map(|(x, z)| (origin.y - 8..origin.y + 8).map(move |y| Vector3::new(x, y, z)))
+            .filter(|pos| region.get_block_state(pos).0 == fluid.id)
+            .collect();
+        (placed, fluids)
+    }
+
+    /// Whether each fluid block sits in a basin, held in from below and from the sides.
+    fn holds_in(chunks: &mut [ProtoChunk<'static>; 4], fluids: &[Vector3<i32>]) -> bool {
+        let [center, east, south, south_east] = chunks;
+        let region = ProtoChunkRegion::new(center)
+            .with_neighbor(east)
+            .with_neighbor(south)
+            .with_neighbor(south_east);
+        fluids.iter().all(|pos| {
+            [(0, -1, 0), (1, 0, 0), (-1, 0, 0), (0, 0, 1), (0, 0, -1)]
+                .into_iter()
+                .map(|(x, y, z)| pos.add(&Vector3::new(x, y, z)))
+                .all(|next| {
+                    region.get_block_state(&next).to_state().is_solid() || fluids.contains(&next)
+                })
+        })
+    }
+
+    fn lava_lakes() -> &'static LakeFeature {
+        let Some(ConfiguredFeature::Lake(feature)) = CONFIGURED_FEATURES.get("lake_lava") else {
+            panic!("lake_lava is a lake");
+        };
+        feature
+    }
+
+    #[test]
+    fn lava_lakes_pool_underground() {
+        let mut chunks = stone_chunks(Vector2::new(0, 0), 0, 40);
+        let origin = Vector3::new(8, 20, 8);
+        let (placed, lava) = place(lava_lakes(), &mut chunks, origin);
+        assert!(placed);
+        assert!(!lava.is_empty());
+        // Filled up to the surface of the lake, with the air above it
+        assert!(lava.iter().all(|pos| pos.y < origin.y));
+        // Reaching into the neighbors, with the barrier holding it in
+        assert!(lava.iter().any(|pos| pos.x >= 16 || pos.z >= 16));
+        assert!(holds_in(&mut chunks, &lava));
+    }
+
+    #[test]
+    fn water_lakes_sink_into_the_ground() {
+        let lakes: LakeFeature = serde_json::from_str(
+            r#"{
+                "fluid": {"type": "minecraft:simple_state_provider", "state": {"Name": "minecraft:water", "Properties": {"level": "0"}}},
+                "barrier": {"type": "minecraft:simple_state_provider", "state": {"Name": "minecraft:air"}}
+            }"#,
+        )
+        .unwrap();
+        // Plains for this seed, too warm to freeze over
+        let mut chunks = stone_chunks(Vector2::new(-4, 16), 80, 100);
+        let origin = Vector3::new(-56, 100, 264);
+        let (placed, water) = place(&lakes, &mut chunks, origin);
+        assert!(placed);
+        assert!(!water.is_empty());
+        // Nothing floats above the ground
+        assert!(water.iter().all(|pos| pos.y < origin.y));
+        assert!(holds_in(&mut chunks, &water));
+
+        // A lake cut open by a cave would drain into it
+        let mut chunks = stone_chunks(Vector2::new(-4, 16), 80, 100);
+        let [center, ..] = &mut chunks;
+        for x in 0..16 {
+            for y in 90..99 {
+                center.set_block_state(&Vector3::new(x, y, 15), Block::AIR.default_state);
+            }
+        }
+        let (placed, _) = place(&lakes, &mut chunks, origin);
+        assert!(!placed);
+    }
 }
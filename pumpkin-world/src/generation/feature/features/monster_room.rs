@@ -1,6 +1,237 @@
+use pumpkin_data::{
+    Block, BlockDirection, BlockState,
+    block_properties::{BlockProperties, ChestLikeProperties, ChestType},
+    tag,
+    tag::Taggable,
+};
+use pumpkin_util::{
+    math::{position::BlockPos, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::generation::{height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion};
+
+/// The mobs a dungeon spawner may spawn, zombies twice as likely as the others.
+const MOBS: [&str; 4] = [
+    "minecraft:skeleton",
+    "minecraft:zombie",
+    "minecraft:zombie",
+    "minecraft:spider",
+];
+
+/// A small room of cobblestone and mossy cobblestone carved into the rock around a spawner, with
+/// up to two chests against its walls.
 #[derive(Deserialize)]
-pub struct DungeonFeature {
-    // TODO
+pub struct DungeonFeature;
+
+impl DungeonFeature {
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        let origin = pos.0;
+        let half_x = random.next_bounded_i32(2) + 2;
+        let (min_x, max_x) = (-half_x - 1, half_x + 1);
+        let half_z = random.next_bounded_i32(2) + 2;
+        let (min_z, max_z) = (-half_z - 1, half_z + 1);
+        let at = |x, y, z| origin.add(&Vector3::new(x, y, z));
+        let is_wall = |x, z| x == min_x || x == max_x || z == min_z || z == max_z;
+
+        // A solid floor and ceiling, with only a few openings into the walls
+        let mut openings = 0;
+        for x in min_x..=max_x {
+            for y in -1..=4 {
+                for z in min_z..=max_z {
+                    let pos = at(x, y, z);
+                    let solid = chunk.get_block_state(&pos).to_state().is_solid();
+                    if (y == -1 || y == 4) && !solid {
+                        return false;
+                    }
+                    if is_wall(x, z)
+                        && y == 0
+                        && chunk.is_air(&pos)
+                        && chunk.is_air(&at(x, y + 1, z))
+                    {
+                        openings += 1;
+                    }
+                }
+            }
+        }
+        if !(1..=5).contains(&openings) {
+            return false;
+        }
+
+        for x in min_x..=max_x {
+            for y in (-1..=3).rev() {
+                for z in min_z..=max_z {
+                    let pos = at(x, y, z);
+                    let state = chunk.get_block_state(&pos).to_state();
+                    let block = Block::from_state_id(state.id);
+                    if !is_wall(x, z) && y != -1 {
+                        if block != &Block::CHEST && block != &Block::SPAWNER {
+                            set_if_replaceable(chunk, &pos, Block::CAVE_AIR.default_state);
+                        }
+                    } else if pos.y >= chunk.bottom_y() as i32
+                        && !chunk
+                            .get_block_state(&at(x, y - 1, z))
+                            .to_state()
+                            .is_solid()
+                    {
+                        chunk.set_block_state(&pos, Block::CAVE_AIR.default_state);
+                    } else if state.is_solid() && block != &Block::CHEST {
+                        let wall = if y == -1 && random.next_bounded_i32(4) != 0 {
+                            &Block::MOSSY_COBBLESTONE
+                        } else {
+                            &Block::COBBLESTONE
+                        };
+                        set_if_replaceable(chunk, &pos, wall.default_state);
+                    }
+                }
+            }
+        }
+
+        // Up to two chests, each tried at three places along a single wall
+        for _ in 0..2 {
+            for _ in 0..3 {
+                let pos = Vector3::new(
+                    origin.x + random.next_bounded_i32(half_x * 2 + 1) - half_x,
+                    origin.y,
+                    origin.z + random.next_bounded_i32(half_z * 2 + 1) - half_z,
+                );
+                if !chunk.is_air(&pos) {
+                    continue;
+                }
+                let walls: Vec<_> = BlockDirection::horizontal()
+                    .into_iter()
+                    .filter(|direction| {
+                        let neighbor = pos.add(&direction.to_offset());
+                        chunk.get_block_state(&neighbor).to_state().is_solid()
+                    })
+                    .collect();
+                if let [wall] = walls.as_slice() {
+                    let props = ChestLikeProperties {
+                        r#facing: wall
+                            .opposite()
+                            .to_horizontal_facing()
+                            .expect("horizontal directions have a facing"),
+                        r#type: ChestType::Single,
+                        r#waterlogged: false,
+                    };
+                    // TODO: Fill it from the simple_dungeon loot table once chests get block
+                    // entities
+                    let chest = BlockState::from_id(props.to_state_id(&Block::CHEST));
+                    set_if_replaceable(chunk, &pos, chest);
+                    break;
+                }
+            }
+        }
+
+        set_if_replaceable(chunk, &origin, Block::SPAWNER.default_state);
+        // TODO: Spawn this mob once spawners get block entities
+        let _mob = MOBS[random.next_bounded_i32(MOBS.len() as i32) as usize];
+        true
+    }
+}
+
+/// Places `state` unless the block there is one features must not replace, like bedrock.
+fn set_if_replaceable(chunk: &mut ProtoChunkRegion, pos: &Vector3<i32>, state: &BlockState) {
+    if !chunk
+        .get_block_state(pos)
+        .to_block()
+        .is_tagged_with_by_tag(&tag::Block::MINECRAFT_FEATURES_CANNOT_REPLACE)
+    {
+        chunk.set_block_state(pos, state);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::LazyLock};
+
+    use pumpkin_data::{Block, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            generator::{decorate, tests::BlockRegistry},
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    #[test]
+    fn dungeons_at_the_vanilla_rate() {
+        const RADIUS: i32 = 3;
+        // Seed 0 has a dungeon close to here
+        const ORIGIN: Vector2<i32> = Vector2::new(-60, 20);
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunks = HashMap::new();
+        for chunk_x in -RADIUS..=RADIUS {
+            for chunk_z in -RADIUS..=RADIUS {
+                let pos = ORIGIN.add(&Vector2::new(chunk_x, chunk_z));
+                let mut chunk = ProtoChunk::new(
+                    pos,
+                    &BASE_NOISE_ROUTER,
+                    &RANDOM_CONFIG,
+                    settings,
+                    &TERRAIN_CACHE,
+                    settings.default_block.get_state(),
+                );
+                chunk.populate_biomes(&Dimension::Overworld);
+                chunk.populate_noise();
+                chunk.build_surface();
+                chunk.carve(&Dimension::Overworld, 1);
+                chunks.insert(pos, chunk);
+            }
+        }
+        // Every chunk but the outer ring is decorated with all of its neighbors around it
+        for chunk_x in 1 - RADIUS..RADIUS {
+            for chunk_z in 1 - RADIUS..RADIUS {
+                let pos = ORIGIN.add(&Vector2::new(chunk_x, chunk_z));
+                decorate(&mut chunks, pos, &BlockRegistry);
+            }
+        }
+
+        let mut dungeons = 0;
+        for chunk in chunks.values() {
+            for y in -64..320 {
+                for z in 0..16 {
+                    for x in 0..16 {
+                        let pos = Vector3::new(x, y, z);
+                        if chunk.get_block_state(&pos).to_block() != &Block::SPAWNER {
+                            continue;
+                        }
+                        dungeons += 1;
+                        // Standing on the floor of the room
+                        let below = chunk.get_block_state(&Vector3::new(x, y - 1, z));
+                        let floor = below.to_block();
+                        assert!(floor == &Block::COBBLESTONE || floor == &Block::MOSSY_COBBLESTONE);
+                        assert!(chunk.is_air(&Vector3::new(x, y + 1, z)));
+                    }
+                }
+            }
+        }
+        // Most of the tries are in solid rock or open caves, like in vanilla
+        let per_chunk = dungeons as f64 / ((RADIUS * 2 - 1) * (RADIUS * 2 - 1)) as f64;
+        assert!(
+            dungeons > 0 && per_chunk < 0.5,
+            "{per_chunk} dungeons per chunk"
+        );
+    }
 }
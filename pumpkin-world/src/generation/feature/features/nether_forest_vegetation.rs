@@ -6,8 +6,10 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk,
-    generation::{block_state_provider::BlockStateProvider, height_limit::HeightLimitView},
+    generation::{
+        block_state_provider::BlockStateProvider, height_limit::HeightLimitView,
+        proto_chunk_region::ProtoChunkRegion,
+    },
     world::BlockRegistryExt,
 };
 
@@ -22,7 +24,7 @@ impl NetherForestVegetationFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
@@ -6,7 +6,8 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk, block::BlockStateCodec, generation::height_limit::HeightLimitView,
+    block::BlockStateCodec,
+    generation::{height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion},
     world::BlockRegistryExt,
 };
 
@@ -21,7 +22,7 @@ impl ReplaceBlobsFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
@@ -58,7 +59,7 @@ impl ReplaceBlobsFeature {
 
     fn move_down_to_target(
         mut pos: BlockPos,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         target: &'static Block,
     ) -> Option<BlockPos> {
         while pos.0.y > chunk.bottom_y() as i32 + 1 {
@@ -9,9 +9,10 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk,
     block::BlockStateCodec,
-    generation::{height_limit::HeightLimitView, rule::RuleTest},
+    generation::{
+        height_limit::HeightLimitView, proto_chunk_region::ProtoChunkRegion, rule::RuleTest,
+    },
     world::BlockRegistryExt,
 };
 
@@ -32,7 +33,7 @@ impl OreFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
@@ -73,7 +74,7 @@ impl OreFeature {
     #[expect(clippy::too_many_arguments)]
     fn generate_vein_part(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         start_x: f64,
         end_x: f64,
@@ -212,7 +213,7 @@ impl OreFeature {
 
     fn should_place(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         state: &'static BlockState,
         random: &mut RandomGenerator,
         target: &OreTarget,
@@ -237,7 +238,7 @@ impl OreFeature {
         random.next_f32() >= chance
     }
 
-    fn is_exposed_to_air(chunk: &mut ProtoChunk, pos: &BlockPos) -> bool {
+    fn is_exposed_to_air(chunk: &mut ProtoChunkRegion, pos: &BlockPos) -> bool {
         for dir in BlockDirection::all() {
             if chunk
                 .get_block_state(&pos.offset(dir.to_offset()).0)
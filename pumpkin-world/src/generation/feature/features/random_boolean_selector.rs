@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_util::{
     math::position::BlockPos,
     random::{RandomGenerator, RandomImpl},
@@ -7,7 +5,9 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk, generation::feature::placed_features::PlacedFeatureWrapper, level::Level,
+    generation::{
+        feature::placed_features::PlacedFeatureWrapper, proto_chunk_region::ProtoChunkRegion,
+    },
     world::BlockRegistryExt,
 };
 
@@ -21,8 +21,7 @@ impl RandomBooleanFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         min_y: i8,
         height: u16,
@@ -38,7 +37,6 @@ impl RandomBooleanFeature {
         };
         feature.get().generate(
             chunk,
-            level,
             block_registry,
             min_y,
             height,
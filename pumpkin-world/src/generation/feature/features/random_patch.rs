@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_util::{
     math::{position::BlockPos, vector3::Vector3},
     random::{RandomGenerator, RandomImpl},
@@ -7,7 +5,7 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk, generation::feature::placed_features::PlacedFeature, level::Level,
+    generation::{feature::placed_features::PlacedFeature, proto_chunk_region::ProtoChunkRegion},
     world::BlockRegistryExt,
 };
 
@@ -23,8 +21,7 @@ impl RandomPatchFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         min_y: i8,
         height: u16,
@@ -43,7 +40,6 @@ impl RandomPatchFeature {
             );
             if !self.feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
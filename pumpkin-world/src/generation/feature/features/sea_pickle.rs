@@ -8,7 +8,7 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 #[derive(Deserialize)]
 pub struct SeaPickleFeature {
@@ -18,7 +18,7 @@ pub struct SeaPickleFeature {
 impl SeaPickleFeature {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _min_y: i8,
         _height: u16,
         _feature: &str, // This placed feature
@@ -1,13 +1,26 @@
-use pumpkin_data::{Block, BlockDirection};
+use pumpkin_data::{
+    Block, BlockDirection, BlockState,
+    block_properties::{BlockProperties, DoubleBlockHalf, TallSeagrassLikeProperties},
+};
 use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk,
-    generation::block_state_provider::BlockStateProvider,
+    generation::{block_state_provider::BlockStateProvider, proto_chunk_region::ProtoChunkRegion},
     world::{BlockAccessor, BlockRegistryExt},
 };
 
+/// The plants two blocks tall, placed together with their upper half.
+const TALL_PLANTS: [&Block; 7] = [
+    &Block::TALL_GRASS,
+    &Block::LARGE_FERN,
+    &Block::PITCHER_PLANT,
+    &Block::SUNFLOWER,
+    &Block::LILAC,
+    &Block::PEONY,
+    &Block::ROSE_BUSH,
+];
+
 #[derive(Deserialize)]
 pub struct SimpleBlockFeature {
     to_place: BlockStateProvider,
@@ -18,7 +31,7 @@ impl SimpleBlockFeature {
     pub fn generate(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
@@ -29,6 +42,18 @@ impl SimpleBlockFeature {
             return false;
         }
 
+        if TALL_PLANTS.contains(&block) {
+            let above = pos.up();
+            if !chunk.get_block_state(&above.0).to_state().is_air() {
+                return false;
+            }
+            let mut props = TallSeagrassLikeProperties::from_state_id(state.id, block);
+            props.half = DoubleBlockHalf::Upper;
+            chunk.set_block_state(&pos.0, state);
+            chunk.set_block_state(&above.0, BlockState::from_id(props.to_state_id(block)));
+            return true;
+        }
+
         // TODO: check things..
         chunk.set_block_state(&pos.0, state);
         // TODO: schedule tick when needed
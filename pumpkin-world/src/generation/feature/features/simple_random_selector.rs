@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_util::{
     math::position::BlockPos,
     random::{RandomGenerator, RandomImpl},
@@ -7,7 +5,7 @@ use pumpkin_util::{
 use serde::Deserialize;
 
 use crate::{
-    ProtoChunk, generation::feature::placed_features::PlacedFeature, level::Level,
+    generation::{feature::placed_features::PlacedFeature, proto_chunk_region::ProtoChunkRegion},
     world::BlockRegistryExt,
 };
 
@@ -20,8 +18,7 @@ impl SimpleRandomFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         min_y: i8,
         height: u16,
@@ -33,7 +30,6 @@ impl SimpleRandomFeature {
         let feature = &self.features[i as usize];
         feature.generate(
             chunk,
-            level,
             block_registry,
             min_y,
             height,
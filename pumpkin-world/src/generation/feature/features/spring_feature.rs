@@ -2,7 +2,10 @@ use pumpkin_data::BlockDirection;
 use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, block::BlockStateCodec, world::BlockRegistryExt};
+use crate::{
+    block::BlockStateCodec, generation::proto_chunk_region::ProtoChunkRegion,
+    world::BlockRegistryExt,
+};
 
 #[derive(Deserialize)]
 pub struct SpringFeatureFeature {
@@ -24,7 +27,7 @@ impl SpringFeatureFeature {
     pub fn generate(
         &self,
         _block_registry: &dyn BlockRegistryExt,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
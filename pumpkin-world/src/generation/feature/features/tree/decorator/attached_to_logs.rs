@@ -5,7 +5,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::block_state_provider::BlockStateProvider};
+use crate::generation::{
+    block_state_provider::BlockStateProvider, proto_chunk_region::ProtoChunkRegion,
+};
 
 #[derive(Deserialize)]
 pub struct AttachedToLogsTreeDecorator {
@@ -17,7 +19,7 @@ pub struct AttachedToLogsTreeDecorator {
 impl AttachedToLogsTreeDecorator {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         _root_positions: Vec<BlockPos>,
         log_positions: Vec<BlockPos>,
@@ -11,7 +11,7 @@ use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 use trunk_vine::TrunkVineTreeDecorator;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 mod alter_ground;
 mod attached_to_leaves;
@@ -52,7 +52,7 @@ pub enum TreeDecorator {
 impl TreeDecorator {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         root_positions: Vec<BlockPos>,
         log_positions: Vec<BlockPos>,
@@ -5,7 +5,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::block_state_provider::BlockStateProvider};
+use crate::generation::{
+    block_state_provider::BlockStateProvider, proto_chunk_region::ProtoChunkRegion,
+};
 
 use super::TreeDecorator;
 
@@ -20,7 +22,7 @@ pub struct PlaceOnGroundTreeDecorator {
 impl PlaceOnGroundTreeDecorator {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         root_positions: Vec<BlockPos>,
         log_positions: Vec<BlockPos>,
@@ -65,7 +67,7 @@ impl PlaceOnGroundTreeDecorator {
 
     fn generate_decoration(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         pos: BlockPos,
         random: &mut RandomGenerator,
     ) {
@@ -8,7 +8,7 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::ProtoChunk;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 #[derive(Deserialize)]
 pub struct TrunkVineTreeDecorator;
@@ -16,7 +16,7 @@ pub struct TrunkVineTreeDecorator;
 impl TrunkVineTreeDecorator {
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         random: &mut RandomGenerator,
         log_positions: Vec<BlockPos>,
     ) {
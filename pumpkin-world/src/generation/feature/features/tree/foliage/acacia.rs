@@ -1,10 +1,8 @@
-use std::sync::Arc;
-
 use pumpkin_data::BlockState;
 use pumpkin_util::random::RandomGenerator;
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::feature::features::tree::TreeNode, level::Level};
+use crate::generation::{feature::features::tree::TreeNode, proto_chunk_region::ProtoChunkRegion};
 
 use super::{FoliagePlacer, LeaveValidator};
 
@@ -15,8 +13,7 @@ impl AcaciaFoliagePlacer {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         node: &TreeNode,
         foliage_height: i32,
@@ -27,7 +24,6 @@ impl AcaciaFoliagePlacer {
         FoliagePlacer::generate_square(
             self,
             chunk,
-            level,
             random,
             node.center,
             radius + node.foliage_radius,
@@ -38,7 +34,6 @@ impl AcaciaFoliagePlacer {
         FoliagePlacer::generate_square(
             self,
             chunk,
-            level,
             random,
             node.center,
             radius - 1,
@@ -49,7 +44,6 @@ impl AcaciaFoliagePlacer {
         FoliagePlacer::generate_square(
             self,
             chunk,
-            level,
             random,
             node.center,
             radius + node.foliage_radius - 1,
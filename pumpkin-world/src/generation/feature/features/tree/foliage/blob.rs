@@ -1,10 +1,8 @@
-use std::sync::Arc;
-
 use pumpkin_data::BlockState;
 use pumpkin_util::random::{RandomGenerator, RandomImpl};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::feature::features::tree::TreeNode, level::Level};
+use crate::generation::{feature::features::tree::TreeNode, proto_chunk_region::ProtoChunkRegion};
 
 use super::{FoliagePlacer, LeaveValidator};
 
@@ -17,8 +15,7 @@ impl BlobFoliagePlacer {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         node: &TreeNode,
         foliage_height: i32,
@@ -31,7 +28,6 @@ impl BlobFoliagePlacer {
             FoliagePlacer::generate_square(
                 self,
                 chunk,
-                level,
                 random,
                 node.center,
                 radius,
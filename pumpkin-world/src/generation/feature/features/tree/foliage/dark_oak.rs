@@ -1,10 +1,8 @@
-use std::sync::Arc;
-
 use pumpkin_data::BlockState;
 use pumpkin_util::random::{RandomGenerator, RandomImpl};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::feature::features::tree::TreeNode, level::Level};
+use crate::generation::{feature::features::tree::TreeNode, proto_chunk_region::ProtoChunkRegion};
 
 use super::{FoliagePlacer, LeaveValidator};
 
@@ -15,8 +13,7 @@ impl DarkOakFoliagePlacer {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         node: &TreeNode,
         _foliage_height: i32,
@@ -30,7 +27,6 @@ impl DarkOakFoliagePlacer {
             FoliagePlacer::generate_square(
                 self,
                 chunk,
-                level,
                 random,
                 pos,
                 radius + 2,
@@ -41,7 +37,6 @@ impl DarkOakFoliagePlacer {
             FoliagePlacer::generate_square(
                 self,
                 chunk,
-                level,
                 random,
                 pos,
                 radius + 3,
@@ -52,7 +47,6 @@ impl DarkOakFoliagePlacer {
             FoliagePlacer::generate_square(
                 self,
                 chunk,
-                level,
                 random,
                 pos,
                 radius + 2,
@@ -64,7 +58,6 @@ impl DarkOakFoliagePlacer {
                 FoliagePlacer::generate_square(
                     self,
                     chunk,
-                    level,
                     random,
                     pos,
                     radius,
@@ -77,7 +70,6 @@ impl DarkOakFoliagePlacer {
             FoliagePlacer::generate_square(
                 self,
                 chunk,
-                level,
                 random,
                 pos,
                 radius + 2,
@@ -88,7 +80,6 @@ impl DarkOakFoliagePlacer {
             FoliagePlacer::generate_square(
                 self,
                 chunk,
-                level,
                 random,
                 pos,
                 radius + 1,
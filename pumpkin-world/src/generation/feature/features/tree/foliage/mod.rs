@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use acacia::AcaciaFoliagePlacer;
 use blob::BlobFoliagePlacer;
 use bush::BushFoliagePlacer;
@@ -18,7 +16,7 @@ use random_spread::RandomSpreadFoliagePlacer;
 use serde::Deserialize;
 use spruce::SpruceFoliagePlacer;
 
-use crate::{ProtoChunk, level::Level};
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
 
 use super::{TreeFeature, TreeNode};
 
@@ -80,8 +78,7 @@ impl FoliagePlacer {
     #[expect(clippy::too_many_arguments)]
     pub fn generate_square<T: LeaveValidator>(
         validator: &T,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         center_pos: BlockPos,
         radius: i32,
@@ -97,16 +94,14 @@ impl FoliagePlacer {
                     continue;
                 }
                 let pos = BlockPos(center_pos.0.add(&Vector3::new(x, y, z)));
-                Self::place_foliage_block(chunk, level, pos, foliage_provider);
+                Self::place_foliage_block(chunk, pos, foliage_provider);
             }
         }
     }
 
-    #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         node: &TreeNode,
         foliage_height: i32,
@@ -116,7 +111,6 @@ impl FoliagePlacer {
         let offset = self.offset.get(random);
         self.r#type.generate(
             chunk,
-            level,
             random,
             node,
             foliage_height,
@@ -134,8 +128,7 @@ impl FoliagePlacer {
     }
 
     pub fn place_foliage_block(
-        chunk: &mut ProtoChunk<'_>,
-        _level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         pos: BlockPos,
         block_state: &BlockState,
     ) {
@@ -143,11 +136,7 @@ impl FoliagePlacer {
         if !TreeFeature::can_replace(block.to_state(), block.to_block()) {
             return;
         }
-        if chunk.chunk_pos == pos.chunk_and_chunk_relative_position().0 {
-            chunk.set_block_state(&pos.0, block_state);
-        } else {
-            //level.set_block_state(&pos, block_state.id).await;
-        }
+        chunk.set_block_state(&pos.0, block_state);
     }
 }
 
@@ -182,8 +171,7 @@ impl FoliageType {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         node: &TreeNode,
         foliage_height: i32,
@@ -194,7 +182,6 @@ impl FoliageType {
         match self {
             FoliageType::Blob(blob) => blob.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -204,7 +191,6 @@ impl FoliageType {
             ),
             FoliageType::Spruce(spruce) => spruce.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -214,7 +200,6 @@ impl FoliageType {
             ),
             FoliageType::Pine(pine) => pine.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -224,7 +209,6 @@ impl FoliageType {
             ),
             FoliageType::Acacia(acacia) => acacia.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -234,7 +218,6 @@ impl FoliageType {
             ),
             FoliageType::Bush(bush) => bush.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -244,7 +227,6 @@ impl FoliageType {
             ),
             FoliageType::Fancy(fancy) => fancy.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -254,7 +236,6 @@ impl FoliageType {
             ),
             FoliageType::Jungle(jungle) => jungle.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -264,7 +245,6 @@ impl FoliageType {
             ),
             FoliageType::MegaPine(mega_pine) => mega_pine.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -274,7 +254,6 @@ impl FoliageType {
             ),
             FoliageType::DarkOak(dark_oak) => dark_oak.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -284,7 +263,6 @@ impl FoliageType {
             ),
             FoliageType::RandomSpread(random_spread) => random_spread.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -294,7 +272,6 @@ impl FoliageType {
             ),
             FoliageType::Cherry(cherry) => cherry.generate(
                 chunk,
-                level,
                 random,
                 node,
                 foliage_height,
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_data::BlockState;
 use pumpkin_util::{
     math::{int_provider::IntProvider, position::BlockPos},
@@ -7,7 +5,7 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::feature::features::tree::TreeNode, level::Level};
+use crate::generation::{feature::features::tree::TreeNode, proto_chunk_region::ProtoChunkRegion};
 
 use super::FoliagePlacer;
 
@@ -21,8 +19,7 @@ impl RandomSpreadFoliagePlacer {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         _node: &TreeNode,
         foliage_height: i32,
@@ -36,7 +33,7 @@ impl RandomSpreadFoliagePlacer {
                 random.next_bounded_i32(foliage_height) - random.next_bounded_i32(foliage_height),
                 random.next_bounded_i32(radius) - random.next_bounded_i32(radius),
             );
-            FoliagePlacer::place_foliage_block(chunk, level, pos, foliage_provider);
+            FoliagePlacer::place_foliage_block(chunk, pos, foliage_provider);
         }
     }
     // TODO: getRandomRadius
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use decorator::TreeDecorator;
 use foliage::FoliagePlacer;
 use pumpkin_data::tag;
@@ -8,10 +6,9 @@ use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 use trunk::TrunkPlacer;
 
-use crate::{
-    ProtoChunk,
-    generation::{block_state_provider::BlockStateProvider, feature::size::FeatureSize},
-    level::Level,
+use crate::generation::{
+    block_state_provider::BlockStateProvider, feature::size::FeatureSize,
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 mod decorator;
@@ -38,11 +35,9 @@ pub struct TreeNode {
 }
 
 impl TreeFeature {
-    #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         min_y: i8,
         height: u16,
         feature_name: &str, // This placed feature
@@ -50,8 +45,7 @@ impl TreeFeature {
         pos: BlockPos,
     ) -> bool {
         // TODO
-        let log_positions =
-            self.generate_main(chunk, level, min_y, height, feature_name, random, pos);
+        let log_positions = self.generate_main(chunk, min_y, height, feature_name, random, pos);
 
         for decorator in &self.decorators {
             decorator.generate(chunk, random, Vec::new(), log_positions.clone());
@@ -71,11 +65,9 @@ impl TreeFeature {
         state.is_air() || block.is_tagged_with_by_tag(&tag::Block::MINECRAFT_REPLACEABLE_BY_TREES)
     }
 
-    #[expect(clippy::too_many_arguments)]
     fn generate_main(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         _min_y: i8,
         _height: u16,
         _feature_name: &str, // This placed feature
@@ -96,7 +88,6 @@ impl TreeFeature {
             top,
             pos,
             chunk,
-            level,
             random,
             self.force_dirt,
             dirt_state,
@@ -113,7 +104,6 @@ impl TreeFeature {
         for node in nodes {
             self.foliage_placer.generate(
                 chunk,
-                level,
                 random,
                 &node,
                 foliage_height,
@@ -124,7 +114,7 @@ impl TreeFeature {
         logs
     }
 
-    fn get_top(&self, height: u32, chunk: &ProtoChunk, init_pos: BlockPos) -> u32 {
+    fn get_top(&self, height: u32, chunk: &ProtoChunkRegion, init_pos: BlockPos) -> u32 {
         for y in 0..=height + 1 {
             let j = self.minimum_size.r#type.get_radius(height, y as i32);
             for x in -j..=j {
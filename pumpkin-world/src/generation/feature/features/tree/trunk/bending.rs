@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_data::{BlockDirection, BlockState};
 use pumpkin_util::{
     math::{int_provider::IntProvider, position::BlockPos},
@@ -7,10 +5,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{
-    ProtoChunk,
-    generation::feature::features::tree::{TreeNode, trunk::TrunkPlacer},
-    level::Level,
+use crate::generation::{
+    feature::features::tree::{TreeNode, trunk::TrunkPlacer},
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 #[derive(Deserialize)]
@@ -26,8 +23,7 @@ impl BendingTrunkPlacer {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        _level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_data::{BlockDirection, BlockState};
 use pumpkin_util::{
     math::position::BlockPos,
@@ -7,10 +5,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{
-    ProtoChunk,
-    generation::feature::features::tree::{TreeFeature, TreeNode, trunk::TrunkPlacer},
-    level::Level,
+use crate::generation::{
+    feature::features::tree::{TreeFeature, TreeNode, trunk::TrunkPlacer},
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 #[derive(Deserialize)]
@@ -22,8 +19,7 @@ impl DarkOakTrunkPlacer {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        _level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -69,7 +65,6 @@ impl DarkOakTrunkPlacer {
                 rand -= 1;
             }
             let pos = BlockPos::new(x, y_height, z);
-            // TODO: support multiple chunks
             let state = chunk.get_block_state(&pos.0);
             if !TreeFeature::is_air_or_leaves(state.to_state(), state.to_block()) {
                 continue;
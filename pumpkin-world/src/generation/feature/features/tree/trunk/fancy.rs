@@ -1,5 +1,4 @@
 use core::f32;
-use std::sync::Arc;
 
 use pumpkin_data::{
     Block, BlockState,
@@ -11,10 +10,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{
-    ProtoChunk,
-    generation::feature::features::tree::{TreeFeature, TreeNode},
-    level::Level,
+use crate::generation::{
+    feature::features::tree::{TreeFeature, TreeNode},
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 use super::TrunkPlacer;
@@ -28,8 +26,7 @@ impl FancyTrunkPlacer {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -64,7 +61,6 @@ impl FancyTrunkPlacer {
 
                 let (i, new_logs) = Self::make_or_check_branch(
                     chunk,
-                    level,
                     block_pos.0,
                     block_pos_2.0,
                     trunk_block,
@@ -85,7 +81,6 @@ impl FancyTrunkPlacer {
 
                 let (i, new_logs) = Self::make_or_check_branch(
                     chunk,
-                    level,
                     block_pos_3.0,
                     block_pos.0,
                     trunk_block,
@@ -102,13 +97,12 @@ impl FancyTrunkPlacer {
 
         Self::make_or_check_branch(
             chunk,
-            level,
             start_pos.0,
             start_pos.up_height(k).0,
             trunk_block,
             true,
         );
-        Self::make_branches(chunk, level, j, start_pos.0, trunk_block, &list);
+        Self::make_branches(chunk, j, start_pos.0, trunk_block, &list);
 
         let mut list_2: Vec<TreeNode> = Vec::new();
         for branch_position in list {
@@ -120,8 +114,7 @@ impl FancyTrunkPlacer {
     }
 
     fn make_or_check_branch(
-        chunk: &mut ProtoChunk<'_>,
-        _level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         start_pos: Vector3<i32>,
         branch_pos: Vector3<i32>,
         trunk_provider: &BlockState,
@@ -171,11 +164,7 @@ impl FancyTrunkPlacer {
                         })
                         .collect();
                     let state = block.from_properties(&props).to_state_id(block);
-                    if chunk.chunk_pos == block_pos_2.chunk_and_chunk_relative_position().0 {
-                        chunk.set_block_state(&block_pos_2.0, BlockState::from_id(state));
-                    } else {
-                        // level.set_block_state(&block_pos_2, state).await;
-                    }
+                    chunk.set_block_state(&block_pos_2.0, BlockState::from_id(state));
                     logs.push(block_pos_2);
                     continue;
                 }
@@ -190,8 +179,7 @@ impl FancyTrunkPlacer {
     }
 
     fn make_branches(
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         tree_height: i32,
         start_pos: Vector3<i32>,
         trunk_provider: &BlockState,
@@ -207,7 +195,6 @@ impl FancyTrunkPlacer {
             }
             Self::make_or_check_branch(
                 chunk,
-                level,
                 block_pos.0,
                 branch_position.node.center.0,
                 trunk_provider,
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use pumpkin_data::{BlockDirection, BlockState};
 use pumpkin_util::{
     math::{position::BlockPos, vector3::Vector3},
@@ -7,10 +5,9 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{
-    ProtoChunk,
-    generation::feature::features::tree::{TreeNode, trunk::TrunkPlacer},
-    level::Level,
+use crate::generation::{
+    feature::features::tree::{TreeNode, trunk::TrunkPlacer},
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 #[derive(Deserialize)]
@@ -22,8 +19,7 @@ impl GiantTrunkPlacer {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        _level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         _random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -1,5 +1,4 @@
 use core::f32;
-use std::sync::Arc;
 
 use pumpkin_data::BlockState;
 use pumpkin_util::{
@@ -8,13 +7,12 @@ use pumpkin_util::{
 };
 use serde::Deserialize;
 
-use crate::{
-    ProtoChunk,
-    generation::feature::features::tree::{
+use crate::generation::{
+    feature::features::tree::{
         TreeNode,
         trunk::{TrunkPlacer, giant::GiantTrunkPlacer},
     },
-    level::Level,
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 #[derive(Deserialize)]
@@ -26,8 +24,7 @@ impl MegaJungleTrunkPlacer {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -38,7 +35,6 @@ impl MegaJungleTrunkPlacer {
             height,
             start_pos,
             chunk,
-            level,
             random,
             force_dirt,
             dirt_state,
@@ -1,5 +1,3 @@
-use std::sync::Arc;
-
 use fancy::FancyTrunkPlacer;
 use pumpkin_data::tag;
 use pumpkin_data::{Block, BlockState, tag::Taggable};
@@ -10,14 +8,13 @@ use pumpkin_util::{
 use serde::Deserialize;
 use straight::StraightTrunkPlacer;
 
-use crate::{
-    ProtoChunk,
-    generation::feature::features::tree::trunk::{
+use crate::generation::{
+    feature::features::tree::trunk::{
         bending::BendingTrunkPlacer, cherry::CherryTrunkPlacer, dark_oak::DarkOakTrunkPlacer,
         forking::ForkingTrunkPlacer, giant::GiantTrunkPlacer, mega_jungle::MegaJungleTrunkPlacer,
         upwards_branching::UpwardsBranchingTrunkPlacer,
     },
-    level::Level,
+    proto_chunk_region::ProtoChunkRegion,
 };
 
 use super::{TreeFeature, TreeNode};
@@ -50,7 +47,7 @@ impl TrunkPlacer {
 
     pub fn set_dirt(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -67,7 +64,7 @@ impl TrunkPlacer {
 
     pub fn place(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
         trunk_block: &BlockState,
     ) -> bool {
@@ -81,7 +78,7 @@ impl TrunkPlacer {
 
     pub fn try_place(
         &self,
-        chunk: &mut ProtoChunk<'_>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         pos: &BlockPos,
         trunk_block: &BlockState,
     ) -> bool {
@@ -97,8 +94,7 @@ impl TrunkPlacer {
         &self,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -109,7 +105,6 @@ impl TrunkPlacer {
             height,
             start_pos,
             chunk,
-            level,
             random,
             force_dirt,
             dirt_state,
@@ -148,8 +143,7 @@ impl TrunkType {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         random: &mut RandomGenerator,
         force_dirt: bool,
         dirt_state: &BlockState,
@@ -171,7 +165,6 @@ impl TrunkType {
                 height,
                 start_pos,
                 chunk,
-                level,
                 random,
                 force_dirt,
                 dirt_state,
@@ -182,7 +175,6 @@ impl TrunkType {
                 height,
                 start_pos,
                 chunk,
-                level,
                 random,
                 force_dirt,
                 dirt_state,
@@ -193,7 +185,6 @@ impl TrunkType {
                 height,
                 start_pos,
                 chunk,
-                level,
                 random,
                 force_dirt,
                 dirt_state,
@@ -204,7 +195,6 @@ impl TrunkType {
                 height,
                 start_pos,
                 chunk,
-                level,
                 random,
                 force_dirt,
                 dirt_state,
@@ -215,7 +205,6 @@ impl TrunkType {
                 height,
                 start_pos,
                 chunk,
-                level,
                 random,
                 force_dirt,
                 dirt_state,
@@ -2,7 +2,7 @@ use pumpkin_data::BlockState;
 use pumpkin_util::math::position::BlockPos;
 use serde::Deserialize;
 
-use crate::{ProtoChunk, generation::feature::features::tree::TreeNode};
+use crate::generation::{feature::features::tree::TreeNode, proto_chunk_region::ProtoChunkRegion};
 
 use super::TrunkPlacer;
 
@@ -14,7 +14,7 @@ impl StraightTrunkPlacer {
         placer: &TrunkPlacer,
         height: u32,
         start_pos: BlockPos,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         force_dirt: bool,
         dirt_state: &BlockState,
         trunk_state: &BlockState,
@@ -1,6 +1,162 @@
+use pumpkin_data::{Block, BlockDirection, tag::Taggable};
+use pumpkin_util::{
+    math::{
+        int_provider::IntProvider, position::BlockPos, vertical_surface_type::VerticalSurfaceType,
+    },
+    random::{RandomGenerator, RandomImpl},
+};
 use serde::Deserialize;
 
+use crate::{
+    generation::{
+        block_state_provider::BlockStateProvider, feature::placed_features::PlacedFeatureWrapper,
+        proto_chunk_region::ProtoChunkRegion,
+    },
+    world::BlockRegistryExt,
+};
+
+/// A rough rectangle of ground, like the moss on the floors and ceilings of lush caves, sprinkled
+/// with the vegetation feature.
 #[derive(Deserialize)]
 pub struct VegetationPatchFeature {
-    // TODO
+    replaceable: String,
+    ground_state: BlockStateProvider,
+    vegetation_feature: PlacedFeatureWrapper,
+    surface: VerticalSurfaceType,
+    depth: IntProvider,
+    extra_bottom_block_chance: f32,
+    vertical_range: i32,
+    vegetation_chance: f32,
+    xz_radius: IntProvider,
+    extra_edge_column_chance: f32,
+}
+
+impl VegetationPatchFeature {
+    #[expect(clippy::too_many_arguments)]
+    pub fn generate(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        block_registry: &dyn BlockRegistryExt,
+        min_y: i8,
+        height: u16,
+        feature: &str,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+    ) -> bool {
+        let radius_x = self.xz_radius.get(random) + 1;
+        let radius_z = self.xz_radius.get(random) + 1;
+        let ground = self.place_ground_patch(chunk, random, pos, radius_x, radius_z);
+        // Vegetation grows on the side of the ground facing the cave
+        let towards_cave = self.towards_ground().opposite().to_offset();
+        for ground_pos in &ground {
+            if self.vegetation_chance > 0.0 && random.next_f32() < self.vegetation_chance {
+                self.vegetation_feature.get().generate(
+                    chunk,
+                    block_registry,
+                    min_y,
+                    height,
+                    feature,
+                    random,
+                    ground_pos.offset(towards_cave),
+                );
+            }
+        }
+        !ground.is_empty()
+    }
+
+    /// From the cave into the ground the patch covers.
+    fn towards_ground(&self) -> BlockDirection {
+        match self.surface {
+            VerticalSurfaceType::Floor => BlockDirection::Down,
+            VerticalSurfaceType::Ceiling => BlockDirection::Up,
+        }
+    }
+
+    /// Covers the ground around `pos`, leaving out the corners and some of the edges. Returns
+    /// the topmost ground block of every column that got covered.
+    fn place_ground_patch(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+        radius_x: i32,
+        radius_z: i32,
+    ) -> Vec<BlockPos> {
+        let towards_ground = self.towards_ground();
+        let mut ground = Vec::new();
+        for x in -radius_x..=radius_x {
+            let edge_x = x == -radius_x || x == radius_x;
+            for z in -radius_z..=radius_z {
+                let edge_z = z == -radius_z || z == radius_z;
+                if edge_x && edge_z {
+                    continue;
+                }
+                if (edge_x || edge_z)
+                    && (self.extra_edge_column_chance == 0.0
+                        || random.next_f32() > self.extra_edge_column_chance)
+                {
+                    continue;
+                }
+
+                // Find the last air block in front of the ground
+                let mut cave = pos.add(x, 0, z);
+                for _ in 0..self.vertical_range {
+                    if !chunk.is_air(&cave.0) {
+                        break;
+                    }
+                    cave = cave.offset(towards_ground.to_offset());
+                }
+                for _ in 0..self.vertical_range {
+                    if chunk.is_air(&cave.0) {
+                        break;
+                    }
+                    cave = cave.offset(towards_ground.opposite().to_offset());
+                }
+                let surface = cave.offset(towards_ground.to_offset());
+                let surface_state = chunk.get_block_state(&surface.0).to_state();
+                if !chunk.is_air(&cave.0) || !surface_state.is_side_solid(towards_ground.opposite())
+                {
+                    continue;
+                }
+
+                let depth = self.depth.get(random)
+                    + if self.extra_bottom_block_chance > 0.0
+                        && random.next_f32() < self.extra_bottom_block_chance
+                    {
+                        1
+                    } else {
+                        0
+                    };
+                if self.place_ground(chunk, random, surface, depth) {
+                    ground.push(surface);
+                }
+            }
+        }
+        ground
+    }
+
+    /// Replaces up to `depth` blocks from `pos` into the ground, stopping at the first block that
+    /// can't be replaced. `false` if not even the first one could be.
+    fn place_ground(
+        &self,
+        chunk: &mut ProtoChunkRegion,
+        random: &mut RandomGenerator,
+        pos: BlockPos,
+        depth: i32,
+    ) -> bool {
+        let mut pos = pos;
+        for i in 0..depth {
+            let state = self.ground_state.get(random, pos);
+            let block = chunk.get_block_state(&pos.0).to_block();
+            if Block::from_state_id(state.id) == block {
+                continue;
+            }
+            if !block.is_tagged_with(&self.replaceable).unwrap_or(false) {
+                return i != 0;
+            }
+            chunk.set_block_state(&pos.0, state);
+            pos = pos.offset(self.towards_ground().to_offset());
+        }
+        true
+    }
 }
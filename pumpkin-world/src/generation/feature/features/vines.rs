@@ -2,7 +2,7 @@ use pumpkin_data::{Block, BlockDirection, BlockState, block_properties::BlockPro
 use pumpkin_util::{math::position::BlockPos, random::RandomGenerator};
 use serde::Deserialize;
 
-use crate::{ProtoChunk, world::BlockRegistryExt};
+use crate::{generation::proto_chunk_region::ProtoChunkRegion, world::BlockRegistryExt};
 
 #[derive(Deserialize)]
 pub struct VinesFeature;
@@ -11,7 +11,7 @@ impl VinesFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk,
+        chunk: &mut ProtoChunkRegion,
         _block_registry: &dyn BlockRegistryExt,
         _min_y: i8,
         _height: u16,
@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use pumpkin_data::{Block, BlockDirection};
 use pumpkin_util::{HeightMap, read_data_from_file};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::iter;
 use std::ops::Deref;
 use std::sync::{Arc, LazyLock};
@@ -14,17 +14,18 @@ use pumpkin_util::math::vector2::Vector2;
 use pumpkin_util::math::vector3::Vector3;
 use pumpkin_util::random::{RandomGenerator, RandomImpl};
 
-use crate::ProtoChunk;
 use crate::block::RawBlockState;
 use crate::generation::block_predicate::BlockPredicate;
 use crate::generation::height_limit::HeightLimitView;
 use crate::generation::height_provider::HeightProvider;
-use crate::level::Level;
+use crate::generation::proto_chunk_region::ProtoChunkRegion;
+use crate::generation::section_coords;
 use crate::world::BlockRegistryExt;
 
 use super::configured_features::{CONFIGURED_FEATURES, ConfiguredFeature};
 
-pub static PLACED_FEATURES: LazyLock<HashMap<String, PlacedFeature>> =
+/// Sorted by name, so that every chunk places its features in the same order no matter the run.
+pub static PLACED_FEATURES: LazyLock<BTreeMap<String, PlacedFeature>> =
     LazyLock::new(|| read_data_from_file!("../../../../assets/placed_feature.json"));
 
 #[derive(Deserialize)]
@@ -45,6 +46,42 @@ impl PlacedFeatureWrapper {
     }
 }
 
+/// A placed feature together with the name it is placed under, for the features a generator
+/// places on top of the ones its biomes list. Cheap to clone.
+#[derive(Clone)]
+pub struct NamedPlacedFeature {
+    name: String,
+    feature: Arc<PlacedFeatureWrapper>,
+}
+
+impl NamedPlacedFeature {
+    /// Vanilla's placed feature called `name`, with or without the `minecraft:` namespace.
+    pub fn vanilla(name: &str) -> Option<Self> {
+        let name = name.strip_prefix("minecraft:").unwrap_or(name);
+        PLACED_FEATURES.contains_key(name).then(|| Self {
+            name: name.to_string(),
+            feature: Arc::new(PlacedFeatureWrapper::Named(name.to_string())),
+        })
+    }
+
+    /// `feature` placed under `name`, e.g. one read from a datapack. The `minecraft:biome`
+    /// placement only lets through the features biomes list, so `feature` shouldn't use it.
+    pub fn new(name: impl Into<String>, feature: PlacedFeature) -> Self {
+        Self {
+            name: name.into(),
+            feature: Arc::new(PlacedFeatureWrapper::Direct(Box::new(feature))),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn feature(&self) -> &PlacedFeature {
+        self.feature.get()
+    }
+}
+
 #[derive(Deserialize)]
 pub struct PlacedFeature {
     /// The name of the configuired feature
@@ -63,8 +100,7 @@ impl PlacedFeature {
     #[expect(clippy::too_many_arguments)]
     pub fn generate(
         &self,
-        chunk: &mut ProtoChunk<'_>,
-        level: &Arc<Level>,
+        chunk: &mut ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         min_y: i8,
         height: u16,
@@ -103,7 +139,6 @@ impl PlacedFeature {
         for pos in stream {
             if feature.generate(
                 chunk,
-                level,
                 block_registry,
                 min_y,
                 height,
@@ -150,14 +185,14 @@ pub enum PlacementModifier {
     #[serde(rename = "minecraft:random_offset")]
     RandomOffset(RandomOffsetPlacementModifier),
     #[serde(rename = "minecraft:fixed_placement")]
-    FixedPlacement,
+    FixedPlacement(FixedPlacementModifier),
 }
 
 impl PlacementModifier {
     #[expect(clippy::too_many_arguments)]
     pub fn get_positions(
         &self,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         min_y: i8,
         height: u16,
@@ -200,7 +235,7 @@ impl PlacementModifier {
             }
             PlacementModifier::InSquare(_) => SquarePlacementModifier::get_positions(random, pos),
             PlacementModifier::RandomOffset(modifier) => modifier.get_positions(random, pos),
-            PlacementModifier::FixedPlacement => Box::new(iter::empty()),
+            PlacementModifier::FixedPlacement(modifier) => modifier.get_positions(pos),
         }
     }
 }
@@ -254,7 +289,7 @@ pub struct EnvironmentScanPlacementModifier {
 impl EnvironmentScanPlacementModifier {
     pub fn get_positions(
         &self,
-        chunk: &ProtoChunk<'_>,
+        chunk: &ProtoChunkRegion<'_, '_>,
         block_registry: &dyn BlockRegistryExt,
         pos: BlockPos,
     ) -> Box<dyn Iterator<Item = BlockPos>> {
@@ -317,7 +352,7 @@ impl CountOnEveryLayerPlacementModifier {
     pub fn get_positions(
         &self,
         random: &mut RandomGenerator,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         pos: BlockPos,
     ) -> Box<dyn Iterator<Item = BlockPos>> {
         let mut positions = Vec::new(); // Using a Vec to collect results, analogous to Stream.builder()
@@ -347,7 +382,7 @@ impl CountOnEveryLayerPlacementModifier {
         Box::new(positions.into_iter())
     }
 
-    fn find_pos(chunk: &ProtoChunk, x: i32, y: i32, z: i32, target_y: i32) -> i32 {
+    fn find_pos(chunk: &ProtoChunkRegion, x: i32, y: i32, z: i32, target_y: i32) -> i32 {
         let mut mutable_pos = BlockPos::new(x, y, z);
         let mut found_count = 0;
         let mut current_block_state = chunk.get_block_state(&mutable_pos.0);
@@ -387,7 +422,7 @@ impl ConditionalPlacementModifier for BlockFilterPlacementModifier {
         &self,
         block_registry: &dyn BlockRegistryExt,
         _feature: &str,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
@@ -408,7 +443,7 @@ impl ConditionalPlacementModifier for SurfaceThresholdFilterPlacementModifier {
         &self,
         _block_registry: &dyn BlockRegistryExt,
         _feature: &str,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
@@ -430,7 +465,7 @@ impl ConditionalPlacementModifier for RarityFilterPlacementModifier {
         &self,
         _block_registry: &dyn BlockRegistryExt,
         _feature: &str,
-        _chunk: &ProtoChunk,
+        _chunk: &ProtoChunkRegion,
         random: &mut RandomGenerator,
         _pos: BlockPos,
     ) -> bool {
@@ -438,6 +473,29 @@ impl ConditionalPlacementModifier for RarityFilterPlacementModifier {
     }
 }
 
+/// Places the feature at the given positions, each in the chunk it lies in.
+#[derive(Deserialize)]
+pub struct FixedPlacementModifier {
+    positions: Vec<Vector3<i32>>,
+}
+
+impl FixedPlacementModifier {
+    pub fn get_positions(&self, pos: BlockPos) -> Box<dyn Iterator<Item = BlockPos>> {
+        let chunk_x = section_coords::block_to_section(pos.0.x);
+        let chunk_z = section_coords::block_to_section(pos.0.z);
+        let positions: Vec<_> = self
+            .positions
+            .iter()
+            .filter(|position| {
+                section_coords::block_to_section(position.x) == chunk_x
+                    && section_coords::block_to_section(position.z) == chunk_z
+            })
+            .map(|position| BlockPos(*position))
+            .collect();
+        Box::new(positions.into_iter())
+    }
+}
+
 #[derive(Deserialize)]
 pub struct SquarePlacementModifier;
 
@@ -474,7 +532,7 @@ impl ConditionalPlacementModifier for SurfaceWaterDepthFilterPlacementModifier {
         &self,
         _block_registry: &dyn BlockRegistryExt,
         _feature: &str,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
@@ -493,7 +551,7 @@ impl ConditionalPlacementModifier for BiomePlacementModifier {
         &self,
         _block_registry: &dyn BlockRegistryExt,
         this_feature: &str,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         _random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool {
@@ -537,7 +595,7 @@ pub struct HeightmapPlacementModifier {
 impl HeightmapPlacementModifier {
     pub fn get_positions(
         &self,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         min_y: i8,
         _height: u16,
         _random: &mut RandomGenerator,
@@ -571,7 +629,7 @@ pub trait ConditionalPlacementModifier {
     fn get_positions(
         &self,
         block_registry: &dyn BlockRegistryExt,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         feature: &str,
         random: &mut RandomGenerator,
         pos: BlockPos,
@@ -587,7 +645,7 @@ pub trait ConditionalPlacementModifier {
         &self,
         block_registry: &dyn BlockRegistryExt,
         feature: &str,
-        chunk: &ProtoChunk,
+        chunk: &ProtoChunkRegion,
         random: &mut RandomGenerator,
         pos: BlockPos,
     ) -> bool;
@@ -0,0 +1,174 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use pumpkin_util::math::vector2::Vector2;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    chunk::{ChunkData, format::anvil::SingleChunkDataSerializer},
+    dimension::Dimension,
+    generation::Seed,
+};
+
+/// Bump whenever the generator places different blocks for the same seed, so that chunks cached
+/// by older versions are generated again.
+pub const GENERATOR_VERSION: u32 = 16;
+
+/// Generated chunks kept on disk apart from the world save, keyed by the seed, dimension,
+/// generator settings and [`GENERATOR_VERSION`] they were generated with, so that generating them
+/// again only has to read them back.
+pub struct GenerationCache {
+    folder: PathBuf,
+}
+
+impl GenerationCache {
+    /// Opens the cache in `root`, removing the chunks cached by other generator versions and the
+    /// ones of `dimension` cached with other settings. `settings` describes everything besides the
+    /// seed and dimension that changes the generated blocks, like the level type or the terrain
+    /// shape.
+    pub fn new(root: &Path, seed: Seed, dimension: &Dimension, settings: &str) -> Self {
+        let version = format!("v{GENERATOR_VERSION}");
+        remove_other_folders(root, &version);
+
+        // Custom dimensions are namespaced, and `:` can't be part of a path everywhere
+        let dimension_folder = dimension.name().replace(':', "_");
+        let seed_folder = root
+            .join(version)
+            .join(dimension_folder)
+            .join(seed.0.to_string());
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{dimension:?} {settings}"));
+        let settings: String = hasher.finalize()[..8]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect();
+        remove_other_folders(&seed_folder, &settings);
+
+        Self {
+            folder: seed_folder.join(settings),
+        }
+    }
+
+    fn chunk_path(&self, pos: Vector2<i32>) -> PathBuf {
+        self.folder.join(format!("c.{}.{}.nbt", pos.x, pos.y))
+    }
+
+    /// The chunk at `pos`, if it was cached before.
+    pub fn load(&self, pos: Vector2<i32>) -> Option<ChunkData> {
+        let bytes = fs::read(self.chunk_path(pos)).ok()?;
+        match ChunkData::from_bytes(bytes.into(), pos) {
+            Ok(mut chunk) => {
+                // It still has to be saved to the world
+                chunk.dirty = true;
+                Some(chunk)
+            }
+            Err(err) => {
+                log::warn!("Failed to read cached chunk {pos:?}: {err}");
+                None
+            }
+        }
+    }
+
+    /// Caches the freshly generated `chunk`.
+    pub fn store(&self, chunk: &ChunkData) {
+        if let Err(err) = self.write(chunk) {
+            log::warn!("Failed to cache chunk {:?}: {err}", chunk.position);
+        }
+    }
+
+    fn write(&self, chunk: &ChunkData) -> io::Result<()> {
+        let bytes = futures::executor::block_on(chunk.to_bytes()).map_err(io::Error::other)?;
+        fs::create_dir_all(&self.folder)?;
+        // Written next to the chunk first, so that it is never read half-written
+        let path = self.chunk_path(chunk.position);
+        let partial = path.with_extension("partial");
+        fs::write(&partial, bytes)?;
+        fs::rename(partial, path)
+    }
+}
+
+/// Removes everything in `folder` but `keep`.
+fn remove_other_folders(folder: &Path, keep: &str) {
+    let Ok(entries) = fs::read_dir(folder) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if entry.file_name() != keep
+            && let Err(err) = fs::remove_dir_all(entry.path())
+        {
+            log::warn!("Failed to remove stale generation cache {entry:?}: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fs, sync::Arc};
+
+    use pumpkin_data::noise_router::END_BASE_NOISE_ROUTER;
+    use pumpkin_util::math::vector2::Vector2;
+    use temp_dir::TempDir;
+
+    use super::GenerationCache;
+    use crate::{
+        dimension::{Dimension, DimensionConfig},
+        generation::{Seed, generator::empty_chunk, settings::BiomeSource},
+    };
+
+    #[test]
+    fn stale_versions_are_removed() {
+        let temp_dir = TempDir::new().unwrap();
+        let stale = temp_dir.path().join("v0");
+        fs::create_dir_all(stale.join("overworld/0")).unwrap();
+
+        let cache = GenerationCache::new(temp_dir.path(), Seed(0), &Dimension::Overworld, "");
+        assert!(!stale.exists());
+
+        let pos = Vector2::new(3, -2);
+        assert!(cache.load(pos).is_none());
+        cache.store(&empty_chunk(pos, &Dimension::Overworld));
+        let chunk = cache.load(pos).unwrap();
+        assert_eq!(chunk.position, pos);
+        assert!(chunk.dirty);
+
+        // Other seeds don't share chunks
+        let other = GenerationCache::new(temp_dir.path(), Seed(1), &Dimension::Overworld, "");
+        assert!(other.load(pos).is_none());
+    }
+
+    #[test]
+    fn other_settings_dont_share_chunks() {
+        let temp_dir = TempDir::new().unwrap();
+        let pos = Vector2::new(0, 0);
+        let open = |settings| {
+            GenerationCache::new(temp_dir.path(), Seed(0), &Dimension::Overworld, settings)
+        };
+        open("Normal").store(&empty_chunk(pos, &Dimension::Overworld));
+        assert!(open("Normal").load(pos).is_some());
+        assert!(open("Amplified").load(pos).is_none());
+        // The chunks of the old settings are gone for good
+        assert!(open("Normal").load(pos).is_none());
+
+        // Custom dimensions are cached under their name, whatever it takes to spell it
+        let moon = Dimension::Custom(Arc::new(DimensionConfig {
+            name: "test:moon",
+            based_on: Dimension::End,
+            min_y: 0,
+            height: 256,
+            has_skylight: false,
+            sea_level: 0,
+            noise_router: END_BASE_NOISE_ROUTER,
+            biome_source: BiomeSource::Dimension,
+        }));
+        let cache = GenerationCache::new(temp_dir.path(), Seed(0), &moon, "");
+        cache.store(&empty_chunk(pos, &moon));
+        assert!(cache.load(pos).is_some());
+        assert!(
+            cache
+                .folder
+                .starts_with(temp_dir.path().join("v16/test_moon/0"))
+        );
+    }
+}
@@ -0,0 +1,287 @@
+use pumpkin_util::math::vector2::Vector2;
+
+use super::VanillaGenerator;
+use crate::{
+    ProtoChunk,
+    generation::{
+        positions::chunk_pos,
+        structure::{
+            STRUCTURE_SETS, StructureKind,
+            placement::{RING_BIOME_SEARCH_RADIUS, StructurePlacementType},
+            scaled_biome, start_kind, structure_start,
+        },
+    },
+};
+
+/// Finds the chunk the structure called `structure_id` (like `minecraft:village_plains`) starts
+/// in closest to the block column `from`, searching up to `max_radius` chunks away from it.
+///
+/// Only the placement of the structures and the biomes they need are looked at, no terrain is
+/// generated. Like vanilla `/locate`, structures spread over a grid of regions are searched ring
+/// by ring of regions, taking the closest of the first ring with any, which may now and then
+/// miss one slightly closer in the ring after it.
+pub fn locate_nearest_structure(
+    generator: &VanillaGenerator,
+    structure_id: &str,
+    from: Vector2<i32>,
+    max_radius: u32,
+) -> Option<Vector2<i32>> {
+    let structure_id = structure_id
+        .strip_prefix("minecraft:")
+        .unwrap_or(structure_id);
+    let kind = StructureKind::from_name(structure_id)?;
+    let from_chunk = chunk_pos::to_chunk_pos(&from);
+    let mut chunk = noise_chunk(generator, from_chunk);
+    let radius = max_radius as i32;
+    let in_range = |start: Vector2<i32>| {
+        (start.x - from_chunk.x).abs() <= radius && (start.y - from_chunk.y).abs() <= radius
+    };
+    let distance_squared = |start: Vector2<i32>| {
+        let x = (chunk_pos::start_block_x(&start) - from.x) as i64;
+        let z = (chunk_pos::start_block_z(&start) - from.y) as i64;
+        x * x + z * z
+    };
+
+    let mut nearest: Option<(i64, Vector2<i32>)> = None;
+    for (set_name, set) in STRUCTURE_SETS.iter() {
+        if !set.structures.iter().any(|entry| {
+            entry
+                .structure
+                .strip_prefix("minecraft:")
+                .unwrap_or(&entry.structure)
+                == structure_id
+        }) {
+            continue;
+        }
+        let starts_here = |chunk: &mut ProtoChunk, start: Vector2<i32>| {
+            in_range(start)
+                && start_kind(
+                    set_name,
+                    chunk,
+                    &generator.dimension,
+                    generator.biome_scale,
+                    start,
+                ) == Some(kind)
+        };
+
+        let found = match &set.placement.r#type {
+            StructurePlacementType::RandomSpread(spread) => {
+                let spaced = generator
+                    .structure_spacing
+                    .get(set_name)
+                    .map(|spacing| spread.with_spacing(*spacing));
+                let spread = spaced.as_ref().unwrap_or(spread);
+                let seed = generator.random_config.seed as i64;
+                let rings = max_radius.div_ceil(spread.spacing as u32) as i32;
+                (0..=rings).find_map(|ring| {
+                    let mut found: Option<(i64, Vector2<i32>)> = None;
+                    for region_x in -ring..=ring {
+                        for region_z in -ring..=ring {
+                            if region_x.abs() != ring && region_z.abs() != ring {
+                                continue;
+                            }
+                            let region_chunk = Vector2::new(
+                                from_chunk.x + region_x * spread.spacing,
+                                from_chunk.y + region_z * spread.spacing,
+                            );
+                            let start =
+                                spread.get_start_chunk(seed, region_chunk, set.placement.salt);
+                            let distance = distance_squared(start);
+                            if found.is_none_or(|(nearest, _)| distance < nearest)
+                                && starts_here(&mut chunk, start)
+                            {
+                                found = Some((distance, start));
+                            }
+                        }
+                    }
+                    found
+                })
+            }
+            StructurePlacementType::ConcentricRings(_) => {
+                let Some(ring_starts) = generator.terrain_cache.ring_starts.get(set_name) else {
+                    continue;
+                };
+                // Nearest first by where they are on the rings, which is at most the biome
+                // search radius off from where they start
+                let mut by_distance: Vec<_> = (0..ring_starts.len())
+                    .map(|index| {
+                        let distance =
+                            (distance_squared(ring_starts.ring_position(index)) as f64).sqrt();
+                        (distance, index)
+                    })
+                    .collect();
+                by_distance.sort_by(|a, b| a.0.total_cmp(&b.0));
+                let margin = (RING_BIOME_SEARCH_RADIUS + 16) as f64;
+                let mut found: Option<(i64, Vector2<i32>)> = None;
+                for (ring_distance, index) in by_distance {
+                    if found.is_some_and(|(nearest, _)| {
+                        ring_distance - margin > (nearest as f64).sqrt()
+                    }) {
+                        break;
+                    }
+                    let start = ring_starts.start(
+                        index,
+                        &mut scaled_biome(&mut chunk, &generator.dimension, generator.biome_scale),
+                    );
+                    let distance = distance_squared(start);
+                    if found.is_none_or(|(nearest, _)| distance < nearest)
+                        && starts_here(&mut chunk, start)
+                    {
+                        found = Some((distance, start));
+                    }
+                }
+                found
+            }
+        };
+        if let Some((distance, start)) = found
+            && nearest.is_none_or(|(nearest, _)| distance < nearest)
+        {
+            nearest = Some((distance, start));
+        }
+    }
+    nearest.map(|(_, start)| start)
+}
+
+/// The structure starting in the chunk at `pos`, if any, or the first one by the name of its
+/// structure set if several do.
+///
+/// No terrain is generated: the placement of the structures and the biomes they need are looked
+/// at, and only a structure that does start in the chunk is laid out, to tell whether it fits
+/// there. So it's the structure a full generation would place, at a fraction of the cost.
+pub fn structure_at(generator: &VanillaGenerator, pos: Vector2<i32>) -> Option<StructureKind> {
+    let mut chunk = noise_chunk(generator, pos);
+    STRUCTURE_SETS.keys().find_map(|set_name| {
+        let start = structure_start(
+            set_name,
+            &mut chunk,
+            &generator.dimension,
+            generator.biome_scale,
+            pos,
+        )?;
+        Some(start.kind)
+    })
+}
+
+/// A chunk of `generator` at `pos` which only samples the noises of the world.
+fn noise_chunk(generator: &VanillaGenerator, pos: Vector2<i32>) -> ProtoChunk<'_> {
+    let mut chunk = ProtoChunk::with_sea_level(
+        pos,
+        &generator.base_router,
+        &generator.random_config,
+        &generator.generation_settings,
+        &generator.terrain_cache,
+        generator.default_block,
+        generator.sea_level,
+    );
+    chunk.set_biome_source(generator.biome_source);
+    chunk.set_structure_spacing(&generator.structure_spacing);
+    chunk
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::{locate_nearest_structure, structure_at};
+    use crate::{
+        ProtoChunk,
+        dimension::Dimension,
+        generation::{
+            Seed,
+            generator::{GeneratorInit, VanillaGenerator, tests::BlockRegistry},
+            positions::chunk_pos,
+            structure::{
+                STRUCTURE_SETS, StructureKind, start_kind, structure_start,
+                structures::village::VillageType,
+            },
+        },
+    };
+
+    #[test]
+    fn nearest_village_and_stronghold() {
+        let generator =
+            VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+        let from = Vector2::new(0, 0);
+        let village = locate_nearest_structure(&generator, "minecraft:village_plains", from, 200)
+            .expect("a plains village near spawn");
+
+        // No plains village starts any closer
+        let mut chunk = ProtoChunk::new(
+            from,
+            &generator.base_router,
+            &generator.random_config,
+            &generator.generation_settings,
+            &generator.terrain_cache,
+            generator.default_block,
+        );
+        let distance_squared = |start: Vector2<i32>| {
+            let x = chunk_pos::start_block_x(&start) - from.x;
+            let z = chunk_pos::start_block_z(&start) - from.y;
+            x * x + z * z
+        };
+        let reach = village.x.abs().max(village.y.abs());
+        for x in -reach..=reach {
+            for z in -reach..=reach {
+                let start = Vector2::new(x, z);
+                if distance_squared(start) < distance_squared(village) {
+                    assert_ne!(
+                        start_kind("villages", &mut chunk, &Dimension::Overworld, 1, start),
+                        Some(StructureKind::Village(VillageType::Plains)),
+                        "{start:?} is closer than {village:?}"
+                    );
+                }
+            }
+        }
+
+        // The innermost stronghold, which didn't find any preferred biome around it to move to
+        assert_eq!(
+            locate_nearest_structure(&generator, "stronghold", from, 200),
+            Some(Vector2::new(-13, -106))
+        );
+        assert_eq!(
+            locate_nearest_structure(&generator, "stronghold", from, 50),
+            None
+        );
+        assert_eq!(
+            locate_nearest_structure(&generator, "mansion", from, 200),
+            None
+        );
+    }
+
+    #[test]
+    fn structures_are_found_without_generating() {
+        let generator =
+            VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+        // The ocean monument closest to spawn
+        let monument = Vector2::new(15, -84);
+        assert_eq!(
+            structure_at(&generator, monument),
+            Some(StructureKind::OceanMonument)
+        );
+
+        // Like the structures placed when generating the chunks around it
+        let mut chunk = ProtoChunk::new(
+            monument,
+            &generator.base_router,
+            &generator.random_config,
+            &generator.generation_settings,
+            &generator.terrain_cache,
+            generator.default_block,
+        );
+        for x in monument.x - 8..monument.x + 8 {
+            for z in monument.y - 8..monument.y + 8 {
+                let pos = Vector2::new(x, z);
+                let placed = STRUCTURE_SETS.keys().find_map(|set_name| {
+                    structure_start(set_name, &mut chunk, &Dimension::Overworld, 1, pos)
+                });
+                assert_eq!(
+                    structure_at(&generator, pos),
+                    placed.map(|start| start.kind),
+                    "at {pos:?}"
+                );
+            }
+        }
+    }
+}
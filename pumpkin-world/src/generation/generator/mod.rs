@@ -1,137 +1,1155 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::iter::{self, Enumerate, Map, RepeatN, repeat_n};
-use std::sync::Arc;
+use std::mem;
+use std::num::NonZeroU8;
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, Ordering},
+};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use crossbeam::channel::{Receiver, Sender};
-use crossbeam::deque;
-use itertools::multizip;
-use pumpkin_data::BlockState;
+use crossbeam::channel::{Receiver, Sender, select};
+use crossbeam::deque::{Injector, Steal, Stealer, Worker};
+use pumpkin_config::{
+    BASIC_CONFIG,
+    chunk::{ChunkConfig, StructureSpacing, TerrainShape},
+};
+use pumpkin_data::chunk::{Biome, ChunkStatus};
 use pumpkin_data::noise_router::{
-    END_BASE_NOISE_ROUTER, NETHER_BASE_NOISE_ROUTER, OVERWORLD_BASE_NOISE_ROUTER,
+    BaseNoiseRouters, END_BASE_NOISE_ROUTER, NETHER_BASE_NOISE_ROUTER,
+    OVERWORLD_AMPLIFIED_BASE_NOISE_ROUTER, OVERWORLD_BASE_NOISE_ROUTER,
 };
-use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+use pumpkin_data::{Block, BlockState};
+use pumpkin_util::math::{position::BlockPos, vector2::Vector2, vector3::Vector3};
+use thiserror::Error;
 
 use super::{
-    biome_coords, noise::router::proto_noise_router::ProtoNoiseRouters,
-    settings::gen_settings_from_dimension,
+    feature::decoration::DecorationStep,
+    light::LightRegion,
+    noise::router::custom_router::NoiseSettings,
+    noise::router::{
+        proto_noise_router::{InvalidNoiseRouter, ProtoNoiseRouters},
+        terrain_shape::{InvalidTerrainShape, reshape_terrain},
+    },
+    ore_placer::{OreConfig, OrePlacer},
+    proto_chunk_region::ProtoChunkRegion,
+    settings::{
+        BiomeSource, GENERATION_SETTINGS, GenerationSettings, GenerationShapeConfig,
+        GeneratorSetting, WorldType,
+    },
+    structure::{STRUCTURE_SETS, StructureKind, placement::StructurePlacementType, scaled_biome},
+    tree_placer::{BiomeTrees, TreePlacer},
+    vegetation_placer::{PlantPatch, VegetationPlacer},
 };
+use crate::block::entities::chest::ChestBlockEntity;
 use crate::chunk::format::LightContainer;
 use crate::generation::proto_chunk::TerrainCache;
-use crate::level::{ChunkRequest, Level};
+use crate::level::Level;
 use crate::world::BlockRegistryExt;
 use crate::{chunk::ChunkLight, dimension::Dimension};
 use crate::{
-    chunk::{
-        ChunkData, ChunkSections, SubChunk,
-        palette::{BiomePalette, BlockPalette},
-    },
-    generation::{GlobalRandomConfig, Seed, proto_chunk::ProtoChunk},
+    chunk::{ChunkData, ChunkSections, SubChunk, palette::BlockPalette},
+    generation::{GlobalRandomConfig, Seed, positions::chunk_pos, proto_chunk::ProtoChunk},
 };
 
-pub trait GeneratorInit {
-    fn new(seed: Seed, dimension: Dimension) -> Self;
+mod cache;
+mod locate;
+mod spawn;
+mod superflat;
+#[cfg(test)]
+pub(crate) mod tests;
+mod timings;
+mod void;
+
+pub use cache::{GENERATOR_VERSION, GenerationCache};
+pub use locate::{locate_nearest_structure, structure_at};
+pub use spawn::find_spawn_point;
+pub use superflat::SuperflatGenerator;
+pub use timings::{DEFAULT_SLOW_CHUNK_THRESHOLD, GenerationTimings, StageTimings};
+pub use void::VoidGenerator;
+
+/// Why a generator can't be created for a dimension.
+#[derive(Error, Debug)]
+pub enum GeneratorError {
+    #[error("No generation settings for {0:?}")]
+    MissingSettings(GeneratorSetting),
+    #[error("Invalid noise router: {0}")]
+    InvalidNoiseRouter(#[from] InvalidNoiseRouter),
+    #[error("Unknown default block {block} with properties {properties:?}")]
+    UnknownDefaultBlock {
+        block: &'static str,
+        properties: HashMap<String, String>,
+    },
+    #[error(
+        "The terrain from y {shape_min_y} to {shape_top_y} doesn't fit into the sections of \
+         {dimension:?}, which go from y {min_y} to {top_y}"
+    )]
+    ShapeOutsideDimension {
+        dimension: Dimension,
+        shape_min_y: i32,
+        shape_top_y: i32,
+        min_y: i32,
+        top_y: i32,
+    },
+    #[error("Can't reshape the terrain: {0}")]
+    InvalidTerrainShape(#[from] InvalidTerrainShape),
+    #[error("Can't space out the structures of {set_name} like {spacing:?}: {reason}")]
+    InvalidStructureSpacing {
+        set_name: String,
+        spacing: StructureSpacing,
+        reason: &'static str,
+    },
 }
 
-pub trait WorldGenerator {
-    fn request_load(&self, request: LoadRequest);
+pub trait GeneratorInit: Sized {
+    /// Creates the generator of `dimension`. Its features ask `block_registry` whether the blocks
+    /// they place can stay where they are.
+    fn new(
+        seed: Seed,
+        dimension: Dimension,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        Self::with_world_type(seed, dimension, WorldType::Default, block_registry)
+    }
+
+    fn with_world_type(
+        seed: Seed,
+        dimension: Dimension,
+        world_type: WorldType,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        Self::with_random_source(
+            seed,
+            dimension,
+            world_type,
+            BiomeSource::Dimension,
+            false,
+            block_registry,
+        )
+    }
+
+    /// Like [`GeneratorInit::with_world_type`], but with the biomes coming from `biome_source`.
+    /// Generators that pick their biomes themselves, like the superflat one, ignore it.
+    fn with_biome_source(
+        seed: Seed,
+        dimension: Dimension,
+        world_type: WorldType,
+        biome_source: BiomeSource,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        Self::with_random_source(
+            seed,
+            dimension,
+            world_type,
+            biome_source,
+            false,
+            block_registry,
+        )
+    }
+
+    /// Like [`GeneratorInit::with_biome_source`], but deriving the randoms of the world from
+    /// `seed` with the legacy algorithm if `legacy_random` is set, see [`GlobalRandomConfig::new`].
+    /// Generators that don't use any randoms ignore it.
+    fn with_random_source(
+        seed: Seed,
+        dimension: Dimension,
+        world_type: WorldType,
+        biome_source: BiomeSource,
+        legacy_random: bool,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError>;
+
+    /// Like [`GeneratorInit::new`], but shaping the terrain of `dimension` like the noise settings
+    /// of a datapack, see [`load_noise_settings`]. Generators that don't shape their terrain with
+    /// noise ignore them.
+    ///
+    /// [`load_noise_settings`]: crate::generation::noise::router::custom_router::load_noise_settings
+    fn with_noise_settings(
+        seed: Seed,
+        dimension: Dimension,
+        _settings: &NoiseSettings,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        Self::new(seed, dimension, block_registry)
+    }
+
+    /// Fills the terrain with `default_block` instead of the default block of the dimension, or
+    /// with that one again for `None`, e.g. with glass to see the caves through. The surface rules
+    /// only cover it if `surface_rules` is set. Generators without a default block ignore it.
+    fn with_default_block(
+        self,
+        _default_block: Option<&'static BlockState>,
+        _surface_rules: bool,
+    ) -> Self {
+        self
+    }
 }
 
+#[async_trait]
+pub trait WorldGenerator: Send + Sync + 'static {
+    /// Generates every chunk covered by `request`, handing each one to `level` as soon as it is
+    /// finished.
+    fn request_load(&self, level: &Arc<Level>, request: LoadRequest);
+
+    /// Like [`WorldGenerator::request_load`], but on a blocking thread of the runtime, returning
+    /// once every chunk is handed to `level`. Dropping the future doesn't stop the generation,
+    /// [`LoadRequest::cancel`] does.
+    async fn load(self: Arc<Self>, level: Arc<Level>, request: LoadRequest) {
+        tokio::task::spawn_blocking(move || self.request_load(&level, request))
+            .await
+            .expect("Generator panicked");
+    }
+
+    /// The chunk the structure called `structure_id` starts in closest to the block column
+    /// `from`, up to `max_radius` chunks away, or `None` if this generator doesn't place it.
+    fn locate_nearest_structure(
+        &self,
+        _structure_id: &str,
+        _from: Vector2<i32>,
+        _max_radius: u32,
+    ) -> Option<Vector2<i32>> {
+        None
+    }
+
+    /// The structure starting in the chunk at `pos`, without generating it, or `None` if none
+    /// does or this generator doesn't place any.
+    fn structure_at(&self, _pos: Vector2<i32>) -> Option<StructureKind> {
+        None
+    }
+
+    /// How long the chunks generated so far took, or `None` if this generator doesn't keep track.
+    fn timings(&self) -> Option<GenerationTimings> {
+        None
+    }
+
+    /// Starts generating several requests in a row on the current thread, see
+    /// [`GenerationSession`]. By default, every request is generated on its own.
+    fn session(&self) -> Box<dyn GenerationSession + '_> {
+        Box::new(SingleRequests(self))
+    }
+}
+
+/// Generates requests one after the other, like the tiles a generator thread works through.
+/// Requests next to each other border the same chunks, so a session may keep what it prepared
+/// around one request for the next instead of generating it again.
+pub trait GenerationSession {
+    /// Like [`WorldGenerator::request_load`].
+    fn request_load(&mut self, level: &Arc<Level>, request: LoadRequest);
+}
+
+/// The session of a generator that doesn't keep anything between requests.
+struct SingleRequests<'a, G: ?Sized>(&'a G);
+
+impl<G: WorldGenerator + ?Sized> GenerationSession for SingleRequests<'_, G> {
+    fn request_load(&mut self, level: &Arc<Level>, request: LoadRequest) {
+        self.0.request_load(level, request);
+    }
+}
+
+/// Clones share the noise routers and caches of the original, which are only read while
+/// generating, so handing a clone to every thread is cheap.
+#[derive(Clone)]
 pub struct VanillaGenerator {
-    random_config: GlobalRandomConfig,
-    base_router: ProtoNoiseRouters,
+    random_config: Arc<GlobalRandomConfig>,
+    /// The router the generator was created with, before [`VanillaGenerator::set_terrain_shape`]
+    /// reshaped it.
+    noise_router: BaseNoiseRouters,
+    base_router: Arc<ProtoNoiseRouters>,
     dimension: Dimension,
+    generation_settings: Arc<GenerationSettings>,
+    /// See [`VanillaGenerator::set_sea_level`].
+    sea_level: i32,
+    /// See [`WorldType::biome_scale`].
+    biome_scale: i32,
+    biome_source: BiomeSource,
 
-    terrain_cache: TerrainCache,
+    terrain_cache: Arc<TerrainCache>,
 
+    /// The block the surface rules cover, usually the one of the settings.
     default_block: &'static BlockState,
+    /// See [`GeneratorInit::with_default_block`].
+    fill_block: &'static BlockState,
+    /// Ores placed on top of the ones of the vanilla features.
+    ores: OrePlacer,
+    /// Trees grown on top of the ones of the vanilla features.
+    trees: TreePlacer,
+    /// Grass and flowers grown on top of the ones of the vanilla features.
+    vegetation: VegetationPlacer,
+    /// See [`VanillaGenerator::set_structure_spacing`].
+    structure_spacing: BTreeMap<String, StructureSpacing>,
+    /// See [`VanillaGenerator::set_lighting`].
+    lighting: bool,
+
+    /// See [`VanillaGenerator::set_slow_chunk_threshold`].
+    slow_chunk_threshold: Duration,
+    timings: Arc<Mutex<GenerationTimings>>,
+
+    /// Tells the features where their blocks can stay.
+    block_registry: Arc<dyn BlockRegistryExt>,
 }
 
 impl GeneratorInit for VanillaGenerator {
-    fn new(seed: Seed, dimension: Dimension) -> Self {
-        let random_config = GlobalRandomConfig::new(seed.0, false);
-
+    fn with_random_source(
+        seed: Seed,
+        dimension: Dimension,
+        world_type: WorldType,
+        biome_source: BiomeSource,
+        legacy_random: bool,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
         // TODO: The generation settings contains (part of?) the noise routers too; do we keep the separate or
         // use only the generation settings?
-        let base = match dimension {
-            Dimension::Overworld => OVERWORLD_BASE_NOISE_ROUTER,
-            Dimension::Nether => NETHER_BASE_NOISE_ROUTER,
-            Dimension::End => END_BASE_NOISE_ROUTER,
+        let base = match (&dimension, world_type) {
+            (Dimension::Overworld, WorldType::Amplified) => &OVERWORLD_AMPLIFIED_BASE_NOISE_ROUTER,
+            (Dimension::Overworld, WorldType::Default | WorldType::LargeBiomes) => {
+                &OVERWORLD_BASE_NOISE_ROUTER
+            }
+            (Dimension::Nether, _) => &NETHER_BASE_NOISE_ROUTER,
+            (Dimension::End, _) => &END_BASE_NOISE_ROUTER,
+            (Dimension::Custom(config), _) => &config.noise_router,
         };
-        let terrain_cache = TerrainCache::from_random(&random_config);
-        let generation_settings = gen_settings_from_dimension(&dimension);
+        let settings = vanilla_settings(&dimension, world_type)?;
+        let overworld = dimension == Dimension::Overworld;
+        let mut generator = Self::build(
+            seed,
+            dimension.clone(),
+            settings,
+            base,
+            legacy_random,
+            block_registry,
+        )?;
+        if overworld {
+            generator.biome_scale = world_type.biome_scale();
+        }
+        // Custom dimensions bring their own biomes, unless they are overridden too
+        if biome_source != BiomeSource::Dimension {
+            generator.biome_source = biome_source;
+        }
+        Ok(generator)
+    }
 
-        let default_block = generation_settings.default_block.get_state();
-        let base_router = ProtoNoiseRouters::generate(&base, &random_config);
-        Self {
-            random_config,
-            base_router,
+    fn with_noise_settings(
+        seed: Seed,
+        dimension: Dimension,
+        settings: &NoiseSettings,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        // Like the built in settings, the legacy random is left to `with_random_source`
+        let mut generator = Self::build(
+            seed,
+            dimension,
+            settings.generation_settings.clone(),
+            &settings.noise_router,
+            false,
+            block_registry,
+        )?;
+        generator.sea_level = settings.generation_settings.sea_level;
+        Ok(generator)
+    }
+
+    fn with_default_block(
+        mut self,
+        default_block: Option<&'static BlockState>,
+        surface_rules: bool,
+    ) -> Self {
+        let dimension_block = self.generation_settings.default_block.get_state();
+        self.fill_block = default_block.unwrap_or(dimension_block);
+        self.default_block = if surface_rules {
+            self.fill_block
+        } else {
+            dimension_block
+        };
+        self
+    }
+}
+
+/// The passes a chunk has gone through while a request is being generated, in pipeline order.
+///
+/// A chunk is only advanced as far as its role in the request needs: the chunks bordering a
+/// finished one are decorated, the ones further out only get their terrain or biomes.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum GenerationStage {
+    Empty,
+    Biomes,
+    /// The noise, surface and carver passes have run; the terrain shape is complete.
+    Terrain,
+    /// The chunk is decorated. What its features placed in it and in its neighbors is kept
+    /// apart until each of them is finished.
+    Features,
+    Full,
+}
+
+/// The passes of the vanilla generator, in the order they run on a chunk. See
+/// [`VanillaGenerator::debug_generate`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum GenerationPass {
+    Biomes,
+    /// Shapes the terrain out of the default block and the fluids, without a surface yet.
+    Noise,
+    Surface,
+    Carvers,
+    /// The ores, structures, features, trees and vegetation.
+    Features,
+}
+
+impl VanillaGenerator {
+    /// Creates a generator shaping its terrain with `base` instead of the built in router of
+    /// `dimension`, e.g. one loaded with [`parse_noise_router`]. Everything else, like the height
+    /// and the biomes, still comes from `dimension`; [`GeneratorInit::with_noise_settings`] takes
+    /// the rest of the settings along with the router.
+    ///
+    /// [`parse_noise_router`]: crate::generation::noise::router::custom_router::parse_noise_router
+    pub fn with_noise_router(
+        seed: Seed,
+        dimension: Dimension,
+        base: &BaseNoiseRouters,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        let settings = vanilla_settings(&dimension, WorldType::Default)?;
+        Self::build(seed, dimension, settings, base, false, block_registry)
+    }
+
+    fn build(
+        seed: Seed,
+        dimension: Dimension,
+        generation_settings: Arc<GenerationSettings>,
+        base: &BaseNoiseRouters,
+        legacy_random: bool,
+        block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        let default_block = &generation_settings.default_block;
+        let default_block =
+            default_block
+                .try_get_state()
+                .ok_or_else(|| GeneratorError::UnknownDefaultBlock {
+                    block: default_block.name.name,
+                    properties: default_block.properties.clone().unwrap_or_default(),
+                })?;
+        ProtoNoiseRouters::validate(base)?;
+        check_shape(&generation_settings.shape, &dimension)?;
+
+        let random_config = GlobalRandomConfig::new(seed.0, legacy_random);
+        let terrain_cache = TerrainCache::from_random(&random_config);
+        let base_router = ProtoNoiseRouters::generate(base, &random_config);
+        Ok(Self {
+            random_config: Arc::new(random_config),
+            noise_router: base.clone(),
+            base_router: Arc::new(base_router),
+            sea_level: match &dimension {
+                Dimension::Custom(config) => config.sea_level,
+                _ => generation_settings.sea_level,
+            },
+            generation_settings,
+            biome_scale: 1,
+            biome_source: match &dimension {
+                Dimension::Custom(config) => config.biome_source,
+                _ => BiomeSource::Dimension,
+            },
             dimension,
-            terrain_cache,
+            terrain_cache: Arc::new(terrain_cache),
             default_block,
+            fill_block: default_block,
+            ores: OrePlacer::empty(),
+            trees: TreePlacer::empty(),
+            vegetation: VegetationPlacer::empty(),
+            structure_spacing: BTreeMap::new(),
+            lighting: true,
+            slow_chunk_threshold: DEFAULT_SLOW_CHUNK_THRESHOLD,
+            timings: Arc::default(),
+            block_registry,
+        })
+    }
+
+    /// Fills the oceans and aquifers of every chunk generated from now on up to `sea_level`
+    /// instead of the sea level of the dimension, e.g. for a custom dimension. The surface,
+    /// structures and features follow the water.
+    pub fn set_sea_level(&mut self, sea_level: i32) {
+        self.sea_level = sea_level;
+    }
+
+    /// Reshapes the terrain of every chunk generated from now on like `shape` says, starting from
+    /// the router the generator was created with. Only routers shaped like vanilla's overworld
+    /// can be reshaped; the biomes follow the reshaped terrain.
+    pub fn set_terrain_shape(&mut self, shape: &TerrainShape) -> Result<(), GeneratorError> {
+        let router = reshape_terrain(&self.noise_router, shape)?;
+        self.base_router = Arc::new(ProtoNoiseRouters::generate(&router, &self.random_config));
+        // The structures placed in rings were looked up in the old biomes
+        self.terrain_cache = Arc::new(TerrainCache::from_random(&self.random_config));
+        Ok(())
+    }
+
+    /// Adds `ore` to every chunk generated from now on, on top of the ores vanilla places. See
+    /// [`ore_placer`](crate::generation::ore_placer) for the vanilla ores to start from.
+    pub fn register_ore(&mut self, ore: OreConfig) {
+        self.ores.register(ore);
+    }
+
+    /// Makes `trees` grow in every chunk generated from now on whose middle lies in `biome`, on
+    /// top of the trees vanilla grows. See [`tree_placer`](crate::generation::tree_placer) for the
+    /// vanilla trees to start from.
+    pub fn register_trees(&mut self, biome: &'static Biome, trees: BiomeTrees) {
+        self.trees.register(biome, trees);
+    }
+
+    /// Adds `patch` to the plants growing in every chunk generated from now on whose middle lies
+    /// in `biome`, on top of the plants vanilla grows. See
+    /// [`vegetation_placer`](crate::generation::vegetation_placer) for the vanilla plants to start
+    /// from.
+    pub fn register_plants(&mut self, biome: &'static Biome, patch: PlantPatch) {
+        self.vegetation.register(biome, patch);
+    }
+
+    /// Spaces out the structures of the set called `set_name`, like `villages`, like `spacing` says
+    /// in every chunk generated from now on, instead of like vanilla. Only sets spread over a grid
+    /// can be spaced out, and `separation` must leave room in the squares of the grid.
+    pub fn set_structure_spacing(
+        &mut self,
+        set_name: &str,
+        spacing: StructureSpacing,
+    ) -> Result<(), GeneratorError> {
+        let invalid = |reason| GeneratorError::InvalidStructureSpacing {
+            set_name: set_name.to_string(),
+            spacing,
+            reason,
+        };
+        let set = STRUCTURE_SETS
+            .get(set_name)
+            .ok_or_else(|| invalid("there is no such structure set"))?;
+        if !matches!(
+            set.placement.r#type,
+            StructurePlacementType::RandomSpread(_)
+        ) {
+            return Err(invalid("the set isn't spread over a grid"));
+        }
+        if spacing.separation < 0 || spacing.separation >= spacing.spacing {
+            return Err(invalid(
+                "the separation must be at least 0 and below the spacing",
+            ));
         }
+        self.structure_spacing.insert(set_name.to_string(), spacing);
+        Ok(())
     }
-}
 
-impl WorldGenerator for VanillaGenerator {
-    fn request_load(&self, requested: LoadRequest) {
-        let generation_settings = gen_settings_from_dimension(&self.dimension);
+    /// Whether the chunks generated from now on are lit, which they are by default. Tools only
+    /// looking at the terrain can skip the light passes, leaving every chunk completely dark; the
+    /// blocks stay the same.
+    pub fn set_lighting(&mut self, lighting: bool) {
+        self.lighting = lighting;
+    }
+
+    /// Logs every chunk taking longer than `threshold` to generate from now on, along with how long
+    /// each of its passes took. See [`DEFAULT_SLOW_CHUNK_THRESHOLD`] for the default.
+    pub fn set_slow_chunk_threshold(&mut self, threshold: Duration) {
+        self.slow_chunk_threshold = threshold;
+    }
 
-        let height: usize = match self.dimension {
-            Dimension::Overworld => 384,
-            Dimension::Nether | Dimension::End => 256,
+    /// How long the chunks generated so far by this generator and its clones took.
+    pub fn timings(&self) -> GenerationTimings {
+        *self.timings.lock().unwrap()
+    }
+
+    /// Resolves where the structures placed in rings around `region`, like the strongholds, start
+    /// ahead of generating its chunks, so the worker threads don't wait on each other doing it.
+    /// Those ring starts are the only cache the chunks share; the noises and biomes are sampled
+    /// for each chunk on its own. It never changes what gets generated and calling it again is
+    /// cheap.
+    pub fn prewarm(&self, region: &[Vector2<i32>]) {
+        let Some(&first) = region.first() else {
+            return;
         };
-        let sub_chunks = height / BlockPalette::SIZE;
-        let sections = (0..sub_chunks).map(|_| SubChunk::default()).collect();
-        let mut sections = ChunkSections::new(sections, generation_settings.shape.min_y as i32);
+        // Only samples the noises of the world
+        let mut chunk = ProtoChunk::with_sea_level(
+            first,
+            &self.base_router,
+            &self.random_config,
+            &self.generation_settings,
+            &self.terrain_cache,
+            self.default_block,
+            self.sea_level,
+        );
+        chunk.set_biome_source(self.biome_source);
+        let mut biome = scaled_biome(&mut chunk, &self.dimension, self.biome_scale);
+        for ring_starts in self.terrain_cache.ring_starts.values() {
+            ring_starts.resolve_near(region, STRUCTURE_STARTS_RADIUS as i32, &mut biome);
+        }
+    }
 
-        // These are just vanilla constants
-        let light_radius = requested.with_padding(1); //Light needs to propagate to adjacent chunks
-        let carver_radius = light_radius.with_padding(1); // Terrain shape needs to be complete in order to generate features
-        let biome_radius = carver_radius.with_padding(1); // Ishland couldn't find a reason but vanilla does this so ig yes
-        let structure_starts_radius = biome_radius.with_padding(8); // Chunks need to store a reference to nearby structures
-
-        multizip((
-            requested,
-            light_radius,
-            carver_radius,
-            biome_radius,
-            structure_starts_radius,
-        ))
-        .for_each(
-            |(requested, light_radius, carver_radius, biome_radius, structure_starts_radius)| {
-                todo!();
-            },
+    /// Adds the `timings` of the chunk at `pos` to the ones of the generator, logging it if it was
+    /// slow.
+    fn record_timings(&self, pos: Vector2<i32>, timings: StageTimings) {
+        let slow = timings.total() > self.slow_chunk_threshold;
+        if slow {
+            log::warn!(
+                "Chunk {pos:?} took {:?} to generate ({timings})",
+                timings.total()
+            );
+        }
+        let mut totals = self.timings.lock().unwrap();
+        totals.chunks += 1;
+        totals.slow_chunks += slow as u64;
+        totals.stages += timings;
+    }
+
+    /// Runs the whole pipeline for the single chunk at `pos` and returns it, without going through
+    /// a worker or storing the chunk in a level.
+    ///
+    /// The result only depends on the seed and the dimension of this generator.
+    pub fn generate_chunk(&self, pos: Vector2<i32>) -> ChunkData {
+        let mut proto_chunks = HashMap::new();
+        let mut timings = StageTimings::default();
+        let mut chunk = self
+            .advance(&mut proto_chunks, pos, GenerationStage::Full, &mut timings)
+            .expect("a new chunk can always be generated");
+        chunk.heightmap = chunk.calculate_heightmap();
+        self.record_timings(pos, timings);
+        chunk
+    }
+
+    /// Runs the passes on the chunk at `pos` up to and including `pass`, and returns it unfinished
+    /// to look into what each pass does, e.g. which one leaves an artifact behind.
+    ///
+    /// The chunk holds what it would when generated by [`Self::generate_chunk`] at that point, so
+    /// after [`GenerationPass::Features`] it also holds the features of its neighbors reaching
+    /// into it.
+    pub fn debug_generate(&self, pos: Vector2<i32>, pass: GenerationPass) -> ProtoChunk<'_> {
+        if pass == GenerationPass::Features {
+            let mut proto_chunks = HashMap::new();
+            let mut timings = StageTimings::default();
+            for neighbor in iter::once(pos).chain(RingIterator::new(pos, 1)) {
+                self.advance(
+                    &mut proto_chunks,
+                    neighbor,
+                    GenerationStage::Features,
+                    &mut timings,
+                );
+            }
+            let (mut proto_chunk, _) = proto_chunks
+                .remove(&pos)
+                .expect("the chunk has been decorated");
+            apply_decorations(&mut proto_chunk, &proto_chunks);
+            for (neighbor, _) in proto_chunks.into_values() {
+                neighbor.recycle();
+            }
+            return proto_chunk;
+        }
+
+        let mut proto_chunk = self.new_proto_chunk(pos);
+        proto_chunk.populate_scaled_biomes(&self.dimension, self.biome_scale);
+        if pass >= GenerationPass::Noise {
+            proto_chunk.populate_noise();
+        }
+        if pass >= GenerationPass::Surface {
+            proto_chunk.build_surface();
+        }
+        if pass >= GenerationPass::Carvers {
+            proto_chunk.carve(&self.dimension, self.biome_scale);
+        }
+        proto_chunk
+    }
+
+    /// Runs the passes the chunk at `pos` is still missing to reach `target`, returning it once it
+    /// is fully generated.
+    ///
+    /// A chunk is decorated once the terrain of all of its neighbors is complete, and finished
+    /// once all of them are decorated too, since their features may grow into it. Neighbors
+    /// missing from `proto_chunks` are generated as far as that.
+    ///
+    /// The chunks in `proto_chunks` only ever hold their terrain: the changes of each decoration
+    /// are kept with the chunk decorated and only applied to a copy of the chunks they reach while
+    /// it is finished. Every decoration thus sees the same terrain around it, and a chunk ends up
+    /// the same whichever request and in whichever order it is generated.
+    ///
+    /// How long each pass took, the ones of the neighbors included, is added to `timings`.
+    fn advance<'a>(
+        &'a self,
+        proto_chunks: &mut HashMap<Vector2<i32>, (ProtoChunk<'a>, GenerationStage)>,
+        pos: Vector2<i32>,
+        target: GenerationStage,
+        timings: &mut StageTimings,
+    ) -> Option<ChunkData> {
+        if proto_chunks
+            .get(&pos)
+            .is_some_and(|(_, stage)| *stage >= target)
+        {
+            return None;
+        }
+        let neighbors = RingIterator::new(pos, 1);
+        if target == GenerationStage::Full {
+            for neighbor in iter::once(pos).chain(neighbors) {
+                self.advance(proto_chunks, neighbor, GenerationStage::Features, timings);
+            }
+        } else if target == GenerationStage::Features {
+            for neighbor in neighbors {
+                self.advance(proto_chunks, neighbor, GenerationStage::Terrain, timings);
+            }
+        }
+
+        let (proto_chunk, stage) = proto_chunks
+            .entry(pos)
+            .or_insert_with(|| (self.new_proto_chunk(pos), GenerationStage::Empty));
+        let from = *stage;
+        *stage = target;
+
+        let start = Instant::now();
+        if from < GenerationStage::Biomes {
+            proto_chunk.populate_scaled_biomes(&self.dimension, self.biome_scale);
+            timings.biomes += start.elapsed();
+        }
+        if from < GenerationStage::Terrain && target >= GenerationStage::Terrain {
+            let start = Instant::now();
+            proto_chunk.populate_noise();
+            let noise = Instant::now();
+            proto_chunk.build_surface();
+            let surface = Instant::now();
+            proto_chunk.carve(&self.dimension, self.biome_scale);
+            timings.noise += noise - start;
+            timings.surface += surface - noise;
+            timings.carve += surface.elapsed();
+            timings.terrain_chunks += 1;
+        }
+        if from < GenerationStage::Features && target >= GenerationStage::Features {
+            let start = Instant::now();
+            self.decorate(proto_chunks, pos);
+            timings.features += start.elapsed();
+            timings.decorated_chunks += 1;
+        }
+        if target != GenerationStage::Full {
+            return None;
+        }
+
+        let (mut proto_chunk, stage) = proto_chunks
+            .remove(&pos)
+            .expect("the chunk has been decorated");
+        proto_chunk.record_changes();
+        apply_decorations(&mut proto_chunk, proto_chunks);
+        let mut chunk = self.finish_chunk(&proto_chunk);
+        if self.lighting {
+            let start = Instant::now();
+            self.light_chunk(&mut chunk, proto_chunks);
+            timings.light += start.elapsed();
+        } else {
+            chunk
+                .light_engine
+                .sky_light
+                .fill_with(|| LightContainer::new_empty(0));
+        }
+        // The neighbors still to be finished and lit see the terrain of the chunk only
+        proto_chunk.take_changes();
+        proto_chunks.insert(pos, (proto_chunk, stage));
+        Some(chunk)
+    }
+
+    /// Runs the ores, structures, features, trees and vegetation of the chunk at `pos`, all of its
+    /// neighbors holding their terrain, and keeps what they placed with the chunk instead of
+    /// leaving it in the region. See [`ProtoChunk::set_decoration`].
+    fn decorate<'a>(
+        &'a self,
+        proto_chunks: &mut HashMap<Vector2<i32>, (ProtoChunk<'a>, GenerationStage)>,
+        pos: Vector2<i32>,
+    ) {
+        let mut region = decoration_region(proto_chunks, pos);
+        region.record_changes();
+        region
+            .center_mut()
+            .place_structures(&self.dimension, self.biome_scale);
+        region.generate_features_and_structure(self.block_registry.as_ref());
+        region.generate_extra_features(
+            self.block_registry.as_ref(),
+            DecorationStep::UndergroundOres,
+            self.ores.ores(),
+        );
+        // The trees and plants of the biome in the middle of the chunk grow all over it
+        let biome = {
+            let center = region.center();
+            let x = chunk_pos::start_block_x(&pos) + 8;
+            let z = chunk_pos::start_block_z(&pos) + 8;
+            let y = center.top_block_height_exclusive(&Vector2::new(x, z));
+            center.get_biome_for_terrain_gen(&Vector3::new(x, y, z))
+        };
+        region.generate_extra_features(
+            self.block_registry.as_ref(),
+            DecorationStep::VegetalDecoration,
+            self.trees
+                .feature(biome)
+                .into_iter()
+                .chain(self.vegetation.features(biome)),
         );
+        let decoration = region.take_changes();
+        region.center_mut().set_decoration(decoration);
+    }
 
-        for y in 0..biome_coords::from_block(generation_settings.shape.height) {
-            let relative_y = y as usize;
-            let section_index = relative_y / BiomePalette::SIZE;
-            let relative_y = relative_y % BiomePalette::SIZE;
-            if let Some(section) = sections.sections.get_mut(section_index) {
-                for z in 0..BiomePalette::SIZE {
-                    for x in 0..BiomePalette::SIZE {
-                        let absolute_y =
-                            biome_coords::from_block(generation_settings.shape.min_y as i32)
-                                + y as i32;
-                        let biome =
-                            proto_chunk.get_biome(&Vector3::new(x as i32, absolute_y, z as i32));
-                        section.biomes.set(x, relative_y, z, biome.id);
-                    }
-                }
+    /// A chunk at `pos` which none of the passes have run on yet.
+    fn new_proto_chunk(&self, pos: Vector2<i32>) -> ProtoChunk<'_> {
+        let mut proto_chunk = ProtoChunk::with_sea_level(
+            pos,
+            &self.base_router,
+            &self.random_config,
+            &self.generation_settings,
+            &self.terrain_cache,
+            self.default_block,
+            self.sea_level,
+        );
+        proto_chunk.set_biome_source(self.biome_source);
+        proto_chunk.set_fill_block(self.fill_block);
+        proto_chunk.set_structure_spacing(&self.structure_spacing);
+        proto_chunk
+    }
+
+    /// Runs one step of `request`: the chunks of the `requested` ring are finished and stored in
+    /// `level`, after the rings around them have been advanced only as far as their role needs.
+    ///
+    /// Only light and features reach across chunk borders so far, so apart from the two rings
+    /// around the outermost chunks, the ones decorated and the ones holding the terrain around
+    /// them, chunks the request never finishes are left alone instead of being generated for
+    /// nothing.
+    ///
+    /// A single chunk can take a while, so cancellation is checked before each of them. Returns
+    /// `false` once the request is cancelled.
+    fn generate_ring<'a>(
+        &'a self,
+        level: &Arc<Level>,
+        request: &LoadRequest,
+        proto_chunks: &mut HashMap<Vector2<i32>, (ProtoChunk<'a>, GenerationStage)>,
+        rings: <LoadRequest as IntoIterator>::Item,
+        timings: &mut StageTimings,
+    ) -> bool {
+        let (requested, light_radius, carver_radius, biome_radius, _structure_starts_radius) =
+            rings;
+        // Every chunk lays out the structures reaching into it again from the noise alone, so the
+        // chunks they start in don't have to be generated for them
+        for pos in biome_radius.filter(|pos| request.contains(pos)) {
+            if request.is_cancelled() {
+                return false;
+            }
+            self.advance(proto_chunks, pos, GenerationStage::Biomes, timings);
+        }
+        for pos in carver_radius.filter(|pos| request.contains(pos)) {
+            if request.is_cancelled() {
+                return false;
+            }
+            self.advance(proto_chunks, pos, GenerationStage::Terrain, timings);
+        }
+        // These neighbors are decorated before the ring is finished, growing their features into
+        // it, and its light flows in from them
+        for pos in light_radius.filter(|pos| request.contains(pos)) {
+            if request.is_cancelled() {
+                return false;
+            }
+            self.advance(proto_chunks, pos, GenerationStage::Terrain, timings);
+        }
+        for pos in requested.filter(|pos| request.contains(pos)) {
+            if request.is_cancelled() {
+                return false;
+            }
+            if let Some(chunk) = self.advance(proto_chunks, pos, GenerationStage::Full, timings) {
+                self.record_timings(pos, mem::take(timings));
+                level.set_generated_chunk(pos, chunk);
+            }
+        }
+        true
+    }
+
+    /// Lights a finished `chunk`. Light flows in from the neighbors among `proto_chunks` whose
+    /// terrain is complete; any other neighbor is treated as solid rock.
+    fn light_chunk(
+        &self,
+        chunk: &mut ChunkData,
+        proto_chunks: &HashMap<Vector2<i32>, (ProtoChunk, GenerationStage)>,
+    ) {
+        let start_x = chunk_pos::start_block_x(&chunk.position);
+        let start_z = chunk_pos::start_block_z(&chunk.position);
+        let region = LightRegion::new(&chunk.section, |pos| {
+            let neighbor_pos =
+                chunk_pos::to_chunk_pos(&Vector2::new(pos.x + start_x, pos.z + start_z));
+            let (proto_chunk, stage) = proto_chunks.get(&neighbor_pos)?;
+            (*stage >= GenerationStage::Terrain)
+                .then(|| proto_chunk.get_block_state(&pos).to_state())
+        });
+        if self.dimension.has_skylight() {
+            region.propagate_sky_light(&mut chunk.light_engine);
+        }
+        region.propagate_block_light(&mut chunk.light_engine);
+    }
+
+    /// Copies the blocks and biomes of a finished `proto_chunk` into a new [`ChunkData`].
+    ///
+    /// The chunk spans the whole dimension while only the rows of the terrain shape are copied;
+    /// everything else stays air.
+    fn finish_chunk(&self, proto_chunk: &ProtoChunk) -> ChunkData {
+        let shape = &self.generation_settings.shape;
+
+        let mut chunk = empty_chunk(proto_chunk.chunk_pos, &self.dimension);
+        let sections = &mut chunk.section;
+        let min_y = sections.min_y;
+
+        let shape_min_y = shape.min_y as i32;
+        let shape_top_y = shape_min_y + shape.height as i32;
+        let mut blocks = Box::new([[[Block::AIR.default_state.id; 16]; 16]; 16]);
+        let empty_biome = SubChunk::default().biomes.get(0, 0, 0);
+        let mut biomes = [[[empty_biome; 4]; 4]; 4];
+        for index in 0..sections.sections.len() {
+            let section_min_y = min_y + index as i32 * BlockPalette::SIZE as i32;
+            if section_min_y + BlockPalette::SIZE as i32 <= shape_min_y
+                || section_min_y >= shape_top_y
+            {
+                continue;
+            }
+
+            // Rows outside of the shape stay air
+            blocks
+                .as_flattened_mut()
+                .as_flattened_mut()
+                .fill(Block::AIR.default_state.id);
+            proto_chunk.get_block_states_bulk(section_min_y, &mut blocks);
+            let blocks = blocks.as_flattened().as_flattened();
+            sections.fill_section_from(index, blocks.try_into().expect("a section is a cube"));
+
+            // Cells outside of the shape keep the biome of an empty section
+            biomes
+                .as_flattened_mut()
+                .as_flattened_mut()
+                .fill(empty_biome);
+            proto_chunk.get_biomes_bulk(section_min_y, &mut biomes);
+            let biomes = biomes.as_flattened().as_flattened();
+            sections
+                .fill_section_biomes_from(index, biomes.try_into().expect("a section is a cube"));
+        }
+
+        for (pos, loot_table) in proto_chunk.loot_chests() {
+            let pos = BlockPos(*pos);
+            let chest = ChestBlockEntity::with_loot_table(pos, loot_table);
+            chunk.block_entities.insert(pos, Arc::new(chest));
+        }
+
+        chunk
+    }
+}
+
+/// The settings vanilla generates `dimension` with, or the overworld of `world_type`.
+fn vanilla_settings(
+    dimension: &Dimension,
+    world_type: WorldType,
+) -> Result<Arc<GenerationSettings>, GeneratorError> {
+    let setting = GeneratorSetting::for_world_type(dimension, world_type);
+    GENERATION_SETTINGS
+        .get(&setting)
+        .cloned()
+        .ok_or(GeneratorError::MissingSettings(setting))
+}
+
+/// Makes sure the terrain of `shape` fits into the sections of `dimension`. The finished chunks
+/// only copy the blocks inside of their sections, so the rest of the terrain would be lost.
+fn check_shape(shape: &GenerationShapeConfig, dimension: &Dimension) -> Result<(), GeneratorError> {
+    let shape_min_y = shape.min_y as i32;
+    let shape_top_y = shape_min_y + shape.height as i32;
+    let min_y = dimension.min_y();
+    let top_y = min_y + dimension.height() as i32;
+    if shape_min_y < min_y || shape_top_y > top_y {
+        return Err(GeneratorError::ShapeOutsideDimension {
+            dimension: dimension.clone(),
+            shape_min_y,
+            shape_top_y,
+            min_y,
+            top_y,
+        });
+    }
+    Ok(())
+}
+
+/// The chunk at `pos` together with all of its neighbors, which its features can grow into.
+///
+/// # Panics
+/// If any of the chunks is missing from `proto_chunks`.
+fn decoration_region<'a, 'b>(
+    proto_chunks: &'b mut HashMap<Vector2<i32>, (ProtoChunk<'a>, GenerationStage)>,
+    pos: Vector2<i32>,
+) -> ProtoChunkRegion<'a, 'b> {
+    let [center, neighbors @ ..] = proto_chunks.get_disjoint_mut(with_neighbors(pos).each_ref());
+    let (center, _) = center.expect("the chunk is being decorated");
+    let mut region = ProtoChunkRegion::new(center);
+    for neighbor in neighbors {
+        let (neighbor, _) = neighbor.expect("the terrain of every neighbor is complete");
+        region = region.with_neighbor(neighbor);
+    }
+    region
+}
+
+/// `pos` followed by the eight chunks around it.
+pub(crate) fn with_neighbors(pos: Vector2<i32>) -> [Vector2<i32>; 9] {
+    let mut positions = [pos; 9];
+    for (slot, neighbor) in positions[1..].iter_mut().zip(RingIterator::new(pos, 1)) {
+        *slot = neighbor;
+    }
+    positions
+}
+
+/// Decorates the chunk at `pos` like the generator does, growing its features into the
+/// neighbors around it.
+///
+/// # Panics
+/// If any of the chunks is missing from `chunks`.
+#[cfg(test)]
+pub(crate) fn decorate(
+    chunks: &mut HashMap<Vector2<i32>, ProtoChunk<'_>>,
+    pos: Vector2<i32>,
+    block_registry: &dyn BlockRegistryExt,
+) {
+    let [center, neighbors @ ..] = chunks.get_disjoint_mut(with_neighbors(pos).each_ref());
+    let mut region = ProtoChunkRegion::new(center.expect("the chunk is being decorated"));
+    for neighbor in neighbors {
+        region = region.with_neighbor(neighbor.expect("every neighbor is there"));
+    }
+    region.generate_features_and_structure(block_registry);
+}
+
+/// Applies what the decorations of the chunk and of its neighbors placed in `proto_chunk`, the
+/// neighbors taken from `proto_chunks`. They are applied row by row from the north-west, so the
+/// later ones win where features overlap no matter which chunk was decorated first.
+///
+/// # Panics
+/// If any of the neighbors is missing from `proto_chunks`.
+fn apply_decorations<'a>(
+    proto_chunk: &mut ProtoChunk<'a>,
+    proto_chunks: &HashMap<Vector2<i32>, (ProtoChunk<'a>, GenerationStage)>,
+) {
+    let pos = proto_chunk.chunk_pos;
+    let own = proto_chunk.take_decoration();
+    for z in -1..=1 {
+        for x in -1..=1 {
+            let source = pos + Vector2::new(x, z);
+            let decoration = if source == pos {
+                &own
+            } else {
+                let (neighbor, _) = &proto_chunks[&source];
+                neighbor.decoration()
+            };
+            for changes in decoration
+                .iter()
+                .filter(|changes| changes.chunk_pos() == pos)
+            {
+                proto_chunk.apply_changes(changes);
             }
         }
-        for y in 0..generation_settings.shape.height {
-            let relative_y = (y as i32 - sections.min_y) as usize;
-            let section_index = relative_y / BlockPalette::SIZE;
-            let relative_y = relative_y % BlockPalette::SIZE;
-            if let Some(section) = sections.sections.get_mut(section_index) {
-                for z in 0..BlockPalette::SIZE {
-                    for x in 0..BlockPalette::SIZE {
-                        let absolute_y = generation_settings.shape.min_y as i32 + y as i32;
-                        let block = proto_chunk
-                            .get_block_state(&Vector3::new(x as i32, absolute_y, z as i32));
-                        section.block_states.set(x, relative_y, z, block.0);
+    }
+    proto_chunk.set_decoration(own);
+}
+
+/// Creates an all-air chunk at `pos` spanning the whole height of `dimension`, lit by the sky
+/// everywhere.
+fn empty_chunk(pos: Vector2<i32>, dimension: &Dimension) -> ChunkData {
+    // A partial top section is kept and left empty above the build limit
+    let sub_chunks = (dimension.height() as usize).div_ceil(BlockPalette::SIZE);
+    let sections = (0..sub_chunks).map(|_| SubChunk::default()).collect();
+
+    // Light sits one section below and above the blocks
+    let light_sections = sub_chunks + 2;
+    ChunkData {
+        section: ChunkSections::new(sections, dimension.min_y()),
+        heightmap: Default::default(),
+        position: pos,
+        block_ticks: Default::default(),
+        fluid_ticks: Default::default(),
+        block_entities: HashMap::new(),
+        light_engine: ChunkLight {
+            sky_light: (0..light_sections)
+                .map(|_| LightContainer::new_filled(15))
+                .collect(),
+            block_light: (0..light_sections)
+                .map(|_| LightContainer::new_empty(0))
+                .collect(),
+        },
+        status: ChunkStatus::Full,
+        generated_by: None,
+        dirty: true,
+    }
+}
+
+impl WorldGenerator for VanillaGenerator {
+    fn request_load(&self, level: &Arc<Level>, request: LoadRequest) {
+        VanillaSession::new(self).request_load(level, request);
+    }
+
+    fn locate_nearest_structure(
+        &self,
+        structure_id: &str,
+        from: Vector2<i32>,
+        max_radius: u32,
+    ) -> Option<Vector2<i32>> {
+        locate_nearest_structure(self, structure_id, from, max_radius)
+    }
+
+    fn structure_at(&self, pos: Vector2<i32>) -> Option<StructureKind> {
+        structure_at(self, pos)
+    }
+
+    fn timings(&self) -> Option<GenerationTimings> {
+        Some(VanillaGenerator::timings(self))
+    }
+
+    fn session(&self) -> Box<dyn GenerationSession + '_> {
+        Box::new(VanillaSession::new(self))
+    }
+}
+
+/// Keeps the chunks prepared around a request for the next one, as far as they reach into it.
+///
+/// The chunks only ever hold their terrain and their own decoration, whichever request they
+/// were generated for (see [`VanillaGenerator::advance`]), so a request finishes the same chunks
+/// out of them as it would out of new ones.
+struct VanillaSession<'a> {
+    generator: &'a VanillaGenerator,
+    proto_chunks: HashMap<Vector2<i32>, (ProtoChunk<'a>, GenerationStage)>,
+}
+
+impl<'a> VanillaSession<'a> {
+    fn new(generator: &'a VanillaGenerator) -> Self {
+        Self {
+            generator,
+            proto_chunks: HashMap::new(),
+        }
+    }
+}
+
+impl GenerationSession for VanillaSession<'_> {
+    fn request_load(&mut self, level: &Arc<Level>, request: LoadRequest) {
+        // Only the terrain of the chunks two rings out from the requested ones is needed
+        let reach = request.radius() + CARVER_RADIUS;
+        for (_, (proto_chunk, _)) in self.proto_chunks.extract_if(|pos, _| {
+            let offset = pos.sub(&request.origin);
+            offset.x.unsigned_abs().max(offset.y.unsigned_abs()) >= reach
+        }) {
+            proto_chunk.recycle();
+        }
+        // A chunk finished before is only decorated as far as this request is concerned, since it
+        // may have been unloaded since
+        for (pos, (_, stage)) in &mut self.proto_chunks {
+            if *stage == GenerationStage::Full && request.contains(pos) {
+                *stage = GenerationStage::Features;
+            }
+        }
+
+        // Every step finishes one ring of requested chunks while the rings around it are prepared
+        // as far as the finished ring needs them.
+        // Each finished chunk is timed along with everything prepared for it since the last one.
+        let mut finished_ring = None;
+        let mut timings = StageTimings::default();
+        for rings in request.clone() {
+            let requested = rings.0;
+            if !self.generator.generate_ring(
+                level,
+                &request,
+                &mut self.proto_chunks,
+                rings,
+                &mut timings,
+            ) {
+                return;
+            }
+            // Finished chunks are still neighbors of the next ring, but not of the ones after it
+            if let Some(ring) = finished_ring.replace(requested) {
+                for pos in ring {
+                    if let Some((proto_chunk, _)) = self.proto_chunks.remove(&pos) {
+                        proto_chunk.recycle();
                     }
                 }
             }
@@ -139,10 +1157,27 @@ impl WorldGenerator for VanillaGenerator {
     }
 }
 
-#[derive(Clone, Copy)]
-struct LoadRequest {
-    origin: i32,
+impl Drop for VanillaSession<'_> {
+    fn drop(&mut self) {
+        for (_, (proto_chunk, _)) in self.proto_chunks.drain() {
+            proto_chunk.recycle();
+        }
+    }
+}
+
+/// A square of chunks to generate around `origin`.
+///
+/// Clones share a single cancellation flag, so keeping a clone around is enough to call
+/// [`LoadRequest::cancel`] once the chunks are no longer needed.
+#[derive(Clone)]
+pub struct LoadRequest {
+    origin: Vector2<i32>,
     radius: u32,
+    /// See [`LoadRequest::with_chunks`].
+    chunks: Option<Arc<HashSet<Vector2<i32>>>>,
+    /// See [`LoadRequest::priority`].
+    priority: u32,
+    cancelled: Arc<AtomicBool>,
 }
 
 const LIGHT_RADIUS: u32 = 1;
@@ -193,38 +1228,249 @@ impl IntoIterator for LoadRequest {
     }
 }
 impl LoadRequest {
-    const fn with_radius(self, radius: u32) -> Self {
+    pub fn new(origin: Vector2<i32>, radius: u32) -> Self {
+        Self {
+            origin,
+            radius,
+            chunks: None,
+            priority: 0,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// The request for only `chunks`, as far from `origin` as they are. Their neighbors are still
+    /// generated as far as they need them, but never finished.
+    pub fn for_chunks(
+        origin: Vector2<i32>,
+        chunks: impl IntoIterator<Item = Vector2<i32>>,
+    ) -> Self {
+        Self::new(origin, 0).only(chunks)
+    }
+
+    /// Only generates the `chunks` of this request, growing it as far as the ones outside of it,
+    /// e.g. the chunks missing from the view of a player. Their neighbors are still generated as
+    /// far as they need them, but never finished.
+    pub fn only(self, chunks: impl IntoIterator<Item = Vector2<i32>>) -> Self {
+        let chunks: HashSet<_> = chunks.into_iter().collect();
+        let radius = chunks
+            .iter()
+            .map(|pos| {
+                let offset = pos.sub(&self.origin);
+                offset.x.unsigned_abs().max(offset.y.unsigned_abs()) + 1
+            })
+            .fold(self.radius, u32::max);
+        self.with_radius(radius).with_chunks(chunks)
+    }
+
+    /// Only generates the chunks of the square that are among `chunks`.
+    fn with_chunks(mut self, chunks: HashSet<Vector2<i32>>) -> Self {
+        self.chunks = Some(Arc::new(chunks));
+        self
+    }
+
+    /// The request for every chunk a player at `origin` sees with a view distance of
+    /// `view_distance` chunks. The rings prepared around the chunks stay just as far from them
+    /// whatever the view distance is.
+    pub fn for_view_distance(origin: Vector2<i32>, view_distance: NonZeroU8) -> Self {
+        Self::new(origin, view_distance.get() as u32 + 1)
+    }
+
+    /// Like [`LoadRequest::for_view_distance`], with the view distance the server is configured
+    /// with.
+    pub fn for_configured_view_distance(origin: Vector2<i32>) -> Self {
+        Self::for_view_distance(origin, BASIC_CONFIG.view_distance)
+    }
+
+    /// The chunk the rings of this request are around.
+    pub fn origin(&self) -> Vector2<i32> {
+        self.origin
+    }
+
+    /// How many rings around `origin` are generated, the origin being the first.
+    pub fn radius(&self) -> u32 {
+        self.radius
+    }
+
+    /// Sets how far the chunks are from the closest player, see [`LoadRequest::priority`].
+    pub fn with_priority(mut self, priority: u32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// How far `origin` is from the closest player, in chunks. Requests with a lower priority
+    /// are generated first; the default of 0 is for chunks that are needed right away.
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+
+    /// Stops generating this request and every clone of it. Chunks that are already finished are
+    /// kept; the chunk currently being generated is dropped.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Whether `pos` is one of the chunks this request generates.
+    pub fn contains(&self, pos: &Vector2<i32>) -> bool {
+        let offset = pos.sub(&self.origin);
+        offset.x.unsigned_abs().max(offset.y.unsigned_abs()) < self.radius
+            && self
+                .chunks
+                .as_ref()
+                .is_none_or(|chunks| chunks.contains(pos))
+    }
+
+    /// Every chunk of this request in a single pass, ring by ring outwards from the origin. These
+    /// are exactly the chunks it [`contains`](LoadRequest::contains), e.g. to pregenerate them in
+    /// bulk.
+    pub fn disk_iter(&self) -> impl Iterator<Item = Vector2<i32>> + use<> {
+        let request = self.clone();
+        (0..self.radius)
+            .flat_map(move |radius| RingIterator::new(request.origin, radius))
+            .filter(move |pos| request.contains(pos))
+    }
+
+    /// The chunks of this request split into square tiles of [`TILE_SIZE`] chunks, each walked
+    /// ring by ring outwards from the origin. The tiles with the innermost chunks come first.
+    ///
+    /// The tiles are laid out around the origin, so the one in the middle is walked just like the
+    /// whole request would be.
+    pub(crate) fn tiles(&self) -> Vec<Vec<Vector2<i32>>> {
+        let half = TILE_SIZE / 2;
+        let tile_of = |origin: i32, coord: i32| (coord - origin + half).div_euclid(TILE_SIZE);
+        let mut indices = HashMap::new();
+        let mut tiles: Vec<Vec<_>> = Vec::new();
+        for pos in self.disk_iter() {
+            let tile = (tile_of(self.origin.x, pos.x), tile_of(self.origin.y, pos.y));
+            let index = *indices.entry(tile).or_insert_with(|| {
+                tiles.push(Vec::new());
+                tiles.len() - 1
+            });
+            tiles[index].push(pos);
+        }
+        tiles
+    }
+
+    /// The request for the `chunks` of one of its [tiles](LoadRequest::tiles), which have to be
+    /// in ring order, cancelled along with this one. It is as far from the player as its first
+    /// chunk.
+    fn tile(&self, chunks: Vec<Vector2<i32>>) -> Self {
+        let ring = |pos: &Vector2<i32>| {
+            let offset = pos.sub(&self.origin);
+            offset.x.unsigned_abs().max(offset.y.unsigned_abs())
+        };
+        let half = TILE_SIZE / 2;
+        let center = |origin: i32, coord: i32| {
+            origin + (coord - origin + half).div_euclid(TILE_SIZE) * TILE_SIZE
+        };
+        let closest = chunks[0];
+        Self {
+            origin: Vector2::new(
+                center(self.origin.x, closest.x),
+                center(self.origin.y, closest.y),
+            ),
+            radius: (half + 1) as u32,
+            priority: self.priority.saturating_add(ring(&closest)),
+            chunks: Some(Arc::new(chunks.into_iter().collect())),
+            cancelled: self.cancelled.clone(),
+        }
+    }
+
+    fn with_radius(&self, radius: u32) -> Self {
         Self {
             origin: self.origin,
             radius,
+            chunks: self.chunks.clone(),
+            priority: self.priority,
+            cancelled: self.cancelled.clone(),
         }
     }
 }
 
+/// Several [`LoadRequest`]s sent to the generator threads as a single message, like the chunks
+/// missing around several players. The threads split each of them into tiles just the same.
+#[derive(Clone, Default)]
+pub struct LoadBatch(Vec<LoadRequest>);
+
+impl LoadBatch {
+    pub fn push(&mut self, request: LoadRequest) {
+        self.0.push(request);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<LoadRequest> for LoadBatch {
+    fn from(request: LoadRequest) -> Self {
+        Self(vec![request])
+    }
+}
+
+impl FromIterator<LoadRequest> for LoadBatch {
+    fn from_iter<T: IntoIterator<Item = LoadRequest>>(requests: T) -> Self {
+        Self(requests.into_iter().collect())
+    }
+}
+
+impl IntoIterator for LoadBatch {
+    type Item = LoadRequest;
+    type IntoIter = std::vec::IntoIter<LoadRequest>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+/// Walks the chunks lying exactly on the square ring of `radius` around `position`.
+///
+/// The walk starts at the north-west corner and goes clockwise, so a ring of radius `n` yields
+/// `8 * n` chunks (or only `position` itself for a radius of 0). Successive radii therefore tile
+/// the filled square without yielding any chunk twice.
 #[derive(Clone, Copy)]
-struct RingIterator {
+pub struct RingIterator {
     index: usize,
-    position: i32,
+    position: Vector2<i32>,
     radius: u32,
 }
 
 impl RingIterator {
+    /// Returns an iterator over the chunks `radius` chunks away from `position`.
+    pub(crate) fn new(position: Vector2<i32>, radius: u32) -> Self {
+        Self {
+            index: 0,
+            position,
+            radius,
+        }
+    }
+
+    /// Returns an iterator over the ring `padding` chunks further out. The new iterator always
+    /// starts at the beginning of its own ring.
     fn with_padding(self, padding: u32) -> Self {
         Self {
-            index: self.index,
+            index: 0,
             position: self.position,
             radius: self.radius + padding,
         }
     }
+
+    #[inline]
+    fn ring_len(&self) -> usize {
+        if self.radius == 0 {
+            1
+        } else {
+            8 * self.radius as usize
+        }
+    }
 }
 
 impl From<LoadRequest> for RingIterator {
     fn from(value: LoadRequest) -> Self {
-        RingIterator {
-            index: 0,
-            position: value.origin,
-            radius: value.radius,
-        }
+        RingIterator::new(value.origin, value.radius)
     }
 }
 
@@ -232,65 +1478,336 @@ impl Iterator for RingIterator {
     type Item = Vector2<i32>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index >= self.len() {
+        if self.index >= self.ring_len() {
             return None;
         }
 
-        let r = self.radius;
+        let r = self.radius as i32;
         let i = self.index as i32;
         let side = 2 * r;
 
-        let pos = match i / side {
-            0 => {
+        let offset = if r == 0 {
+            Vector2::new(0, 0)
+        } else {
+            match i / side {
                 // top edge (left → right)
-                Vector2::new(-r + i, -r)
-            }
-            1 => {
+                0 => Vector2::new(-r + i, -r),
                 // right edge (top → bottom)
-                Vector2::new(r, -r + (i - side))
-            }
-            2 => {
+                1 => Vector2::new(r, -r + (i - side)),
                 // bottom edge (right → left)
-                Vector2::new(r - (i - 2 * side), r)
-            }
-            3 => {
+                2 => Vector2::new(r - (i - 2 * side), r),
                 // left edge (bottom → top)
-                Vector2::new(-r, r - (i - 3 * side))
+                3 => Vector2::new(-r, r - (i - 3 * side)),
+                _ => unreachable!(),
             }
-            _ => return None,
         };
 
         self.index += 1;
-        Some(pos)
+        Some(self.position.add(&offset))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.ring_len().saturating_sub(self.index);
+        (remaining, Some(remaining))
     }
 }
 
-/// Call in a new thread
-fn initialize_generator(rx: Receiver<LoadRequest>, generator: impl WorldGenerator, level: ()) {
-    let mut queue = VecDeque::new();
+impl ExactSizeIterator for RingIterator {}
+
+/// What the generator threads are up to, for following the progress of large requests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GenProgress {
+    ChunkStarted {
+        pos: Vector2<i32>,
+    },
+    ChunkFinished {
+        pos: Vector2<i32>,
+    },
+    /// A thread ran out of chunks to generate, and no more are waiting.
+    QueueDrained,
+    /// A thread used up its budget, after working for `worked`, and let the threads it shares
+    /// the cores with run.
+    Yielded {
+        worked: Duration,
+    },
+}
+
+/// How many priorities the tiles waiting in a [`GeneratorPool`] are sorted into. Tiles further
+/// away all share the last one.
+const PRIORITIES: usize = 64;
+/// How many tiles a thread takes at once from the shared queues. A tile takes a while, and the
+/// tiles a thread has taken are generated even if closer ones come in, so only one.
+const STEAL_BATCH: usize = 1;
+/// How many chunks wide the tiles a [`GeneratorPool`] splits requests into are. Neighbors
+/// bordering a tile are generated along with it, so larger tiles waste less work on them, while
+/// smaller ones spread a request over more threads.
+const TILE_SIZE: i32 = 7;
+
+/// The chunks waiting to be generated by the generator threads of a level. Requests are split
+/// into square tiles of neighboring chunks, which idle threads steal from the busy ones. The tiles
+/// closest to a player are taken first.
+pub struct GeneratorPool {
+    /// The tiles nobody took yet, by priority.
+    queues: Box<[Injector<LoadRequest>]>,
+    stealers: Vec<Stealer<LoadRequest>>,
+    /// Every chunk that was queued, finished or not, so overlapping requests only generate it
+    /// once. Chunks are only generated again once they are [forgotten](Self::forget).
+    queued: Mutex<HashSet<Vector2<i32>>>,
+    /// Wakes up threads waiting for new requests once there are chunks to steal.
+    wake_tx: Sender<()>,
+    wake_rx: Receiver<()>,
+}
+
+impl GeneratorPool {
+    /// Creates the pool of `threads` threads, along with the queue of each of them to pass to
+    /// [`initialize_generator`].
+    pub fn new(threads: usize) -> (Arc<Self>, Vec<Worker<LoadRequest>>) {
+        let workers: Vec<_> = (0..threads).map(|_| Worker::new_fifo()).collect();
+        let (wake_tx, wake_rx) = crossbeam::channel::bounded(threads);
+        let pool = Self {
+            queues: (0..PRIORITIES).map(|_| Injector::new()).collect(),
+            stealers: workers.iter().map(Worker::stealer).collect(),
+            queued: Mutex::new(HashSet::new()),
+            wake_tx,
+            wake_rx,
+        };
+        (Arc::new(pool), workers)
+    }
 
+    /// How many generator threads `config` asks for on a machine with `cores` cores, at least one.
+    pub fn thread_count(config: &ChunkConfig, cores: usize) -> usize {
+        if config.generation_threads == 0 {
+            return cores.saturating_sub(config.reserved_cores).max(1);
+        }
+        if config.generation_threads > cores {
+            log::warn!(
+                "Starting {} generation threads on {cores} cores, they will compete for them",
+                config.generation_threads
+            );
+        }
+        config.generation_threads
+    }
+
+    /// Queues the chunks of the requests in `batch` that weren't queued before, split into their
+    /// [tiles](LoadRequest::tiles). Returns how many tiles were queued.
+    ///
+    /// Each tile is generated as a single request, which shares the neighbors and their
+    /// decoration between its chunks instead of generating them for each chunk again.
+    fn submit(&self, batch: LoadBatch) -> usize {
+        let mut queued = self.queued.lock().unwrap();
+        let mut tiles = 0;
+        for request in batch {
+            for chunks in request.tiles() {
+                let chunks: Vec<_> = chunks
+                    .into_iter()
+                    .filter(|pos| queued.insert(*pos))
+                    .collect();
+                if chunks.is_empty() {
+                    continue;
+                }
+                let tile = request.tile(chunks);
+                self.queues[(tile.priority as usize).min(PRIORITIES - 1)].push(tile);
+                tiles += 1;
+            }
+        }
+        for _ in 0..tiles.min(self.stealers.len()) {
+            // The other threads are busy already if this is full
+            let _ = self.wake_tx.try_send(());
+        }
+        tiles
+    }
+
+    /// Takes the next tile to generate, preferring the queue of this thread over the shared ones
+    /// and the shared ones over the queues of others.
+    fn find_job(&self, local: &Worker<LoadRequest>) -> Option<LoadRequest> {
+        local.pop().or_else(|| {
+            iter::repeat_with(|| {
+                self.queues
+                    .iter()
+                    .map(|queue| queue.steal_batch_with_limit_and_pop(local, STEAL_BATCH))
+                    .collect::<Steal<_>>()
+                    .or_else(|| self.stealers.iter().map(Stealer::steal).collect())
+            })
+            .find(|steal| !steal.is_retry())
+            .and_then(Steal::success)
+        })
+    }
+
+    /// Lets the chunks at `chunks` be generated again, once they were unloaded or their requests
+    /// were cancelled.
+    pub fn forget(&self, chunks: impl IntoIterator<Item = Vector2<i32>>) {
+        let mut queued = self.queued.lock().unwrap();
+        for pos in chunks {
+            queued.remove(&pos);
+        }
+    }
+
+    /// How many tiles are waiting to be generated.
+    pub fn len(&self) -> usize {
+        let queued: usize = self.queues.iter().map(Injector::len).sum();
+        queued + self.stealers.iter().map(Stealer::len).sum::<usize>()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// What the generator threads of a level share, see [`initialize_generator`].
+#[derive(Clone)]
+pub struct WorkerSettings {
+    /// The tiles waiting to be generated, which the threads steal from each other.
+    pub pool: Arc<GeneratorPool>,
+    /// Chunks found in the cache are read from it instead of being generated, and the others are
+    /// added to it.
+    pub cache: Option<Arc<GenerationCache>>,
+    /// Where every thread reports what it does, dropping the events there is no room for.
+    pub progress: Option<Sender<GenProgress>>,
+    /// How long a thread works at a stretch before it yields and picks up the batches that came
+    /// in meanwhile, rather than working through its whole queue at once.
+    pub budget: Option<Duration>,
+}
+
+impl WorkerSettings {
+    /// The settings of `threads` threads without a cache, progress reports or budget, along with
+    /// the queue of each thread.
+    pub fn new(threads: usize) -> (Self, Vec<Worker<LoadRequest>>) {
+        let (pool, workers) = GeneratorPool::new(threads);
+        let settings = Self {
+            pool,
+            cache: None,
+            progress: None,
+            budget: None,
+        };
+        (settings, workers)
+    }
+
+    /// The settings `config` asks for on a machine with `cores` cores, see
+    /// [`GeneratorPool::thread_count`]. The cache is left to the level, as it is kept per world.
+    pub fn from_config(config: &ChunkConfig, cores: usize) -> (Self, Vec<Worker<LoadRequest>>) {
+        let (mut settings, workers) = Self::new(GeneratorPool::thread_count(config, cores));
+        settings.budget = (config.generation_budget_ms > 0)
+            .then(|| Duration::from_millis(config.generation_budget_ms));
+        (settings, workers)
+    }
+}
+
+/// Generates every [`LoadBatch`] received on `rx` and hands the finished chunks to `level`.
+/// The tiles of a request are spread over all threads sharing the pool of `settings`, `local`
+/// being the queue of this one. The thread generates its tiles in a single
+/// [session](WorldGenerator::session), so the neighbors one tile prepared are reused by the next.
+///
+/// With a budget, a tile is never interrupted, so a stretch of work can take up to one tile
+/// longer than the budget.
+///
+/// Call in a new thread; it only returns once `rx` is disconnected or `level` shuts down.
+pub fn initialize_generator(
+    rx: Receiver<LoadBatch>,
+    generator: Arc<dyn WorldGenerator>,
+    level: Arc<Level>,
+    local: Worker<LoadRequest>,
+    settings: WorkerSettings,
+) {
+    let WorkerSettings {
+        pool,
+        cache,
+        progress,
+        budget,
+    } = settings;
+    let report = |event| {
+        if let Some(progress) = &progress {
+            let _ = progress.try_send(event);
+        }
+    };
+    // Tiles taken one after the other often border each other
+    let mut session = generator.session();
     let mut poll_countdown = 0;
+    // Whether a chunk was generated since the queue was last drained
+    let mut busy = false;
+    let mut working_since = Instant::now();
     loop {
+        if level.is_shutting_down.load(Ordering::Relaxed) {
+            return;
+        }
         if poll_countdown == 0 {
-            while let Ok(task) = rx.try_recv() {
-                queue.push_front(task.into_iter());
+            while let Ok(batch) = rx.try_recv() {
+                pool.submit(batch);
             }
-            poll_countdown = queue.len(); // Or set it to a constant
+            // Every chunk of the batches is waiting in the pool by now
+            poll_countdown = pool.len(); // Or set it to a constant
         }
-        if let Some(mut task) = queue.pop_back() {
-            if let Some(work) = task.next() {
-                // Do stuff with work
-                queue.push_front(task);
+        if let Some(task) = pool.find_job(&local) {
+            if task.is_cancelled() {
+                log::debug!("Skipping cancelled tile around {:?}", task.origin);
+                pool.forget(task.disk_iter());
+                poll_countdown = poll_countdown.saturating_sub(1);
+                continue;
+            }
+            let mut missing = HashSet::new();
+            for pos in task.disk_iter() {
+                report(GenProgress::ChunkStarted { pos });
+                if let Some(chunk) = cache.as_ref().and_then(|cache| cache.load(pos)) {
+                    log::debug!("Loaded chunk {pos:?} from the generation cache");
+                    level.set_generated_chunk(pos, chunk);
+                    report(GenProgress::ChunkFinished { pos });
+                } else {
+                    missing.insert(pos);
+                }
+            }
+            if !missing.is_empty() {
+                log::debug!(
+                    "Generating {} chunks around {:?}, {} tiles queued, {} batches waiting",
+                    missing.len(),
+                    task.origin,
+                    pool.len(),
+                    rx.len()
+                );
+                let tile = task.with_chunks(missing);
+                session.request_load(&level, tile.clone());
+                let mut unfinished = Vec::new();
+                for pos in tile.disk_iter() {
+                    match level.try_get_chunk(&pos) {
+                        Some(chunk) => {
+                            if let Some(cache) = &cache {
+                                cache.store(&chunk.blocking_read());
+                            }
+                            report(GenProgress::ChunkFinished { pos });
+                        }
+                        // The request was cancelled before the chunk was finished
+                        None => unfinished.push(pos),
+                    }
+                }
+                pool.forget(unfinished);
+            }
+            busy = true;
+
+            let worked = working_since.elapsed();
+            if budget.is_some_and(|budget| worked >= budget) {
+                report(GenProgress::Yielded { worked });
+                std::thread::yield_now();
+                // Poll for new batches right away
+                poll_countdown = 1;
+                working_since = Instant::now();
             }
         } else {
-            // The task queue is empty
-            let Ok(value) = rx.recv() else { return }; // Blocks
-            queue.push_front(value.into_iter());
+            if busy && pool.is_empty() && rx.is_empty() {
+                report(GenProgress::QueueDrained);
+                busy = false;
+                // Don't hold on to the neighbors of the last tile while waiting
+                session = generator.session();
+            }
+            // There is nothing to steal
+            select! {
+                recv(rx) -> batch => {
+                    let Ok(batch) = batch else { return };
+                    // Work through the batch before looking for the next ones, not counting this
+                    // round that didn't generate anything
+                    poll_countdown = pool.submit(batch) + 1;
+                }
+                recv(pool.wake_rx) -> _ => {}
+            }
+            working_since = Instant::now();
         }
-        poll_countdown -= 1;
+        poll_countdown = poll_countdown.saturating_sub(1);
     }
 }
-
-fn initialize_pyramid(pos: Vector2<i32>) {
-    todo!()
-}
@@ -0,0 +1,175 @@
+use pumpkin_data::{
+    Block, BlockState,
+    chunk::Biome,
+    tag::{self, Taggable},
+};
+use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+use super::{RingIterator, VanillaGenerator};
+use crate::{
+    chunk::{ChunkData, ChunkHeightmapType},
+    generation::positions::chunk_pos,
+};
+
+/// How far the ground next to a spawn may rise or drop for it to be taken right away.
+const FLAT_ENOUGH: i32 = 1;
+
+/// Finds where players spawn in a new world: on solid ground open to the sky, away from oceans
+/// and lava, in the first chunk flat enough around it.
+///
+/// Chunks are generated in rings around the origin, up to `search_radius` rings. If none of them
+/// is flat enough, the flattest spot found is used, and without any spot at all, the top of the
+/// origin. The result is the position of the feet of the player.
+pub fn find_spawn_point(generator: &VanillaGenerator, search_radius: u32) -> Vector3<i32> {
+    let mut fallback = None;
+    let mut best: Option<(i32, Vector3<i32>)> = None;
+    for radius in 0..search_radius.max(1) {
+        for pos in RingIterator::new(Vector2::new(0, 0), radius) {
+            let chunk = generator.generate_chunk(pos);
+            let heights = surface_heights(&chunk);
+            fallback.get_or_insert(Vector3::new(0, heights[0][0] + 1, 0));
+
+            if let Some((roughness, spawn)) = flattest_spawn(&chunk, &heights) {
+                if roughness <= FLAT_ENOUGH {
+                    return spawn;
+                }
+                if best.is_none_or(|(best_roughness, _)| roughness < best_roughness) {
+                    best = Some((roughness, spawn));
+                }
+            }
+        }
+    }
+    best.map(|(_, spawn)| spawn)
+        .or(fallback)
+        .expect("at least the origin is searched")
+}
+
+/// The height of the ground of every column of `chunk` by x and z, below any grass or flowers
+/// on top of it.
+fn surface_heights(chunk: &ChunkData) -> [[i32; 16]; 16] {
+    let min_y = chunk.section.min_y;
+    let mut heights = [[min_y; 16]; 16];
+    for (x, column) in heights.iter_mut().enumerate() {
+        for (z, height) in column.iter_mut().enumerate() {
+            let mut y = chunk.heightmap.get_height(
+                ChunkHeightmapType::WorldSurface,
+                x as i32,
+                z as i32,
+                min_y,
+            );
+            // Players stand in plants, not on them
+            while y > min_y && {
+                let state = block_state(chunk, x, y, z);
+                state.replaceable() && !state.is_liquid()
+            } {
+                y -= 1;
+            }
+            *height = y;
+        }
+    }
+    heights
+}
+
+fn block_state(chunk: &ChunkData, x: usize, y: i32, z: usize) -> &'static BlockState {
+    let id = chunk
+        .section
+        .get_block_absolute_y(x, y, z)
+        .unwrap_or(Block::AIR.default_state.id);
+    BlockState::from_id(id)
+}
+
+/// The spot of `chunk` whose neighbors are closest to its own height, with how far they are off
+/// at most. Only columns inside the chunk are candidates, so that all of their neighbors are
+/// known.
+fn flattest_spawn(chunk: &ChunkData, heights: &[[i32; 16]; 16]) -> Option<(i32, Vector3<i32>)> {
+    let start_x = chunk_pos::start_block_x(&chunk.position);
+    let start_z = chunk_pos::start_block_z(&chunk.position);
+    let mut best: Option<(i32, Vector3<i32>)> = None;
+    for x in 1..15 {
+        for z in 1..15 {
+            let y = heights[x][z];
+            let ground = block_state(chunk, x, y, z);
+            if !ground.is_solid()
+                || ground.is_liquid()
+                || Block::from_state_id(ground.id)
+                    .is_tagged_with_by_tag(&tag::Block::MINECRAFT_LEAVES)
+            {
+                continue;
+            }
+            let biome = chunk
+                .section
+                .get_rough_biome_absolute_y(x, y, z)
+                .and_then(Biome::from_id);
+            if biome.is_none_or(|biome| {
+                biome.is_tagged_with_by_tag(&tag::WorldgenBiome::MINECRAFT_IS_OCEAN)
+            }) {
+                continue;
+            }
+
+            let neighbors = [(x - 1, z), (x + 1, z), (x, z - 1), (x, z + 1)];
+            if neighbors.iter().any(|&(x, z)| {
+                Block::from_state_id(block_state(chunk, x, heights[x][z], z).id) == &Block::LAVA
+            }) {
+                continue;
+            }
+            let roughness = neighbors
+                .iter()
+                .map(|&(x, z)| (heights[x][z] - y).abs())
+                .max()
+                .unwrap_or(0);
+            if best.is_none_or(|(best_roughness, _)| roughness < best_roughness) {
+                best = Some((
+                    roughness,
+                    Vector3::new(start_x + x as i32, y + 1, start_z + z as i32),
+                ));
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_data::{
+        Block,
+        tag::{self, Taggable},
+    };
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::{block_state, find_spawn_point};
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            Seed,
+            generator::{GeneratorInit, VanillaGenerator, tests::BlockRegistry},
+            positions::chunk_pos,
+        },
+    };
+
+    #[test]
+    fn spawn_is_on_dry_ground() {
+        let generator =
+            VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+        let spawn = find_spawn_point(&generator, 3);
+
+        let pos = chunk_pos::to_chunk_pos(&Vector2::new(spawn.x, spawn.z));
+        assert!(
+            pos.x.abs() < 3 && pos.y.abs() < 3,
+            "{spawn:?} is too far out"
+        );
+        let chunk = generator.generate_chunk(pos);
+        let (x, z) = ((spawn.x & 15) as usize, (spawn.z & 15) as usize);
+        let ground = block_state(&chunk, x, spawn.y - 1, z);
+        assert!(ground.is_solid() && !ground.is_liquid(), "{ground:?}");
+        assert!(
+            !Block::from_state_id(ground.id).is_tagged_with_by_tag(&tag::Block::MINECRAFT_LEAVES)
+        );
+        // Nothing to suffocate in
+        for y in spawn.y..spawn.y + 2 {
+            let state = block_state(&chunk, x, y, z);
+            assert!(!state.is_solid() && !state.is_liquid(), "{state:?} at {y}");
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use pumpkin_data::{Block, BlockState, chunk::Biome};
+use pumpkin_util::math::vector2::Vector2;
+
+use super::{GeneratorError, GeneratorInit, LoadRequest, WorldGenerator, empty_chunk};
+use crate::{
+    chunk::{
+        ChunkData,
+        palette::{BiomePalette, BlockPalette},
+    },
+    dimension::Dimension,
+    generation::{
+        Seed,
+        settings::{BiomeSource, WorldType},
+    },
+    level::Level,
+    world::BlockRegistryExt,
+};
+
+/// Generates a flat world made of the same stack of layers in every chunk.
+pub struct SuperflatGenerator {
+    dimension: Dimension,
+    /// Every block state with the number of blocks it is stacked, from the bottom up.
+    layers: Box<[(&'static BlockState, u16)]>,
+    biome: &'static Biome,
+}
+
+impl SuperflatGenerator {
+    /// The layers of the vanilla "Classic Flat" preset.
+    pub const CLASSIC_FLAT: [(&'static BlockState, u16); 3] = [
+        (Block::BEDROCK.default_state, 1),
+        (Block::DIRT.default_state, 2),
+        (Block::GRASS_BLOCK.default_state, 1),
+    ];
+
+    /// Creates a generator stacking `layers` from the bottom of `dimension` up. Layers reaching
+    /// past the build limit are cut off.
+    pub fn with_layers(
+        dimension: Dimension,
+        layers: impl Into<Box<[(&'static BlockState, u16)]>>,
+        biome: &'static Biome,
+    ) -> Self {
+        Self {
+            dimension,
+            layers: layers.into(),
+            biome,
+        }
+    }
+
+    fn generate_chunk(&self, pos: Vector2<i32>) -> ChunkData {
+        let mut chunk = empty_chunk(pos, &self.dimension);
+        let sections = &mut chunk.section.sections;
+
+        // The top section may reach past the build limit, which cuts off the rows instead
+        let rows = self
+            .layers
+            .iter()
+            .flat_map(|(state, count)| std::iter::repeat_n(state.id, *count as usize))
+            .take(self.dimension.height() as usize);
+        for (relative_y, state_id) in rows.enumerate() {
+            let section = &mut sections[relative_y / BlockPalette::SIZE];
+            let relative_y = relative_y % BlockPalette::SIZE;
+            for z in 0..BlockPalette::SIZE {
+                for x in 0..BlockPalette::SIZE {
+                    section.block_states.set(x, relative_y, z, state_id);
+                }
+            }
+        }
+        for section in sections.iter_mut() {
+            section.biomes = BiomePalette::Homogeneous(self.biome.id);
+        }
+
+        chunk
+    }
+}
+
+impl GeneratorInit for SuperflatGenerator {
+    fn with_random_source(
+        _seed: Seed,
+        dimension: Dimension,
+        _world_type: WorldType,
+        _biome_source: BiomeSource,
+        _legacy_random: bool,
+        _block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        Ok(Self::with_layers(
+            dimension,
+            Self::CLASSIC_FLAT,
+            &Biome::PLAINS,
+        ))
+    }
+}
+
+impl WorldGenerator for SuperflatGenerator {
+    fn request_load(&self, level: &Arc<Level>, request: LoadRequest) {
+        // Flat chunks don't depend on their neighbors, so only the requested chunks are walked
+        for pos in request.disk_iter() {
+            if request.is_cancelled() {
+                return;
+            }
+            level.set_generated_chunk(pos, self.generate_chunk(pos));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_data::{Block, chunk::Biome, noise_router::NETHER_BASE_NOISE_ROUTER};
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::SuperflatGenerator;
+    use crate::{
+        chunk::ChunkHeightmapType,
+        dimension::{Dimension, DimensionConfig},
+        generation::{
+            Seed,
+            generator::{GeneratorInit, tests::BlockRegistry},
+            settings::BiomeSource,
+        },
+    };
+
+    #[test]
+    fn classic_flat_layers() {
+        let generator =
+            SuperflatGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        let chunk = generator.generate_chunk(Vector2::new(3, -2));
+        let sections = &chunk.section;
+
+        let block_at = |x, y, z| sections.get_block_absolute_y(x, y, z).unwrap();
+        for (x, z) in [(0, 0), (7, 12), (15, 15)] {
+            assert_eq!(block_at(x, -64, z), Block::BEDROCK.default_state.id);
+            assert_eq!(block_at(x, -63, z), Block::DIRT.default_state.id);
+            assert_eq!(block_at(x, -62, z), Block::DIRT.default_state.id);
+            assert_eq!(block_at(x, -61, z), Block::GRASS_BLOCK.default_state.id);
+            assert_eq!(block_at(x, -60, z), Block::AIR.default_state.id);
+            assert_eq!(block_at(x, 319, z), Block::AIR.default_state.id);
+        }
+        assert_eq!(
+            sections.get_rough_biome_absolute_y(0, 100, 0),
+            Some(Biome::PLAINS.id)
+        );
+    }
+
+    #[test]
+    fn ocean_floor_ignores_water() {
+        let generator = SuperflatGenerator::with_layers(
+            Dimension::Overworld,
+            [
+                (Block::BEDROCK.default_state, 1),
+                (Block::STONE.default_state, 3),
+                (Block::WATER.default_state, 5),
+            ],
+            &Biome::OCEAN,
+        );
+        let mut chunk = generator.generate_chunk(Vector2::new(0, 0));
+        let heightmaps = chunk.calculate_heightmap();
+
+        for (x, z) in [(0, 0), (9, 4), (15, 15)] {
+            let height = |heightmap| heightmaps.get_height(heightmap, x, z, -64);
+            assert_eq!(height(ChunkHeightmapType::WorldSurface), -56);
+            assert_eq!(height(ChunkHeightmapType::OceanFloor), -61);
+        }
+    }
+
+    #[test]
+    fn layers_stop_at_the_build_limit() {
+        let generator = SuperflatGenerator::with_layers(
+            Dimension::Nether,
+            [(Block::NETHERRACK.default_state, 1000)],
+            &Biome::NETHER_WASTES,
+        );
+        let chunk = generator.generate_chunk(Vector2::new(0, 0));
+
+        assert_eq!(chunk.section.sections.len(), 16);
+        assert_eq!(
+            chunk.section.get_block_absolute_y(4, 255, 4),
+            Some(Block::NETHERRACK.default_state.id)
+        );
+
+        // The build limit in the middle of the top section
+        const SHORT_NETHER: DimensionConfig = DimensionConfig {
+            name: "test:short_nether",
+            based_on: Dimension::Nether,
+            min_y: 0,
+            height: 120,
+            has_skylight: false,
+            sea_level: 32,
+            noise_router: NETHER_BASE_NOISE_ROUTER,
+            biome_source: BiomeSource::Dimension,
+        };
+        let generator = SuperflatGenerator::with_layers(
+            Dimension::Custom(Arc::new(SHORT_NETHER)),
+            [(Block::NETHERRACK.default_state, 1000)],
+            &Biome::NETHER_WASTES,
+        );
+        let chunk = generator.generate_chunk(Vector2::new(0, 0));
+
+        assert_eq!(chunk.section.sections.len(), 8);
+        assert_eq!(
+            chunk.section.get_block_absolute_y(4, 119, 4),
+            Some(Block::NETHERRACK.default_state.id)
+        );
+        assert_eq!(
+            chunk.section.get_block_absolute_y(4, 120, 4),
+            Some(Block::AIR.default_state.id)
+        );
+    }
+}
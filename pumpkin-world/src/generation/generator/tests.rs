@@ -0,0 +1,1993 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::num::NonZeroU8;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use std::{env, fs, iter};
+
+use async_trait::async_trait;
+use pumpkin_config::LevelType;
+use pumpkin_config::chunk::{ChunkConfig, StructureSpacing};
+use pumpkin_data::{
+    Block, BlockDirection, BlockState,
+    chunk::Biome,
+    dimension_type::DimensionType,
+    noise_router::{NETHER_BASE_NOISE_ROUTER, OVERWORLD_BASE_NOISE_ROUTER},
+};
+use pumpkin_util::math::{position::BlockPos, vector2::Vector2, vector3::Vector3};
+use sha2::{Digest, Sha256};
+use temp_dir::TempDir;
+
+use super::{
+    GenProgress, GenerationCache, GenerationPass, GenerationShapeConfig, GenerationStage,
+    GeneratorError, GeneratorInit, GeneratorPool, LoadBatch, LoadRequest, RingIterator,
+    StageTimings, VanillaGenerator, WorkerSettings, WorldGenerator, check_shape, empty_chunk,
+    initialize_generator,
+};
+use crate::chunk::format::LightContainer;
+use crate::chunk::format::anvil::SingleChunkDataSerializer;
+use crate::chunk::{
+    ChunkData, ChunkHeightmapType,
+    palette::{BlockPalette, NetworkPalette},
+};
+use crate::dimension::{Dimension, DimensionConfig};
+use crate::generation::{
+    GeneratorKind, Seed, get_world_gen,
+    noise::router::{
+        custom_router::{load_noise_settings, test::write_vanilla_nether},
+        proto_noise_router::InvalidNoiseRouter,
+    },
+    settings::{BiomeSource, WorldType},
+};
+use crate::global_path;
+use crate::level::Level;
+use crate::world::{BlockAccessor, BlockRegistryExt};
+
+pub(crate) struct BlockRegistry;
+
+#[async_trait]
+impl BlockRegistryExt for BlockRegistry {
+    fn can_place_at(
+        &self,
+        _block: &pumpkin_data::Block,
+        _block_accessor: &dyn BlockAccessor,
+        _block_pos: &BlockPos,
+        _face: BlockDirection,
+    ) -> bool {
+        true
+    }
+}
+
+/// A level of seed 0 in a temporary folder, which lives as long as the returned [`TempDir`].
+fn test_level(dimension: Dimension) -> (Arc<Level>, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let level = Level::from_root_folder(
+        temp_dir.path().to_path_buf(),
+        Arc::new(BlockRegistry),
+        0,
+        dimension,
+    );
+    (level, temp_dir)
+}
+
+/// How many blocks of `chunk` are `block`, in any of its states.
+fn count_blocks(chunk: &ChunkData, block: &Block) -> usize {
+    chunk
+        .section
+        .dump_blocks()
+        .into_iter()
+        .filter(|state| Block::from_state_id(*state) == block)
+        .count()
+}
+
+fn ring(x: i32, z: i32, radius: u32) -> RingIterator {
+    LoadRequest::new(Vector2::new(x, z), radius).into()
+}
+
+#[test]
+fn ring_zero_is_origin() {
+    let mut ring = ring(3, -7, 0);
+    assert_eq!(ring.next(), Some(Vector2::new(3, -7)));
+    assert_eq!(ring.next(), None);
+}
+
+#[test]
+fn ring_is_border_only() {
+    for radius in 1..8 {
+        let coords: Vec<_> = ring(5, 2, radius).collect();
+        assert_eq!(coords.len(), 8 * radius as usize);
+
+        let unique: HashSet<_> = coords.iter().copied().collect();
+        assert_eq!(unique.len(), coords.len());
+        for pos in coords {
+            let dx = (pos.x - 5).abs();
+            let dz = (pos.y - 2).abs();
+            assert_eq!(dx.max(dz), radius as i32);
+        }
+    }
+}
+
+#[test]
+fn ring_walks_clockwise() {
+    let coords: Vec<_> = ring(0, 0, 1).collect();
+    assert_eq!(
+        coords,
+        vec![
+            Vector2::new(-1, -1),
+            Vector2::new(0, -1),
+            Vector2::new(1, -1),
+            Vector2::new(1, 0),
+            Vector2::new(1, 1),
+            Vector2::new(0, 1),
+            Vector2::new(-1, 1),
+            Vector2::new(-1, 0),
+        ]
+    );
+}
+
+#[test]
+fn rings_tile_the_square() {
+    let mut seen = HashSet::new();
+    for radius in 0..6 {
+        for pos in ring(-4, 9, radius) {
+            assert!(seen.insert(pos), "{pos:?} was yielded twice");
+        }
+    }
+    assert_eq!(seen.len(), 11 * 11);
+}
+
+#[test]
+fn request_contains_its_rings() {
+    let request = LoadRequest::new(Vector2::new(2, 2), 3);
+    let mut last_light_ring = None;
+    for (ring, light, ..) in request.clone() {
+        assert!(ring.into_iter().all(|pos| request.contains(&pos)));
+        last_light_ring = Some(light);
+    }
+    // The light ring of the outermost step lies just outside the request
+    assert!(!last_light_ring.unwrap().any(|pos| request.contains(&pos)));
+}
+
+#[test]
+fn disk_covers_the_request_outwards() {
+    let request = LoadRequest::new(Vector2::new(-3, 5), 4);
+    let chunks: Vec<_> = request.disk_iter().collect();
+    assert_eq!(chunks.len(), 7 * 7);
+    assert_eq!(chunks.iter().collect::<HashSet<_>>().len(), chunks.len());
+    assert!(chunks.iter().all(|pos| request.contains(pos)));
+    let distance = |pos: &Vector2<i32>| (pos.x + 3).abs().max((pos.y - 5).abs());
+    assert!(chunks.is_sorted_by_key(distance));
+    assert_eq!(
+        LoadRequest::new(Vector2::new(0, 0), 0).disk_iter().count(),
+        0
+    );
+}
+
+#[test]
+fn view_distance_sets_the_radius() {
+    let origin = Vector2::new(10, -2);
+    let far = LoadRequest::for_view_distance(origin, NonZeroU8::new(8).unwrap());
+    let near = LoadRequest::for_view_distance(origin, NonZeroU8::new(2).unwrap());
+    assert_eq!(far.radius(), 9);
+    assert_eq!(far.disk_iter().count(), 17 * 17);
+    assert_eq!(near.disk_iter().count(), 5 * 5);
+    assert_eq!(near.clone().into_iter().count(), 3);
+    // The chunks right at the view distance are still in, the ones past it are not
+    assert!(near.contains(&Vector2::new(12, 0)));
+    assert!(!near.contains(&Vector2::new(13, 0)));
+    // Only some chunks of the view, and one past it
+    let some = near
+        .clone()
+        .only([Vector2::new(11, -1), Vector2::new(14, -2)]);
+    assert_eq!(some.radius(), 5);
+    assert_eq!(some.disk_iter().count(), 2);
+    assert_eq!(near.clone().only([origin]).radius(), near.radius());
+
+    // The neighbors of the outermost ring are prepared just as far out for both
+    let outermost = |request: LoadRequest| {
+        let (ring, light, carver, biome, _) = request.into_iter().next_back().unwrap();
+        let reach = |ring: RingIterator| {
+            ring.map(|pos| (pos.x - origin.x).abs().max((pos.y - origin.y).abs()))
+                .max()
+                .unwrap()
+        };
+        [
+            reach(light) - reach(ring),
+            reach(carver) - reach(ring),
+            reach(biome) - reach(ring),
+        ]
+    };
+    assert_eq!(outermost(far), outermost(near));
+}
+
+#[test]
+fn padding_walks_its_own_ring() {
+    let mut inner = ring(0, 0, 1);
+    inner.next();
+    inner.next();
+
+    let padded = inner.with_padding(2);
+    assert_eq!(padded.len(), 8 * 3);
+    assert!(
+        padded
+            .into_iter()
+            .all(|pos| pos.x.abs().max(pos.y.abs()) == 3)
+    );
+}
+
+#[test]
+fn cancelled_request_stops_generating() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+
+    // Nothing is generated once the request is cancelled
+    let request = LoadRequest::new(Vector2::new(0, 0), 16);
+    request.cancel();
+    generator.request_load(&level, request);
+    assert_eq!(level.loaded_chunk_count(), 0);
+
+    // Cancelled right after the first chunk was handed over, the next step stops before
+    // finishing any other
+    let request = LoadRequest::new(Vector2::new(0, 0), 16);
+    let mut steps = request.clone().into_iter();
+    let mut proto_chunks = HashMap::new();
+    let mut timings = StageTimings::default();
+    assert!(generator.generate_ring(
+        &level,
+        &request,
+        &mut proto_chunks,
+        steps.next().unwrap(),
+        &mut timings,
+    ));
+    assert_eq!(level.loaded_chunk_count(), 1);
+    request.cancel();
+    assert!(!generator.generate_ring(
+        &level,
+        &request,
+        &mut proto_chunks,
+        steps.next().unwrap(),
+        &mut timings,
+    ));
+    assert_eq!(level.loaded_chunk_count(), 1);
+}
+
+/// Generates `request` on a new pool of `threads` threads, returning the level the chunks
+/// ended up in once all of them are finished.
+fn generate_on_pool(threads: usize, request: LoadRequest) -> (Arc<Level>, TempDir) {
+    let generator = get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .into();
+    generate_with_pool(threads, generator, Dimension::Overworld, request)
+}
+
+/// Like [`generate_on_pool`], with `generator` generating `dimension`.
+fn generate_with_pool(
+    threads: usize,
+    generator: Arc<dyn WorldGenerator>,
+    dimension: Dimension,
+    request: LoadRequest,
+) -> (Arc<Level>, TempDir) {
+    let (level, temp_dir) = test_level(dimension);
+
+    let chunks = request.disk_iter().count();
+    let (tx, rx) = crossbeam::channel::unbounded();
+    let (settings, workers) = WorkerSettings::new(threads);
+    let threads: Vec<_> = workers
+        .into_iter()
+        .map(|worker| {
+            let (rx, generator, level, settings) = (
+                rx.clone(),
+                generator.clone(),
+                level.clone(),
+                settings.clone(),
+            );
+            std::thread::spawn(move || initialize_generator(rx, generator, level, worker, settings))
+        })
+        .collect();
+    tx.send(request.into()).unwrap();
+
+    let start = Instant::now();
+    while level.loaded_chunk_count() < chunks {
+        assert!(start.elapsed() < Duration::from_secs(600), "timed out");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    // Idle threads return once there are no more requests to wait for
+    drop(tx);
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    (level, temp_dir)
+}
+
+#[test]
+fn pool_matches_a_single_request() {
+    // Two rings across the border between two tiles, which go to different threads
+    let chunks = [(3, 0), (3, 1), (4, 0), (4, 1)].map(|(x, z)| Vector2::new(x, z));
+    let request = LoadRequest::for_chunks(Vector2::new(0, 0), chunks);
+    assert_eq!(request.tiles().len(), 2);
+    let (pool, _pool_dir) = generate_on_pool(2, request.clone());
+
+    let (single, _temp_dir) = test_level(Dimension::Overworld);
+    get_world_gen(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .request_load(&single, request);
+
+    for pos in chunks {
+        let single = single.try_get_chunk(&pos).unwrap();
+        let pool = pool.try_get_chunk(&pos).unwrap();
+        assert!(
+            contents(&single.blocking_read()) == contents(&pool.blocking_read()),
+            "{pos:?} differs"
+        );
+    }
+}
+
+#[test]
+fn pool_reuses_the_neighbors_of_its_tiles() {
+    // Two tiles side by side, taken one after the other by the single thread
+    let chunks = (-3..=10).flat_map(|x| (-3..=3).map(move |z| Vector2::new(x, z)));
+    let request = LoadRequest::for_chunks(Vector2::new(0, 0), chunks);
+    assert_eq!(request.tiles().len(), 2);
+    let pool =
+        Arc::new(VanillaGenerator::new(Seed(0), Dimension::End, Arc::new(BlockRegistry)).unwrap());
+    generate_with_pool(1, pool.clone(), Dimension::End, request.clone());
+
+    let (level, _temp_dir) = test_level(Dimension::End);
+    let single = VanillaGenerator::new(Seed(0), Dimension::End, Arc::new(BlockRegistry)).unwrap();
+    single.request_load(&level, request);
+
+    // The terrain reaches two chunks past the requested ones, the decoration one
+    let (pool, single) = (pool.timings().stages, single.timings().stages);
+    assert_eq!(pool.terrain_chunks, 18 * 11);
+    assert_eq!(pool.decorated_chunks, 16 * 9);
+    assert_eq!(
+        (pool.terrain_chunks, pool.decorated_chunks),
+        (single.terrain_chunks, single.decorated_chunks)
+    );
+}
+
+/// Every block and light level of `chunk`.
+fn contents(chunk: &ChunkData) -> (Vec<u16>, Vec<u8>) {
+    let cube = (0..16).flat_map(|y| (0..16).flat_map(move |z| (0..16).map(move |x| (x, y, z))));
+    let blocks = chunk
+        .section
+        .sections
+        .iter()
+        .flat_map(|section| {
+            cube.clone()
+                .map(|(x, y, z)| section.block_states.get(x, y, z))
+        })
+        .collect();
+    let light = chunk
+        .light_engine
+        .sky_light
+        .iter()
+        .chain(&chunk.light_engine.block_light)
+        .flat_map(|light| cube.clone().map(|(x, y, z)| light.get(x, y, z)))
+        .collect();
+    (blocks, light)
+}
+
+/// Generates the same chunks on several threads at once with a single generator, whose
+/// clones share their noise routers and caches, and compares them with chunks generated one
+/// after the other by a generator of their own. Any state shared between the threads that
+/// isn't only read shows up as a chunk that differs.
+#[test]
+fn shared_generator_is_deterministic_across_threads() {
+    const THREADS: usize = 3;
+    // Spread out to fill the caches with many regions, with neighbors generated at once
+    let positions = [
+        Vector2::new(0, 0),
+        Vector2::new(1, 0),
+        Vector2::new(-37, 21),
+        Vector2::new(64, -90),
+    ];
+    let reference =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let expected: Vec<_> = positions
+        .iter()
+        .map(|pos| {
+            let chunk = reference.generate_chunk(*pos);
+            (contents(&chunk), chunk.section.dump_biomes())
+        })
+        .collect();
+
+    let shared =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let threads: Vec<_> = (0..THREADS)
+        .map(|thread| {
+            let generator = shared.clone();
+            std::thread::spawn(move || {
+                // Every thread starts somewhere else, so that the same chunk is generated
+                // on different threads at the same time
+                (0..positions.len())
+                    .map(|i| (i + thread) % positions.len())
+                    .map(|i| {
+                        let chunk = generator.generate_chunk(positions[i]);
+                        (i, (contents(&chunk), chunk.section.dump_biomes()))
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    for (thread, handle) in threads.into_iter().enumerate() {
+        for (i, generated) in handle.join().unwrap() {
+            assert!(
+                generated == expected[i],
+                "{:?} generated on thread {thread} differs from the reference",
+                positions[i]
+            );
+        }
+    }
+}
+
+#[test]
+fn thread_count_follows_the_config() {
+    let mut config = ChunkConfig::default();
+    // Every core but one by default
+    assert_eq!(GeneratorPool::thread_count(&config, 8), 7);
+    assert_eq!(GeneratorPool::thread_count(&config, 1), 1);
+    config.reserved_cores = 3;
+    assert_eq!(GeneratorPool::thread_count(&config, 8), 5);
+    assert_eq!(GeneratorPool::thread_count(&config, 2), 1);
+
+    config.generation_threads = 2;
+    assert_eq!(GeneratorPool::thread_count(&config, 8), 2);
+    // Only warned about
+    config.generation_threads = 16;
+    assert_eq!(GeneratorPool::thread_count(&config, 8), 16);
+}
+
+#[test]
+fn worker_settings_follow_the_config() {
+    let mut config = ChunkConfig::default();
+    let (settings, workers) = WorkerSettings::from_config(&config, 4);
+    assert_eq!(workers.len(), 3);
+    assert_eq!(settings.budget, None);
+    assert!(settings.cache.is_none());
+
+    config.generation_budget_ms = 20;
+    let (settings, _) = WorkerSettings::from_config(&config, 4);
+    assert_eq!(settings.budget, Some(Duration::from_millis(20)));
+}
+
+#[test]
+fn overlapping_requests_generate_once() {
+    let (pool, workers) = GeneratorPool::new(1);
+    let generate = |request: LoadRequest| {
+        pool.submit(request.into());
+        iter::from_fn(|| pool.find_job(&workers[0]))
+            .flat_map(|tile| tile.disk_iter())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(generate(LoadRequest::new(Vector2::new(0, 0), 2)).len(), 9);
+    let overlapping = generate(LoadRequest::new(Vector2::new(1, 0), 2));
+    assert_eq!(overlapping.len(), 3);
+    assert!(overlapping.iter().all(|pos| pos.x == 2));
+
+    // Once unloaded, a chunk is generated again
+    pool.forget([Vector2::new(0, 0)]);
+    let again = generate(LoadRequest::new(Vector2::new(0, 0), 2));
+    assert_eq!(again, vec![Vector2::new(0, 0)]);
+}
+
+/// Records the order it is asked to generate chunks in, handing out empty ones.
+#[derive(Default)]
+struct RecordingGenerator(Mutex<Vec<Vector2<i32>>>);
+
+impl WorldGenerator for RecordingGenerator {
+    fn request_load(&self, level: &Arc<Level>, request: LoadRequest) {
+        for pos in request.disk_iter() {
+            self.0.lock().unwrap().push(pos);
+            level.set_generated_chunk(pos, empty_chunk(pos, &Dimension::Overworld));
+        }
+    }
+}
+
+#[test]
+fn chunks_near_players_come_first() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let generator = Arc::new(RecordingGenerator::default());
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    // Chunks that were far away from the player, queued before the player teleported
+    let far = LoadRequest::new(Vector2::new(40, 40), 3).with_priority(20);
+    tx.send(far.clone().into()).unwrap();
+    let near = LoadRequest::new(Vector2::new(0, 0), 3);
+    tx.send(near.clone().into()).unwrap();
+
+    let (settings, mut workers) = WorkerSettings::new(1);
+    let thread = {
+        let (generator, level) = (generator.clone(), level.clone());
+        std::thread::spawn(move || {
+            initialize_generator(rx, generator, level, workers.remove(0), settings);
+        })
+    };
+    let start = Instant::now();
+    while generator.0.lock().unwrap().len() < 2 * 25 {
+        assert!(start.elapsed() < Duration::from_secs(60), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    drop(tx);
+    thread.join().unwrap();
+
+    let generated = generator.0.lock().unwrap();
+    assert!(generated[..25].iter().all(|pos| near.contains(pos)));
+    assert_eq!(generated[0], Vector2::new(0, 0));
+    // Within a request, the chunks closer to its center come first too
+    assert_eq!(generated[25], Vector2::new(40, 40));
+    assert!(
+        generated[26..34]
+            .iter()
+            .all(|pos| (pos.x - 40).abs().max((pos.y - 40).abs()) == 1)
+    );
+}
+
+#[test]
+fn batches_are_split_into_their_chunks() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let generator = Arc::new(RecordingGenerator::default());
+
+    // A joining player missing a few scattered chunks and a whole square, in one message
+    let (tx, rx) = crossbeam::channel::unbounded();
+    let scattered = [(5, 0, 7), (-3, 2, 3), (0, 9, 9)]
+        .map(|(x, z, priority)| LoadRequest::new(Vector2::new(x, z), 1).with_priority(priority));
+    let square = LoadRequest::new(Vector2::new(20, 20), 2).with_priority(1);
+    let batch: LoadBatch = scattered.iter().chain([&square]).cloned().collect();
+    tx.send(batch).unwrap();
+    assert_eq!(tx.len(), 1);
+
+    let (settings, mut workers) = WorkerSettings::new(1);
+    let thread = {
+        let (generator, level) = (generator.clone(), level.clone());
+        std::thread::spawn(move || {
+            initialize_generator(rx, generator, level, workers.remove(0), settings);
+        })
+    };
+    let start = Instant::now();
+    while generator.0.lock().unwrap().len() < 3 + 9 {
+        assert!(start.elapsed() < Duration::from_secs(60), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    drop(tx);
+    thread.join().unwrap();
+
+    // The same order as if they had been sent one by one
+    let generated = generator.0.lock().unwrap();
+    assert_eq!(generated[0], Vector2::new(20, 20));
+    assert!(generated[..9].iter().all(|pos| square.contains(pos)));
+    assert_eq!(
+        generated[9..],
+        [Vector2::new(-3, 2), Vector2::new(5, 0), Vector2::new(0, 9)]
+    );
+}
+
+#[test]
+fn cached_chunks_are_not_generated_again() {
+    let temp_dir = TempDir::new().unwrap();
+    let level = Level::from_root_folder(
+        temp_dir.path().join("world"),
+        Arc::new(BlockRegistry),
+        0,
+        Dimension::Overworld,
+    );
+    let generator = Arc::new(RecordingGenerator::default());
+    let cache = Arc::new(GenerationCache::new(
+        &temp_dir.path().join("cache"),
+        Seed(0),
+        &Dimension::Overworld,
+        "",
+    ));
+    let request = LoadRequest::new(Vector2::new(0, 0), 2);
+    for x in -1..=1 {
+        for z in -1..=1 {
+            if (x, z) != (0, 0) {
+                cache.store(&empty_chunk(Vector2::new(x, z), &Dimension::Overworld));
+            }
+        }
+    }
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    tx.send(request.into()).unwrap();
+    let (mut settings, mut workers) = WorkerSettings::new(1);
+    settings.cache = Some(cache);
+    let thread = {
+        let (generator, level) = (generator.clone(), level.clone());
+        std::thread::spawn(move || {
+            initialize_generator(rx, generator, level, workers.remove(0), settings);
+        })
+    };
+    let start = Instant::now();
+    while level.loaded_chunk_count() < 8 || generator.0.lock().unwrap().is_empty() {
+        assert!(start.elapsed() < Duration::from_secs(60), "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    drop(tx);
+    thread.join().unwrap();
+
+    assert_eq!(*generator.0.lock().unwrap(), [Vector2::new(0, 0)]);
+}
+
+#[test]
+fn progress_is_reported() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let generator = Arc::new(RecordingGenerator::default());
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    tx.send(LoadRequest::new(Vector2::new(0, 0), 2).into())
+        .unwrap();
+    let (progress_tx, progress_rx) = crossbeam::channel::bounded(64);
+    let (mut settings, mut workers) = WorkerSettings::new(1);
+    settings.progress = Some(progress_tx);
+    let thread = {
+        let (generator, level) = (generator.clone(), level.clone());
+        std::thread::spawn(move || {
+            initialize_generator(rx, generator, level, workers.remove(0), settings);
+        })
+    };
+    let mut events = Vec::new();
+    while events.last() != Some(&GenProgress::QueueDrained) {
+        events.push(progress_rx.recv_timeout(Duration::from_secs(60)).unwrap());
+    }
+    drop(tx);
+    thread.join().unwrap();
+
+    // The chunks of a tile are generated together
+    let generated = generator.0.lock().unwrap();
+    let expected: Vec<_> = generated
+        .iter()
+        .map(|&pos| GenProgress::ChunkStarted { pos })
+        .chain(
+            generated
+                .iter()
+                .map(|&pos| GenProgress::ChunkFinished { pos }),
+        )
+        .chain([GenProgress::QueueDrained])
+        .collect();
+    assert_eq!(generated.len(), 9);
+    assert_eq!(events, expected);
+}
+
+/// Takes a while for every chunk, like real generation does.
+struct SlowGenerator;
+
+impl WorldGenerator for SlowGenerator {
+    fn request_load(&self, _level: &Arc<Level>, request: LoadRequest) {
+        let chunks = request.disk_iter().count() as u32;
+        std::thread::sleep(Duration::from_micros(200) * chunks);
+    }
+}
+
+#[test]
+fn budget_bounds_each_stretch_of_work() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let budget = Duration::from_millis(5);
+
+    let (tx, rx) = crossbeam::channel::unbounded();
+    tx.send(LoadRequest::new(Vector2::new(0, 0), 16).into())
+        .unwrap();
+    let (progress_tx, progress_rx) = crossbeam::channel::bounded(4096);
+    let (mut settings, mut workers) = WorkerSettings::new(1);
+    settings.progress = Some(progress_tx);
+    settings.budget = Some(budget);
+    let thread = std::thread::spawn(move || {
+        let worker = workers.remove(0);
+        initialize_generator(rx, Arc::new(SlowGenerator), level, worker, settings);
+    });
+    let mut stretches = Vec::new();
+    loop {
+        match progress_rx.recv_timeout(Duration::from_secs(60)).unwrap() {
+            GenProgress::Yielded { worked } => stretches.push(worked),
+            GenProgress::QueueDrained => break,
+            _ => {}
+        }
+    }
+    drop(tx);
+    thread.join().unwrap();
+
+    // 25 tiles of up to 10ms each don't fit into a few stretches of 5ms
+    assert!(stretches.len() >= 20, "{stretches:?}");
+    // Each stretch ends with the tile that used up the budget, leaving some room for a
+    // busy machine
+    for worked in stretches {
+        assert!(worked >= budget && worked < budget + Duration::from_millis(50));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn waiting_returns_the_generated_chunk() {
+    let (level, _temp_dir) = test_level(Dimension::End);
+    let pos = Vector2::new(2, -3);
+    let chunk = level.request_load_and_wait(pos).await;
+    assert_eq!(chunk.read().await.position, pos);
+    // Waiting again hands out the loaded chunk
+    let again = level.request_load_and_wait(pos).await;
+    assert!(Arc::ptr_eq(&chunk, &again));
+    level.shutdown().await;
+}
+
+#[tokio::test]
+async fn chunks_of_another_seed_are_noticed() {
+    let temp_dir = TempDir::new().unwrap();
+    let level = |seed, dimension| {
+        Level::from_root_folder(
+            temp_dir.path().join(format!("{seed} {dimension:?}")),
+            Arc::new(BlockRegistry),
+            seed,
+            dimension,
+        )
+    };
+    let (level, other_seed, other_dimension) = (
+        level(0, Dimension::Overworld),
+        level(1, Dimension::Overworld),
+        level(0, Dimension::End),
+    );
+
+    let pos = Vector2::new(3, 4);
+    level.set_generated_chunk(pos, empty_chunk(pos, &Dimension::Overworld));
+    let chunk = level.try_get_chunk(&pos).unwrap().clone();
+    let bytes = chunk.read().await.to_bytes().await.unwrap();
+    let mut loaded = ChunkData::from_bytes(bytes, pos).unwrap();
+    assert_eq!(loaded.generated_by, Some(level.generator_stamp()));
+
+    assert!(level.check_generator(&loaded));
+    assert!(!other_seed.check_generator(&loaded));
+    assert!(!other_dimension.check_generator(&loaded));
+    // Chunks from before the generator was recorded can't be checked
+    loaded.generated_by = None;
+    assert!(other_seed.check_generator(&loaded));
+}
+
+#[tokio::test]
+async fn generators_can_be_awaited() {
+    let (level, _temp_dir) = test_level(Dimension::End);
+    let generator: Arc<dyn WorldGenerator> =
+        get_world_gen(Seed(0), Dimension::End, Arc::new(BlockRegistry))
+            .unwrap()
+            .into();
+
+    let pos = Vector2::new(1, 2);
+    generator
+        .clone()
+        .load(level.clone(), LoadRequest::new(pos, 1))
+        .await;
+    assert!(level.try_get_chunk(&pos).is_some());
+
+    // A cancelled request returns right away
+    let request = LoadRequest::new(Vector2::new(40, 40), 8);
+    request.cancel();
+    generator.load(level.clone(), request).await;
+    assert_eq!(level.loaded_chunk_count(), 1);
+}
+
+#[tokio::test]
+async fn unloading_chunks_cancels_their_generation() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let center = Vector2::new(0, 0);
+    let chunks: Vec<_> = LoadRequest::new(center, 16).disk_iter().collect();
+    let mut receiver = level.receive_chunks(chunks.clone(), center);
+    assert!(receiver.recv().await.is_some());
+
+    // Nobody watches them, so all of them are unloaded
+    level.clean_chunks(&chunks).await;
+    let mut received = 1;
+    while receiver.recv().await.is_some() {
+        received += 1;
+    }
+    assert!(received < chunks.len());
+}
+
+#[test]
+fn structure_spacing_is_checked() {
+    let mut generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let spacing = |spacing, separation| StructureSpacing {
+        spacing,
+        separation,
+    };
+    assert!(
+        generator
+            .set_structure_spacing("villages", spacing(68, 8))
+            .is_ok()
+    );
+    assert!(
+        generator
+            .set_structure_spacing("mineshafts", spacing(1, 0))
+            .is_ok()
+    );
+    for (set_name, spacing) in [
+        ("castles", spacing(68, 8)),
+        // Placed in rings
+        ("strongholds", spacing(68, 8)),
+        ("villages", spacing(8, 8)),
+        ("villages", spacing(34, -1)),
+    ] {
+        assert!(matches!(
+            generator.set_structure_spacing(set_name, spacing),
+            Err(GeneratorError::InvalidStructureSpacing { .. })
+        ));
+    }
+    assert_eq!(generator.structure_spacing["villages"], spacing(68, 8));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn level_type_picks_the_generator() {
+    let temp_dir = TempDir::new().unwrap();
+    let block_at = async |level_type, dimension: Dimension, y| {
+        let level = dimension
+            .into_level(
+                temp_dir.path().join(format!("{level_type:?}")),
+                Arc::new(BlockRegistry),
+                0,
+                GeneratorKind::from(level_type),
+            )
+            .unwrap();
+        let chunk = level.request_load_and_wait(Vector2::new(0, 0)).await;
+        let state = chunk.read().await.section.get_block_absolute_y(0, y, 0);
+        level.shutdown().await;
+        state.map(Block::from_state_id)
+    };
+
+    assert_eq!(
+        block_at(LevelType::Flat, Dimension::Overworld, -61).await,
+        Some(&Block::GRASS_BLOCK)
+    );
+    assert_eq!(
+        block_at(LevelType::Void, Dimension::Overworld, 63).await,
+        Some(&Block::STONE)
+    );
+    assert_eq!(
+        block_at(LevelType::Void, Dimension::Overworld, -64).await,
+        Some(&Block::AIR)
+    );
+    // The Nether stays the vanilla one
+    assert_ne!(
+        block_at(LevelType::Flat, Dimension::Nether, 3).await,
+        Some(&Block::GRASS_BLOCK)
+    );
+}
+
+#[test]
+fn finished_chunk_spans_the_dimension() {
+    let (level, _temp_dir) = test_level(Dimension::Nether);
+    let generator = get_world_gen(Seed(0), Dimension::Nether, Arc::new(BlockRegistry)).unwrap();
+
+    let pos = Vector2::new(0, 0);
+    generator.request_load(&level, LoadRequest::new(pos, 1));
+    let chunk = level.try_get_chunk(&pos).unwrap();
+    let chunk = chunk.blocking_read();
+
+    // The terrain shape is only 128 blocks high, the dimension is twice that
+    assert_eq!(chunk.section.sections.len(), 16);
+    assert_eq!(chunk.section.min_y, 0);
+    assert_eq!(
+        chunk.section.get_block_absolute_y(0, 0, 0),
+        Some(Block::BEDROCK.default_state.id)
+    );
+    assert_eq!(
+        chunk.section.get_block_absolute_y(0, 200, 0),
+        Some(Block::AIR.default_state.id)
+    );
+}
+
+/// A Nether with a lava sea twice as high and a single biome, only as high as its terrain.
+const LAVA_MOON: DimensionConfig = DimensionConfig {
+    name: "test:lava_moon",
+    based_on: Dimension::Nether,
+    min_y: 0,
+    height: 128,
+    has_skylight: true,
+    sea_level: 64,
+    noise_router: NETHER_BASE_NOISE_ROUTER,
+    biome_source: BiomeSource::Fixed(&Biome::BASALT_DELTAS),
+};
+
+#[test]
+fn levels_without_a_generator_are_not_opened() {
+    let mut noise_router = OVERWORLD_BASE_NOISE_ROUTER;
+    noise_router.noise.final_density = noise_router.noise.full_component_stack.len();
+    let broken = Arc::new(DimensionConfig {
+        name: "test:broken",
+        based_on: Dimension::Overworld,
+        min_y: -64,
+        height: 384,
+        has_skylight: true,
+        sea_level: 63,
+        noise_router,
+        biome_source: BiomeSource::Dimension,
+    });
+    let temp_dir = TempDir::new().unwrap();
+    let level = Dimension::Custom(broken).into_level(
+        temp_dir.path().to_path_buf(),
+        Arc::new(BlockRegistry),
+        0,
+        GeneratorKind::default(),
+    );
+    assert!(matches!(level, Err(GeneratorError::InvalidNoiseRouter(_))));
+}
+
+#[test]
+fn custom_dimension_generates_with_its_own_settings() {
+    let lava_at = |dimension: Dimension| {
+        let generator = VanillaGenerator::new(Seed(0), dimension, Arc::new(BlockRegistry)).unwrap();
+        let chunk = generator.generate_chunk(Vector2::new(0, 0));
+        let lava = (0..16)
+            .flat_map(|x| (0..16).map(move |z| (x, z)))
+            .filter(|(x, z)| {
+                chunk.section.get_block_absolute_y(*x, 63, *z) == Some(Block::LAVA.default_state.id)
+            })
+            .count();
+        (chunk, lava)
+    };
+    let (chunk, moon_lava) = lava_at(Dimension::Custom(Arc::new(LAVA_MOON)));
+    let (_, nether_lava) = lava_at(Dimension::Nether);
+
+    assert_eq!(chunk.section.sections.len(), 8);
+    assert_eq!(chunk.section.min_y, 0);
+    assert!(
+        chunk
+            .section
+            .dump_biomes()
+            .iter()
+            .all(|biome| *biome == Biome::BASALT_DELTAS.id)
+    );
+    assert!(moon_lava > nether_lava, "{moon_lava} <= {nether_lava}");
+    // Shaped like the Nether, with bedrock at the bottom
+    assert_eq!(
+        chunk.section.get_block_absolute_y(0, 0, 0),
+        Some(Block::BEDROCK.default_state.id)
+    );
+}
+
+#[test]
+fn custom_dimensions_can_be_based_on_custom_ones() {
+    let crater = Dimension::Custom(Arc::new(DimensionConfig {
+        name: "test:crater",
+        based_on: Dimension::Custom(Arc::new(LAVA_MOON)),
+        biome_source: BiomeSource::Dimension,
+        ..LAVA_MOON
+    }));
+    assert_eq!(crater.vanilla(), Dimension::Nether);
+    let chunk = VanillaGenerator::new(Seed(0), crater, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(Vector2::new(0, 0));
+    // With the biomes of the Nether
+    let nether = [
+        &Biome::NETHER_WASTES,
+        &Biome::SOUL_SAND_VALLEY,
+        &Biome::CRIMSON_FOREST,
+        &Biome::WARPED_FOREST,
+        &Biome::BASALT_DELTAS,
+    ]
+    .map(|biome| biome.id);
+    assert!(
+        chunk
+            .section
+            .dump_biomes()
+            .iter()
+            .all(|biome| nether.contains(biome))
+    );
+}
+
+#[test]
+fn terrain_reaches_the_edges_of_unaligned_dimensions() {
+    // Sections from y -66 up, so that the cells of the terrain don't line up with them
+    const LOW: DimensionConfig = DimensionConfig {
+        name: "test:low",
+        based_on: Dimension::Overworld,
+        min_y: -66,
+        height: 386,
+        has_skylight: true,
+        sea_level: 63,
+        noise_router: OVERWORLD_BASE_NOISE_ROUTER,
+        biome_source: BiomeSource::Dimension,
+    };
+    // A build limit in the middle of the top section
+    const TALL: DimensionConfig = DimensionConfig {
+        name: "test:tall",
+        based_on: Dimension::Overworld,
+        min_y: -64,
+        height: 390,
+        has_skylight: true,
+        sea_level: 63,
+        noise_router: OVERWORLD_BASE_NOISE_ROUTER,
+        biome_source: BiomeSource::Dimension,
+    };
+
+    let generate = |dimension: Dimension| {
+        let generator = VanillaGenerator::new(Seed(0), dimension, Arc::new(BlockRegistry)).unwrap();
+        generator.generate_chunk(Vector2::new(0, 0))
+    };
+    let overworld = generate(Dimension::Overworld);
+    for dimension in [
+        Dimension::Custom(Arc::new(LOW)),
+        Dimension::Custom(Arc::new(TALL)),
+    ] {
+        let chunk = generate(dimension.clone());
+        for (x, z) in [(0, 0), (7, 12), (15, 15)] {
+            assert_eq!(
+                chunk.section.get_block_absolute_y(x, -64, z),
+                Some(Block::BEDROCK.default_state.id)
+            );
+            for y in -64..320 {
+                assert_eq!(
+                    chunk.section.get_block_absolute_y(x, y, z),
+                    overworld.section.get_block_absolute_y(x, y, z),
+                    "{dimension:?} at {x} {y} {z}"
+                );
+            }
+            // The layers of biomes at the bottom and the top of the terrain aren't skipped
+            for y in [-64, 319] {
+                assert_eq!(
+                    chunk.section.get_rough_biome_absolute_y(x, y, z),
+                    overworld.section.get_rough_biome_absolute_y(x, y, z),
+                    "{dimension:?} at {x} {y} {z}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn chunks_span_the_height_of_their_dimension() {
+    // Taller than any vanilla dimension, with room above the terrain of the Overworld
+    const HIGH: DimensionConfig = DimensionConfig {
+        name: "test:high",
+        based_on: Dimension::Overworld,
+        min_y: -64,
+        height: 512,
+        has_skylight: true,
+        sea_level: 63,
+        noise_router: OVERWORLD_BASE_NOISE_ROUTER,
+        biome_source: BiomeSource::Dimension,
+    };
+
+    let generate = |dimension: Dimension| {
+        let generator = VanillaGenerator::new(Seed(0), dimension, Arc::new(BlockRegistry)).unwrap();
+        generator.generate_chunk(Vector2::new(0, 0))
+    };
+    // The vanilla heights are the ones synced to the clients
+    for (dimension, min_y, height) in [
+        (
+            Dimension::Overworld,
+            DimensionType::OVERWORLD.min_y,
+            DimensionType::OVERWORLD.height,
+        ),
+        (
+            Dimension::Nether,
+            DimensionType::THE_NETHER.min_y,
+            DimensionType::THE_NETHER.height,
+        ),
+        (
+            Dimension::End,
+            DimensionType::THE_END.min_y,
+            DimensionType::THE_END.height,
+        ),
+        (Dimension::Custom(Arc::new(HIGH)), HIGH.min_y, HIGH.height),
+    ] {
+        let chunk = generate(dimension.clone());
+        let top_y = min_y + height as i32;
+        assert_eq!(
+            chunk.section.sections.len(),
+            (height as usize).div_ceil(BlockPalette::SIZE),
+            "{dimension:?}"
+        );
+        assert_eq!(chunk.section.min_y, min_y, "{dimension:?}");
+        for (x, z) in [(0, 0), (7, 12), (15, 15)] {
+            assert!(
+                chunk
+                    .section
+                    .get_block_absolute_y(x, top_y - 1, z)
+                    .is_some(),
+                "{dimension:?} at {x} {z}"
+            );
+            assert_eq!(chunk.section.get_block_absolute_y(x, top_y, z), None);
+            assert_eq!(chunk.section.get_block_absolute_y(x, min_y - 1, z), None);
+        }
+    }
+
+    // Above the terrain of the Overworld, the custom dimension is left empty
+    let overworld = generate(Dimension::Overworld);
+    let chunk = generate(Dimension::Custom(Arc::new(HIGH)));
+    for (x, z) in [(0, 0), (7, 12), (15, 15)] {
+        for y in -64..320 {
+            assert_eq!(
+                chunk.section.get_block_absolute_y(x, y, z),
+                overworld.section.get_block_absolute_y(x, y, z),
+                "at {x} {y} {z}"
+            );
+        }
+        for y in 320..448 {
+            assert_eq!(
+                chunk.section.get_block_absolute_y(x, y, z),
+                Some(Block::AIR.default_state.id),
+                "at {x} {y} {z}"
+            );
+        }
+    }
+}
+
+#[test]
+fn generate_chunk_is_deterministic() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+
+    let pos = Vector2::new(-3, 5);
+    let first = VanillaGenerator::new(Seed(42), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(pos);
+    let second = VanillaGenerator::new(Seed(42), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(pos);
+
+    assert_eq!(first.position, pos);
+    assert_eq!(first.section.sections.len(), second.section.sections.len());
+    for (a, b) in first.section.sections.iter().zip(&second.section.sections) {
+        for y in 0..16 {
+            for z in 0..16 {
+                for x in 0..16 {
+                    assert_eq!(a.block_states.get(x, y, z), b.block_states.get(x, y, z));
+                }
+            }
+        }
+    }
+    // Nothing was handed to the level
+    assert!(level.try_get_chunk(&pos).is_none());
+}
+
+#[test]
+fn debug_generation_stops_after_the_pass() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let pos = Vector2::new(0, 0);
+    let blocks = |pass: GenerationPass| {
+        let proto_chunk = generator.debug_generate(pos, pass);
+        let mut blocks = Vec::new();
+        for x in 0..16 {
+            for y in -64..320 {
+                for z in 0..16 {
+                    let state = proto_chunk.get_block_state(&Vector3::new(x, y, z));
+                    blocks.push(state.0);
+                }
+            }
+        }
+        proto_chunk.recycle();
+        blocks
+    };
+
+    let noise = blocks(GenerationPass::Noise);
+    let surface = blocks(GenerationPass::Surface);
+    let carved = blocks(GenerationPass::Carvers);
+    let grass = Block::GRASS_BLOCK.default_state.id;
+    assert!(!noise.contains(&grass));
+    assert!(surface.contains(&grass));
+    // The carvers only dig out caves
+    let dug = [
+        Block::AIR.default_state.id,
+        Block::CAVE_AIR.default_state.id,
+        Block::WATER.default_state.id,
+        Block::LAVA.default_state.id,
+    ];
+    let mut carved_blocks = 0;
+    for (before, after) in surface.iter().zip(&carved) {
+        if before != after {
+            assert!(dug.contains(after), "{after} carved out of {before}");
+            carved_blocks += 1;
+        }
+    }
+    assert!(carved_blocks > 0);
+
+    // Decorated, the chunk holds the blocks of the generated one
+    let chunk = generator.generate_chunk(pos);
+    let decorated = blocks(GenerationPass::Features);
+    let mut index = 0;
+    for x in 0..16 {
+        for y in -64..320 {
+            for z in 0..16 {
+                assert_eq!(
+                    chunk.section.get_block_absolute_y(x, y, z),
+                    Some(decorated[index]),
+                    "at {x} {y} {z}"
+                );
+                index += 1;
+            }
+        }
+    }
+}
+
+#[test]
+fn generated_chunks_are_timed() {
+    let mut generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    generator.set_slow_chunk_threshold(Duration::ZERO);
+    assert_eq!(generator.timings().chunks, 0);
+
+    generator.generate_chunk(Vector2::new(0, 0));
+    let timings = generator.timings();
+    assert_eq!(timings.chunks, 1);
+    assert_eq!(timings.slow_chunks, 1);
+    // The neighbors of the chunk are shaped and decorated for it too
+    let stages = timings.stages;
+    assert!(stages.noise > Duration::ZERO);
+    assert!(stages.features > Duration::ZERO);
+    assert!(stages.light > Duration::ZERO);
+    assert_eq!(timings.average(), stages.total());
+
+    // Clones add to the same timings
+    generator.set_slow_chunk_threshold(Duration::MAX);
+    generator.clone().generate_chunk(Vector2::new(1, 0));
+    assert_eq!(generator.timings().chunks, 2);
+    assert_eq!(generator.timings().slow_chunks, 1);
+    assert_eq!(
+        WorldGenerator::timings(&generator),
+        Some(generator.timings())
+    );
+}
+
+#[test]
+fn prewarm_resolves_the_ring_starts_nearby() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let resolved = || generator.terrain_cache.stats().resolved_ring_starts;
+    let region =
+        |center: Vector2<i32>| LoadRequest::new(center, 16).disk_iter().collect::<Vec<_>>();
+
+    // No stronghold is anywhere near spawn
+    generator.prewarm(&region(Vector2::new(0, 0)));
+    assert_eq!(resolved(), 0);
+
+    let strongholds = &generator.terrain_cache.ring_starts["strongholds"];
+    let around_stronghold = region(strongholds.ring_position(0));
+    generator.prewarm(&around_stronghold);
+    let nearby = resolved();
+    assert!(nearby > 0);
+    assert_eq!(
+        strongholds.start(0, &mut |_| unreachable!("already resolved")),
+        Vector2::new(-13, -106)
+    );
+
+    generator.prewarm(&around_stronghold);
+    assert_eq!(resolved(), nearby);
+    generator.prewarm(&[]);
+    assert_eq!(resolved(), nearby);
+}
+
+#[test]
+fn legacy_random_changes_the_world() {
+    let legacy = || {
+        VanillaGenerator::with_random_source(
+            Seed(42),
+            Dimension::Overworld,
+            WorldType::Default,
+            BiomeSource::Dimension,
+            true,
+            Arc::new(BlockRegistry),
+        )
+        .unwrap()
+    };
+
+    let pos = Vector2::new(-3, 5);
+    let first = legacy().generate_chunk(pos);
+    let second = legacy().generate_chunk(pos);
+    let modern = VanillaGenerator::new(Seed(42), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(pos);
+    assert_eq!(first.section.dump_blocks(), second.section.dump_blocks());
+    assert_ne!(first.section.dump_blocks(), modern.section.dump_blocks());
+}
+
+#[test]
+fn oceans_grow_seagrass_and_kelp() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let block_at = |chunk: &ChunkData, x: usize, y: i32, z: usize| {
+        Block::from_state_id(chunk.section.get_block_absolute_y(x, y, z).unwrap())
+    };
+
+    // See ocean_floors_follow_the_biome for both oceans
+    let warm = generator.generate_chunk(Vector2::new(0, -160));
+    let seagrass =
+        count_blocks(&warm, &Block::SEAGRASS) + count_blocks(&warm, &Block::TALL_SEAGRASS);
+    assert!(seagrass > 0);
+
+    let deep = generator.generate_chunk(Vector2::new(-160, 100));
+    let mut tallest = 0;
+    for x in 0..16 {
+        for z in 0..16 {
+            let mut column = 0;
+            for y in 0..64 {
+                let block = block_at(&deep, x, y, z);
+                if block == &Block::KELP_PLANT {
+                    column += 1;
+                    let above = block_at(&deep, x, y + 1, z);
+                    assert!(above == &Block::KELP_PLANT || above == &Block::KELP);
+                } else if block == &Block::KELP {
+                    tallest = tallest.max(column + 1);
+                    column = 0;
+                    // Never out of the water
+                    assert_eq!(block_at(&deep, x, y + 1, z), &Block::WATER);
+                }
+            }
+        }
+    }
+    assert!(tallest >= 5, "the tallest kelp is {tallest} blocks");
+}
+
+#[test]
+fn cold_biomes_are_snowed_in() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+
+    // A snowy taiga with a frozen river through it
+    let taiga = generator.generate_chunk(Vector2::new(-250, 70));
+    assert!(count_blocks(&taiga, &Block::SNOW) > 0);
+    assert!(count_blocks(&taiga, &Block::ICE) > 0);
+    let snowy_grass = taiga
+        .section
+        .dump_blocks()
+        .into_iter()
+        .filter(|state| {
+            Block::from_state_id(*state) == &Block::GRASS_BLOCK
+                && *state != Block::GRASS_BLOCK.default_state.id
+        })
+        .count();
+    assert!(snowy_grass > 0);
+
+    // See plains_are_grass_over_dirt
+    let plains = generator.generate_chunk(Vector2::new(-4, 16));
+    assert_eq!(count_blocks(&plains, &Block::SNOW), 0);
+    assert_eq!(count_blocks(&plains, &Block::ICE), 0);
+}
+
+#[test]
+fn coasts_have_sandy_beaches() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+
+    // From plains in the east over a beach into a lukewarm ocean in the west
+    let plains = generator.generate_chunk(Vector2::new(-32, 32));
+    assert!(count_blocks(&plains, &Block::GRASS_BLOCK) > 0);
+    let ocean = generator.generate_chunk(Vector2::new(-36, 32));
+    assert_eq!(count_blocks(&ocean, &Block::GRASS_BLOCK), 0);
+
+    let beach = generator.generate_chunk(Vector2::new(-34, 32));
+    let min_y = beach.section.min_y;
+    let mut sand = 0;
+    for x in 0..16 {
+        for z in 0..16 {
+            let y = beach
+                .heightmap
+                .get_height(ChunkHeightmapType::OceanFloor, x, z, min_y);
+            let block = |y| {
+                Block::from_state_id(
+                    beach
+                        .section
+                        .get_block_absolute_y(x as usize, y, z as usize)
+                        .unwrap(),
+                )
+            };
+            assert_eq!(
+                Biome::from_id(
+                    beach
+                        .section
+                        .get_rough_biome_absolute_y(x as usize, y, z as usize)
+                        .unwrap()
+                ),
+                Some(&Biome::BEACH)
+            );
+            // The shore down to just below the water line is sand, not grass
+            assert_ne!(block(y), &Block::GRASS_BLOCK, "{x} {y} {z}");
+            if y >= generator.sea_level - 2 && block(y) == &Block::SAND {
+                sand += 1;
+            }
+        }
+    }
+    assert!(sand > 200, "{sand}");
+    // Held up by sandstone
+    assert!(count_blocks(&beach, &Block::SANDSTONE) > 0);
+}
+
+#[test]
+fn glass_fill_shows_the_caves() {
+    let glass = Block::GLASS.default_state;
+    // Plains, see plains_are_grass_over_dirt
+    let pos = Vector2::new(-4, 16);
+
+    let generator = VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .with_default_block(Some(glass), false);
+    let chunk = generator.generate_chunk(pos);
+    assert!(count_blocks(&chunk, &Block::GLASS) > 16 * 16 * 64);
+    // Nothing of the surface rules or the ores replacing stone, only the walls of a lava lake
+    assert!(count_blocks(&chunk, &Block::STONE) < 64);
+    for block in [&Block::DEEPSLATE, &Block::GRASS_BLOCK, &Block::BEDROCK] {
+        assert_eq!(count_blocks(&chunk, block), 0, "{}", block.name);
+    }
+    // The caves are still dug through it
+    let mut caves = 0;
+    for x in 0..16 {
+        for z in 0..16 {
+            for y in -50..0 {
+                let state = chunk.section.get_block_absolute_y(x, y, z).unwrap();
+                caves += BlockState::from_id(state).is_air() as usize;
+            }
+        }
+    }
+    assert!(caves > 0);
+
+    // Asked to, the surface rules cover the glass like stone
+    let generator = generator.with_default_block(Some(glass), true);
+    let covered = generator.generate_chunk(pos);
+    assert!(count_blocks(&covered, &Block::GRASS_BLOCK) > 0);
+    assert!(count_blocks(&covered, &Block::BEDROCK) > 0);
+    assert_eq!(
+        count_blocks(&covered, &Block::STONE),
+        count_blocks(&chunk, &Block::STONE)
+    );
+
+    // And back to stone
+    let generator = generator.with_default_block(None, false);
+    assert_eq!(
+        count_blocks(&generator.generate_chunk(pos), &Block::GLASS),
+        0
+    );
+}
+
+#[test]
+fn fixed_biome_source_keeps_the_terrain_shape() {
+    let generator = VanillaGenerator::with_biome_source(
+        Seed(0),
+        Dimension::Overworld,
+        WorldType::Default,
+        BiomeSource::Fixed(&Biome::MUSHROOM_FIELDS),
+        Arc::new(BlockRegistry),
+    )
+    .unwrap();
+    let air = |state: u16| BlockState::from_id(state).is_air();
+    let water = Block::WATER.default_state.id;
+
+    let mut surface_heights = HashSet::new();
+    let mut cave_air = 0;
+    for pos in [
+        Vector2::new(0, 0),
+        Vector2::new(-3, 5),
+        Vector2::new(12, -7),
+    ] {
+        let chunk = generator.generate_chunk(pos);
+        assert!(
+            chunk
+                .section
+                .dump_biomes()
+                .into_iter()
+                .all(|biome| biome == Biome::MUSHROOM_FIELDS.id)
+        );
+        for x in 0..16 {
+            for z in 0..16 {
+                let column: Vec<u16> = (-64..320)
+                    .map(|y| chunk.section.get_block_absolute_y(x, y, z).unwrap())
+                    .collect();
+                let top = column
+                    .iter()
+                    .rposition(|state| !air(*state) && *state != water)
+                    .unwrap();
+                surface_heights.insert(top);
+                cave_air += column[..top.saturating_sub(16)]
+                    .iter()
+                    .filter(|state| air(**state))
+                    .count();
+            }
+        }
+    }
+    // Hills and valleys, and caves underneath them, like the default biomes would have
+    assert!(surface_heights.len() > 10);
+    assert!(cave_air > 100);
+}
+
+#[test]
+fn checkerboard_biome_source_tiles_the_surface() {
+    static BIOMES: [&Biome; 2] = [&Biome::DESERT, &Biome::PLAINS];
+    let generator = VanillaGenerator::with_biome_source(
+        Seed(0),
+        Dimension::Overworld,
+        WorldType::Default,
+        BiomeSource::Checkerboard {
+            biomes: &BIOMES,
+            scale: 0,
+        },
+        Arc::new(BlockRegistry),
+    )
+    .unwrap();
+
+    // Each chunk is a cell of its own, the biomes taking turns along both axes
+    for (pos, biome) in [
+        (Vector2::new(0, 0), &Biome::DESERT),
+        (Vector2::new(1, 0), &Biome::PLAINS),
+        (Vector2::new(1, 1), &Biome::DESERT),
+        (Vector2::new(-1, 0), &Biome::PLAINS),
+    ] {
+        let chunk = generator.generate_chunk(pos);
+        assert!(
+            chunk
+                .section
+                .dump_biomes()
+                .into_iter()
+                .all(|id| id == biome.id),
+            "{pos:?}"
+        );
+        // Sand in the deserts, but grass in the plains around them over the same terrain
+        let (sand, grass) = (
+            count_blocks(&chunk, &Block::SAND),
+            count_blocks(&chunk, &Block::GRASS_BLOCK),
+        );
+        if biome == &Biome::DESERT {
+            assert!(sand > grass * 10, "{pos:?}");
+        } else {
+            assert!(grass * 10 > sand, "{pos:?}");
+        }
+    }
+}
+
+#[test]
+fn end_main_island_forms_around_the_origin() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::End, Arc::new(BlockRegistry)).unwrap();
+    let block_at = |chunk: &ChunkData, x: i32, y: i32, z: i32| {
+        chunk
+            .section
+            .get_block_absolute_y(x as usize, y, z as usize)
+            .unwrap()
+    };
+
+    // The main island is end stone through and through
+    let origin = generator.generate_chunk(Vector2::new(0, 0));
+    assert!(count_blocks(&origin, &Block::END_STONE) > 16 * 16 * 20);
+    assert_eq!(count_blocks(&origin, &Block::STONE), 0);
+    // Its edge falls off into the void long before the outer islands
+    let void = generator.generate_chunk(Vector2::new(20, 0));
+    assert_eq!(count_blocks(&void, &Block::END_STONE), 0);
+
+    // The spike right east of the origin, with the bedrock its crystal stands on
+    let spike = generator.generate_chunk(Vector2::new(2, 0));
+    assert_eq!(
+        block_at(&spike, 10, 70, 0),
+        Block::OBSIDIAN.default_state.id
+    );
+    assert_eq!(count_blocks(&spike, &Block::BEDROCK), 1);
+
+    // The platform players arrive on at 100 49 0
+    let platform = generator.generate_chunk(Vector2::new(6, 0));
+    for x in 2..=6 {
+        for z in 0..=2 {
+            assert_eq!(
+                block_at(&platform, x, 48, z),
+                Block::OBSIDIAN.default_state.id
+            );
+            for y in 49..52 {
+                assert_eq!(block_at(&platform, x, y, z), Block::AIR.default_state.id);
+            }
+        }
+    }
+}
+
+/// The chunks pinned by the golden file in every dimension: spawn, its opposite corner and
+/// one far out.
+const GOLDEN_CHUNKS: [(i32, i32); 3] = [(0, 0), (-1, -1), (37, -21)];
+const GOLDEN_SEED: Seed = Seed(13579);
+
+fn block_hash(chunk: &ChunkData) -> String {
+    let mut hasher = Sha256::new();
+    for state in chunk.section.dump_blocks() {
+        hasher.update(state.to_le_bytes());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// Catches changes to the terrain anywhere in the generator. After one that is meant to be,
+/// write the new hashes with `PUMPKIN_BLESS_GOLDEN=1 cargo test -p pumpkin-world golden`.
+#[test]
+fn generated_terrain_matches_golden_hashes() {
+    let path = global_path!("../../../assets/generation_golden.json");
+    let mut hashes = BTreeMap::new();
+    for dimension in [Dimension::Overworld, Dimension::Nether, Dimension::End] {
+        let generator =
+            VanillaGenerator::new(GOLDEN_SEED, dimension.clone(), Arc::new(BlockRegistry)).unwrap();
+        for (x, z) in GOLDEN_CHUNKS {
+            let chunk = generator.generate_chunk(Vector2::new(x, z));
+            hashes.insert(format!("{dimension:?} {x} {z}"), block_hash(&chunk));
+        }
+    }
+
+    if env::var_os("PUMPKIN_BLESS_GOLDEN").is_some() {
+        fs::write(&path, serde_json::to_string_pretty(&hashes).unwrap() + "\n").unwrap();
+        return;
+    }
+    let golden: BTreeMap<String, String> =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    for (chunk, hash) in &hashes {
+        assert_eq!(
+            golden.get(chunk),
+            Some(hash),
+            "The blocks of chunk {chunk} changed; bless the new ones with \
+             `PUMPKIN_BLESS_GOLDEN=1 cargo test -p pumpkin-world golden` if that is intended"
+        );
+    }
+}
+
+#[test]
+fn chunks_do_not_depend_on_the_request() {
+    // The golden hashes only pin chunks generated on their own
+    let temp_dir = TempDir::new().unwrap();
+    let level = Level::from_root_folder(
+        temp_dir.path().to_path_buf(),
+        Arc::new(BlockRegistry),
+        GOLDEN_SEED.0 as i64,
+        Dimension::Overworld,
+    );
+    let generator =
+        VanillaGenerator::new(GOLDEN_SEED, Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    generator.request_load(&level, LoadRequest::new(Vector2::new(0, 0), 2));
+
+    // The middle, an edge and a corner of the request, which decorates their neighbors in
+    // different orders
+    for pos in [Vector2::new(0, 0), Vector2::new(1, 0), Vector2::new(-1, -1)] {
+        let requested = level.try_get_chunk(&pos).unwrap();
+        let alone = generator.generate_chunk(pos);
+        assert!(
+            contents(&requested.blocking_read()) == contents(&alone),
+            "{pos:?} differs"
+        );
+    }
+}
+
+#[test]
+fn clones_share_the_noise_routers() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let clone = generator.clone();
+    assert!(Arc::ptr_eq(&generator.base_router, &clone.base_router));
+    assert!(Arc::ptr_eq(&generator.random_config, &clone.random_config));
+    assert!(Arc::ptr_eq(&generator.terrain_cache, &clone.terrain_cache));
+
+    fn assert_send_sync<T: Send + Sync>(_: &T) {}
+    assert_send_sync(&clone);
+}
+
+#[test]
+fn shapes_reaching_past_the_dimension_are_rejected() {
+    let shape = |min_y: i32, height: u16| -> GenerationShapeConfig {
+        serde_json::from_str(&format!(
+            r#"{{"min_y": {min_y}, "height": {height}, "size_horizontal": 1, "size_vertical": 2}}"#
+        ))
+        .unwrap()
+    };
+    assert!(check_shape(&shape(-64, 384), &Dimension::Overworld).is_ok());
+    assert!(check_shape(&shape(0, 128), &Dimension::Nether).is_ok());
+    // A section taller than the Overworld
+    assert!(matches!(
+        check_shape(&shape(-64, 400), &Dimension::Overworld),
+        Err(GeneratorError::ShapeOutsideDimension {
+            shape_top_y: 336,
+            top_y: 320,
+            ..
+        })
+    ));
+    assert!(matches!(
+        check_shape(&shape(-16, 128), &Dimension::End),
+        Err(GeneratorError::ShapeOutsideDimension {
+            shape_min_y: -16,
+            min_y: 0,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn invalid_noise_routers_are_rejected() {
+    let mut base = OVERWORLD_BASE_NOISE_ROUTER;
+    let len = base.noise.full_component_stack.len();
+    base.noise.final_density = len;
+    let result = VanillaGenerator::with_noise_router(
+        Seed(0),
+        Dimension::Overworld,
+        &base,
+        Arc::new(BlockRegistry),
+    );
+    assert!(matches!(
+        result,
+        Err(GeneratorError::InvalidNoiseRouter(
+            InvalidNoiseRouter::InvalidOutput { index, len: stack_len }
+        )) if index == len && stack_len == len
+    ));
+    assert!(
+        VanillaGenerator::with_noise_router(
+            Seed(0),
+            Dimension::Overworld,
+            &OVERWORLD_BASE_NOISE_ROUTER,
+            Arc::new(BlockRegistry)
+        )
+        .is_ok()
+    );
+}
+
+#[test]
+fn uniform_sections_use_a_single_value() {
+    let chunk = VanillaGenerator::new(Seed(42), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(Vector2::new(-3, 5));
+    let sections = &chunk.section.sections;
+    // Far above the terrain
+    let top = &sections.last().unwrap().block_states;
+    assert!(matches!(top, BlockPalette::Homogeneous(air) if *air == Block::AIR.default_state.id));
+    // The bottom has bedrock, deepslate and more
+    assert!(matches!(
+        sections[0].block_states,
+        BlockPalette::Heterogeneous(_)
+    ));
+
+    // Blocks are dropped from the palette as soon as their last entry is replaced, so no
+    // section keeps blocks it doesn't use anymore
+    for section in sections {
+        let mut blocks = HashSet::new();
+        section.block_states.for_each(|block| {
+            blocks.insert(block);
+        });
+        match section.block_states.convert_network().palette {
+            NetworkPalette::Single(_) => assert_eq!(blocks.len(), 1),
+            NetworkPalette::Indirect(palette) => assert_eq!(palette.len(), blocks.len()),
+            NetworkPalette::Direct => {}
+        }
+    }
+}
+
+/// The highest non-air block in a few chunks spread over the world, after the noise pass.
+fn highest_block(generator: &VanillaGenerator) -> i32 {
+    let mut proto_chunks = HashMap::new();
+    let mut highest = i32::MIN;
+    for pos in [(100, 100), (60, -100), (200, 0)] {
+        let pos = Vector2::new(pos.0, pos.1);
+        generator.advance(
+            &mut proto_chunks,
+            pos,
+            GenerationStage::Terrain,
+            &mut StageTimings::default(),
+        );
+        let proto_chunk = &proto_chunks[&pos].0;
+        for x in (0..16).step_by(3) {
+            for z in (0..16).step_by(3) {
+                let top = (-64..320).rev().find(|y| {
+                    proto_chunk.get_block_state(&Vector3::new(x, *y, z)).0
+                        != Block::AIR.default_state.id
+                });
+                highest = highest.max(top.unwrap_or(i32::MIN));
+            }
+        }
+    }
+    highest
+}
+
+#[test]
+fn amplified_terrain_is_taller() {
+    let default =
+        VanillaGenerator::new(Seed(7), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let amplified = VanillaGenerator::with_world_type(
+        Seed(7),
+        Dimension::Overworld,
+        WorldType::Amplified,
+        Arc::new(BlockRegistry),
+    )
+    .unwrap();
+
+    // How far the terrain reaches above the sea
+    let sea_level = default.generation_settings.sea_level;
+    let default = highest_block(&default) - sea_level;
+    let amplified = highest_block(&amplified) - sea_level;
+    assert!(
+        amplified * 2 >= default * 3,
+        "amplified reaches {amplified}, default {default}"
+    );
+}
+
+#[test]
+fn generated_fluids_are_settled() {
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let air = |state: u16| BlockState::from_id(state).is_air();
+    // An ocean and its shore, see sea_level_moves_the_water_line
+    for pos in [Vector2::new(-13, -106), Vector2::new(0, 0)] {
+        let chunk = generator.generate_chunk(pos);
+        let state_at = |x: usize, y: i32, z: usize| chunk.section.get_block_absolute_y(x, y, z);
+        let mut fluids = 0;
+        // The neighbors across the edges of the chunk aren't known
+        for x in 1..15 {
+            for z in 1..15 {
+                for y in -63..320 {
+                    let state = state_at(x, y, z).unwrap();
+                    let block = Block::from_state_id(state);
+                    if block != &Block::WATER && block != &Block::LAVA {
+                        continue;
+                    }
+                    // Only sources, nothing flowing yet
+                    assert_eq!(state, block.default_state.id, "{pos:?} {x} {y} {z}");
+                    fluids += 1;
+
+                    // Nothing left to flow into either, but from the springs, which only have a
+                    // single side open
+                    let open = [
+                        (x - 1, y, z),
+                        (x + 1, y, z),
+                        (x, y, z - 1),
+                        (x, y, z + 1),
+                        (x, y - 1, z),
+                    ]
+                    .into_iter()
+                    .filter(|(x, y, z)| air(state_at(*x, *y, *z).unwrap()))
+                    .count();
+                    assert!(open <= 1, "{} at {pos:?} {x} {y} {z}", block.name);
+                }
+            }
+        }
+        assert!(fluids > 0, "{pos:?}");
+    }
+}
+
+#[test]
+fn datapack_noise_settings_shape_the_terrain() {
+    let temp_dir = TempDir::new().unwrap();
+    let data_folder = temp_dir.path().join("data");
+    write_vanilla_nether(&data_folder);
+    let settings = load_noise_settings(&data_folder, "minecraft:nether").unwrap();
+
+    let pos = Vector2::new(-3, 5);
+    let datapack = VanillaGenerator::with_noise_settings(
+        Seed(42),
+        Dimension::Nether,
+        &settings,
+        Arc::new(BlockRegistry),
+    )
+    .unwrap()
+    .generate_chunk(pos);
+    let built_in = VanillaGenerator::new(Seed(42), Dimension::Nether, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(pos);
+    assert_eq!(
+        datapack.section.dump_blocks(),
+        built_in.section.dump_blocks()
+    );
+}
+
+#[test]
+fn sea_level_moves_the_water_line() {
+    // An ocean for this seed
+    let pos = Vector2::new(-13, -106);
+    let water_line = |sea_level: Option<i32>| {
+        let mut generator =
+            VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+        if let Some(sea_level) = sea_level {
+            generator.set_sea_level(sea_level);
+        }
+        let mut proto_chunks = HashMap::new();
+        generator.advance(
+            &mut proto_chunks,
+            pos,
+            GenerationStage::Terrain,
+            &mut StageTimings::default(),
+        );
+        let proto_chunk = &proto_chunks[&pos].0;
+        let mut tops = Vec::new();
+        for x in 0..16 {
+            for z in 0..16 {
+                let state = |y| proto_chunk.get_block_state(&Vector3::new(x, y, z)).0;
+                let top = (-64..320)
+                    .rev()
+                    .find(|y| state(*y) != Block::AIR.default_state.id)
+                    .unwrap();
+                assert_eq!(state(top), Block::WATER.default_state.id);
+                // The sea floor doesn't turn to grass under the deeper water
+                let floor = (-64..top)
+                    .rev()
+                    .find(|y| state(*y) != Block::WATER.default_state.id)
+                    .unwrap();
+                assert_ne!(Block::from_state_id(state(floor)), &Block::GRASS_BLOCK);
+                tops.push(top);
+            }
+        }
+        tops.dedup();
+        tops
+    };
+    assert_eq!(water_line(None), [62]);
+    assert_eq!(water_line(Some(80)), [79]);
+}
+
+#[test]
+fn bordering_chunks_stop_at_their_stage() {
+    let (level, _temp_dir) = test_level(Dimension::Overworld);
+    let generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    let origin = Vector2::new(-4, 16);
+    let request = LoadRequest::new(origin, 5);
+    let mut steps = request.clone().into_iter();
+    let mut proto_chunks = HashMap::new();
+    let mut timings = StageTimings::default();
+    let stage_of_ring = |proto_chunks: &HashMap<_, (_, GenerationStage)>, radius| {
+        let stages: Vec<_> = RingIterator::new(origin, radius)
+            .map(|pos| proto_chunks.get(&pos).map(|(_, stage)| *stage))
+            .collect();
+        assert!(stages.windows(2).all(|pair| pair[0] == pair[1]));
+        stages[0]
+    };
+
+    assert!(generator.generate_ring(
+        &level,
+        &request,
+        &mut proto_chunks,
+        steps.next().unwrap(),
+        &mut timings,
+    ));
+    assert_eq!(stage_of_ring(&proto_chunks, 0), Some(GenerationStage::Full));
+    assert_eq!(
+        stage_of_ring(&proto_chunks, 1),
+        Some(GenerationStage::Features)
+    );
+    assert_eq!(
+        stage_of_ring(&proto_chunks, 2),
+        Some(GenerationStage::Terrain)
+    );
+    assert_eq!(
+        stage_of_ring(&proto_chunks, 3),
+        Some(GenerationStage::Biomes)
+    );
+    assert_eq!(stage_of_ring(&proto_chunks, 4), None);
+    assert_eq!(level.loaded_chunk_count(), 1);
+
+    // Once the next ring is finished, each of the others moves up by one stage
+    assert!(generator.generate_ring(
+        &level,
+        &request,
+        &mut proto_chunks,
+        steps.next().unwrap(),
+        &mut timings,
+    ));
+    assert_eq!(stage_of_ring(&proto_chunks, 1), Some(GenerationStage::Full));
+    assert_eq!(
+        stage_of_ring(&proto_chunks, 2),
+        Some(GenerationStage::Features)
+    );
+    assert_eq!(
+        stage_of_ring(&proto_chunks, 3),
+        Some(GenerationStage::Terrain)
+    );
+    assert_eq!(
+        stage_of_ring(&proto_chunks, 4),
+        Some(GenerationStage::Biomes)
+    );
+    assert_eq!(level.loaded_chunk_count(), 9);
+}
+
+#[test]
+fn unlit_chunks_keep_their_blocks() {
+    let pos = Vector2::new(-4, 16);
+    let lit = VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry))
+        .unwrap()
+        .generate_chunk(pos);
+    let mut generator =
+        VanillaGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+    generator.set_lighting(false);
+    let unlit = generator.generate_chunk(pos);
+
+    assert_eq!(unlit.section.dump_blocks(), lit.section.dump_blocks());
+    assert_eq!(unlit.section.dump_biomes(), lit.section.dump_biomes());
+    let light = &unlit.light_engine;
+    assert_eq!(light.sky_light.len(), lit.light_engine.sky_light.len());
+    assert!(
+        light
+            .sky_light
+            .iter()
+            .chain(&light.block_light)
+            .all(|container| matches!(container, LightContainer::Empty(0)))
+    );
+    assert_eq!(generator.timings().stages.light, Duration::ZERO);
+}
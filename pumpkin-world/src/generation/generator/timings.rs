@@ -0,0 +1,73 @@
+use std::{fmt, ops::AddAssign, time::Duration};
+
+/// Chunks taking longer than this to generate are logged, unless the generator was given another
+/// threshold.
+pub const DEFAULT_SLOW_CHUNK_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// How long each pass of the generator took.
+///
+/// The timings of a single chunk include the passes its neighbors went through for it, like the
+/// terrain its features grow into.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct StageTimings {
+    pub biomes: Duration,
+    pub noise: Duration,
+    pub surface: Duration,
+    pub carve: Duration,
+    /// Ores, structures, features, trees and plants.
+    pub features: Duration,
+    /// Copying the finished chunk out and lighting it.
+    pub light: Duration,
+    /// How many chunks got their terrain, counting the neighbors as well.
+    pub terrain_chunks: u64,
+    /// How many chunks were decorated, counting the neighbors as well.
+    pub decorated_chunks: u64,
+}
+
+impl StageTimings {
+    pub fn total(&self) -> Duration {
+        self.biomes + self.noise + self.surface + self.carve + self.features + self.light
+    }
+}
+
+impl AddAssign for StageTimings {
+    fn add_assign(&mut self, other: Self) {
+        self.biomes += other.biomes;
+        self.noise += other.noise;
+        self.surface += other.surface;
+        self.carve += other.carve;
+        self.features += other.features;
+        self.light += other.light;
+        self.terrain_chunks += other.terrain_chunks;
+        self.decorated_chunks += other.decorated_chunks;
+    }
+}
+
+impl fmt::Display for StageTimings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "biomes {:?}, noise {:?}, surface {:?}, carve {:?}, features {:?}, light {:?}",
+            self.biomes, self.noise, self.surface, self.carve, self.features, self.light
+        )
+    }
+}
+
+/// The timings of every chunk a generator finished so far.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct GenerationTimings {
+    pub chunks: u64,
+    /// The chunks that took longer than the slow chunk threshold.
+    pub slow_chunks: u64,
+    pub stages: StageTimings,
+}
+
+impl GenerationTimings {
+    /// How long a chunk took on average, or zero if none was finished yet.
+    pub fn average(&self) -> Duration {
+        if self.chunks == 0 {
+            return Duration::ZERO;
+        }
+        self.stages.total().div_f64(self.chunks as f64)
+    }
+}
@@ -0,0 +1,161 @@
+use std::sync::Arc;
+
+use pumpkin_data::{Block, chunk::Biome};
+use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+use super::{GeneratorError, GeneratorInit, LoadRequest, WorldGenerator, empty_chunk};
+use crate::{
+    chunk::{
+        ChunkData,
+        palette::{BiomePalette, BlockPalette},
+    },
+    dimension::Dimension,
+    generation::{
+        Seed,
+        positions::chunk_pos,
+        settings::{BiomeSource, WorldType},
+    },
+    level::Level,
+    world::BlockRegistryExt,
+};
+
+/// Generates an empty world, optionally with a small stone platform to spawn on.
+pub struct VoidGenerator {
+    dimension: Dimension,
+    biome: &'static Biome,
+    /// The center block of the spawn platform.
+    platform: Option<Vector3<i32>>,
+}
+
+impl VoidGenerator {
+    /// How far the spawn platform reaches out from its center.
+    const PLATFORM_RADIUS: i32 = 2;
+
+    /// Creates a generator filling every chunk of `dimension` with air and `biome`. A platform is
+    /// placed around `platform`, cut off at the borders of the chunk containing it.
+    pub fn with_platform(
+        dimension: Dimension,
+        biome: &'static Biome,
+        platform: Option<Vector3<i32>>,
+    ) -> Self {
+        Self {
+            dimension,
+            biome,
+            platform,
+        }
+    }
+
+    fn generate_chunk(&self, pos: Vector2<i32>) -> ChunkData {
+        let mut chunk = empty_chunk(pos, &self.dimension);
+        for section in chunk.section.sections.iter_mut() {
+            section.biomes = BiomePalette::Homogeneous(self.biome.id);
+        }
+
+        if let Some(center) = self.platform
+            && chunk_pos::to_chunk_pos(&Vector2::new(center.x, center.z)) == pos
+        {
+            let sections = &mut chunk.section;
+            // Only inside of the build limit, which may end in the middle of the top section
+            let Ok(relative_y) = usize::try_from(center.y - sections.min_y) else {
+                return chunk;
+            };
+            if relative_y >= self.dimension.height() as usize {
+                return chunk;
+            }
+            let Some(section) = sections.sections.get_mut(relative_y / BlockPalette::SIZE) else {
+                return chunk;
+            };
+            let start_x = chunk_pos::start_block_x(&pos);
+            let start_z = chunk_pos::start_block_z(&pos);
+            for x in (center.x - Self::PLATFORM_RADIUS).max(start_x)
+                ..=(center.x + Self::PLATFORM_RADIUS).min(chunk_pos::end_block_x(&pos))
+            {
+                for z in (center.z - Self::PLATFORM_RADIUS).max(start_z)
+                    ..=(center.z + Self::PLATFORM_RADIUS).min(chunk_pos::end_block_z(&pos))
+                {
+                    section.block_states.set(
+                        (x - start_x) as usize,
+                        relative_y % BlockPalette::SIZE,
+                        (z - start_z) as usize,
+                        Block::STONE.default_state.id,
+                    );
+                }
+            }
+        }
+
+        chunk
+    }
+}
+
+impl GeneratorInit for VoidGenerator {
+    fn with_random_source(
+        _seed: Seed,
+        dimension: Dimension,
+        _world_type: WorldType,
+        _biome_source: BiomeSource,
+        _legacy_random: bool,
+        _block_registry: Arc<dyn BlockRegistryExt>,
+    ) -> Result<Self, GeneratorError> {
+        Ok(Self::with_platform(
+            dimension,
+            &Biome::THE_VOID,
+            Some(Vector3::new(0, 63, 0)),
+        ))
+    }
+}
+
+impl WorldGenerator for VoidGenerator {
+    fn request_load(&self, level: &Arc<Level>, request: LoadRequest) {
+        for pos in request.disk_iter() {
+            if request.is_cancelled() {
+                return;
+            }
+            level.set_generated_chunk(pos, self.generate_chunk(pos));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_data::{Block, chunk::Biome};
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::VoidGenerator;
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            Seed,
+            generator::{GeneratorInit, tests::BlockRegistry},
+        },
+    };
+
+    #[test]
+    fn platform_only_in_spawn_chunk() {
+        let generator =
+            VoidGenerator::new(Seed(0), Dimension::Overworld, Arc::new(BlockRegistry)).unwrap();
+
+        let spawn = generator.generate_chunk(Vector2::new(0, 0));
+        let block_at = |x, y, z| spawn.section.get_block_absolute_y(x, y, z).unwrap();
+        assert_eq!(block_at(0, 63, 0), Block::STONE.default_state.id);
+        assert_eq!(block_at(2, 63, 2), Block::STONE.default_state.id);
+        assert_eq!(block_at(3, 63, 0), Block::AIR.default_state.id);
+        assert_eq!(block_at(0, 64, 0), Block::AIR.default_state.id);
+        assert_eq!(
+            spawn.section.get_rough_biome_absolute_y(0, 0, 0),
+            Some(Biome::THE_VOID.id)
+        );
+
+        // The platform is cut off instead of spilling into the neighboring chunk
+        let neighbor = generator.generate_chunk(Vector2::new(-1, 0));
+        assert_eq!(
+            neighbor.section.get_block_absolute_y(15, 63, 0),
+            Some(Block::AIR.default_state.id)
+        );
+        assert_eq!(
+            neighbor.light_engine.sky_light.len(),
+            neighbor.section.sections.len() + 2
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::VecDeque;
+
+use pumpkin_data::BlockState;
+use pumpkin_util::math::vector3::Vector3;
+
+use crate::chunk::{ChunkLight, ChunkSections, format::LightContainer, palette::BlockPalette};
+
+/// The furthest a block can be from a chunk while still lighting it: light loses at least one
+/// level per block, so 15 only makes it 14 blocks far.
+const MARGIN: i32 = 14;
+/// The width of a region, covering a chunk and the margin on both of its sides.
+const WIDTH: i32 = BlockPalette::SIZE as i32 + 2 * MARGIN;
+
+/// An opaque block, used wherever there are no blocks to look at.
+const OPAQUE: u8 = 15;
+
+/// A chunk together with the parts of its neighbors that can light it.
+///
+/// Light is propagated through the whole region so that it flows in from the neighbors, but it is
+/// only ever stored for the chunk in the middle.
+pub struct LightRegion {
+    height: i32,
+    /// The [`opacity`] of every block in the region.
+    opacity: Box<[u8]>,
+    /// Every block in the region giving off light, with its luminance.
+    light_sources: Vec<(usize, u8)>,
+}
+
+impl LightRegion {
+    /// Collects the blocks of `sections` and the blocks around them. `neighbor_block` is asked for
+    /// every block outside of the chunk, relative to the chunk's origin; blocks it can't provide
+    /// are treated as opaque.
+    pub fn new(
+        sections: &ChunkSections,
+        neighbor_block: impl Fn(Vector3<i32>) -> Option<&'static BlockState>,
+    ) -> Self {
+        let min_y = sections.min_y;
+        let height = (sections.sections.len() * BlockPalette::SIZE) as i32;
+        let chunk = 0..BlockPalette::SIZE as i32;
+
+        let mut opacity = vec![OPAQUE; (WIDTH * WIDTH * height) as usize].into_boxed_slice();
+        let mut light_sources = Vec::new();
+        for y in 0..height {
+            for z in -MARGIN..WIDTH - MARGIN {
+                for x in -MARGIN..WIDTH - MARGIN {
+                    let state = if chunk.contains(&x) && chunk.contains(&z) {
+                        sections
+                            .get_block_absolute_y(x as usize, y + min_y, z as usize)
+                            .map(BlockState::from_id)
+                    } else {
+                        neighbor_block(Vector3::new(x, y + min_y, z))
+                    };
+                    if let Some(state) = state {
+                        let index = Self::index(x + MARGIN, y, z + MARGIN);
+                        opacity[index] = self::opacity(state);
+                        if state.luminance > 0 {
+                            light_sources.push((index, state.luminance));
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            height,
+            opacity,
+            light_sources,
+        }
+    }
+
+    #[inline]
+    fn index(x: i32, y: i32, z: i32) -> usize {
+        ((y * WIDTH + z) * WIDTH + x) as usize
+    }
+
+    /// Returns the index of the block next to `index` in each direction that is still part of the
+    /// region.
+    fn neighbors(&self, index: usize) -> impl Iterator<Item = usize> {
+        let index = index as i32;
+        let x = index % WIDTH;
+        let z = index / WIDTH % WIDTH;
+        let y = index / (WIDTH * WIDTH);
+        [
+            (x > 0).then(|| index - 1),
+            (x < WIDTH - 1).then(|| index + 1),
+            (z > 0).then(|| index - WIDTH),
+            (z < WIDTH - 1).then(|| index + WIDTH),
+            (y > 0).then(|| index - WIDTH * WIDTH),
+            (y < self.height - 1).then(|| index + WIDTH * WIDTH),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|index| index as usize)
+    }
+
+    /// Lights the blocks the sky can see with full light, then spreads it into caves and below
+    /// overhangs.
+    pub fn propagate_sky_light(&self, light: &mut ChunkLight) {
+        let levels = self.sky_levels(|_| true);
+        self.store(&levels, &mut light.sky_light);
+    }
+
+    /// Spreads the light of every block giving off light, like lava or glowstone.
+    pub fn propagate_block_light(&self, light: &mut ChunkLight) {
+        let levels = self.block_levels(|_| true);
+        self.store(&levels, &mut light.block_light);
+    }
+
+    /// Like [`LightRegion::propagate_sky_light`] and [`LightRegion::propagate_block_light`]
+    /// after only the blocks of the column at `x`, `z` relative to the chunk were changed. Only
+    /// the blocks of the chunk close enough to the column for their light to change are lit
+    /// again, by spreading just the light that can reach them; the rest keep their light.
+    pub fn relight_column(&self, light: &mut ChunkLight, x: i32, z: i32) {
+        // Light reaching the blocks around the column comes from at most as far again
+        let sources = |index| Self::within(index, x, z, 2 * MARGIN);
+        let levels = self.sky_levels(sources);
+        self.store_around(&levels, &mut light.sky_light, x, z);
+        let levels = self.block_levels(sources);
+        self.store_around(&levels, &mut light.block_light, x, z);
+    }
+
+    /// Whether the block at `index` is at most `distance` blocks away from the column at `x`,
+    /// `z` relative to the chunk along both horizontal axes.
+    fn within(index: usize, x: i32, z: i32, distance: i32) -> bool {
+        let index = index as i32;
+        let index_x = index % WIDTH - MARGIN;
+        let index_z = index / WIDTH % WIDTH - MARGIN;
+        (index_x - x).abs() <= distance && (index_z - z).abs() <= distance
+    }
+
+    /// The sky light of every block, lit by the sky columns of the blocks `sources` accepts.
+    fn sky_levels(&self, sources: impl Fn(usize) -> bool) -> Box<[u8]> {
+        let mut levels = vec![0u8; self.opacity.len()].into_boxed_slice();
+
+        // The sky light goes straight down until a block takes any light away
+        for z in 0..WIDTH {
+            for x in 0..WIDTH {
+                if !sources(Self::index(x, 0, z)) {
+                    continue;
+                }
+                for y in (0..self.height).rev() {
+                    let index = Self::index(x, y, z);
+                    if self.opacity[index] != 0 {
+                        break;
+                    }
+                    levels[index] = 15;
+                }
+            }
+        }
+
+        // Only the edges of the sky columns can light anything else
+        let mut queue: VecDeque<usize> = (0..levels.len())
+            .filter(|&index| {
+                levels[index] == 15
+                    && self
+                        .neighbors(index)
+                        .any(|neighbor| levels[neighbor] < 14 && self.opacity[neighbor] < OPAQUE)
+            })
+            .collect();
+        self.spread(&mut levels, &mut queue);
+        levels
+    }
+
+    /// The block light of every block, lit by the blocks `sources` accepts.
+    fn block_levels(&self, sources: impl Fn(usize) -> bool) -> Box<[u8]> {
+        let mut levels = vec![0u8; self.opacity.len()].into_boxed_slice();
+
+        let mut queue = VecDeque::with_capacity(self.light_sources.len());
+        for &(index, luminance) in &self.light_sources {
+            if sources(index) {
+                levels[index] = luminance;
+                queue.push_back(index);
+            }
+        }
+        self.spread(&mut levels, &mut queue);
+        levels
+    }
+
+    /// Spreads the light of every block in `queue` to its neighbors until it runs out.
+    fn spread(&self, levels: &mut [u8], queue: &mut VecDeque<usize>) {
+        while let Some(index) = queue.pop_front() {
+            let level = levels[index];
+            for neighbor in self.neighbors(index) {
+                let new_level = level.saturating_sub(self.opacity[neighbor].max(1));
+                if new_level > levels[neighbor] {
+                    levels[neighbor] = new_level;
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+    }
+
+    /// Writes the levels of the chunk in the middle into `containers`, which have one section of
+    /// light below and above the blocks that is left untouched.
+    fn store(&self, levels: &[u8], containers: &mut [LightContainer]) {
+        let sections = self.height as usize / BlockPalette::SIZE;
+        for (section, container) in containers.iter_mut().skip(1).take(sections).enumerate() {
+            let mut section_light = LightContainer::new_empty(0);
+            for relative_y in 0..BlockPalette::SIZE {
+                let y = (section * BlockPalette::SIZE + relative_y) as i32;
+                for z in 0..BlockPalette::SIZE {
+                    for x in 0..BlockPalette::SIZE {
+                        let index = Self::index(x as i32 + MARGIN, y, z as i32 + MARGIN);
+                        section_light.set(x, relative_y, z, levels[index]);
+                    }
+                }
+            }
+            *container = section_light;
+        }
+    }
+
+    /// Like [`LightRegion::store`], but only for the blocks whose light can have changed with the
+    /// column at `x`, `z`.
+    fn store_around(&self, levels: &[u8], containers: &mut [LightContainer], x: i32, z: i32) {
+        let sections = self.height as usize / BlockPalette::SIZE;
+        let size = BlockPalette::SIZE as i32;
+        let (min_x, max_x) = ((x - MARGIN).max(0), (x + MARGIN).min(size - 1));
+        let (min_z, max_z) = ((z - MARGIN).max(0), (z + MARGIN).min(size - 1));
+        for (section, container) in containers.iter_mut().skip(1).take(sections).enumerate() {
+            for relative_y in 0..BlockPalette::SIZE {
+                let y = (section * BlockPalette::SIZE + relative_y) as i32;
+                for z in min_z..=max_z {
+                    for x in min_x..=max_x {
+                        let index = Self::index(x + MARGIN, y, z + MARGIN);
+                        container.set(x as usize, relative_y, z as usize, levels[index]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How many levels of light `state` takes away when light passes through it. Sky light only
+/// goes straight down through blocks taking nothing away.
+fn opacity(state: &BlockState) -> u8 {
+    match state.opacity {
+        // Blocks whose opacity depends on their shape don't have it extracted. Fluids dim light
+        // like they do in vanilla, everything else lets it through.
+        u8::MAX if state.is_liquid() => 1,
+        u8::MAX => 0,
+        opacity => opacity,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_data::Block;
+    use pumpkin_util::math::vector3::Vector3;
+
+    use super::LightRegion;
+    use crate::chunk::{ChunkLight, ChunkSections, SubChunk, format::LightContainer};
+
+    fn empty_sections() -> ChunkSections {
+        ChunkSections::new((0..24).map(|_| SubChunk::default()).collect(), -64)
+    }
+
+    fn empty_light() -> ChunkLight {
+        ChunkLight {
+            sky_light: (0..26).map(|_| LightContainer::new_filled(15)).collect(),
+            block_light: (0..26).map(|_| LightContainer::new_empty(0)).collect(),
+        }
+    }
+
+    fn light_at(containers: &[LightContainer], x: usize, y: i32, z: usize) -> u8 {
+        let relative_y = (y + 64) as usize;
+        containers[relative_y / 16 + 1].get(x, relative_y % 16, z)
+    }
+
+    #[test]
+    fn sky_light_spreads_below_overhangs() {
+        let mut sections = empty_sections();
+        for z in 0..16 {
+            for x in 0..16 {
+                sections.set_block_absolute_y(x, 0, z, Block::STONE.default_state.id);
+                if x < 8 {
+                    sections.set_block_absolute_y(x, 10, z, Block::STONE.default_state.id);
+                }
+            }
+        }
+        let mut light = empty_light();
+        // Without the neighbors the chunk is walled in, so light only comes from the open half
+        LightRegion::new(&sections, |_| None).propagate_sky_light(&mut light);
+
+        let sky_light = &light.sky_light;
+        assert_eq!(light_at(sky_light, 3, 100, 3), 15);
+        assert_eq!(light_at(sky_light, 12, 5, 3), 15);
+        assert_eq!(light_at(sky_light, 7, 5, 3), 14);
+        assert_eq!(light_at(sky_light, 0, 5, 3), 7);
+        // Inside the roof and the ground
+        assert_eq!(light_at(sky_light, 3, 10, 3), 0);
+        assert_eq!(light_at(sky_light, 3, -20, 3), 0);
+        assert!(sky_light[1].is_empty());
+    }
+
+    #[test]
+    fn sky_light_flows_in_from_neighbors() {
+        let mut sections = empty_sections();
+        for z in 0..16 {
+            for x in 0..16 {
+                sections.set_block_absolute_y(x, 10, z, Block::STONE.default_state.id);
+            }
+        }
+        let mut light = empty_light();
+        // The chunk is roofed over, but the chunk east of it lies open
+        LightRegion::new(&sections, |pos| {
+            (pos.x >= 16).then_some(Block::AIR.default_state)
+        })
+        .propagate_sky_light(&mut light);
+
+        assert_eq!(light_at(&light.sky_light, 15, 5, 8), 14);
+        assert_eq!(light_at(&light.sky_light, 10, 5, 8), 9);
+    }
+
+    #[test]
+    fn relit_column_matches_the_whole_chunk_lit_again() {
+        let mut sections = empty_sections();
+        for z in 0..16 {
+            for x in 0..16 {
+                sections.set_block_absolute_y(x, 10, z, Block::STONE.default_state.id);
+            }
+        }
+        sections.set_block_absolute_y(14, 20, 14, Block::GLOWSTONE.default_state.id);
+        let neighbor_block = |pos: Vector3<i32>| (pos.x >= 16).then_some(Block::AIR.default_state);
+        let mut light = empty_light();
+        let region = LightRegion::new(&sections, neighbor_block);
+        region.propagate_sky_light(&mut light);
+        region.propagate_block_light(&mut light);
+
+        // A hole in the roof and a light below it
+        sections.set_block_absolute_y(3, 10, 3, Block::AIR.default_state.id);
+        sections.set_block_absolute_y(3, 0, 3, Block::LAVA.default_state.id);
+        let region = LightRegion::new(&sections, neighbor_block);
+        region.relight_column(&mut light, 3, 3);
+        let mut expected = empty_light();
+        region.propagate_sky_light(&mut expected);
+        region.propagate_block_light(&mut expected);
+
+        assert_eq!(light_at(&light.sky_light, 3, 5, 3), 15);
+        assert_eq!(light_at(&light.block_light, 3, 3, 3), 12);
+        for y in -64..320 {
+            for z in 0..16 {
+                for x in 0..16 {
+                    assert_eq!(
+                        light_at(&light.sky_light, x, y, z),
+                        light_at(&expected.sky_light, x, y, z),
+                        "sky light at {x} {y} {z}"
+                    );
+                    assert_eq!(
+                        light_at(&light.block_light, x, y, z),
+                        light_at(&expected.block_light, x, y, z),
+                        "block light at {x} {y} {z}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn block_light_fades_with_distance() {
+        let mut sections = empty_sections();
+        sections.set_block_absolute_y(8, 20, 8, Block::LAVA.default_state.id);
+        sections.set_block_absolute_y(8, 20, 10, Block::STONE.default_state.id);
+        let mut light = empty_light();
+        LightRegion::new(&sections, |_| None).propagate_block_light(&mut light);
+
+        let block_light = &light.block_light;
+        assert_eq!(light_at(block_light, 8, 20, 8), 15);
+        assert_eq!(light_at(block_light, 11, 20, 8), 12);
+        assert_eq!(light_at(block_light, 8, 17, 8), 12);
+        assert_eq!(light_at(block_light, 6, 21, 8), 12);
+        // Light goes around the stone instead of through it
+        assert_eq!(light_at(block_light, 8, 20, 10), 0);
+        assert_eq!(light_at(block_light, 8, 20, 11), 10);
+        assert!(block_light[10].is_empty());
+    }
+}
@@ -7,34 +7,144 @@ mod block_predicate;
 mod block_state_provider;
 pub mod carver;
 pub mod chunk_noise;
+pub mod chunk_view;
 mod feature;
 pub mod generator;
 pub mod height_limit;
 pub mod height_provider;
+pub(crate) mod light;
 pub mod noise;
+pub mod ore_placer;
 pub mod ore_sampler;
 pub mod positions;
+pub mod pregen;
 pub mod proto_chunk;
+pub mod proto_chunk_region;
 pub mod rule;
 mod rule_test;
 mod seed;
 pub mod settings;
 pub mod structure;
 mod surface;
+pub mod tree_placer;
+pub mod vegetation_placer;
 pub mod y_offset;
 
+use std::sync::Arc;
+
 use derive_getters::Getters;
-use generator::{GeneratorInit, VanillaGenerator, WorldGenerator};
+use generator::{
+    GeneratorError, GeneratorInit, SuperflatGenerator, VanillaGenerator, VoidGenerator,
+    WorldGenerator,
+};
+use pumpkin_config::{LevelType, advanced_config, chunk::TerrainShape};
 use pumpkin_util::random::{
     RandomDeriver, RandomDeriverImpl, RandomImpl, legacy_rand::LegacyRand, xoroshiro128::Xoroshiro,
 };
 pub use seed::Seed;
 
 use crate::dimension::Dimension;
+use crate::world::BlockRegistryExt;
+use settings::WorldType;
+
+pub fn get_world_gen(
+    seed: Seed,
+    dimension: Dimension,
+    block_registry: Arc<dyn BlockRegistryExt>,
+) -> Result<Box<dyn WorldGenerator>, GeneratorError> {
+    make_generator(GeneratorKind::default(), seed, dimension, block_registry)
+}
+
+/// Creates a generator of its own, e.g. from a plugin.
+pub type GeneratorFactory = fn(
+    Seed,
+    Dimension,
+    Arc<dyn BlockRegistryExt>,
+) -> Result<Box<dyn WorldGenerator>, GeneratorError>;
+
+/// Which generator the chunks of a level come from.
+#[derive(Clone, Copy, Debug)]
+pub enum GeneratorKind {
+    Vanilla(WorldType),
+    /// Vanilla's "Classic Flat" preset.
+    Superflat,
+    Void,
+    Custom(GeneratorFactory),
+}
+
+impl Default for GeneratorKind {
+    fn default() -> Self {
+        Self::Vanilla(WorldType::Default)
+    }
+}
+
+impl From<LevelType> for GeneratorKind {
+    fn from(level_type: LevelType) -> Self {
+        match level_type {
+            LevelType::Normal => Self::Vanilla(WorldType::Default),
+            LevelType::Flat => Self::Superflat,
+            LevelType::LargeBiomes => Self::Vanilla(WorldType::LargeBiomes),
+            LevelType::Amplified => Self::Vanilla(WorldType::Amplified),
+            LevelType::Void => Self::Void,
+        }
+    }
+}
 
-pub fn get_world_gen(seed: Seed, dimension: Dimension) -> Box<dyn WorldGenerator> {
-    // TODO decide which WorldGenerator to pick based on config.
-    Box::new(VanillaGenerator::new(seed, dimension))
+/// Creates the generator of `kind` for `dimension`. Like vanilla's world presets, only custom
+/// generators replace the Nether and the End, which the others leave to the vanilla one.
+pub fn make_generator(
+    kind: GeneratorKind,
+    seed: Seed,
+    dimension: Dimension,
+    block_registry: Arc<dyn BlockRegistryExt>,
+) -> Result<Box<dyn WorldGenerator>, GeneratorError> {
+    Ok(match (kind, &dimension) {
+        (GeneratorKind::Custom(factory), _) => return factory(seed, dimension, block_registry),
+        (GeneratorKind::Superflat, Dimension::Overworld) => {
+            Box::new(SuperflatGenerator::new(seed, dimension, block_registry)?)
+        }
+        (GeneratorKind::Void, Dimension::Overworld) => {
+            Box::new(VoidGenerator::new(seed, dimension, block_registry)?)
+        }
+        (GeneratorKind::Vanilla(world_type), _) => Box::new(vanilla_generator(
+            seed,
+            dimension,
+            world_type,
+            block_registry,
+        )?),
+        (GeneratorKind::Superflat | GeneratorKind::Void, _) => Box::new(vanilla_generator(
+            seed,
+            dimension,
+            WorldType::Default,
+            block_registry,
+        )?),
+    })
+}
+
+/// The vanilla generator, with the structures spaced out and the overworld shaped like the config
+/// says.
+fn vanilla_generator(
+    seed: Seed,
+    dimension: Dimension,
+    world_type: WorldType,
+    block_registry: Arc<dyn BlockRegistryExt>,
+) -> Result<VanillaGenerator, GeneratorError> {
+    let overworld = dimension == Dimension::Overworld;
+    let mut generator =
+        VanillaGenerator::with_world_type(seed, dimension, world_type, block_registry)?;
+    for (set_name, spacing) in &advanced_config().chunk.structure_spacing {
+        if let Err(err) = generator.set_structure_spacing(set_name, *spacing) {
+            log::warn!("{err}");
+        }
+    }
+    let shape = &advanced_config().chunk.terrain_shape;
+    if overworld
+        && *shape != TerrainShape::default()
+        && let Err(err) = generator.set_terrain_shape(shape)
+    {
+        log::warn!("{err}");
+    }
+    Ok(generator)
 }
 
 #[derive(Getters)]
@@ -46,6 +156,14 @@ pub struct GlobalRandomConfig {
 }
 
 impl GlobalRandomConfig {
+    /// Derives every random of the world from `seed`, with Java's old linear congruential
+    /// generator if `legacy` is set and Xoroshiro128++ otherwise.
+    ///
+    /// Vanilla switched its overworld to Xoroshiro in 1.18; worlds of 1.17 and before, and the
+    /// nether and end still today (see [`GenerationSettings::legacy_random_source`]), use the
+    /// legacy one.
+    ///
+    /// [`GenerationSettings::legacy_random_source`]: settings::GenerationSettings::legacy_random_source
     pub fn new(seed: u64, legacy: bool) -> Self {
         let random_deriver = if legacy {
             LegacyRand::from_seed(seed).next_splitter()
@@ -97,6 +215,12 @@ pub mod biome_coords {
         coord << 2
     }
 
+    /// The number of biome cells covering `height` blocks, including a partial one at the top.
+    #[inline]
+    pub fn cells_covering<T: PrimInt>(height: T) -> T {
+        from_block(height + to_block(T::one()) - T::one())
+    }
+
     #[inline]
     pub fn from_chunk<T: PrimInt>(coord: T) -> T {
         coord << 2
@@ -0,0 +1,1396 @@
+//! Noise routers that are loaded at runtime instead of being baked into `pumpkin-data`.
+//!
+//! [`load_noise_settings`] reads the noise settings of a datapack, whose routers name their
+//! density functions by id or write them out. Those are resolved into the same functions the
+//! extractor dumps for vanilla, see [`density_function_repr`], which [`parse_noise_router`] reads
+//! directly. Either way, they are flattened into component stacks the same way the build script
+//! does it, so identical functions (and therefore their caches) are shared.
+//!
+//! [`density_function_repr`]: pumpkin_data::density_function_repr
+
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use pumpkin_data::{
+    density_function_repr::{
+        self as repr, BinaryData, ClampData, ClampedYGradientData, DensityFunctionRepr,
+        HashableF32, HashableF64, InterpolatedNoiseSamplerData, LinearData, NoiseRouterRepr,
+        RangeChoiceData, UnaryData,
+    },
+    noise_router::{
+        self, BaseMultiNoiseRouter, BaseNoiseFunctionComponent, BaseNoiseRouter, BaseNoiseRouters,
+        BaseSurfaceEstimator, BinaryOperation, LinearOperation, SplinePoint, SplineRepr,
+        UnaryOperation, WeirdScaledMapper, WrapperType,
+    },
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::generation::settings::GenerationSettings;
+
+#[derive(Error, Debug)]
+pub enum NoiseRouterLoadError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid noise router: {0}")]
+    Json5(#[from] serde_json5::Error),
+    #[error("Invalid {id}: {source}")]
+    Json {
+        id: String,
+        source: serde_json::Error,
+    },
+    #[error("Unknown density function {0}")]
+    UnknownDensityFunction(String),
+    #[error("The density function {0} references itself")]
+    CyclicDensityFunction(String),
+}
+
+/// Parses a single noise router document in the format of `assets/density_function.json`, where
+/// every density function is fully resolved and tagged with its class name.
+pub fn parse_noise_router(json: &str) -> Result<BaseNoiseRouters, NoiseRouterLoadError> {
+    let repr: NoiseRouterRepr = serde_json5::from_str(json)?;
+    Ok(base_routers(repr))
+}
+
+/// The noise settings of a datapack, everything a [`VanillaGenerator`] needs to shape the terrain
+/// of a dimension like it.
+///
+/// [`VanillaGenerator`]: crate::generation::generator::VanillaGenerator
+pub struct NoiseSettings {
+    pub generation_settings: Arc<GenerationSettings>,
+    pub noise_router: BaseNoiseRouters,
+}
+
+/// Loads the noise settings `id`, e.g. `my_pack:moon`, out of `data_folder`, the `data` folder of a
+/// datapack. The density functions its router names are looked up in there too, including the
+/// ones of vanilla; ids without a namespace are in `minecraft`, like in vanilla.
+pub fn load_noise_settings(
+    data_folder: &Path,
+    id: &str,
+) -> Result<NoiseSettings, NoiseRouterLoadError> {
+    let invalid = |source| NoiseRouterLoadError::Json {
+        id: id.to_string(),
+        source,
+    };
+    let json = fs::read_to_string(resource_path(data_folder, "noise_settings", id))?;
+    let document: serde_json::Value = serde_json::from_str(&json).map_err(invalid)?;
+    let generation_settings = GenerationSettings::deserialize(&document).map_err(invalid)?;
+    let router = NoiseRouterJson::deserialize(&document["noise_router"]).map_err(invalid)?;
+
+    let mut functions = DensityFunctions {
+        data_folder,
+        resolved: HashMap::new(),
+        resolving: Vec::new(),
+    };
+    Ok(NoiseSettings {
+        generation_settings: Arc::new(generation_settings),
+        noise_router: base_routers(router.resolve(&mut functions)?),
+    })
+}
+
+/// Where the resource `id` of `kind`, e.g. `density_function`, is in the `data` folder of a
+/// datapack.
+fn resource_path(data_folder: &Path, kind: &str, id: &str) -> PathBuf {
+    let (namespace, path) = id.split_once(':').unwrap_or(("minecraft", id));
+    data_folder
+        .join(namespace)
+        .join("worldgen")
+        .join(kind)
+        .join(format!("{path}.json"))
+}
+
+/// Noises are named without their namespace elsewhere, like in the extractor's dumps.
+fn noise_id(id: String) -> String {
+    match id.strip_prefix("minecraft:") {
+        Some(id) => id.to_string(),
+        None => id,
+    }
+}
+
+/// A density function of a datapack: a constant, the id of a function in a file of its own, or
+/// one written out.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DensityFunctionJson {
+    Constant(f64),
+    Reference(String),
+    Inline(Box<InlineDensityFunctionJson>),
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+enum InlineDensityFunctionJson {
+    #[serde(rename = "minecraft:abs")]
+    Abs { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:add")]
+    Add {
+        argument1: DensityFunctionJson,
+        argument2: DensityFunctionJson,
+    },
+    #[serde(rename = "minecraft:beardifier")]
+    Beardifier,
+    #[serde(rename = "minecraft:blend_alpha")]
+    BlendAlpha,
+    #[serde(rename = "minecraft:blend_density")]
+    BlendDensity { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:blend_offset")]
+    BlendOffset,
+    #[serde(rename = "minecraft:cache_2d")]
+    Cache2D { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:cache_all_in_cell")]
+    CacheAllInCell { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:cache_once")]
+    CacheOnce { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:clamp")]
+    Clamp {
+        input: DensityFunctionJson,
+        min: f64,
+        max: f64,
+    },
+    #[serde(rename = "minecraft:constant")]
+    Constant { argument: f64 },
+    #[serde(rename = "minecraft:cube")]
+    Cube { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:end_islands")]
+    EndIslands,
+    #[serde(rename = "minecraft:flat_cache")]
+    FlatCache { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:half_negative")]
+    HalfNegative { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:interpolated")]
+    Interpolated { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:max")]
+    Max {
+        argument1: DensityFunctionJson,
+        argument2: DensityFunctionJson,
+    },
+    #[serde(rename = "minecraft:min")]
+    Min {
+        argument1: DensityFunctionJson,
+        argument2: DensityFunctionJson,
+    },
+    #[serde(rename = "minecraft:mul")]
+    Mul {
+        argument1: DensityFunctionJson,
+        argument2: DensityFunctionJson,
+    },
+    #[serde(rename = "minecraft:noise")]
+    Noise {
+        noise: String,
+        xz_scale: f64,
+        y_scale: f64,
+    },
+    #[serde(rename = "minecraft:old_blended_noise")]
+    OldBlendedNoise {
+        xz_scale: f64,
+        y_scale: f64,
+        xz_factor: f64,
+        y_factor: f64,
+        smear_scale_multiplier: f64,
+    },
+    #[serde(rename = "minecraft:quarter_negative")]
+    QuarterNegative { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:range_choice")]
+    RangeChoice {
+        input: DensityFunctionJson,
+        min_inclusive: f64,
+        max_exclusive: f64,
+        when_in_range: DensityFunctionJson,
+        when_out_of_range: DensityFunctionJson,
+    },
+    #[serde(rename = "minecraft:shift_a")]
+    ShiftA { argument: String },
+    #[serde(rename = "minecraft:shift_b")]
+    ShiftB { argument: String },
+    #[serde(rename = "minecraft:shifted_noise")]
+    ShiftedNoise {
+        shift_x: DensityFunctionJson,
+        shift_y: DensityFunctionJson,
+        shift_z: DensityFunctionJson,
+        xz_scale: f64,
+        y_scale: f64,
+        noise: String,
+    },
+    #[serde(rename = "minecraft:spline")]
+    Spline { spline: SplineJson },
+    #[serde(rename = "minecraft:square")]
+    Square { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:squeeze")]
+    Squeeze { argument: DensityFunctionJson },
+    #[serde(rename = "minecraft:weird_scaled_sampler")]
+    WeirdScaledSampler {
+        input: DensityFunctionJson,
+        noise: String,
+        rarity_value_mapper: RarityValueMapperJson,
+    },
+    #[serde(rename = "minecraft:y_clamped_gradient")]
+    YClampedGradient {
+        from_y: i32,
+        to_y: i32,
+        from_value: f64,
+        to_value: f64,
+    },
+}
+
+#[derive(Deserialize)]
+enum RarityValueMapperJson {
+    #[serde(rename = "type_1")]
+    Tunnels,
+    #[serde(rename = "type_2")]
+    Caves,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SplineJson {
+    Fixed(f32),
+    Standard {
+        coordinate: DensityFunctionJson,
+        points: Vec<SplinePointJson>,
+    },
+}
+
+#[derive(Deserialize)]
+struct SplinePointJson {
+    location: f32,
+    value: SplineJson,
+    derivative: f32,
+}
+
+#[derive(Deserialize)]
+struct NoiseRouterJson {
+    barrier: DensityFunctionJson,
+    fluid_level_floodedness: DensityFunctionJson,
+    fluid_level_spread: DensityFunctionJson,
+    lava: DensityFunctionJson,
+    temperature: DensityFunctionJson,
+    vegetation: DensityFunctionJson,
+    continents: DensityFunctionJson,
+    erosion: DensityFunctionJson,
+    depth: DensityFunctionJson,
+    ridges: DensityFunctionJson,
+    initial_density_without_jaggedness: DensityFunctionJson,
+    final_density: DensityFunctionJson,
+    vein_toggle: DensityFunctionJson,
+    vein_ridged: DensityFunctionJson,
+    vein_gap: DensityFunctionJson,
+}
+
+impl NoiseRouterJson {
+    fn resolve(
+        self,
+        functions: &mut DensityFunctions,
+    ) -> Result<NoiseRouterRepr, NoiseRouterLoadError> {
+        Ok(NoiseRouterRepr {
+            barrier_noise: functions.resolve(self.barrier)?,
+            fluid_level_floodedness_noise: functions.resolve(self.fluid_level_floodedness)?,
+            fluid_level_spread_noise: functions.resolve(self.fluid_level_spread)?,
+            lava_noise: functions.resolve(self.lava)?,
+            temperature: functions.resolve(self.temperature)?,
+            vegetation: functions.resolve(self.vegetation)?,
+            continents: functions.resolve(self.continents)?,
+            erosion: functions.resolve(self.erosion)?,
+            depth: functions.resolve(self.depth)?,
+            ridges: functions.resolve(self.ridges)?,
+            initial_density_without_jaggedness: functions
+                .resolve(self.initial_density_without_jaggedness)?,
+            final_density: functions.resolve(self.final_density)?,
+            vein_toggle: functions.resolve(self.vein_toggle)?,
+            vein_ridged: functions.resolve(self.vein_ridged)?,
+            vein_gap: functions.resolve(self.vein_gap)?,
+        })
+    }
+}
+
+/// Resolves the density functions of a datapack, reading the file of each id once.
+struct DensityFunctions<'a> {
+    data_folder: &'a Path,
+    resolved: HashMap<String, DensityFunctionRepr>,
+    /// The ids being resolved, to catch the functions that end up referencing themselves.
+    resolving: Vec<String>,
+}
+
+impl DensityFunctions<'_> {
+    fn resolve(
+        &mut self,
+        function: DensityFunctionJson,
+    ) -> Result<DensityFunctionRepr, NoiseRouterLoadError> {
+        match function {
+            DensityFunctionJson::Constant(value) => Ok(DensityFunctionRepr::Constant {
+                value: HashableF64(value),
+            }),
+            DensityFunctionJson::Reference(id) => self.resolve_id(id),
+            DensityFunctionJson::Inline(function) => self.resolve_inline(*function),
+        }
+    }
+
+    fn resolve_boxed(
+        &mut self,
+        function: DensityFunctionJson,
+    ) -> Result<Box<DensityFunctionRepr>, NoiseRouterLoadError> {
+        self.resolve(function).map(Box::new)
+    }
+
+    fn resolve_id(&mut self, id: String) -> Result<DensityFunctionRepr, NoiseRouterLoadError> {
+        let id = if id.contains(':') {
+            id
+        } else {
+            format!("minecraft:{id}")
+        };
+        if let Some(function) = self.resolved.get(&id) {
+            return Ok(function.clone());
+        }
+        if self.resolving.contains(&id) {
+            return Err(NoiseRouterLoadError::CyclicDensityFunction(id));
+        }
+
+        let json =
+            match fs::read_to_string(resource_path(self.data_folder, "density_function", &id)) {
+                Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                    return Err(NoiseRouterLoadError::UnknownDensityFunction(id));
+                }
+                json => json?,
+            };
+        let function =
+            serde_json::from_str(&json).map_err(|source| NoiseRouterLoadError::Json {
+                id: id.clone(),
+                source,
+            })?;
+
+        self.resolving.push(id);
+        let function = self.resolve(function);
+        let id = self.resolving.pop().unwrap();
+        let function = function?;
+        self.resolved.insert(id, function.clone());
+        Ok(function)
+    }
+
+    fn resolve_inline(
+        &mut self,
+        function: InlineDensityFunctionJson,
+    ) -> Result<DensityFunctionRepr, NoiseRouterLoadError> {
+        use InlineDensityFunctionJson as Json;
+
+        Ok(match function {
+            Json::Abs { argument } => self.unary(argument, repr::UnaryOperation::Abs)?,
+            Json::Add {
+                argument1,
+                argument2,
+            } => self.binary(argument1, argument2, repr::BinaryOperation::Add)?,
+            Json::Beardifier => DensityFunctionRepr::Beardifier,
+            Json::BlendAlpha => DensityFunctionRepr::BlendAlpha,
+            Json::BlendDensity { argument } => DensityFunctionRepr::BlendDensity {
+                input: self.resolve_boxed(argument)?,
+            },
+            Json::BlendOffset => DensityFunctionRepr::BlendOffset,
+            Json::Cache2D { argument } => self.wrapper(argument, repr::WrapperType::Cache2D)?,
+            Json::CacheAllInCell { argument } => {
+                self.wrapper(argument, repr::WrapperType::CellCache)?
+            }
+            Json::CacheOnce { argument } => self.wrapper(argument, repr::WrapperType::CacheOnce)?,
+            Json::Clamp { input, min, max } => DensityFunctionRepr::Clamp {
+                input: self.resolve_boxed(input)?,
+                data: ClampData {
+                    min_value: HashableF64(min),
+                    max_value: HashableF64(max),
+                },
+            },
+            Json::Constant { argument } => DensityFunctionRepr::Constant {
+                value: HashableF64(argument),
+            },
+            Json::Cube { argument } => self.unary(argument, repr::UnaryOperation::Cube)?,
+            Json::EndIslands => DensityFunctionRepr::EndIslands,
+            Json::FlatCache { argument } => self.wrapper(argument, repr::WrapperType::CacheFlat)?,
+            Json::HalfNegative { argument } => {
+                self.unary(argument, repr::UnaryOperation::HalfNegative)?
+            }
+            Json::Interpolated { argument } => {
+                self.wrapper(argument, repr::WrapperType::Interpolated)?
+            }
+            Json::Max {
+                argument1,
+                argument2,
+            } => self.binary(argument1, argument2, repr::BinaryOperation::Max)?,
+            Json::Min {
+                argument1,
+                argument2,
+            } => self.binary(argument1, argument2, repr::BinaryOperation::Min)?,
+            Json::Mul {
+                argument1,
+                argument2,
+            } => self.binary(argument1, argument2, repr::BinaryOperation::Mul)?,
+            Json::Noise {
+                noise,
+                xz_scale,
+                y_scale,
+            } => DensityFunctionRepr::Noise {
+                data: repr::NoiseData {
+                    noise_id: noise_id(noise),
+                    xz_scale: HashableF64(xz_scale),
+                    y_scale: HashableF64(y_scale),
+                },
+            },
+            // Like vanilla, which scales the noise by the same factor
+            Json::OldBlendedNoise {
+                xz_scale,
+                y_scale,
+                xz_factor,
+                y_factor,
+                smear_scale_multiplier,
+            } => DensityFunctionRepr::InterpolatedNoiseSampler {
+                data: InterpolatedNoiseSamplerData {
+                    scaled_xz_scale: HashableF64(684.412 * xz_scale),
+                    scaled_y_scale: HashableF64(684.412 * y_scale),
+                    xz_factor: HashableF64(xz_factor),
+                    y_factor: HashableF64(y_factor),
+                    smear_scale_multiplier: HashableF64(smear_scale_multiplier),
+                },
+            },
+            Json::QuarterNegative { argument } => {
+                self.unary(argument, repr::UnaryOperation::QuarterNegative)?
+            }
+            Json::RangeChoice {
+                input,
+                min_inclusive,
+                max_exclusive,
+                when_in_range,
+                when_out_of_range,
+            } => DensityFunctionRepr::RangeChoice {
+                input: self.resolve_boxed(input)?,
+                when_in_range: self.resolve_boxed(when_in_range)?,
+                when_out_range: self.resolve_boxed(when_out_of_range)?,
+                data: RangeChoiceData {
+                    min_inclusive: HashableF64(min_inclusive),
+                    max_exclusive: HashableF64(max_exclusive),
+                },
+            },
+            Json::ShiftA { argument } => DensityFunctionRepr::ShiftA {
+                noise_id: noise_id(argument),
+            },
+            Json::ShiftB { argument } => DensityFunctionRepr::ShiftB {
+                noise_id: noise_id(argument),
+            },
+            Json::ShiftedNoise {
+                shift_x,
+                shift_y,
+                shift_z,
+                xz_scale,
+                y_scale,
+                noise,
+            } => DensityFunctionRepr::ShiftedNoise {
+                shift_x: self.resolve_boxed(shift_x)?,
+                shift_y: self.resolve_boxed(shift_y)?,
+                shift_z: self.resolve_boxed(shift_z)?,
+                data: repr::ShiftedNoiseData {
+                    xz_scale: HashableF64(xz_scale),
+                    y_scale: HashableF64(y_scale),
+                    noise_id: noise_id(noise),
+                },
+            },
+            Json::Spline { spline } => match self.spline(spline)? {
+                // Only the splines inside of others can be fixed in the stack
+                repr::SplineRepr::Fixed { value } => DensityFunctionRepr::Constant {
+                    value: HashableF64(value.0 as f64),
+                },
+                spline => DensityFunctionRepr::Spline { spline },
+            },
+            Json::Square { argument } => self.unary(argument, repr::UnaryOperation::Square)?,
+            Json::Squeeze { argument } => self.unary(argument, repr::UnaryOperation::Squeeze)?,
+            Json::WeirdScaledSampler {
+                input,
+                noise,
+                rarity_value_mapper,
+            } => DensityFunctionRepr::WeirdScaled {
+                input: self.resolve_boxed(input)?,
+                data: repr::WeirdScaledData {
+                    noise_id: noise_id(noise),
+                    mapper: match rarity_value_mapper {
+                        RarityValueMapperJson::Tunnels => repr::WeirdScaledMapper::Tunnels,
+                        RarityValueMapperJson::Caves => repr::WeirdScaledMapper::Caves,
+                    },
+                },
+            },
+            Json::YClampedGradient {
+                from_y,
+                to_y,
+                from_value,
+                to_value,
+            } => DensityFunctionRepr::ClampedYGradient {
+                data: ClampedYGradientData {
+                    from_y,
+                    to_y,
+                    from_value: HashableF64(from_value),
+                    to_value: HashableF64(to_value),
+                },
+            },
+        })
+    }
+
+    /// Like vanilla, adding or multiplying by a constant is a linear function of the other
+    /// argument. The references are resolved first, so constants in files of their own count too.
+    fn binary(
+        &mut self,
+        argument1: DensityFunctionJson,
+        argument2: DensityFunctionJson,
+        operation: repr::BinaryOperation,
+    ) -> Result<DensityFunctionRepr, NoiseRouterLoadError> {
+        let argument1 = self.resolve(argument1)?;
+        let argument2 = self.resolve(argument2)?;
+        let linear = match operation {
+            repr::BinaryOperation::Add => Some(repr::LinearOperation::Add),
+            repr::BinaryOperation::Mul => Some(repr::LinearOperation::Mul),
+            repr::BinaryOperation::Min | repr::BinaryOperation::Max => None,
+        };
+        Ok(match (linear, argument1, argument2) {
+            (Some(operation), DensityFunctionRepr::Constant { value }, input)
+            | (Some(operation), input, DensityFunctionRepr::Constant { value }) => {
+                DensityFunctionRepr::Linear {
+                    input: Box::new(input),
+                    data: LinearData {
+                        operation,
+                        argument: value,
+                    },
+                }
+            }
+            (_, argument1, argument2) => DensityFunctionRepr::Binary {
+                argument1: Box::new(argument1),
+                argument2: Box::new(argument2),
+                data: BinaryData { operation },
+            },
+        })
+    }
+
+    fn unary(
+        &mut self,
+        argument: DensityFunctionJson,
+        operation: repr::UnaryOperation,
+    ) -> Result<DensityFunctionRepr, NoiseRouterLoadError> {
+        Ok(DensityFunctionRepr::Unary {
+            input: self.resolve_boxed(argument)?,
+            data: UnaryData { operation },
+        })
+    }
+
+    fn wrapper(
+        &mut self,
+        argument: DensityFunctionJson,
+        wrapper: repr::WrapperType,
+    ) -> Result<DensityFunctionRepr, NoiseRouterLoadError> {
+        Ok(DensityFunctionRepr::Wrapper {
+            input: self.resolve_boxed(argument)?,
+            wrapper,
+        })
+    }
+
+    fn spline(&mut self, spline: SplineJson) -> Result<repr::SplineRepr, NoiseRouterLoadError> {
+        Ok(match spline {
+            SplineJson::Fixed(value) => repr::SplineRepr::Fixed {
+                value: HashableF32(value),
+            },
+            SplineJson::Standard { coordinate, points } => {
+                let location_function = self.resolve_boxed(coordinate)?;
+                let mut locations = Vec::with_capacity(points.len());
+                let mut values = Vec::with_capacity(points.len());
+                let mut derivatives = Vec::with_capacity(points.len());
+                for point in points {
+                    locations.push(HashableF32(point.location));
+                    values.push(self.spline(point.value)?);
+                    derivatives.push(HashableF32(point.derivative));
+                }
+                repr::SplineRepr::Standard {
+                    location_function,
+                    locations: locations.into(),
+                    values: values.into(),
+                    derivatives: derivatives.into(),
+                }
+            }
+        })
+    }
+}
+
+/// Components ordered so that every component comes after the ones it depends on.
+#[derive(Default)]
+struct ComponentStack {
+    components: Vec<BaseNoiseFunctionComponent>,
+    indices: BTreeMap<u64, usize>,
+}
+
+impl ComponentStack {
+    /// Returns the index of `function`, pushing it (and everything it depends on) if an identical
+    /// function isn't on the stack yet.
+    fn push(&mut self, function: DensityFunctionRepr) -> usize {
+        let id = function.unique_id();
+        if let Some(index) = self.indices.get(&id) {
+            return *index;
+        }
+        let component = self.component(function);
+        self.components.push(component);
+        let index = self.components.len() - 1;
+        self.indices.insert(id, index);
+        index
+    }
+
+    fn component(&mut self, function: DensityFunctionRepr) -> BaseNoiseFunctionComponent {
+        match function {
+            DensityFunctionRepr::Beardifier => BaseNoiseFunctionComponent::Beardifier,
+            DensityFunctionRepr::BlendAlpha => BaseNoiseFunctionComponent::BlendAlpha,
+            DensityFunctionRepr::BlendOffset => BaseNoiseFunctionComponent::BlendOffset,
+            DensityFunctionRepr::BlendDensity { input } => {
+                BaseNoiseFunctionComponent::BlendDensity {
+                    input_index: self.push(*input),
+                }
+            }
+            DensityFunctionRepr::EndIslands => BaseNoiseFunctionComponent::EndIslands,
+            DensityFunctionRepr::Noise { data } => BaseNoiseFunctionComponent::Noise {
+                data: noise_router::NoiseData {
+                    noise_id: Cow::Owned(data.noise_id),
+                    xz_scale: data.xz_scale.0,
+                    y_scale: data.y_scale.0,
+                },
+            },
+            DensityFunctionRepr::ShiftA { noise_id } => BaseNoiseFunctionComponent::ShiftA {
+                noise_id: Cow::Owned(noise_id),
+            },
+            DensityFunctionRepr::ShiftB { noise_id } => BaseNoiseFunctionComponent::ShiftB {
+                noise_id: Cow::Owned(noise_id),
+            },
+            DensityFunctionRepr::ShiftedNoise {
+                shift_x,
+                shift_y,
+                shift_z,
+                data,
+            } => BaseNoiseFunctionComponent::ShiftedNoise {
+                shift_x_index: self.push(*shift_x),
+                shift_y_index: self.push(*shift_y),
+                shift_z_index: self.push(*shift_z),
+                data: noise_router::ShiftedNoiseData {
+                    xz_scale: data.xz_scale.0,
+                    y_scale: data.y_scale.0,
+                    noise_id: Cow::Owned(data.noise_id),
+                },
+            },
+            DensityFunctionRepr::InterpolatedNoiseSampler { data } => {
+                BaseNoiseFunctionComponent::InterpolatedNoiseSampler {
+                    data: noise_router::InterpolatedNoiseSamplerData {
+                        scaled_xz_scale: data.scaled_xz_scale.0,
+                        scaled_y_scale: data.scaled_y_scale.0,
+                        xz_factor: data.xz_factor.0,
+                        y_factor: data.y_factor.0,
+                        smear_scale_multiplier: data.smear_scale_multiplier.0,
+                    },
+                }
+            }
+            DensityFunctionRepr::WeirdScaled { input, data } => {
+                BaseNoiseFunctionComponent::WeirdScaled {
+                    input_index: self.push(*input),
+                    data: noise_router::WeirdScaledData {
+                        noise_id: Cow::Owned(data.noise_id),
+                        mapper: match data.mapper {
+                            repr::WeirdScaledMapper::Caves => WeirdScaledMapper::Caves,
+                            repr::WeirdScaledMapper::Tunnels => WeirdScaledMapper::Tunnels,
+                        },
+                    },
+                }
+            }
+            DensityFunctionRepr::Wrapper { input, wrapper } => {
+                BaseNoiseFunctionComponent::Wrapper {
+                    input_index: self.push(*input),
+                    wrapper: match wrapper {
+                        repr::WrapperType::Interpolated => WrapperType::Interpolated,
+                        repr::WrapperType::CacheFlat => WrapperType::CacheFlat,
+                        repr::WrapperType::Cache2D => WrapperType::Cache2D,
+                        repr::WrapperType::CacheOnce => WrapperType::CacheOnce,
+                        repr::WrapperType::CellCache => WrapperType::CellCache,
+                    },
+                }
+            }
+            DensityFunctionRepr::Constant { value } => {
+                BaseNoiseFunctionComponent::Constant { value: value.0 }
+            }
+            DensityFunctionRepr::ClampedYGradient { data } => {
+                BaseNoiseFunctionComponent::ClampedYGradient {
+                    data: noise_router::ClampedYGradientData {
+                        from_y: data.from_y as f64,
+                        to_y: data.to_y as f64,
+                        from_value: data.from_value.0,
+                        to_value: data.to_value.0,
+                    },
+                }
+            }
+            DensityFunctionRepr::Binary {
+                argument1,
+                argument2,
+                data,
+            } => BaseNoiseFunctionComponent::Binary {
+                argument1_index: self.push(*argument1),
+                argument2_index: self.push(*argument2),
+                data: noise_router::BinaryData {
+                    operation: match data.operation {
+                        repr::BinaryOperation::Add => BinaryOperation::Add,
+                        repr::BinaryOperation::Mul => BinaryOperation::Mul,
+                        repr::BinaryOperation::Min => BinaryOperation::Min,
+                        repr::BinaryOperation::Max => BinaryOperation::Max,
+                    },
+                },
+            },
+            DensityFunctionRepr::Linear { input, data } => BaseNoiseFunctionComponent::Linear {
+                input_index: self.push(*input),
+                data: noise_router::LinearData {
+                    operation: match data.operation {
+                        repr::LinearOperation::Add => LinearOperation::Add,
+                        repr::LinearOperation::Mul => LinearOperation::Mul,
+                    },
+                    argument: data.argument.0,
+                },
+            },
+            DensityFunctionRepr::Unary { input, data } => BaseNoiseFunctionComponent::Unary {
+                input_index: self.push(*input),
+                data: noise_router::UnaryData {
+                    operation: match data.operation {
+                        repr::UnaryOperation::Abs => UnaryOperation::Abs,
+                        repr::UnaryOperation::Square => UnaryOperation::Square,
+                        repr::UnaryOperation::Cube => UnaryOperation::Cube,
+                        repr::UnaryOperation::HalfNegative => UnaryOperation::HalfNegative,
+                        repr::UnaryOperation::QuarterNegative => UnaryOperation::QuarterNegative,
+                        repr::UnaryOperation::Squeeze => UnaryOperation::Squeeze,
+                    },
+                },
+            },
+            DensityFunctionRepr::Clamp { input, data } => BaseNoiseFunctionComponent::Clamp {
+                input_index: self.push(*input),
+                data: noise_router::ClampData {
+                    min_value: data.min_value.0,
+                    max_value: data.max_value.0,
+                },
+            },
+            DensityFunctionRepr::RangeChoice {
+                input,
+                when_in_range,
+                when_out_range,
+                data,
+            } => BaseNoiseFunctionComponent::RangeChoice {
+                input_index: self.push(*input),
+                when_in_range_index: self.push(*when_in_range),
+                when_out_range_index: self.push(*when_out_range),
+                data: noise_router::RangeChoiceData {
+                    min_inclusive: data.min_inclusive.0,
+                    max_exclusive: data.max_exclusive.0,
+                },
+            },
+            DensityFunctionRepr::Spline { spline } => {
+                let spline = self.spline(spline);
+                assert!(
+                    matches!(spline, SplineRepr::Standard { .. }),
+                    "Top level splines always take in a density function"
+                );
+                BaseNoiseFunctionComponent::Spline { spline }
+            }
+        }
+    }
+
+    fn spline(&mut self, spline: repr::SplineRepr) -> SplineRepr {
+        match spline {
+            repr::SplineRepr::Standard {
+                location_function,
+                locations,
+                values,
+                derivatives,
+            } => {
+                let location_function_index = self.push(*location_function);
+                let points = locations
+                    .iter()
+                    .zip(values)
+                    .zip(derivatives.iter())
+                    .map(|((location, value), derivative)| SplinePoint {
+                        location: location.0,
+                        value: self.spline(value),
+                        derivative: derivative.0,
+                    })
+                    .collect();
+                SplineRepr::Standard {
+                    location_function_index,
+                    points: Cow::Owned(points),
+                }
+            }
+            repr::SplineRepr::Fixed { value } => SplineRepr::Fixed { value: value.0 },
+        }
+    }
+
+    fn into_stack(self) -> Cow<'static, [BaseNoiseFunctionComponent]> {
+        Cow::Owned(self.components)
+    }
+}
+
+/// The component stack of `function` on its own, which ends with the function itself.
+#[cfg(test)]
+pub(crate) fn component_stack(function: &DensityFunctionRepr) -> Vec<BaseNoiseFunctionComponent> {
+    let mut stack = ComponentStack::default();
+    stack.push(function.clone());
+    stack.components
+}
+
+fn base_routers(router: NoiseRouterRepr) -> BaseNoiseRouters {
+    let final_density = router.final_density.with_beardifier();
+
+    // Same order as the build script: the functions sampled most often come first
+    let mut noise = ComponentStack::default();
+    let final_density = noise.push(final_density);
+    let barrier_noise = noise.push(router.barrier_noise);
+    let fluid_level_floodedness_noise = noise.push(router.fluid_level_floodedness_noise);
+    let fluid_level_spread_noise = noise.push(router.fluid_level_spread_noise);
+    let lava_noise = noise.push(router.lava_noise);
+    let vein_toggle = noise.push(router.vein_toggle);
+    let vein_ridged = noise.push(router.vein_ridged);
+    let vein_gap = noise.push(router.vein_gap);
+    let noise_erosion = noise.push(router.erosion.clone());
+    let noise_depth = noise.push(router.depth.clone());
+
+    let mut surface = ComponentStack::default();
+    surface.push(router.initial_density_without_jaggedness);
+
+    let mut multi_noise = ComponentStack::default();
+    let ridges = multi_noise.push(router.ridges);
+    let temperature = multi_noise.push(router.temperature);
+    let vegetation = multi_noise.push(router.vegetation);
+    let continents = multi_noise.push(router.continents);
+    let multi_erosion = multi_noise.push(router.erosion);
+    let multi_depth = multi_noise.push(router.depth);
+
+    BaseNoiseRouters {
+        noise: BaseNoiseRouter {
+            full_component_stack: noise.into_stack(),
+            barrier_noise,
+            fluid_level_floodedness_noise,
+            fluid_level_spread_noise,
+            lava_noise,
+            erosion: noise_erosion,
+            depth: noise_depth,
+            final_density,
+            vein_toggle,
+            vein_ridged,
+            vein_gap,
+        },
+        surface_estimator: BaseSurfaceEstimator {
+            full_component_stack: surface.into_stack(),
+        },
+        multi_noise: BaseMultiNoiseRouter {
+            full_component_stack: multi_noise.into_stack(),
+            temperature,
+            vegetation,
+            continents,
+            erosion: multi_erosion,
+            depth: multi_depth,
+            ridges,
+        },
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use std::{collections::HashMap, fs, path::Path};
+
+    use pumpkin_data::{
+        density_function_repr::{DensityFunctionRepr, NoiseRouterRepr},
+        noise_router::{
+            BaseNoiseFunctionComponent, BaseNoiseRouters, BinaryOperation, LinearOperation,
+            NETHER_BASE_NOISE_ROUTER, OVERWORLD_BASE_NOISE_ROUTER, SplineRepr, UnaryOperation,
+            WeirdScaledMapper, WrapperType,
+        },
+    };
+    use serde_json::{Value, json};
+    use temp_dir::TempDir;
+
+    use super::{
+        NoiseRouterLoadError, base_routers, component_stack, load_noise_settings, resource_path,
+    };
+
+    fn write_resource(data_folder: &Path, kind: &str, id: &str, json: &Value) {
+        let path = resource_path(data_folder, kind, id);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, json.to_string()).unwrap();
+    }
+
+    /// Vanilla's noise settings `name`, as they are in its data.
+    fn vanilla_noise_settings(name: &str) -> Value {
+        let json = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/chunk_gen_settings.json"
+        ))
+        .expect("no data file");
+        let mut settings: HashMap<String, Value> =
+            serde_json::from_str(&json).expect("failed to decode data");
+        settings.remove(name).unwrap()
+    }
+
+    /// Writes the noise settings of the Nether into `data_folder`, along with the density
+    /// functions of vanilla that its router names.
+    pub(crate) fn write_vanilla_nether(data_folder: &Path) {
+        write_resource(
+            data_folder,
+            "noise_settings",
+            "minecraft:nether",
+            &vanilla_noise_settings("nether"),
+        );
+        for (id, shift) in [
+            ("shift_x", "minecraft:shift_a"),
+            ("shift_z", "minecraft:shift_b"),
+        ] {
+            write_resource(
+                data_folder,
+                "density_function",
+                id,
+                &json!({
+                    "type": "minecraft:flat_cache",
+                    "argument": {
+                        "type": "minecraft:cache_2d",
+                        "argument": { "type": shift, "argument": "minecraft:offset" }
+                    }
+                }),
+            );
+        }
+        write_resource(
+            data_folder,
+            "density_function",
+            "nether/base_3d_noise",
+            &json!({
+                "type": "minecraft:old_blended_noise",
+                "xz_scale": 0.25,
+                "y_scale": 0.375,
+                "xz_factor": 80.0,
+                "y_factor": 60.0,
+                "smear_scale_multiplier": 8.0
+            }),
+        );
+    }
+
+    /// Writes the noise settings `test:flat`, generating the Nether with a router that only has a
+    /// `final_density`.
+    fn write_flat(data_folder: &Path, final_density: Value) {
+        let mut settings = vanilla_noise_settings("nether");
+        let router = settings["noise_router"].as_object_mut().unwrap();
+        for function in router.values_mut() {
+            *function = json!(0.0);
+        }
+        router.insert("final_density".to_string(), final_density);
+        write_resource(data_folder, "noise_settings", "test:flat", &settings);
+    }
+
+    fn assert_same_routers(loaded: &BaseNoiseRouters, built_in: &BaseNoiseRouters) {
+        let noise = (&loaded.noise, &built_in.noise);
+        assert_eq!(
+            noise.0.full_component_stack.len(),
+            noise.1.full_component_stack.len()
+        );
+        assert_eq!(noise.0.final_density, noise.1.final_density);
+        assert_eq!(noise.0.barrier_noise, noise.1.barrier_noise);
+        assert_eq!(noise.0.vein_gap, noise.1.vein_gap);
+        assert_eq!(noise.0.erosion, noise.1.erosion);
+        assert_eq!(noise.0.depth, noise.1.depth);
+
+        assert_eq!(
+            loaded.surface_estimator.full_component_stack.len(),
+            built_in.surface_estimator.full_component_stack.len()
+        );
+
+        let multi_noise = (&loaded.multi_noise, &built_in.multi_noise);
+        assert_eq!(
+            multi_noise.0.full_component_stack.len(),
+            multi_noise.1.full_component_stack.len()
+        );
+        assert_eq!(multi_noise.0.temperature, multi_noise.1.temperature);
+        assert_eq!(multi_noise.0.continents, multi_noise.1.continents);
+        assert_eq!(multi_noise.0.ridges, multi_noise.1.ridges);
+    }
+
+    #[test]
+    fn matches_built_in_routers() {
+        let json = fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../assets/density_function.json"
+        ))
+        .expect("no data file");
+        let mut reprs: HashMap<String, NoiseRouterRepr> =
+            serde_json5::from_str(&json).expect("failed to decode data");
+
+        for (name, built_in) in [
+            ("overworld", &OVERWORLD_BASE_NOISE_ROUTER),
+            ("nether", &NETHER_BASE_NOISE_ROUTER),
+        ] {
+            let loaded = base_routers(reprs.remove(name).unwrap());
+            assert_same_routers(&loaded, built_in);
+        }
+    }
+
+    #[test]
+    fn datapack_matches_built_in_router() {
+        let temp_dir = TempDir::new().unwrap();
+        write_vanilla_nether(temp_dir.path());
+
+        let settings = load_noise_settings(temp_dir.path(), "minecraft:nether").unwrap();
+        assert_same_routers(&settings.noise_router, &NETHER_BASE_NOISE_ROUTER);
+        assert_eq!(settings.generation_settings.sea_level, 32);
+    }
+
+    #[test]
+    fn resolves_references_and_folds_constants() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_folder = temp_dir.path();
+        write_flat(data_folder, json!("test:terrain"));
+        write_resource(
+            data_folder,
+            "density_function",
+            "test:terrain",
+            &json!({ "type": "minecraft:mul", "argument1": "height", "argument2": "test:two" }),
+        );
+        // Without a namespace, like the ids in the file
+        write_resource(
+            data_folder,
+            "density_function",
+            "height",
+            &json!({
+                "type": "minecraft:y_clamped_gradient",
+                "from_y": 0,
+                "to_y": 128,
+                "from_value": 1.0,
+                "to_value": -1.0
+            }),
+        );
+        write_resource(data_folder, "density_function", "test:two", &json!(2.0));
+
+        let router = load_noise_settings(data_folder, "test:flat")
+            .unwrap()
+            .noise_router;
+        let stack = &router.noise.full_component_stack;
+        // Only the final density isn't a constant, which is wrapped with the beardifier
+        let BaseNoiseFunctionComponent::Wrapper { input_index, .. } =
+            &stack[router.noise.final_density]
+        else {
+            panic!("The final density isn't cached");
+        };
+        let BaseNoiseFunctionComponent::Binary {
+            argument1_index, ..
+        } = &stack[*input_index]
+        else {
+            panic!("The beardifier isn't added");
+        };
+        let BaseNoiseFunctionComponent::Linear { input_index, data } = &stack[*argument1_index]
+        else {
+            panic!("Multiplying by a constant isn't linear");
+        };
+        assert!(matches!(data.operation, LinearOperation::Mul));
+        assert_eq!(data.argument, 2.0);
+        assert!(matches!(
+            &stack[*input_index],
+            BaseNoiseFunctionComponent::ClampedYGradient { data } if data.to_y == 128.0
+        ));
+    }
+
+    #[test]
+    fn broken_references_are_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let data_folder = temp_dir.path();
+
+        write_flat(data_folder, json!("test:missing"));
+        assert!(matches!(
+            load_noise_settings(data_folder, "test:flat"),
+            Err(NoiseRouterLoadError::UnknownDensityFunction(id)) if id == "test:missing"
+        ));
+
+        write_flat(data_folder, json!("test:a"));
+        write_resource(
+            data_folder,
+            "density_function",
+            "test:a",
+            &json!({ "type": "minecraft:abs", "argument": "test:b" }),
+        );
+        write_resource(data_folder, "density_function", "test:b", &json!("test:a"));
+        assert!(matches!(
+            load_noise_settings(data_folder, "test:flat"),
+            Err(NoiseRouterLoadError::CyclicDensityFunction(id)) if id == "test:a"
+        ));
+    }
+
+    /// The stack of `function`, written like the extractor dumps it.
+    fn flatten(function: Value) -> Vec<BaseNoiseFunctionComponent> {
+        let repr: DensityFunctionRepr = serde_json::from_value(function).unwrap();
+        component_stack(&repr)
+    }
+
+    #[test]
+    fn every_class_flattens_into_its_component() {
+        use BaseNoiseFunctionComponent as C;
+
+        let noise = json!({
+            "_class": "Noise",
+            "value": { "noise": "noodle", "xzScale": 1.0, "yScale": 2.0 }
+        });
+        let constant = json!({ "_class": "Constant", "value": { "value": -1.0 } });
+        let with_input = |class: &str, value: Value| {
+            let mut value = value;
+            value["input"] = noise.clone();
+            json!({ "_class": class, "value": value })
+        };
+
+        // Without any inputs, the function is all there is to the stack
+        assert!(matches!(
+            flatten(json!({ "_class": "BlendAlpha" }))[..],
+            [C::BlendAlpha]
+        ));
+        assert!(matches!(
+            flatten(json!({ "_class": "BlendOffset" }))[..],
+            [C::BlendOffset]
+        ));
+        assert!(matches!(
+            flatten(json!({ "_class": "EndIslands" }))[..],
+            [C::EndIslands]
+        ));
+        assert!(matches!(
+            flatten(constant.clone())[..],
+            [C::Constant { value }] if value == -1.0
+        ));
+        assert!(matches!(
+            &flatten(noise.clone())[..],
+            [C::Noise { data }]
+                if data.noise_id == "noodle" && data.xz_scale == 1.0 && data.y_scale == 2.0
+        ));
+        for class in ["ShiftA", "ShiftB"] {
+            let stack = flatten(json!({ "_class": class, "value": { "offsetNoise": "offset" } }));
+            assert!(matches!(
+                &stack[..],
+                [C::ShiftA { noise_id } | C::ShiftB { noise_id }] if noise_id == "offset"
+            ));
+        }
+        assert!(matches!(
+            &flatten(json!({
+                "_class": "InterpolatedNoiseSampler",
+                "value": {
+                    "scaledXzScale": 171.103,
+                    "scaledYScale": 85.5515,
+                    "xzFactor": 80.0,
+                    "yFactor": 160.0,
+                    "smearScaleMultiplier": 8.0,
+                    "maxValue": 173.103,
+                    "xzScale": 0.25,
+                    "yScale": 0.125
+                }
+            }))[..],
+            [C::InterpolatedNoiseSampler { data }]
+                if data.scaled_xz_scale == 171.103
+                    && data.scaled_y_scale == 85.5515
+                    && data.xz_factor == 80.0
+                    && data.y_factor == 160.0
+                    && data.smear_scale_multiplier == 8.0
+        ));
+        assert!(matches!(
+            &flatten(json!({
+                "_class": "YClampedGradient",
+                "value": { "fromY": -64, "toY": 320, "fromValue": 1.5, "toValue": -1.5 }
+            }))[..],
+            [C::ClampedYGradient { data }]
+                if data.from_y == -64.0
+                    && data.to_y == 320.0
+                    && data.from_value == 1.5
+                    && data.to_value == -1.5
+        ));
+
+        // The inputs come first, the function last
+        assert!(matches!(
+            &flatten(json!({ "_class": "BlendDensity", "value": { "input": noise } }))[..],
+            [C::Noise { .. }, C::BlendDensity { input_index: 0 }]
+        ));
+        assert!(matches!(
+            &flatten(with_input(
+                "WeirdScaledSampler",
+                json!({ "noise": "spaghetti_2d", "rarityValueMapper": "TYPE2" })
+            ))[..],
+            [C::Noise { .. }, C::WeirdScaled { input_index: 0, data }]
+                if data.noise_id == "spaghetti_2d" && matches!(data.mapper, WeirdScaledMapper::Caves)
+        ));
+        assert!(matches!(
+            &flatten(json!({
+                "_class": "Wrapping",
+                "value": { "type": "FlatCache", "wrapped": noise }
+            }))[..],
+            [
+                C::Noise { .. },
+                C::Wrapper {
+                    input_index: 0,
+                    wrapper: WrapperType::CacheFlat
+                }
+            ]
+        ));
+        assert!(matches!(
+            &flatten(with_input(
+                "LinearOperation",
+                json!({ "specificType": "MUL", "minValue": -4.0, "maxValue": 4.0, "argument": 2.0 })
+            ))[..],
+            [C::Noise { .. }, C::Linear { input_index: 0, data }]
+                if matches!(data.operation, LinearOperation::Mul) && data.argument == 2.0
+        ));
+        assert!(matches!(
+            &flatten(with_input(
+                "UnaryOperation",
+                json!({ "type": "SQUARE", "minValue": 0.0, "maxValue": 4.0 })
+            ))[..],
+            [C::Noise { .. }, C::Unary { input_index: 0, data }]
+                if matches!(data.operation, UnaryOperation::Square)
+        ));
+        assert!(matches!(
+            &flatten(with_input("Clamp", json!({ "minValue": -1.0, "maxValue": 1.0 })))[..],
+            [C::Noise { .. }, C::Clamp { input_index: 0, data }]
+                if data.min_value == -1.0 && data.max_value == 1.0
+        ));
+        assert!(matches!(
+            &flatten(json!({
+                "_class": "BinaryOperation",
+                "value": {
+                    "type": "MAX",
+                    "argument1": noise,
+                    "argument2": constant,
+                    "minValue": -1.0,
+                    "maxValue": 1.0
+                }
+            }))[..],
+            [
+                C::Noise { .. },
+                C::Constant { .. },
+                C::Binary { argument1_index: 0, argument2_index: 1, data }
+            ] if matches!(data.operation, BinaryOperation::Max)
+        ));
+        assert!(matches!(
+            &flatten(with_input(
+                "RangeChoice",
+                json!({
+                    "minInclusive": -60.0,
+                    "maxExclusive": 321.0,
+                    "whenInRange": noise,
+                    "whenOutOfRange": constant
+                })
+            ))[..],
+            [
+                C::Noise { .. },
+                C::Constant { .. },
+                C::RangeChoice {
+                    input_index: 0,
+                    when_in_range_index: 0,
+                    when_out_range_index: 1,
+                    data
+                }
+            ] if data.min_inclusive == -60.0 && data.max_exclusive == 321.0
+        ));
+
+        let shift_a = json!({ "_class": "ShiftA", "value": { "offsetNoise": "offset" } });
+        let shift_b = json!({ "_class": "ShiftB", "value": { "offsetNoise": "offset" } });
+        assert!(matches!(
+            &flatten(json!({
+                "_class": "ShiftedNoise",
+                "value": {
+                    "shiftX": shift_a,
+                    "shiftY": constant,
+                    "shiftZ": shift_b,
+                    "xzScale": 0.25,
+                    "yScale": 0.0,
+                    "noise": "ridge"
+                }
+            }))[..],
+            [
+                C::ShiftA { .. },
+                C::Constant { .. },
+                C::ShiftB { .. },
+                C::ShiftedNoise {
+                    shift_x_index: 0,
+                    shift_y_index: 1,
+                    shift_z_index: 2,
+                    data
+                }
+            ] if data.noise_id == "ridge" && data.xz_scale == 0.25 && data.y_scale == 0.0
+        ));
+
+        // The nested splines share the location function of the outer one
+        let stack = flatten(json!({
+            "_class": "Spline",
+            "value": {
+                "minValue": -1.0,
+                "maxValue": 1.0,
+                "spline": {
+                    "_type": "standard",
+                    "value": {
+                        "locationFunction": noise,
+                        "locations": [-1.0, 1.0],
+                        "values": [
+                            { "_type": "fixed", "value": { "value": -1.0 } },
+                            {
+                                "_type": "standard",
+                                "value": {
+                                    "locationFunction": noise,
+                                    "locations": [0.0],
+                                    "values": [{ "_type": "fixed", "value": { "value": 1.0 } }],
+                                    "derivatives": [0.5]
+                                }
+                            }
+                        ],
+                        "derivatives": [0.0, 2.0]
+                    }
+                }
+            }
+        }));
+        let [
+            C::Noise { .. },
+            C::Spline {
+                spline:
+                    SplineRepr::Standard {
+                        location_function_index: 0,
+                        points,
+                    },
+            },
+        ] = &stack[..]
+        else {
+            panic!("The spline isn't flattened along with its location function");
+        };
+        assert_eq!(points.len(), 2);
+        assert_eq!((points[0].location, points[1].derivative), (-1.0, 2.0));
+        assert!(matches!(points[0].value, SplineRepr::Fixed { value } if value == -1.0));
+        assert!(matches!(
+            &points[1].value,
+            SplineRepr::Standard { location_function_index: 0, points }
+                if points.len() == 1 && points[0].derivative == 0.5
+        ));
+    }
+
+    #[test]
+    fn identical_functions_share_their_component() {
+        let noise = |name: &str| json!({ "_class": "Noise", "value": { "noise": name, "xzScale": 1.0, "yScale": 1.0 } });
+        let add = |argument1: Value, argument2: Value| {
+            json!({
+                "_class": "BinaryOperation",
+                "value": { "type": "ADD", "argument1": argument1, "argument2": argument2 }
+            })
+        };
+
+        let stack = flatten(add(noise("a"), noise("a")));
+        assert!(matches!(
+            stack[..],
+            [
+                BaseNoiseFunctionComponent::Noise { .. },
+                BaseNoiseFunctionComponent::Binary {
+                    argument1_index: 0,
+                    argument2_index: 0,
+                    ..
+                }
+            ]
+        ));
+        // Only the same function, not one of another noise
+        assert_eq!(flatten(add(noise("a"), noise("b"))).len(), 3);
+        // Nor one differing further down
+        let stack = flatten(add(
+            add(noise("a"), noise("b")),
+            add(noise("a"), noise("c")),
+        ));
+        assert_eq!(stack.len(), 6);
+    }
+}
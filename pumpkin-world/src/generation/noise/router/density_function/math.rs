@@ -48,7 +48,7 @@ pub struct Linear {
     pub(crate) input_index: usize,
     min_value: f64,
     max_value: f64,
-    data: &'static LinearData,
+    data: LinearData,
 }
 
 impl NoiseFunctionComponentRange for Linear {
@@ -80,17 +80,12 @@ impl StaticChunkNoiseFunctionComponentImpl for Linear {
 }
 
 impl Linear {
-    pub fn new(
-        input_index: usize,
-        min_value: f64,
-        max_value: f64,
-        data: &'static LinearData,
-    ) -> Self {
+    pub fn new(input_index: usize, min_value: f64, max_value: f64, data: &LinearData) -> Self {
         Self {
             input_index,
             min_value,
             max_value,
-            data,
+            data: *data,
         }
     }
 }
@@ -101,7 +96,7 @@ pub struct Binary {
     pub(crate) input2_index: usize,
     min_value: f64,
     max_value: f64,
-    data: &'static BinaryData,
+    data: BinaryData,
 }
 
 impl NoiseFunctionComponentRange for Binary {
@@ -260,14 +255,14 @@ impl Binary {
         input2_index: usize,
         min_value: f64,
         max_value: f64,
-        data: &'static BinaryData,
+        data: &BinaryData,
     ) -> Self {
         Self {
             input1_index,
             input2_index,
             min_value,
             max_value,
-            data,
+            data: *data,
         }
     }
 }
@@ -277,7 +272,7 @@ pub struct Unary {
     pub(crate) input_index: usize,
     min_value: f64,
     max_value: f64,
-    data: &'static UnaryData,
+    data: UnaryData,
 }
 
 impl NoiseFunctionComponentRange for Unary {
@@ -327,17 +322,12 @@ impl StaticChunkNoiseFunctionComponentImpl for Unary {
 }
 
 impl Unary {
-    pub fn new(
-        input_index: usize,
-        min_value: f64,
-        max_value: f64,
-        data: &'static UnaryData,
-    ) -> Self {
+    pub fn new(input_index: usize, min_value: f64, max_value: f64, data: &UnaryData) -> Self {
         Self {
             input_index,
             min_value,
             max_value,
-            data,
+            data: *data,
         }
     }
 }
@@ -345,12 +335,15 @@ impl Unary {
 #[derive(Clone)]
 pub struct Clamp {
     input_index: usize,
-    data: &'static ClampData,
+    data: ClampData,
 }
 
 impl Clamp {
-    pub fn new(input_index: usize, data: &'static ClampData) -> Self {
-        Self { input_index, data }
+    pub fn new(input_index: usize, data: &ClampData) -> Self {
+        Self {
+            input_index,
+            data: *data,
+        }
     }
 }
 
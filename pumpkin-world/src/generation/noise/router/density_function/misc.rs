@@ -173,12 +173,12 @@ impl NoiseFunctionComponentRange for WeirdScaled {
 
 #[derive(Clone)]
 pub struct ClampedYGradient {
-    data: &'static ClampedYGradientData,
+    data: ClampedYGradientData,
 }
 
 impl ClampedYGradient {
-    pub fn new(data: &'static ClampedYGradientData) -> Self {
-        Self { data }
+    pub fn new(data: &ClampedYGradientData) -> Self {
+        Self { data: *data }
     }
 }
 
@@ -211,7 +211,7 @@ pub struct RangeChoice {
     input_index: usize,
     pub(crate) when_in_index: usize,
     pub(crate) when_out_index: usize,
-    data: &'static RangeChoiceData,
+    data: RangeChoiceData,
     min_value: f64,
     max_value: f64,
 }
@@ -223,7 +223,7 @@ impl RangeChoice {
         when_out_index: usize,
         min_value: f64,
         max_value: f64,
-        data: &'static RangeChoiceData,
+        data: &RangeChoiceData,
     ) -> Self {
         Self {
             input_index,
@@ -231,7 +231,7 @@ impl RangeChoice {
             when_out_index,
             min_value,
             max_value,
-            data,
+            data: *data,
         }
     }
 }
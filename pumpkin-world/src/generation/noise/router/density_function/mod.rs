@@ -16,10 +16,6 @@ pub(crate) mod spline;
 #[cfg(test)]
 mod test;
 
-// Helper functions for deserializing unique density functions for testing
-#[cfg(test)]
-mod test_deserializer;
-
 pub trait NoisePos: Debug {
     fn x(&self) -> i32;
     fn y(&self) -> i32;
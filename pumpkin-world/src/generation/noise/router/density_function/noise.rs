@@ -19,12 +19,17 @@ use super::{
 
 pub struct Noise {
     sampler: DoublePerlinNoiseSampler,
-    data: &'static NoiseData,
+    xz_scale: f64,
+    y_scale: f64,
 }
 
 impl Noise {
-    pub fn new(sampler: DoublePerlinNoiseSampler, data: &'static NoiseData) -> Self {
-        Self { sampler, data }
+    pub fn new(sampler: DoublePerlinNoiseSampler, data: &NoiseData) -> Self {
+        Self {
+            sampler,
+            xz_scale: data.xz_scale,
+            y_scale: data.y_scale,
+        }
     }
 }
 
@@ -43,9 +48,9 @@ impl NoiseFunctionComponentRange for Noise {
 impl StaticIndependentChunkNoiseFunctionComponentImpl for Noise {
     fn sample(&self, pos: &impl NoisePos) -> f64 {
         self.sampler.sample(
-            pos.x() as f64 * self.data.xz_scale,
-            pos.y() as f64 * self.data.y_scale,
-            pos.z() as f64 * self.data.xz_scale,
+            pos.x() as f64 * self.xz_scale,
+            pos.y() as f64 * self.y_scale,
+            pos.z() as f64 * self.xz_scale,
         )
     }
 }
@@ -116,7 +121,8 @@ pub struct ShiftedNoise {
     input_y_index: usize,
     input_z_index: usize,
     sampler: DoublePerlinNoiseSampler,
-    data: &'static ShiftedNoiseData,
+    xz_scale: f64,
+    y_scale: f64,
 }
 
 impl NoiseFunctionComponentRange for ShiftedNoise {
@@ -138,19 +144,19 @@ impl StaticChunkNoiseFunctionComponentImpl for ShiftedNoise {
         pos: &impl NoisePos,
         sample_options: &ChunkNoiseFunctionSampleOptions,
     ) -> f64 {
-        let translated_x = pos.x() as f64 * self.data.xz_scale
+        let translated_x = pos.x() as f64 * self.xz_scale
             + ChunkNoiseFunctionComponent::sample_from_stack(
                 &mut component_stack[..=self.input_x_index],
                 pos,
                 sample_options,
             );
-        let translated_y = pos.y() as f64 * self.data.y_scale
+        let translated_y = pos.y() as f64 * self.y_scale
             + ChunkNoiseFunctionComponent::sample_from_stack(
                 &mut component_stack[..=self.input_y_index],
                 pos,
                 sample_options,
             );
-        let translated_z = pos.z() as f64 * self.data.xz_scale
+        let translated_z = pos.z() as f64 * self.xz_scale
             + ChunkNoiseFunctionComponent::sample_from_stack(
                 &mut component_stack[..=self.input_z_index],
                 pos,
@@ -168,14 +174,15 @@ impl ShiftedNoise {
         input_y_index: usize,
         input_z_index: usize,
         sampler: DoublePerlinNoiseSampler,
-        data: &'static ShiftedNoiseData,
+        data: &ShiftedNoiseData,
     ) -> Self {
         Self {
             input_x_index,
             input_y_index,
             input_z_index,
             sampler,
-            data,
+            xz_scale: data.xz_scale,
+            y_scale: data.y_scale,
         }
     }
 }
@@ -184,13 +191,13 @@ pub struct InterpolatedNoiseSampler {
     lower_noise: Box<OctavePerlinNoiseSampler>,
     upper_noise: Box<OctavePerlinNoiseSampler>,
     noise: Box<OctavePerlinNoiseSampler>,
-    data: &'static InterpolatedNoiseSamplerData,
+    data: InterpolatedNoiseSamplerData,
     fractions: [f64; 16],
     max_value: f64,
 }
 
 impl InterpolatedNoiseSampler {
-    pub fn new(data: &'static InterpolatedNoiseSamplerData, random: &mut RandomGenerator) -> Self {
+    pub fn new(data: &InterpolatedNoiseSamplerData, random: &mut RandomGenerator) -> Self {
         let big_start = -15;
         let big_amplitudes = [1.0; 16];
 
@@ -230,7 +237,7 @@ impl InterpolatedNoiseSampler {
             lower_noise,
             upper_noise,
             noise,
-            data,
+            data: *data,
             fractions,
             max_value,
         }
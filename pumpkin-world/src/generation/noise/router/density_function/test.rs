@@ -1,3 +1,4 @@
+use pumpkin_data::density_function_repr::DensityFunctionRepr;
 use pumpkin_data::noise_router::OVERWORLD_BASE_NOISE_ROUTER;
 use pumpkin_util::{assert_eq_delta, read_data_from_file};
 use serde::Deserialize;
@@ -13,11 +14,11 @@ use crate::generation::noise::router::chunk_density_function::{
 use crate::generation::noise::router::chunk_noise_router::{
     ChunkNoiseDensityFunction, ChunkNoiseFunctionComponent,
 };
+use crate::generation::noise::router::custom_router::component_stack;
 use crate::generation::noise::router::proto_noise_router::{
     ProtoNoiseFunctionComponent, ProtoNoiseRouters,
 };
 
-use super::test_deserializer::DensityFunctionRepr;
 use super::{NoiseFunctionComponentRange, NoisePos, PassThrough};
 
 #[derive(Debug)]
@@ -583,7 +584,7 @@ fn test_config_final_density() {
 
     let router = &OVERWORLD_BASE_NOISE_ROUTER.noise;
     let proto_stack =
-        ProtoNoiseRouters::generate_proto_stack(router.full_component_stack, &RANDOM_CONFIG);
+        ProtoNoiseRouters::generate_proto_stack(&router.full_component_stack, &RANDOM_CONFIG);
     let mut stack = build_function_stack!(proto_stack);
     let mut function = build_function!(&mut stack[..router.final_density]);
 
@@ -651,7 +652,7 @@ static DENSITY_FUNCTION_REPRS: LazyLock<DensityFunctionReprs> = LazyLock::new(||
 
 #[test]
 fn test_base_sloped_cheese() {
-    let base_stack = DENSITY_FUNCTION_REPRS.sloped_cheese.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.sloped_cheese);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut stack = build_function_stack!(proto_stack);
     let mut function = build_function!(stack);
@@ -670,7 +671,7 @@ fn test_base_sloped_cheese() {
 
 #[test]
 fn test_base_factor() {
-    let base_stack = DENSITY_FUNCTION_REPRS.factor.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.factor);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut stack = build_function_stack!(proto_stack);
     let mut function = build_function!(stack);
@@ -689,7 +690,7 @@ fn test_base_factor() {
 
 #[test]
 fn test_base_depth() {
-    let base_stack = DENSITY_FUNCTION_REPRS.depth.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.depth);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -708,7 +709,7 @@ fn test_base_depth() {
 
 #[test]
 fn test_base_offset() {
-    let base_stack = DENSITY_FUNCTION_REPRS.offset.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.offset);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -727,7 +728,7 @@ fn test_base_offset() {
 
 #[test]
 fn test_base_cave_entrances() {
-    let base_stack = DENSITY_FUNCTION_REPRS.cave_entrances.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.cave_entrances);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -746,7 +747,7 @@ fn test_base_cave_entrances() {
 
 #[test]
 fn test_base_3d_noise() {
-    let base_stack = DENSITY_FUNCTION_REPRS.base_3d_noise.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.base_3d_noise);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -765,9 +766,7 @@ fn test_base_3d_noise() {
 
 #[test]
 fn test_base_spahetti_roughness() {
-    let base_stack = DENSITY_FUNCTION_REPRS
-        .spaghetti_roughness
-        .base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.spaghetti_roughness);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -787,7 +786,7 @@ fn test_base_spahetti_roughness() {
 
 #[test]
 fn test_base_cave_noodle() {
-    let base_stack = DENSITY_FUNCTION_REPRS.cave_noodle.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.cave_noodle);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -806,7 +805,7 @@ fn test_base_cave_noodle() {
 
 #[test]
 fn test_base_cave_pillars() {
-    let base_stack = DENSITY_FUNCTION_REPRS.cave_pillars.base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.cave_pillars);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
@@ -825,9 +824,7 @@ fn test_base_cave_pillars() {
 
 #[test]
 fn test_base_spaghetti_2d_thickness() {
-    let base_stack = DENSITY_FUNCTION_REPRS
-        .spaghetti_2d_thickness
-        .base_component_stack();
+    let base_stack = component_stack(&DENSITY_FUNCTION_REPRS.spaghetti_2d_thickness);
     let proto_stack = ProtoNoiseRouters::generate_proto_stack(&base_stack, &RANDOM_CONFIG);
     let mut function_stack = build_function_stack!(proto_stack);
     let mut function = build_function!(function_stack);
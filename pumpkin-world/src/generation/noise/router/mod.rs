@@ -1,6 +1,8 @@
 pub mod chunk_density_function;
 pub mod chunk_noise_router;
+pub mod custom_router;
 pub mod density_function;
 pub mod multi_noise_sampler;
 pub mod proto_noise_router;
 pub mod surface_height_sampler;
+pub mod terrain_shape;
@@ -2,11 +2,13 @@ use enum_dispatch::enum_dispatch;
 use pumpkin_data::{
     chunk::DoublePerlinNoiseParameters,
     noise_router::{
-        BaseNoiseFunctionComponent, BaseNoiseRouters, BinaryOperation, LinearOperation, SplineRepr,
-        UnaryOperation,
+        BaseNoiseFunctionComponent, BaseNoiseRouters, BinaryOperation, END_BASE_NOISE_ROUTER,
+        LinearOperation, NETHER_BASE_NOISE_ROUTER, OVERWORLD_AMPLIFIED_BASE_NOISE_ROUTER,
+        OVERWORLD_BASE_NOISE_ROUTER, SplineRepr, UnaryOperation,
     },
 };
 use pumpkin_util::random::RandomDeriverImpl;
+use thiserror::Error;
 
 use crate::{GlobalRandomConfig, generation::noise::perlin::DoublePerlinNoiseSampler};
 
@@ -115,6 +117,113 @@ pub struct ProtoNoiseRouters {
     pub multi_noise: ProtoMultiNoiseRouter,
 }
 
+/// Why a noise router can't be turned into [`ProtoNoiseRouters`].
+#[derive(Error, Debug)]
+pub enum InvalidNoiseRouter {
+    #[error("Unknown noise {0}")]
+    UnknownNoise(String),
+    #[error("Component {component} reads component {input}, which doesn't come before it")]
+    InvalidInput { component: usize, input: usize },
+    #[error("The router reads component {index} of a stack of {len}")]
+    InvalidOutput { index: usize, len: usize },
+}
+
+/// Collects the components the splines of `spline_repr` read into `inputs`.
+fn spline_inputs(spline_repr: &SplineRepr, inputs: &mut Vec<usize>) {
+    if let SplineRepr::Standard {
+        location_function_index,
+        points,
+    } = spline_repr
+    {
+        inputs.push(*location_function_index);
+        for point in points.iter() {
+            spline_inputs(&point.value, inputs);
+        }
+    }
+}
+
+/// Checks that the components of `stack` only read noises that exist and components before them,
+/// and that the router only reads the `outputs` of the stack.
+fn validate_stack(
+    stack: &[BaseNoiseFunctionComponent],
+    outputs: &[usize],
+) -> Result<(), InvalidNoiseRouter> {
+    for (component, function) in stack.iter().enumerate() {
+        let mut inputs = Vec::new();
+        let noise = match function {
+            BaseNoiseFunctionComponent::Noise { data } => Some(&*data.noise_id),
+            BaseNoiseFunctionComponent::ShiftA { noise_id }
+            | BaseNoiseFunctionComponent::ShiftB { noise_id } => Some(&**noise_id),
+            BaseNoiseFunctionComponent::ShiftedNoise {
+                shift_x_index,
+                shift_y_index,
+                shift_z_index,
+                data,
+            } => {
+                inputs.extend([*shift_x_index, *shift_y_index, *shift_z_index]);
+                Some(&*data.noise_id)
+            }
+            BaseNoiseFunctionComponent::WeirdScaled { input_index, data } => {
+                inputs.push(*input_index);
+                Some(&*data.noise_id)
+            }
+            BaseNoiseFunctionComponent::BlendDensity { input_index }
+            | BaseNoiseFunctionComponent::Wrapper { input_index, .. }
+            | BaseNoiseFunctionComponent::Linear { input_index, .. }
+            | BaseNoiseFunctionComponent::Unary { input_index, .. }
+            | BaseNoiseFunctionComponent::Clamp { input_index, .. } => {
+                inputs.push(*input_index);
+                None
+            }
+            BaseNoiseFunctionComponent::Binary {
+                argument1_index,
+                argument2_index,
+                ..
+            } => {
+                inputs.extend([*argument1_index, *argument2_index]);
+                None
+            }
+            BaseNoiseFunctionComponent::RangeChoice {
+                input_index,
+                when_in_range_index,
+                when_out_range_index,
+                ..
+            } => {
+                inputs.extend([*input_index, *when_in_range_index, *when_out_range_index]);
+                None
+            }
+            BaseNoiseFunctionComponent::Spline { spline } => {
+                spline_inputs(spline, &mut inputs);
+                None
+            }
+            BaseNoiseFunctionComponent::Beardifier
+            | BaseNoiseFunctionComponent::BlendAlpha
+            | BaseNoiseFunctionComponent::BlendOffset
+            | BaseNoiseFunctionComponent::EndIslands
+            | BaseNoiseFunctionComponent::InterpolatedNoiseSampler { .. }
+            | BaseNoiseFunctionComponent::Constant { .. }
+            | BaseNoiseFunctionComponent::ClampedYGradient { .. } => None,
+        };
+
+        if let Some(noise) = noise
+            && DoublePerlinNoiseParameters::id_to_parameters(noise).is_none()
+        {
+            return Err(InvalidNoiseRouter::UnknownNoise(noise.to_string()));
+        }
+        if let Some(&input) = inputs.iter().find(|&&input| input >= component) {
+            return Err(InvalidNoiseRouter::InvalidInput { component, input });
+        }
+    }
+
+    match outputs.iter().find(|&&index| index >= stack.len()) {
+        Some(&index) => Err(InvalidNoiseRouter::InvalidOutput {
+            index,
+            len: stack.len(),
+        }),
+        None => Ok(()),
+    }
+}
+
 fn build_spline_recursive(spline_repr: &SplineRepr) -> SplineValue {
     match spline_repr {
         SplineRepr::Standard {
@@ -124,7 +233,7 @@ fn build_spline_recursive(spline_repr: &SplineRepr) -> SplineValue {
             let points = points
                 .iter()
                 .map(|point| {
-                    let value = build_spline_recursive(point.value);
+                    let value = build_spline_recursive(&point.value);
                     SplinePoint::new(point.location, value, point.derivative)
                 })
                 .collect();
@@ -166,7 +275,7 @@ impl ProtoNoiseRouters {
                     )),
                 ),
                 BaseNoiseFunctionComponent::Noise { data } => {
-                    let sampler = perlin_noise_builder.get_noise_sampler_for_id(data.noise_id);
+                    let sampler = perlin_noise_builder.get_noise_sampler_for_id(&data.noise_id);
                     ProtoNoiseFunctionComponent::Independent(
                         IndependentProtoNoiseFunctionComponent::Noise(Noise::new(sampler, data)),
                     )
@@ -218,7 +327,7 @@ impl ProtoNoiseRouters {
                     shift_z_index,
                     data,
                 } => {
-                    let sampler = perlin_noise_builder.get_noise_sampler_for_id(data.noise_id);
+                    let sampler = perlin_noise_builder.get_noise_sampler_for_id(&data.noise_id);
                     ProtoNoiseFunctionComponent::Dependent(
                         DependentProtoNoiseFunctionComponent::ShiftedNoise(ShiftedNoise::new(
                             *shift_x_index,
@@ -396,7 +505,7 @@ impl ProtoNoiseRouters {
                     )
                 }
                 BaseNoiseFunctionComponent::WeirdScaled { input_index, data } => {
-                    let sampler = perlin_noise_builder.get_noise_sampler_for_id(data.noise_id);
+                    let sampler = perlin_noise_builder.get_noise_sampler_for_id(&data.noise_id);
                     ProtoNoiseFunctionComponent::Dependent(
                         DependentProtoNoiseFunctionComponent::WeirdScaled(WeirdScaled::new(
                             *input_index,
@@ -424,13 +533,47 @@ impl ProtoNoiseRouters {
         stack.into()
     }
 
+    /// Checks that `base` can be generated, which [`ProtoNoiseRouters::generate`] would panic on
+    /// otherwise.
+    pub fn validate(base: &BaseNoiseRouters) -> Result<(), InvalidNoiseRouter> {
+        let noise = &base.noise;
+        validate_stack(
+            &noise.full_component_stack,
+            &[
+                noise.barrier_noise,
+                noise.fluid_level_floodedness_noise,
+                noise.fluid_level_spread_noise,
+                noise.lava_noise,
+                noise.erosion,
+                noise.depth,
+                noise.final_density,
+                noise.vein_toggle,
+                noise.vein_ridged,
+                noise.vein_gap,
+            ],
+        )?;
+        validate_stack(&base.surface_estimator.full_component_stack, &[])?;
+        let multi_noise = &base.multi_noise;
+        validate_stack(
+            &multi_noise.full_component_stack,
+            &[
+                multi_noise.temperature,
+                multi_noise.vegetation,
+                multi_noise.continents,
+                multi_noise.erosion,
+                multi_noise.depth,
+                multi_noise.ridges,
+            ],
+        )
+    }
+
     pub fn generate(base: &BaseNoiseRouters, random_config: &GlobalRandomConfig) -> Self {
         let noise_stack =
-            Self::generate_proto_stack(base.noise.full_component_stack, random_config);
+            Self::generate_proto_stack(&base.noise.full_component_stack, random_config);
         let surface_stack =
-            Self::generate_proto_stack(base.surface_estimator.full_component_stack, random_config);
+            Self::generate_proto_stack(&base.surface_estimator.full_component_stack, random_config);
         let multi_noise_stack =
-            Self::generate_proto_stack(base.multi_noise.full_component_stack, random_config);
+            Self::generate_proto_stack(&base.multi_noise.full_component_stack, random_config);
 
         Self {
             noise: ProtoNoiseRouter {
@@ -461,3 +604,58 @@ impl ProtoNoiseRouters {
         }
     }
 }
+
+/// The routers the vanilla generators pick from, by the name they are reported with.
+pub const VANILLA_NOISE_ROUTERS: [(&str, &BaseNoiseRouters); 4] = [
+    ("overworld", &OVERWORLD_BASE_NOISE_ROUTER),
+    ("amplified", &OVERWORLD_AMPLIFIED_BASE_NOISE_ROUTER),
+    ("nether", &NETHER_BASE_NOISE_ROUTER),
+    ("end", &END_BASE_NOISE_ROUTER),
+];
+
+/// Generates every one of the named `routers` once, e.g. [`VANILLA_NOISE_ROUTERS`] along with the
+/// routers of custom dimensions. A generator only generates the router of its own dimension when
+/// it is created, so a broken one would otherwise only show once someone enters the dimension.
+/// Returns the names of the broken routers with what is wrong with them.
+pub fn check_noise_routers<'a>(
+    routers: impl IntoIterator<Item = (&'a str, &'a BaseNoiseRouters)>,
+) -> Result<(), Vec<(&'a str, InvalidNoiseRouter)>> {
+    let random_config = GlobalRandomConfig::new(0, false);
+    let broken: Vec<_> = routers
+        .into_iter()
+        .filter_map(|(name, router)| match ProtoNoiseRouters::validate(router) {
+            Ok(()) => {
+                ProtoNoiseRouters::generate(router, &random_config);
+                None
+            }
+            Err(err) => Some((name, err)),
+        })
+        .collect();
+    if broken.is_empty() {
+        Ok(())
+    } else {
+        Err(broken)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_data::noise_router::END_BASE_NOISE_ROUTER;
+
+    use super::{InvalidNoiseRouter, VANILLA_NOISE_ROUTERS, check_noise_routers};
+
+    #[test]
+    fn broken_routers_are_reported_by_name() {
+        assert!(check_noise_routers(VANILLA_NOISE_ROUTERS).is_ok());
+
+        let mut end = END_BASE_NOISE_ROUTER;
+        end.multi_noise.temperature = end.multi_noise.full_component_stack.len();
+        let mut routers = VANILLA_NOISE_ROUTERS;
+        routers[3].1 = &end;
+        let broken = check_noise_routers(routers).unwrap_err();
+        assert!(matches!(
+            broken.as_slice(),
+            [("end", InvalidNoiseRouter::InvalidOutput { .. })]
+        ));
+    }
+}
@@ -0,0 +1,340 @@
+//! Reshapes the terrain of routers shaped like vanilla's overworld, see [`TerrainShape`].
+//!
+//! Vanilla shapes its land with three splines over the continentalness, erosion and weirdness:
+//! the `offset` raising the land, which goes into its `depth`, the `jaggedness` of the peaks added
+//! to the depth, and the `factor` the sum is multiplied with. The routers are fully resolved, so
+//! the splines are found by where they go instead of by their names. A spline is linear in its
+//! values and derivatives, so the knobs are applied to those.
+
+use std::borrow::Cow;
+
+use pumpkin_config::chunk::TerrainShape;
+use pumpkin_data::noise_router::{
+    BaseNoiseFunctionComponent, BaseNoiseRouters, BinaryOperation, SplinePoint, SplineRepr,
+};
+use thiserror::Error;
+
+/// Why the terrain of a router can't be reshaped.
+#[derive(Error, Debug)]
+pub enum InvalidTerrainShape {
+    #[error("The {0} of the terrain shape isn't a finite number")]
+    NotFinite(&'static str),
+    #[error("The router has no {0} spline to reshape")]
+    MissingSpline(&'static str),
+}
+
+/// `base` with its terrain reshaped like `shape` says, or `base` itself if `shape` is vanilla's.
+///
+/// The splines are reshaped wherever the router reads them, so that the biomes and the surface
+/// estimates follow the reshaped terrain.
+pub fn reshape_terrain(
+    base: &BaseNoiseRouters,
+    shape: &TerrainShape,
+) -> Result<BaseNoiseRouters, InvalidTerrainShape> {
+    for (name, value) in [
+        ("factor", shape.factor),
+        ("offset", shape.offset),
+        ("jaggedness", shape.jaggedness),
+    ] {
+        if !value.is_finite() {
+            return Err(InvalidTerrainShape::NotFinite(name));
+        }
+    }
+    if *shape == TerrainShape::default() {
+        return Ok(base.clone());
+    }
+
+    let stack = &*base.noise.full_component_stack;
+    let offset = spline_below(stack, base.noise.depth)
+        .ok_or(InvalidTerrainShape::MissingSpline("offset"))?;
+    // The jaggedness is multiplied with the jagged noise
+    let (peaks, jaggedness) = multiplications(stack)
+        .find_map(|(index, argument1, argument2)| {
+            let jaggedness = if is_jagged_noise(stack, argument2) {
+                argument1
+            } else if is_jagged_noise(stack, argument1) {
+                argument2
+            } else {
+                return None;
+            };
+            Some((index, spline_below(stack, jaggedness)?))
+        })
+        .ok_or(InvalidTerrainShape::MissingSpline("jaggedness"))?;
+    // The peaks are added to the depth, and the sum is multiplied with the factor
+    let factor = multiplications(stack)
+        .find_map(|(_, argument1, argument2)| {
+            let reads_peaks = |index: usize| {
+                matches!(
+                    stack[index],
+                    BaseNoiseFunctionComponent::Binary {
+                        argument1_index,
+                        argument2_index,
+                        ..
+                    } if argument1_index == peaks || argument2_index == peaks
+                )
+            };
+            if reads_peaks(argument1) {
+                spline_below(stack, argument2)
+            } else if reads_peaks(argument2) {
+                spline_below(stack, argument1)
+            } else {
+                None
+            }
+        })
+        .ok_or(InvalidTerrainShape::MissingSpline("factor"))?;
+
+    let spline = |index: usize| match &stack[index] {
+        BaseNoiseFunctionComponent::Spline { spline } => spline,
+        _ => unreachable!("only splines are found"),
+    };
+    let knobs: Vec<_> = [
+        (spline(offset), 1.0, shape.offset),
+        (spline(factor), shape.factor, 0.0),
+        (spline(jaggedness), shape.jaggedness, 0.0),
+    ]
+    .into_iter()
+    .filter(|&(_, scale, shift)| scale != 1.0 || shift != 0.0)
+    .collect();
+    let reshape =
+        |stack: &[BaseNoiseFunctionComponent]| -> Cow<'static, [BaseNoiseFunctionComponent]> {
+            stack
+                .iter()
+                .map(|component| match component {
+                    BaseNoiseFunctionComponent::Spline { spline } => knobs
+                        .iter()
+                        .find(|(original, ..)| same_points(original, spline))
+                        .map_or_else(
+                            || component.clone(),
+                            |&(_, scale, shift)| BaseNoiseFunctionComponent::Spline {
+                                spline: map_spline(spline, scale, shift),
+                            },
+                        ),
+                    _ => component.clone(),
+                })
+                .collect()
+        };
+
+    let mut reshaped = base.clone();
+    reshaped.noise.full_component_stack = reshape(&base.noise.full_component_stack);
+    reshaped.surface_estimator.full_component_stack =
+        reshape(&base.surface_estimator.full_component_stack);
+    reshaped.multi_noise.full_component_stack = reshape(&base.multi_noise.full_component_stack);
+    Ok(reshaped)
+}
+
+/// The components of `stack` multiplying two others, along with the ones they multiply.
+fn multiplications(
+    stack: &[BaseNoiseFunctionComponent],
+) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+    stack
+        .iter()
+        .enumerate()
+        .filter_map(|(index, component)| match component {
+            BaseNoiseFunctionComponent::Binary {
+                argument1_index,
+                argument2_index,
+                data,
+            } if matches!(data.operation, BinaryOperation::Mul) => {
+                Some((index, *argument1_index, *argument2_index))
+            }
+            _ => None,
+        })
+}
+
+fn is_jagged_noise(stack: &[BaseNoiseFunctionComponent], index: usize) -> bool {
+    let BaseNoiseFunctionComponent::Unary { input_index, .. } = stack[index] else {
+        return false;
+    };
+    matches!(
+        stack[input_index],
+        BaseNoiseFunctionComponent::Noise { ref data } if data.noise_id == "jagged"
+    )
+}
+
+/// The spline the component at `index` passes on, through caches, constant offsets and the
+/// blending with old chunks, which only reads fixed values besides the spline.
+fn spline_below(stack: &[BaseNoiseFunctionComponent], index: usize) -> Option<usize> {
+    match stack.get(index)? {
+        BaseNoiseFunctionComponent::Spline { .. } => Some(index),
+        BaseNoiseFunctionComponent::Wrapper { input_index, .. }
+        | BaseNoiseFunctionComponent::Linear { input_index, .. } => {
+            spline_below(stack, *input_index)
+        }
+        BaseNoiseFunctionComponent::Binary {
+            argument1_index,
+            argument2_index,
+            ..
+        } => match (
+            spline_below(stack, *argument1_index),
+            spline_below(stack, *argument2_index),
+        ) {
+            (Some(spline), None) | (None, Some(spline)) => Some(spline),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether the splines have the same points. The components they read aren't compared, since
+/// each stack keeps them at its own indices.
+fn same_points(a: &SplineRepr, b: &SplineRepr) -> bool {
+    match (a, b) {
+        (SplineRepr::Fixed { value: a }, SplineRepr::Fixed { value: b }) => a == b,
+        (SplineRepr::Standard { points: a, .. }, SplineRepr::Standard { points: b, .. }) => {
+            a.len() == b.len()
+                && a.iter().zip(b.iter()).all(|(a, b)| {
+                    a.location == b.location
+                        && a.derivative == b.derivative
+                        && same_points(&a.value, &b.value)
+                })
+        }
+        _ => false,
+    }
+}
+
+/// `spline` multiplied with `scale`, then moved by `shift`.
+fn map_spline(spline: &SplineRepr, scale: f32, shift: f32) -> SplineRepr {
+    match spline {
+        SplineRepr::Fixed { value } => SplineRepr::Fixed {
+            value: value * scale + shift,
+        },
+        SplineRepr::Standard {
+            location_function_index,
+            points,
+        } => SplineRepr::Standard {
+            location_function_index: *location_function_index,
+            points: points
+                .iter()
+                .map(|point| SplinePoint {
+                    location: point.location,
+                    value: map_spline(&point.value, scale, shift),
+                    derivative: point.derivative * scale,
+                })
+                .collect(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::ptr;
+
+    use pumpkin_config::chunk::TerrainShape;
+    use pumpkin_data::noise_router::{
+        BaseNoiseRouters, NETHER_BASE_NOISE_ROUTER, OVERWORLD_BASE_NOISE_ROUTER,
+    };
+    use pumpkin_util::math::vector2::Vector2;
+
+    use super::{InvalidTerrainShape, reshape_terrain};
+    use crate::generation::{
+        GlobalRandomConfig,
+        noise::router::proto_noise_router::ProtoNoiseRouters,
+        positions::chunk_pos::{start_block_x, start_block_z},
+        proto_chunk::{ProtoChunk, TerrainCache},
+        settings::{GENERATION_SETTINGS, GeneratorSetting},
+    };
+
+    /// The height of every column of the chunk at `pos` on seed 0, by x and then z.
+    fn heights(router: &BaseNoiseRouters, pos: Vector2<i32>) -> Vec<i32> {
+        let random_config = GlobalRandomConfig::new(0, false);
+        let terrain_cache = TerrainCache::from_random(&random_config);
+        let router = ProtoNoiseRouters::generate(router, &random_config);
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            pos,
+            &router,
+            &random_config,
+            settings,
+            &terrain_cache,
+            settings.default_block.get_state(),
+        );
+        chunk.populate_noise();
+        let mut heights = Vec::new();
+        for x in start_block_x(&pos)..start_block_x(&pos) + 16 {
+            for z in start_block_z(&pos)..start_block_z(&pos) + 16 {
+                heights.push(chunk.top_block_height_exclusive(&Vector2::new(x, z)));
+            }
+        }
+        heights
+    }
+
+    /// How far the heights of neighboring columns are apart, all summed up.
+    fn roughness(heights: &[i32]) -> i32 {
+        let mut roughness = 0;
+        for x in 0..16 {
+            for z in 0..16 {
+                let height = heights[x * 16 + z];
+                if x < 15 {
+                    roughness += (height - heights[(x + 1) * 16 + z]).abs();
+                }
+                if z < 15 {
+                    roughness += (height - heights[x * 16 + z + 1]).abs();
+                }
+            }
+        }
+        roughness
+    }
+
+    #[test]
+    fn vanilla_shape_keeps_the_router() {
+        let router =
+            reshape_terrain(&OVERWORLD_BASE_NOISE_ROUTER, &TerrainShape::default()).unwrap();
+        assert!(ptr::eq(
+            &*router.noise.full_component_stack,
+            &*OVERWORLD_BASE_NOISE_ROUTER.noise.full_component_stack
+        ));
+
+        let shape = TerrainShape {
+            offset: f32::NAN,
+            ..Default::default()
+        };
+        assert!(matches!(
+            reshape_terrain(&OVERWORLD_BASE_NOISE_ROUTER, &shape),
+            Err(InvalidTerrainShape::NotFinite("offset"))
+        ));
+        // The nether isn't shaped by splines
+        let shape = TerrainShape {
+            factor: 2.0,
+            ..Default::default()
+        };
+        assert!(matches!(
+            reshape_terrain(&NETHER_BASE_NOISE_ROUTER, &shape),
+            Err(InvalidTerrainShape::MissingSpline(_))
+        ));
+    }
+
+    #[test]
+    fn lower_jaggedness_smooths_the_peaks() {
+        // Jagged peaks on seed 0
+        let pos = Vector2::new(93, 219);
+        let vanilla = heights(&OVERWORLD_BASE_NOISE_ROUTER, pos);
+
+        let smooth = TerrainShape {
+            jaggedness: 0.0,
+            ..Default::default()
+        };
+        let smooth = heights(
+            &reshape_terrain(&OVERWORLD_BASE_NOISE_ROUTER, &smooth).unwrap(),
+            pos,
+        );
+        assert!(roughness(&smooth) < roughness(&vanilla) / 2);
+        assert!(smooth.iter().max() < vanilla.iter().max());
+
+        let raised = TerrainShape {
+            offset: 0.3,
+            ..Default::default()
+        };
+        let raised = heights(
+            &reshape_terrain(&OVERWORLD_BASE_NOISE_ROUTER, &raised).unwrap(),
+            pos,
+        );
+        assert!(
+            raised
+                .iter()
+                .zip(&vanilla)
+                .all(|(raised, vanilla)| raised >= vanilla)
+        );
+    }
+}
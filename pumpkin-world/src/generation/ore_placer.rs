@@ -0,0 +1,257 @@
+use std::ops::RangeInclusive;
+
+use pumpkin_data::{Block, BlockState};
+use serde_json::json;
+
+use crate::block::BlockStateCodec;
+
+use super::feature::placed_features::{NamedPlacedFeature, PlacedFeature};
+
+/// An ore scattered through the ground of every chunk in blobs, replacing one kind of block. It
+/// is placed as an `ore` feature, like vanilla's ores.
+#[derive(Clone, Debug)]
+pub struct OreConfig {
+    pub ore: &'static BlockState,
+    /// The block the ore grows in. Every other block is left alone.
+    pub target: &'static BlockState,
+    pub veins_per_chunk: u32,
+    /// How many blocks a single vein replaces at most.
+    pub vein_size: u32,
+    /// The heights the veins are centered on, picked uniformly.
+    pub y_range: RangeInclusive<i32>,
+}
+
+impl OreConfig {
+    pub const fn new(
+        ore: &'static BlockState,
+        target: &'static BlockState,
+        veins_per_chunk: u32,
+        vein_size: u32,
+        y_range: RangeInclusive<i32>,
+    ) -> Self {
+        Self {
+            ore,
+            target,
+            veins_per_chunk,
+            vein_size,
+            y_range,
+        }
+    }
+
+    /// The placed feature scattering the veins, named after the ore and its target.
+    pub fn placed_feature(&self) -> NamedPlacedFeature {
+        let ore = Block::from_state_id(self.ore.id);
+        let target = Block::from_state_id(self.target.id);
+        let feature: PlacedFeature = serde_json::from_value(json!({
+            "feature": {
+                "type": "minecraft:ore",
+                "config": {
+                    "size": self.vein_size,
+                    "discard_chance_on_air_exposure": 0.0,
+                    "targets": [{
+                        "target": {
+                            "predicate_type": "minecraft:block_match",
+                            "block": format!("minecraft:{}", target.name),
+                        },
+                        "state": BlockStateCodec::from(self.ore),
+                    }],
+                },
+            },
+            "placement": [
+                { "type": "minecraft:count", "count": self.veins_per_chunk },
+                { "type": "minecraft:in_square" },
+                {
+                    "type": "minecraft:height_range",
+                    "height": {
+                        "type": "minecraft:uniform",
+                        "min_inclusive": { "absolute": self.y_range.start() },
+                        "max_inclusive": { "absolute": self.y_range.end() },
+                    },
+                },
+            ],
+        }))
+        .expect("ore features are valid placed features");
+        NamedPlacedFeature::new(format!("{}_in_{}", ore.name, target.name), feature)
+    }
+}
+
+/// Vanilla's placed features of coal in stone and deepslate.
+pub const COAL_ORES: [&str; 2] = ["ore_coal_upper", "ore_coal_lower"];
+
+/// Vanilla's placed features of iron in stone and deepslate.
+pub const IRON_ORES: [&str; 3] = ["ore_iron_upper", "ore_iron_middle", "ore_iron_small"];
+
+/// Vanilla's placed features of diamonds in stone and deepslate.
+pub const DIAMOND_ORES: [&str; 4] = [
+    "ore_diamond",
+    "ore_diamond_medium",
+    "ore_diamond_large",
+    "ore_diamond_buried",
+];
+
+/// Scatters a list of ore features through chunks whose terrain is complete, on top of the ones
+/// their biomes list.
+#[derive(Clone)]
+pub struct OrePlacer {
+    ores: Vec<NamedPlacedFeature>,
+}
+
+impl OrePlacer {
+    /// A placer without any ores, for generators whose biomes already place vanilla's.
+    pub fn empty() -> Self {
+        Self { ores: Vec::new() }
+    }
+
+    /// A placer for vanilla's coal, iron and diamonds, in the biomes listing them.
+    pub fn vanilla() -> Self {
+        let mut placer = Self::empty();
+        for name in COAL_ORES.iter().chain(&IRON_ORES).chain(&DIAMOND_ORES) {
+            placer.register_feature(
+                NamedPlacedFeature::vanilla(name).expect("vanilla has its own ores"),
+            );
+        }
+        placer
+    }
+
+    /// Adds `ore` to the ores placed in every chunk. Ores are placed in the order they were
+    /// registered, so an earlier ore can't grow into a later one.
+    pub fn register(&mut self, ore: OreConfig) {
+        self.register_feature(ore.placed_feature());
+    }
+
+    /// Like [`OrePlacer::register`], for an ore feature of a datapack.
+    pub fn register_feature(&mut self, ore: NamedPlacedFeature) {
+        self.ores.push(ore);
+    }
+
+    /// The ores in the order they are placed, in the step vanilla places its ores in.
+    pub fn ores(&self) -> &[NamedPlacedFeature] {
+        &self.ores
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, sync::LazyLock};
+
+    use pumpkin_data::{Block, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+    use super::{OreConfig, OrePlacer};
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            feature::decoration::DecorationStep,
+            generator::tests::BlockRegistry,
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            proto_chunk_region::ProtoChunkRegion,
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    /// Places the ores of `placer` into the terrain of every chunk in the square of `radius`
+    /// around the origin, returning how many blocks of `ores` they placed and the blocks those
+    /// replaced.
+    fn count_ores(
+        placer: &OrePlacer,
+        radius: i32,
+        ores: &[&Block],
+    ) -> (usize, HashSet<&'static str>) {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut count = 0;
+        let mut replaced = HashSet::new();
+        for chunk_x in -radius..=radius {
+            for chunk_z in -radius..=radius {
+                let mut chunk = ProtoChunk::new(
+                    Vector2::new(chunk_x, chunk_z),
+                    &BASE_NOISE_ROUTER,
+                    &RANDOM_CONFIG,
+                    settings,
+                    &TERRAIN_CACHE,
+                    settings.default_block.get_state(),
+                );
+                chunk.populate_biomes(&Dimension::Overworld);
+                chunk.populate_noise();
+                chunk.build_surface();
+                let blocks = |chunk: &ProtoChunk| {
+                    (-64..320)
+                        .flat_map(|y| (0..16).flat_map(move |z| (0..16).map(move |x| (x, y, z))))
+                        .map(|(x, y, z)| chunk.get_block_state(&Vector3::new(x, y, z)).to_block())
+                        .collect::<Vec<_>>()
+                };
+                let before = blocks(&chunk);
+                ProtoChunkRegion::new(&mut chunk).generate_extra_features(
+                    &BlockRegistry,
+                    DecorationStep::UndergroundOres,
+                    placer.ores(),
+                );
+
+                for (before, after) in before.into_iter().zip(blocks(&chunk)) {
+                    if before != after && ores.contains(&after) {
+                        count += 1;
+                        replaced.insert(before.name);
+                    }
+                }
+            }
+        }
+        (count, replaced)
+    }
+
+    #[test]
+    fn diamonds_at_the_vanilla_rate() {
+        const RADIUS: i32 = 3;
+        let (diamonds, replaced) = count_ores(
+            &OrePlacer::vanilla(),
+            RADIUS,
+            &[&Block::DIAMOND_ORE, &Block::DEEPSLATE_DIAMOND_ORE],
+        );
+        let replaceable = HashSet::from([
+            "stone",
+            "granite",
+            "diorite",
+            "andesite",
+            "deepslate",
+            "tuff",
+        ]);
+        assert!(replaced.is_subset(&replaceable), "{replaced:?}");
+
+        // Vanilla places a couple dozen diamonds per chunk, before it throws away some of those
+        // exposed to air
+        let per_chunk = diamonds as f64 / ((RADIUS * 2 + 1) * (RADIUS * 2 + 1)) as f64;
+        assert!(
+            (15.0..=40.0).contains(&per_chunk),
+            "{per_chunk} diamonds per chunk"
+        );
+    }
+
+    #[test]
+    fn custom_ores() {
+        let gold = OreConfig::new(
+            Block::GOLD_BLOCK.default_state,
+            Block::DEEPSLATE.default_state,
+            8,
+            12,
+            -64..=0,
+        );
+        let mut placer = OrePlacer::empty();
+        placer.register(gold);
+        assert_eq!(placer.ores().len(), 1);
+        assert_eq!(placer.ores()[0].name(), "gold_block_in_deepslate");
+
+        let (gold_blocks, replaced) = count_ores(&placer, 0, &[&Block::GOLD_BLOCK]);
+        assert!(gold_blocks > 0);
+        assert_eq!(replaced, HashSet::from(["deepslate"]));
+    }
+}
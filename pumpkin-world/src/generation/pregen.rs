@@ -0,0 +1,225 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use pumpkin_util::math::vector2::Vector2;
+use tokio::{
+    select,
+    sync::{Semaphore, watch},
+    task::JoinSet,
+};
+
+use crate::{generation::generator::LoadRequest, level::Level};
+
+/// How far a [`Pregenerator`] got.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PregenProgress {
+    /// The chunks that are on disk by now, whether they were generated or found there.
+    pub done: u64,
+    /// How many of the chunks done had to be generated.
+    pub generated: u64,
+    pub total: u64,
+    /// The chunks being generated or written right now.
+    pub in_flight: usize,
+}
+
+impl PregenProgress {
+    /// How much of the region is done, from 0 to 1.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.done as f64 / self.total as f64
+    }
+}
+
+/// Generates every chunk of a square region and writes each of them to disk as soon as it is
+/// done, only keeping the chunks players watch in memory.
+///
+/// The chunks are requested tile by tile outwards from the center, like the generator threads
+/// split requests, and only while fewer than `max_in_flight` of them are being generated or
+/// written; the others aren't even queued yet. The chunks of a tile share the neighbors they are
+/// generated along with, so this bounds the memory used however large the region is. Chunks
+/// that are on disk already are skipped, so a region that was interrupted, e.g. by a restart,
+/// continues where it stopped when it is pregenerated again.
+pub struct Pregenerator {
+    level: Arc<Level>,
+    center: Vector2<i32>,
+    /// Every chunk of the region; cancelling it stops the pregeneration.
+    request: LoadRequest,
+    max_in_flight: usize,
+    slots: Arc<Semaphore>,
+    done: AtomicU64,
+    generated: AtomicU64,
+    paused: watch::Sender<bool>,
+}
+
+impl Pregenerator {
+    /// A pregenerator for the chunks of `level` up to `radius` chunks away from `center`, started
+    /// with [`Pregenerator::run`].
+    pub fn new(
+        level: Arc<Level>,
+        center: Vector2<i32>,
+        radius: u32,
+        max_in_flight: NonZeroUsize,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            level,
+            center,
+            request: LoadRequest::new(center, radius + 1),
+            max_in_flight: max_in_flight.get(),
+            slots: Arc::new(Semaphore::new(max_in_flight.get())),
+            done: AtomicU64::new(0),
+            generated: AtomicU64::new(0),
+            paused: watch::Sender::new(false),
+        })
+    }
+
+    pub fn progress(&self) -> PregenProgress {
+        let side = 2 * u64::from(self.request.radius()) - 1;
+        PregenProgress {
+            done: self.done.load(Ordering::Relaxed),
+            generated: self.generated.load(Ordering::Relaxed),
+            total: side * side,
+            in_flight: self.max_in_flight - self.slots.available_permits(),
+        }
+    }
+
+    /// Stops requesting chunks until [`Pregenerator::resume`]. The chunks in flight are still
+    /// finished.
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Stops the pregeneration for good, even while it is paused. The chunks in flight are still
+    /// finished.
+    pub fn cancel(&self) {
+        self.request.cancel();
+        self.resume();
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.request.is_cancelled() || self.level.is_shutting_down.load(Ordering::Relaxed)
+    }
+
+    /// Pregenerates the region, returning once every chunk is on disk or the pregeneration was
+    /// cancelled. If the level shuts down, returns right away, leaving the chunks in flight to be
+    /// saved with the level.
+    pub async fn run(self: Arc<Self>) {
+        let mut paused = self.paused.subscribe();
+        let mut tasks = JoinSet::new();
+        for tile in self.request.tiles() {
+            // A tile larger than the limit still goes through on its own
+            let permits = tile.len().min(self.max_in_flight) as u32;
+            let permit = select! {
+                permit = async {
+                    // Only fails once the pregenerator is dropped
+                    let _ = paused.wait_for(|paused| !paused).await;
+                    self.slots.clone().acquire_many_owned(permits).await
+                } => permit.expect("the slots are never closed"),
+                () = self.level.shutdown_notifier.notified() => return,
+            };
+            if self.is_stopped() {
+                break;
+            }
+            let pregenerator = self.clone();
+            tasks.spawn(async move {
+                let generated = pregenerator
+                    .level
+                    .pregenerate_chunks(pregenerator.center, &tile)
+                    .await;
+                pregenerator
+                    .generated
+                    .fetch_add(generated as u64, Ordering::Relaxed);
+                pregenerator
+                    .done
+                    .fetch_add(tile.len() as u64, Ordering::Relaxed);
+                drop(permit);
+            });
+            while tasks.try_join_next().is_some() {}
+        }
+
+        select! {
+            () = async { while tasks.join_next().await.is_some() {} } => {}
+            () = self.level.shutdown_notifier.notified() => return,
+        }
+        let progress = self.progress();
+        log::info!(
+            "Pregenerated {} of {} chunks around {:?}, the others were generated already",
+            progress.generated,
+            progress.done,
+            self.center,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{num::NonZeroUsize, sync::Arc, time::Duration};
+
+    use pumpkin_util::math::vector2::Vector2;
+    use temp_dir::TempDir;
+
+    use super::Pregenerator;
+    use crate::{
+        dimension::Dimension,
+        generation::{GeneratorKind, generator::tests::BlockRegistry},
+        level::Level,
+    };
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pregenerated_chunks_are_written_and_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let level = Level::with_generator(
+            temp_dir.path().to_path_buf(),
+            Arc::new(BlockRegistry),
+            0,
+            Dimension::Overworld,
+            GeneratorKind::Superflat,
+        )
+        .unwrap();
+        let pregenerator = Pregenerator::new(
+            level.clone(),
+            Vector2::new(5, -2),
+            3,
+            NonZeroUsize::new(4).unwrap(),
+        );
+        pregenerator.pause();
+        let run = tokio::spawn(pregenerator.clone().run());
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(pregenerator.progress().done, 0);
+        pregenerator.resume();
+        run.await.unwrap();
+
+        let progress = pregenerator.progress();
+        assert_eq!(progress.total, 49);
+        assert_eq!(progress.done, 49);
+        assert_eq!(progress.generated, 49);
+        assert_eq!(progress.in_flight, 0);
+        assert_eq!(level.loaded_chunk_count(), 0);
+
+        // Everything is on disk now
+        let again = Pregenerator::new(
+            level.clone(),
+            Vector2::new(5, -2),
+            4,
+            NonZeroUsize::new(4).unwrap(),
+        );
+        again.clone().run().await;
+        assert_eq!(again.progress().done, 81);
+        assert_eq!(again.progress().generated, 81 - 49);
+        level.shutdown().await;
+    }
+}
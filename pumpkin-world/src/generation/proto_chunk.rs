@@ -1,7 +1,7 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::{array, cell::RefCell, collections::BTreeMap, mem};
 
 use async_trait::async_trait;
+use pumpkin_config::chunk::StructureSpacing;
 use pumpkin_data::tag;
 use pumpkin_data::{
     Block, BlockState, block_properties::blocks_movement, chunk::Biome, tag::Taggable,
@@ -9,21 +9,18 @@ use pumpkin_data::{
 use pumpkin_util::{
     HeightMap,
     math::{position::BlockPos, vector2::Vector2, vector3::Vector3},
-    random::{RandomGenerator, get_decorator_seed, xoroshiro128::Xoroshiro},
+    random::{RandomGenerator, get_carver_seed, legacy_rand::LegacyRand},
 };
+use thiserror::Error;
 
 use crate::generation::noise::perlin::DoublePerlinNoiseSampler;
-use crate::generation::structure::placement::StructurePlacementCalculator;
-use crate::generation::structure::structures::StructurePosition;
-use crate::generation::structure::{STRUCTURE_SETS, STRUCTURES, Structure, StructureType};
 use crate::{
     BlockStateId,
     biome::{BiomeSupplier, MultiNoiseBiomeSupplier, end::TheEndBiomeSupplier, hash_seed},
     block::RawBlockState,
-    chunk::CHUNK_AREA,
+    chunk::{CHUNK_AREA, palette::AbstractCube},
     dimension::Dimension,
     generation::{biome, positions::chunk_pos},
-    level::Level,
     world::{BlockAccessor, BlockRegistryExt},
 };
 
@@ -31,8 +28,8 @@ use super::{
     GlobalRandomConfig,
     aquifer_sampler::{FluidLevel, FluidLevelSampler, FluidLevelSamplerImpl},
     biome_coords,
+    carver::{CARVER_RADIUS, CONFIGURED_CARVERS, CarvingContext, CarvingMask},
     chunk_noise::{CHUNK_DIM, ChunkNoiseGenerator, LAVA_BLOCK, WATER_BLOCK},
-    feature::placed_features::PLACED_FEATURES,
     height_limit::HeightLimitView,
     noise::router::{
         multi_noise_sampler::{MultiNoiseSampler, MultiNoiseSamplerBuilderOptions},
@@ -42,13 +39,101 @@ use super::{
         },
     },
     positions::chunk_pos::{start_block_x, start_block_z},
-    section_coords,
-    settings::GenerationSettings,
+    proto_chunk_region::ProtoChunkRegion,
+    settings::{BiomeSource, GenerationSettings},
+    structure::{STRUCTURE_SETS, placement::RingStarts},
     surface::{MaterialRuleContext, estimate_surface_height, terrain::SurfaceTerrainBuilder},
 };
 
 const AIR_BLOCK: Block = Block::AIR;
 
+/// What a [`ProtoChunk`] recording its changes needs to undo them. See
+/// [`ProtoChunk::record_changes`].
+struct ChangeLog {
+    /// The blocks set, by their index in the chunk, with the state they held before.
+    blocks: Vec<(usize, BlockStateId)>,
+    /// The biome cells set, by their index in the chunk, with the biome they held before.
+    biomes: Vec<(usize, &'static Biome)>,
+    heightmaps: [Box<[i16]>; 4],
+    /// How many loot chests were marked before.
+    loot_chests: usize,
+}
+
+/// The blocks, biomes and loot chests changed in the chunk at [`ChunkChanges::chunk_pos`], taken
+/// with [`ProtoChunk::take_changes`] to be made again with [`ProtoChunk::apply_changes`].
+pub struct ChunkChanges {
+    chunk_pos: Vector2<i32>,
+    /// The new state of every block changed, by its index in the chunk.
+    blocks: Vec<(usize, BlockStateId)>,
+    /// The new biome of every cell changed, by its index in the chunk.
+    biomes: Vec<(usize, &'static Biome)>,
+    loot_chests: Vec<(Vector3<i32>, &'static str)>,
+}
+
+impl ChunkChanges {
+    pub fn chunk_pos(&self) -> Vector2<i32> {
+        self.chunk_pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty() && self.biomes.is_empty() && self.loot_chests.is_empty()
+    }
+}
+
+/// How many biome cells past each side of the chunk are kept too. Biome blending picks from the
+/// cells around a block, which at the edges of the chunk belong to its neighbors.
+const BIOME_PADDING: i32 = 1;
+/// How many biome cells the biome map spans along each horizontal axis.
+const PADDED_BIOME_DIM: usize = CHUNK_DIM as usize / 4 + 2 * BIOME_PADDING as usize;
+
+/// How many recycled proto chunks each thread keeps the storage of. Generating a ring of chunks
+/// frees about as many as the next ring needs.
+const MAX_POOLED_STORAGE: usize = 32;
+
+thread_local! {
+    /// The storage of the proto chunks recycled on this thread. See [`ProtoChunk::recycle`].
+    static STORAGE_POOL: RefCell<Vec<ChunkStorage>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The blocks, biomes and heightmaps of a [`ProtoChunk`], which take up most of its memory.
+struct ChunkStorage {
+    blocks: Box<[BlockStateId]>,
+    biomes: Box<[&'static Biome]>,
+    heightmaps: [Box<[i16]>; 4],
+}
+
+impl ChunkStorage {
+    /// Storage for a chunk `height` blocks high, and whether it was recycled rather than newly
+    /// allocated. Recycled storage still holds the chunk it was recycled from.
+    fn take(height: u16) -> (Self, bool) {
+        let blocks = CHUNK_AREA * height as usize;
+        let recycled = STORAGE_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            let index = pool
+                .iter()
+                .position(|storage| storage.blocks.len() == blocks)?;
+            Some(pool.swap_remove(index))
+        });
+        match recycled {
+            Some(storage) => (storage, true),
+            None => {
+                let storage = Self {
+                    blocks: vec![0; blocks].into_boxed_slice(),
+                    biomes: vec![
+                        &Biome::PLAINS;
+                        PADDED_BIOME_DIM
+                            * PADDED_BIOME_DIM
+                            * biome_coords::cells_covering(height as usize)
+                    ]
+                    .into_boxed_slice(),
+                    heightmaps: array::from_fn(|_| vec![i16::MIN; CHUNK_AREA].into_boxed_slice()),
+                };
+                (storage, false)
+            }
+        }
+    }
+}
+
 pub struct StandardChunkFluidLevelSampler {
     top_fluid: FluidLevel,
     bottom_fluid: FluidLevel,
@@ -78,6 +163,31 @@ impl FluidLevelSamplerImpl for StandardChunkFluidLevelSampler {
     }
 }
 
+/// A block or biome cell written to a [`ProtoChunk`] it isn't part of. Writes reaching into the
+/// neighbors go through a [`ProtoChunkRegion`] instead.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("{pos:?} is outside of chunk {chunk_pos:?}")]
+pub struct OutsideChunk {
+    pub chunk_pos: Vector2<i32>,
+    /// In block coordinates for blocks and in biome coordinates for biomes.
+    pub pos: Vector3<i32>,
+}
+
+/// The climate the biome at a position is picked by, as the multi-noise biome source sees it,
+/// e.g. to find out why a biome ended up where it did. See [`ProtoChunk::get_climate_sample`].
+#[derive(Clone, Debug)]
+pub struct ClimateSample {
+    pub temperature: f32,
+    pub humidity: f32,
+    pub continentalness: f32,
+    pub erosion: f32,
+    pub depth: f32,
+    pub weirdness: f32,
+    /// The biome picked for the position, which only follows the climate if the biome source
+    /// is the dimension's.
+    pub biome: &'static Biome,
+}
+
 /// Vanilla Chunk Steps
 ///
 /// 1. empty: The chunk is not yet loaded or generated.
@@ -112,8 +222,22 @@ pub struct ProtoChunk<'a> {
     pub multi_noise_sampler: MultiNoiseSampler<'a>,
     pub surface_height_estimate_sampler: SurfaceHeightEstimateSampler<'a>,
     pub default_block: &'static BlockState,
-    random_config: &'a GlobalRandomConfig,
+    /// See [`ProtoChunk::set_fill_block`].
+    fill_block: &'static BlockState,
+    pub(crate) random_config: &'a GlobalRandomConfig,
     settings: &'a GenerationSettings,
+    /// The top of the oceans, usually the one of the settings. See [`ProtoChunk::with_sea_level`].
+    sea_level: i32,
+    /// See [`ProtoChunk::set_biome_source`].
+    biome_source: BiomeSource,
+    /// See [`ProtoChunk::set_structure_spacing`].
+    structure_spacing: Option<&'a BTreeMap<String, StructureSpacing>>,
+    /// See [`ProtoChunk::mark_loot_chest`].
+    loot_chests: Vec<(Vector3<i32>, &'static str)>,
+    /// See [`ProtoChunk::record_changes`].
+    change_log: Option<ChangeLog>,
+    /// See [`ProtoChunk::set_decoration`].
+    decoration: Vec<ChunkChanges>,
     biome_mixer_seed: i64,
     // These are local positions
     flat_block_map: Box<[BlockStateId]>,
@@ -125,14 +249,14 @@ pub struct ProtoChunk<'a> {
     flat_ocean_floor_height_map: Box<[i16]>,
     pub flat_motion_blocking_height_map: Box<[i16]>,
     pub flat_motion_blocking_no_leaves_height_map: Box<[i16]>,
-    // may want to use chunk status
-    structure_starts: HashMap<Structure, (StructurePosition, StructureType)>,
 }
 
 pub struct TerrainCache {
     pub terrain_builder: SurfaceTerrainBuilder,
     pub surface_noise: DoublePerlinNoiseSampler,
     pub secondary_noise: DoublePerlinNoiseSampler,
+    /// Where the structures of the sets placed in rings start, by the name of the set.
+    pub ring_starts: BTreeMap<String, RingStarts>,
 }
 
 impl TerrainCache {
@@ -142,14 +266,48 @@ impl TerrainCache {
         let terrain_builder = SurfaceTerrainBuilder::new(&mut noise_builder, random);
         let surface_noise = noise_builder.get_noise_sampler_for_id("surface");
         let secondary_noise = noise_builder.get_noise_sampler_for_id("surface_secondary");
+        let ring_starts = STRUCTURE_SETS
+            .iter()
+            .filter_map(|(name, set)| {
+                RingStarts::new(&set.placement, random_config.seed)
+                    .map(|starts| (name.clone(), starts))
+            })
+            .collect();
         Self {
             terrain_builder,
             surface_noise,
             secondary_noise,
+            ring_starts,
+        }
+    }
+
+    /// Throws away everything cached so far and builds the caches for `random_config` instead,
+    /// e.g. when a tool switches to another seed.
+    pub fn reset(&mut self, random_config: &GlobalRandomConfig) {
+        *self = Self::from_random(random_config);
+    }
+
+    pub fn stats(&self) -> TerrainCacheStats {
+        TerrainCacheStats {
+            ring_sets: self.ring_starts.len(),
+            ring_starts: self.ring_starts.values().map(RingStarts::len).sum(),
+            resolved_ring_starts: self.ring_starts.values().map(RingStarts::resolved).sum(),
         }
     }
 }
 
+/// How much a [`TerrainCache`] holds. The noises are built up front; only the structure starts
+/// placed in rings are looked up as chunks need them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TerrainCacheStats {
+    /// The structure sets placed in rings, like the strongholds.
+    pub ring_sets: usize,
+    /// The structures of all of those sets.
+    pub ring_starts: usize,
+    /// The structures whose start chunk was looked up already.
+    pub resolved_ring_starts: usize,
+}
+
 impl<'a> ProtoChunk<'a> {
     pub fn new(
         chunk_pos: Vector2<i32>,
@@ -158,6 +316,28 @@ impl<'a> ProtoChunk<'a> {
         settings: &'a GenerationSettings,
         terrain_cache: &'a TerrainCache,
         default_block: &'static BlockState,
+    ) -> Self {
+        Self::with_sea_level(
+            chunk_pos,
+            base_router,
+            random_config,
+            settings,
+            terrain_cache,
+            default_block,
+            settings.sea_level,
+        )
+    }
+
+    /// Like [`ProtoChunk::new`], but with the oceans and aquifers filled up to `sea_level` instead
+    /// of the sea level of `settings`, e.g. for a custom dimension. The surface follows the water.
+    pub fn with_sea_level(
+        chunk_pos: Vector2<i32>,
+        base_router: &'a ProtoNoiseRouters,
+        random_config: &'a GlobalRandomConfig,
+        settings: &'a GenerationSettings,
+        terrain_cache: &'a TerrainCache,
+        default_block: &'static BlockState,
+        sea_level: i32,
     ) -> Self {
         let generation_shape = &settings.shape;
 
@@ -165,7 +345,7 @@ impl<'a> ProtoChunk<'a> {
 
         let sampler = FluidLevelSampler::Chunk(Box::new(StandardChunkFluidLevelSampler::new(
             FluidLevel::new(
-                settings.sea_level,
+                sea_level,
                 // Block
                 settings.default_fluid.name,
             ),
@@ -214,37 +394,90 @@ impl<'a> ProtoChunk<'a> {
         let surface_height_estimate_sampler =
             SurfaceHeightEstimateSampler::generate(&base_router.surface_estimator, &surface_config);
 
-        let default_heightmap = vec![i16::MIN; CHUNK_AREA].into_boxed_slice();
-        Self {
+        let (storage, recycled) = ChunkStorage::take(height);
+        let [
+            surface,
+            ocean_floor,
+            motion_blocking,
+            motion_blocking_no_leaves,
+        ] = storage.heightmaps;
+        let mut chunk = Self {
             chunk_pos,
             settings,
+            sea_level,
+            biome_source: BiomeSource::Dimension,
+            structure_spacing: None,
+            loot_chests: Vec::new(),
             terrain_cache,
             default_block,
+            fill_block: default_block,
             random_config,
+            change_log: None,
+            decoration: Vec::new(),
             noise_sampler: sampler,
             multi_noise_sampler,
             surface_height_estimate_sampler,
-            flat_block_map: vec![0; CHUNK_AREA * height as usize].into_boxed_slice(),
-            flat_biome_map: vec![
-                &Biome::PLAINS;
-                biome_coords::from_block(CHUNK_DIM as usize)
-                    * biome_coords::from_block(CHUNK_DIM as usize)
-                    * biome_coords::from_block(height as usize)
-            ]
-            .into_boxed_slice(),
+            flat_block_map: storage.blocks,
+            flat_biome_map: storage.biomes,
             biome_mixer_seed: hash_seed(random_config.seed),
-            flat_surface_height_map: default_heightmap.clone(),
-            flat_ocean_floor_height_map: default_heightmap.clone(),
-            flat_motion_blocking_height_map: default_heightmap.clone(),
-            flat_motion_blocking_no_leaves_height_map: default_heightmap,
-            structure_starts: HashMap::new(),
+            flat_surface_height_map: surface,
+            flat_ocean_floor_height_map: ocean_floor,
+            flat_motion_blocking_height_map: motion_blocking,
+            flat_motion_blocking_no_leaves_height_map: motion_blocking_no_leaves,
+        };
+        if recycled {
+            chunk.clear();
+        }
+        chunk
+    }
+
+    /// Throws away the blocks, biomes, heightmaps, loot chests and decoration of the chunk, leaving
+    /// it as empty as a new one.
+    pub fn clear(&mut self) {
+        self.loot_chests.clear();
+        self.change_log = None;
+        self.decoration.clear();
+        self.flat_block_map.fill(0);
+        self.flat_biome_map.fill(&Biome::PLAINS);
+        for heightmap in [
+            &mut self.flat_surface_height_map,
+            &mut self.flat_ocean_floor_height_map,
+            &mut self.flat_motion_blocking_height_map,
+            &mut self.flat_motion_blocking_no_leaves_height_map,
+        ] {
+            heightmap.fill(i16::MIN);
         }
     }
 
+    /// Hands the storage of the chunk to the next proto chunk created on this thread, which
+    /// saves allocating it again. Generating a chunk needs its neighbors too, so pregenerating
+    /// creates and drops a lot of them.
+    pub fn recycle(self) {
+        STORAGE_POOL.with(|pool| {
+            let mut pool = pool.borrow_mut();
+            if pool.len() < MAX_POOLED_STORAGE {
+                pool.push(ChunkStorage {
+                    blocks: self.flat_block_map,
+                    biomes: self.flat_biome_map,
+                    heightmaps: [
+                        self.flat_surface_height_map,
+                        self.flat_ocean_floor_height_map,
+                        self.flat_motion_blocking_height_map,
+                        self.flat_motion_blocking_no_leaves_height_map,
+                    ],
+                });
+            }
+        });
+    }
+
     pub fn generation_settings(&self) -> &GenerationSettings {
         self.settings
     }
 
+    pub fn sea_level(&self) -> i32 {
+        self.sea_level
+    }
+
     fn maybe_update_surface_height_map(&mut self, pos: &Vector3<i32>) {
         let local_x = pos.x & 15;
         let local_z = pos.z & 15;
@@ -349,27 +582,35 @@ impl<'a> ProtoChunk<'a> {
             + local_pos.z as usize
     }
 
+    /// The index of a biome cell relative to the chunk, which may lie up to [`BIOME_PADDING`]
+    /// cells past its sides.
     #[inline]
     fn local_biome_pos_to_biome_index(&self, local_biome_pos: &Vector3<i32>) -> usize {
+        let horizontal = -BIOME_PADDING..biome_coords::from_block(CHUNK_DIM as i32) + BIOME_PADDING;
         #[cfg(debug_assertions)]
         {
-            assert!(local_biome_pos.x >= 0 && local_biome_pos.x <= 3);
+            assert!(horizontal.contains(&local_biome_pos.x));
             assert!(
-                local_biome_pos.y < biome_coords::from_chunk(self.height() as i32)
-                    && local_biome_pos.y >= 0,
-                "{} - {} vs {}",
-                0,
-                biome_coords::from_chunk(self.height() as i32),
+                (0..self.biome_height()).contains(&local_biome_pos.y),
+                "0 - {} vs {}",
+                self.biome_height(),
                 local_biome_pos.y
             );
-            assert!(local_biome_pos.z >= 0 && local_biome_pos.z <= 3);
+            assert!(horizontal.contains(&local_biome_pos.z));
         }
 
-        biome_coords::from_block(self.noise_sampler.height() as usize)
-            * biome_coords::from_block(CHUNK_DIM as usize)
-            * local_biome_pos.x as usize
-            + biome_coords::from_block(CHUNK_DIM as usize) * local_biome_pos.y as usize
-            + local_biome_pos.z as usize
+        let x = (local_biome_pos.x - horizontal.start) as usize;
+        let z = (local_biome_pos.z - horizontal.start) as usize;
+        self.biome_height() as usize * PADDED_BIOME_DIM * x
+            + PADDED_BIOME_DIM * local_biome_pos.y as usize
+            + z
+    }
+
+    /// The number of layers of biome cells, counting the last one even if the top of the chunk
+    /// cuts through it.
+    #[inline]
+    fn biome_height(&self) -> i32 {
+        biome_coords::cells_covering(self.height() as i32)
     }
 
     #[inline]
@@ -392,6 +633,48 @@ impl<'a> ProtoChunk<'a> {
         RawBlockState(self.flat_block_map[index])
     }
 
+    /// Copies the 16 layers of block states starting at `min_y` into `cube`, the way a
+    /// [`BlockPalette`](crate::chunk::palette::BlockPalette) lays them out. Layers outside of the
+    /// chunk are left as they are.
+    pub fn get_block_states_bulk(&self, min_y: i32, cube: &mut AbstractCube<u16, 16>) {
+        for (y, layer) in (min_y..).zip(cube.iter_mut()) {
+            let local_y = y - self.bottom_y() as i32;
+            if local_y < 0 || local_y >= self.height() as i32 {
+                continue;
+            }
+            for x in 0..CHUNK_DIM as usize {
+                // The blocks along z lie next to each other
+                let start = self.local_pos_to_block_index(&Vector3::new(x as i32, local_y, 0));
+                let column = &self.flat_block_map[start..start + CHUNK_DIM as usize];
+                for (row, state) in layer.iter_mut().zip(column) {
+                    row[x] = *state;
+                }
+            }
+        }
+    }
+
+    /// Like [`ProtoChunk::set_block_state`], but fails instead of wrapping `pos` into the chunk if
+    /// it lies in another one, or ignoring it if it lies above or below the chunk.
+    pub fn try_set_block_state(
+        &mut self,
+        pos: &Vector3<i32>,
+        block_state: &BlockState,
+    ) -> Result<(), OutsideChunk> {
+        let in_chunk = chunk_pos::to_chunk_pos(&Vector2::new(pos.x, pos.z)) == self.chunk_pos
+            && pos.y >= self.bottom_y() as i32
+            && pos.y < self.top_y() as i32;
+        if !in_chunk {
+            return Err(OutsideChunk {
+                chunk_pos: self.chunk_pos,
+                pos: *pos,
+            });
+        }
+        self.set_block_state(pos, block_state);
+        Ok(())
+    }
+
+    /// Sets the block at `pos`, updating the heightmaps. Only the chunk-relative x and z of `pos`
+    /// count, and blocks above or below the chunk are ignored.
     pub fn set_block_state(&mut self, pos: &Vector3<i32>, block_state: &BlockState) {
         let local_pos = Vector3::new(pos.x & 15, pos.y - self.bottom_y() as i32, pos.z & 15);
         if local_pos.y < 0 || local_pos.y >= self.height() as i32 {
@@ -416,6 +699,9 @@ impl<'a> ProtoChunk<'a> {
         }
 
         let index = self.local_pos_to_block_index(&local_pos);
+        if let Some(log) = &mut self.change_log {
+            log.blocks.push((index, self.flat_block_map[index]));
+        }
         self.flat_block_map[index] = block_state.id;
     }
 
@@ -430,58 +716,198 @@ impl<'a> ProtoChunk<'a> {
         self.flat_biome_map[index]
     }
 
-    pub fn populate_biomes(&mut self, dimension: Dimension) {
-        let min_y = self.noise_sampler.min_y();
-        let bottom_section = section_coords::block_to_section(min_y) as i32;
-        let top_section =
-            section_coords::block_to_section(min_y as i32 + self.noise_sampler.height() as i32 - 1);
+    /// Sets the biome of the cell at `global_biome_pos`, which has to be one of the chunk's own.
+    /// The neighbors only see the change once they sample their biomes again.
+    pub fn set_biome(
+        &mut self,
+        global_biome_pos: &Vector3<i32>,
+        biome: &'static Biome,
+    ) -> Result<(), OutsideChunk> {
+        let local_pos = Vector3::new(
+            global_biome_pos.x - biome_coords::from_block(self.start_block_x()),
+            global_biome_pos.y - biome_coords::from_block(self.bottom_y() as i32),
+            global_biome_pos.z - biome_coords::from_block(self.start_block_z()),
+        );
+        let cells = biome_coords::from_block(CHUNK_DIM as i32);
+        if !(0..cells).contains(&local_pos.x)
+            || !(0..self.biome_height()).contains(&local_pos.y)
+            || !(0..cells).contains(&local_pos.z)
+        {
+            return Err(OutsideChunk {
+                chunk_pos: self.chunk_pos,
+                pos: *global_biome_pos,
+            });
+        }
+        let index = self.local_biome_pos_to_biome_index(&local_pos);
+        self.write_biome(index, biome);
+        Ok(())
+    }
+
+    fn write_biome(&mut self, index: usize, biome: &'static Biome) {
+        if let Some(log) = &mut self.change_log {
+            log.biomes.push((index, self.flat_biome_map[index]));
+        }
+        self.flat_biome_map[index] = biome;
+    }
+
+    /// The biome of every cell of the chunk, bottom to top.
+    pub fn biomes(&self) -> impl Iterator<Item = &'static Biome> + '_ {
+        self.flat_biome_map.iter().copied()
+    }
+
+    /// Copies the ids of the 4 layers of biome cells of the section starting at the block `min_y`
+    /// into `cube`, the way a [`BiomePalette`](crate::chunk::palette::BiomePalette) lays them out.
+    /// The section doesn't have to line up with the cells of the chunk: every layer takes the
+    /// cells of the chunk at its bottom, or at the edge of the chunk if it only reaches into it.
+    /// Layers outside of the chunk are left as they are.
+    pub fn get_biomes_bulk(&self, min_y: i32, cube: &mut AbstractCube<u8, 4>) {
+        let bottom_y = self.bottom_y() as i32;
+        let top_y = bottom_y + self.height() as i32;
+        let cell_size = biome_coords::to_block(1);
+        for (y, layer) in (min_y..).step_by(cell_size as usize).zip(cube.iter_mut()) {
+            if y + cell_size <= bottom_y || y >= top_y {
+                continue;
+            }
+            let local_y = biome_coords::from_block(y.clamp(bottom_y, top_y - 1))
+                - biome_coords::from_block(bottom_y);
+            for (z, row) in (0..).zip(layer.iter_mut()) {
+                for (x, id) in (0..).zip(row.iter_mut()) {
+                    let index = self.local_biome_pos_to_biome_index(&Vector3::new(x, local_y, z));
+                    *id = self.flat_biome_map[index].id;
+                }
+            }
+        }
+    }
 
+    /// Like [`ProtoChunk::get_biome`], but also knows the cells of the neighbors right next to the
+    /// chunk. Cells further away are clamped to those.
+    fn get_padded_biome(&self, global_biome_pos: &Vector3<i32>) -> &'static Biome {
+        let last = biome_coords::from_block(CHUNK_DIM as i32) - 1 + BIOME_PADDING;
+        let local_pos = Vector3::new(
+            (global_biome_pos.x - biome_coords::from_block(self.start_block_x()))
+                .clamp(-BIOME_PADDING, last),
+            global_biome_pos.y - biome_coords::from_block(self.bottom_y() as i32),
+            (global_biome_pos.z - biome_coords::from_block(self.start_block_z()))
+                .clamp(-BIOME_PADDING, last),
+        );
+        let index = self.local_biome_pos_to_biome_index(&local_pos);
+        self.flat_biome_map[index]
+    }
+
+    pub fn populate_biomes(&mut self, dimension: &Dimension) {
+        self.populate_scaled_biomes(dimension, 1);
+    }
+
+    /// Like [`ProtoChunk::populate_biomes`], but with the biome regions stretched by
+    /// `horizontal_scale` along both horizontal axes. Only the biomes are stretched; the terrain
+    /// keeps sampling its own coordinates.
+    pub fn populate_scaled_biomes(&mut self, dimension: &Dimension, horizontal_scale: i32) {
         let start_block_x = chunk_pos::start_block_x(&self.chunk_pos);
         let start_block_z = chunk_pos::start_block_z(&self.chunk_pos);
 
         let start_biome_x = biome_coords::from_block(start_block_x);
+        let start_biome_y = biome_coords::from_block(self.noise_sampler.min_y() as i32);
         let start_biome_z = biome_coords::from_block(start_block_z);
 
-        for i in bottom_section..=top_section {
-            let start_block_y = section_coords::section_to_block(i);
-            let start_biome_y = biome_coords::from_block(start_block_y);
-
-            let biomes_per_section = biome_coords::from_block(CHUNK_DIM) as i32;
-            for x in 0..biomes_per_section {
-                for y in 0..biomes_per_section {
-                    for z in 0..biomes_per_section {
-                        let biome_pos =
-                            Vector3::new(start_biome_x + x, start_biome_y + y, start_biome_z + z);
-                        let biome = if dimension == Dimension::End {
-                            TheEndBiomeSupplier::biome(
-                                &biome_pos,
-                                &mut self.multi_noise_sampler,
-                                dimension,
-                            )
-                        } else {
-                            MultiNoiseBiomeSupplier::biome(
-                                &biome_pos,
-                                &mut self.multi_noise_sampler,
-                                dimension,
-                            )
-                        };
-                        //dbg!("Populating biome: {:?} -> {:?}", biome_pos, biome);
-
-                        let local_biome_pos = Vector3 {
-                            x,
-                            // Make the y start from 0
-                            y: start_biome_y + y - biome_coords::from_block(min_y as i32),
-                            z,
-                        };
-                        let index = self.local_biome_pos_to_biome_index(&local_biome_pos);
-
-                        self.flat_biome_map[index] = biome;
-                    }
+        let biomes_per_section = biome_coords::from_block(CHUNK_DIM) as i32;
+        let horizontal = -BIOME_PADDING..biomes_per_section + BIOME_PADDING;
+        for x in horizontal.clone() {
+            // Up to the layer the top of the chunk cuts through
+            for y in 0..self.biome_height() {
+                for z in horizontal.clone() {
+                    let biome_pos = Vector3::new(
+                        (start_biome_x + x).div_euclid(horizontal_scale),
+                        start_biome_y + y,
+                        (start_biome_z + z).div_euclid(horizontal_scale),
+                    );
+                    let biome = self.sample_biome(&biome_pos, dimension);
+
+                    let index = self.local_biome_pos_to_biome_index(&Vector3::new(x, y, z));
+                    self.flat_biome_map[index] = biome;
                 }
             }
         }
     }
 
+    /// Fills the terrain shaped by the noise with `fill_block` instead of the default block. The
+    /// surface rules only cover it if it is the default block as well, and carvers always dig
+    /// through it.
+    pub fn set_fill_block(&mut self, fill_block: &'static BlockState) {
+        self.fill_block = fill_block;
+    }
+
+    pub fn fill_block(&self) -> &'static BlockState {
+        self.fill_block
+    }
+
+    /// Takes the biomes sampled from now on from `biome_source` instead of the dimension.
+    pub fn set_biome_source(&mut self, biome_source: BiomeSource) {
+        self.biome_source = biome_source;
+    }
+
+    /// Spaces out the structures of the sets in `spacing`, by the name of the set, like that
+    /// instead of like vanilla.
+    pub fn set_structure_spacing(&mut self, spacing: &'a BTreeMap<String, StructureSpacing>) {
+        self.structure_spacing = Some(spacing);
+    }
+
+    pub(crate) fn structure_spacing(&self, set_name: &str) -> Option<StructureSpacing> {
+        self.structure_spacing?.get(set_name).copied()
+    }
+
+    /// Marks the chest at `pos` to be filled from `loot_table` once it is first opened, like
+    /// vanilla does for the chests of structures. The finished chunk gets a block entity for it.
+    pub fn mark_loot_chest(&mut self, pos: Vector3<i32>, loot_table: &'static str) {
+        self.loot_chests.push((pos, loot_table));
+    }
+
+    /// The chests marked with [`ProtoChunk::mark_loot_chest`], with their loot tables.
+    pub fn loot_chests(&self) -> &[(Vector3<i32>, &'static str)] {
+        &self.loot_chests
+    }
+
+    pub(crate) fn sample_biome(
+        &mut self,
+        biome_pos: &Vector3<i32>,
+        dimension: &Dimension,
+    ) -> &'static Biome {
+        if let Some(biome) = self.biome_source.biome(biome_pos) {
+            biome
+        } else if dimension.vanilla() == Dimension::End {
+            TheEndBiomeSupplier::biome(biome_pos, &mut self.multi_noise_sampler, dimension)
+        } else {
+            MultiNoiseBiomeSupplier::biome(biome_pos, &mut self.multi_noise_sampler, dimension)
+        }
+    }
+
+    /// Samples the climate of the block at `pos` and the biome it picks, the way
+    /// [`ProtoChunk::populate_biomes`] does for the cell the block is in. The values are the ones
+    /// the biomes are looked up with, rounded to four decimals.
+    pub fn get_climate_sample(
+        &mut self,
+        pos: &Vector3<i32>,
+        dimension: &Dimension,
+    ) -> ClimateSample {
+        let biome_pos = Vector3::new(
+            biome_coords::from_block(pos.x),
+            biome_coords::from_block(pos.y),
+            biome_coords::from_block(pos.z),
+        );
+        let point = self
+            .multi_noise_sampler
+            .sample(biome_pos.x, biome_pos.y, biome_pos.z);
+        let value = |long: i64| long as f32 / 10000.0;
+        ClimateSample {
+            temperature: value(point.temperature),
+            humidity: value(point.humidity),
+            continentalness: value(point.continentalness),
+            erosion: value(point.erosion),
+            depth: value(point.depth),
+            weirdness: value(point.weirdness),
+            biome: self.sample_biome(&biome_pos, dimension),
+        }
+    }
+
     pub fn populate_noise(&mut self) {
         let horizontal_cell_block_count = self.noise_sampler.horizontal_cell_block_count();
         let vertical_cell_block_count = self.noise_sampler.vertical_cell_block_count();
@@ -559,7 +985,7 @@ impl<'a> ProtoChunk<'a> {
                                         Vector3::new(cell_offset_x, cell_offset_y, cell_offset_z),
                                         &mut self.surface_height_estimate_sampler,
                                     )
-                                    .unwrap_or(self.default_block);
+                                    .unwrap_or(self.fill_block);
                                 self.set_block_state(
                                     &Vector3::new(block_x, block_y, block_z),
                                     block_state,
@@ -581,7 +1007,7 @@ impl<'a> ProtoChunk<'a> {
             global_block_pos,
         );
 
-        self.get_biome(&seed_biome_pos)
+        self.get_padded_biome(&seed_biome_pos)
     }
 
     /// Constructs the terrain surface, although "surface" is a misnomer as it also places underground blocks like bedrock and deepslate.
@@ -700,7 +1126,7 @@ impl<'a> ProtoChunk<'a> {
                         z,
                         surface_estimate,
                         top_block,
-                        self.settings.sea_level,
+                        self.sea_level,
                         &self.random_config.base_random_deriver,
                     );
                 }
@@ -708,86 +1134,173 @@ impl<'a> ProtoChunk<'a> {
         }
     }
 
-    /// This generates "Structure Pieces" and "Features" also known as decorations, which include things like trees, grass, ores, and more.
-    /// Essentially, it encompasses everything above the surface or underground. It's crucial that this step is executed after biomes are generated,
-    /// as the decoration directly depends on the biome. Similarly, running this after the surface is built is logical, as it often involves checking block types.
-    /// For example, flowers are typically placed only on grass blocks.
-    ///
-    /// Features are defined across two separate asset files, each serving a distinct purpose:
-    ///
-    /// 1. First, we determine **whether** to generate a feature and **at which block positions** to place it.
-    /// 2. Then, using the second file, we determine **how** to generate the feature.
-    pub fn generate_features_and_structure(
-        &mut self,
-        level: &Arc<Level>,
-        block_registry: &dyn BlockRegistryExt,
-    ) {
-        let chunk_pos = self.chunk_pos;
+    /// Digs the caves and canyons of every carver starting within the [`CARVER_RADIUS`] into
+    /// this chunk. Each chunk only carves its own blocks, but every carver is seeded by the world
+    /// seed and the chunk it starts in, so the tunnels line up across chunk borders.
+    pub fn carve(&mut self, dimension: &Dimension, biome_scale: i32) {
+        let seed = self.random_config.seed;
+        // Vanilla looks up the biome deciding the carvers once and then reuses it for every
+        // chunk around, so the carvers all come from the corner chunk
+        let corner = Vector2::new(
+            self.chunk_pos.x - CARVER_RADIUS,
+            self.chunk_pos.y - CARVER_RADIUS,
+        );
+        let biome_pos = Vector3::new(
+            biome_coords::from_block(chunk_pos::start_block_x(&corner)).div_euclid(biome_scale),
+            0,
+            biome_coords::from_block(chunk_pos::start_block_z(&corner)).div_euclid(biome_scale),
+        );
+        let carvers = self
+            .sample_biome(&biome_pos, dimension)
+            .carvers
+            .iter()
+            .filter_map(|name| {
+                CONFIGURED_CARVERS.get(name.strip_prefix("minecraft:").unwrap_or(name))
+            })
+            .collect::<Vec<_>>();
+        if carvers.is_empty() {
+            return;
+        }
+
         let min_y = self.noise_sampler.min_y();
         let height = self.noise_sampler.height();
+        let mut mask = CarvingMask::new(self.bottom_y() as i32, self.height());
+        let mut context = CarvingContext {
+            chunk: self,
+            mask: &mut mask,
+            min_y,
+            height,
+        };
+        let center = context.chunk.chunk_pos;
+        for offset_x in -CARVER_RADIUS..=CARVER_RADIUS {
+            for offset_z in -CARVER_RADIUS..=CARVER_RADIUS {
+                let start_chunk = Vector2::new(center.x + offset_x, center.y + offset_z);
+                for (index, carver) in carvers.iter().enumerate() {
+                    let carver_seed = seed.wrapping_add(index as u64);
+                    let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(carver_seed));
+                    let carver_seed =
+                        get_carver_seed(&mut random, carver_seed, start_chunk.x, start_chunk.y);
+                    let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(carver_seed));
+                    if carver.should_carve(&mut random) {
+                        carver.carve(&mut context, &mut random, &start_chunk);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns what fills the space a carver dug out at `pos`, like water below the level of
+    /// the aquifer around it, or `None` where the block has to stay.
+    pub fn carved_block_state(&mut self, pos: &Vector3<i32>) -> Option<&'static BlockState> {
+        self.noise_sampler
+            .sample_carved_block_state(pos, &mut self.surface_height_estimate_sampler)
+    }
 
-        let bottom_section = section_coords::block_to_section(min_y) as i32;
-        let block_pos = BlockPos(Vector3::new(
-            section_coords::section_to_block(chunk_pos.x),
-            bottom_section,
-            section_coords::section_to_block(chunk_pos.y),
-        ));
+    /// Decorates this chunk on its own, cutting off the features reaching past its edges. See
+    /// [`ProtoChunkRegion::generate_features_and_structure`] to let them grow into the neighbors.
+    pub fn generate_features_and_structure(&mut self, block_registry: &dyn BlockRegistryExt) {
+        ProtoChunkRegion::new(self).generate_features_and_structure(block_registry);
+    }
 
-        let population_seed =
-            Xoroshiro::get_population_seed(self.random_config.seed, block_pos.0.x, block_pos.0.z);
+    /// Starts recording the blocks, biomes and loot chests changed in the chunk, until
+    /// [`ProtoChunk::take_changes`]. Recording again starts over.
+    pub fn record_changes(&mut self) {
+        self.change_log = Some(ChangeLog {
+            blocks: Vec::new(),
+            biomes: Vec::new(),
+            heightmaps: [
+                self.flat_surface_height_map.clone(),
+                self.flat_ocean_floor_height_map.clone(),
+                self.flat_motion_blocking_height_map.clone(),
+                self.flat_motion_blocking_no_leaves_height_map.clone(),
+            ],
+            loot_chests: self.loot_chests.len(),
+        });
+    }
 
-        for (_structure, (pos, stype)) in self.structure_starts.clone() {
-            dbg!("generating structure");
-            stype.generate(pos.clone(), self);
+    /// Undoes the changes made since [`ProtoChunk::record_changes`] and returns them, leaving the
+    /// chunk as it was when recording started.
+    ///
+    /// # Panics
+    /// If the chunk isn't recording its changes.
+    pub fn take_changes(&mut self) -> ChunkChanges {
+        let log = self
+            .change_log
+            .take()
+            .expect("the chunk records its changes");
+
+        let mut blocks: Vec<_> = log
+            .blocks
+            .iter()
+            .map(|(index, _)| (*index, self.flat_block_map[*index]))
+            .collect();
+        blocks.sort_unstable_by_key(|(index, _)| *index);
+        blocks.dedup_by_key(|(index, _)| *index);
+        let mut biomes: Vec<_> = log
+            .biomes
+            .iter()
+            .map(|(index, _)| (*index, self.flat_biome_map[*index]))
+            .collect();
+        biomes.sort_unstable_by_key(|(index, _)| *index);
+        biomes.dedup_by_key(|(index, _)| *index);
+
+        // The first change of a block or cell saw what it held before, so it is undone last
+        for (index, state) in log.blocks.into_iter().rev() {
+            self.flat_block_map[index] = state;
+        }
+        for (index, biome) in log.biomes.into_iter().rev() {
+            self.flat_biome_map[index] = biome;
+        }
+        [
+            self.flat_surface_height_map,
+            self.flat_ocean_floor_height_map,
+            self.flat_motion_blocking_height_map,
+            self.flat_motion_blocking_no_leaves_height_map,
+        ] = log.heightmaps;
+        let loot_chests = self.loot_chests.split_off(log.loot_chests);
+
+        ChunkChanges {
+            chunk_pos: self.chunk_pos,
+            blocks,
+            biomes,
+            loot_chests,
         }
+    }
 
-        // TODO: This needs to be different depending on what biomes are in the chunk -> affects the
-        // random
-        for (name, feature) in PLACED_FEATURES.iter() {
-            // TODO: Properly set index and step
-            let decorator_seed = get_decorator_seed(population_seed, 0, 0);
-            let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(decorator_seed));
-            feature.generate(
-                self,
-                level,
-                block_registry,
-                min_y,
-                height,
-                name,
-                &mut random,
-                block_pos,
+    /// Makes the `changes` taken from this chunk again, on top of whatever it holds by now.
+    pub fn apply_changes(&mut self, changes: &ChunkChanges) {
+        debug_assert_eq!(changes.chunk_pos, self.chunk_pos);
+        let column_len = self.height() as usize * CHUNK_DIM as usize;
+        for (index, state) in &changes.blocks {
+            let pos = Vector3::new(
+                self.start_block_x() + (index / column_len) as i32,
+                self.bottom_y() as i32 + (index % column_len / CHUNK_DIM as usize) as i32,
+                self.start_block_z() + (index % CHUNK_DIM as usize) as i32,
             );
+            self.set_block_state(&pos, BlockState::from_id(*state));
+        }
+        for (index, biome) in &changes.biomes {
+            self.write_biome(*index, biome);
+        }
+        for (pos, loot_table) in &changes.loot_chests {
+            self.mark_loot_chest(*pos, loot_table);
         }
     }
 
-    pub fn set_structure_starts(&mut self) {
-        for (name, set) in STRUCTURE_SETS.iter() {
-            let calculator = StructurePlacementCalculator {
-                seed: self.random_config.seed as i64,
-            };
-            // for structure in &set.structures {
-            //     let start = self.structure_starts.get(STRUCTURES.get(name).unwrap());
-            // }
-            if !set.placement.should_generate(calculator, self.chunk_pos) {
-                continue; // ??
-            }
+    /// Keeps the changes the decoration of this chunk made to it and to its neighbors, so that
+    /// each of them can be applied once the chunk they belong to is finished.
+    pub fn set_decoration(&mut self, decoration: Vec<ChunkChanges>) {
+        self.decoration = decoration;
+    }
 
-            if set.structures.len() == 1 {
-                let position = set.structures[0]
-                    .structure
-                    .get_structure_position(name, self);
-                if let Some(position) = position
-                    && !position.generator.pieces_positions.is_empty()
-                {
-                    self.structure_starts.insert(
-                        STRUCTURES.get(name).unwrap().clone(),
-                        (position, set.structures[0].structure.clone()),
-                    );
-                }
-                return;
-            }
-            // TODO: handle multiple structures
-        }
+    /// The changes kept with [`ProtoChunk::set_decoration`].
+    pub fn decoration(&self) -> &[ChunkChanges] {
+        &self.decoration
+    }
+
+    /// Takes the changes kept with [`ProtoChunk::set_decoration`], leaving none.
+    pub fn take_decoration(&mut self) -> Vec<ChunkChanges> {
+        mem::take(&mut self.decoration)
     }
 
     fn start_cell_x(&self) -> i32 {
@@ -828,21 +1341,34 @@ impl BlockAccessor for ProtoChunk<'_> {
 
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
     use std::sync::LazyLock;
+    use std::thread;
 
-    use pumpkin_data::noise_router::{OVERWORLD_BASE_NOISE_ROUTER, WrapperType};
-    use pumpkin_util::{math::vector2::Vector2, read_data_from_file};
+    use pumpkin_data::{
+        Block,
+        chunk::Biome,
+        noise_router::{NETHER_BASE_NOISE_ROUTER, OVERWORLD_BASE_NOISE_ROUTER, WrapperType},
+    };
+    use pumpkin_util::{
+        HeightMap,
+        math::{vector2::Vector2, vector3::Vector3},
+        read_data_from_file,
+    };
 
     use crate::{
         dimension::Dimension,
         generation::{
-            GlobalRandomConfig,
+            GlobalRandomConfig, biome, biome_coords,
+            chunk_view::ChunkView,
+            height_limit::HeightLimitView,
             noise::router::{
                 density_function::{NoiseFunctionComponentRange, PassThrough},
                 proto_noise_router::{ProtoNoiseFunctionComponent, ProtoNoiseRouters},
             },
+            positions::chunk_pos::{start_block_x, start_block_z},
             proto_chunk::TerrainCache,
-            settings::{GENERATION_SETTINGS, GeneratorSetting},
+            settings::{GENERATION_SETTINGS, GeneratorSetting, gen_settings_from_dimension},
         },
     };
 
@@ -1314,6 +1840,146 @@ mod test {
             });
     }
 
+    #[test]
+    fn scaled_biomes_stretch_regions() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let new_chunk = |x, z| {
+            ProtoChunk::new(
+                Vector2::new(x, z),
+                &BASE_NOISE_ROUTER,
+                &RANDOM_CONFIG,
+                settings,
+                &TERRAIN_CACHE,
+                settings.default_block.get_state(),
+            )
+        };
+
+        let mut scaled = new_chunk(8, -4);
+        scaled.populate_scaled_biomes(&Dimension::Overworld, 4);
+        let mut unscaled = new_chunk(2, -1);
+        unscaled.populate_biomes(&Dimension::Overworld);
+
+        // Every biome of the scaled chunk comes from the first biome column of the unscaled one
+        for biome_y in -16..80 {
+            let expected = unscaled.get_biome(&Vector3::new(0, biome_y, 0));
+            for x in 0..4 {
+                for z in 0..4 {
+                    assert_eq!(scaled.get_biome(&Vector3::new(x, biome_y, z)), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn caves_continue_across_chunks() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let carve_chunk = |x, z| {
+            let mut chunk = ProtoChunk::new(
+                Vector2::new(x, z),
+                &BASE_NOISE_ROUTER,
+                &RANDOM_CONFIG,
+                settings,
+                &TERRAIN_CACHE,
+                settings.default_block.get_state(),
+            );
+            chunk.populate_biomes(&Dimension::Overworld);
+            chunk.populate_noise();
+            chunk.build_surface();
+            let uncarved = chunk.flat_block_map.clone();
+            chunk.carve(&Dimension::Overworld, 1);
+            // Every block in the chunk that was dug out
+            let carved = chunk
+                .flat_block_map
+                .iter()
+                .zip(&uncarved)
+                .map(|(carved, uncarved)| carved != uncarved && *uncarved == chunk.default_block.id)
+                .collect::<Vec<_>>();
+            (chunk, carved)
+        };
+
+        let (west, west_carved) = carve_chunk(-4, -1);
+        let (east, east_carved) = carve_chunk(-3, -1);
+        // A tunnel crossing the border between both chunks was carved into both of their sides
+        let crossing = (0..west.noise_sampler.height() as i32)
+            .flat_map(|y| (0..16).map(move |z| Vector3::new(0, y, z)))
+            .filter(|pos| {
+                west_carved[west.local_pos_to_block_index(&Vector3::new(15, pos.y, pos.z))]
+                    && east_carved[east.local_pos_to_block_index(pos)]
+            })
+            .count();
+        assert!(crossing > 100);
+
+        // Nothing but the world seed goes into the caves
+        let (again, _) = carve_chunk(-4, -1);
+        assert_eq!(west.flat_block_map, again.flat_block_map);
+    }
+
+    #[test]
+    fn biomes_blend_into_neighbors() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let biome_chunk = |x, z| {
+            let mut chunk = ProtoChunk::new(
+                Vector2::new(x, z),
+                &BASE_NOISE_ROUTER,
+                &RANDOM_CONFIG,
+                settings,
+                &TERRAIN_CACHE,
+                settings.default_block.get_state(),
+            );
+            chunk.populate_biomes(&Dimension::Overworld);
+            chunk
+        };
+
+        // A river runs through this chunk for this seed
+        let chunk = biome_chunk(0, 0);
+        let neighbors = [(-1, 0), (1, 0), (0, -1), (0, 1)].map(|(x, z)| biome_chunk(x, z));
+        let mut from_neighbors = 0;
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in (40..90).step_by(7) {
+                    let pos = Vector3::new(x, y, z);
+                    let cell = biome::get_biome_blend(
+                        chunk.bottom_y(),
+                        chunk.height(),
+                        chunk.biome_mixer_seed,
+                        &pos,
+                    );
+                    let cell_chunk = Vector2::new(cell.x >> 2, cell.z >> 2);
+                    let Some(neighbor) = neighbors.iter().find(|n| n.chunk_pos == cell_chunk)
+                    else {
+                        continue;
+                    };
+                    // The edge of the chunk picks the same biome its neighbor has there
+                    assert_eq!(
+                        chunk.get_biome_for_terrain_gen(&pos),
+                        neighbor.get_biome(&cell)
+                    );
+                    from_neighbors += 1;
+                }
+            }
+        }
+        assert!(from_neighbors > 0);
+    }
+
+    /// Compares the blocks of `chunk` after building its surface with `expected_data`.
+    fn assert_surface_eq(expected_data: Vec<u16>, chunk: &ProtoChunk) {
+        expected_data
+            .into_iter()
+            .zip(&chunk.flat_block_map)
+            .enumerate()
+            .for_each(|(index, (expected, actual))| {
+                if expected != *actual {
+                    panic!("expected {expected}, was {actual} (at {index})");
+                }
+            });
+    }
+
     #[test]
     fn test_no_blend_no_beard_surface() {
         let expected_data: Vec<u16> =
@@ -1330,19 +1996,11 @@ mod test {
             surface_config.default_block.get_state(),
         );
 
-        chunk.populate_biomes(Dimension::Overworld);
+        chunk.populate_biomes(&Dimension::Overworld);
         chunk.populate_noise();
         chunk.build_surface();
 
-        expected_data
-            .into_iter()
-            .zip(chunk.flat_block_map)
-            .enumerate()
-            .for_each(|(index, (expected, actual))| {
-                if expected != actual {
-                    panic!("expected {expected}, was {actual} (at {index})");
-                }
-            });
+        assert_surface_eq(expected_data, &chunk);
     }
 
     #[test]
@@ -1361,19 +2019,11 @@ mod test {
             surface_config.default_block.get_state(),
         );
 
-        chunk.populate_biomes(Dimension::Overworld);
+        chunk.populate_biomes(&Dimension::Overworld);
         chunk.populate_noise();
         chunk.build_surface();
 
-        expected_data
-            .into_iter()
-            .zip(chunk.flat_block_map)
-            .enumerate()
-            .for_each(|(index, (expected, actual))| {
-                if expected != actual {
-                    panic!("expected {expected}, was {actual} (at {index})");
-                }
-            });
+        assert_surface_eq(expected_data, &chunk);
     }
 
     #[test]
@@ -1393,19 +2043,11 @@ mod test {
             surface_config.default_block.get_state(),
         );
 
-        chunk.populate_biomes(Dimension::Overworld);
+        chunk.populate_biomes(&Dimension::Overworld);
         chunk.populate_noise();
         chunk.build_surface();
 
-        expected_data
-            .into_iter()
-            .zip(chunk.flat_block_map)
-            .enumerate()
-            .for_each(|(index, (expected, actual))| {
-                if expected != actual {
-                    panic!("expected {expected}, was {actual} (at {index})");
-                }
-            });
+        assert_surface_eq(expected_data, &chunk);
     }
 
     #[test]
@@ -1426,19 +2068,11 @@ mod test {
             surface_config.default_block.get_state(),
         );
 
-        chunk.populate_biomes(Dimension::Overworld);
+        chunk.populate_biomes(&Dimension::Overworld);
         chunk.populate_noise();
         chunk.build_surface();
 
-        expected_data
-            .into_iter()
-            .zip(chunk.flat_block_map)
-            .enumerate()
-            .for_each(|(index, (expected, actual))| {
-                if expected != actual {
-                    panic!("expected {expected}, was {actual} (at {index})");
-                }
-            });
+        assert_surface_eq(expected_data, &chunk);
     }
 
     #[test]
@@ -1459,19 +2093,11 @@ mod test {
             surface_config.default_block.get_state(),
         );
 
-        chunk.populate_biomes(Dimension::Overworld);
+        chunk.populate_biomes(&Dimension::Overworld);
         chunk.populate_noise();
         chunk.build_surface();
 
-        expected_data
-            .into_iter()
-            .zip(chunk.flat_block_map)
-            .enumerate()
-            .for_each(|(index, (expected, actual))| {
-                if expected != actual {
-                    panic!("expected {expected}, was {actual} (at {index})");
-                }
-            });
+        assert_surface_eq(expected_data, &chunk);
     }
 
     #[test]
@@ -1491,18 +2117,492 @@ mod test {
             surface_config.default_block.get_state(),
         );
 
-        chunk.populate_biomes(Dimension::Overworld);
+        chunk.populate_biomes(&Dimension::Overworld);
         chunk.populate_noise();
         chunk.build_surface();
 
-        expected_data
-            .into_iter()
-            .zip(chunk.flat_block_map)
-            .enumerate()
-            .for_each(|(index, (expected, actual))| {
-                if expected != actual {
-                    panic!("expected {expected}, was {actual} (at {index})");
+        assert_surface_eq(expected_data, &chunk);
+    }
+
+    #[test]
+    fn ocean_floors_follow_the_biome() {
+        let floor = |chunk: &ProtoChunk, biome: &Biome| {
+            let mut blocks = HashMap::new();
+            for x in 0..16 {
+                for z in 0..16 {
+                    let y = chunk.get_top_y(&HeightMap::OceanFloorWg, &Vector2::new(x, z)) - 1;
+                    let pos = Vector3::new(x, y, z);
+                    assert!(
+                        chunk
+                            .get_block_state(&Vector3::new(x, y + 1, z))
+                            .to_state()
+                            .is_liquid()
+                    );
+                    assert_eq!(chunk.get_biome_for_terrain_gen(&pos), biome);
+                    *blocks
+                        .entry(chunk.get_block_state(&pos).to_block())
+                        .or_insert(0) += 1;
                 }
-            });
+            }
+            blocks
+        };
+
+        let warm = floor(&surface_chunk(0, -160), &Biome::WARM_OCEAN);
+        assert!(warm[&Block::SAND] > 200, "{warm:?}");
+        assert!(!warm.contains_key(&Block::GRAVEL));
+        // Deep enough under the water for no dirt to mix in, like it does on shallow floors
+        let cold = floor(&surface_chunk(-160, 100), &Biome::DEEP_COLD_OCEAN);
+        assert_eq!(cold.get(&Block::GRAVEL), Some(&256), "{cold:?}");
+    }
+
+    #[test]
+    fn rivers_are_carved_into_the_land() {
+        let sea_level = gen_settings_from_dimension(&Dimension::Overworld).sea_level;
+        // A river winding from west to east, with dry land north of it
+        for chunk_x in -6..=-2 {
+            let chunk = surface_chunk(chunk_x, -2);
+            let (mut river, mut underwater) = (0, 0);
+            for x in chunk_x * 16..chunk_x * 16 + 16 {
+                for z in -32..-16 {
+                    let y = chunk.get_top_y(&HeightMap::OceanFloorWg, &Vector2::new(x, z)) - 1;
+                    if chunk.get_biome_for_terrain_gen(&Vector3::new(x, y, z)) != &Biome::RIVER {
+                        continue;
+                    }
+                    river += 1;
+                    let water = chunk.get_block_state(&Vector3::new(x, sea_level - 1, z));
+                    if y < sea_level - 1 && water.to_block() == &Block::WATER {
+                        underwater += 1;
+                    }
+                }
+            }
+            assert!(river > 100, "{chunk_x}: {river}");
+            assert!(
+                underwater * 10 > river * 9,
+                "{chunk_x}: {underwater} of {river}"
+            );
+
+            let bank = surface_chunk(chunk_x, -4);
+            for x in chunk_x * 16..chunk_x * 16 + 16 {
+                for z in -64..-48 {
+                    let y = bank.get_top_y(&HeightMap::OceanFloorWg, &Vector2::new(x, z));
+                    assert!(y >= sea_level, "{x} {y} {z}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn climate_sample_explains_the_biome() {
+        let sample_at = |chunk_x: i32, chunk_z: i32| {
+            let mut chunk = surface_chunk(chunk_x, chunk_z);
+            let pos = Vector3::new(chunk_x * 16 + 8, 60, chunk_z * 16 + 8);
+            let sample = chunk.get_climate_sample(&pos, &Dimension::Overworld);
+            let biome_pos = Vector3::new(pos.x >> 2, pos.y >> 2, pos.z >> 2);
+            assert_eq!(sample.biome, chunk.get_biome(&biome_pos));
+            sample
+        };
+
+        // Oceans lie where the continentalness is low, the land where it is high
+        let plains = sample_at(-4, 16);
+        assert_eq!(plains.biome, &Biome::PLAINS);
+        assert!(plains.continentalness > -0.11, "{plains:?}");
+        let ocean = sample_at(0, -160);
+        assert_eq!(ocean.biome, &Biome::WARM_OCEAN);
+        assert!(ocean.continentalness < -0.19, "{ocean:?}");
+        // Warm oceans are the warmest
+        assert!(ocean.temperature > 0.55, "{ocean:?}");
+    }
+
+    #[test]
+    fn recycled_chunks_generate_like_new_ones() {
+        // On a thread of its own, so that nothing was recycled before
+        thread::spawn(|| {
+            let contents = |chunk: &ProtoChunk| {
+                (
+                    chunk.flat_block_map.clone(),
+                    chunk.flat_biome_map.clone(),
+                    chunk.flat_surface_height_map.clone(),
+                    chunk.flat_ocean_floor_height_map.clone(),
+                )
+            };
+            let new = contents(&surface_chunk(-4, 16));
+
+            let ocean = surface_chunk(0, -160);
+            let storage = ocean.flat_block_map.as_ptr();
+            ocean.recycle();
+            let recycled = surface_chunk(-4, 16);
+            assert_eq!(recycled.flat_block_map.as_ptr(), storage);
+            assert!(contents(&recycled) == new);
+        })
+        .join()
+        .unwrap();
+    }
+
+    #[test]
+    fn writes_outside_the_chunk_are_refused() {
+        let mut chunk = surface_chunk(-4, 16);
+        let stone = Block::STONE.default_state;
+        // The north-west corner of the chunk, high up in the air
+        let corner = Vector3::new(-64, 100, 256);
+        assert_eq!(chunk.try_set_block_state(&corner, stone), Ok(()));
+        assert_eq!(chunk.get_block_state(&corner).to_block(), &Block::STONE);
+        // The same column in the chunk east of it
+        let east = Vector3::new(-48, 101, 256);
+        assert!(chunk.try_set_block_state(&east, stone).is_err());
+        assert_eq!(
+            chunk
+                .get_block_state(&Vector3::new(-64, 101, 256))
+                .to_block(),
+            &Block::AIR
+        );
+        assert!(
+            chunk
+                .try_set_block_state(&Vector3::new(-64, 320, 256), stone)
+                .is_err()
+        );
+
+        let cell = Vector3::new(-64 >> 2, 100 >> 2, 256 >> 2);
+        assert_eq!(chunk.set_biome(&cell, &Biome::DESERT), Ok(()));
+        assert_eq!(chunk.get_biome(&cell), &Biome::DESERT);
+        // The cells of the neighbors next to the chunk are only kept for blending
+        let east_cell = Vector3::new(-48 >> 2, 100 >> 2, 256 >> 2);
+        assert!(chunk.set_biome(&east_cell, &Biome::DESERT).is_err());
+    }
+
+    fn surface_chunk(x: i32, z: i32) -> ProtoChunk<'static> {
+        let settings = gen_settings_from_dimension(&Dimension::Overworld);
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(x, z),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        chunk.populate_biomes(&Dimension::Overworld);
+        chunk.populate_noise();
+        chunk.build_surface();
+        chunk
+    }
+
+    /// Checks that every column of `chunk` is topped by a layer of `top` with `filler` right
+    /// below it, returning how deep the layers of `top` go.
+    fn assert_layers(chunk: &ProtoChunk, top: &Block, filler: &Block) -> Vec<i32> {
+        let mut depths = Vec::new();
+        for x in 0..16 {
+            for z in 0..16 {
+                let surface = chunk.top_block_height_exclusive(&Vector2::new(x, z)) - 1;
+                let block_at = |y| chunk.get_block_state(&Vector3::new(x, y, z)).to_block();
+                let mut y = surface;
+                while block_at(y) == top {
+                    y -= 1;
+                }
+                assert!(
+                    y < surface,
+                    "{:?} on top at {x} {z}",
+                    block_at(surface).name
+                );
+                assert_eq!(block_at(y), filler, "below the {} at {x} {z}", top.name);
+                depths.push(surface - y);
+            }
+        }
+        depths
+    }
+
+    #[test]
+    fn deserts_are_sand_over_sandstone() {
+        // A desert chunk for this seed
+        let chunk = surface_chunk(-32, -184);
+        let depths = assert_layers(&chunk, &Block::SAND, &Block::SANDSTONE);
+        // The depth of the sand follows the surface noise
+        assert!(depths.iter().any(|&depth| depth != depths[0]));
+    }
+
+    #[test]
+    fn plains_are_grass_over_dirt() {
+        // A plains chunk for this seed
+        let chunk = surface_chunk(-4, 16);
+        let depths = assert_layers(&chunk, &Block::GRASS_BLOCK, &Block::DIRT);
+        assert!(depths.iter().all(|&depth| depth == 1));
+    }
+
+    #[test]
+    fn windswept_gravelly_hills_are_bare_cliffs() {
+        // A windswept gravelly hills chunk for this seed
+        let mut chunk = surface_chunk(-910, -562);
+        let (start_x, start_z) = (
+            start_block_x(&chunk.chunk_pos),
+            start_block_z(&chunk.chunk_pos),
+        );
+        let mut tops: HashMap<&str, usize> = HashMap::new();
+        let mut heights = Vec::new();
+        for x in start_x..start_x + 16 {
+            for z in start_z..start_z + 16 {
+                let top = chunk.top_block_height_exclusive(&Vector2::new(x, z)) - 1;
+                let pos = Vector3::new(x, top, z);
+                let biome = chunk.get_climate_sample(&pos, &Dimension::Overworld).biome;
+                assert_eq!(biome, &Biome::WINDSWEPT_GRAVELLY_HILLS, "at {x} {z}");
+                *tops
+                    .entry(chunk.get_block_state(&pos).to_block().name)
+                    .or_default() += 1;
+                heights.push(top);
+            }
+        }
+
+        // The surface noise lays the stone bare and covers the rest with gravel, leaving only a
+        // few patches of grass
+        assert!(tops["stone"] > 64, "{tops:?}");
+        assert!(tops["gravel"] > 64, "{tops:?}");
+        assert!(
+            tops.get("grass_block").copied().unwrap_or_default() < 32,
+            "{tops:?}"
+        );
+        // Steep instead of rolling
+        let (min, max) = (heights.iter().min().unwrap(), heights.iter().max().unwrap());
+        assert!(max - min >= 12, "from {min} to {max}");
+    }
+
+    #[test]
+    fn bedrock_thins_out_above_the_floor() {
+        // Vanilla's gradient places bedrock for sure at the bottom and then linearly less likely
+        // until it stops 5 blocks up
+        const GRADIENT: i32 = 5;
+        let mut layers = [0; GRADIENT as usize + 1];
+        let mut columns = 0;
+        for (chunk_x, chunk_z) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            let chunk = surface_chunk(chunk_x, chunk_z);
+            let bottom = chunk.bottom_y() as i32;
+            for x in 0..16 {
+                for z in 0..16 {
+                    for (layer, count) in layers.iter_mut().enumerate() {
+                        let pos = Vector3::new(x, bottom + layer as i32, z);
+                        if chunk.get_block_state(&pos).to_block() == &Block::BEDROCK {
+                            *count += 1;
+                        }
+                    }
+                    columns += 1;
+                }
+            }
+        }
+
+        for (layer, count) in layers.into_iter().enumerate() {
+            let expected = 1.0 - layer as f32 / GRADIENT as f32;
+            let actual = count as f32 / columns as f32;
+            assert!(
+                (actual - expected).abs() < 0.05,
+                "{actual} of layer {layer} is bedrock, expected {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn caves_fill_with_aquifers() {
+        let settings = gen_settings_from_dimension(&Dimension::Overworld);
+        let (mut deep_lava, mut shallow_lava, mut water) = (0, 0, 0);
+        for chunk_x in -3..3 {
+            for chunk_z in -3..3 {
+                let mut chunk = ProtoChunk::new(
+                    Vector2::new(chunk_x * 5, chunk_z * 5),
+                    &BASE_NOISE_ROUTER,
+                    &RANDOM_CONFIG,
+                    settings,
+                    &TERRAIN_CACHE,
+                    settings.default_block.get_state(),
+                );
+                chunk.populate_biomes(&Dimension::Overworld);
+                chunk.populate_noise();
+                for x in 0..16 {
+                    for z in 0..16 {
+                        // Only the fluids below the ground, not those of oceans and rivers
+                        let mut underground = false;
+                        for y in (-54..40).rev() {
+                            let state = chunk.get_block_state(&Vector3::new(x, y, z));
+                            let block = state.to_block();
+                            if !state.to_state().is_air() && !state.to_state().is_liquid() {
+                                underground = true;
+                            } else if underground && block == &Block::LAVA {
+                                if y < -30 {
+                                    deep_lava += 1;
+                                } else if y >= -10 {
+                                    shallow_lava += 1;
+                                }
+                            } else if underground && block == &Block::WATER && y >= 0 {
+                                water += 1;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        assert!(deep_lava > 0, "no lava in the deep caves");
+        // Only aquifers whose fluid stops at y -10 or below can be lava
+        assert_eq!(shallow_lava, 0);
+        assert!(water > 0, "no water in the caves");
+    }
+
+    fn nether_chunk(x: i32, z: i32) -> ProtoChunk<'static> {
+        static NETHER_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> = LazyLock::new(|| {
+            ProtoNoiseRouters::generate(&NETHER_BASE_NOISE_ROUTER, &RANDOM_CONFIG)
+        });
+        let settings = gen_settings_from_dimension(&Dimension::Nether);
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(x, z),
+            &NETHER_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        chunk.populate_biomes(&Dimension::Nether);
+        chunk.populate_noise();
+        chunk.build_surface();
+        chunk
+    }
+
+    #[test]
+    fn nether_is_netherrack_over_a_lava_sea() {
+        // Nether wastes for this seed
+        let chunk = nether_chunk(0, 0);
+        let block_at = |x, y, z| chunk.get_block_state(&Vector3::new(x, y, z)).to_block();
+        let mut lava_sea = 0;
+        let mut netherrack = 0;
+        for x in 0..16 {
+            for z in 0..16 {
+                // Bedrock floor and roof, thinning out over 5 blocks
+                assert_eq!(block_at(x, 0, z), &Block::BEDROCK);
+                assert_eq!(block_at(x, 127, z), &Block::BEDROCK);
+                assert_ne!(block_at(x, 5, z), &Block::BEDROCK);
+                assert_ne!(block_at(x, 122, z), &Block::BEDROCK);
+                for y in 0..128 {
+                    let block = block_at(x, y, z);
+                    assert_ne!(block, &Block::STONE, "at {x} {y} {z}");
+                    if block == &Block::NETHERRACK {
+                        netherrack += 1;
+                    } else if block == &Block::LAVA {
+                        // The open caves fill with lava up to the sea level of 32
+                        assert!(y < 32, "lava above the sea at {x} {y} {z}");
+                        if y == 31 {
+                            lava_sea += 1;
+                        }
+                    }
+                }
+            }
+        }
+        assert!(netherrack > 0);
+        assert!(lava_sea > 0);
+    }
+
+    #[test]
+    fn soul_sand_valleys_are_soul_sand_and_soil() {
+        // A soul sand valley for this seed
+        let chunk = nether_chunk(-40, -52);
+        let (mut soul_sand, mut soul_soil) = (0, 0);
+        for y in 0..128 {
+            for z in 0..16 {
+                for x in 0..16 {
+                    let block = chunk.get_block_state(&Vector3::new(x, y, z)).to_block();
+                    if block == &Block::SOUL_SAND {
+                        soul_sand += 1;
+                    } else if block == &Block::SOUL_SOIL {
+                        soul_soil += 1;
+                    }
+                }
+            }
+        }
+        assert!(soul_sand > 0 && soul_soil > 0, "{soul_sand} {soul_soil}");
+    }
+
+    #[test]
+    fn resetting_the_terrain_cache_forgets_the_old_seed() {
+        let mut cache = TerrainCache::from_random(&RANDOM_CONFIG);
+        let stats = cache.stats();
+        assert!(stats.ring_starts > 0);
+        assert_eq!(stats.resolved_ring_starts, 0);
+        let old_start = cache.ring_starts["strongholds"].start(0, &mut |_| &Biome::PLAINS);
+        assert_eq!(cache.stats().resolved_ring_starts, 1);
+
+        cache.reset(&RANDOM_CONFIG2);
+        let fresh = TerrainCache::from_random(&RANDOM_CONFIG2);
+        assert_eq!(cache.stats(), fresh.stats());
+        let strongholds = &cache.ring_starts["strongholds"];
+        assert_eq!(
+            strongholds.ring_position(0),
+            fresh.ring_starts["strongholds"].ring_position(0)
+        );
+        assert_ne!(strongholds.start(0, &mut |_| &Biome::PLAINS), old_start);
+    }
+
+    #[test]
+    fn chunk_view_samples_the_terrain() {
+        // The way a map renderer outside of the crate would look at a chunk
+        fn render(view: &dyn ChunkView) -> Vec<(Vector3<i32>, &'static Biome)> {
+            let start_x = start_block_x(&view.chunk_pos());
+            let start_z = start_block_z(&view.chunk_pos());
+            let mut pixels = Vec::new();
+            for z in start_z..start_z + 16 {
+                for x in start_x..start_x + 16 {
+                    let top = view.get_top_y(&HeightMap::WorldSurfaceWg, &Vector2::new(x, z));
+                    assert!(top > view.bottom_y() as i32 && top <= view.top_y() as i32);
+                    let surface = Vector3::new(x, top - 1, z);
+                    let biome_pos = Vector3::new(
+                        biome_coords::from_block(x),
+                        biome_coords::from_block(surface.y),
+                        biome_coords::from_block(z),
+                    );
+                    pixels.push((surface, view.get_biome(&biome_pos)));
+                }
+            }
+            pixels
+        }
+
+        let chunk = surface_chunk(-4, 16);
+        let pixels = render(&chunk);
+        for (surface, _) in &pixels {
+            let above = Vector3::new(surface.x, surface.y + 1, surface.z);
+            assert!(!chunk.get_block_state(surface).to_state().is_air());
+            assert!(chunk.get_block_state(&above).to_state().is_air());
+        }
+        // See plains_are_grass_over_dirt
+        assert!(pixels.iter().any(|(_, biome)| *biome == &Biome::PLAINS));
+    }
+
+    #[test]
+    fn bulk_copies_match_single_lookups() {
+        let chunk = surface_chunk(0, 0);
+        for min_y in [-64, 48, 304] {
+            let mut blocks = Box::new([[[u16::MAX; 16]; 16]; 16]);
+            chunk.get_block_states_bulk(min_y, &mut blocks);
+            for (y, layer) in (min_y..).zip(blocks.iter()) {
+                for (z, row) in (0..).zip(layer) {
+                    for (x, state) in (0..).zip(row) {
+                        let pos = Vector3::new(x, y, z);
+                        assert_eq!(*state, chunk.get_block_state(&pos).0, "at {pos:?}");
+                    }
+                }
+            }
+
+            let min_y = biome_coords::from_block(min_y);
+            let mut biomes = Box::new([[[u8::MAX; 4]; 4]; 4]);
+            chunk.get_biomes_bulk(min_y, &mut biomes);
+            for (y, layer) in (min_y..).zip(biomes.iter()) {
+                for (z, row) in (0..).zip(layer) {
+                    for (x, id) in (0..).zip(row) {
+                        let pos = Vector3::new(x, y, z);
+                        assert_eq!(*id, chunk.get_biome(&pos).id, "at {pos:?}");
+                    }
+                }
+            }
+        }
+
+        // Layers above the chunk are left alone
+        let mut blocks = Box::new([[[u16::MAX; 16]; 16]; 16]);
+        chunk.get_block_states_bulk(320, &mut blocks);
+        assert!(
+            blocks
+                .as_flattened()
+                .as_flattened()
+                .iter()
+                .all(|state| *state == u16::MAX)
+        );
     }
 }
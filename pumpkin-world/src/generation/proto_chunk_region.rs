@@ -0,0 +1,294 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use pumpkin_data::{Block, BlockState, chunk::Biome};
+use pumpkin_util::{
+    HeightMap,
+    math::{position::BlockPos, vector2::Vector2, vector3::Vector3},
+};
+
+use crate::{
+    ProtoChunk,
+    block::RawBlockState,
+    world::{BlockAccessor, BlockRegistryExt},
+};
+
+use super::{
+    biome_coords,
+    feature::{
+        decoration::{DecorationStep, FEATURE_ORDER},
+        placed_features::{NamedPlacedFeature, PLACED_FEATURES, PlacedFeature},
+    },
+    height_limit::HeightLimitView,
+    positions::chunk_pos,
+    proto_chunk::ChunkChanges,
+    section_coords,
+};
+
+/// A chunk being decorated together with the neighbors its features may grow into.
+///
+/// Blocks are addressed by their position in the world and end up in whichever chunk of the
+/// region holds them, so a tree next to the edge of the chunk in the middle puts its leaves into
+/// the neighbor. Neighbors that aren't part of the region read as void air and drop the blocks
+/// placed in them, like the space above and below a chunk does, so features reaching into them
+/// may come out differently. The generator always decorates with all 8 neighbors.
+pub struct ProtoChunkRegion<'a, 'b> {
+    /// The position of the chunk in the middle.
+    pub chunk_pos: Vector2<i32>,
+    /// The chunk in the middle and its 8 neighbors, row by row from the north-west.
+    chunks: [Option<&'b mut ProtoChunk<'a>>; 9],
+}
+
+impl<'a, 'b> ProtoChunkRegion<'a, 'b> {
+    /// A region of only `center`, until neighbors are added with
+    /// [`ProtoChunkRegion::with_neighbor`].
+    pub fn new(center: &'b mut ProtoChunk<'a>) -> Self {
+        let chunk_pos = center.chunk_pos;
+        let mut chunks = [const { None }; 9];
+        chunks[4] = Some(center);
+        Self { chunk_pos, chunks }
+    }
+
+    /// Adds `neighbor`, which has to be right next to the chunk in the middle, diagonals
+    /// included. Its terrain should be complete, or it overwrites whatever gets placed in it.
+    pub fn with_neighbor(mut self, neighbor: &'b mut ProtoChunk<'a>) -> Self {
+        let index = self
+            .index(&neighbor.chunk_pos)
+            .filter(|index| *index != 4)
+            .expect("neighbors lie next to the chunk in the middle");
+        self.chunks[index] = Some(neighbor);
+        self
+    }
+
+    pub fn center(&self) -> &ProtoChunk<'a> {
+        self.chunks[4]
+            .as_deref()
+            .expect("the middle is always there")
+    }
+
+    pub fn center_mut(&mut self) -> &mut ProtoChunk<'a> {
+        self.chunks[4]
+            .as_deref_mut()
+            .expect("the middle is always there")
+    }
+
+    /// The chunk of the region holding the block column at `x`, `z`, if there is one.
+    pub fn chunk(&self, x: i32, z: i32) -> Option<&ProtoChunk<'a>> {
+        let index = self.index(&chunk_pos::to_chunk_pos(&Vector2::new(x, z)))?;
+        self.chunks[index].as_deref()
+    }
+
+    fn chunk_mut(&mut self, x: i32, z: i32) -> Option<&mut ProtoChunk<'a>> {
+        let index = self.index(&chunk_pos::to_chunk_pos(&Vector2::new(x, z)))?;
+        self.chunks[index].as_deref_mut()
+    }
+
+    /// Where the chunk at `chunk_pos` goes in [`ProtoChunkRegion::chunks`], if it is close enough.
+    fn index(&self, chunk_pos: &Vector2<i32>) -> Option<usize> {
+        let offset = chunk_pos.sub(&self.chunk_pos);
+        ((-1..=1).contains(&offset.x) && (-1..=1).contains(&offset.y))
+            .then(|| ((offset.y + 1) * 3 + offset.x + 1) as usize)
+    }
+
+    /// Starts recording the changes made to every chunk of the region. See
+    /// [`ProtoChunk::record_changes`].
+    pub fn record_changes(&mut self) {
+        for chunk in self.chunks.iter_mut().flatten() {
+            chunk.record_changes();
+        }
+    }
+
+    /// Undoes the changes made to the chunks of the region since
+    /// [`ProtoChunkRegion::record_changes`], returning them for the chunks that changed.
+    pub fn take_changes(&mut self) -> Vec<ChunkChanges> {
+        self.chunks
+            .iter_mut()
+            .flatten()
+            .map(|chunk| chunk.take_changes())
+            .filter(|changes| !changes.is_empty())
+            .collect()
+    }
+
+    #[inline]
+    pub fn is_air(&self, pos: &Vector3<i32>) -> bool {
+        self.get_block_state(pos).to_state().is_air()
+    }
+
+    pub fn get_block_state(&self, pos: &Vector3<i32>) -> RawBlockState {
+        self.chunk(pos.x, pos.z)
+            .map_or(RawBlockState(Block::VOID_AIR.default_state.id), |chunk| {
+                chunk.get_block_state(pos)
+            })
+    }
+
+    pub fn set_block_state(&mut self, pos: &Vector3<i32>, block_state: &BlockState) {
+        if let Some(chunk) = self.chunk_mut(pos.x, pos.z) {
+            chunk.set_block_state(pos, block_state);
+        }
+    }
+
+    /// Sets the biome of the cell at `global_biome_pos` in whichever chunk of the region it is in,
+    /// ignoring cells outside of the region.
+    pub fn set_biome(&mut self, global_biome_pos: &Vector3<i32>, biome: &'static Biome) {
+        let x = biome_coords::to_block(global_biome_pos.x);
+        let z = biome_coords::to_block(global_biome_pos.z);
+        if let Some(chunk) = self.chunk_mut(x, z) {
+            // Cells above or below the chunks are left alone like blocks are
+            let _ = chunk.set_biome(global_biome_pos, biome);
+        }
+    }
+
+    /// The height of `heightmap` at the block column `pos`, or the bottom of the world outside of
+    /// the region.
+    pub fn get_top_y(&self, heightmap: &HeightMap, pos: &Vector2<i32>) -> i32 {
+        self.chunk(pos.x, pos.y)
+            .map_or(self.bottom_y() as i32, |chunk| {
+                chunk.get_top_y(heightmap, pos)
+            })
+    }
+
+    pub fn top_block_height_exclusive(&self, pos: &Vector2<i32>) -> i32 {
+        self.get_top_y(&HeightMap::WorldSurfaceWg, pos)
+    }
+
+    pub fn ocean_floor_height_exclusive(&self, pos: &Vector2<i32>) -> i32 {
+        self.get_top_y(&HeightMap::OceanFloorWg, pos)
+    }
+
+    pub fn top_motion_blocking_block_height_exclusive(&self, pos: &Vector2<i32>) -> i32 {
+        self.get_top_y(&HeightMap::MotionBlocking, pos)
+    }
+
+    pub fn top_motion_blocking_block_no_leaves_height_exclusive(&self, pos: &Vector2<i32>) -> i32 {
+        self.get_top_y(&HeightMap::MotionBlockingNoLeaves, pos)
+    }
+
+    /// The biome at `global_block_pos`. Outside of the region, the chunk in the middle guesses it
+    /// from its own biomes.
+    pub fn get_biome_for_terrain_gen(&self, global_block_pos: &Vector3<i32>) -> &'static Biome {
+        self.chunk(global_block_pos.x, global_block_pos.z)
+            .unwrap_or_else(|| self.center())
+            .get_biome_for_terrain_gen(global_block_pos)
+    }
+
+    /// This generates "Structure Pieces" and "Features" also known as decorations, which include things like trees, grass, ores, and more.
+    /// Essentially, it encompasses everything above the surface or underground. It's crucial that this step is executed after biomes are generated,
+    /// as the decoration directly depends on the biome. Similarly, running this after the surface is built is logical, as it often involves checking block types.
+    /// For example, flowers are typically placed only on grass blocks.
+    ///
+    /// Features are defined across two separate asset files, each serving a distinct purpose:
+    ///
+    /// 1. First, we determine **whether** to generate a feature and **at which block positions** to place it.
+    /// 2. Then, using the second file, we determine **how** to generate the feature.
+    ///
+    /// Features start in the chunk in the middle and may reach into the rest of the region.
+    pub fn generate_features_and_structure(&mut self, block_registry: &dyn BlockRegistryExt) {
+        // Like vanilla, the features of every biome in the region are placed, not only the ones
+        // of the chunk in the middle; the biome placement filter sorts out the rest
+        let mut biomes = HashSet::new();
+        for chunk in self.chunks.iter().flatten() {
+            biomes.extend(chunk.biomes().map(|biome| biome.id));
+        }
+        let biomes: Vec<_> = biomes.into_iter().filter_map(Biome::from_id).collect();
+
+        for step in DecorationStep::ALL {
+            for (index, name) in FEATURE_ORDER.features(step).iter().enumerate() {
+                let listed = biomes.iter().any(|biome| {
+                    biome.features.get(step.index()).is_some_and(|features| {
+                        features
+                            .iter()
+                            .any(|feature| feature.strip_prefix("minecraft:") == Some(*name))
+                    })
+                });
+                let Some(feature) = PLACED_FEATURES.get(*name).filter(|_| listed) else {
+                    continue;
+                };
+                self.generate_feature(block_registry, step, index, name, feature);
+            }
+        }
+    }
+
+    /// Places `feature`, called `name`, from the chunk in the middle like the `index`th feature
+    /// of `step`, which goes into the seed it is placed with. Returns whether it placed anything.
+    pub fn generate_feature(
+        &mut self,
+        block_registry: &dyn BlockRegistryExt,
+        step: DecorationStep,
+        index: usize,
+        name: &str,
+        feature: &PlacedFeature,
+    ) -> bool {
+        let chunk_pos = self.chunk_pos;
+        let center = self.center();
+        let min_y = center.noise_sampler.min_y();
+        let height = center.noise_sampler.height();
+        let world_seed = center.random_config.seed;
+
+        let bottom_section = section_coords::block_to_section(min_y) as i32;
+        let block_pos = BlockPos(Vector3::new(
+            section_coords::section_to_block(chunk_pos.x),
+            bottom_section,
+            section_coords::section_to_block(chunk_pos.y),
+        ));
+
+        let mut random = step.feature_random(world_seed, &chunk_pos, index);
+        feature.generate(
+            self,
+            block_registry,
+            min_y,
+            height,
+            name,
+            &mut random,
+            block_pos,
+        )
+    }
+
+    /// Places `features` in `step` after the ones the biomes list, seeded like they came right
+    /// after them in [`FEATURE_ORDER`].
+    pub fn generate_extra_features<'f>(
+        &mut self,
+        block_registry: &dyn BlockRegistryExt,
+        step: DecorationStep,
+        features: impl IntoIterator<Item = &'f NamedPlacedFeature>,
+    ) {
+        let first = FEATURE_ORDER.features(step).len();
+        for (index, feature) in features.into_iter().enumerate() {
+            self.generate_feature(
+                block_registry,
+                step,
+                first + index,
+                feature.name(),
+                feature.feature(),
+            );
+        }
+    }
+}
+
+impl HeightLimitView for ProtoChunkRegion<'_, '_> {
+    fn height(&self) -> u16 {
+        self.center().height()
+    }
+
+    fn bottom_y(&self) -> i8 {
+        self.center().bottom_y()
+    }
+}
+
+#[async_trait]
+impl BlockAccessor for ProtoChunkRegion<'_, '_> {
+    async fn get_block(&self, position: &BlockPos) -> &'static Block {
+        self.get_block_state(&position.0).to_block()
+    }
+
+    async fn get_block_state(&self, position: &BlockPos) -> &'static BlockState {
+        self.get_block_state(&position.0).to_state()
+    }
+
+    async fn get_block_and_state(
+        &self,
+        position: &BlockPos,
+    ) -> (&'static Block, &'static BlockState) {
+        let id = self.get_block_state(&position.0);
+        BlockState::from_id_with_block(id.0)
+    }
+}
@@ -6,18 +6,47 @@ use pumpkin_util::{
 #[derive(Clone, Copy)]
 pub struct Seed(pub u64);
 
+impl Seed {
+    /// Parses a seed typed by a player the way vanilla does it: numbers are used as they are,
+    /// anything else is replaced by its Java `String.hashCode()`. A blank seed picks a random one.
+    pub fn from_string(value: &str) -> Self {
+        let trimmed = value.trim();
+        if trimmed.is_empty() {
+            return Seed(LegacyRand::from_seed(get_seed()).next_i64() as u64);
+        }
+
+        // Widened like a Java int to a long, so negative hashes stay negative
+        let i64_value = trimmed
+            .parse::<i64>()
+            .unwrap_or_else(|_| java_string_hash(trimmed) as i64);
+        Seed(i64_value as u64)
+    }
+}
+
 impl From<&str> for Seed {
     fn from(value: &str) -> Self {
-        let trimmed = value.trim();
-        let value = if !trimmed.is_empty() {
-            let i64_value = trimmed
-                .parse::<i64>()
-                .unwrap_or_else(|_| java_string_hash(trimmed) as i64);
-            Some(i64_value as u64)
-        } else {
-            None
-        };
+        Self::from_string(value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Seed;
 
-        Seed(value.unwrap_or_else(|| LegacyRand::from_seed(get_seed()).next_i64() as u64))
+    #[test]
+    fn string_seeds_match_vanilla() {
+        assert_eq!(Seed::from_string("12345").0, 12345);
+        assert_eq!(Seed::from_string(" -42 ").0, -42i64 as u64);
+        assert_eq!(Seed::from_string("hello world").0, 1794106052);
+        // Hashes to `i32::MIN`, which has to be sign extended
+        assert_eq!(
+            Seed::from_string("polygenelubricants").0,
+            i32::MIN as i64 as u64
+        );
+        // Too large for a long, so it is hashed like any other string
+        assert_eq!(
+            Seed::from_string("99999999999999999999").0,
+            pumpkin_util::math::java_string_hash("99999999999999999999") as i64 as u64
+        );
     }
 }
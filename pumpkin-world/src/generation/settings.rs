@@ -1,26 +1,99 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+};
 
-use pumpkin_util::read_data_from_file;
+use pumpkin_data::chunk::Biome;
+use pumpkin_util::{math::vector3::Vector3, read_data_from_file};
 use serde::Deserialize;
 
 use crate::{block::BlockStateCodec, dimension::Dimension};
 
 use super::{biome_coords::to_block, height_limit::HeightLimitView, surface::rule::MaterialRule};
 
-pub static GENERATION_SETTINGS: LazyLock<HashMap<GeneratorSetting, GenerationSettings>> =
-    LazyLock::new(|| read_data_from_file!("../../../assets/chunk_gen_settings.json"));
+/// Shared so that the generators of vanilla and the ones of datapacks hold on to their settings
+/// alike, see [`load_noise_settings`].
+///
+/// [`load_noise_settings`]: super::noise::router::custom_router::load_noise_settings
+pub static GENERATION_SETTINGS: LazyLock<HashMap<GeneratorSetting, Arc<GenerationSettings>>> =
+    LazyLock::new(|| {
+        let settings: HashMap<GeneratorSetting, GenerationSettings> =
+            read_data_from_file!("../../../assets/chunk_gen_settings.json");
+        settings
+            .into_iter()
+            .map(|(setting, settings)| (setting, Arc::new(settings)))
+            .collect()
+    });
 
-pub fn gen_settings_from_dimension(dimension: &Dimension) -> &GenerationSettings {
-    match dimension {
-        Dimension::Overworld => GENERATION_SETTINGS
-            .get(&GeneratorSetting::Overworld)
-            .unwrap(),
-        Dimension::Nether => GENERATION_SETTINGS.get(&GeneratorSetting::Nether).unwrap(),
-        Dimension::End => GENERATION_SETTINGS.get(&GeneratorSetting::End).unwrap(),
+pub fn gen_settings_from_dimension(dimension: &Dimension) -> &'static GenerationSettings {
+    gen_settings_for_world_type(dimension, WorldType::Default)
+}
+
+/// Like [`gen_settings_from_dimension`], but with the overworld settings of `world_type`. The
+/// world type doesn't change the other dimensions.
+pub fn gen_settings_for_world_type(
+    dimension: &Dimension,
+    world_type: WorldType,
+) -> &'static GenerationSettings {
+    GENERATION_SETTINGS
+        .get(&GeneratorSetting::for_world_type(dimension, world_type))
+        .unwrap()
+}
+
+/// The world presets vanilla offers for a default world.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldType {
+    #[default]
+    Default,
+    /// Much taller and steeper terrain.
+    Amplified,
+    LargeBiomes,
+}
+
+impl WorldType {
+    /// How much the overworld biome regions are stretched along both horizontal axes.
+    pub const fn biome_scale(self) -> i32 {
+        match self {
+            WorldType::Default | WorldType::Amplified => 1,
+            WorldType::LargeBiomes => 4,
+        }
     }
 }
 
-#[derive(Deserialize, Hash, PartialEq, Eq)]
+/// Where the biomes of the generated chunks come from.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum BiomeSource {
+    /// The biomes vanilla picks for the dimension.
+    #[default]
+    Dimension,
+    /// The same biome everywhere, like vanilla's single biome worlds. The terrain is still
+    /// shaped like the dimension's, only its surface, features and structures follow the biome.
+    Fixed(&'static Biome),
+    /// The `biomes` tiled in turn along both axes, in square cells of `1 << (scale + 4)` blocks,
+    /// like vanilla's checkerboard biome source. `biomes` must not be empty.
+    Checkerboard {
+        biomes: &'static [&'static Biome],
+        scale: u8,
+    },
+}
+
+impl BiomeSource {
+    /// The biome at `biome_pos` in biome coordinates, or `None` if it's up to the dimension.
+    pub fn biome(&self, biome_pos: &Vector3<i32>) -> Option<&'static Biome> {
+        match *self {
+            Self::Dimension => None,
+            Self::Fixed(biome) => Some(biome),
+            Self::Checkerboard { biomes, scale } => {
+                let shift = scale as i32 + 2;
+                let cell = (biome_pos.x >> shift) + (biome_pos.z >> shift);
+                Some(biomes[cell.rem_euclid(biomes.len() as i32) as usize])
+            }
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, Hash, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum GeneratorSetting {
     Overworld,
@@ -31,6 +104,20 @@ pub enum GeneratorSetting {
     Caves,
     FloatingIslands,
 }
+
+impl GeneratorSetting {
+    /// The settings of `dimension` in a world of `world_type`.
+    pub fn for_world_type(dimension: &Dimension, world_type: WorldType) -> Self {
+        match (dimension, world_type) {
+            (Dimension::Overworld, WorldType::Default) => Self::Overworld,
+            (Dimension::Overworld, WorldType::Amplified) => Self::Amplified,
+            (Dimension::Overworld, WorldType::LargeBiomes) => Self::LargeBiomes,
+            (Dimension::Nether, _) => Self::Nether,
+            (Dimension::End, _) => Self::End,
+            (Dimension::Custom(config), _) => Self::for_world_type(&config.based_on, world_type),
+        }
+    }
+}
 #[derive(Deserialize)]
 pub struct GenerationSettings {
     pub aquifers_enabled: bool,
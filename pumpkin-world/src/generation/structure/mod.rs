@@ -1,16 +1,34 @@
-use std::{collections::HashMap, sync::LazyLock};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::LazyLock,
+};
 
 use pumpkin_data::{chunk::Biome, tag::Taggable};
-use pumpkin_util::read_data_from_file;
+use pumpkin_util::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl, get_carver_seed, legacy_rand::LegacyRand},
+    read_data_from_file,
+};
 use serde::Deserialize;
 
 use crate::{
     ProtoChunk,
-    generation::structure::{
-        placement::StructurePlacement,
-        structures::{
-            StructureGenerator, StructurePosition, buried_treasure::BuriedTreasureGenerator,
-            nether_fortress::NetherFortressGenerator,
+    dimension::Dimension,
+    generation::{
+        biome_coords,
+        height_limit::HeightLimitView,
+        positions::chunk_pos,
+        structure::{
+            placement::{StructurePlacement, StructurePlacementCalculator},
+            structures::{
+                BlockBox, StructureGenerator, StructurePiece,
+                mineshaft::MineshaftType,
+                ocean_monument::OceanMonumentGenerator,
+                ruined_portal::{RuinedPortalSetup, RuinedPortalType},
+                stronghold::StrongholdGenerator,
+                temple::TempleType,
+                village::VillageType,
+            },
         },
     },
 };
@@ -18,64 +36,778 @@ use crate::{
 pub mod placement;
 pub mod structures;
 
+/// How many chunks away from the chunk it starts in a structure can still place pieces.
+pub const STRUCTURE_RADIUS: i32 = 8;
+
 #[derive(Deserialize)]
 pub struct StructureSet {
     pub placement: StructurePlacement,
-    pub structures: Vec<Structures>,
+    pub structures: Vec<WeightedStructure>,
 }
 
 #[derive(Deserialize)]
-pub struct Structures {
-    pub structure: StructureType,
+pub struct WeightedStructure {
+    pub structure: String,
+    pub weight: u32,
 }
 
-#[derive(Deserialize, Clone)]
-pub enum StructureType {
-    #[serde(rename = "minecraft:buried_treasure")]
-    BuriedTreasure(BuriedTreasureGenerator),
-    #[serde(rename = "minecraft:fortress")]
-    NetherFortress(NetherFortressGenerator),
-}
-
-impl StructureType {
-    pub fn get_structure_position(
+impl StructureSet {
+    /// Picks the structure of this set starting in `chunk_pos` like vanilla: weighted by a random
+    /// seeded with the chunk, trying the others if `can_start` rejects the one picked.
+    pub fn pick_structure(
         &self,
-        name: &str,
-        chunk: &ProtoChunk,
-    ) -> Option<StructurePosition> {
-        let position = match self {
-            StructureType::BuriedTreasure(generator) => generator.get_structure_position(chunk),
-            StructureType::NetherFortress(generator) => generator.get_structure_position(chunk),
-        };
-        if let Some(structure) = STRUCTURES.get(name) {
-            let current_biome = chunk.get_biome(&position.position.0);
-            if Biome::get_tag_values(&structure.biomes)
-                .unwrap()
-                .contains(&current_biome.registry_id)
-            {
-                return Some(position);
+        seed: u64,
+        chunk_pos: Vector2<i32>,
+        mut can_start: impl FnMut(&str) -> bool,
+    ) -> Option<&str> {
+        if let [single] = self.structures.as_slice() {
+            return can_start(&single.structure).then_some(single.structure.as_str());
+        }
+
+        let mut random = large_feature_random(seed, chunk_pos);
+        let mut candidates: Vec<_> = self.structures.iter().collect();
+        let mut total_weight: u32 = candidates.iter().map(|entry| entry.weight).sum();
+        while !candidates.is_empty() && total_weight > 0 {
+            let mut target = random.next_bounded_i32(total_weight as i32);
+            let index = candidates
+                .iter()
+                .position(|entry| {
+                    target -= entry.weight as i32;
+                    target < 0
+                })
+                .unwrap_or(candidates.len() - 1);
+            let entry = candidates.remove(index);
+            if can_start(&entry.structure) {
+                return Some(&entry.structure);
             }
+            total_weight -= entry.weight;
         }
         None
     }
+}
+
+/// The random vanilla lays out the structure starting in `chunk_pos` with.
+pub fn large_feature_random(seed: u64, chunk_pos: Vector2<i32>) -> RandomGenerator {
+    let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(seed));
+    let seed = get_carver_seed(&mut random, seed, chunk_pos.x, chunk_pos.y);
+    RandomGenerator::Legacy(LegacyRand::from_seed(seed))
+}
+
+/// The structures the generator can lay out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StructureKind {
+    Village(VillageType),
+    Stronghold,
+    Mineshaft(MineshaftType),
+    Temple(TempleType),
+    OceanMonument,
+    RuinedPortal(RuinedPortalType),
+}
+
+impl StructureKind {
+    /// The kind of the structure called `name` in the structure sets, if it is generated at all.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let kind = match name.strip_prefix("minecraft:").unwrap_or(name) {
+            "village_plains" => Self::Village(VillageType::Plains),
+            "village_desert" => Self::Village(VillageType::Desert),
+            "village_savanna" => Self::Village(VillageType::Savanna),
+            "village_snowy" => Self::Village(VillageType::Snowy),
+            "village_taiga" => Self::Village(VillageType::Taiga),
+            "stronghold" => Self::Stronghold,
+            "mineshaft" => Self::Mineshaft(MineshaftType::Normal),
+            "mineshaft_mesa" => Self::Mineshaft(MineshaftType::Mesa),
+            "desert_pyramid" => Self::Temple(TempleType::Desert),
+            "jungle_pyramid" => Self::Temple(TempleType::Jungle),
+            "monument" => Self::OceanMonument,
+            "ruined_portal" => Self::RuinedPortal(RuinedPortalType::Standard),
+            "ruined_portal_desert" => Self::RuinedPortal(RuinedPortalType::Desert),
+            "ruined_portal_jungle" => Self::RuinedPortal(RuinedPortalType::Jungle),
+            "ruined_portal_swamp" => Self::RuinedPortal(RuinedPortalType::Swamp),
+            "ruined_portal_mountain" => Self::RuinedPortal(RuinedPortalType::Mountain),
+            "ruined_portal_ocean" => Self::RuinedPortal(RuinedPortalType::Ocean),
+            "ruined_portal_nether" => Self::RuinedPortal(RuinedPortalType::Nether),
+            _ => return None,
+        };
+        Some(kind)
+    }
+
+    fn generator(&self) -> &dyn StructureGenerator {
+        match self {
+            Self::Village(village_type) => village_type,
+            Self::Stronghold => &StrongholdGenerator,
+            Self::Mineshaft(mineshaft_type) => mineshaft_type,
+            Self::Temple(temple_type) => temple_type,
+            Self::OceanMonument => &OceanMonumentGenerator,
+            Self::RuinedPortal(portal_type) => portal_type,
+        }
+    }
+}
+
+/// A structure starting in some chunk, laid out as the pieces it places.
+pub struct StructureStart {
+    pub kind: StructureKind,
+    pub chunk_pos: Vector2<i32>,
+    pub pieces: Vec<Box<dyn StructurePiece>>,
+}
+
+/// What a [`StructureGenerator`] needs to lay out the structure starting in `start`.
+pub struct StartContext<'a, 'b> {
+    /// Only samples the noises of the world, which it can do anywhere; its blocks belong to some
+    /// other chunk.
+    chunk: &'a mut ProtoChunk<'b>,
+    dimension: &'a Dimension,
+    biome_scale: i32,
+    pub start: Vector2<i32>,
+    pub random: RandomGenerator,
+}
+
+impl<'a, 'b> StartContext<'a, 'b> {
+    fn new(
+        chunk: &'a mut ProtoChunk<'b>,
+        dimension: &'a Dimension,
+        biome_scale: i32,
+        start: Vector2<i32>,
+    ) -> Self {
+        let random = large_feature_random(chunk.random_config.seed, start);
+        Self {
+            chunk,
+            dimension,
+            biome_scale,
+            start,
+            random,
+        }
+    }
+
+    /// The biome at a block anywhere in the world.
+    pub fn biome(&mut self, pos: &Vector3<i32>) -> &'static Biome {
+        let biome_pos = Vector3::new(
+            biome_coords::from_block(pos.x).div_euclid(self.biome_scale),
+            biome_coords::from_block(pos.y),
+            biome_coords::from_block(pos.z).div_euclid(self.biome_scale),
+        );
+        self.chunk.sample_biome(&biome_pos, self.dimension)
+    }
+
+    /// A rough guess of the surface height at a column anywhere in the world, a few blocks low.
+    pub fn estimate_height(&mut self, x: i32, z: i32) -> i32 {
+        self.chunk
+            .surface_height_estimate_sampler
+            .estimate_height(x, z)
+    }
+
+    pub fn bottom_y(&self) -> i32 {
+        self.chunk.bottom_y() as i32
+    }
+
+    pub fn top_y(&self) -> i32 {
+        self.chunk.top_y() as i32
+    }
+
+    pub fn sea_level(&self) -> i32 {
+        self.chunk.sea_level()
+    }
+
+    /// How far to move a structure reaching from `bottom` to `top` down or up to put its top
+    /// below sea level by at least `penalty`, somewhere between its lowest possible height and
+    /// there, like vanilla.
+    pub fn below_sea_level_shift(&mut self, bottom: i32, top: i32, penalty: i32) -> i32 {
+        let limit = self.sea_level() - penalty;
+        let mut new_top = top - bottom + 1 + self.bottom_y() + 1;
+        if new_top < limit {
+            new_top += self.random.next_bounded_i32(limit - new_top);
+        }
+        new_top - top
+    }
+}
+
+/// The biome at a biome position anywhere in the world, for the placements preferring some biomes.
+pub(crate) fn scaled_biome<'a>(
+    chunk: &'a mut ProtoChunk,
+    dimension: &'a Dimension,
+    biome_scale: i32,
+) -> impl FnMut(&Vector3<i32>) -> &'static Biome + 'a {
+    move |biome_pos| {
+        let biome_pos = Vector3::new(
+            biome_pos.x.div_euclid(biome_scale),
+            biome_pos.y,
+            biome_pos.z.div_euclid(biome_scale),
+        );
+        chunk.sample_biome(&biome_pos, dimension)
+    }
+}
+
+/// The structure of the set called `set_name` starting in `start`, if one starts there, without
+/// laying it out.
+///
+/// `chunk` only samples the noises of the world, so any chunk of the same generator gives the same
+/// answer.
+pub fn start_kind(
+    set_name: &str,
+    chunk: &mut ProtoChunk,
+    dimension: &Dimension,
+    biome_scale: i32,
+    start: Vector2<i32>,
+) -> Option<StructureKind> {
+    let set = STRUCTURE_SETS.get(set_name)?;
+    let seed = chunk.random_config.seed;
+    let ring_starts = chunk.terrain_cache.ring_starts.get(set_name);
+    let spacing = chunk.structure_spacing(set_name);
+    let placed = {
+        let mut biome = scaled_biome(chunk, dimension, biome_scale);
+        let calculator = StructurePlacementCalculator {
+            seed: seed as i64,
+            ring_starts,
+            spacing,
+            biome: &mut biome,
+        };
+        set.placement.should_generate(calculator, start)
+    };
+    if !placed {
+        return None;
+    }
+
+    let mut context = StartContext::new(chunk, dimension, biome_scale, start);
+    let mut kind = None;
+    set.pick_structure(seed, start, |name| {
+        kind = StructureKind::from_name(name).filter(|_| {
+            STRUCTURES
+                .get(name.strip_prefix("minecraft:").unwrap_or(name))
+                .is_some_and(|structure| structure.can_start(&mut context))
+        });
+        kind.is_some()
+    })?;
+    kind
+}
+
+/// Lays out the structure of the set called `set_name` starting in `start`, if one starts there.
+///
+/// `chunk` only samples the noises of the world, so any chunk of the same generator gives the same
+/// start.
+pub fn structure_start(
+    set_name: &str,
+    chunk: &mut ProtoChunk,
+    dimension: &Dimension,
+    biome_scale: i32,
+    start: Vector2<i32>,
+) -> Option<StructureStart> {
+    let kind = start_kind(set_name, chunk, dimension, biome_scale, start)?;
+    let mut context = StartContext::new(chunk, dimension, biome_scale, start);
+    let pieces = kind.generator().create_start(&mut context)?;
+    Some(StructureStart {
+        kind,
+        chunk_pos: start,
+        pieces,
+    })
+}
 
-    pub fn generate(&self, position: StructurePosition, chunk: &mut crate::ProtoChunk) {
-        for pos in position.generator.pieces_positions {
-            match self {
-                StructureType::BuriedTreasure(generator) => generator.generate(pos, chunk),
-                StructureType::NetherFortress(generator) => generator.generate(pos, chunk),
+impl ProtoChunk<'_> {
+    /// Places the pieces of every structure reaching into this chunk, which may start in any
+    /// chunk up to [`STRUCTURE_RADIUS`] away.
+    pub fn place_structures(&mut self, dimension: &Dimension, biome_scale: i32) {
+        let chunk_box = BlockBox::of_chunk(
+            self.chunk_pos,
+            self.bottom_y() as i32,
+            self.top_y() as i32 - 1,
+        );
+        let center = self.chunk_pos;
+        for (name, set) in STRUCTURE_SETS.iter() {
+            if !set
+                .structures
+                .iter()
+                .any(|entry| StructureKind::from_name(&entry.structure).is_some())
+            {
+                continue;
+            }
+            for offset_x in -STRUCTURE_RADIUS..=STRUCTURE_RADIUS {
+                for offset_z in -STRUCTURE_RADIUS..=STRUCTURE_RADIUS {
+                    let start_chunk = Vector2::new(center.x + offset_x, center.y + offset_z);
+                    let Some(start) =
+                        structure_start(name, self, dimension, biome_scale, start_chunk)
+                    else {
+                        continue;
+                    };
+                    for piece in &start.pieces {
+                        if piece.bounding_box().intersects(&chunk_box) {
+                            piece.place(self, &chunk_box);
+                        }
+                    }
+                }
             }
         }
     }
 }
 
-#[derive(Deserialize, Clone, PartialEq, Eq, Hash)]
+#[derive(Deserialize, Clone, PartialEq)]
 pub struct Structure {
     biomes: String,
+    /// The ways a ruined portal can be set into the world; empty for other structures.
+    #[serde(default)]
+    pub setups: Vec<RuinedPortalSetup>,
+}
+
+impl Structure {
+    /// Whether the biome in the middle of the start chunk allows this structure, at about the
+    /// height of its surface.
+    fn can_start(&self, context: &mut StartContext) -> bool {
+        let x = chunk_pos::start_block_x(&context.start) + 8;
+        let z = chunk_pos::start_block_z(&context.start) + 8;
+        let y = context.estimate_height(x, z);
+        let biome = context.biome(&Vector3::new(x, y, z));
+        biome.is_tagged_with(&self.biomes) == Some(true)
+    }
 }
 
 pub static STRUCTURES: LazyLock<HashMap<String, Structure>> =
-    LazyLock::new(|| read_data_from_file!("../../../assets/structure.json"));
+    LazyLock::new(|| read_data_from_file!("../../../../assets/structures.json"));
+
+/// Sorted by name, so that structures overlapping each other are placed in the same order every
+/// time.
+pub static STRUCTURE_SETS: LazyLock<BTreeMap<String, StructureSet>> =
+    LazyLock::new(|| read_data_from_file!("../../../../assets/structure_set.json"));
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashSet,
+        sync::{Arc, LazyLock},
+    };
+
+    use pumpkin_data::{Block, noise_router::OVERWORLD_BASE_NOISE_ROUTER, tag::Taggable};
+    use pumpkin_util::math::{position::BlockPos, vector2::Vector2, vector3::Vector3};
+
+    use super::{
+        STRUCTURE_RADIUS, STRUCTURE_SETS, StructureKind,
+        placement::StructurePlacementType,
+        scaled_biome, structure_start,
+        structures::{
+            mineshaft::MineshaftType, ruined_portal::RuinedPortalType, temple::TempleType,
+            village::VillageType,
+        },
+    };
+    use crate::{
+        ProtoChunk,
+        block::entities::chest::ChestBlockEntity,
+        chunk::ChunkHeightmapType,
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig, Seed,
+            generator::{GeneratorInit, VanillaGenerator, tests::BlockRegistry},
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::TerrainCache,
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    #[test]
+    fn plains_village_has_a_well() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        // Any chunk can lay out the villages of the world
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(0, 0),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        let mut start = |x, z| {
+            structure_start(
+                "villages",
+                &mut chunk,
+                &Dimension::Overworld,
+                1,
+                Vector2::new(x, z),
+            )
+        };
+        // The village of region 0 1 for this seed; the start of region 0 0 is in a forest
+        let village = start(17, 59).unwrap();
+        assert_eq!(village.kind, StructureKind::Village(VillageType::Plains));
+        assert!(start(17, 58).is_none());
+        assert!(start(15, 2).is_none());
+
+        let generator =
+            VanillaGenerator::new(Seed(SEED), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        let chunk = generator.generate_chunk(village.chunk_pos);
+        let block =
+            |x, y, z| Block::from_state_id(chunk.section.get_block_absolute_y(x, y, z).unwrap());
+        // The well is in the middle of the chunk, below a roof. Plants don't block motion, so
+        // they're looked through in case some grew on top.
+        let roof = chunk.heightmap.get_height(
+            ChunkHeightmapType::MotionBlocking,
+            7,
+            7,
+            chunk.section.min_y,
+        );
+        assert_eq!(block(7, roof, 7), &Block::OAK_SLAB);
+        assert_eq!(block(7, roof - 3, 7), &Block::WATER);
+        assert_eq!(block(6, roof - 3, 7), &Block::COBBLESTONE);
+        // Streets lead away from it
+        let street = chunk.heightmap.get_height(
+            ChunkHeightmapType::MotionBlocking,
+            12,
+            7,
+            chunk.section.min_y,
+        );
+        assert_eq!(block(12, street, 7), &Block::DIRT_PATH);
+    }
+
+    #[test]
+    fn stronghold_portal_room_points_to_the_origin() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(0, 0),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        // The second innermost stronghold, moved from chunk 121 52 on its ring to a preferred biome
+        let start_pos = TERRAIN_CACHE.ring_starts["strongholds"]
+            .start(1, &mut scaled_biome(&mut chunk, &Dimension::Overworld, 1));
+        assert_eq!(start_pos, Vector2::new(125, 57));
+        let stronghold = structure_start(
+            "strongholds",
+            &mut chunk,
+            &Dimension::Overworld,
+            1,
+            start_pos,
+        )
+        .unwrap();
+        assert_eq!(stronghold.kind, StructureKind::Stronghold);
+        assert!(
+            structure_start(
+                "strongholds",
+                &mut chunk,
+                &Dimension::Overworld,
+                1,
+                Vector2::new(121, 52)
+            )
+            .is_none()
+        );
+
+        // The rooms lead west from the staircase, towards the origin
+        let staircase = stronghold.pieces[0].bounding_box();
+        let portal_room = stronghold.pieces.last().unwrap().bounding_box();
+        assert!(portal_room.max.x < staircase.min.x);
+        assert!(portal_room.max.y < settings.sea_level - 10);
+
+        let generator =
+            VanillaGenerator::new(Seed(SEED), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        let mut frames = 0;
+        for chunk_x in portal_room.min.x >> 4..=portal_room.max.x >> 4 {
+            for chunk_z in portal_room.min.z >> 4..=portal_room.max.z >> 4 {
+                let chunk = generator.generate_chunk(Vector2::new(chunk_x, chunk_z));
+                for x in 0..16 {
+                    for z in 0..16 {
+                        for y in portal_room.min.y..=portal_room.max.y {
+                            let id = chunk.section.get_block_absolute_y(x, y, z).unwrap();
+                            frames +=
+                                usize::from(Block::from_state_id(id) == &Block::END_PORTAL_FRAME);
+                        }
+                    }
+                }
+            }
+        }
+        assert_eq!(frames, 12);
+    }
+
+    #[test]
+    fn mineshaft_runs_underground() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(0, 0),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        let mut start = || {
+            structure_start(
+                "mineshafts",
+                &mut chunk,
+                &Dimension::Overworld,
+                1,
+                Vector2::new(-13, -7),
+            )
+            .unwrap()
+        };
+        let mineshaft = start();
+        assert_eq!(
+            mineshaft.kind,
+            StructureKind::Mineshaft(MineshaftType::Normal)
+        );
+        let boxes: Vec<_> = mineshaft
+            .pieces
+            .iter()
+            .map(|piece| piece.bounding_box())
+            .collect();
+        // Laid out the same every time
+        let again: Vec<_> = start()
+            .pieces
+            .iter()
+            .map(|piece| piece.bounding_box())
+            .collect();
+        assert_eq!(boxes, again);
+        // Below sea level, and not too far for every chunk it reaches into to place its pieces
+        let (min_x, max_x) = (
+            (-13 - STRUCTURE_RADIUS) * 16,
+            (-13 + STRUCTURE_RADIUS) * 16 + 15,
+        );
+        let (min_z, max_z) = (
+            (-7 - STRUCTURE_RADIUS) * 16,
+            (-7 + STRUCTURE_RADIUS) * 16 + 15,
+        );
+        for bounding_box in &boxes {
+            assert!(bounding_box.max.y < settings.sea_level - 10);
+            assert!(bounding_box.min.x >= min_x && bounding_box.max.x <= max_x);
+            assert!(bounding_box.min.z >= min_z && bounding_box.max.z <= max_z);
+        }
+
+        // The supports and rails of the corridors leading north from the room
+        let generator =
+            VanillaGenerator::new(Seed(SEED), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        let chunk = generator.generate_chunk(Vector2::new(-13, -9));
+        let mut wood = 0;
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in i32::from(settings.shape.min_y)..settings.sea_level - 10 {
+                    let id = chunk.section.get_block_absolute_y(x, y, z).unwrap();
+                    let block = Block::from_state_id(id);
+                    wood += usize::from(
+                        block == &Block::OAK_PLANKS
+                            || block == &Block::OAK_FENCE
+                            || block == &Block::RAIL,
+                    );
+                }
+            }
+        }
+        assert!(wood > 0);
+    }
+
+    #[test]
+    fn desert_pyramid_hides_trapped_chests() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(0, 0),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        // The closest desert pyramid of this seed, reaching into the chunks south and east of it
+        let start_pos = Vector2::new(-10, -178);
+        let pyramid = structure_start(
+            "desert_pyramids",
+            &mut chunk,
+            &Dimension::Overworld,
+            1,
+            start_pos,
+        )
+        .unwrap();
+        assert_eq!(pyramid.kind, StructureKind::Temple(TempleType::Desert));
+
+        let generator =
+            VanillaGenerator::new(Seed(SEED), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        let chunk = generator.generate_chunk(start_pos);
+        let block = |x: i32, y, z: i32| {
+            let id = chunk
+                .section
+                .get_block_absolute_y(x as usize, y, z as usize)
+                .unwrap();
+            Block::from_state_id(id)
+        };
+        // The blue terracotta in the middle of the floor is right above the chamber
+        let (x, y, z) = (0..16)
+            .flat_map(|x| (0..16).map(move |z| (x, z)))
+            .find_map(|(x, z)| {
+                (0..200)
+                    .find(|y| block(x, *y, z) == &Block::BLUE_TERRACOTTA)
+                    .map(|y| (x, y, z))
+            })
+            .unwrap();
+        assert_eq!(block(x + 1, y, z), &Block::ORANGE_TERRACOTTA);
+        assert_eq!(block(x, y - 11, z), &Block::STONE_PRESSURE_PLATE);
+        for (dx, dz) in [(-1, -1), (0, 0), (1, 1)] {
+            assert_eq!(block(x + dx, y - 13, z + dz), &Block::TNT);
+        }
+
+        let mut chests = 0;
+        for (dx, dz) in [(-2, 0), (2, 0), (0, -2), (0, 2)] {
+            let (chest_x, chest_z) = (x + dx, z + dz);
+            assert_eq!(block(chest_x, y - 11, chest_z), &Block::CHEST);
+            let pos = BlockPos::new(
+                start_pos.x * 16 + chest_x,
+                y - 11,
+                start_pos.y * 16 + chest_z,
+            );
+            let chest = chunk.block_entities[&pos]
+                .as_any()
+                .downcast_ref::<ChestBlockEntity>()
+                .unwrap();
+            assert_eq!(
+                chest.loot_table.as_deref(),
+                Some("minecraft:chests/desert_pyramid")
+            );
+            chests += 1;
+        }
+        assert_eq!(chests, 4);
+        assert_eq!(chunk.block_entities.len(), 4);
+    }
+
+    #[test]
+    fn monument_guards_gold_in_deep_ocean() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(0, 0),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        // One of the closest monuments of this seed, reaching two chunks past its start each way
+        let start_pos = Vector2::new(15, -84);
+        let monument = structure_start(
+            "ocean_monuments",
+            &mut chunk,
+            &Dimension::Overworld,
+            1,
+            start_pos,
+        )
+        .unwrap();
+        assert_eq!(monument.kind, StructureKind::OceanMonument);
+        let bounding_box = monument.pieces[0].bounding_box();
+        assert_eq!(bounding_box.max.x - bounding_box.min.x, 57);
+        assert!(bounding_box.max.y < settings.sea_level);
+        let middle = Vector3::new(
+            start_pos.x * 16 + 9,
+            settings.sea_level,
+            start_pos.y * 16 + 9,
+        );
+        let biome = scaled_biome(&mut chunk, &Dimension::Overworld, 1)(&Vector3::new(
+            middle.x >> 2,
+            middle.y >> 2,
+            middle.z >> 2,
+        ));
+        assert_eq!(
+            biome.is_tagged_with("#minecraft:has_structure/ocean_monument"),
+            Some(true)
+        );
 
-pub static STRUCTURE_SETS: LazyLock<HashMap<String, StructureSet>> =
-    LazyLock::new(|| read_data_from_file!("../../../assets/structure_set.json"));
+        let generator =
+            VanillaGenerator::new(Seed(SEED), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        // The gold is in the middle, in the start chunk
+        let chunk = generator.generate_chunk(start_pos);
+        let mut gold = 0;
+        let mut prismarine = 0;
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in bounding_box.min.y..=bounding_box.max.y {
+                    let id = chunk.section.get_block_absolute_y(x, y, z).unwrap();
+                    let block = Block::from_state_id(id);
+                    gold += usize::from(block == &Block::GOLD_BLOCK);
+                    prismarine += usize::from(block == &Block::PRISMARINE_BRICKS);
+                }
+            }
+        }
+        assert_eq!(gold, 8);
+        assert!(prismarine > 0);
+    }
+
+    #[test]
+    fn ruined_portal_in_every_region() {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(0, 0),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        let placement = &STRUCTURE_SETS["ruined_portals"].placement;
+        let StructurePlacementType::RandomSpread(spread) = &placement.r#type else {
+            panic!("ruined portals are spread randomly");
+        };
+        // Like in vanilla, every biome has some kind of ruined portal, so every region has one
+        let mut kinds = HashSet::new();
+        for region_x in -5..5 {
+            for region_z in -5..5 {
+                let region = Vector2::new(region_x * 40, region_z * 40);
+                let start_pos = spread.get_start_chunk(SEED as i64, region, placement.salt);
+                let portal = structure_start(
+                    "ruined_portals",
+                    &mut chunk,
+                    &Dimension::Overworld,
+                    1,
+                    start_pos,
+                )
+                .unwrap();
+                kinds.insert(portal.kind);
+            }
+        }
+        for portal_type in [
+            RuinedPortalType::Standard,
+            RuinedPortalType::Ocean,
+            RuinedPortalType::Jungle,
+        ] {
+            assert!(kinds.contains(&StructureKind::RuinedPortal(portal_type)));
+        }
+        assert!(!kinds.contains(&StructureKind::RuinedPortal(RuinedPortalType::Nether)));
+
+        let generator =
+            VanillaGenerator::new(Seed(SEED), Dimension::Overworld, Arc::new(BlockRegistry))
+                .unwrap();
+        let chunk = generator.generate_chunk(Vector2::new(20, 9));
+        let mut obsidian = 0;
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in i32::from(settings.shape.min_y)..200 {
+                    let id = chunk.section.get_block_absolute_y(x, y, z).unwrap();
+                    let block = Block::from_state_id(id);
+                    obsidian +=
+                        usize::from(block == &Block::OBSIDIAN || block == &Block::CRYING_OBSIDIAN);
+                }
+            }
+        }
+        assert!(obsidian > 0);
+        let chests: Vec<_> = chunk
+            .block_entities
+            .values()
+            .filter_map(|entity| entity.as_any().downcast_ref::<ChestBlockEntity>())
+            .collect();
+        assert_eq!(chests.len(), 1);
+        assert_eq!(
+            chests[0].loot_table.as_deref(),
+            Some("minecraft:chests/ruined_portal")
+        );
+    }
+}
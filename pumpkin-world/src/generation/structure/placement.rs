@@ -1,28 +1,34 @@
+use std::{f64::consts::PI, sync::OnceLock};
+
+use pumpkin_config::chunk::StructureSpacing;
+use pumpkin_data::{chunk::Biome, tag::Taggable};
 use pumpkin_util::{
-    math::{floor_div, vector2::Vector2},
+    math::{floor_div, vector2::Vector2, vector3::Vector3},
     random::{
-        RandomGenerator, RandomImpl, get_carver_seed, get_region_seed, xoroshiro128::Xoroshiro,
+        RandomGenerator, RandomImpl, get_carver_seed, get_region_seed, legacy_rand::LegacyRand,
     },
 };
 use serde::Deserialize;
 
+use crate::generation::biome_coords;
+
 #[derive(Deserialize)]
 pub struct StructurePlacement {
     frequency_reduction_method: Option<FrequencyReductionMethod>,
     frequency: Option<f32>,
-    salt: i32,
+    pub(crate) salt: i32,
     #[serde(flatten)]
-    r#type: StructurePlacementType,
+    pub(crate) r#type: StructurePlacementType,
 }
 
 impl StructurePlacement {
     pub fn should_generate(
         &self,
-        calculator: StructurePlacementCalculator,
+        mut calculator: StructurePlacementCalculator,
         chunk_pos: Vector2<i32>,
     ) -> bool {
         self.r#type
-            .is_start_chunk(&calculator, chunk_pos, self.salt)
+            .is_start_chunk(&mut calculator, chunk_pos, self.salt)
             && self.apply_frequency_reduction(calculator.seed, chunk_pos)
         // TODO: add exclusion_zone, only used for pillager_outposts
     }
@@ -42,8 +48,11 @@ impl StructurePlacement {
 #[serde(rename_all = "snake_case")]
 pub enum FrequencyReductionMethod {
     Default,
+    #[serde(rename = "legacy_type_1")]
     LegacyType1,
+    #[serde(rename = "legacy_type_2")]
     LegacyType2,
+    #[serde(rename = "legacy_type_3")]
     LegacyType3,
 }
 
@@ -58,13 +67,13 @@ impl FrequencyReductionMethod {
         match self {
             FrequencyReductionMethod::Default => {
                 let region_seed = get_region_seed(seed as u64, chunk_pos.x, chunk_pos.y, salt);
-                let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(region_seed));
+                let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(region_seed));
                 random.next_f32() < frequency
             }
             FrequencyReductionMethod::LegacyType1 => {
                 let x = chunk_pos.x >> 4;
                 let z = chunk_pos.y >> 4;
-                let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(
+                let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(
                     (x ^ z << 4) as u64 ^ seed as u64,
                 ));
                 random.next_i32(); // yeah mojang just does that and does not use the value
@@ -72,16 +81,14 @@ impl FrequencyReductionMethod {
             }
             FrequencyReductionMethod::LegacyType2 => {
                 let region_seed = get_region_seed(seed as u64, chunk_pos.x, chunk_pos.y, 10387320);
-                let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(region_seed));
+                let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(region_seed));
                 random.next_f32() < frequency
             }
             FrequencyReductionMethod::LegacyType3 => {
-                let mut random: RandomGenerator =
-                    RandomGenerator::Xoroshiro(Xoroshiro::from_seed(seed as u64));
+                let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(seed as u64));
                 let carver_seed =
                     get_carver_seed(&mut random, seed as u64, chunk_pos.x, chunk_pos.y);
-                let mut random: RandomGenerator =
-                    RandomGenerator::Xoroshiro(Xoroshiro::from_seed(carver_seed));
+                let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(carver_seed));
 
                 random.next_f64() < frequency as f64
             }
@@ -95,35 +102,211 @@ pub enum StructurePlacementType {
     #[serde(rename = "minecraft:random_spread")]
     RandomSpread(RandomSpreadStructurePlacement),
     #[serde(rename = "minecraft:concentric_rings")]
-    ConcentricRings,
+    ConcentricRings(ConcentricRingsStructurePlacement),
 }
 
 impl StructurePlacementType {
     pub fn is_start_chunk(
         &self,
-        calculator: &StructurePlacementCalculator,
+        calculator: &mut StructurePlacementCalculator,
         chunk_pos: Vector2<i32>,
         salt: i32,
     ) -> bool {
         match self {
-            StructurePlacementType::RandomSpread(placement) => {
-                placement.is_start_chunk(calculator, chunk_pos, salt)
+            StructurePlacementType::RandomSpread(placement) => match calculator.spacing {
+                Some(spacing) => placement
+                    .with_spacing(spacing)
+                    .is_start_chunk(calculator, chunk_pos, salt),
+                None => placement.is_start_chunk(calculator, chunk_pos, salt),
+            },
+            StructurePlacementType::ConcentricRings(_) => calculator
+                .ring_starts
+                .is_some_and(|starts| starts.is_start_chunk(chunk_pos, calculator.biome)),
+        }
+    }
+}
+
+/// How far vanilla looks for a preferred biome around the positions on the rings, in blocks.
+pub(crate) const RING_BIOME_SEARCH_RADIUS: i32 = 112;
+
+/// Places structures on rings around the origin, like strongholds. The first ring holds `spread`
+/// of them at a distance of about `4 * distance` chunks, with more on every ring further out.
+#[derive(Deserialize)]
+pub struct ConcentricRingsStructurePlacement {
+    distance: i32,
+    spread: i32,
+    count: i32,
+    preferred_biomes: String,
+}
+
+impl ConcentricRingsStructurePlacement {
+    /// The chunk on its ring of every structure, with the seed of the random it looks for a
+    /// preferred biome with, like vanilla.
+    fn ring_positions(&self, seed: u64) -> Vec<(Vector2<i32>, u64)> {
+        let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(seed));
+        let mut angle = random.next_f64() * PI * 2.0;
+        let mut spread = self.spread;
+        let mut ring = 0;
+        let mut on_ring = 0;
+        let mut positions = Vec::with_capacity(self.count as usize);
+        for index in 0..self.count {
+            let distance = (4 * self.distance + self.distance * ring * 6) as f64
+                + (random.next_f64() - 0.5) * (self.distance as f64 * 2.5);
+            // Like Java's `Math.round`, which rounds halves up
+            let x = (angle.cos() * distance + 0.5).floor() as i32;
+            let z = (angle.sin() * distance + 0.5).floor() as i32;
+            positions.push((Vector2::new(x, z), random.next_i64() as u64));
+
+            angle += PI * 2.0 / spread as f64;
+            on_ring += 1;
+            if on_ring == spread {
+                ring += 1;
+                on_ring = 0;
+                spread += 2 * spread / (ring + 1);
+                spread = spread.min(self.count - index);
+                angle += random.next_f64() * PI * 2.0;
+            }
+        }
+        positions
+    }
+
+    /// The chunk a structure on a ring at `ring_pos` starts in: a random one of the preferred
+    /// biomes around it, or `ring_pos` itself without any.
+    fn find_start(
+        &self,
+        ring_pos: Vector2<i32>,
+        random_seed: u64,
+        biome: &mut dyn FnMut(&Vector3<i32>) -> &'static Biome,
+    ) -> Vector2<i32> {
+        let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(random_seed));
+        let center_x = biome_coords::from_block(ring_pos.x * 16 + 8);
+        let center_z = biome_coords::from_block(ring_pos.y * 16 + 8);
+        let radius = biome_coords::from_block(RING_BIOME_SEARCH_RADIUS);
+        let mut start = None;
+        let mut found = 0;
+        for offset_z in -radius..=radius {
+            for offset_x in -radius..=radius {
+                let biome_pos = Vector3::new(center_x + offset_x, 0, center_z + offset_z);
+                if biome(&biome_pos).is_tagged_with(&self.preferred_biomes) != Some(true) {
+                    continue;
+                }
+                // Every matching biome is as likely to be picked
+                if start.is_none() || random.next_bounded_i32(found + 1) == 0 {
+                    start = Some(Vector2::new(
+                        biome_coords::to_block(biome_pos.x) >> 4,
+                        biome_coords::to_block(biome_pos.z) >> 4,
+                    ));
+                }
+                found += 1;
             }
-            StructurePlacementType::ConcentricRings => false, // TODO, This is needed for Stronghold, since it is placed in rings
         }
+        start.unwrap_or(ring_pos)
+    }
+}
+
+/// The chunks the structures of a ring placement start in for one world seed. Finding the
+/// preferred biome of a structure samples thousands of biomes, so each is only found once a
+/// chunk near it asks.
+pub struct RingStarts {
+    placement: &'static ConcentricRingsStructurePlacement,
+    ring_positions: Vec<(Vector2<i32>, u64)>,
+    starts: Vec<OnceLock<Vector2<i32>>>,
+}
+
+impl RingStarts {
+    pub fn new(placement: &'static StructurePlacement, seed: u64) -> Option<Self> {
+        let StructurePlacementType::ConcentricRings(placement) = &placement.r#type else {
+            return None;
+        };
+        let ring_positions = placement.ring_positions(seed);
+        Some(Self {
+            placement,
+            starts: ring_positions.iter().map(|_| OnceLock::new()).collect(),
+            ring_positions,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring_positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ring_positions.is_empty()
+    }
+
+    /// How many of the starts were already looked up and are cached.
+    pub fn resolved(&self) -> usize {
+        self.starts
+            .iter()
+            .filter(|start| start.get().is_some())
+            .count()
+    }
+
+    /// The chunk on its ring of the structure at `index`, which it starts close to.
+    pub fn ring_position(&self, index: usize) -> Vector2<i32> {
+        self.ring_positions[index].0
+    }
+
+    /// The chunk the structure at `index`, counting outwards from the innermost ring, starts in.
+    /// `biome` gives the biome at a biome position.
+    pub fn start(
+        &self,
+        index: usize,
+        biome: &mut dyn FnMut(&Vector3<i32>) -> &'static Biome,
+    ) -> Vector2<i32> {
+        *self.starts[index].get_or_init(|| {
+            let (ring_pos, random_seed) = self.ring_positions[index];
+            self.placement.find_start(ring_pos, random_seed, biome)
+        })
+    }
+
+    /// Looks up the starts of the structures that could move within `reach` chunks of any of
+    /// `chunks`. Starts already looked up are left as they are.
+    pub fn resolve_near(
+        &self,
+        chunks: &[Vector2<i32>],
+        reach: i32,
+        biome: &mut dyn FnMut(&Vector3<i32>) -> &'static Biome,
+    ) {
+        let reach = reach + RING_BIOME_SEARCH_RADIUS / 16 + 1;
+        for index in 0..self.len() {
+            let ring_pos = self.ring_positions[index].0;
+            if chunks.iter().any(|chunk| {
+                (ring_pos.x - chunk.x).abs() <= reach && (ring_pos.y - chunk.y).abs() <= reach
+            }) {
+                self.start(index, biome);
+            }
+        }
+    }
+
+    pub fn is_start_chunk(
+        &self,
+        chunk_pos: Vector2<i32>,
+        biome: &mut dyn FnMut(&Vector3<i32>) -> &'static Biome,
+    ) -> bool {
+        // Only the structures close enough to move into the chunk have to be looked at
+        let reach = RING_BIOME_SEARCH_RADIUS / 16 + 1;
+        (0..self.len()).any(|index| {
+            let ring_pos = self.ring_positions[index].0;
+            (ring_pos.x - chunk_pos.x).abs() <= reach
+                && (ring_pos.y - chunk_pos.y).abs() <= reach
+                && self.start(index, biome) == chunk_pos
+        })
     }
 }
 
 #[derive(Deserialize)]
 pub struct RandomSpreadStructurePlacement {
-    spacing: i32,
+    pub(crate) spacing: i32,
     separation: i32,
+    #[serde(default)]
     spread_type: SpreadType,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default, Clone, Copy)]
 #[serde(rename_all = "lowercase")]
 pub enum SpreadType {
+    #[default]
     Linear,
     Triangular,
 }
@@ -140,11 +323,25 @@ impl SpreadType {
 }
 
 impl RandomSpreadStructurePlacement {
-    fn get_start_chunk(&self, seed: i64, chunk_pos: Vector2<i32>, salt: i32) -> Vector2<i32> {
+    /// This placement with the structures spaced out like `spacing` says instead.
+    pub fn with_spacing(&self, spacing: StructureSpacing) -> Self {
+        Self {
+            spacing: spacing.spacing,
+            separation: spacing.separation,
+            spread_type: self.spread_type,
+        }
+    }
+
+    pub(crate) fn get_start_chunk(
+        &self,
+        seed: i64,
+        chunk_pos: Vector2<i32>,
+        salt: i32,
+    ) -> Vector2<i32> {
         let x = floor_div(chunk_pos.x, self.spacing);
         let z = floor_div(chunk_pos.y, self.spacing);
         let region_seed = get_region_seed(seed as u64, x, z, salt);
-        let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(region_seed));
+        let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(region_seed));
         let bound = self.spacing - self.separation;
         let rand_x = self.spread_type.get(&mut random, bound);
         let rand_z = self.spread_type.get(&mut random, bound);
@@ -162,6 +359,124 @@ impl RandomSpreadStructurePlacement {
     }
 }
 
-pub struct StructurePlacementCalculator {
+pub struct StructurePlacementCalculator<'a> {
     pub seed: i64,
+    /// Where the structures start if the set is placed in rings.
+    pub ring_starts: Option<&'a RingStarts>,
+    /// How far apart the structures are instead of vanilla's, if the set is spread over a grid.
+    pub spacing: Option<StructureSpacing>,
+    /// The biome at a biome position, for the placements preferring some biomes.
+    pub biome: &'a mut dyn FnMut(&Vector3<i32>) -> &'static Biome,
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::chunk::StructureSpacing;
+    use pumpkin_data::chunk::Biome;
+    use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+    use super::{RingStarts, StructurePlacementCalculator, StructurePlacementType};
+    use crate::generation::structure::STRUCTURE_SETS;
+
+    #[test]
+    fn village_start_chunks_match_vanilla() {
+        let placement = &STRUCTURE_SETS["villages"].placement;
+        let StructurePlacementType::RandomSpread(spread) = &placement.r#type else {
+            panic!("villages are spread randomly");
+        };
+        // Regions and the chunks the village of each starts in, taken from vanilla
+        let regions = [(0, 0), (-1, 0), (0, -1), (-1, -1), (1, 2)];
+        for (seed, starts) in [
+            (0, [(15, 2), (-27, 1), (19, -15), (-9, -34), (57, 72)]),
+            (13579, [(20, 7), (-21, 2), (25, -14), (-13, -23), (49, 85)]),
+        ] {
+            for ((region_x, region_z), (x, z)) in regions.into_iter().zip(starts) {
+                let chunk = Vector2::new(region_x * 34, region_z * 34);
+                assert_eq!(
+                    spread.get_start_chunk(seed, chunk, placement.salt),
+                    Vector2::new(x, z),
+                    "seed {seed}, region {region_x} {region_z}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wider_spacing_spreads_villages_out() {
+        let placement = &STRUCTURE_SETS["villages"].placement;
+        let starts = |spacing| {
+            let mut biome = |_: &Vector3<i32>| &Biome::PLAINS;
+            let mut starts = Vec::new();
+            for x in -340..340 {
+                for z in -340..340 {
+                    let calculator = StructurePlacementCalculator {
+                        seed: 0,
+                        ring_starts: None,
+                        spacing,
+                        biome: &mut biome,
+                    };
+                    if placement.should_generate(calculator, Vector2::new(x, z)) {
+                        starts.push((x, z));
+                    }
+                }
+            }
+            starts
+        };
+
+        // One village per square of the grid, which is four times as large with twice the spacing
+        let vanilla = starts(None);
+        assert_eq!(vanilla.len(), 20 * 20);
+        let spacing = StructureSpacing {
+            spacing: 68,
+            separation: 8,
+        };
+        let spaced = starts(Some(spacing));
+        assert_eq!(spaced.len(), 10 * 10);
+        assert_eq!(starts(Some(spacing)), spaced);
+        // Spaced out the same as vanilla is just vanilla
+        let same = StructureSpacing {
+            spacing: 34,
+            separation: 8,
+        };
+        assert_eq!(starts(Some(same)), vanilla);
+    }
+
+    #[test]
+    fn stronghold_rings_match_vanilla() {
+        let placement = &STRUCTURE_SETS["strongholds"].placement;
+        // The chunks of the three innermost strongholds on their ring and their biome search
+        // seeds, and the outermost one, taken from vanilla
+        for (seed, innermost, outermost) in [
+            (
+                0,
+                [
+                    ((-13, -106), -6688467811848818630_i64),
+                    ((121, 52), -7423979211207825555),
+                    ((-92, 69), 7105486291024734541),
+                ],
+                (455, 1402),
+            ),
+            (
+                13579,
+                [
+                    ((-26, 92), -8187449713052551745),
+                    ((-63, -65), -1964739605929925454),
+                    ((87, -22), 6175895888336053718),
+                ],
+                (-1075, 999),
+            ),
+        ] {
+            let starts = RingStarts::new(placement, seed).unwrap();
+            assert_eq!(starts.len(), 128);
+            for (index, ((x, z), random_seed)) in innermost.into_iter().enumerate() {
+                assert_eq!(
+                    starts.ring_positions[index],
+                    (Vector2::new(x, z), random_seed as u64),
+                    "seed {seed}, stronghold {index}"
+                );
+            }
+            let (x, z) = outermost;
+            assert_eq!(starts.ring_positions[127].0, Vector2::new(x, z));
+        }
+    }
 }
@@ -3,45 +3,52 @@ use pumpkin_util::{
     HeightMap,
     math::{position::BlockPos, vector2::Vector2, vector3::Vector3},
 };
-use serde::Deserialize;
 
 use crate::{
     ProtoChunk,
     generation::{
         height_limit::HeightLimitView,
-        positions::chunk_pos::{get_center_x, get_center_z, get_offset_x, get_offset_z},
-        structure::structures::{StructureGenerator, StructurePiecesCollector, StructurePosition},
+        positions::chunk_pos::{get_offset_x, get_offset_z},
+        structure::{
+            StartContext,
+            structures::{BlockBox, StructureGenerator, StructurePiece},
+        },
     },
 };
 
-#[derive(Deserialize, Clone)]
 pub struct BuriedTreasureGenerator;
 
 impl StructureGenerator for BuriedTreasureGenerator {
-    fn get_structure_position(&self, chunk: &ProtoChunk) -> super::StructurePosition {
-        let x = get_center_x(chunk.chunk_pos.x);
-        let z = get_center_z(chunk.chunk_pos.y);
-        let y = chunk.get_top_y(&HeightMap::OceanFloorWg, &Vector2::new(x, z)) - 1;
-        let generator = StructurePiecesCollector {
-            pieces_positions: vec![BlockPos::new(
-                get_offset_x(chunk.chunk_pos.x, 9),
-                90,
-                get_offset_z(chunk.chunk_pos.y, 9),
-            )],
-        };
-        StructurePosition {
-            position: BlockPos(Vector3::new(x, y, z)),
-            generator,
-        }
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let x = get_offset_x(context.start.x, 9);
+        let z = get_offset_z(context.start.y, 9);
+        Some(vec![Box::new(BuriedTreasurePiece {
+            bounding_box: BlockBox::new(
+                Vector3::new(x - 1, context.bottom_y(), z - 1),
+                Vector3::new(x + 1, context.top_y() - 1, z + 1),
+            ),
+            x,
+            z,
+        })])
     }
+}
 
-    fn generate(&self, position: BlockPos, chunk: &mut crate::ProtoChunk) {
-        let y = chunk.get_top_y(
-            &HeightMap::OceanFloorWg,
-            &Vector2::new(position.0.x, position.0.z),
-        );
-        let mut pos = BlockPos::new(position.0.x, y, position.0.z);
-        for _ in y..chunk.bottom_y() as i32 {
+/// A chest buried in the column at `x` and `z`, on the first stone below the ocean floor.
+pub struct BuriedTreasurePiece {
+    bounding_box: BlockBox,
+    x: i32,
+    z: i32,
+}
+
+impl StructurePiece for BuriedTreasurePiece {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let y = chunk.get_top_y(&HeightMap::OceanFloorWg, &Vector2::new(self.x, self.z));
+        let mut pos = BlockPos::new(self.x, y, self.z);
+        for _ in chunk.bottom_y() as i32..y {
             let state = chunk.get_block_state(&pos.0);
             let down_raw_state = chunk.get_block_state(&pos.down().0);
             let down_block = down_raw_state.to_block();
@@ -53,6 +60,9 @@ impl StructureGenerator for BuriedTreasureGenerator {
             {
                 for dir in BlockDirection::all() {
                     let pos = pos.offset(dir.to_offset());
+                    if !chunk_box.contains(&pos.0) {
+                        continue;
+                    }
                     let dir_state = chunk.get_block_state(&pos.0);
                     if !dir_state.to_state().is_air() && !Self::is_liquid(dir_state.to_block()) {
                         continue;
@@ -72,8 +82,10 @@ impl StructureGenerator for BuriedTreasureGenerator {
                     };
                     chunk.set_block_state(&pos.0, state1);
                 }
-                // TODO: add loot
-                chunk.set_block_state(&pos.0, Block::CHEST.default_state);
+                if chunk_box.contains(&pos.0) {
+                    // TODO: add loot
+                    chunk.set_block_state(&pos.0, Block::CHEST.default_state);
+                }
                 return;
             }
             pos = pos.down();
@@ -81,7 +93,7 @@ impl StructureGenerator for BuriedTreasureGenerator {
     }
 }
 
-impl BuriedTreasureGenerator {
+impl BuriedTreasurePiece {
     fn is_liquid(block: &Block) -> bool {
         block == &Block::WATER || block == &Block::LAVA
     }
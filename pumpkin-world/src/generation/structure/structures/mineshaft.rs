@@ -0,0 +1,594 @@
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{BlockProperties, RailLikeProperties, RailShape},
+};
+use pumpkin_util::{
+    math::vector3::Vector3,
+    random::{RandomGenerator, RandomImpl},
+};
+
+use crate::{
+    ProtoChunk,
+    generation::{
+        positions::chunk_pos,
+        structure::{
+            StartContext,
+            structures::{BlockBox, Frame, FrameWriter, StructureGenerator, StructurePiece, roll},
+        },
+    },
+};
+
+/// The height the room of a mineshaft is laid out at before the mineshaft is moved, like vanilla.
+const LAYOUT_Y: i32 = 50;
+/// How far below sea level the top of a normal mineshaft is at least.
+const SEA_LEVEL_PENALTY: i32 = 10;
+/// How many pieces away from the room a piece can be at most.
+const MAX_DEPTH: i32 = 8;
+/// How many blocks away from the room a piece can start at most, which keeps the whole mineshaft
+/// within [`STRUCTURE_RADIUS`](crate::generation::structure::STRUCTURE_RADIUS) chunks of the
+/// chunk it starts in.
+const MAX_DISTANCE: i32 = 80;
+/// The length of a section of corridor, each with a support in it.
+const SECTION_LENGTH: i32 = 5;
+
+/// The kinds of mineshafts, built from the wood of the biomes they are found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MineshaftType {
+    Normal,
+    /// Found in badlands, close to the surface.
+    Mesa,
+}
+
+impl MineshaftType {
+    pub fn planks(&self) -> &'static BlockState {
+        match self {
+            Self::Normal => Block::OAK_PLANKS.default_state,
+            Self::Mesa => Block::DARK_OAK_PLANKS.default_state,
+        }
+    }
+
+    pub fn fence(&self) -> &'static BlockState {
+        match self {
+            Self::Normal => Block::OAK_FENCE.default_state,
+            Self::Mesa => Block::DARK_OAK_FENCE.default_state,
+        }
+    }
+}
+
+/// Lays out an abandoned mineshaft: a room in the start chunk with corridors leading away from
+/// its walls, which branch off, cross each other and go down stairs up to [`MAX_DEPTH`] pieces
+/// away from the room.
+///
+/// Like vanilla, the pieces are picked one after the other and left out where they would run into
+/// one already there. A chunk doesn't place the blocks of a piece that touches liquids in it, so
+/// that mineshafts don't flood caves and aquifers.
+impl StructureGenerator for MineshaftType {
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let start = context.start;
+        let random = &mut context.random;
+        let length = 8 + random.next_bounded_i32(6);
+        let height = 5 + random.next_bounded_i32(6);
+        let width = 8 + random.next_bounded_i32(6);
+        let mut room = MineshaftRoom {
+            frame: Frame {
+                origin: Vector3::new(
+                    chunk_pos::start_block_x(&start) + 2,
+                    LAYOUT_Y,
+                    chunk_pos::start_block_z(&start) + 2,
+                ),
+                facing: (1, 0),
+            },
+            length,
+            height,
+            width,
+            exits: Vec::new(),
+        };
+
+        let mut layout = Layout {
+            random,
+            mineshaft_type: *self,
+            room_box: room.bounding_box(),
+            pieces: Vec::new(),
+        };
+        room.exits = layout.add_room_exits(&room);
+        let mut pieces = layout.pieces;
+
+        let bottom = pieces
+            .iter()
+            .map(|piece| piece.bounding_box().min.y)
+            .fold(room.bounding_box().min.y, i32::min);
+        let top = pieces
+            .iter()
+            .map(|piece| piece.bounding_box().max.y)
+            .fold(room.bounding_box().max.y, i32::max);
+        let shift = match self {
+            Self::Normal => context.below_sea_level_shift(bottom, top, SEA_LEVEL_PENALTY),
+            // Vanilla moves them up to about sea level
+            Self::Mesa => context.sea_level() - top + (top - bottom + 1) / 2 + 5,
+        };
+        room.frame.origin.y += shift;
+        for exit in &mut room.exits {
+            exit.origin.y += shift;
+        }
+        for piece in &mut pieces {
+            piece.frame.origin.y += shift;
+        }
+
+        let mut all: Vec<Box<dyn StructurePiece>> = vec![Box::new(room)];
+        all.extend(
+            pieces
+                .into_iter()
+                .map(|piece| Box::new(piece) as Box<dyn StructurePiece>),
+        );
+        Some(all)
+    }
+}
+
+/// The pieces of a mineshaft laid out so far.
+struct Layout<'a> {
+    random: &'a mut RandomGenerator,
+    mineshaft_type: MineshaftType,
+    room_box: BlockBox,
+    pieces: Vec<MineshaftPiece>,
+}
+
+impl Layout<'_> {
+    /// Leads corridors away from the walls of the room at random places and heights, returning
+    /// the frames of those that fit.
+    fn add_room_exits(&mut self, room: &MineshaftRoom) -> Vec<Frame> {
+        let frame = room.frame;
+        let mut exits = Vec::new();
+        // Across the room on both sides, then along it at both ends
+        for wall in 0..4 {
+            let span = if wall < 2 { room.length } else { room.width };
+            let mut offset = 0;
+            while offset < span {
+                offset += self.random.next_bounded_i32(span);
+                if offset + 3 > span {
+                    break;
+                }
+                let y = 1 + self.random.next_bounded_i32(room.height - 4);
+                let mut exit = match wall {
+                    0 => frame.turned(offset + 1, -1, -1),
+                    1 => frame.turned(offset + 1, room.width, 1),
+                    2 => Frame {
+                        origin: frame.to_world(-1, 0, offset + 1),
+                        facing: (-frame.facing.0, -frame.facing.1),
+                    },
+                    _ => Frame {
+                        origin: frame.to_world(room.length, 0, offset + 1),
+                        facing: frame.facing,
+                    },
+                };
+                exit.origin.y += y;
+                if self.add_piece(exit, 1) {
+                    exits.push(exit);
+                }
+                offset += 4;
+            }
+        }
+        exits
+    }
+
+    /// Adds a random piece starting at `frame`, `depth` pieces away from the room, and the pieces
+    /// leading away from it. Returns whether it fit.
+    fn add_piece(&mut self, frame: Frame, depth: i32) -> bool {
+        let room = self.room_box.min;
+        if depth > MAX_DEPTH
+            || (frame.origin.x - room.x).abs() > MAX_DISTANCE
+            || (frame.origin.z - room.z).abs() > MAX_DISTANCE
+        {
+            return false;
+        }
+
+        let pick = self.random.next_bounded_i32(100);
+        let shaft = if pick >= 80 {
+            let two_floors = self.random.next_bounded_i32(4) == 0;
+            Some(Shaft::Crossing { two_floors }).filter(|shaft| self.fits(frame, shaft))
+        } else if pick >= 70 {
+            Some(Shaft::Stairs).filter(|shaft| self.fits(frame, shaft))
+        } else {
+            // The longest corridor that fits
+            let corridor = |sections, rails, spiders| Shaft::Corridor {
+                sections,
+                rails,
+                spiders,
+            };
+            (1..=2 + self.random.next_bounded_i32(3))
+                .rev()
+                .find(|&sections| self.fits(frame, &corridor(sections, false, false)))
+                .map(|sections| {
+                    let rails = self.random.next_bounded_i32(3) == 0;
+                    let spiders = !rails && self.random.next_bounded_i32(23) == 0;
+                    corridor(sections, rails, spiders)
+                })
+        };
+        let Some(shaft) = shaft else {
+            return false;
+        };
+        self.pieces.push(MineshaftPiece {
+            frame,
+            shaft,
+            mineshaft_type: self.mineshaft_type,
+        });
+        self.add_exits(frame, shaft, depth);
+        true
+    }
+
+    /// Whether `shaft` runs into none of the pieces already there.
+    fn fits(&self, frame: Frame, shaft: &Shaft) -> bool {
+        let bounding_box = shaft.bounding_box(frame);
+        !self.room_box.intersects(&bounding_box)
+            && self
+                .pieces
+                .iter()
+                .all(|piece| !piece.bounding_box().intersects(&bounding_box))
+    }
+
+    fn add_exits(&mut self, frame: Frame, shaft: Shaft, depth: i32) {
+        let depth = depth + 1;
+        match shaft {
+            Shaft::Corridor { sections, .. } => {
+                let length = sections * SECTION_LENGTH;
+                // Straight on or around a corner at the end, a little higher or lower
+                let turn = self.random.next_bounded_i32(4);
+                let y = self.random.next_bounded_i32(3) - 1;
+                let mut end = match turn {
+                    2 => frame.turned(length - 2, -2, -1),
+                    3 => frame.turned(length - 2, 2, 1),
+                    _ => frame.forward(length),
+                };
+                end.origin.y += y;
+                self.add_piece(end, depth);
+
+                // Branches off the sides
+                if depth <= MAX_DEPTH {
+                    let mut along = 3;
+                    while along + 3 < length {
+                        match self.random.next_bounded_i32(5) {
+                            0 => {
+                                self.add_piece(frame.turned(along + 1, -2, -1), depth);
+                            }
+                            1 => {
+                                self.add_piece(frame.turned(along + 1, 2, 1), depth);
+                            }
+                            _ => {}
+                        }
+                        along += SECTION_LENGTH;
+                    }
+                }
+            }
+            Shaft::Crossing { two_floors } => {
+                let exits = [
+                    frame.forward(5),
+                    frame.turned(2, -3, -1),
+                    frame.turned(2, 3, 1),
+                ];
+                for exit in exits {
+                    self.add_piece(exit, depth);
+                }
+                if two_floors {
+                    let back = Frame {
+                        origin: frame.to_world(-1, 0, 0),
+                        facing: (-frame.facing.0, -frame.facing.1),
+                    };
+                    for mut exit in exits.into_iter().chain([back]) {
+                        if self.random.next_bool() {
+                            exit.origin.y += 4;
+                            self.add_piece(exit, depth);
+                        }
+                    }
+                }
+            }
+            Shaft::Stairs => {
+                let mut bottom = frame.forward(Shaft::STAIRS_LENGTH);
+                bottom.origin.y -= Shaft::STAIRS_DROP;
+                self.add_piece(bottom, depth);
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Shaft {
+    /// Three blocks wide and high, with a wooden support in every section.
+    Corridor {
+        sections: i32,
+        rails: bool,
+        /// Full of cobwebs, around a cave spider spawner.
+        spiders: bool,
+    },
+    /// Where corridors lead off in every direction, on two floors sometimes.
+    Crossing { two_floors: bool },
+    /// Going down to a corridor below.
+    Stairs,
+}
+
+impl Shaft {
+    const STAIRS_LENGTH: i32 = 9;
+    const STAIRS_DROP: i32 = 5;
+
+    fn bounding_box(&self, frame: Frame) -> BlockBox {
+        let (min, max) = match self {
+            Self::Corridor { sections, .. } => ((0, 0, -1), (sections * SECTION_LENGTH - 1, 2, 1)),
+            Self::Crossing { two_floors } => ((0, 0, -2), (4, if *two_floors { 6 } else { 2 }, 2)),
+            Self::Stairs => ((0, -Self::STAIRS_DROP, -1), (Self::STAIRS_LENGTH - 1, 2, 1)),
+        };
+        BlockBox::new(
+            frame.to_world(min.0, min.1, min.2),
+            frame.to_world(max.0, max.1, max.2),
+        )
+    }
+}
+
+/// The room a mineshaft starts at, its floor left as it is below a domed ceiling.
+struct MineshaftRoom {
+    /// Facing along x, its corner at the origin.
+    frame: Frame,
+    length: i32,
+    height: i32,
+    width: i32,
+    /// The frames of the corridors leading away from it, where its walls are opened up.
+    exits: Vec<Frame>,
+}
+
+impl StructurePiece for MineshaftRoom {
+    fn bounding_box(&self) -> BlockBox {
+        BlockBox::new(
+            self.frame.origin,
+            self.frame
+                .to_world(self.length - 1, self.height - 1, self.width - 1),
+        )
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        if touches_liquid(chunk, chunk_box, &self.bounding_box()) {
+            return;
+        }
+        let mut writer = FrameWriter::new(chunk, chunk_box, self.frame);
+        writer.fill(
+            0,
+            1,
+            0,
+            self.length - 1,
+            3.min(self.height - 1),
+            self.width - 1,
+            cave_air(),
+        );
+        // Half an ellipsoid above it, like vanilla
+        let dome_height = (self.height - 4) as f32;
+        for y in 4..self.height {
+            let dy = (y - 4) as f32 / dome_height;
+            for along in 0..self.length {
+                let da = (along as f32 - self.length as f32 / 2.0) / (self.length as f32 * 0.5);
+                for cross in 0..self.width {
+                    let dc = (cross as f32 - self.width as f32 / 2.0) / (self.width as f32 * 0.5);
+                    if da * da + dy * dy + dc * dc <= 1.05 {
+                        writer.set(along, y, cross, cave_air());
+                    }
+                }
+            }
+        }
+
+        for exit in &self.exits {
+            let mut writer = FrameWriter::new(chunk, chunk_box, *exit);
+            writer.fill(-1, 0, -1, -1, 2, 1, cave_air());
+        }
+    }
+}
+
+struct MineshaftPiece {
+    frame: Frame,
+    shaft: Shaft,
+    mineshaft_type: MineshaftType,
+}
+
+impl StructurePiece for MineshaftPiece {
+    fn bounding_box(&self) -> BlockBox {
+        self.shaft.bounding_box(self.frame)
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        if touches_liquid(chunk, chunk_box, &self.bounding_box()) {
+            return;
+        }
+        let mut writer = FrameWriter::new(chunk, chunk_box, self.frame);
+        match self.shaft {
+            Shaft::Corridor {
+                sections,
+                rails,
+                spiders,
+            } => place_corridor(&mut writer, self.mineshaft_type, sections, rails, spiders),
+            Shaft::Crossing { two_floors } => {
+                place_crossing(&mut writer, self.mineshaft_type, two_floors);
+            }
+            Shaft::Stairs => place_stairs(&mut writer),
+        }
+    }
+}
+
+fn cave_air() -> &'static BlockState {
+    Block::CAVE_AIR.default_state
+}
+
+/// Sets the block at a local position to `state` by the chance of `chance` if it is air.
+fn maybe_set(
+    writer: &mut FrameWriter,
+    chance: f32,
+    (along, y, cross): (i32, i32, i32),
+    state: &BlockState,
+) {
+    if roll(&writer.frame.to_world(along, y, cross)) < chance
+        && writer
+            .get(along, y, cross)
+            .is_some_and(|block| block.is_air())
+    {
+        writer.set(along, y, cross, state);
+    }
+}
+
+/// Lays planks where there is air at a local position, so that the floor doesn't have gaps.
+fn bridge(writer: &mut FrameWriter, planks: &BlockState, along: i32, y: i32, cross: i32) {
+    if writer
+        .get(along, y, cross)
+        .is_some_and(|block| block.is_air())
+    {
+        writer.set(along, y, cross, planks);
+    }
+}
+
+fn place_corridor(
+    writer: &mut FrameWriter,
+    mineshaft_type: MineshaftType,
+    sections: i32,
+    rails: bool,
+    spiders: bool,
+) {
+    let length = sections * SECTION_LENGTH;
+    let planks = mineshaft_type.planks();
+    writer.fill(0, 0, -1, length - 1, 2, 1, cave_air());
+    if spiders {
+        for along in 0..length {
+            for y in 0..=1 {
+                for cross in -1..=1 {
+                    maybe_set(writer, 0.6, (along, y, cross), Block::COBWEB.default_state);
+                }
+            }
+        }
+        // TODO: Spawn cave spiders once spawners get block entities
+        writer.set(length / 2, 0, 0, Block::SPAWNER.default_state);
+    }
+    for along in 0..length {
+        for cross in -1..=1 {
+            bridge(writer, planks, along, -1, cross);
+        }
+    }
+
+    for section in 0..sections {
+        let along = 2 + section * SECTION_LENGTH;
+        // Only held up by something above it
+        if (-1..=1).all(|cross| {
+            writer
+                .get(along, 3, cross)
+                .is_none_or(|above| !above.is_air())
+        }) {
+            for cross in [-1, 1] {
+                writer.fill(along, 0, cross, along, 1, cross, mineshaft_type.fence());
+            }
+            writer.fill(along, 2, -1, along, 2, 1, planks);
+            for (along, cross) in [
+                (along - 1, -1),
+                (along + 1, -1),
+                (along - 1, 1),
+                (along + 1, 1),
+            ] {
+                maybe_set(writer, 0.1, (along, 2, cross), Block::COBWEB.default_state);
+            }
+        }
+        // TODO: Vanilla leaves chest minecarts with the abandoned_mineshaft loot table here
+        for (along, cross) in [(along - 1, 1), (along + 1, -1)] {
+            if writer
+                .get(along, -1, cross)
+                .is_some_and(|floor| !floor.is_air())
+            {
+                maybe_set(writer, 0.01, (along, 0, cross), Block::CHEST.default_state);
+            }
+        }
+    }
+
+    if rails {
+        let props = RailLikeProperties {
+            r#shape: if writer.frame.facing.0 == 0 {
+                RailShape::NorthSouth
+            } else {
+                RailShape::EastWest
+            },
+            r#waterlogged: false,
+        };
+        let rail = BlockState::from_id(props.to_state_id(&Block::RAIL));
+        for along in 0..length {
+            if writer
+                .get(along, -1, 0)
+                .is_some_and(|floor| !floor.is_air())
+            {
+                maybe_set(writer, 0.7, (along, 0, 0), rail);
+            }
+        }
+    }
+}
+
+fn place_crossing(writer: &mut FrameWriter, mineshaft_type: MineshaftType, two_floors: bool) {
+    let planks = mineshaft_type.planks();
+    let top = if two_floors { 6 } else { 2 };
+    // A plus of two passages running into each other
+    let floors: &[i32] = if two_floors { &[0, 4] } else { &[0] };
+    for &y in floors {
+        writer.fill(0, y, -1, 4, y + 2, 1, cave_air());
+        writer.fill(1, y, -2, 3, y + 2, 2, cave_air());
+    }
+    if two_floors {
+        writer.fill(1, 3, -1, 3, 3, 1, cave_air());
+    }
+    for (along, cross) in [(1, -1), (1, 1), (3, -1), (3, 1)] {
+        writer.fill(along, 0, cross, along, top, cross, planks);
+    }
+    for along in 0..=4 {
+        for cross in -2..=2 {
+            bridge(writer, planks, along, -1, cross);
+            if two_floors {
+                bridge(writer, planks, along, 3, cross);
+            }
+        }
+    }
+}
+
+fn place_stairs(writer: &mut FrameWriter) {
+    writer.fill(0, 0, -1, 1, 2, 1, cave_air());
+    let (length, drop) = (Shaft::STAIRS_LENGTH, Shaft::STAIRS_DROP);
+    writer.fill(length - 2, -drop, -1, length - 1, 2 - drop, 1, cave_air());
+    for step in 0..drop {
+        let floor = -step - i32::from(step < drop - 1);
+        writer.fill(2 + step, floor, -1, 2 + step, 2 - step, 1, cave_air());
+    }
+}
+
+/// Whether there is any liquid on the faces of `bounding_box` grown by a block, as far as they
+/// are in the chunk.
+fn touches_liquid(chunk: &ProtoChunk, chunk_box: &BlockBox, bounding_box: &BlockBox) -> bool {
+    let min = Vector3::new(
+        (bounding_box.min.x - 1).max(chunk_box.min.x),
+        (bounding_box.min.y - 1).max(chunk_box.min.y),
+        (bounding_box.min.z - 1).max(chunk_box.min.z),
+    );
+    let max = Vector3::new(
+        (bounding_box.max.x + 1).min(chunk_box.max.x),
+        (bounding_box.max.y + 1).min(chunk_box.max.y),
+        (bounding_box.max.z + 1).min(chunk_box.max.z),
+    );
+    let liquid = |x, y, z| {
+        chunk
+            .get_block_state(&Vector3::new(x, y, z))
+            .to_state()
+            .is_liquid()
+    };
+    for x in min.x..=max.x {
+        for z in min.z..=max.z {
+            if liquid(x, min.y, z) || liquid(x, max.y, z) {
+                return true;
+            }
+        }
+    }
+    for y in min.y..=max.y {
+        for x in min.x..=max.x {
+            if liquid(x, y, min.z) || liquid(x, y, max.z) {
+                return true;
+            }
+        }
+        for z in min.z..=max.z {
+            if liquid(min.x, y, z) || liquid(max.x, y, z) {
+                return true;
+            }
+        }
+    }
+    false
+}
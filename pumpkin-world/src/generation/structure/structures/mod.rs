@@ -1,76 +1,338 @@
-use pumpkin_data::BlockState;
-use pumpkin_util::math::{position::BlockPos, vector3::Vector3};
+use pumpkin_data::{BlockState, block_properties::HorizontalFacing};
+use pumpkin_util::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::hash_block_pos,
+};
 
-use crate::{ProtoChunk, generation::height_limit::HeightLimitView};
+use crate::{
+    ProtoChunk,
+    generation::{positions::chunk_pos, structure::StartContext},
+};
 
 pub mod buried_treasure;
+pub mod mineshaft;
 pub mod nether_fortress;
+pub mod ocean_monument;
+pub mod ruined_portal;
+pub mod stronghold;
+pub mod temple;
+pub mod village;
 
-pub trait StructureGenerator {
-    fn get_structure_position(&self, chunk: &ProtoChunk) -> StructurePosition;
+pub trait StructureGenerator: Sync {
+    /// Lays out the pieces of the structure starting in `context.start`, or `None` if it can't
+    /// start there after all.
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>>;
+}
+
+/// A part of a structure, placing its blocks in every chunk its bounding box reaches into.
+pub trait StructurePiece: Send + Sync {
+    fn bounding_box(&self) -> BlockBox;
 
-    fn generate(&self, position: BlockPos, chunk: &mut crate::ProtoChunk);
+    /// Places the blocks of this piece that are within `chunk_box`, the blocks of `chunk`.
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox);
 }
 
-#[expect(clippy::too_many_arguments)]
-pub fn fill_with_outline(
-    min_x: i32,
-    min_y: i32,
-    min_z: i32,
-    max_x: i32,
-    max_y: i32,
-    max_z: i32,
-    outline: &BlockState,
-    inside: &BlockState,
-    chunk: &mut crate::ProtoChunk,
-) {
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            for z in min_z..=max_z {
-                if y == min_y || y == max_y || x == min_x || x == max_x || z == min_z || z == max_z
-                {
-                    chunk.set_block_state(&Vector3::new(x, y, z), outline);
-                } else {
-                    chunk.set_block_state(&Vector3::new(x, y, z), inside);
-                }
-            }
+/// A box of blocks, including both corners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockBox {
+    pub min: Vector3<i32>,
+    pub max: Vector3<i32>,
+}
+
+impl BlockBox {
+    pub fn new(a: Vector3<i32>, b: Vector3<i32>) -> Self {
+        Self {
+            min: Vector3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Vector3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
         }
     }
+
+    /// The blocks of the chunk at `chunk_pos` from `bottom_y` to `top_y`.
+    pub fn of_chunk(chunk_pos: Vector2<i32>, bottom_y: i32, top_y: i32) -> Self {
+        let x = chunk_pos::start_block_x(&chunk_pos);
+        let z = chunk_pos::start_block_z(&chunk_pos);
+        Self::new(
+            Vector3::new(x, bottom_y, z),
+            Vector3::new(x + 15, top_y, z + 15),
+        )
+    }
+
+    pub fn contains(&self, pos: &Vector3<i32>) -> bool {
+        (self.min.x..=self.max.x).contains(&pos.x)
+            && (self.min.y..=self.max.y).contains(&pos.y)
+            && (self.min.z..=self.max.z).contains(&pos.z)
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.max.x >= other.min.x
+            && self.min.x <= other.max.x
+            && self.max.y >= other.min.y
+            && self.min.y <= other.max.y
+            && self.max.z >= other.min.z
+            && self.min.z <= other.max.z
+    }
+}
+
+/// Places the blocks of a piece at positions relative to its `origin`, leaving out any outside of
+/// the chunk being generated.
+pub struct PieceWriter<'a, 'b> {
+    chunk: &'a mut ProtoChunk<'b>,
+    chunk_box: &'a BlockBox,
+    origin: Vector3<i32>,
 }
 
-#[expect(clippy::too_many_arguments)]
-pub fn fill(
-    min_x: i32,
-    min_y: i32,
-    min_z: i32,
-    max_x: i32,
-    max_y: i32,
-    max_z: i32,
-    state: &BlockState,
-    chunk: &mut crate::ProtoChunk,
-) {
-    for y in min_y..=max_y {
-        for x in min_x..=max_x {
-            for z in min_z..=max_z {
-                chunk.set_block_state(&Vector3::new(x, y, z), state);
+impl<'a, 'b> PieceWriter<'a, 'b> {
+    pub fn new(
+        chunk: &'a mut ProtoChunk<'b>,
+        chunk_box: &'a BlockBox,
+        origin: Vector3<i32>,
+    ) -> Self {
+        Self {
+            chunk,
+            chunk_box,
+            origin,
+        }
+    }
+
+    fn absolute(&self, x: i32, y: i32, z: i32) -> Vector3<i32> {
+        Vector3::new(self.origin.x + x, self.origin.y + y, self.origin.z + z)
+    }
+
+    /// The block at a relative position, if it is in the chunk.
+    pub fn get(&self, x: i32, y: i32, z: i32) -> Option<&'static BlockState> {
+        let pos = self.absolute(x, y, z);
+        self.chunk_box
+            .contains(&pos)
+            .then(|| self.chunk.get_block_state(&pos).to_state())
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, z: i32, state: &BlockState) {
+        let pos = self.absolute(x, y, z);
+        if self.chunk_box.contains(&pos) {
+            self.chunk.set_block_state(&pos, state);
+        }
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    pub fn fill(
+        &mut self,
+        min_x: i32,
+        min_y: i32,
+        min_z: i32,
+        max_x: i32,
+        max_y: i32,
+        max_z: i32,
+        state: &BlockState,
+    ) {
+        self.fill_with_outline(min_x, min_y, min_z, max_x, max_y, max_z, state, state);
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    pub fn fill_with_outline(
+        &mut self,
+        min_x: i32,
+        min_y: i32,
+        min_z: i32,
+        max_x: i32,
+        max_y: i32,
+        max_z: i32,
+        outline: &BlockState,
+        inside: &BlockState,
+    ) {
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                for z in min_z..=max_z {
+                    if y == min_y
+                        || y == max_y
+                        || x == min_x
+                        || x == max_x
+                        || z == min_z
+                        || z == max_z
+                    {
+                        self.set(x, y, z, outline);
+                    } else {
+                        self.set(x, y, z, inside);
+                    }
+                }
             }
         }
     }
+
+    /// Places `chest` at a relative position and marks it to be filled from `loot_table`, if it
+    /// is in the chunk. See [`ProtoChunk::mark_loot_chest`].
+    pub fn loot_chest(
+        &mut self,
+        x: i32,
+        y: i32,
+        z: i32,
+        chest: &BlockState,
+        loot_table: &'static str,
+    ) {
+        let pos = self.absolute(x, y, z);
+        if self.chunk_box.contains(&pos) {
+            self.chunk.set_block_state(&pos, chest);
+            self.chunk.mark_loot_chest(pos, loot_table);
+        }
+    }
+
+    /// Fills the air and liquids from `y` down to the ground, so that the piece doesn't float.
+    pub fn fill_downwards(&mut self, x: i32, y: i32, z: i32, state: &BlockState) {
+        let bottom_y = self.chunk_box.min.y - self.origin.y;
+        let mut y = y;
+        while y >= bottom_y
+            && self
+                .get(x, y, z)
+                .is_some_and(|below| below.is_air() || below.is_liquid())
+        {
+            self.set(x, y, z, state);
+            y -= 1;
+        }
+    }
+}
+
+/// Where a piece starts and which way it leads, for pieces that can be laid out facing any
+/// horizontal direction. They are laid out in local coordinates: `along` away from their
+/// entrance, `y` up from their floor and `cross` to the right, centered on `origin`, the middle of
+/// their entrance wall.
+#[derive(Clone, Copy)]
+pub(crate) struct Frame {
+    pub origin: Vector3<i32>,
+    /// The direction of `along` by x and z.
+    pub facing: (i32, i32),
 }
 
-pub fn fill_downwards(x: i32, y: i32, z: i32, state: &BlockState, chunk: &mut crate::ProtoChunk) {
-    for y in y..chunk.bottom_y() as i32 {
-        chunk.set_block_state(&Vector3::new(x, y, z), state);
+impl Frame {
+    /// The direction of `cross` by x and z.
+    pub fn cross(&self) -> (i32, i32) {
+        (-self.facing.1, self.facing.0)
+    }
+
+    pub fn to_relative(self, along: i32, y: i32, cross: i32) -> Vector3<i32> {
+        let (cross_x, cross_z) = self.cross();
+        Vector3::new(
+            self.facing.0 * along + cross_x * cross,
+            y,
+            self.facing.1 * along + cross_z * cross,
+        )
+    }
+
+    pub fn to_world(self, along: i32, y: i32, cross: i32) -> Vector3<i32> {
+        self.origin.add(&self.to_relative(along, y, cross))
+    }
+
+    /// The frame of a piece `length` blocks along and `width` blocks across facing `facing`,
+    /// placed so that its footprint starts at `corner` by x and z whichever way it faces.
+    pub fn at_corner(facing: (i32, i32), corner: (i32, i32), length: i32, width: i32) -> Self {
+        let frame = Self {
+            origin: Vector3::new(0, 0, 0),
+            facing,
+        };
+        let footprint = BlockBox::new(
+            frame.to_world(0, 0, 0),
+            frame.to_world(length - 1, 0, width - 1),
+        );
+        Self {
+            origin: Vector3::new(corner.0 - footprint.min.x, 0, corner.1 - footprint.min.z),
+            facing,
+        }
+    }
+
+    /// The frame of a piece right after `along` blocks of this one.
+    pub fn forward(self, along: i32) -> Self {
+        Self {
+            origin: self.to_world(along, 0, 0),
+            facing: self.facing,
+        }
+    }
+
+    /// The frame of a piece starting at `along` and `cross` of this one, leading away to the
+    /// right for a positive `side` and to the left for a negative one.
+    pub fn turned(self, along: i32, cross: i32, side: i32) -> Self {
+        let (cross_x, cross_z) = self.cross();
+        Self {
+            origin: self.to_world(along, 0, cross),
+            facing: (cross_x * side, cross_z * side),
+        }
+    }
+
+    pub fn horizontal_facing(direction: (i32, i32)) -> HorizontalFacing {
+        match direction {
+            (1, _) => HorizontalFacing::East,
+            (-1, _) => HorizontalFacing::West,
+            (_, 1) => HorizontalFacing::South,
+            _ => HorizontalFacing::North,
+        }
     }
 }
 
-#[derive(Clone)]
-pub struct StructurePosition {
-    pub position: BlockPos,
-    pub generator: StructurePiecesCollector,
+/// Writes the blocks of a piece in the local coordinates of its [`Frame`].
+pub(crate) struct FrameWriter<'a, 'b> {
+    pub writer: PieceWriter<'a, 'b>,
+    pub frame: Frame,
+}
+
+impl<'a, 'b> FrameWriter<'a, 'b> {
+    pub fn new(chunk: &'a mut ProtoChunk<'b>, chunk_box: &'a BlockBox, frame: Frame) -> Self {
+        Self {
+            writer: PieceWriter::new(chunk, chunk_box, frame.origin),
+            frame,
+        }
+    }
+
+    /// The block at a local position, if it is in the chunk.
+    pub fn get(&self, along: i32, y: i32, cross: i32) -> Option<&'static BlockState> {
+        let pos = self.frame.to_relative(along, y, cross);
+        self.writer.get(pos.x, pos.y, pos.z)
+    }
+
+    pub fn set(&mut self, along: i32, y: i32, cross: i32, state: &BlockState) {
+        let pos = self.frame.to_relative(along, y, cross);
+        self.writer.set(pos.x, pos.y, pos.z, state);
+    }
+
+    /// See [`PieceWriter::loot_chest`].
+    pub fn loot_chest(
+        &mut self,
+        along: i32,
+        y: i32,
+        cross: i32,
+        chest: &BlockState,
+        loot_table: &'static str,
+    ) {
+        let pos = self.frame.to_relative(along, y, cross);
+        self.writer
+            .loot_chest(pos.x, pos.y, pos.z, chest, loot_table);
+    }
+
+    /// See [`PieceWriter::fill_downwards`].
+    pub fn fill_downwards(&mut self, along: i32, y: i32, cross: i32, state: &BlockState) {
+        let pos = self.frame.to_relative(along, y, cross);
+        self.writer.fill_downwards(pos.x, pos.y, pos.z, state);
+    }
+
+    #[expect(clippy::too_many_arguments)]
+    pub fn fill(
+        &mut self,
+        min_along: i32,
+        min_y: i32,
+        min_cross: i32,
+        max_along: i32,
+        max_y: i32,
+        max_cross: i32,
+        state: &BlockState,
+    ) {
+        for along in min_along..=max_along {
+            for y in min_y..=max_y {
+                for cross in min_cross..=max_cross {
+                    self.set(along, y, cross, state);
+                }
+            }
+        }
+    }
 }
 
-#[derive(Default, Clone)]
-pub struct StructurePiecesCollector {
-    pub pieces_positions: Vec<BlockPos>,
+/// A number from 0 to 1 picked by the position of a block, so that every chunk a piece reaches
+/// into agrees on it.
+pub(crate) fn roll(pos: &Vector3<i32>) -> f32 {
+    (hash_block_pos(pos.x, pos.y, pos.z) & 0xFFFF) as f32 / 65536.0
 }
@@ -0,0 +1,158 @@
+use pumpkin_data::{Block, tag::Taggable};
+use pumpkin_util::{math::vector3::Vector3, random::RandomImpl};
+
+use crate::{
+    ProtoChunk,
+    generation::{
+        positions::chunk_pos,
+        structure::{
+            StartContext,
+            structures::{BlockBox, Frame, FrameWriter, StructureGenerator, StructurePiece},
+        },
+    },
+};
+
+/// The directions a monument can face, by x and z.
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (-1, 0), (0, -1), (1, 0)];
+
+/// How far the monument reaches from its middle, by x and z.
+const RADIUS: i32 = 29;
+
+/// How far the floor of the monument is below sea level.
+const DEPTH: i32 = 24;
+
+/// The biomes all of the water around a monument has to be in.
+const SURROUNDING_BIOMES: &str = "#minecraft:required_ocean_monument_surrounding";
+
+/// Lays out an ocean monument around the middle of the start chunk, facing a random direction.
+///
+/// Vanilla only builds a monument where every biome within its reach is ocean or river, so that
+/// it is never cut into a shore, and so does this. Vanilla lays out the inside from a graph of
+/// rooms; this one is a fixed shell with the same footprint instead: a lower story, a wing in each
+/// back corner and a tall middle building holding the gold.
+pub struct OceanMonumentGenerator;
+
+impl StructureGenerator for OceanMonumentGenerator {
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let x = chunk_pos::start_block_x(&context.start) + 9;
+        let z = chunk_pos::start_block_z(&context.start) + 9;
+        let sea_level = context.sea_level();
+        for offset_x in (-RADIUS..=RADIUS).step_by(4) {
+            for offset_z in (-RADIUS..=RADIUS).step_by(4) {
+                let biome = context.biome(&Vector3::new(x + offset_x, sea_level, z + offset_z));
+                if biome.is_tagged_with(SURROUNDING_BIOMES) != Some(true) {
+                    return None;
+                }
+            }
+        }
+
+        let facing = DIRECTIONS[context.random.next_bounded_i32(4) as usize];
+        let size = 2 * RADIUS;
+        let mut frame = Frame::at_corner(facing, (x - RADIUS, z - RADIUS), size, size);
+        frame.origin.y = sea_level - DEPTH;
+        let footprint = BlockBox::new(
+            frame.to_world(0, 0, 0),
+            frame.to_world(size - 1, 22, size - 1),
+        );
+        Some(vec![Box::new(OceanMonument {
+            bounding_box: BlockBox::new(
+                Vector3::new(footprint.min.x, context.bottom_y(), footprint.min.z),
+                footprint.max,
+            ),
+            frame,
+        })])
+    }
+}
+
+/// A whole monument. Its frame starts at the left corner of the entrance side, on the floor.
+struct OceanMonument {
+    bounding_box: BlockBox,
+    frame: Frame,
+}
+
+impl StructurePiece for OceanMonument {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let mut writer = FrameWriter::new(chunk, chunk_box, self.frame);
+        place_monument(&mut writer);
+    }
+}
+
+fn place_monument(writer: &mut FrameWriter) {
+    let prismarine = Block::PRISMARINE.default_state;
+    let bricks = Block::PRISMARINE_BRICKS.default_state;
+    let dark = Block::DARK_PRISMARINE.default_state;
+    let lantern = Block::SEA_LANTERN.default_state;
+    let water = Block::WATER.default_state;
+
+    // Anything of the ocean floor reaching into it is washed away, and it stands on prismarine
+    // down to the floor
+    writer.fill(0, 0, 0, 57, 22, 57, water);
+    for along in 0..=57 {
+        for cross in 0..=57 {
+            writer.fill_downwards(along, -1, cross, prismarine);
+        }
+    }
+
+    // The lower story, its roof held up by four pillars
+    writer.fill(0, 0, 0, 57, 0, 57, bricks);
+    writer.fill(0, 1, 0, 0, 7, 57, prismarine);
+    writer.fill(57, 1, 0, 57, 7, 57, prismarine);
+    writer.fill(0, 1, 0, 57, 7, 0, prismarine);
+    writer.fill(0, 1, 57, 57, 7, 57, prismarine);
+    writer.fill(0, 8, 0, 57, 8, 57, bricks);
+    for along in [8, 49] {
+        for cross in [8, 49] {
+            writer.fill(along, 1, cross, along, 7, cross, prismarine);
+        }
+    }
+    for i in (4..=53).step_by(7) {
+        writer.set(57, 4, i, lantern);
+        writer.set(i, 4, 0, lantern);
+        writer.set(i, 4, 57, lantern);
+    }
+    writer.fill(0, 1, 23, 0, 6, 34, water);
+    writer.fill(0, 7, 22, 0, 7, 35, dark);
+    for cross in [21, 36] {
+        writer.fill(0, 1, cross, 0, 7, cross, dark);
+        writer.set(0, 4, cross, lantern);
+    }
+
+    // A wing in each back corner, open to the story below
+    for cross in [0, 40] {
+        writer.fill(40, 9, cross, 57, 15, cross + 17, prismarine);
+        writer.fill(41, 8, cross + 1, 56, 15, cross + 16, water);
+        writer.fill(40, 16, cross, 57, 16, cross + 17, dark);
+        for (along, corner) in [(40, cross), (57, cross + 17)] {
+            writer.set(along, 16, corner, lantern);
+        }
+    }
+
+    // The middle building rises in steps above everything else
+    writer.fill(17, 1, 17, 40, 18, 40, bricks);
+    writer.fill(18, 1, 18, 39, 18, 39, water);
+    writer.fill(17, 1, 26, 17, 5, 31, water);
+    for (inset, y) in [(0, 19), (2, 20), (4, 21), (6, 22)] {
+        writer.fill(17 + inset, y, 17 + inset, 40 - inset, y, 40 - inset, dark);
+    }
+    for along in [17, 40] {
+        for cross in [17, 40] {
+            writer.set(along, 19, cross, lantern);
+        }
+    }
+
+    // The chamber in its middle, on four posts, holds eight blocks of gold
+    for along in [25, 32] {
+        for cross in [25, 32] {
+            writer.fill(along, 1, cross, along, 5, cross, bricks);
+        }
+    }
+    writer.fill(25, 6, 25, 32, 12, 32, dark);
+    writer.fill(26, 7, 26, 31, 11, 31, water);
+    writer.fill(25, 7, 28, 25, 9, 29, water);
+    writer.fill(28, 8, 28, 29, 9, 29, Block::GOLD_BLOCK.default_state);
+    writer.fill(28, 12, 28, 29, 12, 29, lantern);
+}
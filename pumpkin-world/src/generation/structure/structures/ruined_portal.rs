@@ -0,0 +1,252 @@
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{BlockProperties, ChestLikeProperties, ChestType},
+};
+use pumpkin_util::{
+    HeightMap,
+    math::{vector2::Vector2, vector3::Vector3},
+    random::{RandomGenerator, RandomImpl, legacy_rand::LegacyRand},
+};
+use serde::Deserialize;
+
+use crate::{
+    ProtoChunk,
+    generation::{
+        height_limit::HeightLimitView,
+        positions::chunk_pos,
+        structure::{
+            STRUCTURES, StartContext,
+            structures::{BlockBox, Frame, FrameWriter, StructureGenerator, StructurePiece},
+        },
+    },
+};
+
+const LOOT_TABLE: &str = "minecraft:chests/ruined_portal";
+
+/// The ruined portals, one kind for each group of biomes they are found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RuinedPortalType {
+    Standard,
+    Desert,
+    Jungle,
+    Swamp,
+    Mountain,
+    Ocean,
+    Nether,
+}
+
+impl RuinedPortalType {
+    /// The name of the structure of this kind, which holds its setups.
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Standard => "ruined_portal",
+            Self::Desert => "ruined_portal_desert",
+            Self::Jungle => "ruined_portal_jungle",
+            Self::Swamp => "ruined_portal_swamp",
+            Self::Mountain => "ruined_portal_mountain",
+            Self::Ocean => "ruined_portal_ocean",
+            Self::Nether => "ruined_portal_nether",
+        }
+    }
+}
+
+/// Where a ruined portal is set into the world.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PortalPlacement {
+    OnLandSurface,
+    PartlyBuried,
+    OnOceanFloor,
+    InMountain,
+    Underground,
+    InNether,
+}
+
+/// One of the ways a kind of ruined portal can be set into the world, picked by its `weight`.
+///
+/// The vines and overgrowth of the jungle and swamp portals are left out, and so is the snow of
+/// the cold ones.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct RuinedPortalSetup {
+    placement: PortalPlacement,
+    weight: f32,
+    air_pocket_probability: f32,
+    mossiness: f32,
+    replace_with_blackstone: bool,
+}
+
+/// Lays out a ruined portal somewhere in the middle of the start chunk, in one of the setups of
+/// its kind.
+///
+/// Vanilla builds ruined portals from templates; this one is a small frame of obsidian with gaps
+/// in it, on a patch of netherrack, with a loot chest and sometimes a block of gold beside it.
+/// It always fits into the start chunk, so it is set into the world by the ground of that chunk
+/// when it is placed.
+impl StructureGenerator for RuinedPortalType {
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let setups = &STRUCTURES.get(self.name())?.setups;
+        let total_weight: f32 = setups.iter().map(|setup| setup.weight).sum();
+        let mut target = context.random.next_f32() * total_weight;
+        let setup = *setups
+            .iter()
+            .find(|setup| {
+                target -= setup.weight;
+                target < 0.0
+            })
+            .or(setups.last())?;
+
+        // The frame stands along x or z, and the netherrack around it stays in the chunk
+        let facing = if context.random.next_bool() {
+            (1, 0)
+        } else {
+            (0, 1)
+        };
+        let x = chunk_pos::start_block_x(&context.start) + 3 + context.random.next_bounded_i32(8);
+        let z = chunk_pos::start_block_z(&context.start) + 3 + context.random.next_bounded_i32(8);
+        Some(vec![Box::new(RuinedPortal {
+            bounding_box: BlockBox::of_chunk(
+                context.start,
+                context.bottom_y(),
+                context.top_y() - 1,
+            ),
+            frame: Frame {
+                origin: Vector3::new(x, 0, z),
+                facing,
+            },
+            setup,
+            seed: context.random.next_i64(),
+        })])
+    }
+}
+
+/// A whole ruined portal. Its frame starts at the bottom of one side of the obsidian, leading
+/// along it; its height is only picked when it is placed.
+struct RuinedPortal {
+    bounding_box: BlockBox,
+    frame: Frame,
+    setup: RuinedPortalSetup,
+    /// Seeds the random the portal decays with, so that it does the same every time.
+    seed: i64,
+}
+
+impl StructurePiece for RuinedPortal {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let mut random = RandomGenerator::Legacy(LegacyRand::from_seed(self.seed as u64));
+        let middle = self.frame.to_world(1, 0, 0);
+        let floor = chunk.get_top_y(&HeightMap::OceanFloorWg, &Vector2::new(middle.x, middle.z));
+        let mut frame = self.frame;
+        frame.origin.y = match self.setup.placement {
+            PortalPlacement::OnLandSurface | PortalPlacement::OnOceanFloor => floor,
+            PortalPlacement::PartlyBuried => floor - 2 - random.next_bounded_i32(3),
+            PortalPlacement::InMountain => between(&mut random, 70, floor - 15),
+            PortalPlacement::Underground => {
+                between(&mut random, chunk.bottom_y() as i32 + 15, floor - 15)
+            }
+            PortalPlacement::InNether => {
+                let mut y = between(&mut random, 32, 100);
+                let mut pos = Vector3::new(middle.x, y - 1, middle.z);
+                while pos.y > 31 && chunk.get_block_state(&pos).to_state().is_air() {
+                    y -= 1;
+                    pos.y -= 1;
+                }
+                y
+            }
+        };
+
+        let mut writer = FrameWriter::new(chunk, chunk_box, frame);
+        place_portal(&mut writer, &self.setup, &mut random);
+    }
+}
+
+/// A random height from `min` to `max`, or `min` if there is no room between them.
+fn between(random: &mut RandomGenerator, min: i32, max: i32) -> i32 {
+    if max <= min {
+        min
+    } else {
+        min + random.next_bounded_i32(max - min + 1)
+    }
+}
+
+fn place_portal(writer: &mut FrameWriter, setup: &RuinedPortalSetup, random: &mut RandomGenerator) {
+    let underwater = setup.placement == PortalPlacement::OnOceanFloor;
+    let air = Block::AIR.default_state;
+    if random.next_f32() < setup.air_pocket_probability {
+        writer.fill(-1, 0, -1, 4, 5, 1, air);
+    }
+
+    // The ground around the portal turned to netherrack, thinning out away from it
+    for along in -2..=5 {
+        for cross in -3..=3 {
+            let distance = (along as f32 - 1.5).hypot(cross as f32);
+            if random.next_f32() >= 1.0 - distance / 4.0 {
+                continue;
+            }
+            if !writer
+                .get(along, -1, cross)
+                .is_some_and(|ground| !ground.is_air() && !ground.is_liquid())
+            {
+                continue;
+            }
+            let block = if random.next_f32() < 0.1 {
+                &Block::MAGMA_BLOCK
+            } else {
+                &Block::NETHERRACK
+            };
+            writer.set(along, -1, cross, block.default_state);
+        }
+    }
+
+    // What is left of the floor the portal was built on
+    for along in -1..=4 {
+        for cross in -1..=1 {
+            let block = match (setup.replace_with_blackstone, random.next_f32()) {
+                (true, roll) if roll < 0.2 => &Block::CRACKED_POLISHED_BLACKSTONE_BRICKS,
+                (true, _) => &Block::POLISHED_BLACKSTONE_BRICKS,
+                (false, roll) if roll < setup.mossiness => &Block::MOSSY_STONE_BRICKS,
+                (false, roll) if roll < setup.mossiness + 0.2 => &Block::CRACKED_STONE_BRICKS,
+                (false, _) => &Block::STONE_BRICKS,
+            };
+            writer.set(along, -1, cross, block.default_state);
+            writer.fill_downwards(along, -2, cross, block.default_state);
+        }
+    }
+
+    // The frame, more of it missing the higher up it is
+    for along in 0..=3 {
+        for y in 0..=4 {
+            if (1..=2).contains(&along) && (1..=3).contains(&y) {
+                if !underwater {
+                    writer.set(along, y, 0, air);
+                }
+                continue;
+            }
+            if random.next_f32() < 0.1 + 0.05 * y as f32 {
+                continue;
+            }
+            let block = if random.next_f32() < 0.1 {
+                &Block::CRYING_OBSIDIAN
+            } else {
+                &Block::OBSIDIAN
+            };
+            writer.set(along, y, 0, block.default_state);
+        }
+    }
+
+    if random.next_f32() < 0.5 {
+        let along = random.next_bounded_i32(4);
+        writer.set(along, 0, 1, Block::GOLD_BLOCK.default_state);
+    }
+    let along = if random.next_bool() { -1 } else { 4 };
+    let direction = writer.frame.cross();
+    let props = ChestLikeProperties {
+        r#facing: Frame::horizontal_facing((-direction.0, -direction.1)),
+        r#type: ChestType::Single,
+        r#waterlogged: underwater,
+    };
+    let chest = BlockState::from_id(props.to_state_id(&Block::CHEST));
+    writer.loot_chest(along, 0, -1, chest, LOOT_TABLE);
+}
@@ -0,0 +1,392 @@
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{BlockProperties, EndPortalFrameLikeProperties},
+};
+use pumpkin_util::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::RandomImpl,
+};
+
+use crate::{
+    ProtoChunk,
+    generation::{
+        positions::chunk_pos,
+        structure::{
+            StartContext,
+            structures::{BlockBox, Frame, FrameWriter, StructureGenerator, StructurePiece, roll},
+        },
+    },
+};
+
+/// The height the stronghold is laid out at before it is moved below sea level, like vanilla.
+const LAYOUT_TOP_Y: i32 = 64;
+/// How far below sea level the top of the stronghold is at least.
+const SEA_LEVEL_PENALTY: i32 = 10;
+/// The number of eyes of ender filling the frames of a portal, one per frame.
+const PORTAL_FRAMES: usize = 12;
+
+/// Lays out a stronghold: a spiral staircase in the start chunk leading down to corridors and
+/// crossings, some of which branch off into libraries, ending in the portal room. The rooms line
+/// up towards the world origin, so that the portal room points at it.
+///
+/// Vanilla picks every next room at random from a weighted list of pieces; this only knows a few
+/// of them and follows a fixed order, picking their sizes and branches at random.
+pub struct StrongholdGenerator;
+
+impl StructureGenerator for StrongholdGenerator {
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let start = context.start;
+        let center = Vector2::new(
+            chunk_pos::start_block_x(&start) + 4,
+            chunk_pos::start_block_z(&start) + 4,
+        );
+        let facing = if start.x.abs() >= start.y.abs() {
+            (if start.x > 0 { -1 } else { 1 }, 0)
+        } else {
+            (0, if start.y > 0 { -1 } else { 1 })
+        };
+        let mut stairs = Frame {
+            origin: Vector3::new(
+                center.x,
+                LAYOUT_TOP_Y - RoomKind::STAIRCASE_HEIGHT + 1,
+                center.y,
+            ),
+            facing,
+        };
+        stairs.origin = stairs.forward(-2).origin;
+
+        let random = &mut context.random;
+        let mut pieces = vec![StrongholdPiece {
+            frame: stairs,
+            room: RoomKind::SpiralStaircase,
+        }];
+        let mut cursor = stairs.forward(RoomKind::SpiralStaircase.length());
+        for _ in 0..random.next_inbetween_i32(2, 4) {
+            let length = random.next_inbetween_i32(3, 7);
+            pieces.push(StrongholdPiece {
+                frame: cursor,
+                room: RoomKind::Corridor { length },
+            });
+            cursor = cursor.forward(length);
+            let crossing = cursor;
+            pieces.push(StrongholdPiece {
+                frame: crossing,
+                room: RoomKind::Crossing,
+            });
+            cursor = cursor.forward(RoomKind::Crossing.length());
+
+            for side in [1, -1] {
+                if !random.next_bool() {
+                    continue;
+                }
+                let corridor = crossing.turned(
+                    RoomKind::Crossing.length() / 2,
+                    side * (RoomKind::CROSSING_HALF + 1),
+                    side,
+                );
+                let length = random.next_inbetween_i32(3, 5);
+                let branch = [
+                    StrongholdPiece {
+                        frame: corridor,
+                        room: RoomKind::Corridor { length },
+                    },
+                    StrongholdPiece {
+                        frame: corridor.forward(length),
+                        room: RoomKind::Library,
+                    },
+                ];
+                // Branches of crossings close to each other could run into each other
+                if branch.iter().all(|new| {
+                    pieces
+                        .iter()
+                        .all(|piece| !piece.bounding_box().intersects(&new.bounding_box()))
+                }) {
+                    pieces.extend(branch);
+                }
+            }
+        }
+        let length = random.next_inbetween_i32(3, 7);
+        pieces.push(StrongholdPiece {
+            frame: cursor,
+            room: RoomKind::Corridor { length },
+        });
+        pieces.push(StrongholdPiece {
+            frame: cursor.forward(length),
+            room: RoomKind::PortalRoom,
+        });
+
+        // Moves it below sea level
+        let bottom = pieces
+            .iter()
+            .map(|piece| piece.bounding_box().min.y)
+            .min()?;
+        let top = pieces
+            .iter()
+            .map(|piece| piece.bounding_box().max.y)
+            .max()?;
+        let shift = context.below_sea_level_shift(bottom, top, SEA_LEVEL_PENALTY);
+        Some(
+            pieces
+                .into_iter()
+                .map(|mut piece| {
+                    piece.frame.origin.y += shift;
+                    Box::new(piece) as Box<dyn StructurePiece>
+                })
+                .collect(),
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+enum RoomKind {
+    SpiralStaircase,
+    Corridor { length: i32 },
+    Crossing,
+    Library,
+    PortalRoom,
+}
+
+impl RoomKind {
+    const STAIRCASE_HEIGHT: i32 = 11;
+    const CROSSING_HALF: i32 = 3;
+
+    fn length(&self) -> i32 {
+        match self {
+            Self::SpiralStaircase => 5,
+            Self::Corridor { length } => *length,
+            Self::Crossing => 2 * Self::CROSSING_HALF + 1,
+            Self::Library => 14,
+            Self::PortalRoom => 16,
+        }
+    }
+
+    /// How far the room reaches to each side of its entrance.
+    fn half_width(&self) -> i32 {
+        match self {
+            Self::SpiralStaircase | Self::Corridor { .. } => 2,
+            Self::Crossing => Self::CROSSING_HALF,
+            Self::Library | Self::PortalRoom => 5,
+        }
+    }
+
+    fn height(&self) -> i32 {
+        match self {
+            Self::SpiralStaircase => Self::STAIRCASE_HEIGHT,
+            Self::Corridor { .. } | Self::Crossing => 5,
+            Self::Library => 6,
+            Self::PortalRoom => 8,
+        }
+    }
+}
+
+struct StrongholdPiece {
+    frame: Frame,
+    room: RoomKind,
+}
+
+impl StructurePiece for StrongholdPiece {
+    fn bounding_box(&self) -> BlockBox {
+        let half_width = self.room.half_width();
+        BlockBox::new(
+            self.frame.to_world(0, 0, -half_width),
+            self.frame
+                .to_world(self.room.length() - 1, self.room.height() - 1, half_width),
+        )
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let mut writer = FrameWriter::new(chunk, chunk_box, self.frame);
+        let (length, half_width, height) = (
+            self.room.length(),
+            self.room.half_width(),
+            self.room.height(),
+        );
+        hollow(&mut writer, length, half_width, height);
+        match self.room {
+            RoomKind::SpiralStaircase => place_spiral_staircase(&mut writer),
+            RoomKind::Corridor { .. } => {
+                doorway(&mut writer, 0);
+                doorway(&mut writer, length - 1);
+            }
+            RoomKind::Crossing => {
+                doorway(&mut writer, 0);
+                doorway(&mut writer, length - 1);
+                // The side openings, where branches may lead off
+                let middle = length / 2;
+                for cross in [-half_width, half_width] {
+                    writer.fill(middle - 1, 1, cross, middle + 1, 3, cross, air());
+                }
+                writer.set(1, 1, half_width - 1, Block::TORCH.default_state);
+            }
+            RoomKind::Library => place_library(&mut writer, length, half_width, height),
+            RoomKind::PortalRoom => place_portal_room(&mut writer, length, half_width),
+        }
+    }
+}
+
+fn air() -> &'static BlockState {
+    Block::AIR.default_state
+}
+
+/// A stone brick of the walls, cracked, mossy or infested in places like in vanilla.
+fn brick(writer: &mut FrameWriter, along: i32, y: i32, cross: i32) {
+    let chance = roll(&writer.frame.to_world(along, y, cross));
+    let block = if chance < 0.2 {
+        &Block::CRACKED_STONE_BRICKS
+    } else if chance < 0.5 {
+        &Block::MOSSY_STONE_BRICKS
+    } else if chance < 0.55 {
+        &Block::INFESTED_STONE_BRICKS
+    } else {
+        &Block::STONE_BRICKS
+    };
+    writer.set(along, y, cross, block.default_state);
+}
+
+/// Walls, floor and ceiling of stone bricks around air.
+fn hollow(writer: &mut FrameWriter, length: i32, half_width: i32, height: i32) {
+    for along in 0..length {
+        for y in 0..height {
+            for cross in -half_width..=half_width {
+                if along == 0
+                    || along == length - 1
+                    || y == 0
+                    || y == height - 1
+                    || cross.abs() == half_width
+                {
+                    brick(writer, along, y, cross);
+                } else {
+                    writer.set(along, y, cross, air());
+                }
+            }
+        }
+    }
+}
+
+/// An opening through the wall at `along`, where the next room connects.
+fn doorway(writer: &mut FrameWriter, along: i32) {
+    writer.fill(along, 1, -1, along, 3, 1, air());
+}
+
+/// Stairs winding around a pillar down to the floor, leaving through the far wall.
+fn place_spiral_staircase(writer: &mut FrameWriter) {
+    let length = RoomKind::SpiralStaircase.length();
+    doorway(writer, length - 1);
+    writer.fill(
+        2,
+        1,
+        0,
+        2,
+        RoomKind::STAIRCASE_HEIGHT - 2,
+        0,
+        Block::STONE_BRICKS.default_state,
+    );
+    // The blocks around the pillar, one lower with every step
+    let around = [
+        (1, -1),
+        (1, 0),
+        (1, 1),
+        (2, 1),
+        (3, 1),
+        (3, 0),
+        (3, -1),
+        (2, -1),
+    ];
+    for step in 0..RoomKind::STAIRCASE_HEIGHT - 3 {
+        let (along, cross) = around[step as usize % around.len()];
+        writer.set(
+            along,
+            RoomKind::STAIRCASE_HEIGHT - 3 - step,
+            cross,
+            Block::STONE_BRICK_SLAB.default_state,
+        );
+    }
+}
+
+/// Bookshelves along the walls between wooden pillars, with cobwebs below the ceiling.
+fn place_library(writer: &mut FrameWriter, length: i32, half_width: i32, height: i32) {
+    doorway(writer, 0);
+    for along in 1..length - 1 {
+        for cross in [-(half_width - 1), half_width - 1] {
+            let block = if along % 4 == 0 {
+                &Block::OAK_PLANKS
+            } else {
+                &Block::BOOKSHELF
+            };
+            writer.fill(
+                along,
+                1,
+                cross,
+                along,
+                height - 3,
+                cross,
+                block.default_state,
+            );
+        }
+        for cross in 2 - half_width..=half_width - 2 {
+            if roll(&writer.frame.to_world(along, height - 2, cross)) < 0.1 {
+                writer.set(along, height - 2, cross, Block::COBWEB.default_state);
+            }
+        }
+    }
+    writer.set(1, 1, 0, Block::TORCH.default_state);
+}
+
+/// Stairs up to a platform with the end portal, over a pool of lava and guarded by a spawner,
+/// with lava on both sides of the way in.
+fn place_portal_room(writer: &mut FrameWriter, length: i32, half_width: i32) {
+    doorway(writer, 0);
+    let bricks = Block::STONE_BRICKS.default_state;
+    for side in [-1, 1] {
+        writer.fill(
+            1,
+            1,
+            side * 2,
+            3,
+            1,
+            side * (half_width - 1),
+            Block::LAVA.default_state,
+        );
+    }
+    for step in 0..3 {
+        writer.fill(4 + step, 1, -1, 4 + step, 1 + step, 1, bricks);
+    }
+    writer.fill(7, 1, 1 - half_width, length - 2, 3, half_width - 1, bricks);
+
+    // The frames around the portal face into it
+    let portal = (9, 11);
+    writer.fill(portal.0, 3, -1, portal.1, 3, 1, Block::LAVA.default_state);
+    let mut frames = Vec::with_capacity(PORTAL_FRAMES);
+    for offset in -1..=1 {
+        let middle = (portal.0 + portal.1) / 2;
+        frames.push((portal.0 - 1, offset, (1, 0)));
+        frames.push((portal.1 + 1, offset, (-1, 0)));
+        frames.push((middle + offset, -2, (0, 1)));
+        frames.push((middle + offset, 2, (0, -1)));
+    }
+    let mut eyes = 0;
+    for (along, cross, (facing_along, facing_cross)) in frames {
+        let facing = writer.frame.to_relative(facing_along, 0, facing_cross);
+        let eye = roll(&writer.frame.to_world(along, 4, cross)) < 0.1;
+        eyes += usize::from(eye);
+        let props = EndPortalFrameLikeProperties {
+            eye,
+            facing: Frame::horizontal_facing((facing.x, facing.z)),
+        };
+        let state = BlockState::from_id(props.to_state_id(&Block::END_PORTAL_FRAME));
+        writer.set(along, 4, cross, state);
+    }
+    if eyes == PORTAL_FRAMES {
+        writer.fill(
+            portal.0,
+            4,
+            -1,
+            portal.1,
+            4,
+            1,
+            Block::END_PORTAL.default_state,
+        );
+    }
+    // TODO: Spawn silverfish once spawners get block entities
+    writer.set(7, 4, 0, Block::SPAWNER.default_state);
+}
@@ -0,0 +1,333 @@
+use pumpkin_data::{
+    Block, BlockState,
+    block_properties::{
+        BlockFace, BlockProperties, ChestLikeProperties, ChestType, DispenserLikeProperties,
+        Facing, HorizontalFacing, LeverLikeProperties, TripwireHookLikeProperties,
+        TripwireLikeProperties,
+    },
+};
+use pumpkin_util::{math::vector3::Vector3, random::RandomImpl};
+
+use crate::{
+    ProtoChunk,
+    generation::{
+        positions::chunk_pos,
+        structure::{
+            StartContext,
+            structures::{
+                BlockBox, Frame, FrameWriter, StructureGenerator, StructurePiece, roll,
+                village::floor_height,
+            },
+        },
+    },
+};
+
+/// The directions a temple can face, by x and z.
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (-1, 0), (0, -1), (1, 0)];
+
+/// How far [`StartContext::estimate_height`] can be below the ground, the height of a noise cell.
+const ESTIMATE_ERROR: i32 = 8;
+
+/// The temples standing on their own in the desert and in the jungle, each a single piece.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TempleType {
+    Desert,
+    Jungle,
+}
+
+impl TempleType {
+    /// How far the temple reaches away from its entrance, up from its floor and across it.
+    fn size(&self) -> (i32, i32, i32) {
+        match self {
+            Self::Desert => (21, 15, 21),
+            Self::Jungle => (15, 10, 12),
+        }
+    }
+
+    /// The loot table the chests of the temple are filled from.
+    pub fn loot_table(&self) -> &'static str {
+        match self {
+            Self::Desert => "minecraft:chests/desert_pyramid",
+            Self::Jungle => "minecraft:chests/jungle_temple",
+        }
+    }
+}
+
+/// Lays out a temple in the corner of the start chunk, facing a random direction. Like in vanilla,
+/// temples are only built on land.
+///
+/// Jungle temples fit into the start chunk and stand on its ground. Desert pyramids reach into the
+/// chunks next to it, so they are built at the estimated height of the ground below their corners
+/// instead, their base sunk into the sand.
+impl StructureGenerator for TempleType {
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let (length, _, width) = self.size();
+        let facing = DIRECTIONS[context.random.next_bounded_i32(4) as usize];
+        // The temple covers the same square of the chunk whichever way it faces
+        let corner = (
+            chunk_pos::start_block_x(&context.start),
+            chunk_pos::start_block_z(&context.start),
+        );
+        let mut frame = Frame::at_corner(facing, corner, length, width);
+        let footprint = BlockBox::new(
+            frame.to_world(0, 0, 0),
+            frame.to_world(length - 1, 0, width - 1),
+        );
+        let (min, max) = (footprint.min, footprint.max);
+
+        let mut total = 0;
+        for (x, z) in [
+            (min.x, min.z),
+            (min.x, max.z),
+            (max.x, min.z),
+            (max.x, max.z),
+        ] {
+            let height = context.estimate_height(x, z);
+            if height + ESTIMATE_ERROR <= context.sea_level() {
+                return None;
+            }
+            total += height;
+        }
+        frame.origin.y = total / 4;
+
+        Some(vec![Box::new(Temple {
+            bounding_box: BlockBox::new(
+                Vector3::new(min.x, context.bottom_y(), min.z),
+                Vector3::new(max.x, context.top_y() - 1, max.z),
+            ),
+            frame,
+            temple_type: *self,
+        })])
+    }
+}
+
+/// A whole temple. Its frame starts at the left corner of the entrance side, on the floor.
+struct Temple {
+    bounding_box: BlockBox,
+    frame: Frame,
+    temple_type: TempleType,
+}
+
+impl StructurePiece for Temple {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let mut frame = self.frame;
+        if self.temple_type == TempleType::Jungle {
+            let Some(floor) = floor_height(chunk, &self.bounding_box) else {
+                return;
+            };
+            frame.origin.y = floor;
+        }
+        let mut writer = FrameWriter::new(chunk, chunk_box, frame);
+        match self.temple_type {
+            TempleType::Desert => place_desert_pyramid(&mut writer),
+            TempleType::Jungle => place_jungle_temple(&mut writer),
+        }
+    }
+}
+
+fn air() -> &'static BlockState {
+    Block::AIR.default_state
+}
+
+/// The way a direction in the local coordinates of `writer` faces in the world.
+fn facing(writer: &FrameWriter, along: i32, cross: i32) -> HorizontalFacing {
+    let direction = writer.frame.to_relative(along, 0, cross);
+    Frame::horizontal_facing((direction.x, direction.z))
+}
+
+/// A chest facing `towards` in the local coordinates of `writer`, filled from `loot_table` later.
+fn loot_chest(
+    writer: &mut FrameWriter,
+    (along, y, cross): (i32, i32, i32),
+    towards: (i32, i32),
+    loot_table: &'static str,
+) {
+    let props = ChestLikeProperties {
+        r#facing: facing(writer, towards.0, towards.1),
+        r#type: ChestType::Single,
+        r#waterlogged: false,
+    };
+    let chest = BlockState::from_id(props.to_state_id(&Block::CHEST));
+    writer.loot_chest(along, y, cross, chest, loot_table);
+}
+
+/// A stepped pyramid of sandstone with a tower on each side of the entrance. Below the middle of
+/// its floor, a chamber holds four chests around a pressure plate wired to TNT.
+fn place_desert_pyramid(writer: &mut FrameWriter) {
+    let sandstone = Block::SANDSTONE.default_state;
+    let cut_sandstone = Block::CUT_SANDSTONE.default_state;
+
+    for along in 0..=20 {
+        for cross in 0..=20 {
+            writer.fill_downwards(along, -5, cross, sandstone);
+        }
+    }
+    writer.fill(0, -4, 0, 20, 0, 20, sandstone);
+    for step in 1..=9 {
+        writer.fill(step, step, step, 20 - step, step, 20 - step, sandstone);
+        writer.fill(step + 1, step, step + 1, 19 - step, step, 19 - step, air());
+    }
+
+    // Hollow towers open to the sky, trimmed at the front
+    for cross in [0, 16] {
+        writer.fill(0, 1, cross, 4, 10, cross + 4, sandstone);
+        writer.fill(1, 1, cross + 1, 3, 10, cross + 3, air());
+        writer.fill(0, 10, cross, 4, 10, cross, cut_sandstone);
+    }
+    writer.fill(0, 1, 8, 4, 4, 12, sandstone);
+    writer.fill(0, 1, 9, 4, 3, 11, air());
+    writer.fill(0, 4, 8, 0, 4, 12, cut_sandstone);
+
+    // The colored floor marks the way down
+    writer.fill(9, 0, 9, 11, 0, 11, Block::ORANGE_TERRACOTTA.default_state);
+    writer.set(10, 0, 10, Block::BLUE_TERRACOTTA.default_state);
+
+    writer.fill(6, -14, 6, 14, -1, 14, cut_sandstone);
+    writer.fill(8, -11, 8, 12, -8, 12, air());
+    writer.fill(10, -7, 10, 10, -1, 10, air());
+    writer.fill(9, -13, 9, 11, -13, 11, Block::TNT.default_state);
+    writer.set(10, -11, 10, Block::STONE_PRESSURE_PLATE.default_state);
+    for (pos, towards) in [
+        ((8, -11, 10), (1, 0)),
+        ((12, -11, 10), (-1, 0)),
+        ((10, -11, 8), (0, 1)),
+        ((10, -11, 12), (0, -1)),
+    ] {
+        loot_chest(writer, pos, towards, TempleType::Desert.loot_table());
+    }
+}
+
+/// Fills a box with cobblestone, two in five of its blocks mossy.
+fn cobblestone(
+    writer: &mut FrameWriter,
+    min_along: i32,
+    min_y: i32,
+    min_cross: i32,
+    max_along: i32,
+    max_y: i32,
+    max_cross: i32,
+) {
+    for along in min_along..=max_along {
+        for y in min_y..=max_y {
+            for cross in min_cross..=max_cross {
+                let block = if roll(&writer.frame.to_world(along, y, cross)) < 0.4 {
+                    &Block::MOSSY_COBBLESTONE
+                } else {
+                    &Block::COBBLESTONE
+                };
+                writer.set(along, y, cross, block.default_state);
+            }
+        }
+    }
+}
+
+/// A temple of mossy cobblestone with a second story. Steps lead down into the basement, where a
+/// tripwire arms a dispenser in front of one chest and three levers wait beside another.
+fn place_jungle_temple(writer: &mut FrameWriter) {
+    let loot_table = TempleType::Jungle.loot_table();
+
+    for along in 0..=14 {
+        for cross in 0..=11 {
+            writer.fill_downwards(along, -5, cross, Block::COBBLESTONE.default_state);
+        }
+    }
+    cobblestone(writer, 0, -4, 0, 14, 5, 11);
+    writer.fill(1, -3, 1, 13, -1, 10, air());
+    writer.fill(1, 1, 1, 13, 4, 10, air());
+    cobblestone(writer, 2, 6, 2, 12, 9, 9);
+    writer.fill(3, 6, 3, 11, 8, 8, air());
+
+    writer.fill(0, 1, 5, 0, 3, 6, air());
+    for along in [4, 10] {
+        writer.set(along, 2, 0, air());
+        writer.set(along, 2, 11, air());
+        writer.set(along, 7, 2, air());
+        writer.set(along, 7, 9, air());
+    }
+
+    // Steps up to the second story and down into the basement
+    let steps = |writer: &mut FrameWriter, along: i32, min_y: i32, max_y: i32, cross: i32| {
+        cobblestone(writer, along, min_y, cross, along, max_y, cross + 1);
+    };
+    for (along, top) in [(12, 1), (11, 2), (10, 3), (9, 4)] {
+        steps(writer, along, 1, top, 3);
+    }
+    writer.fill(9, 5, 3, 11, 5, 4, air());
+    writer.fill(9, 0, 8, 11, 0, 9, air());
+    for (along, top) in [(9, -1), (10, -2), (11, -3)] {
+        steps(writer, along, -3, top, 8);
+    }
+
+    // A wall splits the basement, leaving room to pass at the back
+    cobblestone(writer, 1, -3, 6, 11, -1, 6);
+
+    // The corridor along the wall is trapped: a dispenser in the front wall shoots at whoever
+    // trips the wire
+    let across = facing(writer, 0, 1);
+    let hook = |hook_facing| {
+        let props = TripwireHookLikeProperties {
+            r#attached: true,
+            r#facing: hook_facing,
+            r#powered: false,
+        };
+        BlockState::from_id(props.to_state_id(&Block::TRIPWIRE_HOOK))
+    };
+    writer.set(8, -3, 1, hook(across));
+    writer.set(8, -3, 5, hook(across.opposite()));
+    let along_x = matches!(across, HorizontalFacing::East | HorizontalFacing::West);
+    let wire = TripwireLikeProperties {
+        r#attached: true,
+        r#disarmed: false,
+        r#east: along_x,
+        r#north: !along_x,
+        r#powered: false,
+        r#south: !along_x,
+        r#west: along_x,
+    };
+    writer.fill(
+        8,
+        -3,
+        2,
+        8,
+        -3,
+        4,
+        BlockState::from_id(wire.to_state_id(&Block::TRIPWIRE)),
+    );
+    // TODO: Fill it from the jungle_temple_dispenser loot table once dispensers get block entities
+    let dispenser = DispenserLikeProperties {
+        r#facing: to_facing(facing(writer, 1, 0)),
+        r#triggered: false,
+    };
+    writer.set(
+        0,
+        -2,
+        3,
+        BlockState::from_id(dispenser.to_state_id(&Block::DISPENSER)),
+    );
+    loot_chest(writer, (1, -3, 1), (1, 0), loot_table);
+
+    // The levers on the back wall; the pistons vanilla opens the hidden chest with are left out
+    let lever = LeverLikeProperties {
+        r#face: BlockFace::Wall,
+        r#facing: facing(writer, -1, 0),
+        r#powered: false,
+    };
+    let lever = BlockState::from_id(lever.to_state_id(&Block::LEVER));
+    for cross in [7, 8, 10] {
+        writer.set(13, -2, cross, lever);
+    }
+    loot_chest(writer, (4, -3, 7), (0, 1), loot_table);
+}
+
+fn to_facing(facing: HorizontalFacing) -> Facing {
+    match facing {
+        HorizontalFacing::North => Facing::North,
+        HorizontalFacing::East => Facing::East,
+        HorizontalFacing::South => Facing::South,
+        HorizontalFacing::West => Facing::West,
+    }
+}
@@ -0,0 +1,370 @@
+use pumpkin_data::{Block, BlockState};
+use pumpkin_util::{
+    math::{vector2::Vector2, vector3::Vector3},
+    random::RandomImpl,
+};
+
+use crate::{
+    ProtoChunk,
+    generation::{
+        positions::chunk_pos,
+        structure::{
+            StartContext,
+            structures::{BlockBox, PieceWriter, StructureGenerator, StructurePiece},
+        },
+    },
+};
+
+/// How many chunks a street reaches out of the chunk of the well at most.
+const MAX_STREET_CHUNKS: i32 = 3;
+
+/// The directions streets lead away from the well in, by x and z.
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// The blocks the buildings of a village are made of.
+pub struct Palette {
+    pub walls: &'static BlockState,
+    pub corners: &'static BlockState,
+    pub foundation: &'static BlockState,
+    pub path: &'static BlockState,
+    pub roof: &'static BlockState,
+}
+
+const PLAINS: Palette = Palette {
+    walls: Block::OAK_PLANKS.default_state,
+    corners: Block::OAK_LOG.default_state,
+    foundation: Block::COBBLESTONE.default_state,
+    path: Block::DIRT_PATH.default_state,
+    roof: Block::OAK_SLAB.default_state,
+};
+
+const DESERT: Palette = Palette {
+    walls: Block::SMOOTH_SANDSTONE.default_state,
+    corners: Block::CUT_SANDSTONE.default_state,
+    foundation: Block::SANDSTONE.default_state,
+    path: Block::SMOOTH_SANDSTONE.default_state,
+    roof: Block::SMOOTH_SANDSTONE_SLAB.default_state,
+};
+
+const SAVANNA: Palette = Palette {
+    walls: Block::ACACIA_PLANKS.default_state,
+    corners: Block::ACACIA_LOG.default_state,
+    foundation: Block::COBBLESTONE.default_state,
+    path: Block::DIRT_PATH.default_state,
+    roof: Block::ACACIA_SLAB.default_state,
+};
+
+const SPRUCE: Palette = Palette {
+    walls: Block::SPRUCE_PLANKS.default_state,
+    corners: Block::SPRUCE_LOG.default_state,
+    foundation: Block::COBBLESTONE.default_state,
+    path: Block::DIRT_PATH.default_state,
+    roof: Block::SPRUCE_SLAB.default_state,
+};
+
+/// The kinds of villages, built from the blocks of the biomes they are found in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VillageType {
+    Plains,
+    Desert,
+    Savanna,
+    Snowy,
+    Taiga,
+}
+
+impl VillageType {
+    pub fn palette(&self) -> &'static Palette {
+        match self {
+            Self::Plains => &PLAINS,
+            Self::Desert => &DESERT,
+            Self::Savanna => &SAVANNA,
+            Self::Snowy | Self::Taiga => &SPRUCE,
+        }
+    }
+}
+
+/// Lays out a village as a well in the start chunk with streets leading away from it in every
+/// direction, lined by houses and farms.
+///
+/// Vanilla assembles villages from jigsaw templates, which are not part of our assets. Until they
+/// are, every building fits into a single chunk, so that it can stand on the ground of that chunk
+/// even though the chunks around it aren't generated yet.
+impl StructureGenerator for VillageType {
+    fn create_start(&self, context: &mut StartContext) -> Option<Vec<Box<dyn StructurePiece>>> {
+        let palette = self.palette();
+        let (bottom_y, top_y) = (context.bottom_y(), context.top_y() - 1);
+        let start = context.start;
+        let random = &mut context.random;
+
+        // `along` and `cross` are local to the chunk, along the street and across it
+        let area = |chunk: Vector2<i32>, along_x: bool, along: (i32, i32), cross: (i32, i32)| {
+            let (x, z) = if along_x {
+                (along, cross)
+            } else {
+                (cross, along)
+            };
+            let start_x = chunk_pos::start_block_x(&chunk);
+            let start_z = chunk_pos::start_block_z(&chunk);
+            BlockBox::new(
+                Vector3::new(start_x + x.0, bottom_y, start_z + z.0),
+                Vector3::new(start_x + x.1, top_y, start_z + z.1),
+            )
+        };
+
+        let mut pieces: Vec<Box<dyn StructurePiece>> = vec![Box::new(Well {
+            bounding_box: area(start, true, (6, 9), (6, 9)),
+            palette,
+        })];
+        for (dx, dz) in DIRECTIONS {
+            let along_x = dx != 0;
+            let outwards = if dx + dz > 0 { (10, 15) } else { (0, 5) };
+            pieces.push(Box::new(Street {
+                bounding_box: area(start, along_x, outwards, (7, 8)),
+                path: palette.path,
+            }));
+
+            for distance in 1..=random.next_inbetween_i32(1, MAX_STREET_CHUNKS) {
+                let chunk = Vector2::new(start.x + dx * distance, start.y + dz * distance);
+                pieces.push(Box::new(Street {
+                    bounding_box: area(chunk, along_x, (0, 15), (7, 8)),
+                    path: palette.path,
+                }));
+
+                // Buildings on both sides of the street, their doors facing it
+                for (cross, door_cross) in [((1, 5), 5), ((10, 14), 10)] {
+                    if random.next_bounded_i32(4) == 0 {
+                        continue;
+                    }
+                    if random.next_bounded_i32(4) == 0 {
+                        let along = random.next_inbetween_i32(1, 8);
+                        pieces.push(Box::new(Farm {
+                            bounding_box: area(chunk, along_x, (along, along + 6), cross),
+                            palette,
+                        }));
+                        continue;
+                    }
+                    let width = random.next_inbetween_i32(5, 7);
+                    let along = random.next_inbetween_i32(1, 15 - width);
+                    let bounding_box = area(chunk, along_x, (along, along + width - 1), cross);
+                    let door = area(
+                        chunk,
+                        along_x,
+                        (along + width / 2, along + width / 2),
+                        (door_cross, door_cross),
+                    );
+                    pieces.push(Box::new(House {
+                        bounding_box,
+                        door: Vector2::new(door.min.x, door.min.z),
+                        palette,
+                    }));
+                }
+            }
+        }
+        Some(pieces)
+    }
+}
+
+/// The height of the floor of a building standing on `area`, the average height of the ground
+/// below it, or `None` if any of it is under water or lava.
+pub(crate) fn floor_height(chunk: &ProtoChunk, area: &BlockBox) -> Option<i32> {
+    let mut sum = 0;
+    let mut count = 0;
+    for x in area.min.x..=area.max.x {
+        for z in area.min.z..=area.max.z {
+            let y = chunk.top_block_height_exclusive(&Vector2::new(x, z)) - 1;
+            if chunk
+                .get_block_state(&Vector3::new(x, y, z))
+                .to_state()
+                .is_liquid()
+            {
+                return None;
+            }
+            sum += y;
+            count += 1;
+        }
+    }
+    Some(sum / count)
+}
+
+/// Fills the ground below the footprint of a building, so that none of it floats.
+fn lay_foundation(writer: &mut PieceWriter, width: i32, depth: i32, state: &BlockState) {
+    for x in 0..=width {
+        for z in 0..=depth {
+            writer.fill_downwards(x, -1, z, state);
+        }
+    }
+}
+
+/// The well in the middle of a village, with a roof held up by posts.
+struct Well {
+    bounding_box: BlockBox,
+    palette: &'static Palette,
+}
+
+impl StructurePiece for Well {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let Some(floor) = floor_height(chunk, &self.bounding_box) else {
+            return;
+        };
+        let min = self.bounding_box.min;
+        let size = self.bounding_box.max.x - min.x;
+        let mut writer = PieceWriter::new(chunk, chunk_box, Vector3::new(min.x, floor, min.z));
+        let palette = self.palette;
+
+        lay_foundation(&mut writer, size, size, palette.foundation);
+        writer.fill(0, -2, 0, size, 1, size, palette.foundation);
+        writer.fill(1, -1, 1, size - 1, 1, size - 1, Block::WATER.default_state);
+        writer.fill(0, 2, 0, size, 4, size, Block::AIR.default_state);
+        for (x, z) in [(0, 0), (0, size), (size, 0), (size, size)] {
+            writer.fill(x, 2, z, x, 3, z, palette.corners);
+        }
+        writer.fill(0, 4, 0, size, 4, size, palette.roof);
+    }
+}
+
+/// A stretch of street, following the ground.
+struct Street {
+    bounding_box: BlockBox,
+    path: &'static BlockState,
+}
+
+impl StructurePiece for Street {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        for x in self.bounding_box.min.x..=self.bounding_box.max.x {
+            for z in self.bounding_box.min.z..=self.bounding_box.max.z {
+                let y = chunk.top_block_height_exclusive(&Vector2::new(x, z)) - 1;
+                let pos = Vector3::new(x, y, z);
+                // Streets don't cross rivers and lakes
+                if chunk_box.contains(&pos) && !chunk.get_block_state(&pos).to_state().is_liquid() {
+                    chunk.set_block_state(&pos, self.path);
+                }
+            }
+        }
+    }
+}
+
+/// A house with a door at `door`, windows in the other walls and a flat roof.
+struct House {
+    bounding_box: BlockBox,
+    door: Vector2<i32>,
+    palette: &'static Palette,
+}
+
+impl StructurePiece for House {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let Some(floor) = floor_height(chunk, &self.bounding_box) else {
+            return;
+        };
+        let min = self.bounding_box.min;
+        let width = self.bounding_box.max.x - min.x;
+        let depth = self.bounding_box.max.z - min.z;
+        let mut writer = PieceWriter::new(chunk, chunk_box, Vector3::new(min.x, floor, min.z));
+        let palette = self.palette;
+
+        lay_foundation(&mut writer, width, depth, palette.foundation);
+        writer.fill(0, 0, 0, width, 0, depth, palette.foundation);
+        writer.fill(0, 1, 0, width, 5, depth, Block::AIR.default_state);
+        for x in 0..=width {
+            for z in 0..=depth {
+                let edges = [x == 0 || x == width, z == 0 || z == depth];
+                let wall = match edges {
+                    [true, true] => palette.corners,
+                    [true, false] | [false, true] => palette.walls,
+                    [false, false] => continue,
+                };
+                writer.fill(x, 1, z, x, 3, z, wall);
+            }
+        }
+        for (x, z) in [
+            (width / 2, 0),
+            (width / 2, depth),
+            (0, depth / 2),
+            (width, depth / 2),
+        ] {
+            writer.set(x, 2, z, Block::GLASS_PANE.default_state);
+        }
+        let (door_x, door_z) = (self.door.x - min.x, self.door.y - min.z);
+        writer.fill(
+            door_x,
+            1,
+            door_z,
+            door_x,
+            2,
+            door_z,
+            Block::AIR.default_state,
+        );
+        writer.fill(0, 4, 0, width, 4, depth, palette.roof);
+    }
+}
+
+/// A field of wheat around a line of water, fenced by logs.
+struct Farm {
+    bounding_box: BlockBox,
+    palette: &'static Palette,
+}
+
+impl StructurePiece for Farm {
+    fn bounding_box(&self) -> BlockBox {
+        self.bounding_box
+    }
+
+    fn place(&self, chunk: &mut ProtoChunk, chunk_box: &BlockBox) {
+        let Some(floor) = floor_height(chunk, &self.bounding_box) else {
+            return;
+        };
+        let min = self.bounding_box.min;
+        let width = self.bounding_box.max.x - min.x;
+        let depth = self.bounding_box.max.z - min.z;
+        let mut writer = PieceWriter::new(chunk, chunk_box, Vector3::new(min.x, floor, min.z));
+
+        lay_foundation(&mut writer, width, depth, self.palette.foundation);
+        writer.fill(0, 1, 0, width, 3, depth, Block::AIR.default_state);
+        writer.fill(0, 0, 0, width, 0, depth, self.palette.corners);
+        writer.fill(
+            1,
+            0,
+            1,
+            width - 1,
+            0,
+            depth - 1,
+            Block::FARMLAND.default_state,
+        );
+        writer.fill(1, 1, 1, width - 1, 1, depth - 1, Block::WHEAT.default_state);
+        // The water runs along the middle of the longer side
+        let (water_x, water_z) = if width >= depth {
+            ((1, width - 1), (depth / 2, depth / 2))
+        } else {
+            ((width / 2, width / 2), (1, depth - 1))
+        };
+        writer.fill(
+            water_x.0,
+            0,
+            water_z.0,
+            water_x.1,
+            1,
+            water_z.1,
+            Block::AIR.default_state,
+        );
+        writer.fill(
+            water_x.0,
+            0,
+            water_z.0,
+            water_x.1,
+            0,
+            water_z.1,
+            Block::WATER.default_state,
+        );
+    }
+}
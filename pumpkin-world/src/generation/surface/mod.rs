@@ -172,7 +172,7 @@ impl MaterialCondition {
                 let temperature = context
                     .biome
                     .weather
-                    .compute_temperature(&context.block_pos, chunk.generation_settings().sea_level);
+                    .compute_temperature(&context.block_pos, chunk.sea_level());
                 temperature < 0.15f32
             }
             MaterialCondition::Steep => {
@@ -397,8 +397,10 @@ pub struct VerticalGradientMaterialCondition {
     random_name: String,
     true_at_and_below: YOffset,
     false_at_and_above: YOffset,
+    /// The splitter of each random deriver, keyed by the deriver rather than its address, which
+    /// a deriver of another world may reuse.
     #[serde(skip)]
-    random_deriver: ThreadLocal<RefCell<LruCache<usize, RandomDeriver>>>,
+    random_deriver: ThreadLocal<RefCell<LruCache<RandomDeriver, RandomDeriver>>>,
 }
 
 impl VerticalGradientMaterialCondition {
@@ -406,9 +408,6 @@ impl VerticalGradientMaterialCondition {
         let true_at = self.true_at_and_below.get_y(context.min_y, context.height);
         let false_at = self.false_at_and_above.get_y(context.min_y, context.height);
 
-        let context_pointer: *const RandomDeriver = context.random_deriver;
-        let key = context_pointer.addr();
-
         let mut cache = self
             .random_deriver
             .get_or(|| {
@@ -418,7 +417,7 @@ impl VerticalGradientMaterialCondition {
             })
             .borrow_mut();
 
-        let splitter = cache.get_or_insert(key, || {
+        let splitter = cache.get_or_insert(context.random_deriver.clone(), || {
             context
                 .random_deriver
                 .split_string(&self.random_name)
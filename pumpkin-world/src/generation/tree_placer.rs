@@ -0,0 +1,486 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use pumpkin_data::{Block, BlockState, chunk::Biome};
+use serde_json::{Value, json};
+
+use crate::block::BlockStateCodec;
+
+use super::feature::placed_features::{NamedPlacedFeature, PLACED_FEATURES, PlacedFeature};
+
+/// How the leaves of a [`ConfiguredTree`] are laid out around the top of its trunk.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FoliageShape {
+    /// A rounded blob, like oaks and birches have.
+    Blob,
+    /// A cone of alternately wider and narrower rings, like spruces have.
+    Cone,
+}
+
+/// A tree with a straight trunk and a crown of leaves, grown by a `tree` feature like vanilla's
+/// oaks and spruces.
+#[derive(Clone, Debug)]
+pub struct ConfiguredTree {
+    pub trunk: &'static BlockState,
+    pub foliage: &'static BlockState,
+    /// How many logs the trunk is tall, picked uniformly.
+    pub trunk_height: RangeInclusive<i32>,
+    /// How far the leaves reach out from the trunk at most.
+    pub leaf_radius: i32,
+    pub shape: FoliageShape,
+}
+
+impl ConfiguredTree {
+    pub const fn new(
+        trunk: &'static BlockState,
+        foliage: &'static BlockState,
+        trunk_height: RangeInclusive<i32>,
+        leaf_radius: i32,
+        shape: FoliageShape,
+    ) -> Self {
+        Self {
+            trunk,
+            foliage,
+            trunk_height,
+            leaf_radius,
+            shape,
+        }
+    }
+
+    /// The tree as a placed feature in the format of a datapack, only growing on dirt.
+    fn to_json(&self) -> Value {
+        let state_provider = |state: &BlockState| {
+            json!({
+                "type": "minecraft:simple_state_provider",
+                "state": BlockStateCodec::from(state),
+            })
+        };
+        let uniform = |min: i32, max: i32| json!({ "type": "minecraft:uniform", "min_inclusive": min, "max_inclusive": max });
+        let (foliage_placer, limit) = match self.shape {
+            FoliageShape::Blob => (
+                json!({
+                    "type": "minecraft:blob_foliage_placer",
+                    "radius": self.leaf_radius,
+                    "offset": 0,
+                    "height": 3,
+                }),
+                1,
+            ),
+            FoliageShape::Cone => (
+                json!({
+                    "type": "minecraft:spruce_foliage_placer",
+                    "radius": uniform((self.leaf_radius - 1).max(0), self.leaf_radius),
+                    "offset": uniform(0, 2),
+                    "trunk_height": uniform(1, 2),
+                }),
+                2,
+            ),
+        };
+        json!({
+            "feature": {
+                "type": "minecraft:tree",
+                "config": {
+                    "decorators": [],
+                    "ignore_vines": true,
+                    "force_dirt": false,
+                    "dirt_provider": state_provider(Block::DIRT.default_state),
+                    "minimum_size": {
+                        "type": "minecraft:two_layers_feature_size",
+                        "limit": limit,
+                        "lower_size": 0,
+                        "upper_size": limit,
+                    },
+                    "foliage_provider": state_provider(self.foliage),
+                    "foliage_placer": foliage_placer,
+                    "trunk_provider": state_provider(self.trunk),
+                    "trunk_placer": {
+                        "type": "minecraft:straight_trunk_placer",
+                        "base_height": self.trunk_height.start(),
+                        "height_rand_a": self.trunk_height.end() - self.trunk_height.start(),
+                        "height_rand_b": 0,
+                    },
+                },
+            },
+            "placement": [{
+                "type": "minecraft:block_predicate_filter",
+                "predicate": {
+                    "type": "minecraft:matching_block_tag",
+                    "offset": [0, -1, 0],
+                    "tag": "minecraft:dirt",
+                },
+            }],
+        })
+    }
+
+    /// The placed feature growing the tree from the position it is placed at, only on dirt.
+    pub fn placed_feature(&self, name: impl Into<String>) -> NamedPlacedFeature {
+        let feature: PlacedFeature = serde_json::from_value(self.to_json())
+            .expect("configured trees are valid placed features");
+        NamedPlacedFeature::new(name, feature)
+    }
+}
+
+/// A tree a biome grows.
+#[derive(Clone, Debug)]
+pub enum Tree {
+    /// The placed feature of vanilla with this name, like `oak_checked`.
+    Vanilla(&'static str),
+    Configured(ConfiguredTree),
+}
+
+impl Tree {
+    fn to_json(&self) -> Value {
+        match self {
+            Self::Vanilla(name) => json!(format!("minecraft:{name}")),
+            Self::Configured(tree) => tree.to_json(),
+        }
+    }
+}
+
+impl From<ConfiguredTree> for Tree {
+    fn from(tree: ConfiguredTree) -> Self {
+        Self::Configured(tree)
+    }
+}
+
+/// Vanilla's oak, like the ones saplings grow into.
+pub const OAK: Tree = Tree::Vanilla("oak_checked");
+
+/// Vanilla's birch, a bit taller than an [`OAK`].
+pub const BIRCH: Tree = Tree::Vanilla("birch_checked");
+
+/// Vanilla's spruce, whose leaves form a cone down most of its trunk.
+pub const SPRUCE: Tree = Tree::Vanilla("spruce_checked");
+
+/// Which trees grow in a biome, and how many.
+#[derive(Clone, Debug)]
+pub struct BiomeTrees {
+    /// How many trees a chunk of the biome gets on average. The fraction is the chance of one
+    /// more tree, in hundredths.
+    pub trees_per_chunk: f32,
+    /// The trees to pick from, with their weights.
+    pub trees: Vec<(Tree, u32)>,
+}
+
+impl BiomeTrees {
+    pub fn new(trees_per_chunk: f32, trees: Vec<(Tree, u32)>) -> Self {
+        Self {
+            trees_per_chunk,
+            trees,
+        }
+    }
+
+    /// The trees as a placed feature like vanilla's `trees_*`: a random selector over the trees,
+    /// placed on the ocean floor of random columns out of the water.
+    fn placed_feature(&self, name: String) -> NamedPlacedFeature {
+        let trees: Vec<_> = self
+            .trees
+            .iter()
+            .filter(|(_, weight)| *weight > 0)
+            .collect();
+        let (default, rest) = trees.split_last().expect("a biome needs a tree to pick");
+        // The selector tries the trees one after the other, so each one's chance is its share of
+        // the weight left
+        let mut weight_left: u32 = trees.iter().map(|(_, weight)| weight).sum();
+        let features: Vec<_> = rest
+            .iter()
+            .map(|(tree, weight)| {
+                let chance = *weight as f32 / weight_left as f32;
+                weight_left -= weight;
+                json!({ "feature": tree.to_json(), "chance": chance })
+            })
+            .collect();
+
+        let count = self.trees_per_chunk.trunc() as i32;
+        let extra = (self.trees_per_chunk.fract() * 100.0).round() as i32;
+        let count = if extra == 0 {
+            json!(count)
+        } else {
+            json!({
+                "type": "minecraft:weighted_list",
+                "distribution": [
+                    { "data": count, "weight": 100 - extra },
+                    { "data": count + 1, "weight": extra },
+                ],
+            })
+        };
+
+        let feature: PlacedFeature = serde_json::from_value(json!({
+            "feature": {
+                "type": "minecraft:random_selector",
+                "config": { "features": features, "default": default.0.to_json() },
+            },
+            "placement": [
+                { "type": "minecraft:count", "count": count },
+                { "type": "minecraft:in_square" },
+                { "type": "minecraft:surface_water_depth_filter", "max_water_depth": 0 },
+                { "type": "minecraft:heightmap", "heightmap": "OCEAN_FLOOR" },
+            ],
+        }))
+        .expect("biome trees are valid placed features");
+        NamedPlacedFeature::new(name, feature)
+    }
+}
+
+/// Grows trees in chunks whose terrain is complete, depending on their biome, on top of the ones
+/// their biomes list.
+#[derive(Clone)]
+pub struct TreePlacer {
+    /// The trees of each biome and the feature placing them, by biome id.
+    biomes: HashMap<u8, (BiomeTrees, NamedPlacedFeature)>,
+}
+
+impl TreePlacer {
+    /// A placer without any trees, for generators whose biomes already grow vanilla's.
+    pub fn empty() -> Self {
+        Self {
+            biomes: HashMap::new(),
+        }
+    }
+
+    /// A placer for the oak, birch and spruce forests of vanilla.
+    pub fn vanilla() -> Self {
+        let mut placer = Self::empty();
+        let oaks = |trees_per_chunk| BiomeTrees::new(trees_per_chunk, vec![(OAK, 1)]);
+        let spruces = |trees_per_chunk| BiomeTrees::new(trees_per_chunk, vec![(SPRUCE, 1)]);
+        let birches = |trees_per_chunk| BiomeTrees::new(trees_per_chunk, vec![(BIRCH, 1)]);
+
+        placer.register(&Biome::PLAINS, oaks(0.05));
+        placer.register(&Biome::SUNFLOWER_PLAINS, oaks(0.05));
+        placer.register(&Biome::MEADOW, oaks(0.05));
+        placer.register(&Biome::SWAMP, oaks(2.0));
+        placer.register(
+            &Biome::FOREST,
+            BiomeTrees::new(10.1, vec![(OAK, 4), (BIRCH, 1)]),
+        );
+        placer.register(
+            &Biome::FLOWER_FOREST,
+            BiomeTrees::new(6.1, vec![(OAK, 4), (BIRCH, 1)]),
+        );
+        placer.register(&Biome::BIRCH_FOREST, birches(10.1));
+        placer.register(&Biome::OLD_GROWTH_BIRCH_FOREST, birches(10.1));
+        placer.register(&Biome::TAIGA, spruces(10.1));
+        placer.register(&Biome::SNOWY_TAIGA, spruces(10.1));
+        placer.register(&Biome::OLD_GROWTH_PINE_TAIGA, spruces(10.1));
+        placer.register(&Biome::OLD_GROWTH_SPRUCE_TAIGA, spruces(10.1));
+        placer.register(&Biome::GROVE, spruces(10.1));
+        placer.register(&Biome::SNOWY_PLAINS, spruces(0.1));
+        placer.register(
+            &Biome::WINDSWEPT_FOREST,
+            BiomeTrees::new(3.1, vec![(SPRUCE, 2), (OAK, 1)]),
+        );
+        placer.register(
+            &Biome::WINDSWEPT_HILLS,
+            BiomeTrees::new(0.1, vec![(SPRUCE, 2), (OAK, 1)]),
+        );
+        placer
+    }
+
+    /// Makes `trees` grow in `biome`, replacing the trees registered for it before.
+    pub fn register(&mut self, biome: &'static Biome, trees: BiomeTrees) {
+        assert!(
+            trees.trees.iter().any(|(_, weight)| *weight > 0),
+            "a biome needs a tree to pick"
+        );
+        for (tree, _) in &trees.trees {
+            if let Tree::Vanilla(name) = tree {
+                assert!(
+                    PLACED_FEATURES.contains_key(*name),
+                    "no vanilla tree {name}"
+                );
+            }
+        }
+        let feature = trees.placed_feature(format!("trees_{}", biome.registry_id));
+        self.biomes.insert(biome.id, (trees, feature));
+    }
+
+    pub fn trees(&self, biome: &Biome) -> Option<&BiomeTrees> {
+        self.biomes.get(&biome.id).map(|(trees, _)| trees)
+    }
+
+    /// The feature growing the trees of `biome` from a chunk, in the step vanilla grows its
+    /// trees in.
+    pub fn feature(&self, biome: &Biome) -> Option<&NamedPlacedFeature> {
+        self.biomes.get(&biome.id).map(|(_, feature)| feature)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::LazyLock;
+
+    use pumpkin_data::{Block, chunk::Biome, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::{
+        math::{position::BlockPos, vector2::Vector2, vector3::Vector3},
+        random::{RandomGenerator, xoroshiro128::Xoroshiro},
+    };
+
+    use super::{BiomeTrees, ConfiguredTree, FoliageShape, OAK, TreePlacer};
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            feature::decoration::DecorationStep,
+            generator::tests::BlockRegistry,
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            positions::chunk_pos,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            proto_chunk_region::ProtoChunkRegion,
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    fn new_chunk(pos: Vector2<i32>) -> ProtoChunk<'static> {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        ProtoChunk::new(
+            pos,
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        )
+    }
+
+    /// The blocks of the chunk at `pos` once the trees of `biome` have grown, column by column.
+    fn grow_trees(pos: Vector2<i32>, biome: &Biome) -> Vec<u16> {
+        let mut chunk = new_chunk(pos);
+        chunk.populate_biomes(&Dimension::Overworld);
+        chunk.populate_noise();
+        chunk.build_surface();
+        let placer = TreePlacer::vanilla();
+        ProtoChunkRegion::new(&mut chunk).generate_extra_features(
+            &BlockRegistry,
+            DecorationStep::VegetalDecoration,
+            placer.feature(biome),
+        );
+
+        (0..16)
+            .flat_map(|x| (0..16).flat_map(move |z| (-64..320).map(move |y| (x, y, z))))
+            .map(|(x, y, z)| chunk.get_block_state(&Vector3::new(x, y, z)).0)
+            .collect()
+    }
+
+    #[test]
+    fn forests_grow_on_dirt() {
+        // A forest for this seed
+        let pos = Vector2::new(-4, 0);
+        let mut chunk = new_chunk(pos);
+        chunk.populate_biomes(&Dimension::Overworld);
+        let x = chunk_pos::start_block_x(&pos) + 8;
+        let z = chunk_pos::start_block_z(&pos) + 8;
+        assert_eq!(
+            chunk.get_biome_for_terrain_gen(&Vector3::new(x, 64, z)),
+            &Biome::FOREST
+        );
+        let blocks = grow_trees(pos, &Biome::FOREST);
+        assert_eq!(blocks, grow_trees(pos, &Biome::FOREST));
+
+        let mut trunks = 0;
+        for column in blocks.chunks(384) {
+            let Some(bottom) = column.iter().position(|state| {
+                [&Block::OAK_LOG, &Block::BIRCH_LOG].contains(&Block::from_state_id(*state))
+            }) else {
+                continue;
+            };
+            trunks += 1;
+            assert_eq!(Block::from_state_id(column[bottom - 1]), &Block::DIRT);
+        }
+        assert!(trunks >= 5, "only {trunks} trees grew");
+    }
+
+    #[test]
+    fn configured_trees_replace_the_presets() {
+        let acacia = ConfiguredTree::new(
+            Block::ACACIA_LOG.default_state,
+            Block::ACACIA_LEAVES.default_state,
+            5..=5,
+            2,
+            FoliageShape::Blob,
+        );
+        let mut placer = TreePlacer::vanilla();
+        placer.register(
+            &Biome::PLAINS,
+            BiomeTrees::new(1.5, vec![(acacia.into(), 3), (OAK, 1)]),
+        );
+        assert_eq!(placer.trees(&Biome::PLAINS).unwrap().trees.len(), 2);
+        assert_eq!(
+            placer.feature(&Biome::PLAINS).unwrap().name(),
+            "trees_plains"
+        );
+        assert!(placer.feature(&Biome::DESERT).is_none());
+    }
+
+    #[test]
+    fn crowns_grow_into_the_neighbors() {
+        let mut center = new_chunk(Vector2::new(0, 0));
+        let mut east = new_chunk(Vector2::new(1, 0));
+        let mut south = new_chunk(Vector2::new(0, 1));
+        let mut south_east = new_chunk(Vector2::new(1, 1));
+        let mut region = ProtoChunkRegion::new(&mut center)
+            .with_neighbor(&mut east)
+            .with_neighbor(&mut south)
+            .with_neighbor(&mut south_east);
+
+        // Up in the empty sky, on the very corner of the chunk
+        let ground = Vector3::new(15, 200, 15);
+        let oak = ConfiguredTree::new(
+            Block::OAK_LOG.default_state,
+            Block::OAK_LEAVES.default_state,
+            4..=6,
+            2,
+            FoliageShape::Blob,
+        )
+        .placed_feature("oak");
+        let mut random = RandomGenerator::Xoroshiro(Xoroshiro::from_seed(SEED));
+        let grow = |region: &mut ProtoChunkRegion, random: &mut RandomGenerator| {
+            oak.feature().generate(
+                region,
+                &BlockRegistry,
+                -64,
+                384,
+                oak.name(),
+                random,
+                BlockPos(Vector3::new(ground.x, ground.y + 1, ground.z)),
+            )
+        };
+        // Only on dirt
+        assert!(!grow(&mut region, &mut random));
+        region.set_block_state(&ground, Block::GRASS_BLOCK.default_state);
+        assert!(grow(&mut region, &mut random));
+
+        let leaves = |chunk: &ProtoChunk| {
+            (0..16)
+                .flat_map(|x| (200..220).flat_map(move |y| (0..16).map(move |z| (x, y, z))))
+                .filter(|(x, y, z)| {
+                    chunk.get_block_state(&Vector3::new(*x, *y, *z)).to_block()
+                        == &Block::OAK_LEAVES
+                })
+                .count()
+        };
+        assert_eq!(
+            region
+                .get_block_state(&Vector3::new(15, 201, 15))
+                .to_block(),
+            &Block::OAK_LOG
+        );
+        for neighbor in [&east, &south, &south_east] {
+            assert!(
+                leaves(neighbor) > 0,
+                "{:?} has no leaves",
+                neighbor.chunk_pos
+            );
+        }
+        // Nothing wrapped around to the far side of the chunk
+        assert!((200..220).all(|y| center.is_air(&Vector3::new(0, y, 0))));
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use pumpkin_data::{Block, BlockState, chunk::Biome};
+use serde_json::json;
+
+use crate::block::BlockStateCodec;
+
+use super::feature::{
+    decoration::DecorationStep,
+    placed_features::{NamedPlacedFeature, PlacedFeature},
+};
+
+/// A plant growing on top of a grass block.
+#[derive(Clone, Copy, Debug)]
+pub enum Plant {
+    /// A plant one block tall, like grass or most flowers.
+    Single(&'static BlockState),
+    /// A plant two blocks tall, like tall grass or sunflowers, stacking the lower and upper half
+    /// of the block.
+    Tall(&'static Block),
+}
+
+impl Plant {
+    /// The block the plant is placed as. Tall plants start with their lower half, the default.
+    fn state(&self) -> &'static BlockState {
+        match self {
+            Self::Single(state) => state,
+            Self::Tall(block) => block.default_state,
+        }
+    }
+}
+
+/// Patches of a single plant scattered through every chunk of a biome, placed as a
+/// `random_patch` feature like vanilla's grass and flowers.
+#[derive(Clone, Debug)]
+pub struct PlantPatch {
+    pub plant: Plant,
+    pub patches_per_chunk: u32,
+    /// How many times each patch tries to grow a plant.
+    pub tries: u8,
+    /// How far the plants of a patch grow from its center at most.
+    pub spread: u8,
+}
+
+impl PlantPatch {
+    pub const fn new(plant: Plant, patches_per_chunk: u32, tries: u8, spread: u8) -> Self {
+        Self {
+            plant,
+            patches_per_chunk,
+            tries,
+            spread,
+        }
+    }
+
+    /// The placed feature growing the patches on the surface of a chunk, only on grass blocks
+    /// and never in water, named after the plant.
+    pub fn placed_feature(&self) -> NamedPlacedFeature {
+        let state = self.plant.state();
+        let feature: PlacedFeature = serde_json::from_value(json!({
+            "feature": {
+                "type": "minecraft:random_patch",
+                "config": {
+                    "tries": self.tries,
+                    "xz_spread": self.spread,
+                    "y_spread": 3,
+                    "feature": {
+                        "feature": {
+                            "type": "minecraft:simple_block",
+                            "config": {
+                                "to_place": {
+                                    "type": "minecraft:simple_state_provider",
+                                    "state": BlockStateCodec::from(state),
+                                },
+                            },
+                        },
+                        "placement": [{
+                            "type": "minecraft:block_predicate_filter",
+                            "predicate": {
+                                "type": "minecraft:all_of",
+                                "predicates": [
+                                    {
+                                        "type": "minecraft:matching_blocks",
+                                        "blocks": "minecraft:air",
+                                    },
+                                    {
+                                        "type": "minecraft:matching_blocks",
+                                        "blocks": "minecraft:grass_block",
+                                        "offset": [0, -1, 0],
+                                    },
+                                ],
+                            },
+                        }],
+                    },
+                },
+            },
+            // Water blocks motion too, so the patches start on top of it and find no grass
+            "placement": [
+                { "type": "minecraft:count", "count": self.patches_per_chunk },
+                { "type": "minecraft:in_square" },
+                { "type": "minecraft:heightmap", "heightmap": "MOTION_BLOCKING" },
+            ],
+        }))
+        .expect("plant patches are valid placed features");
+        let block = Block::from_state_id(state.id);
+        NamedPlacedFeature::new(format!("patch_{}", block.name), feature)
+    }
+}
+
+/// Whether vanilla's placed feature called `name` grows grass, ferns or flowers.
+fn is_vanilla_plant(name: &str) -> bool {
+    const PREFIXES: [&str; 6] = [
+        "patch_grass",
+        "patch_tall_grass",
+        "patch_large_fern",
+        "patch_sunflower",
+        "flower_",
+        "forest_flowers",
+    ];
+    PREFIXES.iter().any(|prefix| name.starts_with(prefix))
+}
+
+/// Grows grass and flowers in chunks whose terrain is complete, depending on their biome, on top
+/// of the ones their biomes list.
+#[derive(Clone)]
+pub struct VegetationPlacer {
+    /// The plant features of each biome, by biome id.
+    biomes: HashMap<u8, Vec<NamedPlacedFeature>>,
+}
+
+impl VegetationPlacer {
+    /// A placer without any plants, for generators whose biomes already grow vanilla's.
+    pub fn empty() -> Self {
+        Self {
+            biomes: HashMap::new(),
+        }
+    }
+
+    /// A placer for vanilla's grass, ferns and flowers, each in the biomes listing them.
+    pub fn vanilla() -> Self {
+        let mut placer = Self::empty();
+        for biome in (0..=u8::MAX).filter_map(Biome::from_id) {
+            let Some(features) = biome
+                .features
+                .get(DecorationStep::VegetalDecoration.index())
+            else {
+                continue;
+            };
+            for name in features.iter() {
+                let name = name.strip_prefix("minecraft:").unwrap_or(name);
+                if is_vanilla_plant(name) {
+                    let feature =
+                        NamedPlacedFeature::vanilla(name).expect("biomes list vanilla features");
+                    placer.register_feature(biome, feature);
+                }
+            }
+        }
+        placer
+    }
+
+    /// Adds `patch` to the plants growing in `biome`, after the ones registered before.
+    pub fn register(&mut self, biome: &'static Biome, patch: PlantPatch) {
+        self.register_feature(biome, patch.placed_feature());
+    }
+
+    /// Adds `feature` to the plants growing in `biome`, after the ones registered before, like a
+    /// datapack appending to the features of a biome.
+    pub fn register_feature(&mut self, biome: &'static Biome, feature: NamedPlacedFeature) {
+        self.biomes.entry(biome.id).or_default().push(feature);
+    }
+
+    /// The features growing the plants of `biome` from a chunk, in the order they are placed in
+    /// the step vanilla grows its plants in.
+    pub fn features(&self, biome: &Biome) -> &[NamedPlacedFeature] {
+        self.biomes.get(&biome.id).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::LazyLock;
+
+    use pumpkin_data::{Block, chunk::Biome, noise_router::OVERWORLD_BASE_NOISE_ROUTER};
+    use pumpkin_util::math::{vector2::Vector2, vector3::Vector3};
+
+    use super::{Plant, PlantPatch, VegetationPlacer};
+    use crate::{
+        dimension::Dimension,
+        generation::{
+            GlobalRandomConfig,
+            feature::decoration::DecorationStep,
+            generator::tests::BlockRegistry,
+            noise::router::proto_noise_router::ProtoNoiseRouters,
+            proto_chunk::{ProtoChunk, TerrainCache},
+            proto_chunk_region::ProtoChunkRegion,
+            settings::{GENERATION_SETTINGS, GeneratorSetting},
+        },
+    };
+
+    const SEED: u64 = 0;
+    static RANDOM_CONFIG: LazyLock<GlobalRandomConfig> =
+        LazyLock::new(|| GlobalRandomConfig::new(SEED, false));
+    static BASE_NOISE_ROUTER: LazyLock<ProtoNoiseRouters> =
+        LazyLock::new(|| ProtoNoiseRouters::generate(&OVERWORLD_BASE_NOISE_ROUTER, &RANDOM_CONFIG));
+    static TERRAIN_CACHE: LazyLock<TerrainCache> =
+        LazyLock::new(|| TerrainCache::from_random(&RANDOM_CONFIG));
+
+    /// The plains chunk for this seed, once `placer` grew the plants of the plains in it.
+    fn grow_plains(placer: &VegetationPlacer) -> ProtoChunk<'static> {
+        let settings = GENERATION_SETTINGS
+            .get(&GeneratorSetting::Overworld)
+            .unwrap();
+        let mut chunk = ProtoChunk::new(
+            Vector2::new(-4, 16),
+            &BASE_NOISE_ROUTER,
+            &RANDOM_CONFIG,
+            settings,
+            &TERRAIN_CACHE,
+            settings.default_block.get_state(),
+        );
+        chunk.populate_biomes(&Dimension::Overworld);
+        chunk.populate_noise();
+        chunk.build_surface();
+        ProtoChunkRegion::new(&mut chunk).generate_extra_features(
+            &BlockRegistry,
+            DecorationStep::VegetalDecoration,
+            placer.features(&Biome::PLAINS),
+        );
+        chunk
+    }
+
+    /// How many blocks tall each stack of `plant` on top of the surface of `chunk` is, with the
+    /// block it grows on.
+    fn plants_on_surface(chunk: &ProtoChunk, plant: &Block) -> Vec<(i32, &'static Block)> {
+        let block = |x, y, z| chunk.get_block_state(&Vector3::new(x, y, z)).to_block();
+        let mut plants = Vec::new();
+        for x in 0..16 {
+            for z in 0..16 {
+                let top = chunk.top_block_height_exclusive(&Vector2::new(x, z)) - 1;
+                let mut ground = top;
+                while block(x, ground, z) == plant {
+                    ground -= 1;
+                }
+                if ground < top {
+                    plants.push((top - ground, block(x, ground, z)));
+                }
+            }
+        }
+        plants
+    }
+
+    #[test]
+    fn plains_grow_grass() {
+        let chunk = grow_plains(&VegetationPlacer::vanilla());
+        // The block registry of the tests lets plants grow anywhere, vanilla's grass only checks
+        // for room
+        let grass = plants_on_surface(&chunk, &Block::SHORT_GRASS)
+            .into_iter()
+            .filter(|(_, ground)| *ground == &Block::GRASS_BLOCK)
+            .count();
+        assert!((20..=200).contains(&grass), "{grass} grass on the surface");
+    }
+
+    #[test]
+    fn custom_plants_grow_both_halves() {
+        let mut placer = VegetationPlacer::empty();
+        placer.register(
+            &Biome::PLAINS,
+            PlantPatch::new(Plant::Tall(&Block::PEONY), 4, 64, 7),
+        );
+        assert_eq!(placer.features(&Biome::PLAINS)[0].name(), "patch_peony");
+        assert!(placer.features(&Biome::FOREST).is_empty());
+
+        let chunk = grow_plains(&placer);
+        let peonies = plants_on_surface(&chunk, &Block::PEONY);
+        assert!(!peonies.is_empty());
+        assert!(
+            peonies
+                .iter()
+                .all(|(height, ground)| *height == 2 && *ground == &Block::GRASS_BLOCK),
+            "{peonies:?}"
+        );
+    }
+}
@@ -2,12 +2,20 @@ use crate::{
     BlockStateId,
     block::{RawBlockState, entities::BlockEntity},
     chunk::{
-        ChunkData, ChunkEntityData, ChunkReadingError,
+        ChunkData, ChunkEntityData, ChunkReadingError, GeneratorStamp,
         format::{anvil::AnvilChunkFile, linear::LinearFile},
         io::{Dirtiable, FileIO, LoadedData, file_manager::ChunkFileManager},
+        palette::biome_id_or_plains,
     },
     dimension::Dimension,
-    generation::{Seed, generator::WorldGenerator, get_world_gen},
+    generation::{
+        GeneratorKind, Seed,
+        generator::{
+            GENERATOR_VERSION, GenerationCache, GenerationTimings, GeneratorError, GeneratorPool,
+            LoadBatch, LoadRequest, WorkerSettings, WorldGenerator, initialize_generator,
+        },
+        make_generator,
+    },
     tick::{OrderedTick, ScheduledTick, TickPriority},
     world::BlockRegistryExt,
 };
@@ -58,7 +66,8 @@ pub struct ChunkRequest {
 /// For more details on world generation, refer to the `WorldGenerator` module.
 pub struct Level {
     pub seed: Seed,
-    block_registry: Arc<dyn BlockRegistryExt>,
+    /// Chunks loaded from disk are fitted to its height before they are used.
+    dimension: Dimension,
     level_folder: LevelFolder,
 
     /// Counts the number of ticks that have been scheduled for this world
@@ -70,11 +79,16 @@ pub struct Level {
     loaded_entity_chunks: Arc<DashMap<Vector2<i32>, SyncEntityChunk>>,
 
     chunk_watchers: Arc<DashMap<Vector2<i32>, usize>>,
+    /// How many players are in each chunk, so that the chunks closest to them are generated first.
+    player_chunks: DashMap<Vector2<i32>, usize>,
 
     chunk_saver: Arc<dyn FileIO<Data = SyncChunk>>,
     entity_saver: Arc<dyn FileIO<Data = SyncEntityChunk>>,
 
     world_gen: Arc<dyn WorldGenerator>,
+    /// Whether a chunk generated with another configuration was loaded already, so that only the
+    /// first one is warned about.
+    warned_generator_mismatch: AtomicBool,
 
     /// Tracks tasks associated with this world instance
     tasks: TaskTracker,
@@ -82,8 +96,13 @@ pub struct Level {
     pub shutdown_notifier: Notify,
     pub is_shutting_down: AtomicBool,
 
-    gen_request_tx: Sender<Vector2<i32>>,
+    gen_request_tx: Sender<LoadBatch>,
+    /// Needs to know which chunks were unloaded, to generate them again if they weren't saved.
+    generator_pool: Arc<GeneratorPool>,
     pending_generations: Arc<DashMap<Vector2<i32>, Vec<oneshot::Sender<SyncChunk>>>>,
+    /// The request each pending chunk was sent with, cancelled once all of its chunks that aren't
+    /// generated yet were unloaded.
+    generation_requests: DashMap<Vector2<i32>, Arc<LoadRequest>>,
 
     gen_entity_request_tx: Sender<Vector2<i32>>,
     pending_entity_generations: Arc<DashMap<Vector2<i32>, Vec<oneshot::Sender<SyncEntityChunk>>>>,
@@ -104,12 +123,33 @@ pub struct LevelFolder {
 }
 
 impl Level {
+    /// # Panics
+    /// If the generator of `dimension` can't be created, see [`Level::with_generator`].
     pub fn from_root_folder(
         root_folder: PathBuf,
         block_registry: Arc<dyn BlockRegistryExt>,
         seed: i64,
         dimension: Dimension,
     ) -> Arc<Self> {
+        Self::with_generator(
+            root_folder,
+            block_registry,
+            seed,
+            dimension,
+            GeneratorKind::default(),
+        )
+        .unwrap_or_else(|err| panic!("Failed to create the generator: {err}"))
+    }
+
+    /// Like [`Level::from_root_folder`], but generating new chunks with the generator of `kind`,
+    /// see [`make_generator`]. Fails if that generator can't be created for `dimension`.
+    pub fn with_generator(
+        root_folder: PathBuf,
+        block_registry: Arc<dyn BlockRegistryExt>,
+        seed: i64,
+        dimension: Dimension,
+        kind: GeneratorKind,
+    ) -> Result<Arc<Self>, GeneratorError> {
         // If we are using an already existing world we want to read the seed from the level.dat, If not we want to check if there is a seed in the config, if not lets create a random one
         let region_folder = root_folder.join("region");
         if !region_folder.exists() {
@@ -128,7 +168,7 @@ impl Level {
         // TODO: Load info correctly based on world format type
 
         let seed = Seed(seed as u64);
-        let world_gen = get_world_gen(seed, dimension).into();
+        let world_gen = make_generator(kind, seed, dimension.clone(), block_registry)?.into();
 
         let chunk_saver: Arc<dyn FileIO<Data = SyncChunk>> = match advanced_config().chunk.format {
             ChunkFormat::Linear => Arc::new(ChunkFileManager::<LinearFile<ChunkData>>::default()),
@@ -149,13 +189,33 @@ impl Level {
         let (gen_request_tx, gen_request_rx) = crossbeam::channel::unbounded();
         let pending_generations = Arc::new(DashMap::new());
 
+        let (mut worker_settings, generator_workers) =
+            WorkerSettings::from_config(&advanced_config().chunk, num_cpus::get());
+        let num_threads = generator_workers.len();
+        // What a custom generator places can't be told apart, e.g. its fill block
+        let chunk_config = &advanced_config().chunk;
+        let cached = chunk_config.generation_cache && !matches!(kind, GeneratorKind::Custom(_));
+        worker_settings.cache = cached.then(|| {
+            let settings = format!(
+                "{kind:?} {:?} {:?}",
+                chunk_config.terrain_shape, chunk_config.structure_spacing
+            );
+            Arc::new(GenerationCache::new(
+                &level_folder.root_folder.join("generation_cache"),
+                seed,
+                &dimension,
+                &settings,
+            ))
+        });
+
         let (gen_entity_request_tx, gen_entity_request_rx) = crossbeam::channel::unbounded();
         let pending_entity_generations = Arc::new(DashMap::new());
 
         let level_ref = Arc::new(Self {
             seed,
-            block_registry,
+            dimension,
             world_gen,
+            warned_generator_mismatch: AtomicBool::new(false),
             level_folder,
             chunk_saver,
             entity_saver,
@@ -163,54 +223,27 @@ impl Level {
             loaded_chunks: Arc::new(DashMap::new()),
             loaded_entity_chunks: Arc::new(DashMap::new()),
             chunk_watchers: Arc::new(DashMap::new()),
+            player_chunks: DashMap::new(),
             tasks: TaskTracker::new(),
             shutdown_notifier: Notify::new(),
             is_shutting_down: AtomicBool::new(false),
             gen_request_tx,
+            generator_pool: worker_settings.pool.clone(),
             pending_generations: pending_generations.clone(),
+            generation_requests: DashMap::new(),
             gen_entity_request_tx,
             pending_entity_generations: pending_entity_generations.clone(),
         });
 
-        //TODO: Investigate optimal number of threads
-        let num_threads = num_cpus::get().saturating_sub(1).max(1);
-
         // Normal Chunks
-        for thread_id in 0..num_threads {
+        for worker in generator_workers {
             let level_clone = level_ref.clone();
-            let pending_clone = pending_generations.clone();
+            let generator = level_ref.world_gen.clone();
             let rx = gen_request_rx.clone();
+            let settings = worker_settings.clone();
 
             std::thread::spawn(move || {
-                while let Ok(pos) = rx.recv() {
-                    if level_clone.is_shutting_down.load(Ordering::Relaxed) {
-                        break;
-                    }
-
-                    log::debug!(
-                        "Generating chunk {pos:?}, worker thread {thread_id:?}, queue length {}",
-                        rx.len()
-                    );
-
-                    // Generate chunk
-                    let mut chunk = level_clone.world_gen.generate_chunk(
-                        &level_clone,
-                        level_clone.block_registry.as_ref(),
-                        &pos,
-                    );
-                    chunk.heightmap = chunk.calculate_heightmap();
-                    let arc_chunk = Arc::new(RwLock::new(chunk));
-
-                    // Insert into loaded chunks
-                    level_clone.loaded_chunks.insert(pos, arc_chunk.clone());
-
-                    // Fulfill all waiters
-                    if let Some(waiters) = pending_clone.remove(&pos) {
-                        for tx in waiters.1 {
-                            let _ = tx.send(arc_chunk.clone());
-                        }
-                    }
-                }
+                initialize_generator(rx, generator, level_clone, worker, settings)
             });
         }
 
@@ -251,7 +284,23 @@ impl Level {
             });
         }
 
-        level_ref
+        Ok(level_ref)
+    }
+
+    /// Hands a freshly generated chunk over to the level, waking everyone waiting on it.
+    pub fn set_generated_chunk(&self, pos: Vector2<i32>, mut chunk: ChunkData) {
+        chunk.heightmap = chunk.calculate_heightmap();
+        chunk.generated_by = Some(self.generator_stamp());
+        let arc_chunk = Arc::new(RwLock::new(chunk));
+
+        self.loaded_chunks.insert(pos, arc_chunk.clone());
+
+        self.generation_requests.remove(&pos);
+        if let Some(waiters) = self.pending_generations.remove(&pos) {
+            for tx in waiters.1 {
+                let _ = tx.send(arc_chunk.clone());
+            }
+        }
     }
 
     async fn load_single_chunk(
@@ -267,13 +316,73 @@ impl Level {
 
         // Wait for the result
         match rx.recv().await {
-            Some(LoadedData::Loaded(chunk)) => Ok((chunk, false)),
-            Some(LoadedData::Missing(_)) => Err(ChunkReadingError::ChunkNotExist),
-            Some(LoadedData::Error((_, err))) => Err(err),
+            Some(data) => match self.fit_loaded_chunk(data).await {
+                LoadedData::Loaded(chunk) => Ok((chunk, false)),
+                LoadedData::Missing(_) => Err(ChunkReadingError::ChunkNotExist),
+                LoadedData::Error((_, err)) => Err(err),
+            },
             None => Err(ChunkReadingError::ChunkNotExist),
         }
     }
 
+    /// What the chunks generated for this level are generated with.
+    pub fn generator_stamp(&self) -> GeneratorStamp {
+        GeneratorStamp {
+            seed: self.seed.0 as i64,
+            dimension: self.dimension.name().to_string(),
+            version: GENERATOR_VERSION as i32,
+        }
+    }
+
+    /// Whether `chunk` was generated like the chunks generated for this level now would be.
+    /// Warns about the first chunk that wasn't, since the terrain around it won't line up with
+    /// it. Chunks saved without a record of their generator are taken to match.
+    pub(crate) fn check_generator(&self, chunk: &ChunkData) -> bool {
+        let expected = self.generator_stamp();
+        let Some(stamp) = chunk
+            .generated_by
+            .as_ref()
+            .filter(|stamp| **stamp != expected)
+        else {
+            return true;
+        };
+        if self.warned_generator_mismatch.swap(true, Ordering::Relaxed) {
+            log::debug!(
+                "Chunk {:?} was generated with {stamp:?} instead of {expected:?}",
+                chunk.position
+            );
+        } else {
+            log::warn!(
+                "Chunk {:?} was generated with {stamp:?}, but this level generates with \
+                 {expected:?}. Its terrain may not line up with the chunks generated around it; \
+                 further chunks like it are only logged at debug level.",
+                chunk.position
+            );
+        }
+        false
+    }
+
+    /// Fits a chunk loaded from disk to the height of the dimension, turning it into an error if
+    /// it can't be.
+    async fn fit_loaded_chunk(
+        &self,
+        data: LoadedData<SyncChunk, ChunkReadingError>,
+    ) -> LoadedData<SyncChunk, ChunkReadingError> {
+        let LoadedData::Loaded(chunk) = data else {
+            return data;
+        };
+        let mut chunk_data = chunk.write().await;
+        let pos = chunk_data.position;
+        self.check_generator(&chunk_data);
+        match chunk_data.fit_to_height(self.dimension.min_y(), self.dimension.height()) {
+            Ok(()) => {
+                drop(chunk_data);
+                LoadedData::Loaded(chunk)
+            }
+            Err(err) => LoadedData::Error((pos, ChunkReadingError::ParsingError(err))),
+        }
+    }
+
     /// Spawns a task associated with this world. All tasks spawned with this method are awaited
     /// when the client. This means tasks should complete in a reasonable (no looping) amount of time.
     pub fn spawn_task<F>(&self, task: F) -> JoinHandle<F::Output>
@@ -304,6 +413,8 @@ impl Level {
             .iter()
             .map(|chunk| (*chunk.key(), chunk.value().clone()))
             .collect::<Vec<_>>();
+        self.generator_pool
+            .forget(self.loaded_chunks.iter().map(|chunk| *chunk.key()));
         self.loaded_chunks.clear();
 
         // TODO: I think the chunk_saver should be at the server level
@@ -436,12 +547,15 @@ impl Level {
                     .get(pos)
                     .is_none_or(|count| count.is_zero())
                 {
+                    self.abandon_generation(*pos);
                     self.loaded_chunks.remove(pos).map(|chunk| (*pos, chunk.1))
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
+        self.generator_pool
+            .forget(chunks_with_no_watchers.iter().map(|(pos, _)| *pos));
 
         let level = self.clone();
         self.spawn_task(async move {
@@ -589,10 +703,52 @@ impl Level {
         self.chunk_watchers.get(chunk).is_some()
     }
 
+    /// Records that a player moved from the chunk `from` to the chunk `to`, `None` standing for
+    /// joining or leaving the level. Chunks requested afterwards are generated closest to the
+    /// players first, see [`LoadRequest::priority`].
+    pub fn move_player(&self, from: Option<Vector2<i32>>, to: Option<Vector2<i32>>) {
+        if from == to {
+            return;
+        }
+        if let Some(from) = from {
+            self.player_chunks.remove_if_mut(&from, |_, count| {
+                *count -= 1;
+                count.is_zero()
+            });
+        }
+        if let Some(to) = to {
+            *self.player_chunks.entry(to).or_default() += 1;
+        }
+    }
+
+    /// How many chunks `pos` is away from the closest player, 0 if there are no players.
+    fn player_distance(&self, pos: Vector2<i32>) -> u32 {
+        self.player_chunks
+            .iter()
+            .map(|entry| {
+                let offset = entry.key().sub(&pos);
+                offset.x.unsigned_abs().max(offset.y.unsigned_abs())
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
     pub fn clean_memory(&self) {
-        self.chunk_watchers.retain(|_, watcher| !watcher.is_zero());
-        self.loaded_chunks
-            .retain(|at, _| self.chunk_watchers.get(at).is_some());
+        self.chunk_watchers.retain(|&at, watcher| {
+            if watcher.is_zero() {
+                self.abandon_generation(at);
+            }
+            !watcher.is_zero()
+        });
+        let mut unloaded = Vec::new();
+        self.loaded_chunks.retain(|at, _| {
+            let watched = self.chunk_watchers.get(at).is_some();
+            if !watched {
+                unloaded.push(*at);
+            }
+            watched
+        });
+        self.generator_pool.forget(unloaded);
         self.loaded_entity_chunks
             .retain(|at, _| self.chunk_watchers.get(at).is_some());
 
@@ -625,33 +781,181 @@ impl Level {
                 self.loaded_chunks.insert(pos, chunk.clone());
                 chunk
             }
-            Err(_) => {
-                // Need to generate
-                let (tx, rx) = oneshot::channel();
+            Err(err) => {
+                if !matches!(err, ChunkReadingError::ChunkNotExist) {
+                    log::error!("Failed to load chunk {pos:?}, generating it again: {err}");
+                }
+                self.request_load_and_wait(pos).await
+            }
+        }
+    }
+
+    /// Has the generator threads generate the chunk at `pos` and waits until it is done, without
+    /// looking for it on disk first. A chunk that is loaded already is returned as it is.
+    pub async fn request_load_and_wait(&self, pos: Vector2<i32>) -> SyncChunk {
+        if let Some(chunk) = self.loaded_chunks.get(&pos) {
+            return chunk.clone();
+        }
+        self.wait_for_generation(pos)
+            .await
+            .expect("Generation worker dropped")
+    }
+
+    /// Makes sure the chunk at `pos` is on disk, generating and writing it if it isn't, without
+    /// keeping it in memory unless a player watches it. Returns whether it had to be generated.
+    /// Unlike [`Level::clean_chunks`], only returns once the chunk is written.
+    pub async fn pregenerate_chunk(&self, pos: Vector2<i32>) -> bool {
+        self.pregenerate_chunks(pos, &[pos]).await > 0
+    }
+
+    /// Like [`Level::pregenerate_chunk`] for all of the `chunks`, generating the ones missing
+    /// from disk as a single request around `center`, so that they share the neighbors they need.
+    /// Returns how many of them had to be generated.
+    pub async fn pregenerate_chunks(&self, center: Vector2<i32>, chunks: &[Vector2<i32>]) -> usize {
+        let mut missing = Vec::new();
+        for &pos in chunks {
+            if self.loaded_chunks.contains_key(&pos) {
+                // Written along with the other loaded chunks
+                continue;
+            }
+            match self.load_single_chunk(pos).await {
+                Ok(_) => continue,
+                Err(ChunkReadingError::ChunkNotExist) => {}
+                Err(err) => log::error!("Failed to load chunk {pos:?}, generating it again: {err}"),
+            }
+            missing.push(pos);
+        }
+
+        let receivers = self.request_generations(LoadRequest::new(center, 0), missing);
+        let generated = receivers.len();
+        for (pos, rx) in receivers {
+            let chunk = match rx.await {
+                Ok(chunk) => chunk,
+                // Unloaded before it was generated
+                Err(_) => self
+                    .wait_for_generation(pos)
+                    .await
+                    .expect("Generation worker dropped"),
+            };
+            let unwatched = self
+                .chunk_watchers
+                .get(&pos)
+                .is_none_or(|count| count.is_zero());
+            if unwatched {
+                self.loaded_chunks.remove(&pos);
+                self.generator_pool.forget([pos]);
+            }
+            self.write_chunks(vec![(pos, chunk.clone())]).await;
+            // Someone may have started watching it while it was written
+            if unwatched && self.chunk_watchers.get(&pos).is_some() {
+                self.loaded_chunks.entry(pos).or_insert(chunk);
+            }
+        }
+        generated
+    }
+
+    /// The chunk the structure called `structure_id` starts in closest to the block column `from`,
+    /// up to `max_radius` chunks away. The search runs on a blocking thread, since it samples the
+    /// biomes of a lot of chunks.
+    pub async fn locate_nearest_structure(
+        &self,
+        structure_id: &str,
+        from: Vector2<i32>,
+        max_radius: u32,
+    ) -> Option<Vector2<i32>> {
+        let world_gen = self.world_gen.clone();
+        let structure_id = structure_id.to_string();
+        tokio::task::spawn_blocking(move || {
+            world_gen.locate_nearest_structure(&structure_id, from, max_radius)
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// How long the chunks generated for this level so far took, if its generator keeps track.
+    pub fn generation_timings(&self) -> Option<GenerationTimings> {
+        self.world_gen.timings()
+    }
+
+    /// Sends a request for the chunk at `pos` to the generator threads and waits for the chunk.
+    /// Everyone waiting on the same chunk shares a single request. A chunk unloaded before it was
+    /// generated is requested again.
+    async fn wait_for_generation(
+        &self,
+        pos: Vector2<i32>,
+    ) -> Result<SyncChunk, oneshot::error::RecvError> {
+        loop {
+            let (_, rx) = self
+                .request_generations(LoadRequest::new(pos, 0), [pos])
+                .pop()
+                .expect("a receiver for the chunk");
+            match rx.await {
+                Err(_) if !self.is_shutting_down.load(Ordering::Relaxed) => {}
+                result => return result,
+            }
+        }
+    }
 
-                // Deduplication
+    /// Stops waiting for the chunk at `pos` to be generated, dropping everyone waiting on it.
+    /// Cancels its request once none of the other chunks of the request are waited for either.
+    fn abandon_generation(&self, pos: Vector2<i32>) {
+        let Some((_, request)) = self.generation_requests.remove(&pos) else {
+            return;
+        };
+        self.pending_generations.remove(&pos);
+        if let Some(request) = Arc::into_inner(request) {
+            request.cancel();
+        }
+    }
+
+    /// Sends the `chunks` nobody asked for yet to the generator threads as the part of `area`
+    /// they are in, see [`LoadRequest::only`], the chunks closest to its origin coming first.
+    /// Returns where each chunk will arrive. Requests far from the players wait for the ones
+    /// close to them.
+    fn request_generations(
+        &self,
+        area: LoadRequest,
+        chunks: impl IntoIterator<Item = Vector2<i32>>,
+    ) -> Vec<(Vector2<i32>, oneshot::Receiver<SyncChunk>)> {
+        let mut requested = Vec::new();
+        let receivers = chunks
+            .into_iter()
+            .map(|pos| {
+                let (tx, rx) = oneshot::channel();
                 match self.pending_generations.entry(pos) {
                     dashmap::mapref::entry::Entry::Occupied(mut entry) => {
                         entry.get_mut().push(tx);
                     }
                     dashmap::mapref::entry::Entry::Vacant(entry) => {
                         entry.insert(vec![tx]);
-                        let _ = self.gen_request_tx.send(pos);
+                        requested.push(pos);
                     }
                 }
-
-                rx.await.expect("Generation worker dropped")
+                (pos, rx)
+            })
+            .collect();
+        if !requested.is_empty() {
+            let priority = self.player_distance(area.origin());
+            let request = area.only(requested.clone()).with_priority(priority);
+            let handle = Arc::new(request.clone());
+            for pos in requested {
+                self.generation_requests.insert(pos, handle.clone());
             }
+            let _ = self.gen_request_tx.send(request.into());
         }
+        receivers
     }
 
     // Stream the chunks (don't collect them and then do stuff with them)
-    /// Spawns a tokio task to stream chunks.
+    /// Spawns a tokio task to stream chunks. Chunks that have to be generated are generated
+    /// closest to `center` first, which should be the chunk of the player they are sent to.
     /// Important: must be called from an async function (or changed to accept a tokio runtime
     /// handle)
     pub fn receive_chunks(
         self: &Arc<Self>,
         chunks: Vec<Vector2<i32>>,
+        center: Vector2<i32>,
     ) -> UnboundedReceiver<(SyncChunk, bool)> {
         let (sender, receiver) = mpsc::unbounded_channel();
         let level = self.clone();
@@ -685,7 +989,12 @@ impl Level {
                         .await;
 
                     // Process loaded/missing/error results
+                    let mut missing = Vec::new();
                     while let Some(data) = rx.recv().await {
+                        let data = level.fit_loaded_chunk(data).await;
+                        if let LoadedData::Error((pos, err)) = &data {
+                            log::error!("Failed to load chunk {pos:?}, generating it again: {err}");
+                        }
                         match data {
                             LoadedData::Loaded(chunk) => {
                                 let pos = chunk.read().await.position;
@@ -693,30 +1002,21 @@ impl Level {
                                 let _ = sender.send((chunk, false));
                             }
                             LoadedData::Missing(pos) | LoadedData::Error((pos, _)) => {
-                                // Need to generate — but don't block here
-                                let sender_clone = sender.clone();
-                                let level_clone = level.clone();
-
-                                tokio::spawn(async move {
-                                    let (tx, rx) = oneshot::channel();
-
-                                    match level_clone.pending_generations.entry(pos) {
-                                        dashmap::mapref::entry::Entry::Occupied(mut entry) => {
-                                            entry.get_mut().push(tx);
-                                        }
-                                        dashmap::mapref::entry::Entry::Vacant(entry) => {
-                                            entry.insert(vec![tx]);
-                                            let _ = level_clone.gen_request_tx.send(pos);
-                                        }
-                                    }
-
-                                    if let Ok(chunk) = rx.await {
-                                        let _ = sender_clone.send((chunk, true));
-                                    }
-                                });
+                                missing.push(pos);
                             }
                         }
                     }
+
+                    // Need to generate — in one batch, but don't block here
+                    let view = LoadRequest::for_configured_view_distance(center);
+                    for (_, rx) in level.request_generations(view, missing) {
+                        let sender_clone = sender.clone();
+                        tokio::spawn(async move {
+                            if let Ok(chunk) = rx.await {
+                                let _ = sender_clone.send((chunk, true));
+                            }
+                        });
+                    }
                 }
             };
 
@@ -870,7 +1170,7 @@ impl Level {
             return &Biome::THE_VOID;
         };
 
-        Biome::from_id(id).unwrap()
+        biome_id_or_plains(id)
     }
 
     pub async fn set_block_state(
@@ -79,7 +79,7 @@ pub fn bench_create_and_populate_biome(
         terrain_cache,
         default_block,
     );
-    chunk.populate_biomes(Dimension::Overworld);
+    chunk.populate_biomes(&Dimension::Overworld);
 }
 
 pub fn bench_create_and_populate_noise_with_surface(
@@ -97,7 +97,7 @@ pub fn bench_create_and_populate_noise_with_surface(
         terrain_cache,
         default_block,
     );
-    chunk.populate_biomes(Dimension::Overworld);
+    chunk.populate_biomes(&Dimension::Overworld);
     chunk.populate_noise();
     chunk.build_surface();
 }
@@ -160,13 +160,18 @@ mod test {
         world_info::{DataPacks, LevelData, WorldGenSettings, WorldInfoError, WorldVersion},
     };
 
-    use super::{AnvilLevelInfo, LEVEL_DAT_FILE_NAME, LevelDat, WorldInfoReader, WorldInfoWriter};
+    use super::{
+        AnvilLevelInfo, LEVEL_DAT_FILE_NAME, LevelDat, MAXIMUM_SUPPORTED_WORLD_DATA_VERSION,
+        WorldInfoReader, WorldInfoWriter,
+    };
 
     #[test]
     fn test_preserve_level_dat_seed() {
         let seed = 1337;
 
-        let mut data = LevelData::default();
+        // Not `LevelData::default()`, which reads the seed from the server config
+        let mut data = LEVEL_DAT.data.clone();
+        data.data_version = MAXIMUM_SUPPORTED_WORLD_DATA_VERSION;
         data.world_gen_settings.seed = seed;
 
         let temp_dir = TempDir::new().unwrap();
@@ -260,10 +265,7 @@ mod test {
                 water_source_conversion: true,
                 ..Default::default()
             },
-            world_gen_settings: WorldGenSettings {
-                seed: 1,
-                ..Default::default()
-            },
+            world_gen_settings: WorldGenSettings::new(1),
             last_played: 1733847709327,
             level_name: "New World".to_string(),
             spawn_x: 160,
@@ -140,11 +140,19 @@ pub struct DataPacks {
 
 fn get_or_create_seed() -> Seed {
     // TODO: if there is a seed in the config (!= "") use it. Otherwise make a random one
-    Seed::from(BASIC_CONFIG.seed.as_str())
+    Seed::from_string(&BASIC_CONFIG.seed)
 }
 
 impl Default for WorldGenSettings {
     fn default() -> Self {
+        Self::new(get_or_create_seed().0 as i64)
+    }
+}
+
+impl WorldGenSettings {
+    /// The settings of a world generated from `seed`, unlike [`WorldGenSettings::default`] which
+    /// takes the seed from the server config.
+    pub fn new(seed: i64) -> Self {
         // TODO: Adjust according to enabled worlds
         let mut dimensions = Dimensions::new();
         dimensions.insert(
@@ -188,10 +196,7 @@ impl Default for WorldGenSettings {
                 dimension_type: "minecraft:the_end".to_string(),
             },
         );
-        Self {
-            dimensions,
-            seed: get_or_create_seed().0 as i64,
-        }
+        Self { dimensions, seed }
     }
 }
 
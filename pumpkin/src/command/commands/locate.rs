@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+use async_trait::async_trait;
+use pumpkin_util::math::vector2::Vector2;
+use pumpkin_util::text::click::ClickEvent;
+use pumpkin_util::text::hover::HoverEvent;
+use pumpkin_util::text::{TextComponent, color::NamedColor};
+
+use crate::command::{
+    CommandError, CommandExecutor, CommandSender,
+    args::{ConsumedArgs, FindArg, resource_location::ResourceLocationArgumentConsumer},
+    tree::CommandTree,
+    tree::builder::{argument, literal},
+};
+
+const NAMES: [&str; 1] = ["locate"];
+
+const DESCRIPTION: &str = "Finds the location of the nearest structure.";
+
+const ARG_STRUCTURE: &str = "structure";
+
+/// How many chunks away structures are searched for, about as far as vanilla looks for villages.
+const SEARCH_RADIUS: u32 = 3200;
+
+struct StructureExecutor;
+
+#[async_trait]
+impl CommandExecutor for StructureExecutor {
+    async fn execute<'a>(
+        &self,
+        sender: &mut CommandSender,
+        server: &crate::server::Server,
+        args: &ConsumedArgs<'a>,
+    ) -> Result<(), CommandError> {
+        let structure = ResourceLocationArgumentConsumer::find_arg(args, ARG_STRUCTURE)?;
+        let world = match sender.world() {
+            Some(world) => world,
+            // TODO: Maybe ask player for world, or get the current world
+            None => server
+                .worlds
+                .read()
+                .await
+                .first()
+                .cloned()
+                .ok_or(CommandError::InvalidRequirement)?,
+        };
+        let from = sender.position().map_or(Vector2::new(0, 0), |pos| {
+            Vector2::new(pos.x.floor() as i32, pos.z.floor() as i32)
+        });
+
+        let Some(start) = world
+            .level
+            .locate_nearest_structure(structure, from, SEARCH_RADIUS)
+            .await
+        else {
+            return Err(CommandError::CommandFailed(Box::new(
+                TextComponent::translate(
+                    "commands.locate.structure.not_found",
+                    [TextComponent::text(structure.to_string())],
+                ),
+            )));
+        };
+
+        // Like vanilla, the structure is reported at the corner of the chunk it starts in
+        let (x, z) = (start.x * 16, start.y * 16);
+        let distance = (f64::from(x - from.x).powi(2) + f64::from(z - from.y).powi(2)).sqrt();
+        let teleport = format!("/tp @s {x} ~ {z}");
+        let coordinates = TextComponent::text("[")
+            .add_child(TextComponent::translate(
+                "chat.coordinates",
+                [
+                    TextComponent::text(x.to_string()),
+                    TextComponent::text("~"),
+                    TextComponent::text(z.to_string()),
+                ],
+            ))
+            .add_child(TextComponent::text("]"))
+            .color_named(NamedColor::Green)
+            .hover_event(HoverEvent::show_text(TextComponent::translate(
+                Cow::from("chat.coordinates.tooltip"),
+                [],
+            )))
+            .click_event(ClickEvent::SuggestCommand {
+                command: Cow::from(teleport),
+            });
+        sender
+            .send_message(TextComponent::translate(
+                "commands.locate.structure.success",
+                [
+                    TextComponent::text(structure.to_string()),
+                    coordinates,
+                    TextComponent::text((distance as i32).to_string()),
+                ],
+            ))
+            .await;
+        Ok(())
+    }
+}
+
+pub fn init_command_tree() -> CommandTree {
+    CommandTree::new(NAMES, DESCRIPTION).then(
+        literal("structure").then(
+            argument(ARG_STRUCTURE, ResourceLocationArgumentConsumer::new(false))
+                .execute(StructureExecutor),
+        ),
+    )
+}
@@ -27,6 +27,7 @@ mod help;
 mod kick;
 mod kill;
 mod list;
+mod locate;
 mod me;
 mod msg;
 mod op;
@@ -83,6 +84,7 @@ pub async fn default_dispatcher() -> CommandDispatcher {
     dispatcher.register(clear::init_command_tree(), "minecraft:command.clear");
     dispatcher.register(setblock::init_command_tree(), "minecraft:command.setblock");
     dispatcher.register(seed::init_command_tree(), "minecraft:command.seed");
+    dispatcher.register(locate::init_command_tree(), "minecraft:command.locate");
     dispatcher.register(fill::init_command_tree(), "minecraft:command.fill");
     dispatcher.register(
         playsound::init_command_tree(),
@@ -265,6 +267,13 @@ fn register_level_2_permissions(registry: &mut PermissionRegistry) {
             PermissionDefault::Op(PermissionLvl::Two),
         ))
         .unwrap();
+    registry
+        .register_permission(Permission::new(
+            "minecraft:command.locate",
+            "Finds the location of the nearest structure",
+            PermissionDefault::Op(PermissionLvl::Two),
+        ))
+        .unwrap();
     registry
         .register_permission(Permission::new(
             "minecraft:command.fill",
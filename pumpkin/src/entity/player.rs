@@ -414,6 +414,7 @@ impl Player {
         );
 
         let level = &world.level;
+        level.move_player(Some(cylindrical.center), None);
 
         // Decrement the value of watched chunks
         let chunks_to_clean = level.mark_chunks_as_not_watched(&radial_chunks).await;
@@ -1043,8 +1044,10 @@ impl Player {
     }
 
     async fn unload_watched_chunks(&self, world: &World) {
-        let radial_chunks = self.watched_section.load().all_chunks_within();
+        let watched_section = self.watched_section.load();
+        let radial_chunks = watched_section.all_chunks_within();
         let level = &world.level;
+        level.move_player(Some(watched_section.center), None);
         let chunks_to_clean = level.mark_chunks_as_not_watched(&radial_chunks).await;
         level.clean_chunks(&chunks_to_clean).await;
         for chunk in chunks_to_clean {
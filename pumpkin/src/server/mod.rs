@@ -19,11 +19,15 @@ use pumpkin_macros::send_cancellable;
 use pumpkin_protocol::java::client::login::CEncryptionRequest;
 use pumpkin_protocol::java::client::play::CChangeDifficulty;
 use pumpkin_protocol::{ClientPacket, java::client::config::CPluginMessage};
-use pumpkin_registry::{Registry, VanillaDimensionType};
+use pumpkin_registry::{Registry, VanillaDimensionType, check_biome_ids};
 use pumpkin_util::Difficulty;
 use pumpkin_util::math::vector3::Vector3;
 use pumpkin_util::text::TextComponent;
 use pumpkin_world::dimension::Dimension;
+use pumpkin_world::generation::GeneratorKind;
+use pumpkin_world::generation::noise::router::proto_noise_router::{
+    VANILLA_NOISE_ROUTERS, check_noise_routers,
+};
 use pumpkin_world::lock::LevelLocker;
 use pumpkin_world::lock::anvil::AnvilLevelLocker;
 use pumpkin_world::world_info::anvil::{
@@ -150,8 +154,22 @@ impl Server {
         });
 
         let seed = level_info.world_gen_settings.seed;
+        let generator = GeneratorKind::from(BASIC_CONFIG.level_type);
         let level_info = Arc::new(RwLock::new(level_info));
 
+        if let Err(biomes) = check_biome_ids() {
+            log::error!(
+                "Clients don't know the generated biomes {biomes:?} by the ids chunks use for them"
+            );
+            panic!("Mismatched biome ids! See the logs for more info.");
+        }
+        if let Err(routers) = check_noise_routers(VANILLA_NOISE_ROUTERS) {
+            for (name, error) in routers {
+                log::error!("The {name} noise router is broken: {error}");
+            }
+            panic!("Broken noise routers! See the logs for more info.");
+        }
+
         let server = Self {
             cached_registry: Registry::get_synced(),
             container_id: 0.into(),
@@ -191,9 +209,17 @@ impl Server {
 
         let server = Arc::new(server);
         let weak = Arc::downgrade(&server);
+        let level = |dimension: Dimension| {
+            dimension
+                .into_level(world_path.clone(), block_registry.clone(), seed, generator)
+                .unwrap_or_else(|err| {
+                    log::error!("Failed to create the generator of {dimension:?}: {err}");
+                    panic!("Broken world generator! See the logs for more info.");
+                })
+        };
         log::info!("Loading Overworld: {seed}");
         let overworld = World::load(
-            Dimension::Overworld.into_level(world_path.clone(), block_registry.clone(), seed),
+            level(Dimension::Overworld),
             level_info.clone(),
             VanillaDimensionType::Overworld,
             block_registry.clone(),
@@ -201,7 +227,7 @@ impl Server {
         );
         log::info!("Loading Nether: {seed}");
         let nether = World::load(
-            Dimension::Nether.into_level(world_path.clone(), block_registry.clone(), seed),
+            level(Dimension::Nether),
             level_info.clone(),
             VanillaDimensionType::TheNether,
             block_registry.clone(),
@@ -209,7 +235,7 @@ impl Server {
         );
         log::info!("Loading End: {seed}");
         let end = World::load(
-            Dimension::End.into_level(world_path.clone(), block_registry.clone(), seed),
+            level(Dimension::End),
             level_info,
             VanillaDimensionType::TheEnd,
             block_registry,
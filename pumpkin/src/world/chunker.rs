@@ -67,6 +67,7 @@ pub async fn update_position(player: &Arc<Player>) {
         // Make sure the watched section and the chunk watcher updates are async atomic. We want to
         // ensure what we unload when the player disconnects is correct.
         let level = &entity.world.level;
+        level.move_player(Some(old_cylindrical.center), Some(new_chunk_center));
         level.mark_chunks_as_newly_watched(&loading_chunks).await;
         let chunks_to_clean = level.mark_chunks_as_not_watched(&unloading_chunks).await;
 
@@ -1867,7 +1867,7 @@ impl World {
             rel_x * rel_x + rel_z * rel_z
         });
 
-        let mut receiver = self.level.receive_chunks(chunks.clone());
+        let mut receiver = self.level.receive_chunks(chunks.clone(), center_chunk);
 
         let level = self.level.clone();
         let player1 = player.clone();